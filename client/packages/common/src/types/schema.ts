@@ -128,6 +128,7 @@ export enum ActivityLogNodeType {
   StocktakeCreated = 'STOCKTAKE_CREATED',
   StocktakeDeleted = 'STOCKTAKE_DELETED',
   StocktakeStatusFinalised = 'STOCKTAKE_STATUS_FINALISED',
+  StocktakeVarianceApproved = 'STOCKTAKE_VARIANCE_APPROVED',
   StockBatchChange = 'STOCK_BATCH_CHANGE',
   StockCostPriceChange = 'STOCK_COST_PRICE_CHANGE',
   StockExpiryDateChange = 'STOCK_EXPIRY_DATE_CHANGE',
@@ -6989,7 +6990,10 @@ export type SensorNode = {
 export enum SensorNodeType {
   Berlinger = 'BERLINGER',
   BlueMaestro = 'BLUE_MAESTRO',
-  Laird = 'LAIRD'
+  Generic = 'GENERIC',
+  Laird = 'LAIRD',
+  LogTag = 'LOG_TAG',
+  ThirtyDayRecorder = 'THIRTY_DAY_RECORDER'
 }
 
 export enum SensorSortFieldInput {
@@ -7210,8 +7214,9 @@ export type StocktakeLineNode = {
   note?: Maybe<Scalars['String']['output']>;
   packSize?: Maybe<Scalars['Float']['output']>;
   sellPricePerPack?: Maybe<Scalars['Float']['output']>;
-  snapshotNumberOfPacks: Scalars['Float']['output'];
+  snapshotNumberOfPacks?: Maybe<Scalars['Float']['output']>;
   stockLine?: Maybe<StockLineNode>;
+  stockLineId?: Maybe<Scalars['String']['output']>;
   stocktakeId: Scalars['String']['output'];
 };
 
@@ -8711,6 +8716,7 @@ export enum UserPermission {
   PatientQuery = 'PATIENT_QUERY',
   PrescriptionMutate = 'PRESCRIPTION_MUTATE',
   PrescriptionQuery = 'PRESCRIPTION_QUERY',
+  PriceListMutate = 'PRICE_LIST_MUTATE',
   Report = 'REPORT',
   RequisitionMutate = 'REQUISITION_MUTATE',
   RequisitionQuery = 'REQUISITION_QUERY',
@@ -8721,6 +8727,8 @@ export enum UserPermission {
   SensorQuery = 'SENSOR_QUERY',
   ServerAdmin = 'SERVER_ADMIN',
   StocktakeMutate = 'STOCKTAKE_MUTATE',
+  StocktakeSupervise = 'STOCKTAKE_SUPERVISE',
+  StocktakeVarianceApprove = 'STOCKTAKE_VARIANCE_APPROVE',
   StocktakeQuery = 'STOCKTAKE_QUERY',
   StockLineMutate = 'STOCK_LINE_MUTATE',
   StockLineQuery = 'STOCK_LINE_QUERY',
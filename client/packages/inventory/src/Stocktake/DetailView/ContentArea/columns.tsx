@@ -155,7 +155,9 @@ export const useStocktakeColumns = ({
       label: 'label.snapshot-num-of-packs',
       description: 'description.snapshot-num-of-packs',
       align: ColumnAlign.Right,
-      Cell: NumberCell,
+      Cell: props => (
+        <NumberCell {...props} defaultValue={UNDEFINED_STRING_VALUE} />
+      ),
       getIsError: row =>
         getLinesFromRow(row).some(
           r =>
@@ -165,12 +167,17 @@ export const useStocktakeColumns = ({
       accessor: ({ rowData }) => {
         if ('lines' in rowData) {
           const { lines } = rowData;
-          return (
-            lines.reduce(
-              (total, line) => total + line.snapshotNumberOfPacks,
+          // Snapshot is hidden (blind count)
+          if (
+            lines.some(line => typeof line.snapshotNumberOfPacks !== 'number')
+          )
+            return null;
+          return lines
+            .reduce(
+              (total, line) => total + (line.snapshotNumberOfPacks ?? 0),
               0
-            ) ?? 0
-          ).toString();
+            )
+            .toString();
         } else {
           return rowData.snapshotNumberOfPacks;
         }
@@ -215,16 +222,25 @@ export const useStocktakeColumns = ({
       accessor: ({ rowData }) => {
         if ('lines' in rowData) {
           const { lines } = rowData;
+          if (
+            lines.some(line => typeof line.snapshotNumberOfPacks !== 'number')
+          )
+            return null;
           const total =
             lines.reduce(
               (total, line) =>
                 total +
-                (line.snapshotNumberOfPacks -
-                  (line.countedNumberOfPacks ?? line.snapshotNumberOfPacks)),
+                ((line.snapshotNumberOfPacks ?? 0) -
+                  (line.countedNumberOfPacks ??
+                    line.snapshotNumberOfPacks ??
+                    0)),
               0
             ) ?? 0;
           return (total < 0 ? Math.abs(total) : -total).toString();
-        } else if (rowData.countedNumberOfPacks === null) {
+        } else if (
+          rowData.countedNumberOfPacks === null ||
+          typeof rowData.snapshotNumberOfPacks !== 'number'
+        ) {
           return null;
         } else {
           return (
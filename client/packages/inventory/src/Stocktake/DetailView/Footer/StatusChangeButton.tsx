@@ -100,7 +100,7 @@ const useStatusChangeButton = () => {
 
         // ids = { stockLineId: stocktakeLineId }
         const ids = mapValues(
-          mapKeys(lines.nodes, line => line.stockLine?.id),
+          mapKeys(lines.nodes, line => line.stockLineId),
           'id'
         );
         // mappedErrors = { stockLineId: StockLineReducedBelowZero }
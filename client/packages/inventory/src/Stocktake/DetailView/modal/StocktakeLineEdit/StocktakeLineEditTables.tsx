@@ -19,6 +19,7 @@ import {
   ColumnAlign,
   AdjustmentTypeInput,
   NumberCell,
+  UNDEFINED_STRING_VALUE,
 } from '@openmsupply-client/common';
 import { DraftStocktakeLine } from './utils';
 import {
@@ -130,7 +131,8 @@ const getInventoryAdjustmentReasonInputColumn = (
           width={column.width}
           onChange={onChange}
           adjustmentType={
-            rowData.snapshotNumberOfPacks > (rowData?.countedNumberOfPacks ?? 0)
+            (rowData.snapshotNumberOfPacks ?? 0) >
+            (rowData?.countedNumberOfPacks ?? 0)
               ? AdjustmentTypeInput.Reduction
               : AdjustmentTypeInput.Addition
           }
@@ -150,7 +152,7 @@ const getInventoryAdjustmentReasonInputColumn = (
 // cell will be re rendered anytime rowData changes, which causes it to loose focus
 // if number of packs is changed and tab is pressed (in quick succession)
 const PackUnitEntryCell = PackSizeEntryCell<DraftStocktakeLine>({
-  getIsDisabled: r => !!r?.stockLine,
+  getIsDisabled: r => !!r?.stockLineId,
 });
 
 export const BatchTable: FC<StocktakeLineEditTableProps> = ({
@@ -177,12 +179,14 @@ export const BatchTable: FC<StocktakeLineEditTableProps> = ({
       label: 'label.snapshot-num-of-packs',
       align: ColumnAlign.Right,
       width: 100,
-      Cell: NumberCell,
+      Cell: props => (
+        <NumberCell {...props} defaultValue={UNDEFINED_STRING_VALUE} />
+      ),
       getIsError: rowData =>
         errorsContext.getError(rowData)?.__typename ===
         'SnapshotCountCurrentCountMismatchLine',
       setter: patch => update({ ...patch, countThisLine: true }),
-      accessor: ({ rowData }) => rowData.snapshotNumberOfPacks || '0',
+      accessor: ({ rowData }) => rowData.snapshotNumberOfPacks ?? null,
     },
 
     {
@@ -56,8 +56,8 @@ export const DraftLine = {
       isCreated: true,
       isUpdated: false,
       countThisLine,
-      stockLine,
       ...stockLine,
+      stockLineId: stockLine.id,
       snapshotNumberOfPacks: stockLine.totalNumberOfPacks,
       expiryDate: stockLine.expiryDate ? stockLine.expiryDate : null,
       id: FnUtils.generateUUID(),
@@ -98,7 +98,7 @@ export const get = {
     const uncountedLines =
       stockLines.filter(
         ({ id }) =>
-          !stocktakeLines?.some(({ stockLine }) => stockLine?.id === id)
+          !stocktakeLines?.some(({ stockLineId }) => stockLineId === id)
       ) ?? [];
 
     // Default countThisLine to true when first adding an item to the stocktake.
@@ -77,9 +77,9 @@ const stocktakeParser = {
       costPricePerPack: line.costPricePerPack,
       countedNumberOfPacks: line.countedNumberOfPacks,
       id: line.id,
-      itemId: !line.stockLine?.id ? line.itemId : undefined,
+      itemId: !line.stockLineId ? line.itemId : undefined,
       sellPricePerPack: line.sellPricePerPack,
-      stockLineId: line.stockLine?.id,
+      stockLineId: line.stockLineId,
       stocktakeId: line.stocktakeId,
       expiryDate: line.expiryDate
         ? Formatter.naiveDate(new Date(line.expiryDate))
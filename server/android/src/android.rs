@@ -64,6 +64,7 @@ pub mod android {
                     .with_directory(files_dir.to_string_lossy().to_string()),
             ),
             backup: None,
+            mail: None,
         };
 
         logging_init(settings.logging.clone(), None);
@@ -1,55 +1,128 @@
 use super::*;
 use chrono::Utc;
 use copy_dir::copy_dir;
+use rusqlite::{backup::Backup, Connection, OpenFlags};
 use service::settings::Settings;
-use std::{fs, io, path::PathBuf, process::Command, str::FromStr};
+use std::{fs, path::PathBuf, str::FromStr, time::Duration};
 
-pub(crate) fn backup(settings: &Settings) -> Result<(), BackupError> {
+// Number of pages copied in each step of sqlite online backup, database stays available to
+// the server between steps
+const SQLITE_BACKUP_PAGES_PER_STEP: i32 = 1000;
+const SQLITE_BACKUP_PAUSE_BETWEEN_STEPS: Duration = Duration::from_millis(10);
+
+pub(crate) fn backup(
+    settings: &Settings,
+    BackupArguments { mode }: BackupArguments,
+) -> Result<(), BackupError> {
     let DirSettings {
         backup_dir,
         pg_bin_dir,
+        ..
     } = get_dirs_from_settings(settings)?;
 
+    if !cfg!(feature = "postgres") && mode != BackupMode::Full {
+        return Err(BackupError::BackupModeRequiresPostgres(mode));
+    }
+
     let max_number_of_backups = settings
         .backup
         .as_ref()
         .map(|b| b.max_number_of_backups)
         .flatten();
 
-    let Dirs {
+    let dirs = create_backup_dir(backup_dir)?;
+
+    if let Err(error) = take_backup(settings, mode, &dirs, &pg_bin_dir) {
+        // Remove failed backup so that it's not used for restore or as a parent backup
+        let _ = fs::remove_dir_all(&dirs.base_dir);
+        return Err(error);
+    }
+
+    cleanup_backups(&dirs.backups_dir, max_number_of_backups)?;
+
+    println!(
+        "Backup completed and verified in folder {}",
+        dirs.backup_name
+    );
+    Ok(())
+}
+
+/// Backs up files and database into the backup folder, the backup is complete once its metadata
+/// is written
+fn take_backup(
+    settings: &Settings,
+    mode: BackupMode,
+    Dirs {
         backup_name,
+        base_dir,
         file_dir,
         database_dir,
         backups_dir,
-    } = create_backup_dir(backup_dir)?;
+    }: &Dirs,
+    pg_bin_dir: &Option<String>,
+) -> Result<(), BackupError> {
+    // Incremental backup is taken against the latest base or incremental backup
+    let parent_backup = match mode {
+        BackupMode::Incremental => Some(
+            read_backups(backups_dir)?
+                .into_iter()
+                .filter(|backup| {
+                    &backup.name != backup_name && backup.metadata.mode != BackupMode::Full
+                })
+                .last()
+                .ok_or(BackupError::NoParentForIncrementalBackup)?,
+        ),
+        _ => None,
+    };
 
-    copy_files(settings, &file_dir)?;
+    copy_files(settings, file_dir)?;
 
     // Backup database
-    if cfg!(feature = "postgres") {
-        dump_postgres_database(settings, &database_dir, pg_bin_dir)?;
-    } else {
-        copy_sqlite_files(settings, &database_dir)?;
-    }
-
-    cleanup_backups(&backups_dir, max_number_of_backups)?;
+    match (&mode, &parent_backup) {
+        (BackupMode::Full, _) if cfg!(feature = "postgres") => {
+            dump_postgres_database(settings, database_dir, pg_bin_dir)?
+        }
+        (BackupMode::Full, _) => backup_sqlite_database(settings, database_dir)?,
+        (_, parent_backup) => {
+            let parent_manifest = parent_backup.as_ref().map(|parent| {
+                backups_dir
+                    .join(&parent.name)
+                    .join(BACKUP_DATABASE_DIR)
+                    .join("backup_manifest")
+            });
+            base_backup_postgres_database(
+                settings,
+                database_dir,
+                pg_bin_dir,
+                parent_manifest.as_ref(),
+            )?
+        }
+    };
 
-    println!("Backup completed in folder {backup_name}");
-    Ok(())
+    verify_backup(settings, &mode, database_dir, pg_bin_dir).map_err(|error| {
+        BackupError::BackupVerificationFailed(backup_name.clone(), error.to_string())
+    })?;
+
+    write_metadata(
+        base_dir,
+        &BackupMetadata {
+            mode,
+            created_datetime: Utc::now().naive_utc(),
+            parent_backup: parent_backup.map(|parent| parent.name),
+        },
+    )
 }
 
 struct Dirs {
     backup_name: String,
+    base_dir: PathBuf,
     file_dir: PathBuf,
     database_dir: PathBuf,
     backups_dir: PathBuf,
 }
 
 fn create_backup_dir(output_dir: String) -> Result<Dirs, BackupError> {
-    let backup_name = Utc::now()
-        .naive_local()
-        .format("D%Y_%m_%dT%H_%M_%S")
-        .to_string();
+    let backup_name = metadata::backup_name(&Utc::now().naive_local());
 
     let backups_dir = PathBuf::from_str(&output_dir)
         .map_err(|_| BackupError::InvalidPath(output_dir.to_string()))?;
@@ -58,6 +131,7 @@ fn create_backup_dir(output_dir: String) -> Result<Dirs, BackupError> {
 
     fs::create_dir_all(&base_dir)
         .map_err(|e| BackupError::CannotCreateBackupFolder(e, base_dir.clone()))?;
+    mark_backup_in_progress(&base_dir)?;
 
     let file_dir = base_dir.join(BACKUP_FILE_DIR);
     fs::create_dir_all(&file_dir)
@@ -69,6 +143,7 @@ fn create_backup_dir(output_dir: String) -> Result<Dirs, BackupError> {
 
     Ok(Dirs {
         backup_name,
+        base_dir,
         file_dir,
         database_dir,
         backups_dir,
@@ -97,54 +172,121 @@ fn copy_files(settings: &Settings, backup_file_dir: &PathBuf) -> Result<(), Back
 fn dump_postgres_database(
     settings: &Settings,
     backup_database_dir: &PathBuf,
-    pg_bin_dir_opt: Option<String>,
+    pg_bin_dir: &Option<String>,
 ) -> Result<(), BackupError> {
-    let pg_bin_dir = pg_bin_dir_opt.clone().unwrap_or_default();
-
-    let command = PathBuf::from_str(&pg_bin_dir)
-        .map_err(|_| BackupError::InvalidPath(pg_bin_dir.clone()))?
-        .join("pg_dump");
-
-    let result = Command::new(command.to_str().unwrap())
-        .args([
+    run_pg_command(
+        pg_bin_dir,
+        "pg_dump",
+        &[
             "--file",
             backup_database_dir.to_str().unwrap(),
             "--format",
             "d",
             "--dbname",
             &settings.database.connection_string(),
-        ])
-        .output()
-        .map_err(|e| match (e.kind(), pg_bin_dir_opt.is_some()) {
-            (io::ErrorKind::NotFound, true) => BackupError::PgCommandNotFoundInBinPath,
-            (io::ErrorKind::NotFound, false) => BackupError::PgCommandNotFoundInPath,
-            _ => e.into(),
-        })?;
-
-    if !result.status.success() {
-        return Err(BackupError::CommandLineError(result));
-    }
+        ],
+    )?;
 
     Ok(())
 }
 
-fn copy_sqlite_files(
+/// Physical backup of the whole postgres cluster, WAL needed to make the backup consistent is
+/// streamed into the backup. When `parent_manifest` is provided only blocks changed since the
+/// parent backup are copied (postgres 17+ with `summarize_wal = on`)
+fn base_backup_postgres_database(
     settings: &Settings,
     backup_database_dir: &PathBuf,
+    pg_bin_dir: &Option<String>,
+    parent_manifest: Option<&PathBuf>,
 ) -> Result<(), BackupError> {
-    let sqlite_files = get_sqlite_files_paths(settings)?;
+    // pg_basebackup requires target directory to be empty or to not exist
+    fs::remove_dir(backup_database_dir)?;
+
+    let connection_string = settings.database.connection_string();
+    let mut args = vec![
+        "--pgdata",
+        backup_database_dir.to_str().unwrap(),
+        "--format",
+        "p",
+        "--wal-method",
+        "stream",
+        "--checkpoint",
+        "fast",
+        "--dbname",
+        &connection_string,
+    ];
+    if let Some(parent_manifest) = parent_manifest {
+        args.extend(["--incremental", parent_manifest.to_str().unwrap()]);
+    }
+
+    run_pg_command(pg_bin_dir, "pg_basebackup", &args)?;
+
+    Ok(())
+}
 
-    if sqlite_files.is_empty() {
+/// Uses sqlite online backup API, which copies the database in steps and includes changes made
+/// by the server while the backup is running
+fn backup_sqlite_database(
+    settings: &Settings,
+    backup_database_dir: &PathBuf,
+) -> Result<(), BackupError> {
+    let database_path = PathBuf::from(settings.database.database_path());
+    if !database_path.is_file() {
         return Err(BackupError::CannotFindSqliteBackup(
             settings.database.database_name.clone(),
         ));
     }
 
-    for sqlite_filename in sqlite_files {
-        // Unwrap should be safe (would panic only if pathname terminates with '...')
-        let sqlite_filename = sqlite_filename.file_name().unwrap();
+    let source = Connection::open_with_flags(&database_path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let mut destination = Connection::open(sqlite_backup_file(settings, backup_database_dir))?;
 
-        fs::copy(&sqlite_filename, &backup_database_dir.join(sqlite_filename))?;
+    Backup::new(&source, &mut destination)?.run_to_completion(
+        SQLITE_BACKUP_PAGES_PER_STEP,
+        SQLITE_BACKUP_PAUSE_BETWEEN_STEPS,
+        None,
+    )?;
+
+    Ok(())
+}
+
+fn sqlite_backup_file(settings: &Settings, backup_database_dir: &PathBuf) -> PathBuf {
+    // omSupply database name can be specified with .sqlite extension, remove it here
+    let database_name = settings.database.database_name.replace(".sqlite", "");
+    backup_database_dir.join(format!("{database_name}.sqlite"))
+}
+
+fn verify_backup(
+    settings: &Settings,
+    mode: &BackupMode,
+    backup_database_dir: &PathBuf,
+    pg_bin_dir: &Option<String>,
+) -> Result<(), BackupError> {
+    let backup_database_dir_str = backup_database_dir.to_str().unwrap();
+
+    match mode {
+        // Reading table of contents checks that the dump is complete and readable
+        BackupMode::Full if cfg!(feature = "postgres") => {
+            run_pg_command(
+                pg_bin_dir,
+                "pg_restore",
+                &["--list", backup_database_dir_str],
+            )?;
+        }
+        BackupMode::Full => {
+            let connection = Connection::open_with_flags(
+                sqlite_backup_file(settings, backup_database_dir),
+                OpenFlags::SQLITE_OPEN_READ_ONLY,
+            )?;
+            let result: String =
+                connection.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+            if result != "ok" {
+                return Err(anyhow::anyhow!(result).into());
+            }
+        }
+        // Checks files against checksums in backup_manifest and that the WAL needed is present
+        BackupMode::Base | BackupMode::Incremental => {
+            run_pg_command(pg_bin_dir, "pg_verifybackup", &[backup_database_dir_str])?;
+        }
     }
 
     Ok(())
@@ -154,27 +296,14 @@ fn cleanup_backups(
     backups_dir: &PathBuf,
     max_number_of_backups: Option<u32>,
 ) -> Result<(), BackupError> {
-    let Some(max_number_of_backups) = &max_number_of_backups else {
+    let Some(max_number_of_backups) = max_number_of_backups else {
         return Ok(());
     };
 
-    let mut paths: Vec<PathBuf> = fs::read_dir(backups_dir)?
-        .into_iter()
-        .filter_map(Result::ok)
-        .map(|e| e.path())
-        .filter(|f| f.is_dir())
-        .collect();
-
-    paths.sort();
-
-    let max_number_of_backups = *max_number_of_backups as usize;
-    let number_of_backups_to_delete = if paths.len() <= max_number_of_backups {
-        0
-    } else {
-        paths.len() - max_number_of_backups
-    };
+    let backups = read_backups(backups_dir)?;
 
-    for path in paths.iter().take(number_of_backups_to_delete) {
+    for backup in backups_to_delete(&backups, max_number_of_backups as usize) {
+        let path = backups_dir.join(&backup.name);
         println!("Deleting old backup: {:?}", path);
         let _ = fs::remove_dir_all(path);
     }
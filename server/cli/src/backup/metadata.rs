@@ -0,0 +1,282 @@
+use super::*;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+const BACKUP_METADATA_FILE: &str = "backup.json";
+/// Exists in backup folders until the backup is completed (metadata written)
+const BACKUP_IN_PROGRESS_FILE: &str = "backup_in_progress";
+const BACKUP_NAME_FORMAT: &str = "D%Y_%m_%dT%H_%M_%S";
+
+#[derive(clap::ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum BackupMode {
+    /// pg_dump for postgres, online backup of the database file for sqlite
+    #[default]
+    Full,
+    /// Postgres only, physical backup with pg_basebackup. Combined with WAL archiving
+    /// (`wal_archive_dir` in configurations) allows point in time restore
+    Base,
+    /// Postgres 17+ only, physical backup of changes since the latest base or incremental backup
+    /// (requires `summarize_wal = on` in postgresql.conf)
+    Incremental,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(super) struct BackupMetadata {
+    pub(super) mode: BackupMode,
+    /// UTC
+    pub(super) created_datetime: NaiveDateTime,
+    /// Backup an incremental backup was taken against
+    pub(super) parent_backup: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct BackupInfo {
+    pub(super) name: String,
+    pub(super) metadata: BackupMetadata,
+}
+
+pub(super) fn backup_name(datetime: &NaiveDateTime) -> String {
+    datetime.format(BACKUP_NAME_FORMAT).to_string()
+}
+
+pub(super) fn mark_backup_in_progress(backup_dir: &PathBuf) -> Result<(), BackupError> {
+    fs::write(backup_dir.join(BACKUP_IN_PROGRESS_FILE), "")?;
+    Ok(())
+}
+
+/// Completes the backup
+pub(super) fn write_metadata(
+    backup_dir: &PathBuf,
+    metadata: &BackupMetadata,
+) -> Result<(), BackupError> {
+    let json = serde_json::to_string_pretty(metadata).map_err(anyhow::Error::from)?;
+    fs::write(backup_dir.join(BACKUP_METADATA_FILE), json)?;
+    fs::remove_file(backup_dir.join(BACKUP_IN_PROGRESS_FILE))?;
+    Ok(())
+}
+
+/// Completed backups in the backup folder, oldest first. Backups taken before metadata was added
+/// are full backups, created at the time in their name
+pub(super) fn read_backups(backups_dir: &PathBuf) -> Result<Vec<BackupInfo>, BackupError> {
+    let mut backups: Vec<BackupInfo> = fs::read_dir(backups_dir)
+        .map_err(|e| BackupError::BackupFolderNotExist(e, backups_dir.clone()))?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|path| path.is_dir())
+        // Failed or interrupted backup
+        .filter(|path| !path.join(BACKUP_IN_PROGRESS_FILE).exists())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            let metadata = match fs::read_to_string(path.join(BACKUP_METADATA_FILE)) {
+                Ok(json) => serde_json::from_str(&json).ok()?,
+                Err(_) => BackupMetadata {
+                    mode: BackupMode::Full,
+                    created_datetime: NaiveDateTime::parse_from_str(&name, BACKUP_NAME_FORMAT)
+                        .ok()?,
+                    parent_backup: None,
+                },
+            };
+            Some(BackupInfo { name, metadata })
+        })
+        .collect();
+
+    backups.sort_by(|a, b| {
+        a.metadata
+            .created_datetime
+            .cmp(&b.metadata.created_datetime)
+            .then(a.name.cmp(&b.name))
+    });
+    Ok(backups)
+}
+
+/// Latest backup taken at or before the point in time. When archived WAL can be replayed, base and
+/// incremental backups are preferred (they can be replayed up to the point in time), full backups
+/// are only used if there are no base or incremental backups before the point in time
+pub(super) fn backup_for_point_in_time(
+    backups: &[BackupInfo],
+    point_in_time: NaiveDateTime,
+    replay_wal: bool,
+) -> Option<&BackupInfo> {
+    let latest = |include_full: bool| {
+        backups
+            .iter()
+            .filter(|backup| backup.metadata.created_datetime <= point_in_time)
+            .filter(|backup| include_full || backup.metadata.mode != BackupMode::Full)
+            .max_by_key(|backup| backup.metadata.created_datetime)
+    };
+
+    match replay_wal {
+        true => latest(false).or_else(|| latest(true)),
+        false => latest(true),
+    }
+}
+
+/// Backups needed to restore the backup, starting with the base backup and ending with the backup
+pub(super) fn backup_chain<'a>(
+    backups: &'a [BackupInfo],
+    name: &str,
+) -> Result<Vec<&'a BackupInfo>, BackupError> {
+    let mut chain = Vec::new();
+    let mut next = Some(name.to_string());
+
+    while let Some(name) = next {
+        let backup = backups
+            .iter()
+            .find(|backup| backup.name == name)
+            .ok_or_else(|| BackupError::BackupNotFound(name.clone()))?;
+        if chain.iter().any(|b: &&BackupInfo| b.name == backup.name) {
+            return Err(BackupError::InvalidBackupChain(name));
+        }
+        chain.push(backup);
+        next = backup.metadata.parent_backup.clone();
+    }
+
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Oldest backups over the maximum number of backups, backups needed by the incremental backups
+/// that are kept are not deleted
+pub(super) fn backups_to_delete(
+    backups: &[BackupInfo],
+    max_number_of_backups: usize,
+) -> Vec<&BackupInfo> {
+    let number_to_delete = backups.len().saturating_sub(max_number_of_backups);
+    let (to_delete, to_keep) = backups.split_at(number_to_delete);
+
+    let required: Vec<&str> = to_keep
+        .iter()
+        .filter_map(|backup| backup_chain(backups, &backup.name).ok())
+        .flatten()
+        .map(|backup| backup.name.as_str())
+        .collect();
+
+    to_delete
+        .iter()
+        .filter(|backup| !required.contains(&backup.name.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn backup(name: &str, mode: BackupMode, hour: u32, parent: Option<&str>) -> BackupInfo {
+        BackupInfo {
+            name: name.to_string(),
+            metadata: BackupMetadata {
+                mode,
+                created_datetime: NaiveDate::from_ymd_opt(2024, 8, 22)
+                    .unwrap()
+                    .and_hms_opt(hour, 0, 0)
+                    .unwrap(),
+                parent_backup: parent.map(str::to_string),
+            },
+        }
+    }
+
+    #[test]
+    fn incremental_backups() {
+        let backups = vec![
+            backup("full", BackupMode::Full, 1, None),
+            backup("base", BackupMode::Base, 2, None),
+            backup("incremental_1", BackupMode::Incremental, 3, Some("base")),
+            backup(
+                "incremental_2",
+                BackupMode::Incremental,
+                4,
+                Some("incremental_1"),
+            ),
+        ];
+
+        let at = |hour: u32| {
+            NaiveDate::from_ymd_opt(2024, 8, 22)
+                .unwrap()
+                .and_hms_opt(hour, 30, 0)
+                .unwrap()
+        };
+        let for_point_in_time = |hour: u32, replay_wal: bool| {
+            backup_for_point_in_time(&backups, at(hour), replay_wal).map(|b| b.name.as_str())
+        };
+        assert_eq!(for_point_in_time(0, false), None);
+        assert_eq!(for_point_in_time(0, true), None);
+        assert_eq!(for_point_in_time(1, true), Some("full"));
+        assert_eq!(for_point_in_time(3, false), Some("incremental_1"));
+        assert_eq!(for_point_in_time(3, true), Some("incremental_1"));
+        // Full backup taken after the latest incremental backup
+        let mut with_later_full = backups.clone();
+        with_later_full.push(backup("full_2", BackupMode::Full, 5, None));
+        let for_point_in_time = |hour: u32, replay_wal: bool| {
+            backup_for_point_in_time(&with_later_full, at(hour), replay_wal)
+                .map(|b| b.name.as_str())
+        };
+        assert_eq!(for_point_in_time(5, false), Some("full_2"));
+        assert_eq!(for_point_in_time(5, true), Some("incremental_2"));
+
+        let names = |backups: Vec<&BackupInfo>| -> Vec<String> {
+            backups.iter().map(|b| b.name.clone()).collect()
+        };
+        assert_eq!(
+            names(backup_chain(&backups, "incremental_2").unwrap()),
+            vec!["base", "incremental_1", "incremental_2"]
+        );
+        assert_eq!(names(backup_chain(&backups, "full").unwrap()), vec!["full"]);
+        assert!(backup_chain(&backups, "missing").is_err());
+
+        // Base backup is kept while an incremental backup depends on it
+        assert_eq!(names(backups_to_delete(&backups, 1)), vec!["full"]);
+        assert_eq!(names(backups_to_delete(&backups, 4)), Vec::<String>::new());
+    }
+
+    #[test]
+    fn read_backup_folders() {
+        let backups_dir = std::env::temp_dir().join("omsupply_read_backup_folders");
+        let _ = fs::remove_dir_all(&backups_dir);
+
+        let created_datetime = NaiveDate::from_ymd_opt(2024, 8, 22)
+            .unwrap()
+            .and_hms_opt(1, 0, 0)
+            .unwrap();
+        // Taken before metadata was added
+        let legacy_name = backup_name(&created_datetime);
+        fs::create_dir_all(backups_dir.join(&legacy_name)).unwrap();
+
+        let completed = backups_dir.join("completed");
+        fs::create_dir_all(&completed).unwrap();
+        mark_backup_in_progress(&completed).unwrap();
+        let metadata = BackupMetadata {
+            mode: BackupMode::Base,
+            created_datetime: created_datetime + chrono::Duration::hours(1),
+            parent_backup: None,
+        };
+        write_metadata(&completed, &metadata).unwrap();
+
+        // Failed before metadata was written, named like a legacy backup
+        let failed_name = backup_name(&(created_datetime + chrono::Duration::hours(2)));
+        let failed = backups_dir.join(&failed_name);
+        fs::create_dir_all(&failed).unwrap();
+        mark_backup_in_progress(&failed).unwrap();
+
+        let backups = read_backups(&backups_dir).unwrap();
+        let _ = fs::remove_dir_all(&backups_dir);
+        assert_eq!(
+            backups,
+            vec![
+                BackupInfo {
+                    name: legacy_name,
+                    metadata: BackupMetadata {
+                        mode: BackupMode::Full,
+                        created_datetime,
+                        parent_backup: None,
+                    },
+                },
+                BackupInfo {
+                    name: "completed".to_string(),
+                    metadata,
+                },
+            ]
+        );
+    }
+}
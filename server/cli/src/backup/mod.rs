@@ -1,10 +1,13 @@
 mod backup;
 pub(super) use self::backup::*;
+mod metadata;
+use self::metadata::*;
 mod restore;
 pub(super) use self::restore::*;
 
 use std::env::VarError;
 use std::fs;
+use std::process::{Command, Output};
 use std::str::FromStr;
 use std::{io, path::PathBuf};
 
@@ -23,9 +26,9 @@ const BACKUP_DATABASE_DIR: &'static str = "sqlite";
 
 #[derive(Error, Debug)]
 pub(super) enum BackupError {
-    #[error("Cannot find pg_dump or pg_restore executable in PATH, add it to PATH or specify Postgres bin directory in the configuration file")]
+    #[error("Cannot find postgres command line tools (pg_dump, pg_restore, pg_basebackup etc..) in PATH, add them to PATH or specify Postgres bin directory in the configuration file")]
     PgCommandNotFoundInPath,
-    #[error("Cannot find pg_dump or pg_restore executable in Postgres bin directory specified in configurations")]
+    #[error("Cannot find postgres command line tools (pg_dump, pg_restore, pg_basebackup etc..) in Postgres bin directory specified in configurations")]
     PgCommandNotFoundInBinPath,
     #[error("Problem create folder at path: {1}")]
     CannotCreateBackupFolder(#[source] io::Error, PathBuf),
@@ -51,16 +54,52 @@ pub(super) enum BackupError {
     ErrorWhileConvertingPath(LookupError<VarError>, String),
     #[error("Issue opening backup folder {1}")]
     BackupFolderNotExist(#[source] io::Error, PathBuf),
+    #[error("Backup {0} not found")]
+    BackupNotFound(String),
+    #[error("No backup found at or before {0}")]
+    NoBackupForPointInTime(String),
+    #[error("Either backup name or point in time must be specified")]
+    BackupNotSpecified,
+    #[error("Invalid point in time {0}, expected format is YYYY-MM-DD HH:MM:SS (UTC)")]
+    InvalidPointInTime(String),
+    #[error("Backup {0} has an invalid chain of incremental backups")]
+    InvalidBackupChain(String),
+    #[error("No base or incremental backup to take an incremental backup against")]
+    NoParentForIncrementalBackup,
+    #[error("Backup mode {0:?} is only supported for postgres")]
+    BackupModeRequiresPostgres(BackupMode),
+    #[error("Target data directory must be specified to restore a base or incremental backup")]
+    TargetDataDirNotSpecified,
+    #[error("Target data directory {0} is not empty")]
+    TargetDataDirNotEmpty(PathBuf),
+    #[error("Backup {0} failed verification: {1}")]
+    BackupVerificationFailed(String, String),
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
     #[error(transparent)]
     DatabaseError(#[from] RepositoryError),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+#[derive(clap::Parser, Debug)]
+pub(super) struct BackupArguments {
+    #[clap(short, long, value_enum, default_value_t)]
+    mode: BackupMode,
+}
+
 #[derive(clap::Parser, Debug)]
 pub(super) struct RestoreArguments {
     /// Name of backup in directory specified by backup configurations
     #[clap(short, long)]
-    backup_name: String,
+    backup_name: Option<String>,
+    /// Restore to a point in time (UTC, YYYY-MM-DD HH:MM:SS), the latest backup taken at or
+    /// before this time is used (if backup name is not specified). Postgres base and incremental
+    /// backups replay the WAL archive up to this time
+    #[clap(short, long)]
+    point_in_time: Option<String>,
+    /// Empty postgres data directory to restore base and incremental backups into
+    #[clap(short, long)]
+    target_data_dir: Option<String>,
     /// In dev can specify this to skip confirmation
     #[clap(short, long)]
     skip_confirmation: bool,
@@ -69,12 +108,14 @@ pub(super) struct RestoreArguments {
 struct DirSettings {
     backup_dir: String,
     pg_bin_dir: Option<String>,
+    wal_archive_dir: Option<String>,
 }
 
 fn get_dirs_from_settings(settings: &Settings) -> Result<DirSettings, BackupError> {
     let Some(BackupSettings {
         backup_dir,
         pg_bin_dir,
+        wal_archive_dir,
         ..
     }) = settings.backup.clone()
     else {
@@ -85,17 +126,19 @@ fn get_dirs_from_settings(settings: &Settings) -> Result<DirSettings, BackupErro
     let backup_dir = shellexpand::full(&backup_dir)
         .map_err(|e| BackupError::ErrorWhileConvertingPath(e, backup_dir.clone()))?
         .to_string();
-    let pg_bin_dir = pg_bin_dir
-        .map(|d| {
+    let expand = |dir: Option<String>| {
+        dir.map(|d| {
             shellexpand::full(&d)
                 .map_err(|e| BackupError::ErrorWhileConvertingPath(e, d.clone()))
                 .map(|s| s.to_string())
         })
-        .transpose()?;
+        .transpose()
+    };
 
     Ok(DirSettings {
         backup_dir,
-        pg_bin_dir,
+        pg_bin_dir: expand(pg_bin_dir)?,
+        wal_archive_dir: expand(wal_archive_dir)?,
     })
 }
 
@@ -124,3 +167,32 @@ fn get_sqlite_files_paths(settings: &Settings) -> Result<Vec<PathBuf>, BackupErr
 
     Ok(paths)
 }
+
+/// Runs postgres command line tool (pg_dump, pg_basebackup etc..) from configured Postgres bin
+/// directory, or from PATH if bin directory is not configured
+fn run_pg_command(
+    pg_bin_dir_opt: &Option<String>,
+    command: &str,
+    args: &[&str],
+) -> Result<Output, BackupError> {
+    let pg_bin_dir = pg_bin_dir_opt.clone().unwrap_or_default();
+
+    let command = PathBuf::from_str(&pg_bin_dir)
+        .map_err(|_| BackupError::InvalidPath(pg_bin_dir.clone()))?
+        .join(command);
+
+    let result = Command::new(command.to_str().unwrap())
+        .args(args)
+        .output()
+        .map_err(|e| match (e.kind(), pg_bin_dir_opt.is_some()) {
+            (io::ErrorKind::NotFound, true) => BackupError::PgCommandNotFoundInBinPath,
+            (io::ErrorKind::NotFound, false) => BackupError::PgCommandNotFoundInPath,
+            _ => e.into(),
+        })?;
+
+    if !result.status.success() {
+        return Err(BackupError::CommandLineError(result));
+    }
+
+    Ok(result)
+}
@@ -1,43 +1,128 @@
 use super::*;
+use chrono::NaiveDateTime;
 use copy_dir::copy_dir;
 use diesel::{Connection, RunQueryDsl};
 use repository::DBBackendConnection;
 use service::settings::{is_develop, Settings};
-use std::{fs, io, path::PathBuf, process::Command, str::FromStr};
+use std::{fs, io, path::PathBuf, str::FromStr};
 
 pub(crate) fn restore(
     settings: &Settings,
     RestoreArguments {
         skip_confirmation,
         backup_name,
+        point_in_time,
+        target_data_dir,
     }: RestoreArguments,
 ) -> Result<(), BackupError> {
     let DirSettings {
         backup_dir,
         pg_bin_dir,
+        wal_archive_dir,
     } = get_dirs_from_settings(settings)?;
 
-    confirmation(skip_confirmation)?;
+    let point_in_time = point_in_time
+        .map(|point_in_time| parse_point_in_time(&point_in_time))
+        .transpose()?;
+
+    let backups_dir =
+        PathBuf::from_str(&backup_dir).map_err(|_| BackupError::InvalidPath(backup_dir.clone()))?;
+    let backups = read_backups(&backups_dir)?;
+
+    let backup = match (backup_name, point_in_time) {
+        (Some(backup_name), _) => backups
+            .iter()
+            .find(|backup| backup.name == backup_name)
+            .ok_or(BackupError::BackupNotFound(backup_name))?,
+        (None, Some(point_in_time)) => backup_for_point_in_time(
+            &backups,
+            point_in_time,
+            cfg!(feature = "postgres") && wal_archive_dir.is_some(),
+        )
+        .ok_or(BackupError::NoBackupForPointInTime(
+            point_in_time.to_string(),
+        ))?,
+        (None, None) => return Err(BackupError::BackupNotSpecified),
+    };
+    println!("Restoring from backup {}", backup.name);
+
+    if backup.metadata.mode == BackupMode::Full {
+        confirmation(skip_confirmation)?;
+
+        let Dirs {
+            file_dir,
+            database_dir,
+        } = get_backup_dir(&backups_dir, &backup.name);
+
+        copy_files(settings, &file_dir)?;
+
+        // Backup database
+        if cfg!(feature = "postgres") {
+            restore_postgres_database(settings, &database_dir, &pg_bin_dir)?;
+        } else {
+            copy_sqlite_files(settings, &database_dir)?;
+        }
+
+        if point_in_time.is_some() {
+            println!("Full backups cannot be replayed to a point in time, database is restored to the time of the backup {}", backup.metadata.created_datetime);
+        }
+        println!("Restore completed");
 
-    let Dirs {
-        file_dir,
-        database_dir,
-    } = get_backup_dir(backup_dir, backup_name)?;
+        return Ok(());
+    }
 
-    copy_files(settings, &file_dir)?;
+    // Base and incremental backups are restored into a new postgres data directory
+    let target_data_dir = target_data_dir.ok_or(BackupError::TargetDataDirNotSpecified)?;
+    let target_data_dir = PathBuf::from_str(&target_data_dir)
+        .map_err(|_| BackupError::InvalidPath(target_data_dir.clone()))?;
+    if target_data_dir.exists() {
+        if fs::read_dir(&target_data_dir)?.next().is_some() {
+            return Err(BackupError::TargetDataDirNotEmpty(target_data_dir));
+        }
+        fs::remove_dir(&target_data_dir)?;
+    }
+
+    confirmation(skip_confirmation)?;
 
-    // Backup database
-    if cfg!(feature = "postgres") {
-        restore_postgres_database(settings, &database_dir, pg_bin_dir)?;
-    } else {
-        copy_sqlite_files(settings, &database_dir)?;
+    let chain = backup_chain(&backups, &backup.name)?;
+    let database_dirs: Vec<PathBuf> = chain
+        .iter()
+        .map(|backup| get_backup_dir(&backups_dir, &backup.name).database_dir)
+        .collect();
+
+    copy_files(
+        settings,
+        &get_backup_dir(&backups_dir, &backup.name).file_dir,
+    )?;
+    restore_postgres_data_dir(&database_dirs, &target_data_dir, &pg_bin_dir)?;
+
+    match (point_in_time, wal_archive_dir) {
+        (Some(point_in_time), Some(wal_archive_dir)) => {
+            configure_point_in_time_recovery(&target_data_dir, &wal_archive_dir, point_in_time)?;
+            println!("Postgres will replay archived WAL up to {point_in_time} (UTC) on start up");
+        }
+        (Some(_), None) => println!(
+            "wal_archive_dir is not configured, database is restored to the time of the backup {}",
+            backup.metadata.created_datetime
+        ),
+        (None, _) => {}
     }
 
-    println!("Restore completed");
+    println!(
+        "Restore completed, stop postgres and start it with data directory {:?} (or replace existing data directory with it), then start omSupply server",
+        target_data_dir
+    );
 
     Ok(())
 }
 
+fn parse_point_in_time(point_in_time: &str) -> Result<NaiveDateTime, BackupError> {
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(point_in_time, format).ok())
+        .ok_or(BackupError::InvalidPointInTime(point_in_time.to_string()))
+}
+
 fn confirmation(skip_confirmation: bool) -> Result<(), BackupError> {
     if is_develop() && skip_confirmation {
         return Ok(());
@@ -63,19 +148,17 @@ struct Dirs {
     database_dir: PathBuf,
 }
 
-fn get_backup_dir(input_dir: String, backup_name: String) -> Result<Dirs, BackupError> {
-    let base_dir = PathBuf::from_str(&input_dir)
-        .map_err(|_| BackupError::InvalidPath(input_dir.clone()))?
-        .join(backup_name);
+fn get_backup_dir(backups_dir: &PathBuf, backup_name: &str) -> Dirs {
+    let base_dir = backups_dir.join(backup_name);
 
     let file_dir = base_dir.join(BACKUP_FILE_DIR);
 
     let database_dir = base_dir.join(BACKUP_DATABASE_DIR);
 
-    Ok(Dirs {
+    Dirs {
         file_dir,
         database_dir,
-    })
+    }
 }
 
 fn copy_files(settings: &Settings, backup_file_dir: &PathBuf) -> Result<(), BackupError> {
@@ -106,36 +189,70 @@ fn copy_files(settings: &Settings, backup_file_dir: &PathBuf) -> Result<(), Back
 fn restore_postgres_database(
     settings: &Settings,
     backup_database_dir: &PathBuf,
-    pg_bin_dir_opt: Option<String>,
+    pg_bin_dir: &Option<String>,
 ) -> Result<(), BackupError> {
-    let pg_bin_dir = pg_bin_dir_opt.clone().unwrap_or_default();
-
-    let command = PathBuf::from_str(&pg_bin_dir)
-        .map_err(|_| BackupError::InvalidPath(pg_bin_dir.clone()))?
-        .join("pg_restore");
-
     drop_and_create_database(settings)?;
 
     // Pg restore into database
-    let result = Command::new(command.to_str().unwrap())
-        .args([
+    run_pg_command(
+        pg_bin_dir,
+        "pg_restore",
+        &[
             "--format",
             "d",
             "--dbname",
             &settings.database.connection_string(),
             backup_database_dir.to_str().unwrap(),
-        ])
-        .output()
-        .map_err(|e| match (e.kind(), pg_bin_dir_opt.is_some()) {
-            (io::ErrorKind::NotFound, true) => BackupError::PgCommandNotFoundInBinPath,
-            (io::ErrorKind::NotFound, false) => BackupError::PgCommandNotFoundInPath,
-            _ => e.into(),
-        })?;
+        ],
+    )?;
 
-    if !result.status.success() {
-        return Err(BackupError::CommandLineError(result));
+    Ok(())
+}
+
+/// Base backup is copied as is, incremental backup is combined with the backups it depends on
+/// (`database_dirs` start with base backup)
+fn restore_postgres_data_dir(
+    database_dirs: &[PathBuf],
+    target_data_dir: &PathBuf,
+    pg_bin_dir: &Option<String>,
+) -> Result<(), BackupError> {
+    if let [base_backup_dir] = database_dirs {
+        copy_dir(base_backup_dir, target_data_dir).map_err(|e| {
+            BackupError::ProblemCopyingFolder(e, base_backup_dir.clone(), target_data_dir.clone())
+        })?;
+        return Ok(());
     }
 
+    let mut args: Vec<&str> = database_dirs
+        .iter()
+        .map(|dir| dir.to_str().unwrap())
+        .collect();
+    args.extend(["--output", target_data_dir.to_str().unwrap()]);
+
+    run_pg_command(pg_bin_dir, "pg_combinebackup", &args)?;
+
+    Ok(())
+}
+
+/// Postgres replays WAL from the archive up to the point in time when started with the data
+/// directory, and then promotes the database to accept writes
+fn configure_point_in_time_recovery(
+    target_data_dir: &PathBuf,
+    wal_archive_dir: &str,
+    point_in_time: NaiveDateTime,
+) -> Result<(), BackupError> {
+    fs::write(target_data_dir.join("recovery.signal"), "")?;
+
+    let wal_archive_dir = wal_archive_dir.replace('\\', "\\\\").replace('\'', "''");
+    let copy_command = if cfg!(windows) { "copy" } else { "cp" };
+    let recovery_config = format!(
+        "\n# Added by omSupply restore\nrestore_command = '{copy_command} \"{wal_archive_dir}/%f\" \"%p\"'\nrecovery_target_time = '{point_in_time} UTC'\nrecovery_target_action = 'promote'\n"
+    );
+
+    let auto_conf = target_data_dir.join("postgresql.auto.conf");
+    let existing = fs::read_to_string(&auto_conf).unwrap_or_default();
+    fs::write(&auto_conf, existing + &recovery_config)?;
+
     Ok(())
 }
 
@@ -140,8 +140,9 @@ enum Action {
     },
     /// Will back up database to a generated folder (the name of which will be returned).
     /// Folder will be generated in the backup directory specified by configuration file.
-    /// User can specify max number of backup to keep, see example configuration file
-    Backup,
+    /// User can specify max number of backup to keep, see example configuration file.
+    /// Backup is verified after it's created
+    Backup(BackupArguments),
     Restore(RestoreArguments),
     BuildStandardReports,
     UpsertReportsJson {
@@ -612,8 +613,8 @@ async fn main() -> anyhow::Result<()> {
 
             info!("Report upserted");
         }
-        Action::Backup => {
-            backup(&settings)?;
+        Action::Backup(arguments) => {
+            backup(&settings, arguments)?;
         }
         Action::Restore(arguments) => {
             restore(&settings, arguments)?;
@@ -5,17 +5,21 @@ pub mod property;
 pub mod types;
 
 use async_graphql::*;
+use chrono::{DateTime, Utc};
 use graphql_core::{
     pagination::PaginationInput,
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
 use repository::{assets::asset::AssetFilter, PaginationOption};
-use service::auth::{Resource, ResourceAccessRequest};
+use service::{
+    asset::uptime::{AssetUptimeError, AssetUptimeInput},
+    auth::{Resource, ResourceAccessRequest},
+};
 
 use types::{
     map_parse_error, AssetConnector, AssetFilterInput, AssetNode, AssetParseResponse,
-    AssetSortInput, AssetsResponse, GS1DataElement, ScannedDataParseError,
+    AssetSortInput, AssetUptimeReportNode, AssetsResponse, GS1DataElement, ScannedDataParseError,
 };
 
 #[derive(Default, Clone)]
@@ -90,6 +94,55 @@ impl AssetQueries {
             })),
         }
     }
+
+    /// Functional status of the store's assets over a period, per asset, per category and for
+    /// the store, computed from the asset log status changes
+    pub async fn asset_uptime(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        category_id: Option<String>,
+        asset_id: Option<String>,
+    ) -> Result<AssetUptimeReportNode> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryAsset,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+        let result = service_provider.asset_service.get_asset_uptime(
+            &service_context,
+            AssetUptimeInput {
+                from: from.naive_utc(),
+                to: to.naive_utc(),
+                category_id,
+                asset_id,
+            },
+        );
+
+        match result {
+            Ok(report) => Ok(AssetUptimeReportNode { report }),
+            Err(error) => {
+                let formatted_error = format!("{:#?}", error);
+                let graphql_error = match error {
+                    AssetUptimeError::InvalidPeriod => {
+                        StandardGraphqlError::BadUserInput(formatted_error)
+                    }
+                    AssetUptimeError::DatabaseError(_) => {
+                        StandardGraphqlError::InternalError(formatted_error)
+                    }
+                };
+                Err(graphql_error.extend())
+            }
+        }
+    }
 }
 
 #[derive(Default, Clone)]
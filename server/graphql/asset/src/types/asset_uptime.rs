@@ -0,0 +1,138 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use service::asset::uptime::{
+    AssetCategoryUptime, AssetUptime, AssetUptimeReport, AssetUptimeStatistics,
+};
+
+use super::{AssetNode, StatusType};
+
+pub struct AssetUptimeStatisticsNode {
+    pub statistics: AssetUptimeStatistics,
+}
+
+#[Object]
+impl AssetUptimeStatisticsNode {
+    pub async fn asset_count(&self) -> u32 {
+        self.statistics.asset_count
+    }
+
+    /// Assets functioning at the end of the period
+    pub async fn functional_asset_count(&self) -> u32 {
+        self.statistics.functional_asset_count
+    }
+
+    /// Functioning (or functioning but needs attention) time, in percent of the time with a
+    /// known status other than decommissioned. Null if there is no status for the period
+    pub async fn functional_percentage(&self) -> Option<f64> {
+        self.statistics.functional_percentage()
+    }
+
+    pub async fn functional_milliseconds(&self) -> f64 {
+        self.statistics.functional_milliseconds as f64
+    }
+
+    pub async fn observed_milliseconds(&self) -> f64 {
+        self.statistics.observed_milliseconds as f64
+    }
+
+    /// Changes to not functioning during the period
+    pub async fn breakdown_count(&self) -> u32 {
+        self.statistics.breakdown_count
+    }
+
+    /// Breakdowns that were repaired (functioning again) during the period
+    pub async fn repair_count(&self) -> u32 {
+        self.statistics.repair_count
+    }
+
+    pub async fn mean_time_to_repair_milliseconds(&self) -> Option<f64> {
+        self.statistics
+            .mean_time_to_repair_milliseconds()
+            .map(|milliseconds| milliseconds as f64)
+    }
+
+    /// Temperature breaches that started during the period at locations of the assets
+    pub async fn temperature_breach_count(&self) -> u32 {
+        self.statistics.temperature_breach_count
+    }
+}
+
+pub struct AssetUptimeNode {
+    pub uptime: AssetUptime,
+}
+
+#[Object]
+impl AssetUptimeNode {
+    pub async fn asset(&self) -> AssetNode {
+        AssetNode::from_domain(self.uptime.asset.clone())
+    }
+
+    /// Status at the end of the period
+    pub async fn status(&self) -> Option<StatusType> {
+        self.uptime.status.as_ref().map(StatusType::from_domain)
+    }
+
+    pub async fn statistics(&self) -> AssetUptimeStatisticsNode {
+        AssetUptimeStatisticsNode {
+            statistics: self.uptime.statistics.clone(),
+        }
+    }
+}
+
+pub struct AssetCategoryUptimeNode {
+    pub uptime: AssetCategoryUptime,
+}
+
+#[Object]
+impl AssetCategoryUptimeNode {
+    /// Null for assets without a category
+    pub async fn category_id(&self) -> &Option<String> {
+        &self.uptime.category_id
+    }
+
+    pub async fn statistics(&self) -> AssetUptimeStatisticsNode {
+        AssetUptimeStatisticsNode {
+            statistics: self.uptime.statistics.clone(),
+        }
+    }
+}
+
+pub struct AssetUptimeReportNode {
+    pub report: AssetUptimeReport,
+}
+
+#[Object]
+impl AssetUptimeReportNode {
+    pub async fn from(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.report.from, Utc)
+    }
+
+    pub async fn to(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.report.to, Utc)
+    }
+
+    pub async fn assets(&self) -> Vec<AssetUptimeNode> {
+        self.report
+            .assets
+            .iter()
+            .cloned()
+            .map(|uptime| AssetUptimeNode { uptime })
+            .collect()
+    }
+
+    pub async fn categories(&self) -> Vec<AssetCategoryUptimeNode> {
+        self.report
+            .categories
+            .iter()
+            .cloned()
+            .map(|uptime| AssetCategoryUptimeNode { uptime })
+            .collect()
+    }
+
+    /// Totals for all assets of the store
+    pub async fn store(&self) -> AssetUptimeStatisticsNode {
+        AssetUptimeStatisticsNode {
+            statistics: self.report.store.clone(),
+        }
+    }
+}
@@ -6,3 +6,5 @@ pub mod asset_property;
 pub use asset_property::*;
 pub mod gs1;
 pub use gs1::*;
+pub mod asset_uptime;
+pub use asset_uptime::*;
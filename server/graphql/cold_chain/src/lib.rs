@@ -7,13 +7,22 @@ use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
-use mutations::{update_sensor, UpdateSensorInput, UpdateSensorResponse};
+use graphql_types::types::DeleteResponse;
+use mutations::{
+    acknowledge_temperature_notification, delete_temperature_notification_rule, update_sensor,
+    upsert_temperature_notification_rule, UpdateSensorInput, UpdateSensorResponse,
+    UpsertTemperatureNotificationRuleInput,
+};
 use repository::{
     temperature_breach::TemperatureBreachFilter, EqualFilter, PaginationOption, SensorFilter,
     TemperatureBreachSortField,
 };
 use repository::{temperature_log::TemperatureLogFilter, TemperatureBreachSort};
-use service::auth::{Resource, ResourceAccessRequest};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::notification::get_temperature_notification_rules,
+};
+use types::temperature_breach::TemperatureBreachNode;
 use types::{
     sensor::{SensorConnector, SensorFilterInput, SensorsResponse},
     temperature_breach::{
@@ -27,6 +36,9 @@ use types::{
     temperature_notification::{
         TemperatureNotificationConnector, TemperatureNotificationsResponse,
     },
+    temperature_notification_rule::{
+        TemperatureNotificationRuleConnector, TemperatureNotificationRuleNode,
+    },
 };
 
 use crate::types::sensor::SensorSortInput;
@@ -215,6 +227,27 @@ impl ColdChainQueries {
             sensors,
         )))
     }
+
+    /// Rules for who is notified about temperature breaches of the store
+    pub async fn temperature_notification_rules(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<TemperatureNotificationRuleConnector> {
+        let user = validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryTemperatureBreach,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+        let service_context = ctx
+            .service_provider()
+            .context(store_id.clone(), user.user_id)?;
+
+        let rules = get_temperature_notification_rules(&service_context, &store_id)?;
+        Ok(TemperatureNotificationRuleConnector::from_domain(rules))
+    }
 }
 
 #[derive(Default, Clone)]
@@ -239,6 +272,35 @@ impl ColdChainMutations {
     ) -> Result<UpdateSensorResponse> {
         update_sensor(ctx, &store_id, input)
     }
+
+    async fn upsert_temperature_notification_rule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertTemperatureNotificationRuleInput,
+    ) -> Result<TemperatureNotificationRuleNode> {
+        upsert_temperature_notification_rule(ctx, store_id, input)
+    }
+
+    async fn delete_temperature_notification_rule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<DeleteResponse> {
+        delete_temperature_notification_rule(ctx, store_id, id)
+    }
+
+    /// Acknowledges the temperature breach of a notification, e.g. from a link in the notification
+    async fn acknowledge_temperature_notification(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Id of the notification")] id: String,
+        comment: Option<String>,
+    ) -> Result<TemperatureBreachNode> {
+        acknowledge_temperature_notification(ctx, store_id, id, comment)
+    }
 }
 
 #[cfg(test)]
@@ -2,3 +2,5 @@ pub mod temperature_breach;
 pub use temperature_breach::*;
 pub mod sensor;
 pub use sensor::*;
+pub mod temperature_notification_rule;
+pub use temperature_notification_rule::*;
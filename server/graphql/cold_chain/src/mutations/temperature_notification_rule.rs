@@ -0,0 +1,179 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cold_chain::{
+        notification::{
+            acknowledge_temperature_notification as acknowledge,
+            delete_temperature_notification_rule as delete,
+            upsert_temperature_notification_rule as upsert,
+            AcknowledgeTemperatureNotificationError, DeleteTemperatureNotificationRuleError,
+            UpsertTemperatureNotificationRule, UpsertTemperatureNotificationRuleError,
+        },
+        update_temperature_breach::UpdateTemperatureBreachError,
+    },
+};
+
+use crate::types::{
+    temperature_breach::{TemperatureBreachNode, TemperatureBreachNodeType},
+    temperature_notification_rule::{
+        TemperatureNotificationChannelNode, TemperatureNotificationRuleNode,
+    },
+};
+
+#[derive(InputObject)]
+pub struct UpsertTemperatureNotificationRuleInput {
+    pub id: String,
+    /// Only notify breaches at this location, all locations if not set
+    pub location_id: Option<String>,
+    /// Only notify breaches of this type, all types if not set
+    pub breach_type: Option<TemperatureBreachNodeType>,
+    /// Breaches shorter than this are not notified
+    pub minimum_duration_milliseconds: i32,
+    pub channel: TemperatureNotificationChannelNode,
+    /// Email address, webhook URL or log file name within the server's temperature_notifications
+    /// directory (empty to only log) depending on the channel
+    pub recipient: String,
+    /// Notify again if the breach is still unacknowledged this long after the first notification
+    pub escalation_after_milliseconds: Option<i32>,
+    /// Recipient of the escalation, defaults to the recipient
+    pub escalation_recipient: Option<String>,
+    pub is_active: bool,
+}
+
+pub fn upsert_temperature_notification_rule(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: UpsertTemperatureNotificationRuleInput,
+) -> Result<TemperatureNotificationRuleNode> {
+    // Rules make the server write files and send requests to any URL
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_context = ctx.service_provider().context(store_id, user.user_id)?;
+
+    match upsert(&service_context, input.to_domain()) {
+        Ok(rule) => Ok(TemperatureNotificationRuleNode { rule }),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                UpsertTemperatureNotificationRuleError::RuleDoesNotBelongToCurrentStore
+                | UpsertTemperatureNotificationRuleError::LocationDoesNotExist
+                | UpsertTemperatureNotificationRuleError::InvalidDuration
+                | UpsertTemperatureNotificationRuleError::InvalidRecipient(_) => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                UpsertTemperatureNotificationRuleError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn delete_temperature_notification_rule(
+    ctx: &Context<'_>,
+    store_id: String,
+    id: String,
+) -> Result<DeleteResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_context = ctx.service_provider().context(store_id, user.user_id)?;
+
+    match delete(&service_context, id) {
+        Ok(id) => Ok(DeleteResponse(id)),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                DeleteTemperatureNotificationRuleError::RuleDoesNotExist
+                | DeleteTemperatureNotificationRuleError::RuleDoesNotBelongToCurrentStore => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                DeleteTemperatureNotificationRuleError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+/// Acknowledges the temperature breach a notification was sent for
+pub fn acknowledge_temperature_notification(
+    ctx: &Context<'_>,
+    store_id: String,
+    id: String,
+    comment: Option<String>,
+) -> Result<TemperatureBreachNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateTemperatureBreach,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_context = ctx.service_provider().context(store_id, user.user_id)?;
+
+    match acknowledge(&service_context, &id, comment) {
+        Ok(temperature_breach) => Ok(TemperatureBreachNode::from_domain(temperature_breach)),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                AcknowledgeTemperatureNotificationError::NotificationDoesNotExist
+                | AcknowledgeTemperatureNotificationError::TemperatureBreachDoesNotExist
+                | AcknowledgeTemperatureNotificationError::UpdateTemperatureBreach(
+                    UpdateTemperatureBreachError::TemperatureBreachDoesNotBelongToCurrentStore
+                    | UpdateTemperatureBreachError::LocationIsOnHold
+                    | UpdateTemperatureBreachError::CommentNotProvided,
+                ) => StandardGraphqlError::BadUserInput(formatted_error),
+                AcknowledgeTemperatureNotificationError::UpdateTemperatureBreach(_)
+                | AcknowledgeTemperatureNotificationError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl UpsertTemperatureNotificationRuleInput {
+    pub fn to_domain(self) -> UpsertTemperatureNotificationRule {
+        let UpsertTemperatureNotificationRuleInput {
+            id,
+            location_id,
+            breach_type,
+            minimum_duration_milliseconds,
+            channel,
+            recipient,
+            escalation_after_milliseconds,
+            escalation_recipient,
+            is_active,
+        } = self;
+
+        UpsertTemperatureNotificationRule {
+            id,
+            location_id,
+            breach_type: breach_type.map(TemperatureBreachNodeType::to_domain),
+            minimum_duration_milliseconds,
+            channel: channel.to_domain(),
+            recipient,
+            escalation_after_milliseconds,
+            escalation_recipient,
+            is_active,
+        }
+    }
+}
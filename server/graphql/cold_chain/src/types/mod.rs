@@ -2,3 +2,4 @@ pub(crate) mod sensor;
 pub(crate) mod temperature_breach;
 pub(crate) mod temperature_log;
 pub(crate) mod temperature_notification;
+pub(crate) mod temperature_notification_rule;
@@ -71,6 +71,9 @@ pub enum SensorNodeType {
     BlueMaestro,
     Laird,
     Berlinger,
+    LogTag,
+    ThirtyDayRecorder,
+    Generic,
 }
 
 #[Object]
@@ -252,6 +255,9 @@ impl SensorNodeType {
             from::BlueMaestro => to::BlueMaestro,
             from::Laird => to::Laird,
             from::Berlinger => to::Berlinger,
+            from::LogTag => to::LogTag,
+            from::ThirtyDayRecorder => to::ThirtyDayRecorder,
+            from::Generic => to::Generic,
         }
     }
 
@@ -263,6 +269,9 @@ impl SensorNodeType {
             from::BlueMaestro => to::BlueMaestro,
             from::Laird => to::Laird,
             from::Berlinger => to::Berlinger,
+            from::LogTag => to::LogTag,
+            from::ThirtyDayRecorder => to::ThirtyDayRecorder,
+            from::Generic => to::Generic,
         }
     }
 }
@@ -0,0 +1,95 @@
+use async_graphql::*;
+use repository::{TemperatureNotificationChannel, TemperatureNotificationRuleRow};
+
+use super::temperature_breach::TemperatureBreachNodeType;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TemperatureNotificationChannelNode {
+    Email,
+    Webhook,
+    Log,
+}
+
+pub struct TemperatureNotificationRuleNode {
+    pub rule: TemperatureNotificationRuleRow,
+}
+
+#[derive(SimpleObject)]
+pub struct TemperatureNotificationRuleConnector {
+    total_count: u32,
+    nodes: Vec<TemperatureNotificationRuleNode>,
+}
+
+#[Object]
+impl TemperatureNotificationRuleNode {
+    pub async fn id(&self) -> &str {
+        &self.rule.id
+    }
+
+    /// Only breaches at this location are notified, all locations if null
+    pub async fn location_id(&self) -> &Option<String> {
+        &self.rule.location_id
+    }
+
+    /// Only breaches of this type are notified, all types if null
+    pub async fn breach_type(&self) -> Option<TemperatureBreachNodeType> {
+        self.rule
+            .breach_type
+            .as_ref()
+            .map(TemperatureBreachNodeType::from_domain)
+    }
+
+    pub async fn minimum_duration_milliseconds(&self) -> i32 {
+        self.rule.minimum_duration_milliseconds
+    }
+
+    pub async fn channel(&self) -> TemperatureNotificationChannelNode {
+        TemperatureNotificationChannelNode::from_domain(&self.rule.channel)
+    }
+
+    pub async fn recipient(&self) -> &str {
+        &self.rule.recipient
+    }
+
+    pub async fn escalation_after_milliseconds(&self) -> Option<i32> {
+        self.rule.escalation_after_milliseconds
+    }
+
+    pub async fn escalation_recipient(&self) -> &Option<String> {
+        &self.rule.escalation_recipient
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.rule.is_active
+    }
+}
+
+impl TemperatureNotificationRuleConnector {
+    pub fn from_domain(rules: Vec<TemperatureNotificationRuleRow>) -> Self {
+        TemperatureNotificationRuleConnector {
+            total_count: rules.len() as u32,
+            nodes: rules
+                .into_iter()
+                .map(|rule| TemperatureNotificationRuleNode { rule })
+                .collect(),
+        }
+    }
+}
+
+impl TemperatureNotificationChannelNode {
+    pub fn from_domain(from: &TemperatureNotificationChannel) -> Self {
+        match from {
+            TemperatureNotificationChannel::Email => TemperatureNotificationChannelNode::Email,
+            TemperatureNotificationChannel::Webhook => TemperatureNotificationChannelNode::Webhook,
+            TemperatureNotificationChannel::Log => TemperatureNotificationChannelNode::Log,
+        }
+    }
+
+    pub fn to_domain(self) -> TemperatureNotificationChannel {
+        match self {
+            TemperatureNotificationChannelNode::Email => TemperatureNotificationChannel::Email,
+            TemperatureNotificationChannelNode::Webhook => TemperatureNotificationChannel::Webhook,
+            TemperatureNotificationChannelNode::Log => TemperatureNotificationChannel::Log,
+        }
+    }
+}
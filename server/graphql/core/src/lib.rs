@@ -89,6 +89,16 @@ pub struct RequestUserData {
     pub refresh_token: Option<String>,
 }
 
+impl RequestUserData {
+    /// User data for requests made by the server itself, e.g. for scheduled reports
+    pub fn from_auth_token(auth_token: String) -> Self {
+        RequestUserData {
+            auth_token: Some(auth_token),
+            refresh_token: None,
+        }
+    }
+}
+
 pub fn auth_data_from_request(http_req: &HttpRequest) -> RequestUserData {
     let headers = http_req.headers();
     // retrieve auth token
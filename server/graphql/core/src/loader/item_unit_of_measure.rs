@@ -0,0 +1,26 @@
+use actix_web::web::Data;
+use repository::{ItemUnitOfMeasureRow, ItemUnitOfMeasureRowRepository, RepositoryError};
+
+use async_graphql::dataloader::*;
+use service::service_provider::ServiceProvider;
+use std::collections::HashMap;
+
+pub struct ItemUnitsOfMeasureByItemIdLoader {
+    pub service_provider: Data<ServiceProvider>,
+}
+
+impl Loader<String> for ItemUnitsOfMeasureByItemIdLoader {
+    type Value = Vec<ItemUnitOfMeasureRow>;
+    type Error = RepositoryError;
+
+    async fn load(&self, item_ids: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let service_context = self.service_provider.basic_context()?;
+        let repo = ItemUnitOfMeasureRowRepository::new(&service_context.connection);
+
+        let mut map: HashMap<String, Vec<ItemUnitOfMeasureRow>> = HashMap::new();
+        for (item_id, unit_of_measure) in repo.find_many_by_item_ids(item_ids)? {
+            map.entry(item_id).or_default().push(unit_of_measure);
+        }
+        Ok(map)
+    }
+}
@@ -126,6 +126,20 @@ pub async fn get_loaders(
         async_std::task::spawn,
     );
 
+    let stocktake_blind_count_loader = DataLoader::new(
+        StocktakeBlindCountByStocktakeIdLoader {
+            connection_manager: connection_manager.clone(),
+        },
+        async_std::task::spawn,
+    );
+
+    let stocktake_line_recount_loader = DataLoader::new(
+        StocktakeLineRecountByStocktakeIdLoader {
+            connection_manager: connection_manager.clone(),
+        },
+        async_std::task::spawn,
+    );
+
     let requisitions_by_id_loader = DataLoader::new(
         RequisitionsByIdLoader {
             service_provider: service_provider.clone(),
@@ -270,6 +284,8 @@ pub async fn get_loaders(
     loaders.insert(requisition_line_by_linked_requisition_line_id_loader);
     loaders.insert(item_stats_for_item_loader);
     loaders.insert(stocktake_line_loader);
+    loaders.insert(stocktake_blind_count_loader);
+    loaders.insert(stocktake_line_recount_loader);
     loaders.insert(requisition_line_supply_status_loader);
     loaders.insert(requisition_lines_remaining_to_supply_loader);
     loaders.insert(name_row_loader);
@@ -424,6 +440,12 @@ pub async fn get_loaders(
         },
         async_std::task::spawn,
     ));
+    loaders.insert(DataLoader::new(
+        ItemUnitsOfMeasureByItemIdLoader {
+            service_provider: service_provider.clone(),
+        },
+        async_std::task::spawn,
+    ));
     loaders.insert(DataLoader::new(
         PackagingVariantRowLoader {
             service_provider: service_provider.clone(),
@@ -18,6 +18,7 @@ mod invoice_line;
 mod item;
 mod item_stats;
 mod item_stock_on_hand;
+mod item_unit_of_measure;
 mod item_variant;
 mod json_schema;
 mod loader_registry;
@@ -38,6 +39,7 @@ mod return_reason;
 mod rnr_form_line;
 mod sensor;
 mod stock_line;
+mod stocktake_blind_count;
 mod stocktake_lines;
 mod store;
 mod sync_file_reference;
@@ -71,6 +73,7 @@ pub use invoice_line::*;
 pub use item::ItemLoader;
 pub use item_stats::*;
 pub use item_stock_on_hand::*;
+pub use item_unit_of_measure::*;
 pub use item_variant::*;
 pub use json_schema::*;
 pub use loader_registry::{get_loaders, LoaderMap, LoaderRegistry};
@@ -91,6 +94,7 @@ pub use return_reason::*;
 pub use rnr_form_line::*;
 pub use sensor::*;
 pub use stock_line::*;
+pub use stocktake_blind_count::*;
 pub use stocktake_lines::*;
 pub use store::*;
 pub use sync_file_reference::*;
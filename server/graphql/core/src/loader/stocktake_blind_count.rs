@@ -0,0 +1,55 @@
+use async_graphql::dataloader::*;
+use repository::{
+    RepositoryError, StocktakeBlindCountRow, StocktakeBlindCountRowRepository,
+    StocktakeLineRecountRow, StocktakeLineRecountRowRepository, StorageConnectionManager,
+};
+use std::collections::HashMap;
+
+pub struct StocktakeBlindCountByStocktakeIdLoader {
+    pub connection_manager: StorageConnectionManager,
+}
+
+impl Loader<String> for StocktakeBlindCountByStocktakeIdLoader {
+    type Value = StocktakeBlindCountRow;
+    type Error = RepositoryError;
+
+    async fn load(
+        &self,
+        stocktake_ids: &[String],
+    ) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let connection = self.connection_manager.connection()?;
+        let result =
+            StocktakeBlindCountRowRepository::new(&connection).find_many_by_ids(stocktake_ids)?;
+
+        Ok(result
+            .into_iter()
+            .map(|blind_count| (blind_count.id.clone(), blind_count))
+            .collect())
+    }
+}
+
+pub struct StocktakeLineRecountByStocktakeIdLoader {
+    pub connection_manager: StorageConnectionManager,
+}
+
+impl Loader<String> for StocktakeLineRecountByStocktakeIdLoader {
+    type Value = Vec<StocktakeLineRecountRow>;
+    type Error = RepositoryError;
+
+    async fn load(
+        &self,
+        stocktake_ids: &[String],
+    ) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let connection = self.connection_manager.connection()?;
+        let recounts = StocktakeLineRecountRowRepository::new(&connection)
+            .find_many_by_stocktake_ids(stocktake_ids)?;
+
+        let mut map: HashMap<String, Vec<StocktakeLineRecountRow>> = HashMap::new();
+        for recount in recounts {
+            map.entry(recount.stocktake_id.clone())
+                .or_default()
+                .push(recount);
+        }
+        Ok(map)
+    }
+}
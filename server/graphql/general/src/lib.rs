@@ -6,6 +6,7 @@ pub mod types;
 pub use self::queries::sync_status::*;
 use self::queries::*;
 
+use chrono::{DateTime, Utc};
 use graphql_core::pagination::PaginationInput;
 use service::sync::CentralServerConfig;
 
@@ -16,6 +17,7 @@ use graphql_types::types::{
 };
 use mutations::{
     barcode::{insert_barcode, BarcodeInput},
+    close_period::{close_period, ClosePeriodInput, ClosePeriodResponse},
     common::SyncSettingsInput,
     display_settings::{
         update_display_settings, DisplaySettingsInput, UpdateDisplaySettingsResponse,
@@ -25,9 +27,24 @@ use mutations::{
         update_label_printer_settings, LabelPrinterSettingsInput,
         UpdateLabelPrinterSettingsResponse,
     },
+    label_template::{
+        delete_label_printer, delete_label_template, print_labels, upsert_label_printer,
+        upsert_label_template, DeleteLabelPrinterResponse, DeleteLabelTemplateResponse,
+        PrintLabelsInput, PrintLabelsResponse, UpsertLabelPrinterInput, UpsertLabelPrinterResponse,
+        UpsertLabelTemplateInput, UpsertLabelTemplateResponse,
+    },
     log::{update_log_level, LogLevelInput, UpsertLogLevelResponse},
     manual_sync::manual_sync,
+    price_list::{
+        delete_price_list, upsert_price_list, DeletePriceListResponse, UpsertPriceListInput,
+        UpsertPriceListResponse,
+    },
+    shelf_life_rule::{
+        delete_shelf_life_rule, upsert_shelf_life_rule, DeleteShelfLifeRuleResponse,
+        UpsertShelfLifeRuleInput, UpsertShelfLifeRuleResponse,
+    },
     sync_settings::{update_sync_settings, UpdateSyncSettingsResponse},
+    transfer_dead_letter::{retry_transfer_dead_letter, RetryTransferDeadLetterResponse},
     update_name_properties::{
         update_name_properties, UpdateNamePropertiesInput, UpdateNamePropertiesResponse,
     },
@@ -71,6 +88,16 @@ impl GeneralQueries {
         item_price(ctx, store_id, input).await
     }
 
+    /// Forecast monthly consumption of items, adjusted for days the items were out of stock
+    pub async fn item_forecasts(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: ItemForecastInput,
+    ) -> Result<ItemForecastConnector> {
+        item_forecasts(ctx, store_id, input)
+    }
+
     pub async fn logout(&self, ctx: &Context<'_>) -> Result<LogoutResponse> {
         logout(ctx)
     }
@@ -167,6 +194,73 @@ impl GeneralQueries {
         ledger(ctx, store_id, filter, sort)
     }
 
+    /// Stock movements of an item with opening, running and closing balances
+    pub async fn stock_card(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        item_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<LedgerFilterInput>,
+    ) -> Result<StockCardResponse> {
+        stock_card(ctx, store_id, item_id, page, filter)
+    }
+
+    /// Value of the store's stock at the datetime, FIFO by default
+    pub async fn inventory_valuation(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        datetime: DateTime<Utc>,
+        method: Option<ValuationMethodNode>,
+        item_ids: Option<Vec<String>>,
+    ) -> Result<InventoryValuationNode> {
+        inventory_valuation(ctx, store_id, datetime, method, item_ids)
+    }
+
+    pub async fn period_closes(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<PeriodCloseNode>> {
+        period_closes(ctx, store_id)
+    }
+
+    /// Minimum remaining shelf life rules applied when allocating stock to customers
+    pub async fn shelf_life_rules(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<ShelfLifeRuleNode>> {
+        shelf_life_rules(ctx, store_id)
+    }
+
+    /// Customer price lists of the store
+    pub async fn price_lists(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<PriceListNode>> {
+        price_lists(ctx, store_id)
+    }
+
+    pub async fn label_templates(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        template_type: Option<LabelTemplateTypeNode>,
+    ) -> Result<Vec<LabelTemplateNode>> {
+        label_templates(ctx, store_id, template_type)
+    }
+
+    pub async fn label_printers(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<LabelPrinterNode>> {
+        label_printers(ctx, store_id)
+    }
+
     pub async fn invoice_counts(
         &self,
         ctx: &Context<'_>,
@@ -233,6 +327,23 @@ impl GeneralQueries {
         number_of_records_in_push_queue(ctx)
     }
 
+    /// Cursor lag and errors of the background changelog processors
+    pub async fn changelog_processor_statuses(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Vec<ChangelogProcessorStatusNode>> {
+        changelog_processor_statuses(ctx)
+    }
+
+    /// Requisition and shipment transfers that failed to process, unresolved only by default
+    pub async fn transfer_dead_letters(
+        &self,
+        ctx: &Context<'_>,
+        include_resolved: Option<bool>,
+    ) -> Result<Vec<TransferDeadLetterNode>> {
+        transfer_dead_letters(ctx, include_resolved)
+    }
+
     pub async fn sync_settings(&self, ctx: &Context<'_>) -> Result<Option<SyncSettingsNode>> {
         sync_settings(ctx, true)
     }
@@ -291,6 +402,15 @@ impl GeneralQueries {
         barcode_by_gtin(ctx, store_id, gtin)
     }
 
+    pub async fn scan_barcode(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        scanned: String,
+    ) -> Result<ScannedBarcodeNode> {
+        scan_barcode(ctx, store_id, scanned)
+    }
+
     pub async fn requisition_counts(
         &self,
         ctx: &Context<'_>,
@@ -483,6 +603,103 @@ impl GeneralMutations {
     ) -> Result<UpdateNamePropertiesResponse> {
         update_name_properties(ctx, &store_id, input)
     }
+
+    /// Processes the failed transfer record again on the next run of the transfer processor
+    pub async fn retry_transfer_dead_letter(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<RetryTransferDeadLetterResponse> {
+        retry_transfer_dead_letter(ctx, id)
+    }
+
+    /// Closes a month of the store, transactions can't be backdated into a closed month
+    pub async fn close_period(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: ClosePeriodInput,
+    ) -> Result<ClosePeriodResponse> {
+        close_period(ctx, &store_id, input)
+    }
+
+    pub async fn upsert_shelf_life_rule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertShelfLifeRuleInput,
+    ) -> Result<UpsertShelfLifeRuleResponse> {
+        upsert_shelf_life_rule(ctx, &store_id, input)
+    }
+
+    pub async fn delete_shelf_life_rule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<DeleteShelfLifeRuleResponse> {
+        delete_shelf_life_rule(ctx, &store_id, id)
+    }
+
+    pub async fn upsert_price_list(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertPriceListInput,
+    ) -> Result<UpsertPriceListResponse> {
+        upsert_price_list(ctx, &store_id, input)
+    }
+
+    pub async fn delete_price_list(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<DeletePriceListResponse> {
+        delete_price_list(ctx, &store_id, id)
+    }
+
+    pub async fn upsert_label_template(
+        &self,
+        ctx: &Context<'_>,
+        input: UpsertLabelTemplateInput,
+    ) -> Result<UpsertLabelTemplateResponse> {
+        upsert_label_template(ctx, input)
+    }
+
+    pub async fn delete_label_template(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<DeleteLabelTemplateResponse> {
+        delete_label_template(ctx, id)
+    }
+
+    pub async fn upsert_label_printer(
+        &self,
+        ctx: &Context<'_>,
+        input: UpsertLabelPrinterInput,
+    ) -> Result<UpsertLabelPrinterResponse> {
+        upsert_label_printer(ctx, input)
+    }
+
+    pub async fn delete_label_printer(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> Result<DeleteLabelPrinterResponse> {
+        delete_label_printer(ctx, id)
+    }
+
+    /// Renders the labels with the template and sends them to the printer in a single job
+    pub async fn print_labels(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: PrintLabelsInput,
+    ) -> Result<PrintLabelsResponse> {
+        print_labels(ctx, &store_id, input)
+    }
 }
 
 /// Auth is not checked during initialisation stage
@@ -0,0 +1,76 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::PeriodCloseRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    period_close::{ClosePeriod, ClosePeriodError as ServiceError},
+};
+
+use crate::queries::PeriodCloseNode;
+
+#[derive(InputObject)]
+pub struct ClosePeriodInput {
+    pub id: String,
+    pub year: i32,
+    /// 1 to 12
+    pub month: u32,
+}
+
+#[derive(Union)]
+pub enum ClosePeriodResponse {
+    Response(PeriodCloseNode),
+}
+
+pub fn close_period(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ClosePeriodInput,
+) -> Result<ClosePeriodResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ClosePeriod,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    map_response(
+        service_provider
+            .period_close_service
+            .close_period(&service_context, input.to_domain()),
+    )
+}
+
+impl ClosePeriodInput {
+    pub fn to_domain(self) -> ClosePeriod {
+        let ClosePeriodInput { id, year, month } = self;
+        ClosePeriod { id, year, month }
+    }
+}
+
+pub fn map_response(from: Result<PeriodCloseRow, ServiceError>) -> Result<ClosePeriodResponse> {
+    match from {
+        Ok(period_close) => Ok(ClosePeriodResponse::Response(PeriodCloseNode::from_domain(
+            period_close,
+        ))),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                ServiceError::InvalidMonth
+                | ServiceError::PeriodNotEnded
+                | ServiceError::PeriodAlreadyClosed => BadUserInput(formatted_error),
+                ServiceError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
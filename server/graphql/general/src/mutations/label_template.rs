@@ -0,0 +1,349 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use repository::{LabelPrinterRow, LabelTemplateRow};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    print::{
+        label_printer::{DeleteLabelPrinterError, UpsertLabelPrinter, UpsertLabelPrinterError},
+        label_template::{
+            DeleteLabelTemplateError, PrintLabels, PrintLabelsError, UpsertLabelTemplate,
+            UpsertLabelTemplateError,
+        },
+    },
+};
+
+use crate::queries::{
+    LabelPrinterNode, LabelTemplateNode, LabelTemplateTypeNode, PrinterLanguageNode,
+};
+
+#[derive(InputObject)]
+pub struct UpsertLabelTemplateInput {
+    pub id: String,
+    pub name: String,
+    pub template_type: LabelTemplateTypeNode,
+    pub printer_language: PrinterLanguageNode,
+    /// Tera template rendering the printer commands of a single label
+    pub template: String,
+    /// Label width in dots
+    pub label_width: i32,
+    /// Label height in dots
+    pub label_height: i32,
+}
+
+#[derive(InputObject)]
+pub struct UpsertLabelPrinterInput {
+    pub id: String,
+    pub name: String,
+    pub address: String,
+    pub port: i32,
+    pub printer_language: PrinterLanguageNode,
+}
+
+#[derive(InputObject)]
+pub struct PrintLabelsInput {
+    pub template_id: String,
+    /// Printer from the label printer settings is used if not set
+    pub printer_id: Option<String>,
+    /// Ids of the stock lines, locations or assets to print, depending on the template type
+    pub record_ids: Vec<String>,
+    /// Number of labels printed for each record, defaults to 1 (at most 100, and at most 1000
+    /// labels in total)
+    pub copies: Option<u32>,
+}
+
+#[derive(SimpleObject)]
+pub struct PrintLabelsNode {
+    pub number_of_labels: u32,
+}
+
+#[derive(Union)]
+pub enum UpsertLabelTemplateResponse {
+    Response(LabelTemplateNode),
+}
+
+#[derive(Union)]
+pub enum UpsertLabelPrinterResponse {
+    Response(LabelPrinterNode),
+}
+
+#[derive(Union)]
+pub enum DeleteLabelTemplateResponse {
+    Response(DeleteResponse),
+}
+
+#[derive(Union)]
+pub enum DeleteLabelPrinterResponse {
+    Response(DeleteResponse),
+}
+
+#[derive(Union)]
+pub enum PrintLabelsResponse {
+    Response(PrintLabelsNode),
+}
+
+pub fn upsert_label_template(
+    ctx: &Context<'_>,
+    input: UpsertLabelTemplateInput,
+) -> Result<UpsertLabelTemplateResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    map_upsert_label_template_response(
+        service_provider
+            .label_printing_service
+            .upsert_label_template(&service_context, input.to_domain()),
+    )
+}
+
+pub fn delete_label_template(ctx: &Context<'_>, id: String) -> Result<DeleteLabelTemplateResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    match service_provider
+        .label_printing_service
+        .delete_label_template(&service_context, id)
+    {
+        Ok(id) => Ok(DeleteLabelTemplateResponse::Response(DeleteResponse(id))),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                DeleteLabelTemplateError::LabelTemplateDoesNotExist => {
+                    BadUserInput(formatted_error)
+                }
+                DeleteLabelTemplateError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn upsert_label_printer(
+    ctx: &Context<'_>,
+    input: UpsertLabelPrinterInput,
+) -> Result<UpsertLabelPrinterResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    map_upsert_label_printer_response(
+        service_provider
+            .label_printing_service
+            .upsert_label_printer(&service_context, input.to_domain()),
+    )
+}
+
+pub fn delete_label_printer(ctx: &Context<'_>, id: String) -> Result<DeleteLabelPrinterResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    match service_provider
+        .label_printing_service
+        .delete_label_printer(&service_context, id)
+    {
+        Ok(id) => Ok(DeleteLabelPrinterResponse::Response(DeleteResponse(id))),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                DeleteLabelPrinterError::LabelPrinterDoesNotExist => BadUserInput(formatted_error),
+                DeleteLabelPrinterError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn print_labels(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: PrintLabelsInput,
+) -> Result<PrintLabelsResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::PrintLabels,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    map_print_labels_response(
+        service_provider
+            .label_printing_service
+            .print_labels(&service_context, input.to_domain()),
+    )
+}
+
+impl UpsertLabelTemplateInput {
+    pub fn to_domain(self) -> UpsertLabelTemplate {
+        let UpsertLabelTemplateInput {
+            id,
+            name,
+            template_type,
+            printer_language,
+            template,
+            label_width,
+            label_height,
+        } = self;
+
+        UpsertLabelTemplate {
+            id,
+            name,
+            template_type: template_type.into(),
+            printer_language: printer_language.into(),
+            template,
+            label_width,
+            label_height,
+        }
+    }
+}
+
+impl UpsertLabelPrinterInput {
+    pub fn to_domain(self) -> UpsertLabelPrinter {
+        let UpsertLabelPrinterInput {
+            id,
+            name,
+            address,
+            port,
+            printer_language,
+        } = self;
+
+        UpsertLabelPrinter {
+            id,
+            name,
+            address,
+            port,
+            printer_language: printer_language.into(),
+        }
+    }
+}
+
+impl PrintLabelsInput {
+    pub fn to_domain(self) -> PrintLabels {
+        let PrintLabelsInput {
+            template_id,
+            printer_id,
+            record_ids,
+            copies,
+        } = self;
+
+        PrintLabels {
+            template_id,
+            printer_id,
+            record_ids,
+            copies: copies.unwrap_or(1),
+        }
+    }
+}
+
+fn map_upsert_label_template_response(
+    from: Result<LabelTemplateRow, UpsertLabelTemplateError>,
+) -> Result<UpsertLabelTemplateResponse> {
+    match from {
+        Ok(label_template) => Ok(UpsertLabelTemplateResponse::Response(
+            LabelTemplateNode::from_domain(label_template),
+        )),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpsertLabelTemplateError::NameCannotBeEmpty
+                | UpsertLabelTemplateError::InvalidLabelSize
+                | UpsertLabelTemplateError::InvalidTemplate(_) => BadUserInput(formatted_error),
+                UpsertLabelTemplateError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+fn map_upsert_label_printer_response(
+    from: Result<LabelPrinterRow, UpsertLabelPrinterError>,
+) -> Result<UpsertLabelPrinterResponse> {
+    match from {
+        Ok(label_printer) => Ok(UpsertLabelPrinterResponse::Response(
+            LabelPrinterNode::from_domain(label_printer),
+        )),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpsertLabelPrinterError::NameCannotBeEmpty
+                | UpsertLabelPrinterError::AddressCannotBeEmpty
+                | UpsertLabelPrinterError::InvalidPort => BadUserInput(formatted_error),
+                UpsertLabelPrinterError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+fn map_print_labels_response(from: Result<u32, PrintLabelsError>) -> Result<PrintLabelsResponse> {
+    match from {
+        Ok(number_of_labels) => Ok(PrintLabelsResponse::Response(PrintLabelsNode {
+            number_of_labels,
+        })),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                PrintLabelsError::NoLabelsToPrint
+                | PrintLabelsError::TooManyCopies
+                | PrintLabelsError::TooManyLabels
+                | PrintLabelsError::LabelTemplateDoesNotExist
+                | PrintLabelsError::LabelPrinterDoesNotExist
+                | PrintLabelsError::NoLabelPrinterConfigured
+                | PrintLabelsError::PrinterLanguageMismatch
+                | PrintLabelsError::RecordDoesNotExist(_) => BadUserInput(formatted_error),
+                PrintLabelsError::RenderError(_)
+                | PrintLabelsError::PrinterError(_)
+                | PrintLabelsError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
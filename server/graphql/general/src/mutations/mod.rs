@@ -1,10 +1,15 @@
 pub mod barcode;
+pub mod close_period;
 pub mod common;
 pub mod display_settings;
 pub mod initialise_site;
 pub mod label_printer_settings;
+pub mod label_template;
 pub mod log;
 pub mod manual_sync;
+pub mod price_list;
+pub mod shelf_life_rule;
 pub mod sync_settings;
+pub mod transfer_dead_letter;
 pub mod update_name_properties;
 pub mod update_user;
@@ -0,0 +1,176 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use repository::PriceListRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    pricing::price_list::{
+        DeletePriceListError, UpsertPriceList, UpsertPriceListError, UpsertPriceListLine,
+    },
+};
+
+use crate::queries::PriceListNode;
+
+#[derive(InputObject)]
+pub struct UpsertPriceListLineInput {
+    pub item_id: String,
+    pub price_per_unit: f64,
+}
+
+#[derive(InputObject)]
+pub struct UpsertPriceListInput {
+    pub id: String,
+    pub name: String,
+    /// Price lists with a higher priority are used first
+    pub priority: i32,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+    pub is_active: bool,
+    /// Replaces all existing lines of the price list
+    pub lines: Vec<UpsertPriceListLineInput>,
+    /// Customers the price list applies to
+    pub name_ids: Vec<String>,
+    /// Name tags of customers the price list applies to. The price list applies to all
+    /// customers if neither names nor name tags are set
+    pub name_tag_ids: Vec<String>,
+}
+
+#[derive(Union)]
+pub enum UpsertPriceListResponse {
+    Response(PriceListNode),
+}
+
+#[derive(Union)]
+pub enum DeletePriceListResponse {
+    Response(DeleteResponse),
+}
+
+pub fn upsert_price_list(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertPriceListInput,
+) -> Result<UpsertPriceListResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePriceList,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    map_upsert_response(
+        service_provider
+            .pricing_service
+            .upsert_price_list(&service_context, input.to_domain()),
+    )
+}
+
+pub fn delete_price_list(
+    ctx: &Context<'_>,
+    store_id: &str,
+    id: String,
+) -> Result<DeletePriceListResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePriceList,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    match service_provider
+        .pricing_service
+        .delete_price_list(&service_context, id)
+    {
+        Ok(id) => Ok(DeletePriceListResponse::Response(DeleteResponse(id))),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                DeletePriceListError::PriceListDoesNotExist
+                | DeletePriceListError::PriceListDoesNotBelongToCurrentStore => {
+                    BadUserInput(formatted_error)
+                }
+                DeletePriceListError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl UpsertPriceListInput {
+    pub fn to_domain(self) -> UpsertPriceList {
+        let UpsertPriceListInput {
+            id,
+            name,
+            priority,
+            valid_from,
+            valid_to,
+            is_active,
+            lines,
+            name_ids,
+            name_tag_ids,
+        } = self;
+
+        UpsertPriceList {
+            id,
+            name,
+            priority,
+            valid_from,
+            valid_to,
+            is_active,
+            lines: lines
+                .into_iter()
+                .map(
+                    |UpsertPriceListLineInput {
+                         item_id,
+                         price_per_unit,
+                     }| UpsertPriceListLine {
+                        item_id,
+                        price_per_unit,
+                    },
+                )
+                .collect(),
+            name_ids,
+            name_tag_ids,
+        }
+    }
+}
+
+fn map_upsert_response(
+    from: Result<PriceListRow, UpsertPriceListError>,
+) -> Result<UpsertPriceListResponse> {
+    match from {
+        Ok(price_list) => Ok(UpsertPriceListResponse::Response(
+            PriceListNode::from_domain(price_list),
+        )),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpsertPriceListError::PriceListDoesNotBelongToCurrentStore
+                | UpsertPriceListError::ValidToBeforeValidFrom
+                | UpsertPriceListError::ItemDoesNotExist(_)
+                | UpsertPriceListError::DuplicateItem(_)
+                | UpsertPriceListError::PricePerUnitCannotBeNegative(_)
+                | UpsertPriceListError::NameDoesNotExist(_)
+                | UpsertPriceListError::NameTagDoesNotExist(_) => BadUserInput(formatted_error),
+                UpsertPriceListError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
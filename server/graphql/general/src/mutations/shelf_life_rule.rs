@@ -0,0 +1,137 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use repository::ShelfLifeRuleRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    shelf_life_rule::{DeleteShelfLifeRuleError, UpsertShelfLifeRule, UpsertShelfLifeRuleError},
+};
+
+use crate::queries::ShelfLifeRuleNode;
+
+#[derive(InputObject)]
+pub struct UpsertShelfLifeRuleInput {
+    pub id: String,
+    /// Rule applies to all customers if not set
+    pub name_id: Option<String>,
+    /// Rule applies to all items if not set
+    pub item_id: Option<String>,
+    pub minimum_remaining_days: i32,
+}
+
+#[derive(Union)]
+pub enum UpsertShelfLifeRuleResponse {
+    Response(ShelfLifeRuleNode),
+}
+
+#[derive(Union)]
+pub enum DeleteShelfLifeRuleResponse {
+    Response(DeleteResponse),
+}
+
+pub fn upsert_shelf_life_rule(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertShelfLifeRuleInput,
+) -> Result<UpsertShelfLifeRuleResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateShelfLifeRule,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    map_upsert_response(
+        service_provider
+            .shelf_life_rule_service
+            .upsert_shelf_life_rule(&service_context, input.to_domain()),
+    )
+}
+
+pub fn delete_shelf_life_rule(
+    ctx: &Context<'_>,
+    store_id: &str,
+    id: String,
+) -> Result<DeleteShelfLifeRuleResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateShelfLifeRule,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    match service_provider
+        .shelf_life_rule_service
+        .delete_shelf_life_rule(&service_context, id)
+    {
+        Ok(id) => Ok(DeleteShelfLifeRuleResponse::Response(DeleteResponse(id))),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                DeleteShelfLifeRuleError::RuleDoesNotExist
+                | DeleteShelfLifeRuleError::RuleDoesNotBelongToCurrentStore => {
+                    BadUserInput(formatted_error)
+                }
+                DeleteShelfLifeRuleError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl UpsertShelfLifeRuleInput {
+    pub fn to_domain(self) -> UpsertShelfLifeRule {
+        let UpsertShelfLifeRuleInput {
+            id,
+            name_id,
+            item_id,
+            minimum_remaining_days,
+        } = self;
+
+        UpsertShelfLifeRule {
+            id,
+            name_id,
+            item_id,
+            minimum_remaining_days,
+        }
+    }
+}
+
+fn map_upsert_response(
+    from: Result<ShelfLifeRuleRow, UpsertShelfLifeRuleError>,
+) -> Result<UpsertShelfLifeRuleResponse> {
+    match from {
+        Ok(shelf_life_rule) => Ok(UpsertShelfLifeRuleResponse::Response(
+            ShelfLifeRuleNode::from_domain(shelf_life_rule),
+        )),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpsertShelfLifeRuleError::RuleDoesNotBelongToCurrentStore
+                | UpsertShelfLifeRuleError::NameDoesNotExist
+                | UpsertShelfLifeRuleError::ItemDoesNotExist
+                | UpsertShelfLifeRuleError::MinimumRemainingDaysCannotBeNegative => {
+                    BadUserInput(formatted_error)
+                }
+                UpsertShelfLifeRuleError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::TransferDeadLetterRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    processors::dead_letter::RetryTransferDeadLetterError as ServiceError,
+};
+
+use crate::queries::TransferDeadLetterNode;
+
+#[derive(Union)]
+pub enum RetryTransferDeadLetterResponse {
+    Response(TransferDeadLetterNode),
+}
+
+pub fn retry_transfer_dead_letter(
+    ctx: &Context<'_>,
+    id: String,
+) -> Result<RetryTransferDeadLetterResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    map_response(
+        service_provider
+            .transfer_dead_letter_service
+            .retry_dead_letter(&service_context, &id),
+    )
+}
+
+pub fn map_response(
+    from: Result<TransferDeadLetterRow, ServiceError>,
+) -> Result<RetryTransferDeadLetterResponse> {
+    match from {
+        Ok(dead_letter) => Ok(RetryTransferDeadLetterResponse::Response(
+            TransferDeadLetterNode::from_domain(dead_letter),
+        )),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                ServiceError::DeadLetterDoesNotExist | ServiceError::DeadLetterAlreadyResolved => {
+                    BadUserInput(formatted_error)
+                }
+                ServiceError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
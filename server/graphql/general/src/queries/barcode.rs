@@ -1,11 +1,15 @@
 use async_graphql::*;
+use chrono::NaiveDate;
 use graphql_core::{
     simple_generic_errors::{NodeError, NodeErrorInterface},
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
 use graphql_types::types::BarcodeNode;
-use service::auth::{Resource, ResourceAccessRequest};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    barcode::{ScanBarcodeError, ScannedBarcode},
+};
 
 #[derive(Union)]
 pub enum BarcodeResponse {
@@ -13,6 +17,47 @@ pub enum BarcodeResponse {
     Response(BarcodeNode),
 }
 
+pub struct ScannedBarcodeNode {
+    pub scanned: ScannedBarcode,
+}
+
+#[Object]
+impl ScannedBarcodeNode {
+    /// GTIN padded to 14 digits
+    pub async fn gtin(&self) -> &Option<String> {
+        &self.scanned.gtin
+    }
+
+    /// Barcode matching the GTIN, not set if the GTIN isn't linked to an item
+    pub async fn barcode(&self) -> Option<BarcodeNode> {
+        self.scanned.barcode.clone().map(BarcodeNode::from_domain)
+    }
+
+    pub async fn item_id(&self) -> Option<&str> {
+        self.scanned
+            .barcode
+            .as_ref()
+            .map(|barcode| barcode.barcode_row.item_id.as_str())
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.scanned.batch
+    }
+
+    pub async fn expiry_date(&self) -> Option<NaiveDate> {
+        self.scanned.expiry_date
+    }
+
+    pub async fn serial_number(&self) -> &Option<String> {
+        &self.scanned.serial_number
+    }
+
+    /// Number of units in a variable count trade item
+    pub async fn count(&self) -> Option<u32> {
+        self.scanned.count
+    }
+}
+
 pub fn barcode_by_gtin(
     ctx: &Context<'_>,
     store_id: String,
@@ -42,3 +87,39 @@ pub fn barcode_by_gtin(
 
     Ok(response)
 }
+
+/// Reads a plain GTIN (EAN/UPC) or GS1 barcode, scanned raw or as a human readable string
+pub fn scan_barcode(
+    ctx: &Context<'_>,
+    store_id: String,
+    scanned: String,
+) -> Result<ScannedBarcodeNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryItems,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+    match service_provider
+        .barcode_service
+        .scan_barcode(&service_context, &scanned)
+    {
+        Ok(scanned) => Ok(ScannedBarcodeNode { scanned }),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                ScanBarcodeError::InvalidBarcode(_) => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                ScanBarcodeError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
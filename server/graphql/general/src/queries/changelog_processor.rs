@@ -0,0 +1,87 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    processors::changelog::ChangelogProcessorStatus,
+};
+
+pub struct ChangelogProcessorStatusNode {
+    status: ChangelogProcessorStatus,
+}
+
+#[Object]
+impl ChangelogProcessorStatusNode {
+    pub async fn code(&self) -> &str {
+        &self.status.code
+    }
+
+    pub async fn description(&self) -> &str {
+        &self.status.description
+    }
+
+    pub async fn cursor(&self) -> u64 {
+        self.status.cursor
+    }
+
+    /// Number of changelogs still to be processed
+    pub async fn lag(&self) -> u64 {
+        self.status.lag
+    }
+
+    pub async fn last_run_datetime(&self) -> Option<DateTime<Utc>> {
+        self.status.last_run_datetime.map(to_utc)
+    }
+
+    pub async fn last_success_datetime(&self) -> Option<DateTime<Utc>> {
+        self.status.last_success_datetime.map(to_utc)
+    }
+
+    /// Consecutive failed attempts to process the current changelog
+    pub async fn error_count(&self) -> i32 {
+        self.status.error_count
+    }
+
+    pub async fn last_error(&self) -> &Option<String> {
+        &self.status.last_error
+    }
+
+    pub async fn last_error_datetime(&self) -> Option<DateTime<Utc>> {
+        self.status.last_error_datetime.map(to_utc)
+    }
+
+    pub async fn next_retry_datetime(&self) -> Option<DateTime<Utc>> {
+        self.status.next_retry_datetime.map(to_utc)
+    }
+}
+
+fn to_utc(datetime: NaiveDateTime) -> DateTime<Utc> {
+    DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc)
+}
+
+pub fn changelog_processor_statuses(
+    ctx: &Context<'_>,
+) -> Result<Vec<ChangelogProcessorStatusNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::SyncInfo,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    let statuses = service_provider
+        .changelog_processor_service
+        .get_statuses(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(statuses
+        .into_iter()
+        .map(|status| ChangelogProcessorStatusNode { status })
+        .collect())
+}
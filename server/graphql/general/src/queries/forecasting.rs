@@ -0,0 +1,137 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::ForecastMethodNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    forecasting::{ConsumptionPeriod, ForecastOptions, ItemForecast},
+};
+
+#[derive(InputObject, Clone)]
+pub struct ItemForecastInput {
+    item_ids: Vec<String>,
+    /// Defaults to the store preference forecast method
+    method: Option<ForecastMethodNode>,
+    /// Defaults to the store preference AMC look back period
+    lookback_months: Option<u32>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ConsumptionPeriodNode {
+    period: ConsumptionPeriod,
+}
+
+#[Object]
+impl ConsumptionPeriodNode {
+    pub async fn start_date(&self) -> NaiveDate {
+        self.period.start_date
+    }
+
+    pub async fn end_date(&self) -> NaiveDate {
+        self.period.end_date
+    }
+
+    pub async fn consumption(&self) -> f64 {
+        self.period.consumption
+    }
+
+    pub async fn days_out_of_stock(&self) -> u32 {
+        self.period.days_out_of_stock
+    }
+
+    /// Monthly consumption if stock had been available for the whole period, null if out of
+    /// stock for the whole period
+    pub async fn adjusted_monthly_consumption(&self) -> Option<f64> {
+        self.period.adjusted_monthly_consumption
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ItemForecastNode {
+    forecast: ItemForecast,
+}
+
+#[Object]
+impl ItemForecastNode {
+    pub async fn item_id(&self) -> &str {
+        &self.forecast.item_id
+    }
+
+    /// Method used, can differ from the requested method if there was not enough data for it
+    pub async fn method(&self) -> ForecastMethodNode {
+        ForecastMethodNode::from_domain(&self.forecast.method)
+    }
+
+    pub async fn forecast_monthly_consumption(&self) -> f64 {
+        self.forecast.forecast_monthly_consumption
+    }
+
+    /// Most recent month first
+    pub async fn history(&self) -> Vec<ConsumptionPeriodNode> {
+        self.forecast
+            .history
+            .iter()
+            .cloned()
+            .map(|period| ConsumptionPeriodNode { period })
+            .collect()
+    }
+
+    pub async fn same_period_last_year(&self) -> Option<ConsumptionPeriodNode> {
+        self.forecast
+            .same_period_last_year
+            .clone()
+            .map(|period| ConsumptionPeriodNode { period })
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ItemForecastConnector {
+    total_count: u32,
+    nodes: Vec<ItemForecastNode>,
+}
+
+pub fn item_forecasts(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: ItemForecastInput,
+) -> Result<ItemForecastConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryItems,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let ItemForecastInput {
+        item_ids,
+        method,
+        lookback_months,
+    } = input;
+    let forecasts = service_provider
+        .forecasting_service
+        .get_item_forecasts(
+            &service_context,
+            &store_id,
+            item_ids,
+            ForecastOptions {
+                method: method.map(ForecastMethodNode::to_domain),
+                lookback_months,
+            },
+        )
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(ItemForecastConnector {
+        total_count: forecasts.len() as u32,
+        nodes: forecasts
+            .into_iter()
+            .map(|forecast| ItemForecastNode { forecast })
+            .collect(),
+    })
+}
@@ -0,0 +1,103 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    inventory_valuation::{
+        get_inventory_valuation, InventoryValuation, ItemValuation, ValuationMethod,
+    },
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "service::inventory_valuation::ValuationMethod")]
+pub enum ValuationMethodNode {
+    Fifo,
+    WeightedAverageCost,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ItemValuationNode {
+    item_valuation: ItemValuation,
+}
+
+#[Object]
+impl ItemValuationNode {
+    pub async fn item_id(&self) -> &str {
+        &self.item_valuation.item_id
+    }
+
+    /// Quantity in units
+    pub async fn quantity(&self) -> f64 {
+        self.item_valuation.quantity
+    }
+
+    pub async fn value(&self) -> f64 {
+        self.item_valuation.value
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct InventoryValuationNode {
+    valuation: InventoryValuation,
+}
+
+#[Object]
+impl InventoryValuationNode {
+    pub async fn store_id(&self) -> &str {
+        &self.valuation.store_id
+    }
+
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.valuation.datetime, Utc)
+    }
+
+    pub async fn method(&self) -> ValuationMethodNode {
+        ValuationMethodNode::from(self.valuation.method)
+    }
+
+    pub async fn total_value(&self) -> f64 {
+        self.valuation.total_value
+    }
+
+    pub async fn items(&self) -> Vec<ItemValuationNode> {
+        self.valuation
+            .items
+            .iter()
+            .cloned()
+            .map(|item_valuation| ItemValuationNode { item_valuation })
+            .collect()
+    }
+}
+
+pub fn inventory_valuation(
+    ctx: &Context<'_>,
+    store_id: String,
+    datetime: DateTime<Utc>,
+    method: Option<ValuationMethodNode>,
+    item_ids: Option<Vec<String>>,
+) -> Result<InventoryValuationNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::Report,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let valuation = get_inventory_valuation(
+        &service_context,
+        &store_id,
+        datetime.naive_utc(),
+        method.map(ValuationMethod::from).unwrap_or_default(),
+        item_ids,
+    )
+    .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(InventoryValuationNode { valuation })
+}
@@ -0,0 +1,147 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::{LabelPrinterRow, LabelTemplateRow};
+use service::auth::{Resource, ResourceAccessRequest};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::LabelTemplateType")]
+pub enum LabelTemplateTypeNode {
+    StockLine,
+    Location,
+    Asset,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::PrinterLanguage")]
+pub enum PrinterLanguageNode {
+    Zpl,
+    Epl,
+}
+
+pub struct LabelTemplateNode {
+    pub label_template: LabelTemplateRow,
+}
+
+#[Object]
+impl LabelTemplateNode {
+    pub async fn id(&self) -> &str {
+        &self.label_template.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.label_template.name
+    }
+
+    pub async fn template_type(&self) -> LabelTemplateTypeNode {
+        LabelTemplateTypeNode::from(self.label_template.template_type)
+    }
+
+    pub async fn printer_language(&self) -> PrinterLanguageNode {
+        PrinterLanguageNode::from(self.label_template.printer_language)
+    }
+
+    /// Tera template rendering the printer commands of a single label
+    pub async fn template(&self) -> &str {
+        &self.label_template.template
+    }
+
+    /// Label width in dots
+    pub async fn label_width(&self) -> i32 {
+        self.label_template.label_width
+    }
+
+    /// Label height in dots
+    pub async fn label_height(&self) -> i32 {
+        self.label_template.label_height
+    }
+}
+
+impl LabelTemplateNode {
+    pub fn from_domain(label_template: LabelTemplateRow) -> Self {
+        LabelTemplateNode { label_template }
+    }
+}
+
+pub struct LabelPrinterNode {
+    pub label_printer: LabelPrinterRow,
+}
+
+#[Object]
+impl LabelPrinterNode {
+    pub async fn id(&self) -> &str {
+        &self.label_printer.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.label_printer.name
+    }
+
+    pub async fn address(&self) -> &str {
+        &self.label_printer.address
+    }
+
+    pub async fn port(&self) -> i32 {
+        self.label_printer.port
+    }
+
+    pub async fn printer_language(&self) -> PrinterLanguageNode {
+        PrinterLanguageNode::from(self.label_printer.printer_language)
+    }
+}
+
+impl LabelPrinterNode {
+    pub fn from_domain(label_printer: LabelPrinterRow) -> Self {
+        LabelPrinterNode { label_printer }
+    }
+}
+
+pub fn label_templates(
+    ctx: &Context<'_>,
+    store_id: String,
+    template_type: Option<LabelTemplateTypeNode>,
+) -> Result<Vec<LabelTemplateNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::PrintLabels,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+    let label_templates = service_provider
+        .label_printing_service
+        .get_label_templates(&service_context, template_type.map(|t| t.into()))
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(label_templates
+        .into_iter()
+        .map(LabelTemplateNode::from_domain)
+        .collect())
+}
+
+pub fn label_printers(ctx: &Context<'_>, store_id: String) -> Result<Vec<LabelPrinterNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::PrintLabels,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+    let label_printers = service_provider
+        .label_printing_service
+        .get_label_printers(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(label_printers
+        .into_iter()
+        .map(LabelPrinterNode::from_domain)
+        .collect())
+}
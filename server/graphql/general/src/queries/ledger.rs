@@ -1,20 +1,22 @@
 use async_graphql::*;
 use chrono::{DateTime, Utc};
 use graphql_core::{
-    generic_filters::EqualFilterStringInput,
+    generic_filters::{DatetimeFilterInput, EqualFilterStringInput},
+    map_filter,
+    pagination::PaginationInput,
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
 
-use graphql_types::types::InvoiceNodeType;
+use graphql_types::types::{EqualFilterInvoiceTypeInput, InvoiceNodeType};
 use repository::{
     ledger::{LedgerFilter, LedgerRow, LedgerSort, LedgerSortField},
-    EqualFilter,
+    DatetimeFilter, EqualFilter, PaginationOption,
 };
 
 use service::{
     auth::{Resource, ResourceAccessRequest},
-    ledger::get_ledger,
+    ledger::{get_ledger, get_stock_card, StockCard},
     ListResult,
 };
 
@@ -41,11 +43,15 @@ pub struct LedgerSortInput {
 #[derive(InputObject, Clone)]
 pub struct LedgerFilterInput {
     pub stock_line_id: Option<EqualFilterStringInput>,
+    pub item_id: Option<EqualFilterStringInput>,
+    pub datetime: Option<DatetimeFilterInput>,
+    pub invoice_type: Option<EqualFilterInvoiceTypeInput>,
 }
 
 #[derive(PartialEq, Debug)]
 pub struct LedgerNode {
     ledger: LedgerRow,
+    running_balance: Option<f64>,
 }
 
 #[Object]
@@ -83,6 +89,10 @@ impl LedgerNode {
         }
         &self.ledger.inventory_adjustment_reason
     }
+    /// Stock balance after the movement, only available on the stock card
+    pub async fn running_balance(&self) -> &Option<f64> {
+        &self.running_balance
+    }
 }
 
 #[derive(SimpleObject)]
@@ -96,6 +106,21 @@ pub enum LedgerResponse {
     Response(LedgerConnector),
 }
 
+#[derive(SimpleObject)]
+pub struct StockCardConnector {
+    total_count: u32,
+    /// Stock balance at the start of the period
+    opening_balance: f64,
+    /// Stock balance at the end of the period
+    closing_balance: f64,
+    nodes: Vec<LedgerNode>,
+}
+
+#[derive(Union)]
+pub enum StockCardResponse {
+    Response(StockCardConnector),
+}
+
 pub fn ledger(
     ctx: &Context<'_>,
     store_id: String,
@@ -115,7 +140,12 @@ pub fn ledger(
     let ledger = get_ledger(
         connection_manager,
         // page.map(PaginationOption::from),
-        filter.map(|filter| filter.to_domain()),
+        Some(
+            filter
+                .map(|filter| filter.to_domain())
+                .unwrap_or_default()
+                .store_id(EqualFilter::equal_to(&store_id)),
+        ),
         // Currently only one sort option is supported, use the first from the list.
         sort.and_then(|mut sort_list| sort_list.pop())
             .map(|sort| sort.to_domain()),
@@ -127,6 +157,56 @@ pub fn ledger(
     )))
 }
 
+/// Movements of the item in the store in chronological order, with opening, running and closing
+/// stock balances for the period set by the datetime filter
+pub fn stock_card(
+    ctx: &Context<'_>,
+    store_id: String,
+    item_id: String,
+    page: Option<PaginationInput>,
+    filter: Option<LedgerFilterInput>,
+) -> Result<StockCardResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryItems,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let filter = filter
+        .map(|filter| filter.to_domain())
+        .unwrap_or_default()
+        .store_id(EqualFilter::equal_to(&store_id))
+        .item_id(EqualFilter::equal_to(&item_id));
+
+    let connection_manager = ctx.get_connection_manager();
+    let stock_card = get_stock_card(connection_manager, page.map(PaginationOption::from), filter)
+        .map_err(StandardGraphqlError::from_list_error)?;
+
+    Ok(StockCardResponse::Response(
+        StockCardConnector::from_domain(stock_card),
+    ))
+}
+
+impl StockCardConnector {
+    pub fn from_domain(stock_card: StockCard) -> StockCardConnector {
+        StockCardConnector {
+            total_count: stock_card.count,
+            opening_balance: stock_card.opening_balance,
+            closing_balance: stock_card.closing_balance,
+            nodes: stock_card
+                .lines
+                .into_iter()
+                .map(|line| LedgerNode {
+                    ledger: line.ledger,
+                    running_balance: Some(line.running_balance),
+                })
+                .collect(),
+        }
+    }
+}
+
 impl LedgerConnector {
     pub fn from_domain(rows: ListResult<LedgerRow>) -> LedgerConnector {
         LedgerConnector {
@@ -134,17 +214,29 @@ impl LedgerConnector {
             nodes: rows
                 .rows
                 .into_iter()
-                .map(|ledger| LedgerNode { ledger })
+                .map(|ledger| LedgerNode {
+                    ledger,
+                    running_balance: None,
+                })
                 .collect(),
         }
     }
 }
 impl LedgerFilterInput {
     pub fn to_domain(self) -> LedgerFilter {
-        let LedgerFilterInput { stock_line_id } = self;
+        let LedgerFilterInput {
+            stock_line_id,
+            item_id,
+            datetime,
+            invoice_type,
+        } = self;
 
         LedgerFilter {
             stock_line_id: stock_line_id.map(EqualFilter::from),
+            item_id: item_id.map(EqualFilter::from),
+            store_id: None,
+            datetime: datetime.map(DatetimeFilter::from),
+            invoice_type: invoice_type.map(|t| map_filter!(t, InvoiceNodeType::to_domain)),
         }
     }
 }
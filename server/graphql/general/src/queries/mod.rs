@@ -58,8 +58,24 @@ pub mod label_printer_settings;
 pub use self::label_printer_settings::*;
 pub mod pricing;
 pub use self::pricing::*;
+pub mod price_list;
+pub use self::price_list::*;
+pub mod forecasting;
+pub use self::forecasting::*;
+pub mod changelog_processor;
+pub use self::changelog_processor::*;
+pub mod transfer_dead_letter;
+pub use self::transfer_dead_letter::*;
+pub mod inventory_valuation;
+pub use self::inventory_valuation::*;
+pub mod period_close;
+pub use self::period_close::*;
+pub mod label_template;
+pub use self::label_template::*;
 pub mod reason_option;
 pub use self::reason_option::*;
+pub mod shelf_life_rule;
+pub use self::shelf_life_rule::*;
 
 pub mod generate_customer_return_lines;
 pub use self::generate_customer_return_lines::*;
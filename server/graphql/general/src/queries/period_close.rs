@@ -0,0 +1,68 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::PeriodCloseRow;
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub struct PeriodCloseNode {
+    pub period_close: PeriodCloseRow,
+}
+
+#[Object]
+impl PeriodCloseNode {
+    pub async fn id(&self) -> &str {
+        &self.period_close.id
+    }
+
+    pub async fn store_id(&self) -> &str {
+        &self.period_close.store_id
+    }
+
+    pub async fn period_start(&self) -> NaiveDate {
+        self.period_close.period_start
+    }
+
+    pub async fn period_end(&self) -> NaiveDate {
+        self.period_close.period_end
+    }
+
+    pub async fn closed_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.period_close.closed_datetime, Utc)
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.period_close.user_id
+    }
+}
+
+impl PeriodCloseNode {
+    pub fn from_domain(period_close: PeriodCloseRow) -> Self {
+        PeriodCloseNode { period_close }
+    }
+}
+
+/// Closed periods of the store, most recent first
+pub fn period_closes(ctx: &Context<'_>, store_id: String) -> Result<Vec<PeriodCloseNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::Report,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+    let period_closes = service_provider
+        .period_close_service
+        .get_period_closes(&service_context, &store_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(period_closes
+        .into_iter()
+        .map(PeriodCloseNode::from_domain)
+        .collect())
+}
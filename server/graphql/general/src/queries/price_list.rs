@@ -0,0 +1,97 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::{PriceListLineRow, PriceListLineRowRepository, PriceListRow};
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub struct PriceListNode {
+    pub price_list: PriceListRow,
+}
+
+pub struct PriceListLineNode {
+    pub line: PriceListLineRow,
+}
+
+#[Object]
+impl PriceListNode {
+    pub async fn id(&self) -> &str {
+        &self.price_list.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.price_list.name
+    }
+
+    /// Price lists with a higher priority are used first
+    pub async fn priority(&self) -> i32 {
+        self.price_list.priority
+    }
+
+    pub async fn valid_from(&self) -> &Option<NaiveDate> {
+        &self.price_list.valid_from
+    }
+
+    pub async fn valid_to(&self) -> &Option<NaiveDate> {
+        &self.price_list.valid_to
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.price_list.is_active
+    }
+
+    pub async fn lines(&self, ctx: &Context<'_>) -> Result<Vec<PriceListLineNode>> {
+        let lines = PriceListLineRowRepository::new(&ctx.get_connection_manager().connection()?)
+            .find_many_by_price_list_id(&self.price_list.id)?;
+
+        Ok(lines
+            .into_iter()
+            .map(|line| PriceListLineNode { line })
+            .collect())
+    }
+}
+
+#[Object]
+impl PriceListLineNode {
+    pub async fn id(&self) -> &str {
+        &self.line.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.line.item_link_id
+    }
+
+    pub async fn price_per_unit(&self) -> f64 {
+        self.line.price_per_unit
+    }
+}
+
+impl PriceListNode {
+    pub fn from_domain(price_list: PriceListRow) -> Self {
+        PriceListNode { price_list }
+    }
+}
+
+pub fn price_lists(ctx: &Context<'_>, store_id: String) -> Result<Vec<PriceListNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryPriceList,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+    let price_lists = service_provider
+        .pricing_service
+        .get_price_lists(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(price_lists
+        .into_iter()
+        .map(PriceListNode::from_domain)
+        .collect())
+}
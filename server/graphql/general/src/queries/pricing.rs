@@ -1,4 +1,5 @@
 use async_graphql::*;
+use chrono::NaiveDate;
 use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
@@ -6,13 +7,23 @@ use graphql_core::{
 
 use service::{
     auth::{Resource, ResourceAccessRequest},
-    pricing::item_price::{ItemPrice, ItemPriceLookup},
+    pricing::item_price::{ItemPrice, ItemPriceLookup, ItemPriceSource},
 };
 
 #[derive(InputObject, Clone)]
 pub struct ItemPriceInput {
     item_id: String,
     name_id: Option<String>, // Name Id could be used to get discount for a specific name
+    date: Option<NaiveDate>, // Date the price should be valid on, defaults to today
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ItemPriceSourceNode {
+    None,
+    DefaultPriceList,
+    GeneralPriceList,
+    NameTagPriceList,
+    CustomerPriceList,
 }
 
 #[derive(PartialEq, Debug)]
@@ -37,6 +48,21 @@ impl ItemPriceNode {
     pub async fn calculated_price_per_unit(&self) -> Option<f64> {
         self.pricing.calculated_price_per_unit
     }
+
+    /// Why this price was used
+    pub async fn source(&self) -> ItemPriceSourceNode {
+        ItemPriceSourceNode::from_domain(&self.pricing.source)
+    }
+
+    /// Price list (or default price list master list) the price per unit came from
+    pub async fn price_list_id(&self) -> &Option<String> {
+        &self.pricing.price_list_id
+    }
+
+    /// Discount list master list the discount came from
+    pub async fn discount_list_id(&self) -> &Option<String> {
+        &self.pricing.discount_list_id
+    }
 }
 
 #[derive(Union)]
@@ -70,11 +96,28 @@ pub async fn item_price(
 
 impl ItemPriceInput {
     pub fn to_domain(self) -> ItemPriceLookup {
-        let ItemPriceInput { name_id, item_id } = self;
+        let ItemPriceInput {
+            name_id,
+            item_id,
+            date,
+        } = self;
 
         ItemPriceLookup {
             customer_name_id: name_id,
             item_id,
+            date,
+        }
+    }
+}
+
+impl ItemPriceSourceNode {
+    pub fn from_domain(source: &ItemPriceSource) -> Self {
+        match source {
+            ItemPriceSource::None => ItemPriceSourceNode::None,
+            ItemPriceSource::DefaultPriceList => ItemPriceSourceNode::DefaultPriceList,
+            ItemPriceSource::GeneralPriceList => ItemPriceSourceNode::GeneralPriceList,
+            ItemPriceSource::NameTagPriceList => ItemPriceSourceNode::NameTagPriceList,
+            ItemPriceSource::CustomerPriceList => ItemPriceSourceNode::CustomerPriceList,
         }
     }
 }
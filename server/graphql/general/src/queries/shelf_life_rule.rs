@@ -0,0 +1,60 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::ShelfLifeRuleRow;
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub struct ShelfLifeRuleNode {
+    pub shelf_life_rule: ShelfLifeRuleRow,
+}
+
+#[Object]
+impl ShelfLifeRuleNode {
+    pub async fn id(&self) -> &str {
+        &self.shelf_life_rule.id
+    }
+
+    /// Rule applies to all customers if not set
+    pub async fn name_id(&self) -> &Option<String> {
+        &self.shelf_life_rule.name_link_id
+    }
+
+    /// Rule applies to all items if not set
+    pub async fn item_id(&self) -> &Option<String> {
+        &self.shelf_life_rule.item_link_id
+    }
+
+    pub async fn minimum_remaining_days(&self) -> i32 {
+        self.shelf_life_rule.minimum_remaining_days
+    }
+}
+
+impl ShelfLifeRuleNode {
+    pub fn from_domain(shelf_life_rule: ShelfLifeRuleRow) -> Self {
+        ShelfLifeRuleNode { shelf_life_rule }
+    }
+}
+
+pub fn shelf_life_rules(ctx: &Context<'_>, store_id: String) -> Result<Vec<ShelfLifeRuleNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryShelfLifeRule,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+    let shelf_life_rules = service_provider
+        .shelf_life_rule_service
+        .get_shelf_life_rules(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(shelf_life_rules
+        .into_iter()
+        .map(ShelfLifeRuleNode::from_domain)
+        .collect())
+}
@@ -0,0 +1,113 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::{TransferDeadLetterRow, TransferProcessorType};
+use service::auth::{Resource, ResourceAccessRequest};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TransferProcessorTypeNode {
+    RequisitionTransfer,
+    InvoiceTransfer,
+}
+
+impl TransferProcessorTypeNode {
+    pub fn from_domain(processor_type: &TransferProcessorType) -> Self {
+        match processor_type {
+            TransferProcessorType::RequisitionTransfer => {
+                TransferProcessorTypeNode::RequisitionTransfer
+            }
+            TransferProcessorType::InvoiceTransfer => TransferProcessorTypeNode::InvoiceTransfer,
+        }
+    }
+}
+
+pub struct TransferDeadLetterNode {
+    pub dead_letter: TransferDeadLetterRow,
+}
+
+#[Object]
+impl TransferDeadLetterNode {
+    pub async fn id(&self) -> &str {
+        &self.dead_letter.id
+    }
+
+    pub async fn processor_type(&self) -> TransferProcessorTypeNode {
+        TransferProcessorTypeNode::from_domain(&self.dead_letter.processor_type)
+    }
+
+    /// Id of the requisition or invoice that failed to transfer
+    pub async fn record_id(&self) -> &str {
+        &self.dead_letter.record_id
+    }
+
+    pub async fn changelog_cursor(&self) -> i64 {
+        self.dead_letter.changelog_cursor
+    }
+
+    pub async fn error(&self) -> &str {
+        &self.dead_letter.error
+    }
+
+    pub async fn attempt_count(&self) -> i32 {
+        self.dead_letter.attempt_count
+    }
+
+    pub async fn first_attempt_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.dead_letter.first_attempt_datetime, Utc)
+    }
+
+    pub async fn last_attempt_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.dead_letter.last_attempt_datetime, Utc)
+    }
+
+    pub async fn retry_requested(&self) -> bool {
+        self.dead_letter.retry_requested
+    }
+
+    /// Record is retried automatically at this time, not set once automatic retries are exhausted
+    pub async fn next_retry_datetime(&self) -> Option<DateTime<Utc>> {
+        self.dead_letter
+            .next_retry_datetime
+            .map(|v| DateTime::<Utc>::from_naive_utc_and_offset(v, Utc))
+    }
+
+    pub async fn resolved_datetime(&self) -> Option<DateTime<Utc>> {
+        self.dead_letter
+            .resolved_datetime
+            .map(|v| DateTime::<Utc>::from_naive_utc_and_offset(v, Utc))
+    }
+}
+
+impl TransferDeadLetterNode {
+    pub fn from_domain(dead_letter: TransferDeadLetterRow) -> Self {
+        TransferDeadLetterNode { dead_letter }
+    }
+}
+
+pub fn transfer_dead_letters(
+    ctx: &Context<'_>,
+    include_resolved: Option<bool>,
+) -> Result<Vec<TransferDeadLetterNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::SyncInfo,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    let dead_letters = service_provider
+        .transfer_dead_letter_service
+        .get_dead_letters(&service_context, include_resolved.unwrap_or(false))
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(dead_letters
+        .into_iter()
+        .map(TransferDeadLetterNode::from_domain)
+        .collect())
+}
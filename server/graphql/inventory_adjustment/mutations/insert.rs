@@ -107,6 +107,7 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         // Standard Graphql Errors
         ServiceError::StockLineDoesNotExist
         | ServiceError::InvalidStore
+        | ServiceError::DateInClosedPeriod
         | ServiceError::InvalidAdjustment
         | ServiceError::AdjustmentReasonNotValid
         | ServiceError::AdjustmentReasonNotProvided => BadUserInput(formatted_error),
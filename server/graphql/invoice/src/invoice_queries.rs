@@ -16,6 +16,8 @@ use repository::{
 };
 use service::auth::{Resource, ResourceAccessRequest};
 
+pub use graphql_types::types::EqualFilterInvoiceTypeInput;
+
 #[derive(Union)]
 pub enum InvoiceResponse {
     Error(NodeError),
@@ -55,13 +57,6 @@ pub struct InvoiceSortInput {
     desc: Option<bool>,
 }
 
-#[derive(InputObject, Clone)]
-pub struct EqualFilterInvoiceTypeInput {
-    pub equal_to: Option<InvoiceNodeType>,
-    pub equal_any: Option<Vec<InvoiceNodeType>>,
-    pub not_equal_to: Option<InvoiceNodeType>,
-}
-
 #[derive(InputObject, Clone)]
 pub struct EqualFilterInvoiceStatusInput {
     pub equal_to: Option<InvoiceNodeStatus>,
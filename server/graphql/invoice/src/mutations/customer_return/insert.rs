@@ -104,6 +104,7 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         | ServiceError::OriginalInvoiceNotAnOutboundShipment
         | ServiceError::CannotReturnOutboundShipment
         | ServiceError::InvoiceAlreadyExists
+        | ServiceError::DateInClosedPeriod
         | ServiceError::OtherPartyDoesNotExist => BadUserInput(formatted_error),
 
         ServiceError::NewlyCreatedInvoiceDoesNotExist
@@ -5,7 +5,7 @@ use graphql_core::generic_inputs::NullableUpdateInput;
 use graphql_core::simple_generic_errors::{CannotEditInvoice, ForeignKey, ForeignKeyError};
 use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
 use graphql_core::ContextExt;
-use graphql_types::types::InvoiceLineNode;
+use graphql_types::types::{InvoiceLineNode, QuantityInUnitInput};
 
 use repository::InvoiceLine;
 use service::auth::{Resource, ResourceAccessRequest};
@@ -30,6 +30,8 @@ pub struct InsertInput {
     pub total_before_tax: Option<f64>,
     pub tax_percentage: Option<f64>,
     pub item_variant_id: Option<String>,
+    /// Quantity in one of the item's units of measure, number_of_packs is ignored when set
+    pub quantity_in_unit: Option<QuantityInUnitInput>,
 }
 
 #[derive(SimpleObject)]
@@ -88,6 +90,7 @@ impl InsertInput {
             total_before_tax,
             tax_percentage,
             item_variant_id,
+            quantity_in_unit,
         } = self;
 
         ServiceInput {
@@ -107,6 +110,7 @@ impl InsertInput {
             tax_percentage,
             r#type: StockInType::InboundShipment,
             item_variant_id,
+            quantity_in_unit: quantity_in_unit.map(QuantityInUnitInput::to_domain),
             // Default
             note: None,
             stock_line_id: None,
@@ -153,7 +157,8 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         | ServiceError::PackSizeBelowOne
         | ServiceError::LocationDoesNotExist
         | ServiceError::ItemVariantDoesNotExist
-        | ServiceError::ItemNotFound => BadUserInput(formatted_error),
+        | ServiceError::ItemNotFound
+        | ServiceError::InvalidQuantityInUnit(_) => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) | ServiceError::NewlyCreatedLineDoesNotExist => {
             InternalError(formatted_error)
         }
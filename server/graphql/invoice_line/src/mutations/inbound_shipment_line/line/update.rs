@@ -6,7 +6,7 @@ use graphql_core::simple_generic_errors::{
 };
 use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
 use graphql_core::ContextExt;
-use graphql_types::types::InvoiceLineNode;
+use graphql_types::types::{InvoiceLineNode, QuantityInUnitInput};
 
 use repository::InvoiceLine;
 use service::auth::{Resource, ResourceAccessRequest};
@@ -33,6 +33,8 @@ pub struct UpdateInput {
     pub total_before_tax: Option<f64>,
     pub tax: Option<TaxInput>,
     pub item_variant_id: Option<NullableUpdateInput<String>>,
+    /// Quantity in one of the item's units of measure, number_of_packs is ignored when set
+    pub quantity_in_unit: Option<QuantityInUnitInput>,
 }
 
 #[derive(SimpleObject)]
@@ -99,6 +101,7 @@ impl UpdateInput {
             total_before_tax,
             tax,
             item_variant_id,
+            quantity_in_unit,
         } = self;
 
         ServiceInput {
@@ -121,6 +124,7 @@ impl UpdateInput {
                 percentage: tax.percentage,
             }),
             r#type: StockInType::InboundShipment,
+            quantity_in_unit: quantity_in_unit.map(QuantityInUnitInput::to_domain),
             // Default
             note: None,
         }
@@ -168,7 +172,8 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         | ServiceError::PackSizeBelowOne
         | ServiceError::LocationDoesNotExist
         | ServiceError::ItemVariantDoesNotExist
-        | ServiceError::ItemNotFound => BadUserInput(formatted_error),
+        | ServiceError::ItemNotFound
+        | ServiceError::InvalidQuantityInUnit(_) => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::UpdatedLineDoesNotExist => InternalError(formatted_error),
     };
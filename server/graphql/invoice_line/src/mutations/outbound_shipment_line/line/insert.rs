@@ -3,7 +3,7 @@ use async_graphql::*;
 use graphql_core::simple_generic_errors::{self, CannotEditInvoice, ForeignKey, ForeignKeyError};
 use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
 use graphql_core::ContextExt;
-use graphql_types::types::InvoiceLineNode;
+use graphql_types::types::{InvoiceLineNode, QuantityInUnitInput};
 
 use repository::InvoiceLine;
 use service::auth::{Resource, ResourceAccessRequest};
@@ -24,6 +24,8 @@ pub struct InsertInput {
     pub stock_line_id: String,
     pub number_of_packs: f64,
     pub tax_percentage: Option<f64>,
+    /// Quantity in one of the item's units of measure, number_of_packs is ignored when set
+    pub quantity_in_unit: Option<QuantityInUnitInput>,
 }
 
 #[derive(SimpleObject)]
@@ -90,6 +92,7 @@ impl InsertInput {
             stock_line_id,
             number_of_packs,
             tax_percentage,
+            quantity_in_unit,
         } = self;
 
         ServiceInput {
@@ -108,6 +111,7 @@ impl InsertInput {
             expiry_date: None,
             cost_price_per_pack: None,
             sell_price_per_pack: None,
+            quantity_in_unit: quantity_in_unit.map(QuantityInUnitInput::to_domain),
         }
     }
 }
@@ -164,8 +168,10 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         // Standard Graphql Errors
         NotThisStoreInvoice
         | InvoiceTypeDoesNotMatch
+        | DateInClosedPeriod
         | LineAlreadyExists
-        | NumberOfPacksBelowZero => StandardGraphqlError::BadUserInput(formatted_error),
+        | NumberOfPacksBelowZero
+        | InvalidQuantityInUnit(_) => StandardGraphqlError::BadUserInput(formatted_error),
         DatabaseError(_) => StandardGraphqlError::InternalError(formatted_error),
         NewlyCreatedLineDoesNotExist => StandardGraphqlError::InternalError(formatted_error),
     };
@@ -542,7 +548,8 @@ mod test {
                     pack_size: None,
                     expiry_date: None,
                     cost_price_per_pack: None,
-                    sell_price_per_pack: None
+                    sell_price_per_pack: None,
+                    quantity_in_unit: None,
                 }
             );
             Ok(InvoiceLine {
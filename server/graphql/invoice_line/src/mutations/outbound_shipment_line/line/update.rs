@@ -6,7 +6,7 @@ use graphql_core::{
     simple_generic_errors::{CannotEditInvoice, ForeignKey, ForeignKeyError, RecordNotFound},
     ContextExt,
 };
-use graphql_types::types::InvoiceLineNode;
+use graphql_types::types::{InvoiceLineNode, QuantityInUnitInput};
 
 use repository::InvoiceLine;
 use service::auth::{Resource, ResourceAccessRequest};
@@ -27,6 +27,8 @@ pub struct UpdateInput {
     stock_line_id: Option<String>,
     number_of_packs: Option<f64>,
     tax: Option<TaxInput>,
+    /// Quantity in one of the item's units of measure, number_of_packs is ignored when set
+    quantity_in_unit: Option<QuantityInUnitInput>,
 }
 
 pub fn update(ctx: &Context<'_>, store_id: &str, input: UpdateInput) -> Result<UpdateResponse> {
@@ -93,6 +95,7 @@ impl UpdateInput {
             stock_line_id,
             number_of_packs,
             tax,
+            quantity_in_unit,
         } = self;
         ServiceInput {
             id,
@@ -104,6 +107,7 @@ impl UpdateInput {
                 percentage: tax.percentage,
             }),
             note: None,
+            quantity_in_unit: quantity_in_unit.map(QuantityInUnitInput::to_domain),
         }
     }
 }
@@ -164,12 +168,14 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         // Standard Graphql Errors
         NotThisStoreInvoice
         | InvoiceTypeDoesNotMatch
+        | DateInClosedPeriod
         | NoInvoiceType
         | NumberOfPacksBelowZero
         | ItemNotFound
         | ItemDoesNotMatchStockLine
         | NotThisInvoiceLine(_)
-        | LineDoesNotReferenceStockLine => StandardGraphqlError::BadUserInput(formatted_error),
+        | LineDoesNotReferenceStockLine
+        | InvalidQuantityInUnit(_) => StandardGraphqlError::BadUserInput(formatted_error),
         DatabaseError(_) | UpdatedLineDoesNotExist => {
             StandardGraphqlError::InternalError(formatted_error)
         }
@@ -549,6 +555,7 @@ mod test {
                         percentage: Some(1.0),
                     }),
                     note: None,
+                    quantity_in_unit: None,
                 }
             );
             Ok(InvoiceLine {
@@ -39,6 +39,8 @@ pub struct ResponseNode {
     deletes: Vec<DeleteResponse>,
     skipped_expired_stock_lines: StockLineConnector,
     skipped_on_hold_stock_lines: StockLineConnector,
+    skipped_short_shelf_life_stock_lines: StockLineConnector,
+    skipped_vvm_failed_stock_lines: StockLineConnector,
     issued_expiring_soon_stock_lines: StockLineConnector,
 }
 
@@ -101,6 +103,8 @@ impl ResponseNode {
             inserts,
             skipped_expired_stock_lines,
             skipped_on_hold_stock_lines,
+            skipped_short_shelf_life_stock_lines,
+            skipped_vvm_failed_stock_lines,
             issued_expiring_soon_stock_lines,
         } = from;
         ResponseNode {
@@ -109,6 +113,12 @@ impl ResponseNode {
             inserts: InvoiceLineConnector::from_vec(inserts),
             skipped_expired_stock_lines: StockLineConnector::from_vec(skipped_expired_stock_lines),
             skipped_on_hold_stock_lines: StockLineConnector::from_vec(skipped_on_hold_stock_lines),
+            skipped_short_shelf_life_stock_lines: StockLineConnector::from_vec(
+                skipped_short_shelf_life_stock_lines,
+            ),
+            skipped_vvm_failed_stock_lines: StockLineConnector::from_vec(
+                skipped_vvm_failed_stock_lines,
+            ),
             issued_expiring_soon_stock_lines: StockLineConnector::from_vec(
                 issued_expiring_soon_stock_lines,
             ),
@@ -283,6 +293,16 @@ mod graphql {
                             id
                         }
                     }
+                    skippedShortShelfLifeStockLines {
+                        nodes {
+                            id
+                        }
+                    }
+                    skippedVvmFailedStockLines {
+                        nodes {
+                            id
+                        }
+                    }
                     issuedExpiringSoonStockLines {
                         nodes {
                             id
@@ -318,6 +338,12 @@ mod graphql {
                 skipped_on_hold_stock_lines: vec![inline_init(|r: &mut StockLine| {
                     r.stock_line_row.id = "skipped_on_hold".to_string();
                 })],
+                skipped_short_shelf_life_stock_lines: vec![inline_init(|r: &mut StockLine| {
+                    r.stock_line_row.id = "skipped_short_shelf_life".to_string();
+                })],
+                skipped_vvm_failed_stock_lines: vec![inline_init(|r: &mut StockLine| {
+                    r.stock_line_row.id = "skipped_vvm_failed".to_string();
+                })],
                 issued_expiring_soon_stock_lines: vec![inline_init(|r: &mut StockLine| {
                     r.stock_line_row.id = "expiring_soon".to_string();
                 })],
@@ -351,6 +377,16 @@ mod graphql {
                         "id": "skipped_on_hold"
                     }]
                 },
+                "skippedShortShelfLifeStockLines": {
+                    "nodes": [{
+                        "id": "skipped_short_shelf_life"
+                    }]
+                },
+                "skippedVvmFailedStockLines": {
+                    "nodes": [{
+                        "id": "skipped_vvm_failed"
+                    }]
+                },
                 "issuedExpiringSoonStockLines": {
                     "nodes": [{
                         "id": "expiring_soon"
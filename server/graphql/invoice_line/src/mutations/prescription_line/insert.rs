@@ -134,8 +134,10 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         // Standard Graphql Errors
         NotThisStoreInvoice
         | InvoiceTypeDoesNotMatch
+        | DateInClosedPeriod
         | LineAlreadyExists
-        | NumberOfPacksBelowZero => StandardGraphqlError::BadUserInput(formatted_error),
+        | NumberOfPacksBelowZero
+        | InvalidQuantityInUnit(_) => StandardGraphqlError::BadUserInput(formatted_error),
         DatabaseError(_) | NewlyCreatedLineDoesNotExist => {
             StandardGraphqlError::InternalError(formatted_error)
         }
@@ -170,6 +172,7 @@ impl InsertInput {
             expiry_date: None,
             cost_price_per_pack: None,
             sell_price_per_pack: None,
+            quantity_in_unit: None,
         }
     }
 }
@@ -542,7 +545,8 @@ mod test {
                     pack_size: None,
                     expiry_date: None,
                     cost_price_per_pack: None,
-                    sell_price_per_pack: None
+                    sell_price_per_pack: None,
+                    quantity_in_unit: None,
                 }
             );
             Ok(InvoiceLine {
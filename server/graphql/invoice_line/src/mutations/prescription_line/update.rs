@@ -100,6 +100,7 @@ impl UpdateInput {
             total_before_tax: None,
             tax: None,
             note,
+            quantity_in_unit: None,
         }
     }
 }
@@ -160,12 +161,14 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         // Standard Graphql Errors
         NotThisStoreInvoice
         | InvoiceTypeDoesNotMatch
+        | DateInClosedPeriod
         | NoInvoiceType
         | NumberOfPacksBelowZero
         | ItemNotFound
         | ItemDoesNotMatchStockLine
         | NotThisInvoiceLine(_)
-        | LineDoesNotReferenceStockLine => StandardGraphqlError::BadUserInput(formatted_error),
+        | LineDoesNotReferenceStockLine
+        | InvalidQuantityInUnit(_) => StandardGraphqlError::BadUserInput(formatted_error),
         DatabaseError(_) | UpdatedLineDoesNotExist => {
             StandardGraphqlError::InternalError(formatted_error)
         }
@@ -541,6 +544,7 @@ mod test {
                     note: Some("some note".to_string()),
                     total_before_tax: None,
                     tax: None,
+                    quantity_in_unit: None,
                 }
             );
             Ok(InvoiceLine {
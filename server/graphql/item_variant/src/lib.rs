@@ -60,4 +60,22 @@ impl ItemVariantMutations {
     ) -> Result<DeleteItemVariantResponse> {
         delete_item_variant(ctx, store_id, input)
     }
+
+    async fn upsert_item_unit_of_measure(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertItemUnitOfMeasureInput,
+    ) -> Result<UpsertItemUnitOfMeasureResponse> {
+        upsert_item_unit_of_measure(ctx, store_id, input)
+    }
+
+    async fn delete_item_unit_of_measure(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: DeleteItemUnitOfMeasureInput,
+    ) -> Result<DeleteItemUnitOfMeasureResponse> {
+        delete_item_unit_of_measure(ctx, store_id, input)
+    }
 }
@@ -3,3 +3,6 @@ pub use upsert::*;
 
 mod delete;
 pub use delete::*;
+
+mod unit_of_measure;
+pub use unit_of_measure::*;
@@ -0,0 +1,182 @@
+use async_graphql::*;
+use graphql_core::{
+    simple_generic_errors::InternalError,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{DeleteResponse, ItemUnitOfMeasureNode, UnitOfMeasureTypeNode};
+use repository::ItemUnitOfMeasureRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    item::unit_of_measure::{
+        DeleteItemUnitOfMeasure, DeleteItemUnitOfMeasureError, UpsertItemUnitOfMeasure,
+        UpsertItemUnitOfMeasureError as ServiceError,
+    },
+};
+
+#[derive(InputObject)]
+pub struct UpsertItemUnitOfMeasureInput {
+    pub id: String,
+    pub item_id: String,
+    pub name: String,
+    pub r#type: UnitOfMeasureTypeNode,
+    /// Number of the item's base units in one of this unit
+    pub conversion_factor: f64,
+}
+
+pub struct DuplicateDispensingUnit;
+#[Object]
+impl DuplicateDispensingUnit {
+    pub async fn description(&self) -> &str {
+        "Item already has a dispensing unit"
+    }
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", ty = "String"))]
+pub enum UpsertItemUnitOfMeasureErrorInterface {
+    InternalError(InternalError),
+    DuplicateDispensingUnit(DuplicateDispensingUnit),
+}
+
+#[derive(SimpleObject)]
+pub struct UpsertItemUnitOfMeasureError {
+    pub error: UpsertItemUnitOfMeasureErrorInterface,
+}
+
+#[derive(Union)]
+pub enum UpsertItemUnitOfMeasureResponse {
+    Error(UpsertItemUnitOfMeasureError),
+    Response(ItemUnitOfMeasureNode),
+}
+
+pub fn upsert_item_unit_of_measure(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: UpsertItemUnitOfMeasureInput,
+) -> Result<UpsertItemUnitOfMeasureResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateItemNamesCodesAndUnits,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+
+    let result = service_provider
+        .item_service
+        .upsert_item_unit_of_measure(&service_context, input.to_domain());
+
+    map_upsert_response(result)
+}
+
+impl UpsertItemUnitOfMeasureInput {
+    pub fn to_domain(self) -> UpsertItemUnitOfMeasure {
+        let UpsertItemUnitOfMeasureInput {
+            id,
+            item_id,
+            name,
+            r#type,
+            conversion_factor,
+        } = self;
+
+        UpsertItemUnitOfMeasure {
+            id,
+            item_id,
+            name,
+            r#type: r#type.to_domain(),
+            conversion_factor,
+        }
+    }
+}
+
+fn map_upsert_response(
+    from: Result<ItemUnitOfMeasureRow, ServiceError>,
+) -> Result<UpsertItemUnitOfMeasureResponse> {
+    let result = match from {
+        Ok(unit_of_measure) => UpsertItemUnitOfMeasureResponse::Response(
+            ItemUnitOfMeasureNode::from_domain(unit_of_measure),
+        ),
+        Err(error) => UpsertItemUnitOfMeasureResponse::Error(UpsertItemUnitOfMeasureError {
+            error: map_upsert_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+fn map_upsert_error(error: ServiceError) -> Result<UpsertItemUnitOfMeasureErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured errors
+        ServiceError::DuplicateDispensingUnit => {
+            return Ok(
+                UpsertItemUnitOfMeasureErrorInterface::DuplicateDispensingUnit(
+                    DuplicateDispensingUnit,
+                ),
+            )
+        }
+        // Generic errors
+        ServiceError::ItemDoesNotExist
+        | ServiceError::CantChangeItem
+        | ServiceError::NameCannotBeEmpty
+        | ServiceError::ConversionFactorNotPositive => BadUserInput(formatted_error),
+        ServiceError::CreatedRecordNotFound | ServiceError::DatabaseError(_) => {
+            InternalError(formatted_error)
+        }
+    };
+
+    Err(graphql_error.extend())
+}
+
+#[derive(InputObject)]
+pub struct DeleteItemUnitOfMeasureInput {
+    pub id: String,
+}
+
+#[derive(Union)]
+pub enum DeleteItemUnitOfMeasureResponse {
+    Response(DeleteResponse),
+}
+
+pub fn delete_item_unit_of_measure(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: DeleteItemUnitOfMeasureInput,
+) -> Result<DeleteItemUnitOfMeasureResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateItemNamesCodesAndUnits,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+
+    let result = service_provider
+        .item_service
+        .delete_item_unit_of_measure(&service_context, DeleteItemUnitOfMeasure { id: input.id });
+
+    match result {
+        Ok(id) => Ok(DeleteItemUnitOfMeasureResponse::Response(DeleteResponse(
+            id,
+        ))),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                DeleteItemUnitOfMeasureError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
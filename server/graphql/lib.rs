@@ -37,9 +37,9 @@ use graphql_item_bundle::BundledItemMutations;
 use graphql_item_variant::{ItemVariantMutations, ItemVariantQueries};
 use graphql_location::{LocationMutations, LocationQueries};
 use graphql_plugin::{PluginMutations, PluginQueries};
-use graphql_programs::{ProgramsMutations, ProgramsQueries};
+use graphql_programs::{CentralProgramsMutations, ProgramsMutations, ProgramsQueries};
 use graphql_repack::{RepackMutations, RepackQueries};
-use graphql_reports::ReportQueries;
+use graphql_reports::{ReportMutations, ReportQueries, SelfRequestReportDataFetcher};
 use graphql_requisition::{RequisitionMutations, RequisitionQueries};
 use graphql_requisition_line::RequisitionLineMutations;
 use graphql_stock_line::{StockLineMutations, StockLineQueries};
@@ -50,6 +50,7 @@ use graphql_vaccine_course::{VaccineCourseMutations, VaccineCourseQueries};
 use repository::StorageConnectionManager;
 use service::auth_data::AuthData;
 use service::plugin::validation::ValidatedPluginBucket;
+use service::report::schedule::scheduler::ReportScheduler;
 use service::service_provider::ServiceProvider;
 use service::settings::Settings;
 use service::sync::CentralServerConfig;
@@ -84,6 +85,9 @@ impl CentralServerMutationNode {
     async fn vaccine_course(&self) -> VaccineCourseMutations {
         VaccineCourseMutations
     }
+    async fn programs(&self) -> CentralProgramsMutations {
+        CentralProgramsMutations
+    }
 
     async fn general(&self) -> CentralGeneralMutations {
         CentralGeneralMutations
@@ -181,6 +185,7 @@ pub struct Mutations(
     pub AssetMutations,
     pub AssetLogMutations,
     pub InventoryAdjustmentMutations,
+    pub ReportMutations,
 );
 
 impl Mutations {
@@ -205,6 +210,7 @@ impl Mutations {
             AssetMutations,
             AssetLogMutations,
             InventoryAdjustmentMutations,
+            ReportMutations,
         )
     }
 }
@@ -219,6 +225,7 @@ pub struct GraphqlSchema {
     is_operational: RwLock<bool>,
 }
 
+#[derive(Clone)]
 pub struct GraphSchemaData {
     pub connection_manager: Data<StorageConnectionManager>,
     pub loader_registry: Data<LoaderRegistry>,
@@ -228,8 +235,38 @@ pub struct GraphSchemaData {
     pub validated_plugins: Data<Mutex<ValidatedPluginBucket>>,
 }
 
+fn self_requester_schema(data: &GraphSchemaData) -> OperationalSchema {
+    // Self requester does not need loggers
+    OperationalSchema::build(Queries::new(), Mutations::new(), EmptySubscription)
+        .data(data.connection_manager.clone())
+        .data(data.loader_registry.clone())
+        .data(data.service_provider.clone())
+        .data(data.auth.clone())
+        .data(data.settings.clone())
+        .data(data.validated_plugins.clone())
+        .finish()
+}
+
+/// Scheduled reports query their data through a self requester, like the report endpoint
+pub fn report_scheduler(data: &GraphSchemaData) -> ReportScheduler {
+    ReportScheduler::new(
+        data.service_provider.clone().into_inner(),
+        data.auth.clone().into_inner(),
+        data.settings.server.base_dir.clone(),
+        Box::new(SelfRequestReportDataFetcher::new(
+            data.connection_manager.clone(),
+            data.settings.clone(),
+            SelfRequestImpl::new_boxed(self_requester_schema(data)),
+        )),
+    )
+}
+
 impl GraphqlSchema {
     pub fn new(data: GraphSchemaData, is_operational: bool) -> GraphqlSchema {
+        // Self requester schema is a copy of operational schema, used for reports
+        // needs to be available as data in operational schema
+        let self_requester_schema = self_requester_schema(&data);
+
         let GraphSchemaData {
             connection_manager,
             loader_registry,
@@ -239,19 +276,6 @@ impl GraphqlSchema {
             validated_plugins,
         } = data;
 
-        // Self requester schema is a copy of operational schema, used for reports
-        // needs to be available as data in operational schema
-        let self_requester_schema =
-            OperationalSchema::build(Queries::new(), Mutations::new(), EmptySubscription)
-                .data(connection_manager.clone())
-                .data(loader_registry.clone())
-                .data(service_provider.clone())
-                .data(auth.clone())
-                .data(settings.clone())
-                .data(validated_plugins.clone())
-                .finish();
-        // Self requester does not need loggers
-
         // Operational schema
         let operational_builder =
             OperationalSchema::build(Queries::new(), Mutations::new(), EmptySubscription)
@@ -39,6 +39,9 @@ use mutations::insert_document_registry::*;
 use mutations::patient::insert::insert_patient;
 use mutations::patient::insert::InsertPatientInput;
 use mutations::patient::insert::InsertPatientResponse;
+use mutations::patient::merge::merge_patients;
+use mutations::patient::merge::MergePatientsInput;
+use mutations::patient::merge::MergePatientsResponse;
 use mutations::patient::update::update_patient;
 use mutations::patient::update::UpdatePatientInput;
 use mutations::patient::update::UpdatePatientResponse;
@@ -52,6 +55,9 @@ use mutations::program_patient::insert::*;
 use mutations::program_patient::update::update_program_patient;
 use mutations::program_patient::update::UpdateProgramPatientInput;
 use mutations::program_patient::update::UpdateProgramPatientResponse;
+use mutations::resolve_document_conflict::{
+    resolve_document_conflict, ResolveDocumentConflictInput,
+};
 use mutations::rnr_form::finalise::{
     finalise_rnr_form, FinaliseRnRFormInput, FinaliseRnRFormResponse,
 };
@@ -117,6 +123,15 @@ impl ProgramsQueries {
         document_history(ctx, store_id, name)
     }
 
+    /// Unresolved conflicts of documents edited concurrently on different sites
+    pub async fn document_conflicts(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Store id")] store_id: String,
+    ) -> Result<DocumentConflictConnector> {
+        document_conflicts(ctx, store_id)
+    }
+
     pub async fn document_registries(
         &self,
         ctx: &Context<'_>,
@@ -154,6 +169,28 @@ impl ProgramsQueries {
         patient_search(ctx, store_id, input)
     }
 
+    /// Pairs of patients that are likely the same person, best matches first
+    pub async fn duplicate_patient_candidates(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        page: Option<PaginationInput>,
+        #[graphql(desc = "Minimum similarity score between 0.0 and 1.0, defaults to 0.8")]
+        min_score: Option<f64>,
+    ) -> Result<DuplicatePatientCandidateConnector> {
+        duplicate_patient_candidates(ctx, store_id, page, min_score)
+    }
+
+    /// Generates a printable patient ID card with a QR code of the patient code
+    pub async fn patient_id_card(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        patient_id: String,
+    ) -> Result<PatientIdCardNode> {
+        patient_id_card(ctx, store_id, patient_id)
+    }
+
     pub async fn central_patient_search(
         &self,
         ctx: &Context<'_>,
@@ -323,6 +360,17 @@ impl ProgramsMutations {
         insert_document_registry(ctx, input)
     }
 
+    /// Resolves a document conflict by saving a document version with both conflicting versions
+    /// as parents
+    pub async fn resolve_document_conflict(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: ResolveDocumentConflictInput,
+    ) -> Result<DocumentNode> {
+        resolve_document_conflict(ctx, store_id, input)
+    }
+
     /// Inserts a new patient (without document data)
     pub async fn insert_patient(
         &self,
@@ -486,3 +534,22 @@ impl ProgramsMutations {
         update_vaccination(ctx, store_id, input)
     }
 }
+
+// Central server only mutations
+#[derive(Default, Clone)]
+pub struct CentralProgramsMutations;
+
+#[Object]
+impl CentralProgramsMutations {
+    /// Merges a duplicate patient into the surviving patient. Documents, encounters and program
+    /// enrolments of the merged patient are moved to the surviving patient.
+    /// Only available on the central server, which owns the name links of patients
+    pub async fn merge_patients(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: MergePatientsInput,
+    ) -> Result<MergePatientsResponse> {
+        merge_patients(ctx, store_id, input)
+    }
+}
@@ -5,5 +5,6 @@ pub mod insert_document_registry;
 pub mod patient;
 pub mod program_enrolment;
 pub mod program_patient;
+pub mod resolve_document_conflict;
 pub mod rnr_form;
 pub mod vaccination;
@@ -14,6 +14,7 @@ use service::{
 #[derive(InputObject)]
 pub struct InsertPatientInput {
     pub id: String,
+    /// Generated from the store's patient code scheme if empty
     pub code: String,
     pub code_2: Option<String>,
     pub first_name: Option<String>,
@@ -68,6 +69,12 @@ pub fn insert_patient(
                 InsertPatientError::NotAPatient => {
                     StandardGraphqlError::InternalError(formatted_error)
                 }
+                InsertPatientError::InvalidPatientCode => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                InsertPatientError::InvalidPatientCodePrefix(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
                 InsertPatientError::InternalError(_) => {
                     StandardGraphqlError::InternalError(formatted_error)
                 }
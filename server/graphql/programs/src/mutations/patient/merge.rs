@@ -0,0 +1,75 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::patient::PatientNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    programs::patient::{MergePatients, MergePatientsError},
+};
+
+#[derive(InputObject)]
+pub struct MergePatientsInput {
+    /// Patient that is kept
+    pub surviving_patient_id: String,
+    /// Duplicate patient, its documents, encounters and program enrolments are moved to the
+    /// surviving patient and it is deleted
+    pub merged_patient_id: String,
+}
+
+#[derive(Union)]
+pub enum MergePatientsResponse {
+    Response(PatientNode),
+}
+
+pub fn merge_patients(
+    ctx: &Context<'_>,
+    store_id: String,
+    MergePatientsInput {
+        surviving_patient_id,
+        merged_patient_id,
+    }: MergePatientsInput,
+) -> Result<MergePatientsResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePatient,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let allowed_ctx = user.capabilities();
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    match service_provider.patient_service.merge_patients(
+        &service_context,
+        service_provider,
+        MergePatients {
+            surviving_patient_id,
+            merged_patient_id,
+        },
+    ) {
+        Ok(patient) => Ok(MergePatientsResponse::Response(PatientNode {
+            store_id,
+            patient,
+            allowed_ctx: allowed_ctx.clone(),
+        })),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let std_err = match error {
+                MergePatientsError::SurvivingPatientDoesNotExist
+                | MergePatientsError::MergedPatientDoesNotExist
+                | MergePatientsError::CannotMergePatientWithItself
+                | MergePatientsError::BothPatientsEnrolledInProgram(_) => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                MergePatientsError::InternalError(_) | MergePatientsError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(std_err.extend())
+        }
+    }
+}
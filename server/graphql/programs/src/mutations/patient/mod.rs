@@ -1,2 +1,3 @@
 pub(crate) mod insert;
+pub(crate) mod merge;
 pub(crate) mod update;
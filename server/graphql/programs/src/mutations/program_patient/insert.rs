@@ -76,6 +76,12 @@ pub fn insert_program_patient(
                 UpdateProgramPatientError::PatientExists => {
                     StandardGraphqlError::BadUserInput(formatted_error)
                 }
+                UpdateProgramPatientError::InvalidPatientCode => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                UpdateProgramPatientError::InvalidPatientCodePrefix(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
                 UpdateProgramPatientError::InvalidParentId => {
                     StandardGraphqlError::BadUserInput(formatted_error)
                 }
@@ -77,6 +77,12 @@ pub fn update_program_patient(
                 UpdateProgramPatientError::PatientExists => {
                     StandardGraphqlError::BadUserInput(formatted_error)
                 }
+                UpdateProgramPatientError::InvalidPatientCode => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                UpdateProgramPatientError::InvalidPatientCodePrefix(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
                 UpdateProgramPatientError::InvalidParentId => {
                     StandardGraphqlError::BadUserInput(formatted_error)
                 }
@@ -0,0 +1,70 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::document::DocumentNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    document::document_conflict::{ResolveDocumentConflict, ResolveDocumentConflictError},
+};
+
+#[derive(InputObject)]
+pub struct ResolveDocumentConflictInput {
+    /// The document conflict id
+    pub id: String,
+    /// Document data combining both conflicting versions
+    pub data: serde_json::Value,
+}
+
+pub fn resolve_document_conflict(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: ResolveDocumentConflictInput,
+) -> Result<DocumentNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateDocument,
+            store_id: Some(store_id),
+        },
+    )?;
+    let allowed_ctx = user.capabilities();
+
+    let service_provider = ctx.service_provider();
+    let context = service_provider.basic_context()?;
+
+    match service_provider.document_service.resolve_document_conflict(
+        &context,
+        &user.user_id,
+        ResolveDocumentConflict {
+            id: input.id,
+            data: input.data,
+        },
+        allowed_ctx,
+    ) {
+        Ok(document) => Ok(DocumentNode {
+            allowed_ctx: allowed_ctx.clone(),
+            document,
+        }),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let std_err = match error {
+                ResolveDocumentConflictError::NotAllowedToMutateDocument => {
+                    StandardGraphqlError::Forbidden(formatted_error)
+                }
+                ResolveDocumentConflictError::ConflictDoesNotExist
+                | ResolveDocumentConflictError::ConflictAlreadyResolved
+                | ResolveDocumentConflictError::InvalidDataSchema(_)
+                | ResolveDocumentConflictError::DataSchemaDoesNotExist => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                ResolveDocumentConflictError::DatabaseError(_)
+                | ResolveDocumentConflictError::InternalError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(std_err.extend())
+        }
+    }
+}
@@ -108,7 +108,8 @@ fn map_error(error: ServiceError) -> Result<InsertVaccinationResponse> {
         | ServiceError::ReasonNotProvided
         | ServiceError::StockLineDoesNotExist
         | ServiceError::VaccineIsNotNextDose
-        | ServiceError::ItemDoesNotBelongToVaccineCourse => BadUserInput(formatted_error),
+        | ServiceError::ItemDoesNotBelongToVaccineCourse
+        | ServiceError::DateInClosedPeriod => BadUserInput(formatted_error),
 
         ServiceError::CreatedRecordNotFound
         | ServiceError::ProgramEnrolmentDoesNotExist
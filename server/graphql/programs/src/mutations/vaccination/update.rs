@@ -134,7 +134,8 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         | ServiceError::ReasonNotProvided
         | ServiceError::StockLineDoesNotExist
         | ServiceError::NotNextDose
-        | ServiceError::ItemDoesNotBelongToVaccineCourse => BadUserInput(formatted_error),
+        | ServiceError::ItemDoesNotBelongToVaccineCourse
+        | ServiceError::DateInClosedPeriod => BadUserInput(formatted_error),
 
         ServiceError::UpdatedRecordNotFound
         | ServiceError::InternalError(_)
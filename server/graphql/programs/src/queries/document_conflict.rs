@@ -0,0 +1,99 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::document::DocumentNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    document::document_conflict::DocumentConflict,
+    usize_to_u32,
+};
+
+pub struct DocumentConflictNode {
+    pub allowed_ctx: Vec<String>,
+    pub conflict: DocumentConflict,
+}
+
+#[derive(SimpleObject)]
+pub struct DocumentConflictConnector {
+    pub total_count: u32,
+    pub nodes: Vec<DocumentConflictNode>,
+}
+
+#[Object]
+impl DocumentConflictNode {
+    pub async fn id(&self) -> &str {
+        &self.conflict.row.id
+    }
+
+    pub async fn document_name(&self) -> &str {
+        &self.conflict.row.document_name
+    }
+
+    /// JSON pointers to the values that have been changed differently in both versions
+    pub async fn conflicting_paths(&self) -> &[String] {
+        &self.conflict.conflicting_paths
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.conflict.row.created_datetime, Utc)
+    }
+
+    /// Latest common ancestor of the conflicting versions
+    pub async fn base(&self) -> Option<DocumentNode> {
+        self.conflict.base.clone().map(|document| DocumentNode {
+            allowed_ctx: self.allowed_ctx.clone(),
+            document,
+        })
+    }
+
+    pub async fn left(&self) -> DocumentNode {
+        DocumentNode {
+            allowed_ctx: self.allowed_ctx.clone(),
+            document: self.conflict.left.clone(),
+        }
+    }
+
+    pub async fn right(&self) -> DocumentNode {
+        DocumentNode {
+            allowed_ctx: self.allowed_ctx.clone(),
+            document: self.conflict.right.clone(),
+        }
+    }
+}
+
+/// Documents edited concurrently that couldn't be merged automatically
+pub fn document_conflicts(
+    ctx: &Context<'_>,
+    store_id: String,
+) -> Result<DocumentConflictConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryDocument,
+            store_id: Some(store_id),
+        },
+    )?;
+    let allowed_ctx = user.capabilities();
+
+    let service_provider = ctx.service_provider();
+    let context = service_provider.basic_context()?;
+
+    let conflicts = service_provider
+        .document_service
+        .document_conflicts(&context, allowed_ctx)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(DocumentConflictConnector {
+        total_count: usize_to_u32(conflicts.len()),
+        nodes: conflicts
+            .into_iter()
+            .map(|conflict| DocumentConflictNode {
+                allowed_ctx: allowed_ctx.clone(),
+                conflict,
+            })
+            .collect(),
+    })
+}
@@ -0,0 +1,87 @@
+use async_graphql::*;
+use graphql_core::{
+    pagination::PaginationInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::patient::PatientNode;
+use repository::PaginationOption;
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub struct DuplicatePatientCandidateNode {
+    pub patient: PatientNode,
+    pub duplicate: PatientNode,
+    pub score: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct DuplicatePatientCandidateConnector {
+    pub total_count: u32,
+    pub nodes: Vec<DuplicatePatientCandidateNode>,
+}
+
+#[Object]
+impl DuplicatePatientCandidateNode {
+    async fn patient(&self) -> &PatientNode {
+        &self.patient
+    }
+
+    /// Patient that is likely the same person as `patient`
+    async fn duplicate(&self) -> &PatientNode {
+        &self.duplicate
+    }
+
+    /// How similar the patients are, between 0.0 and 1.0
+    async fn score(&self) -> f64 {
+        self.score
+    }
+}
+
+pub fn duplicate_patient_candidates(
+    ctx: &Context<'_>,
+    store_id: String,
+    page: Option<PaginationInput>,
+    min_score: Option<f64>,
+) -> Result<DuplicatePatientCandidateConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryPatient,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let allowed_ctx = user.capabilities();
+
+    let service_provider = ctx.service_provider();
+    let context = service_provider.basic_context()?;
+
+    let candidates = service_provider
+        .patient_service
+        .duplicate_patient_candidates(
+            &context,
+            page.map(PaginationOption::from),
+            min_score,
+            Some(allowed_ctx),
+        )
+        .map_err(StandardGraphqlError::from_list_error)?;
+
+    let patient_node = |patient| PatientNode {
+        store_id: store_id.clone(),
+        patient,
+        allowed_ctx: allowed_ctx.clone(),
+    };
+    let nodes: Vec<DuplicatePatientCandidateNode> = candidates
+        .rows
+        .into_iter()
+        .map(|candidate| DuplicatePatientCandidateNode {
+            patient: patient_node(candidate.patient),
+            duplicate: patient_node(candidate.duplicate),
+            score: candidate.score,
+        })
+        .collect();
+
+    Ok(DuplicatePatientCandidateConnector {
+        total_count: candidates.count,
+        nodes,
+    })
+}
@@ -2,10 +2,16 @@ pub mod document;
 pub use self::document::*;
 pub mod document_history;
 pub use self::document_history::*;
+pub mod document_conflict;
+pub use self::document_conflict::*;
 pub mod patient;
 pub use self::patient::*;
 pub mod patient_search;
 pub use self::patient_search::*;
+pub mod duplicate_patients;
+pub use self::duplicate_patients::*;
+pub mod patient_id_card;
+pub use self::patient_id_card::*;
 pub mod patient_search_central;
 pub use self::patient_search_central::*;
 pub mod link_patient_to_store;
@@ -0,0 +1,55 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    programs::patient::PrintPatientIdCardError,
+};
+
+#[derive(SimpleObject)]
+pub struct PatientIdCardNode {
+    /// Id of the generated HTML file, to be downloaded from the files endpoint
+    pub file_id: String,
+}
+
+pub fn patient_id_card(
+    ctx: &Context<'_>,
+    store_id: String,
+    patient_id: String,
+) -> Result<PatientIdCardNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryPatient,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let context = service_provider.basic_context()?;
+
+    match service_provider.patient_service.print_patient_id_card(
+        &context,
+        &ctx.get_settings().server.base_dir,
+        &store_id,
+        &patient_id,
+    ) {
+        Ok(file_id) => Ok(PatientIdCardNode { file_id }),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let std_err = match error {
+                PrintPatientIdCardError::PatientDoesNotExist => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                PrintPatientIdCardError::StoreDoesNotExist
+                | PrintPatientIdCardError::FileError(_)
+                | PrintPatientIdCardError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(std_err.extend())
+        }
+    }
+}
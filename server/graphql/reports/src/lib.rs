@@ -1,13 +1,23 @@
 use async_graphql::*;
 use graphql_core::{generic_inputs::PrintReportSortInput, pagination::PaginationInput};
+use graphql_types::types::DeleteResponse;
 use print::{generate_report, generate_report_definition, PrintReportResponse};
 use reports::{
     report, reports, ReportFilterInput, ReportResponse, ReportSortInput, ReportsResponse,
 };
+use schedule::{
+    delete_report_schedule, report_schedule_runs, report_schedules, upsert_report_schedule,
+    ReportScheduleConnector, ReportScheduleNode, ReportScheduleRunConnector,
+    UpsertReportScheduleInput,
+};
 use service::report::report_service::PrintFormat as ServicePrintFormat;
 
 mod print;
 mod reports;
+mod schedule;
+mod scheduler;
+
+pub use scheduler::SelfRequestReportDataFetcher;
 
 #[derive(Default, Clone)]
 pub struct ReportQueries;
@@ -17,6 +27,7 @@ pub enum PrintFormat {
     Pdf,
     Html,
     Excel,
+    Csv,
 }
 
 #[Object]
@@ -80,6 +91,50 @@ impl ReportQueries {
     ) -> Result<PrintReportResponse> {
         generate_report_definition(ctx, store_id, name, report, data_id, arguments, format, current_language).await
     }
+
+    /// Reports that are generated on a schedule and written to a directory on the server
+    pub async fn report_schedules(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<ReportScheduleConnector> {
+        report_schedules(ctx, store_id)
+    }
+
+    /// Run history of a report schedule, latest first
+    pub async fn report_schedule_runs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        report_schedule_id: String,
+        limit: Option<u32>,
+    ) -> Result<ReportScheduleRunConnector> {
+        report_schedule_runs(ctx, store_id, report_schedule_id, limit)
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct ReportMutations;
+
+#[Object]
+impl ReportMutations {
+    pub async fn upsert_report_schedule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertReportScheduleInput,
+    ) -> Result<ReportScheduleNode> {
+        upsert_report_schedule(ctx, store_id, input)
+    }
+
+    pub async fn delete_report_schedule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<DeleteResponse> {
+        delete_report_schedule(ctx, store_id, id)
+    }
 }
 
 impl PrintFormat {
@@ -88,6 +143,7 @@ impl PrintFormat {
             PrintFormat::Pdf => ServicePrintFormat::Pdf,
             PrintFormat::Html => ServicePrintFormat::Html,
             PrintFormat::Excel => ServicePrintFormat::Excel,
+            PrintFormat::Csv => ServicePrintFormat::Csv,
         }
     }
 }
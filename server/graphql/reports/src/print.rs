@@ -2,11 +2,12 @@ use async_graphql::*;
 use chrono::Utc;
 use graphql_core::generic_inputs::PrintReportSortInput;
 use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
-use graphql_core::{ContextExt, RequestUserData};
-use repository::query_json;
+use graphql_core::{BoxedSelfRequest, ContextExt, RequestUserData};
+use repository::{query_json, StorageConnectionManager};
 use service::auth::{Resource, ResourceAccessRequest};
 use service::report::definition::{GraphQlQuery, PrintReportSort, ReportDefinition, SQLQuery};
 use service::report::report_service::{ReportError, ResolvedReportQuery};
+use service::settings::Settings;
 
 use crate::PrintFormat;
 
@@ -90,8 +91,10 @@ pub async fn generate_report(
     };
 
     // fetch data required for the report
+    let fetch_context = FetchContext::from_ctx(ctx)
+        .map_err(|err| StandardGraphqlError::InternalError(format!("{:#?}", err)).extend())?;
     let result = fetch_data(
-        ctx,
+        &fetch_context,
         &resolved_report.queries,
         &store_id,
         data_id,
@@ -173,8 +176,10 @@ pub async fn generate_report_definition(
     };
 
     // fetch data required for the report
+    let fetch_context = FetchContext::from_ctx(ctx)
+        .map_err(|err| StandardGraphqlError::InternalError(format!("{:#?}", err)).extend())?;
     let result = fetch_data(
-        ctx,
+        &fetch_context,
         &resolved_report.queries,
         &store_id,
         data_id,
@@ -217,11 +222,33 @@ pub async fn generate_report_definition(
     Ok(PrintReportResponse::Response(PrintReportNode { file_id }))
 }
 
-enum FetchResult {
+pub(crate) enum FetchResult {
     Data(serde_json::Value),
     Error(serde_json::Value),
 }
 
+/// Everything needed to fetch report data, this allows to fetch data outside of a GraphQL request
+/// (e.g. for scheduled reports)
+pub(crate) struct FetchContext<'a> {
+    pub(crate) self_requester: &'a BoxedSelfRequest,
+    pub(crate) user_data: RequestUserData,
+    pub(crate) settings: &'a Settings,
+    pub(crate) connection_manager: &'a StorageConnectionManager,
+}
+
+impl<'a> FetchContext<'a> {
+    fn from_ctx(ctx: &'a Context<'_>) -> anyhow::Result<FetchContext<'a>> {
+        Ok(FetchContext {
+            self_requester: ctx
+                .self_request()
+                .ok_or_else(|| anyhow::Error::msg("Self requester not available"))?,
+            user_data: ctx.data_unchecked::<RequestUserData>().clone(),
+            settings: ctx.get_settings(),
+            connection_manager: ctx.get_connection_manager(),
+        })
+    }
+}
+
 /// Create query variables for the query
 /// * `query_variables` Some variables that came with the query
 fn query_variables(
@@ -279,8 +306,8 @@ fn query_variables(
     variables
 }
 
-async fn fetch_data(
-    ctx: &Context<'_>,
+pub(crate) async fn fetch_data(
+    ctx: &FetchContext<'_>,
     queries: &Vec<ResolvedReportQuery>,
     store_id: &str,
     data_id: Option<String>,
@@ -324,39 +351,36 @@ async fn fetch_data(
 
 #[cfg(not(feature = "postgres"))]
 fn fetch_sql_data(
-    ctx: &Context<'_>,
+    ctx: &FetchContext<'_>,
     query: &SQLQuery,
     variables: serde_json::Map<String, serde_json::Value>,
 ) -> anyhow::Result<serde_json::Value> {
-    let data = query_json(
-        &ctx.get_settings().database,
-        &query.query_sqlite,
-        &variables,
-    )?;
+    let data = query_json(&ctx.settings.database, &query.query_sqlite, &variables)?;
     Ok(serde_json::Value::Array(data))
 }
 
 #[cfg(feature = "postgres")]
 fn fetch_sql_data(
-    ctx: &Context<'_>,
+    ctx: &FetchContext<'_>,
     query: &SQLQuery,
     variables: serde_json::Map<String, serde_json::Value>,
 ) -> anyhow::Result<serde_json::Value> {
-    let connection = ctx.get_connection_manager().connection()?;
+    let connection = ctx.connection_manager.connection()?;
     let data = query_json(&connection, &query.query_postgres, &variables)?;
     Ok(serde_json::Value::Array(data))
 }
 
 async fn fetch_graphql_data(
-    ctx: &Context<'_>,
+    ctx: &FetchContext<'_>,
     query: &GraphQlQuery,
     variables: serde_json::Map<String, serde_json::Value>,
 ) -> anyhow::Result<FetchResult> {
     let variables = serde_json::from_value(serde_json::Value::Object(variables))?;
-    let user_data = ctx.data_unchecked::<RequestUserData>().clone();
-    let self_requester = ctx.self_request().unwrap();
     let request = Request::new(query.query.clone()).variables(variables);
-    let response = self_requester.call(request, user_data).await;
+    let response = ctx
+        .self_requester
+        .call(request, ctx.user_data.clone())
+        .await;
     if !response.errors.is_empty() {
         return Ok(FetchResult::Error(serde_json::to_value(&response.errors)?));
     }
@@ -0,0 +1,304 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use repository::{
+    ReportScheduleFormat, ReportScheduleRow, ReportScheduleRunRow, ReportScheduleRunStatus,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    report::schedule::{
+        delete_report_schedule as delete, get_report_schedule_runs, get_report_schedules,
+        upsert_report_schedule as upsert, DeleteReportScheduleError, UpsertReportSchedule,
+        UpsertReportScheduleError,
+    },
+};
+
+use crate::PrintFormat;
+
+pub struct ReportScheduleNode {
+    schedule: ReportScheduleRow,
+}
+
+#[Object]
+impl ReportScheduleNode {
+    pub async fn id(&self) -> &str {
+        &self.schedule.id
+    }
+
+    pub async fn report_id(&self) -> &str {
+        &self.schedule.report_id
+    }
+
+    /// User the report is generated as
+    pub async fn user_id(&self) -> &str {
+        &self.schedule.user_id
+    }
+
+    pub async fn arguments(&self) -> Option<serde_json::Value> {
+        self.schedule
+            .arguments
+            .as_deref()
+            .and_then(|arguments| serde_json::from_str(arguments).ok())
+    }
+
+    pub async fn format(&self) -> PrintFormat {
+        PrintFormat::from_schedule_format(&self.schedule.format)
+    }
+
+    /// Cron expression: minute hour day-of-month month day-of-week (server local time)
+    pub async fn schedule(&self) -> &str {
+        &self.schedule.schedule
+    }
+
+    pub async fn output_directory(&self) -> &str {
+        &self.schedule.output_directory
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.schedule.is_active
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.schedule.created_datetime, Utc)
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ReportScheduleRunStatusNode {
+    Running,
+    Success,
+    Error,
+}
+
+pub struct ReportScheduleRunNode {
+    run: ReportScheduleRunRow,
+}
+
+#[Object]
+impl ReportScheduleRunNode {
+    pub async fn id(&self) -> &str {
+        &self.run.id
+    }
+
+    pub async fn report_schedule_id(&self) -> &str {
+        &self.run.report_schedule_id
+    }
+
+    pub async fn scheduled_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.run.scheduled_datetime, Utc)
+    }
+
+    pub async fn started_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.run.started_datetime, Utc)
+    }
+
+    pub async fn finished_datetime(&self) -> Option<DateTime<Utc>> {
+        self.run
+            .finished_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn status(&self) -> ReportScheduleRunStatusNode {
+        match self.run.status {
+            ReportScheduleRunStatus::Running => ReportScheduleRunStatusNode::Running,
+            ReportScheduleRunStatus::Success => ReportScheduleRunStatusNode::Success,
+            ReportScheduleRunStatus::Error => ReportScheduleRunStatusNode::Error,
+        }
+    }
+
+    /// Path of the generated report in the output directory
+    pub async fn output_path(&self) -> &Option<String> {
+        &self.run.output_path
+    }
+
+    pub async fn error(&self) -> &Option<String> {
+        &self.run.error
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ReportScheduleConnector {
+    total_count: u32,
+    nodes: Vec<ReportScheduleNode>,
+}
+
+#[derive(SimpleObject)]
+pub struct ReportScheduleRunConnector {
+    total_count: u32,
+    nodes: Vec<ReportScheduleRunNode>,
+}
+
+#[derive(InputObject)]
+pub struct UpsertReportScheduleInput {
+    pub id: String,
+    pub report_id: String,
+    pub arguments: Option<serde_json::Value>,
+    pub format: PrintFormat,
+    /// Cron expression: minute hour day-of-month month day-of-week (server local time), e.g.
+    /// `0 6 1 * *` for 6am on the first of every month
+    pub schedule: String,
+    /// Absolute path of the directory on the server reports are written to
+    pub output_directory: String,
+    pub is_active: bool,
+}
+
+pub fn report_schedules(ctx: &Context<'_>, store_id: String) -> Result<ReportScheduleConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::Report,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_context = ctx
+        .service_provider()
+        .context(store_id.clone(), user.user_id)?;
+
+    let schedules = get_report_schedules(&service_context, &store_id)?;
+    Ok(ReportScheduleConnector {
+        total_count: schedules.len() as u32,
+        nodes: schedules
+            .into_iter()
+            .map(|schedule| ReportScheduleNode { schedule })
+            .collect(),
+    })
+}
+
+pub fn report_schedule_runs(
+    ctx: &Context<'_>,
+    store_id: String,
+    report_schedule_id: String,
+    limit: Option<u32>,
+) -> Result<ReportScheduleRunConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::Report,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_context = ctx
+        .service_provider()
+        .context(store_id.clone(), user.user_id)?;
+
+    let runs = get_report_schedule_runs(&service_context, &store_id, &report_schedule_id, limit)?;
+    Ok(ReportScheduleRunConnector {
+        total_count: runs.len() as u32,
+        nodes: runs
+            .into_iter()
+            .map(|run| ReportScheduleRunNode { run })
+            .collect(),
+    })
+}
+
+pub fn upsert_report_schedule(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: UpsertReportScheduleInput,
+) -> Result<ReportScheduleNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateReportSchedule,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_context = ctx.service_provider().context(store_id, user.user_id)?;
+
+    let UpsertReportScheduleInput {
+        id,
+        report_id,
+        arguments,
+        format,
+        schedule,
+        output_directory,
+        is_active,
+    } = input;
+    let result = upsert(
+        &service_context,
+        UpsertReportSchedule {
+            id,
+            report_id,
+            arguments,
+            format: format.to_schedule_format(),
+            schedule,
+            output_directory,
+            is_active,
+        },
+    );
+
+    match result {
+        Ok(schedule) => Ok(ReportScheduleNode { schedule }),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                UpsertReportScheduleError::ScheduleDoesNotBelongToCurrentStore
+                | UpsertReportScheduleError::ReportDoesNotExist
+                | UpsertReportScheduleError::InvalidSchedule(_)
+                | UpsertReportScheduleError::OutputDirectoryMustBeAbsolute => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                UpsertReportScheduleError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn delete_report_schedule(
+    ctx: &Context<'_>,
+    store_id: String,
+    id: String,
+) -> Result<DeleteResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateReportSchedule,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_context = ctx.service_provider().context(store_id, user.user_id)?;
+
+    match delete(&service_context, id) {
+        Ok(id) => Ok(DeleteResponse(id)),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                DeleteReportScheduleError::ScheduleDoesNotExist
+                | DeleteReportScheduleError::ScheduleDoesNotBelongToCurrentStore => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                DeleteReportScheduleError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl PrintFormat {
+    fn to_schedule_format(self) -> ReportScheduleFormat {
+        match self {
+            PrintFormat::Pdf => ReportScheduleFormat::Pdf,
+            PrintFormat::Html => ReportScheduleFormat::Html,
+            PrintFormat::Excel => ReportScheduleFormat::Excel,
+            PrintFormat::Csv => ReportScheduleFormat::Csv,
+        }
+    }
+
+    fn from_schedule_format(format: &ReportScheduleFormat) -> Self {
+        match format {
+            ReportScheduleFormat::Pdf => PrintFormat::Pdf,
+            ReportScheduleFormat::Html => PrintFormat::Html,
+            ReportScheduleFormat::Excel => PrintFormat::Excel,
+            ReportScheduleFormat::Csv => PrintFormat::Csv,
+        }
+    }
+}
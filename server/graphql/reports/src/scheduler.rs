@@ -0,0 +1,58 @@
+use actix_web::web::Data;
+use anyhow::anyhow;
+use graphql_core::{BoxedSelfRequest, RequestUserData};
+use repository::StorageConnectionManager;
+use service::{
+    report::schedule::scheduler::{ReportDataFetcher, ScheduledReportDataRequest},
+    settings::Settings,
+};
+
+use crate::print::{fetch_data, FetchContext, FetchResult};
+
+/// Fetches scheduled report data through the GraphQL self requester, in the same way as for the
+/// `generateReport` query
+pub struct SelfRequestReportDataFetcher {
+    connection_manager: Data<StorageConnectionManager>,
+    settings: Data<Settings>,
+    self_requester: BoxedSelfRequest,
+}
+
+impl SelfRequestReportDataFetcher {
+    pub fn new(
+        connection_manager: Data<StorageConnectionManager>,
+        settings: Data<Settings>,
+        self_requester: BoxedSelfRequest,
+    ) -> Self {
+        SelfRequestReportDataFetcher {
+            connection_manager,
+            settings,
+            self_requester,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ReportDataFetcher for SelfRequestReportDataFetcher {
+    async fn fetch_data(
+        &self,
+        request: ScheduledReportDataRequest<'_>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let ScheduledReportDataRequest {
+            store_id,
+            auth_token,
+            queries,
+            arguments,
+        } = request;
+
+        let fetch_context = FetchContext {
+            self_requester: &self.self_requester,
+            user_data: RequestUserData::from_auth_token(auth_token),
+            settings: &self.settings,
+            connection_manager: &self.connection_manager,
+        };
+        match fetch_data(&fetch_context, queries, store_id, None, arguments, None).await? {
+            FetchResult::Data(data) => Ok(data),
+            FetchResult::Error(errors) => Err(anyhow!("Failed to fetch report data: {}", errors)),
+        }
+    }
+}
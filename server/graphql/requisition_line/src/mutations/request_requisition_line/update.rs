@@ -4,7 +4,7 @@ use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
-use graphql_types::types::RequisitionLineNode;
+use graphql_types::types::{QuantityInUnitInput, RequisitionLineNode};
 use repository::RequisitionLine;
 use service::{
     auth::{Resource, ResourceAccessRequest},
@@ -20,6 +20,9 @@ pub struct UpdateInput {
     pub id: String,
     pub requested_quantity: Option<f64>,
     pub comment: Option<String>,
+    /// Requested quantity in one of the item's units of measure, requested_quantity is ignored
+    /// when set
+    pub requested_quantity_in_unit: Option<QuantityInUnitInput>,
 }
 
 #[derive(Interface)]
@@ -81,12 +84,15 @@ impl UpdateInput {
             id,
             requested_quantity,
             comment,
+            requested_quantity_in_unit,
         } = self;
 
         ServiceInput {
             id,
             requested_quantity,
             comment,
+            requested_quantity_in_unit: requested_quantity_in_unit
+                .map(QuantityInUnitInput::to_domain),
         }
     }
 }
@@ -113,6 +119,7 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         // Standard Graphql Errors
         ServiceError::NotThisStoreRequisition => BadUserInput(formatted_error),
         ServiceError::NotARequestRequisition => BadUserInput(formatted_error),
+        ServiceError::InvalidQuantityInUnit(_) => BadUserInput(formatted_error),
         ServiceError::UpdatedRequisitionLineDoesNotExist => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
@@ -328,7 +335,8 @@ mod test {
                 ServiceInput {
                     id: "update line id input".to_string(),
                     requested_quantity: Some(1.0),
-                    comment: Some("comment".to_string())
+                    comment: Some("comment".to_string()),
+                    requested_quantity_in_unit: None,
                 }
             );
             Ok(RequisitionLine {
@@ -6,7 +6,7 @@ use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
-use graphql_types::types::StockLineNode;
+use graphql_types::types::{StockLineNode, VvmStatusNode};
 use repository::StockLine;
 use service::{
     auth::{Resource, ResourceAccessRequest},
@@ -27,6 +27,13 @@ pub struct UpdateInput {
     /// Empty barcode will unlink barcode from StockLine
     pub barcode: Option<String>,
     pub item_variant_id: Option<NullableUpdateInput<String>>,
+    pub vvm_status: Option<NullableVvmStatusUpdate>,
+}
+
+/// Update a nullable VVM status, see `NullableUpdateInput`
+#[derive(InputObject)]
+pub struct NullableVvmStatusUpdate {
+    pub value: Option<VvmStatusNode>,
 }
 
 #[derive(Interface)]
@@ -93,6 +100,7 @@ impl UpdateInput {
             on_hold,
             barcode,
             item_variant_id,
+            vvm_status,
         } = self;
 
         ServiceInput {
@@ -109,6 +117,9 @@ impl UpdateInput {
             item_variant_id: item_variant_id.map(|item_variant_id| NullableUpdate {
                 value: item_variant_id.value,
             }),
+            vvm_status: vvm_status.map(|vvm_status| NullableUpdate {
+                value: vvm_status.value.map(Into::into),
+            }),
         }
     }
 }
@@ -0,0 +1,423 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use repository::{CycleCountPlanRow, CycleCountPlanRunRow};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::cycle_count::{
+        CycleCountClass, CycleCountCoverage, CycleCountCoverageError, CycleCountCoverageStatistics,
+        DeleteCycleCountPlanError, UpsertCycleCountPlan, UpsertCycleCountPlanError,
+    },
+};
+
+pub struct CycleCountPlanNode {
+    plan: CycleCountPlanRow,
+}
+
+#[Object]
+impl CycleCountPlanNode {
+    pub async fn id(&self) -> &str {
+        &self.plan.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.plan.name
+    }
+
+    /// User the stocktakes are created as
+    pub async fn user_id(&self) -> &str {
+        &self.plan.user_id
+    }
+
+    pub async fn master_list_id(&self) -> &Option<String> {
+        &self.plan.master_list_id
+    }
+
+    pub async fn a_class_percentage(&self) -> f64 {
+        self.plan.a_class_percentage
+    }
+
+    pub async fn b_class_percentage(&self) -> f64 {
+        self.plan.b_class_percentage
+    }
+
+    pub async fn a_class_interval_days(&self) -> i32 {
+        self.plan.a_class_interval_days
+    }
+
+    pub async fn b_class_interval_days(&self) -> i32 {
+        self.plan.b_class_interval_days
+    }
+
+    pub async fn c_class_interval_days(&self) -> i32 {
+        self.plan.c_class_interval_days
+    }
+
+    pub async fn run_interval_days(&self) -> i32 {
+        self.plan.run_interval_days
+    }
+
+    pub async fn consumption_look_back_months(&self) -> i32 {
+        self.plan.consumption_look_back_months
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.plan.is_active
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.plan.created_datetime, Utc)
+    }
+}
+
+pub struct CycleCountPlanRunNode {
+    run: CycleCountPlanRunRow,
+}
+
+#[Object]
+impl CycleCountPlanRunNode {
+    pub async fn id(&self) -> &str {
+        &self.run.id
+    }
+
+    pub async fn cycle_count_plan_id(&self) -> &str {
+        &self.run.cycle_count_plan_id
+    }
+
+    pub async fn run_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.run.run_datetime, Utc)
+    }
+
+    /// Generated stocktake, null if no items were due
+    pub async fn stocktake_id(&self) -> &Option<String> {
+        &self.run.stocktake_id
+    }
+
+    pub async fn item_count(&self) -> i32 {
+        self.run.item_count
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum CycleCountClassNode {
+    A,
+    B,
+    C,
+}
+
+pub struct CycleCountCoverageStatisticsNode {
+    statistics: CycleCountCoverageStatistics,
+}
+
+#[Object]
+impl CycleCountCoverageStatisticsNode {
+    pub async fn item_count(&self) -> u32 {
+        self.statistics.item_count
+    }
+
+    pub async fn counted_item_count(&self) -> u32 {
+        self.statistics.counted_item_count
+    }
+
+    pub async fn percentage(&self) -> Option<f64> {
+        self.statistics.percentage()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct CycleCountClassCoverageNode {
+    class: CycleCountClassNode,
+    statistics: CycleCountCoverageStatisticsNode,
+}
+
+pub struct CycleCountCoverageNode {
+    coverage: CycleCountCoverage,
+}
+
+#[Object]
+impl CycleCountCoverageNode {
+    pub async fn plan(&self) -> CycleCountPlanNode {
+        CycleCountPlanNode {
+            plan: self.coverage.plan.clone(),
+        }
+    }
+
+    pub async fn from(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.coverage.from, Utc)
+    }
+
+    pub async fn to(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.coverage.to, Utc)
+    }
+
+    pub async fn classes(&self) -> Vec<CycleCountClassCoverageNode> {
+        self.coverage
+            .classes
+            .iter()
+            .map(|coverage| CycleCountClassCoverageNode {
+                class: CycleCountClassNode::from_domain(coverage.class),
+                statistics: CycleCountCoverageStatisticsNode {
+                    statistics: coverage.statistics.clone(),
+                },
+            })
+            .collect()
+    }
+
+    pub async fn total(&self) -> CycleCountCoverageStatisticsNode {
+        CycleCountCoverageStatisticsNode {
+            statistics: self.coverage.total.clone(),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct CycleCountPlanConnector {
+    total_count: u32,
+    nodes: Vec<CycleCountPlanNode>,
+}
+
+#[derive(SimpleObject)]
+pub struct CycleCountPlanRunConnector {
+    total_count: u32,
+    nodes: Vec<CycleCountPlanRunNode>,
+}
+
+#[derive(InputObject)]
+pub struct UpsertCycleCountPlanInput {
+    pub id: String,
+    pub name: String,
+    /// Only count items of this master list, otherwise items with stock or consumption in the
+    /// store
+    pub master_list_id: Option<String>,
+    /// Cumulative percentage of the consumption value for class A items (default 80)
+    pub a_class_percentage: Option<f64>,
+    /// Cumulative percentage of the consumption value for class A and B items (default 95)
+    pub b_class_percentage: Option<f64>,
+    /// Days within which each A item is counted (default 30)
+    pub a_class_interval_days: Option<i32>,
+    /// Days within which each B item is counted (default 90)
+    pub b_class_interval_days: Option<i32>,
+    /// Days within which each C item is counted (default 365)
+    pub c_class_interval_days: Option<i32>,
+    /// How often a stocktake is generated (default 7)
+    pub run_interval_days: Option<i32>,
+    /// Months of consumption used for the classification (default 12)
+    pub consumption_look_back_months: Option<i32>,
+    pub is_active: bool,
+}
+
+pub fn cycle_count_plans(ctx: &Context<'_>, store_id: String) -> Result<CycleCountPlanConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let plans = service_provider
+        .stocktake_service
+        .get_cycle_count_plans(&service_context, &store_id)?;
+    Ok(CycleCountPlanConnector {
+        total_count: plans.len() as u32,
+        nodes: plans
+            .into_iter()
+            .map(|plan| CycleCountPlanNode { plan })
+            .collect(),
+    })
+}
+
+pub fn cycle_count_plan_runs(
+    ctx: &Context<'_>,
+    store_id: String,
+    cycle_count_plan_id: String,
+    limit: Option<u32>,
+) -> Result<CycleCountPlanRunConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let runs = service_provider
+        .stocktake_service
+        .get_cycle_count_plan_runs(&service_context, &store_id, &cycle_count_plan_id, limit)?;
+    Ok(CycleCountPlanRunConnector {
+        total_count: runs.len() as u32,
+        nodes: runs
+            .into_iter()
+            .map(|run| CycleCountPlanRunNode { run })
+            .collect(),
+    })
+}
+
+pub fn cycle_count_coverage(
+    ctx: &Context<'_>,
+    store_id: String,
+    cycle_count_plan_id: String,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<CycleCountCoverageNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let result = service_provider.stocktake_service.get_cycle_count_coverage(
+        &service_context,
+        &cycle_count_plan_id,
+        from.naive_utc(),
+        to.naive_utc(),
+    );
+
+    match result {
+        Ok(coverage) => Ok(CycleCountCoverageNode { coverage }),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                CycleCountCoverageError::PlanDoesNotExist
+                | CycleCountCoverageError::InvalidPeriod => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                CycleCountCoverageError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn upsert_cycle_count_plan(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: UpsertCycleCountPlanInput,
+) -> Result<CycleCountPlanNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let result = service_provider
+        .stocktake_service
+        .upsert_cycle_count_plan(&service_context, input.to_domain());
+
+    match result {
+        Ok(plan) => Ok(CycleCountPlanNode { plan }),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                UpsertCycleCountPlanError::PlanDoesNotBelongToCurrentStore
+                | UpsertCycleCountPlanError::MasterListNotFoundForThisStore
+                | UpsertCycleCountPlanError::InvalidClassPercentages
+                | UpsertCycleCountPlanError::InvalidInterval => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                UpsertCycleCountPlanError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn delete_cycle_count_plan(
+    ctx: &Context<'_>,
+    store_id: String,
+    id: String,
+) -> Result<DeleteResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    match service_provider
+        .stocktake_service
+        .delete_cycle_count_plan(&service_context, id)
+    {
+        Ok(id) => Ok(DeleteResponse(id)),
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                DeleteCycleCountPlanError::PlanDoesNotExist
+                | DeleteCycleCountPlanError::PlanDoesNotBelongToCurrentStore => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                DeleteCycleCountPlanError::DatabaseError(_) => {
+                    StandardGraphqlError::InternalError(formatted_error)
+                }
+            };
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl UpsertCycleCountPlanInput {
+    pub fn to_domain(self) -> UpsertCycleCountPlan {
+        let UpsertCycleCountPlanInput {
+            id,
+            name,
+            master_list_id,
+            a_class_percentage,
+            b_class_percentage,
+            a_class_interval_days,
+            b_class_interval_days,
+            c_class_interval_days,
+            run_interval_days,
+            consumption_look_back_months,
+            is_active,
+        } = self;
+        let default = UpsertCycleCountPlan::default();
+
+        UpsertCycleCountPlan {
+            id,
+            name,
+            master_list_id,
+            a_class_percentage: a_class_percentage.unwrap_or(default.a_class_percentage),
+            b_class_percentage: b_class_percentage.unwrap_or(default.b_class_percentage),
+            a_class_interval_days: a_class_interval_days.unwrap_or(default.a_class_interval_days),
+            b_class_interval_days: b_class_interval_days.unwrap_or(default.b_class_interval_days),
+            c_class_interval_days: c_class_interval_days.unwrap_or(default.c_class_interval_days),
+            run_interval_days: run_interval_days.unwrap_or(default.run_interval_days),
+            consumption_look_back_months: consumption_look_back_months
+                .unwrap_or(default.consumption_look_back_months),
+            is_active,
+        }
+    }
+}
+
+impl CycleCountClassNode {
+    pub fn from_domain(class: CycleCountClass) -> Self {
+        match class {
+            CycleCountClass::A => CycleCountClassNode::A,
+            CycleCountClass::B => CycleCountClassNode::B,
+            CycleCountClass::C => CycleCountClassNode::C,
+        }
+    }
+}
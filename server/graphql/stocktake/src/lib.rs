@@ -1,9 +1,13 @@
+mod cycle_count;
+use self::cycle_count::*;
 pub mod mutations;
 mod stocktake_queries;
 use self::stocktake_queries::*;
+mod stocktake_variance;
+use self::stocktake_variance::*;
 use async_graphql::*;
 use graphql_core::pagination::PaginationInput;
-use mutations::{delete::*, insert::*, update::*};
+use mutations::{approve_variances::*, blind_count::*, delete::*, insert::*, update::*};
 
 #[derive(Default, Clone)]
 pub struct StocktakeQueries;
@@ -38,6 +42,48 @@ impl StocktakeQueries {
     ) -> Result<StocktakesResponse> {
         stocktakes(ctx, &store_id, page, filter, sort)
     }
+
+    /// Counted lines of a stocktake with a difference to the snapshot, and whether they need
+    /// approval before the stocktake can be finalised
+    pub async fn stocktake_variances(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        stocktake_id: String,
+    ) -> Result<StocktakeLineVarianceConnector> {
+        stocktake_variances(ctx, &store_id, &stocktake_id)
+    }
+
+    pub async fn cycle_count_plans(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<CycleCountPlanConnector> {
+        cycle_count_plans(ctx, store_id)
+    }
+
+    /// Runs of a cycle count plan, latest first
+    pub async fn cycle_count_plan_runs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        cycle_count_plan_id: String,
+        limit: Option<u32>,
+    ) -> Result<CycleCountPlanRunConnector> {
+        cycle_count_plan_runs(ctx, store_id, cycle_count_plan_id, limit)
+    }
+
+    /// Percentage of the items of each class counted within the period
+    pub async fn cycle_count_coverage(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        cycle_count_plan_id: String,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<CycleCountCoverageNode> {
+        cycle_count_coverage(ctx, store_id, cycle_count_plan_id, from, to)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -71,4 +117,51 @@ impl StocktakeMutations {
     ) -> Result<DeleteResponse> {
         delete(ctx, &store_id, input)
     }
+
+    async fn approve_stocktake_variances(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: ApproveVariancesInput,
+    ) -> Result<ApproveVariancesResponse> {
+        approve_variances(ctx, &store_id, input)
+    }
+
+    /// Turns blind count mode on or off, snapshot quantities are hidden from users without the
+    /// stocktake supervise permission until the count is submitted
+    async fn set_stocktake_blind_count(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: SetBlindCountInput,
+    ) -> Result<SetBlindCountResponse> {
+        set_blind_count(ctx, &store_id, input)
+    }
+
+    async fn submit_stocktake_blind_count(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        stocktake_id: String,
+    ) -> Result<SubmitBlindCountResponse> {
+        submit_blind_count(ctx, &store_id, stocktake_id)
+    }
+
+    async fn upsert_cycle_count_plan(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertCycleCountPlanInput,
+    ) -> Result<CycleCountPlanNode> {
+        upsert_cycle_count_plan(ctx, store_id, input)
+    }
+
+    async fn delete_cycle_count_plan(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<graphql_types::types::DeleteResponse> {
+        delete_cycle_count_plan(ctx, store_id, id)
+    }
 }
@@ -0,0 +1,152 @@
+use async_graphql::*;
+
+use graphql_core::simple_generic_errors::CannotEditStocktake;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use service::stocktake::StocktakeLineVariance;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::{
+        ApproveStocktakeVariances as ServiceInput, ApproveStocktakeVariancesError as ServiceError,
+    },
+};
+
+use crate::stocktake_variance::StocktakeLineVarianceConnector;
+
+#[derive(InputObject)]
+#[graphql(name = "ApproveStocktakeVariancesInput")]
+pub struct ApproveVariancesInput {
+    pub stocktake_id: String,
+    /// Stocktake lines to approve, all lines requiring approval if empty
+    pub line_ids: Option<Vec<String>>,
+    pub comment: Option<String>,
+}
+
+pub struct CannotApproveOwnStocktake;
+#[Object]
+impl CannotApproveOwnStocktake {
+    pub async fn description(&self) -> &str {
+        "Stocktake variances must be approved by a different user than the one who created the stocktake"
+    }
+}
+
+pub struct StocktakeLineVarianceReasonNotProvided(pub String);
+#[Object]
+impl StocktakeLineVarianceReasonNotProvided {
+    pub async fn description(&self) -> &str {
+        "Stocktake line needs an adjustment reason or comment before the variance can be approved"
+    }
+
+    pub async fn stocktake_line_id(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "ApproveStocktakeVariancesErrorInterface")]
+#[graphql(field(name = "description", ty = "String"))]
+pub enum ApproveVariancesErrorInterface {
+    CannotEditStocktake(CannotEditStocktake),
+    CannotApproveOwnStocktake(CannotApproveOwnStocktake),
+    StocktakeLineVarianceReasonNotProvided(StocktakeLineVarianceReasonNotProvided),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "ApproveStocktakeVariancesError")]
+pub struct ApproveVariancesError {
+    pub error: ApproveVariancesErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "ApproveStocktakeVariancesResponse")]
+pub enum ApproveVariancesResponse {
+    Error(ApproveVariancesError),
+    Response(StocktakeLineVarianceConnector),
+}
+
+pub fn approve_variances(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ApproveVariancesInput,
+) -> Result<ApproveVariancesResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ApproveStocktakeVariance,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    map_response(
+        service_provider
+            .stocktake_service
+            .approve_stocktake_variances(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(
+    from: Result<Vec<StocktakeLineVariance>, ServiceError>,
+) -> Result<ApproveVariancesResponse> {
+    let result = match from {
+        Ok(variances) => ApproveVariancesResponse::Response(
+            StocktakeLineVarianceConnector::from_domain(variances),
+        ),
+        Err(error) => ApproveVariancesResponse::Error(ApproveVariancesError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+fn map_error(error: ServiceError) -> Result<ApproveVariancesErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::CannotEditFinalised => {
+            return Ok(ApproveVariancesErrorInterface::CannotEditStocktake(
+                CannotEditStocktake {},
+            ))
+        }
+        ServiceError::CannotApproveOwnStocktake => {
+            return Ok(ApproveVariancesErrorInterface::CannotApproveOwnStocktake(
+                CannotApproveOwnStocktake,
+            ))
+        }
+        ServiceError::ReasonNotProvided(line_id) => {
+            return Ok(
+                ApproveVariancesErrorInterface::StocktakeLineVarianceReasonNotProvided(
+                    StocktakeLineVarianceReasonNotProvided(line_id),
+                ),
+            )
+        }
+        // Standard Graphql Errors
+        ServiceError::InvalidStore
+        | ServiceError::StocktakeDoesNotExist
+        | ServiceError::LineDoesNotExist(_)
+        | ServiceError::LineDoesNotRequireApproval(_) => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+impl ApproveVariancesInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let ApproveVariancesInput {
+            stocktake_id,
+            line_ids,
+            comment,
+        } = self;
+
+        ServiceInput {
+            stocktake_id,
+            line_ids: line_ids.unwrap_or_default(),
+            comment,
+        }
+    }
+}
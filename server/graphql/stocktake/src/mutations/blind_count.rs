@@ -0,0 +1,263 @@
+use async_graphql::*;
+
+use graphql_core::simple_generic_errors::CannotEditStocktake;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::{StocktakeLineNode, StocktakeNode};
+use repository::StocktakeLine;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    service_provider::ServiceContext,
+    stocktake::{
+        SetStocktakeBlindCount as ServiceInput, SetStocktakeBlindCountError,
+        StocktakeBlindCountSubmission, SubmitStocktakeBlindCountError,
+    },
+};
+
+#[derive(InputObject)]
+#[graphql(name = "SetStocktakeBlindCountInput")]
+pub struct SetBlindCountInput {
+    pub stocktake_id: String,
+    pub is_blind_count: bool,
+    /// Counted lines with a variance above this percentage of the snapshot need to be
+    /// recounted, no recounts if not set
+    pub recount_tolerance_percentage: Option<f64>,
+}
+
+pub struct StocktakeCountAlreadySubmitted;
+#[Object]
+impl StocktakeCountAlreadySubmitted {
+    pub async fn description(&self) -> &str {
+        "The blind count of the stocktake has already been submitted"
+    }
+}
+
+pub struct StocktakeLinesNotCounted(pub Vec<StocktakeLine>);
+#[Object]
+impl StocktakeLinesNotCounted {
+    pub async fn description(&self) -> &str {
+        "All stocktake lines need to be counted before the count is submitted"
+    }
+
+    pub async fn lines(&self) -> Vec<StocktakeLineNode> {
+        self.0
+            .clone()
+            .into_iter()
+            .map(StocktakeLineNode::from_domain)
+            .collect()
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "SetStocktakeBlindCountErrorInterface")]
+#[graphql(field(name = "description", ty = "String"))]
+pub enum SetBlindCountErrorInterface {
+    CannotEditStocktake(CannotEditStocktake),
+    StocktakeCountAlreadySubmitted(StocktakeCountAlreadySubmitted),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "SetStocktakeBlindCountError")]
+pub struct SetBlindCountError {
+    pub error: SetBlindCountErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "SetStocktakeBlindCountResponse")]
+pub enum SetBlindCountResponse {
+    Error(SetBlindCountError),
+    Response(StocktakeNode),
+}
+
+#[derive(Interface)]
+#[graphql(name = "SubmitStocktakeBlindCountErrorInterface")]
+#[graphql(field(name = "description", ty = "String"))]
+pub enum SubmitBlindCountErrorInterface {
+    CannotEditStocktake(CannotEditStocktake),
+    StocktakeCountAlreadySubmitted(StocktakeCountAlreadySubmitted),
+    StocktakeLinesNotCounted(StocktakeLinesNotCounted),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "SubmitStocktakeBlindCountError")]
+pub struct SubmitBlindCountError {
+    pub error: SubmitBlindCountErrorInterface,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "SubmitStocktakeBlindCountNode")]
+pub struct SubmitBlindCountNode {
+    pub stocktake: StocktakeNode,
+    pub is_submitted: bool,
+    /// Lines with a variance above the recount tolerance, their count was cleared and they need
+    /// to be recounted before the count is submitted again
+    pub recount_lines: Vec<StocktakeLineNode>,
+}
+
+#[derive(Union)]
+#[graphql(name = "SubmitStocktakeBlindCountResponse")]
+pub enum SubmitBlindCountResponse {
+    Error(SubmitBlindCountError),
+    Response(SubmitBlindCountNode),
+}
+
+pub fn set_blind_count(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: SetBlindCountInput,
+) -> Result<SetBlindCountResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::SuperviseStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let stocktake_id = input.stocktake_id.clone();
+
+    match service_provider
+        .stocktake_service
+        .set_stocktake_blind_count(&service_context, input.to_domain())
+    {
+        Ok(_) => Ok(SetBlindCountResponse::Response(get_stocktake_node(
+            ctx,
+            &service_context,
+            stocktake_id,
+        )?)),
+        Err(error) => Ok(SetBlindCountResponse::Error(SetBlindCountError {
+            error: map_set_error(error)?,
+        })),
+    }
+}
+
+pub fn submit_blind_count(
+    ctx: &Context<'_>,
+    store_id: &str,
+    stocktake_id: String,
+) -> Result<SubmitBlindCountResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    match service_provider
+        .stocktake_service
+        .submit_stocktake_blind_count(&service_context, &stocktake_id)
+    {
+        Ok(StocktakeBlindCountSubmission {
+            blind_count,
+            recount_lines,
+        }) => Ok(SubmitBlindCountResponse::Response(SubmitBlindCountNode {
+            stocktake: get_stocktake_node(ctx, &service_context, stocktake_id)?,
+            is_submitted: blind_count.is_submitted(),
+            recount_lines: recount_lines
+                .into_iter()
+                .map(StocktakeLineNode::from_domain)
+                .collect(),
+        })),
+        Err(error) => Ok(SubmitBlindCountResponse::Error(SubmitBlindCountError {
+            error: map_submit_error(error)?,
+        })),
+    }
+}
+
+fn get_stocktake_node(
+    ctx: &Context<'_>,
+    service_context: &ServiceContext,
+    stocktake_id: String,
+) -> Result<StocktakeNode> {
+    let stocktake = ctx
+        .service_provider()
+        .stocktake_service
+        .get_stocktake(service_context, stocktake_id)
+        .map_err(StandardGraphqlError::from_repository_error)?
+        .ok_or(StandardGraphqlError::InternalError("Stocktake not found".to_string()).extend())?;
+
+    Ok(StocktakeNode::from_domain(stocktake))
+}
+
+fn map_set_error(error: SetStocktakeBlindCountError) -> Result<SetBlindCountErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        SetStocktakeBlindCountError::CannotEditFinalised => {
+            return Ok(SetBlindCountErrorInterface::CannotEditStocktake(
+                CannotEditStocktake {},
+            ))
+        }
+        SetStocktakeBlindCountError::CountAlreadySubmitted => {
+            return Ok(SetBlindCountErrorInterface::StocktakeCountAlreadySubmitted(
+                StocktakeCountAlreadySubmitted,
+            ))
+        }
+        // Standard Graphql Errors
+        SetStocktakeBlindCountError::InvalidStore
+        | SetStocktakeBlindCountError::StocktakeDoesNotExist
+        | SetStocktakeBlindCountError::InvalidRecountTolerance => BadUserInput(formatted_error),
+        SetStocktakeBlindCountError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+fn map_submit_error(
+    error: SubmitStocktakeBlindCountError,
+) -> Result<SubmitBlindCountErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        SubmitStocktakeBlindCountError::CannotEditFinalised => {
+            return Ok(SubmitBlindCountErrorInterface::CannotEditStocktake(
+                CannotEditStocktake {},
+            ))
+        }
+        SubmitStocktakeBlindCountError::CountAlreadySubmitted => {
+            return Ok(
+                SubmitBlindCountErrorInterface::StocktakeCountAlreadySubmitted(
+                    StocktakeCountAlreadySubmitted,
+                ),
+            )
+        }
+        SubmitStocktakeBlindCountError::UncountedLines(lines) => {
+            return Ok(SubmitBlindCountErrorInterface::StocktakeLinesNotCounted(
+                StocktakeLinesNotCounted(lines),
+            ))
+        }
+        // Standard Graphql Errors
+        SubmitStocktakeBlindCountError::InvalidStore
+        | SubmitStocktakeBlindCountError::StocktakeDoesNotExist
+        | SubmitStocktakeBlindCountError::NotABlindCount => BadUserInput(formatted_error),
+        SubmitStocktakeBlindCountError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
+
+impl SetBlindCountInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let SetBlindCountInput {
+            stocktake_id,
+            is_blind_count,
+            recount_tolerance_percentage,
+        } = self;
+
+        ServiceInput {
+            stocktake_id,
+            is_blind_count,
+            recount_tolerance_percentage,
+        }
+    }
+}
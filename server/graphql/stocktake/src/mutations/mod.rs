@@ -1,3 +1,5 @@
+pub mod approve_variances;
+pub mod blind_count;
 pub mod delete;
 pub mod insert;
 pub mod update;
@@ -7,7 +7,7 @@ use graphql_core::ContextExt;
 use graphql_types::generic_errors::{
     SnapshotCountCurrentCountMismatchLine, StockLineReducedBelowZero,
 };
-use graphql_types::types::StocktakeNode;
+use graphql_types::types::{StocktakeLineNode, StocktakeNode};
 use repository::{StockLine, Stocktake, StocktakeLine};
 use service::stocktake::UpdateStocktakeStatus;
 use service::{
@@ -64,6 +64,40 @@ impl StockLinesReducedBelowZero {
     }
 }
 
+pub struct StocktakeVarianceReasonNotProvided(pub Vec<StocktakeLine>);
+
+#[Object]
+impl StocktakeVarianceReasonNotProvided {
+    pub async fn description(&self) -> &str {
+        "Lines with a variance above the store's thresholds need an adjustment reason or comment"
+    }
+
+    pub async fn lines(&self) -> Vec<StocktakeLineNode> {
+        self.0
+            .clone()
+            .into_iter()
+            .map(StocktakeLineNode::from_domain)
+            .collect()
+    }
+}
+
+pub struct StocktakeVarianceNotApproved(pub Vec<StocktakeLine>);
+
+#[Object]
+impl StocktakeVarianceNotApproved {
+    pub async fn description(&self) -> &str {
+        "Lines with a variance above the store's thresholds need to be approved"
+    }
+
+    pub async fn lines(&self) -> Vec<StocktakeLineNode> {
+        self.0
+            .clone()
+            .into_iter()
+            .map(StocktakeLineNode::from_domain)
+            .collect()
+    }
+}
+
 #[derive(Interface)]
 #[graphql(name = "UpdateStocktakeErrorInterface")]
 #[graphql(field(name = "description", ty = "String"))]
@@ -72,6 +106,8 @@ pub enum UpdateErrorInterface {
     StocktakeIsLocked(StocktakeIsLocked),
     CannotEditStocktake(CannotEditStocktake),
     StockLinesReducedBelowZero(StockLinesReducedBelowZero),
+    StocktakeVarianceReasonNotProvided(StocktakeVarianceReasonNotProvided),
+    StocktakeVarianceNotApproved(StocktakeVarianceNotApproved),
 }
 
 #[derive(SimpleObject)]
@@ -141,11 +177,23 @@ fn map_error(err: ServiceError) -> Result<UpdateErrorInterface> {
                 StockLinesReducedBelowZero(lines),
             ))
         }
+        ServiceError::VarianceReasonNotProvided(lines) => {
+            return Ok(UpdateErrorInterface::StocktakeVarianceReasonNotProvided(
+                StocktakeVarianceReasonNotProvided(lines),
+            ))
+        }
+        ServiceError::VarianceNotApproved(lines) => {
+            return Ok(UpdateErrorInterface::StocktakeVarianceNotApproved(
+                StocktakeVarianceNotApproved(lines),
+            ))
+        }
         // Standard Graphql Errors
         // TODO some are structured errors (where can be changed concurrently)
         ServiceError::InvalidStore => BadUserInput(formatted_error),
         ServiceError::StocktakeDoesNotExist => BadUserInput(formatted_error),
         ServiceError::NoLines => BadUserInput(formatted_error),
+        ServiceError::BlindCountNotSubmitted => BadUserInput(formatted_error),
+        ServiceError::DateInClosedPeriod => BadUserInput(formatted_error),
         ServiceError::InternalError(err) => InternalError(err),
         ServiceError::InsertStockInLineError { .. }
         | ServiceError::InsertStockOutLineError { .. }
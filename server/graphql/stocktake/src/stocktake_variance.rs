@@ -0,0 +1,135 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::{is_blind_count_snapshot_hidden, StocktakeLineNode};
+use repository::StocktakeVarianceApprovalRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::StocktakeLineVariance,
+};
+
+pub struct StocktakeVarianceApprovalNode {
+    pub approval: StocktakeVarianceApprovalRow,
+}
+
+#[Object]
+impl StocktakeVarianceApprovalNode {
+    pub async fn id(&self) -> &str {
+        &self.approval.id
+    }
+
+    pub async fn counted_number_of_packs(&self) -> f64 {
+        self.approval.counted_number_of_packs
+    }
+
+    pub async fn approved_by_user_id(&self) -> &str {
+        &self.approval.approved_by_user_id
+    }
+
+    pub async fn approved_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.approval.approved_datetime, Utc)
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.approval.comment
+    }
+}
+
+pub struct StocktakeLineVarianceNode {
+    pub variance: StocktakeLineVariance,
+}
+
+#[Object]
+impl StocktakeLineVarianceNode {
+    pub async fn line(&self) -> StocktakeLineNode {
+        StocktakeLineNode::from_domain(self.variance.line.clone())
+    }
+
+    pub async fn difference_number_of_packs(&self) -> f64 {
+        self.variance.difference_number_of_packs
+    }
+
+    pub async fn value(&self) -> f64 {
+        self.variance.value
+    }
+
+    pub async fn percentage(&self) -> Option<f64> {
+        self.variance.percentage
+    }
+
+    pub async fn requires_approval(&self) -> bool {
+        self.variance.requires_approval
+    }
+
+    pub async fn has_reason(&self) -> bool {
+        self.variance.has_reason()
+    }
+
+    pub async fn approval(&self) -> Option<StocktakeVarianceApprovalNode> {
+        self.variance
+            .approval
+            .clone()
+            .map(|approval| StocktakeVarianceApprovalNode { approval })
+    }
+}
+
+impl StocktakeLineVarianceNode {
+    pub fn from_domain(variance: StocktakeLineVariance) -> StocktakeLineVarianceNode {
+        StocktakeLineVarianceNode { variance }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct StocktakeLineVarianceConnector {
+    total_count: u32,
+    nodes: Vec<StocktakeLineVarianceNode>,
+}
+
+impl StocktakeLineVarianceConnector {
+    pub fn from_domain(variances: Vec<StocktakeLineVariance>) -> StocktakeLineVarianceConnector {
+        StocktakeLineVarianceConnector {
+            total_count: variances.len() as u32,
+            nodes: variances
+                .into_iter()
+                .map(StocktakeLineVarianceNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+pub fn stocktake_variances(
+    ctx: &Context<'_>,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<StocktakeLineVarianceConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_ctx = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    // Variances would reveal the snapshot of a blind count
+    let blind_count = service_provider
+        .stocktake_service
+        .get_stocktake_blind_count(&service_ctx, stocktake_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+    if blind_count.is_some_and(|blind_count| is_blind_count_snapshot_hidden(ctx, &blind_count)) {
+        return Err(StandardGraphqlError::Forbidden(
+            "Variances of a blind count are hidden until the count is submitted".to_string(),
+        )
+        .extend());
+    }
+
+    let variances = service_provider
+        .stocktake_service
+        .get_stocktake_variances(&service_ctx, stocktake_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(StocktakeLineVarianceConnector::from_domain(variances))
+}
@@ -6,7 +6,7 @@ use graphql_core::simple_generic_errors::CannotEditStocktake;
 use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
 use graphql_core::ContextExt;
 use graphql_types::generic_errors::StockLineReducedBelowZero;
-use graphql_types::types::StocktakeLineNode;
+use graphql_types::types::{QuantityInUnitInput, StocktakeLineNode};
 use repository::StocktakeLine;
 use service::NullableUpdate;
 use service::{
@@ -35,6 +35,9 @@ pub struct InsertInput {
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
+    /// Counted quantity in one of the item's units of measure, counted_number_of_packs is
+    /// ignored when set
+    pub counted_quantity_in_unit: Option<QuantityInUnitInput>,
 }
 
 #[derive(Union)]
@@ -124,6 +127,7 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         ServiceError::StockLineAlreadyExistsInStocktake => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
         ServiceError::StocktakeIsLocked => BadUserInput(formatted_error),
+        ServiceError::InvalidQuantityInUnit(_) => BadUserInput(formatted_error),
         ServiceError::StockLineXOrItem => BadUserInput(format!(
             "Either a stock line id or item id must be set (not both), {}",
             formatted_error
@@ -153,6 +157,7 @@ impl InsertInput {
             sell_price_per_pack,
             note,
             inventory_adjustment_reason_id,
+            counted_quantity_in_unit,
         } = self;
 
         ServiceInput {
@@ -172,6 +177,7 @@ impl InsertInput {
             sell_price_per_pack,
             note,
             inventory_adjustment_reason_id,
+            counted_quantity_in_unit: counted_quantity_in_unit.map(QuantityInUnitInput::to_domain),
         }
     }
 }
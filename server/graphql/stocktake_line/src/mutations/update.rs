@@ -8,7 +8,7 @@ use graphql_core::ContextExt;
 use graphql_types::generic_errors::{
     SnapshotCountCurrentCountMismatchLine, StockLineReducedBelowZero,
 };
-use graphql_types::types::StocktakeLineNode;
+use graphql_types::types::{QuantityInUnitInput, StocktakeLineNode};
 use repository::StocktakeLine;
 use service::NullableUpdate;
 use service::{
@@ -37,6 +37,9 @@ pub struct UpdateInput {
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
+    /// Counted quantity in one of the item's units of measure, counted_number_of_packs is
+    /// ignored when set
+    pub counted_quantity_in_unit: Option<QuantityInUnitInput>,
 }
 
 #[derive(Union)]
@@ -107,6 +110,7 @@ impl UpdateInput {
             sell_price_per_pack,
             note,
             inventory_adjustment_reason_id,
+            counted_quantity_in_unit,
         } = self;
 
         ServiceInput {
@@ -124,6 +128,7 @@ impl UpdateInput {
             sell_price_per_pack,
             note,
             inventory_adjustment_reason_id,
+            counted_quantity_in_unit: counted_quantity_in_unit.map(QuantityInUnitInput::to_domain),
         }
     }
 }
@@ -166,6 +171,7 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         ServiceError::StockLineDoesNotExist => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
         ServiceError::StocktakeIsLocked => BadUserInput(formatted_error),
+        ServiceError::InvalidQuantityInUnit(_) => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::InternalError(err) => InternalError(err),
     };
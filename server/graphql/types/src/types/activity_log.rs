@@ -79,6 +79,7 @@ pub enum ActivityLogNodeType {
     DemographicIndicatorUpdated,
     DemographicProjectionCreated,
     DemographicProjectionUpdated,
+    StocktakeVarianceApproved,
 }
 
 #[Object]
@@ -213,6 +214,7 @@ impl ActivityLogNodeType {
             from::DemographicIndicatorUpdated => to::DemographicIndicatorUpdated,
             from::DemographicProjectionCreated => to::DemographicProjectionCreated,
             from::DemographicProjectionUpdated => to::DemographicProjectionUpdated,
+            from::StocktakeVarianceApproved => to::StocktakeVarianceApproved,
         }
     }
 
@@ -277,6 +279,7 @@ impl ActivityLogNodeType {
             from::DemographicIndicatorUpdated => to::DemographicIndicatorUpdated,
             from::DemographicProjectionCreated => to::DemographicProjectionCreated,
             from::DemographicProjectionUpdated => to::DemographicProjectionUpdated,
+            from::StocktakeVarianceApproved => to::StocktakeVarianceApproved,
         }
     }
 }
@@ -1,11 +1,11 @@
-use super::{ItemStatsNode, ItemVariantNode, StockLineConnector};
+use super::{ItemStatsNode, ItemUnitOfMeasureNode, ItemVariantNode, StockLineConnector};
 use async_graphql::dataloader::DataLoader;
 use async_graphql::*;
 use graphql_core::{
     loader::{
-        ItemStatsLoaderInput, ItemVariantsByItemIdLoader, ItemsStatsForItemLoader,
-        ItemsStockOnHandLoader, ItemsStockOnHandLoaderInput, StockLineByItemAndStoreIdLoader,
-        StockLineByItemAndStoreIdLoaderInput,
+        ItemStatsLoaderInput, ItemUnitsOfMeasureByItemIdLoader, ItemVariantsByItemIdLoader,
+        ItemsStatsForItemLoader, ItemsStockOnHandLoader, ItemsStockOnHandLoaderInput,
+        StockLineByItemAndStoreIdLoader, StockLineByItemAndStoreIdLoaderInput,
     },
     simple_generic_errors::InternalError,
     standard_graphql_error::StandardGraphqlError,
@@ -143,6 +143,18 @@ impl ItemNode {
         Ok(ItemVariantNode::from_vec(result))
     }
 
+    /// Dispensing unit and outer packs defined for the item, smallest first. Quantities can be
+    /// entered in these units or in the item's base unit (unit_name)
+    pub async fn units_of_measure(&self, ctx: &Context<'_>) -> Result<Vec<ItemUnitOfMeasureNode>> {
+        let loader = ctx.get_loader::<DataLoader<ItemUnitsOfMeasureByItemIdLoader>>();
+        let result = loader
+            .load_one(self.row().id.clone())
+            .await?
+            .unwrap_or_default();
+
+        Ok(ItemUnitOfMeasureNode::from_vec(result))
+    }
+
     pub async fn msupply_universal_code(&self) -> String {
         self.legacy_string("universalcodes_code")
     }
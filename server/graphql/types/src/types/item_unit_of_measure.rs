@@ -0,0 +1,85 @@
+use async_graphql::*;
+use repository::{ItemUnitOfMeasureRow, UnitOfMeasureType};
+use service::item::unit_of_measure::QuantityInUnit;
+
+#[derive(PartialEq, Debug)]
+pub struct ItemUnitOfMeasureNode {
+    pub unit_of_measure: ItemUnitOfMeasureRow,
+}
+
+#[Object]
+impl ItemUnitOfMeasureNode {
+    pub async fn id(&self) -> &str {
+        &self.unit_of_measure.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.unit_of_measure.name
+    }
+
+    pub async fn r#type(&self) -> UnitOfMeasureTypeNode {
+        UnitOfMeasureTypeNode::from_domain(&self.unit_of_measure.r#type)
+    }
+
+    /// Number of the item's base units in one of this unit
+    pub async fn conversion_factor(&self) -> f64 {
+        self.unit_of_measure.conversion_factor
+    }
+}
+
+impl ItemUnitOfMeasureNode {
+    pub fn from_domain(unit_of_measure: ItemUnitOfMeasureRow) -> ItemUnitOfMeasureNode {
+        ItemUnitOfMeasureNode { unit_of_measure }
+    }
+
+    pub fn from_vec(units: Vec<ItemUnitOfMeasureRow>) -> Vec<ItemUnitOfMeasureNode> {
+        units
+            .into_iter()
+            .map(ItemUnitOfMeasureNode::from_domain)
+            .collect()
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum UnitOfMeasureTypeNode {
+    Dispensing,
+    OuterPack,
+}
+
+impl UnitOfMeasureTypeNode {
+    pub fn from_domain(r#type: &UnitOfMeasureType) -> Self {
+        match r#type {
+            UnitOfMeasureType::Dispensing => UnitOfMeasureTypeNode::Dispensing,
+            UnitOfMeasureType::OuterPack => UnitOfMeasureTypeNode::OuterPack,
+        }
+    }
+
+    pub fn to_domain(self) -> UnitOfMeasureType {
+        match self {
+            UnitOfMeasureTypeNode::Dispensing => UnitOfMeasureType::Dispensing,
+            UnitOfMeasureTypeNode::OuterPack => UnitOfMeasureType::OuterPack,
+        }
+    }
+}
+
+/// Quantity in one of the item's units of measure, converted to number of packs by the server
+#[derive(InputObject, Clone)]
+pub struct QuantityInUnitInput {
+    pub quantity: f64,
+    /// Item's base unit if not provided
+    pub unit_of_measure_id: Option<String>,
+}
+
+impl QuantityInUnitInput {
+    pub fn to_domain(self) -> QuantityInUnit {
+        let QuantityInUnitInput {
+            quantity,
+            unit_of_measure_id,
+        } = self;
+
+        QuantityInUnit {
+            quantity,
+            unit_of_measure_id,
+        }
+    }
+}
@@ -7,6 +7,9 @@ pub use self::item::*;
 pub mod item_variant;
 pub use self::item_variant::*;
 
+pub mod item_unit_of_measure;
+pub use self::item_unit_of_measure::*;
+
 pub mod bundled_item;
 pub use self::bundled_item::*;
 
@@ -43,6 +46,9 @@ pub use self::stocktake::*;
 pub mod stocktake_line;
 pub use self::stocktake_line::*;
 
+pub mod stocktake_blind_count;
+pub use self::stocktake_blind_count::*;
+
 pub mod user;
 pub use self::user::*;
 
@@ -26,6 +26,8 @@ pub enum UserPermission {
     CreateRepack,
     StocktakeQuery,
     StocktakeMutate,
+    StocktakeVarianceApprove,
+    StocktakeSupervise,
     InventoryAdjustmentMutate,
     RequisitionQuery,
     RequisitionMutate,
@@ -47,6 +49,7 @@ pub enum UserPermission {
     StockLineMutate,
     ItemMutate,
     ItemNamesCodesAndUnitsMutate,
+    PriceListMutate,
     PatientQuery,
     PatientMutate,
     DocumentQuery,
@@ -110,6 +113,8 @@ impl UserPermission {
             PermissionType::CreateRepack => UserPermission::CreateRepack,
             PermissionType::StocktakeQuery => UserPermission::StocktakeQuery,
             PermissionType::StocktakeMutate => UserPermission::StocktakeMutate,
+            PermissionType::StocktakeVarianceApprove => UserPermission::StocktakeVarianceApprove,
+            PermissionType::StocktakeSupervise => UserPermission::StocktakeSupervise,
             PermissionType::InventoryAdjustmentMutate => UserPermission::InventoryAdjustmentMutate,
             PermissionType::RequisitionQuery => UserPermission::RequisitionQuery,
             PermissionType::RequisitionMutate => UserPermission::RequisitionMutate,
@@ -137,6 +142,7 @@ impl UserPermission {
             PermissionType::ItemNamesCodesAndUnitsMutate => {
                 UserPermission::ItemNamesCodesAndUnitsMutate
             }
+            PermissionType::PriceListMutate => UserPermission::PriceListMutate,
 
             PermissionType::ColdChainApi => UserPermission::ColdChainApi,
             PermissionType::AssetMutate => UserPermission::AssetMutate,
@@ -160,6 +166,8 @@ impl UserPermission {
             UserPermission::CreateRepack => PermissionType::CreateRepack,
             UserPermission::StocktakeQuery => PermissionType::StocktakeQuery,
             UserPermission::StocktakeMutate => PermissionType::StocktakeMutate,
+            UserPermission::StocktakeVarianceApprove => PermissionType::StocktakeVarianceApprove,
+            UserPermission::StocktakeSupervise => PermissionType::StocktakeSupervise,
             UserPermission::InventoryAdjustmentMutate => PermissionType::InventoryAdjustmentMutate,
             UserPermission::RequisitionQuery => PermissionType::RequisitionQuery,
             UserPermission::RequisitionMutate => PermissionType::RequisitionMutate,
@@ -187,6 +195,7 @@ impl UserPermission {
             UserPermission::ItemNamesCodesAndUnitsMutate => {
                 PermissionType::ItemNamesCodesAndUnitsMutate
             }
+            UserPermission::PriceListMutate => PermissionType::PriceListMutate,
             UserPermission::ColdChainApi => PermissionType::ColdChainApi,
             UserPermission::AssetMutate => PermissionType::AssetMutate,
             UserPermission::AssetQuery => PermissionType::AssetQuery,
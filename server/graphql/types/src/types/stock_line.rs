@@ -19,6 +19,15 @@ pub struct StockLineNode {
     pub stock_line: StockLine,
 }
 
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::VvmStatus")]
+pub enum VvmStatusNode {
+    Stage1,
+    Stage2,
+    Stage3,
+    Stage4,
+}
+
 #[derive(SimpleObject)]
 pub struct StockLineConnector {
     total_count: u32,
@@ -66,6 +75,10 @@ impl StockLineNode {
     pub async fn on_hold(&self) -> bool {
         self.row().on_hold
     }
+    /// Vaccine vial monitor stage, stock at stage 3 or 4 is not allocated
+    pub async fn vvm_status(&self) -> Option<VvmStatusNode> {
+        self.row().vvm_status.map(VvmStatusNode::from)
+    }
     pub async fn note(&self) -> &Option<String> {
         &self.row().note
     }
@@ -4,12 +4,15 @@ use repository::{StocktakeRow, StocktakeStatus};
 use serde::Serialize;
 
 use graphql_core::{
-    loader::{InvoiceByIdLoader, StocktakeLineByStocktakeIdLoader, UserLoader},
+    loader::{
+        InvoiceByIdLoader, StocktakeBlindCountByStocktakeIdLoader,
+        StocktakeLineByStocktakeIdLoader, UserLoader,
+    },
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
 };
 
-use super::{InvoiceNode, StocktakeLineConnector, UserNode};
+use super::{InvoiceNode, StocktakeBlindCountNode, StocktakeLineConnector, UserNode};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")] // only needed to be comparable in tests
@@ -129,6 +132,16 @@ impl StocktakeNode {
 
         Ok(result)
     }
+
+    /// Null if the stocktake isn't counted in blind count mode
+    pub async fn blind_count(&self, ctx: &Context<'_>) -> Result<Option<StocktakeBlindCountNode>> {
+        let loader = ctx.get_loader::<DataLoader<StocktakeBlindCountByStocktakeIdLoader>>();
+
+        Ok(loader
+            .load_one(self.stocktake.id.clone())
+            .await?
+            .map(StocktakeBlindCountNode::from_domain))
+    }
 }
 
 impl StocktakeNode {
@@ -0,0 +1,122 @@
+use async_graphql::{dataloader::DataLoader, Context, Object, Result};
+use chrono::{DateTime, Utc};
+use repository::{StocktakeBlindCountRow, StocktakeLineRecountRow};
+use service::auth::{Resource, ResourceAccessRequest};
+
+use graphql_core::{
+    loader::{StocktakeBlindCountByStocktakeIdLoader, StocktakeLineRecountByStocktakeIdLoader},
+    standard_graphql_error::validate_auth,
+    ContextExt,
+};
+
+pub struct StocktakeBlindCountNode {
+    pub blind_count: StocktakeBlindCountRow,
+}
+
+#[Object]
+impl StocktakeBlindCountNode {
+    /// Counted lines with a variance above this percentage of the snapshot are recounted, null if
+    /// recounts are disabled
+    pub async fn recount_tolerance_percentage(&self) -> Option<f64> {
+        self.blind_count.recount_tolerance_percentage
+    }
+
+    pub async fn is_submitted(&self) -> bool {
+        self.blind_count.is_submitted()
+    }
+
+    pub async fn submitted_datetime(&self) -> Option<DateTime<Utc>> {
+        self.blind_count
+            .submitted_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn submitted_by_user_id(&self) -> &Option<String> {
+        &self.blind_count.submitted_by_user_id
+    }
+
+    pub async fn recounts(&self, ctx: &Context<'_>) -> Result<Vec<StocktakeLineRecountNode>> {
+        let loader = ctx.get_loader::<DataLoader<StocktakeLineRecountByStocktakeIdLoader>>();
+        let recounts = loader
+            .load_one(self.blind_count.id.clone())
+            .await?
+            .unwrap_or_default();
+        let is_snapshot_hidden = is_blind_count_snapshot_hidden(ctx, &self.blind_count);
+
+        Ok(recounts
+            .into_iter()
+            .map(|recount| StocktakeLineRecountNode {
+                recount,
+                is_snapshot_hidden,
+            })
+            .collect())
+    }
+}
+
+pub struct StocktakeLineRecountNode {
+    pub recount: StocktakeLineRecountRow,
+    pub is_snapshot_hidden: bool,
+}
+
+#[Object]
+impl StocktakeLineRecountNode {
+    pub async fn id(&self) -> &str {
+        &self.recount.id
+    }
+
+    pub async fn stocktake_line_id(&self) -> &str {
+        &self.recount.stocktake_line_id
+    }
+
+    /// Hidden like the snapshot until the count is submitted, to not bias the recount
+    pub async fn first_counted_number_of_packs(&self) -> Option<f64> {
+        (!self.is_snapshot_hidden).then_some(self.recount.first_counted_number_of_packs)
+    }
+
+    pub async fn snapshot_number_of_packs(&self) -> Option<f64> {
+        (!self.is_snapshot_hidden).then_some(self.recount.snapshot_number_of_packs)
+    }
+
+    pub async fn requested_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.recount.requested_datetime, Utc)
+    }
+
+    pub async fn recounted_datetime(&self) -> Option<DateTime<Utc>> {
+        self.recount
+            .recounted_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+}
+
+/// Snapshot quantities of a blind count stocktake are hidden until the count is submitted,
+/// unless the user can supervise stocktakes
+pub async fn is_stocktake_snapshot_hidden(ctx: &Context<'_>, stocktake_id: &str) -> Result<bool> {
+    let loader = ctx.get_loader::<DataLoader<StocktakeBlindCountByStocktakeIdLoader>>();
+    let is_hidden = match loader.load_one(stocktake_id.to_string()).await? {
+        Some(blind_count) => is_blind_count_snapshot_hidden(ctx, &blind_count),
+        None => false,
+    };
+
+    Ok(is_hidden)
+}
+
+pub fn is_blind_count_snapshot_hidden(
+    ctx: &Context<'_>,
+    blind_count: &StocktakeBlindCountRow,
+) -> bool {
+    !blind_count.is_submitted()
+        && validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::SuperviseStocktake,
+                store_id: Some(blind_count.store_id.clone()),
+            },
+        )
+        .is_err()
+}
+
+impl StocktakeBlindCountNode {
+    pub fn from_domain(blind_count: StocktakeBlindCountRow) -> StocktakeBlindCountNode {
+        StocktakeBlindCountNode { blind_count }
+    }
+}
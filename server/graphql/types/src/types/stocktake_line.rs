@@ -12,7 +12,10 @@ use graphql_core::{
     ContextExt,
 };
 
-use super::{InventoryAdjustmentReasonNode, ItemNode, LocationNode, StockLineNode};
+use super::{
+    is_stocktake_snapshot_hidden, InventoryAdjustmentReasonNode, ItemNode, LocationNode,
+    StockLineNode,
+};
 
 pub struct StocktakeLineNode {
     pub line: StocktakeLine,
@@ -28,7 +31,16 @@ impl StocktakeLineNode {
         &self.line.line.stocktake_id
     }
 
+    pub async fn stock_line_id(&self) -> &Option<String> {
+        &self.line.line.stock_line_id
+    }
+
+    /// Null for blind count stocktakes until the count is submitted (the stock line has the
+    /// current quantities), unless the user can supervise stocktakes
     pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        if is_stocktake_snapshot_hidden(ctx, &self.line.line.stocktake_id).await? {
+            return Ok(None);
+        }
         if let Some(ref stock_line) = self.line.stock_line {
             let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
             let stock_line = loader.load_one(stock_line.id.clone()).await?.ok_or(
@@ -57,8 +69,13 @@ impl StocktakeLineNode {
         self.line.line.comment.clone()
     }
 
-    pub async fn snapshot_number_of_packs(&self) -> f64 {
-        self.line.line.snapshot_number_of_packs
+    /// Null for blind count stocktakes until the count is submitted, unless the user can
+    /// supervise stocktakes
+    pub async fn snapshot_number_of_packs(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+        if is_stocktake_snapshot_hidden(ctx, &self.line.line.stocktake_id).await? {
+            return Ok(None);
+        }
+        Ok(Some(self.line.line.snapshot_number_of_packs))
     }
 
     pub async fn counted_number_of_packs(&self) -> Option<f64> {
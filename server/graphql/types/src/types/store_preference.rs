@@ -1,5 +1,5 @@
 use async_graphql::*;
-use repository::StorePreferenceRow;
+use repository::{AllocationStrategy, ForecastMethod, PatientCodeScheme, StorePreferenceRow};
 
 // #[derive(Clone)]
 #[derive(PartialEq, Debug)]
@@ -66,6 +66,106 @@ impl StorePreferenceNode {
     pub async fn extra_fields_in_requisition(&self) -> &bool {
         &self.store_preference.extra_fields_in_requisition
     }
+
+    pub async fn allocation_strategy(&self) -> AllocationStrategyNode {
+        AllocationStrategyNode::from_domain(&self.store_preference.allocation_strategy)
+    }
+
+    pub async fn allocation_preferred_location_id(&self) -> &Option<String> {
+        &self.store_preference.allocation_preferred_location_id
+    }
+
+    pub async fn forecast_method(&self) -> ForecastMethodNode {
+        ForecastMethodNode::from_domain(&self.store_preference.forecast_method)
+    }
+
+    /// Stocktake line adjustments worth more than this need approval, 0 if disabled
+    pub async fn stocktake_variance_value_threshold(&self) -> f64 {
+        self.store_preference.stocktake_variance_value_threshold
+    }
+
+    /// Stocktake line adjustments of more than this percentage need approval, 0 if disabled
+    pub async fn stocktake_variance_percentage_threshold(&self) -> f64 {
+        self.store_preference.stocktake_variance_percentage_threshold
+    }
+
+    pub async fn patient_code_scheme(&self) -> PatientCodeSchemeNode {
+        PatientCodeSchemeNode::from_domain(&self.store_preference.patient_code_scheme)
+    }
+
+    /// Prefix of generated patient codes, the site id is used if not set
+    pub async fn patient_code_prefix(&self) -> &Option<String> {
+        &self.store_preference.patient_code_prefix
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AllocationStrategyNode {
+    Fefo,
+    Fifo,
+    PreferLocation,
+    AvoidBreakingPacks,
+    MinimiseBatches,
+}
+
+impl AllocationStrategyNode {
+    pub fn from_domain(strategy: &AllocationStrategy) -> Self {
+        match strategy {
+            AllocationStrategy::Fefo => AllocationStrategyNode::Fefo,
+            AllocationStrategy::Fifo => AllocationStrategyNode::Fifo,
+            AllocationStrategy::PreferLocation => AllocationStrategyNode::PreferLocation,
+            AllocationStrategy::AvoidBreakingPacks => AllocationStrategyNode::AvoidBreakingPacks,
+            AllocationStrategy::MinimiseBatches => AllocationStrategyNode::MinimiseBatches,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ForecastMethodNode {
+    AverageMonthlyConsumption,
+    WeightedMovingAverage,
+    SeasonalNaive,
+}
+
+impl ForecastMethodNode {
+    pub fn from_domain(method: &ForecastMethod) -> Self {
+        match method {
+            ForecastMethod::AverageMonthlyConsumption => {
+                ForecastMethodNode::AverageMonthlyConsumption
+            }
+            ForecastMethod::WeightedMovingAverage => ForecastMethodNode::WeightedMovingAverage,
+            ForecastMethod::SeasonalNaive => ForecastMethodNode::SeasonalNaive,
+        }
+    }
+
+    pub fn to_domain(self) -> ForecastMethod {
+        match self {
+            ForecastMethodNode::AverageMonthlyConsumption => {
+                ForecastMethod::AverageMonthlyConsumption
+            }
+            ForecastMethodNode::WeightedMovingAverage => ForecastMethod::WeightedMovingAverage,
+            ForecastMethodNode::SeasonalNaive => ForecastMethod::SeasonalNaive,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PatientCodeSchemeNode {
+    Manual,
+    PrefixSequenceLuhn,
+    PrefixSequenceVerhoeff,
+}
+
+impl PatientCodeSchemeNode {
+    pub fn from_domain(scheme: &PatientCodeScheme) -> Self {
+        match scheme {
+            PatientCodeScheme::Manual => PatientCodeSchemeNode::Manual,
+            PatientCodeScheme::PrefixSequenceLuhn => PatientCodeSchemeNode::PrefixSequenceLuhn,
+            PatientCodeScheme::PrefixSequenceVerhoeff => {
+                PatientCodeSchemeNode::PrefixSequenceVerhoeff
+            }
+        }
+    }
 }
 
 impl StorePreferenceNode {
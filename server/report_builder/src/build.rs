@@ -2,7 +2,7 @@ use anyhow::Result;
 use base64::prelude::*;
 use service::report::definition::{
     DefaultQuery, GraphQlQuery, Manifest, ReportDefinition, ReportDefinitionEntry,
-    ReportDefinitionIndex, ReportOutputType, SQLQuery, TeraTemplate,
+    ReportDefinitionIndex, ReportOutputType, SQLQuery, SpreadsheetTemplate, TeraTemplate,
 };
 use std::{
     collections::HashMap,
@@ -103,6 +103,7 @@ fn make_report(args: &BuildArgs, mut files: HashMap<String, PathBuf>) -> Result<
         query: vec![],
         convert_data: None,
         custom_wasm_function: None,
+        spreadsheet: None,
     };
     let mut entries: HashMap<String, ReportDefinitionEntry> = HashMap::new();
 
@@ -154,6 +155,24 @@ fn make_report(args: &BuildArgs, mut files: HashMap<String, PathBuf>) -> Result<
         );
     }
 
+    // spreadsheet
+    if let Some(spreadsheet) = &args.spreadsheet {
+        let file_path = files
+            .remove(spreadsheet)
+            .ok_or(anyhow::Error::msg("Spreadsheet file does not exist"))?;
+        let data = fs::read_to_string(file_path).map_err(|err| {
+            anyhow::Error::msg(format!("Failed to load spreadsheet file: {}", err))
+        })?;
+        let template: SpreadsheetTemplate = serde_json::from_str(&data).map_err(|err| {
+            anyhow::Error::msg(format!("Failed to parse spreadsheet file: {}", err))
+        })?;
+        index.spreadsheet = Some(spreadsheet.clone());
+        entries.insert(
+            spreadsheet.clone(),
+            ReportDefinitionEntry::Spreadsheet(template),
+        );
+    }
+
     // query
     let query_specified = args.query_gql.is_some()
         || args.query_default.is_some()
@@ -46,6 +46,10 @@ pub struct BuildArgs {
     #[clap(long)]
     pub custom_wasm_function: Option<String>,
 
+    /// Name of the json file describing the sheets and columns used for Excel and CSV output
+    #[clap(long)]
+    pub spreadsheet: Option<String>,
+
     /// SQL query name.
     /// This argument requires that there is either
     /// - a single {query_sql}.sql file (for both Sqlite and Postgres)
@@ -65,6 +69,7 @@ pub enum Format {
     Pdf,
     Html,
     Excel,
+    Csv,
 }
 
 #[derive(clap::Args)]
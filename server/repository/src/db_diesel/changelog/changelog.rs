@@ -112,6 +112,11 @@ pub enum ChangelogTableName {
     IndicatorValue,
     BundledItem,
     Item,
+    PriceList,
+    PriceListLine,
+    PriceListNameJoin,
+    ItemUnitOfMeasure,
+    PeriodClose,
 }
 
 pub(crate) enum ChangeLogSyncStyle {
@@ -179,6 +184,11 @@ impl ChangelogTableName {
             ChangelogTableName::PackagingVariant => ChangeLogSyncStyle::Central,
             ChangelogTableName::IndicatorValue => ChangeLogSyncStyle::Legacy,
             ChangelogTableName::BundledItem => ChangeLogSyncStyle::Central,
+            ChangelogTableName::PriceList => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::PriceListLine => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::PriceListNameJoin => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::ItemUnitOfMeasure => ChangeLogSyncStyle::Central,
+            ChangelogTableName::PeriodClose => ChangeLogSyncStyle::Remote,
         }
     }
 }
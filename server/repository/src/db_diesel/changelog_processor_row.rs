@@ -0,0 +1,77 @@
+use super::{
+    changelog_processor_row::changelog_processor::dsl as changelog_processor_dsl, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    changelog_processor (id) {
+        id -> Text,
+        cursor -> BigInt,
+        last_run_datetime -> Nullable<Timestamp>,
+        last_success_datetime -> Nullable<Timestamp>,
+        error_count -> Integer,
+        last_error -> Nullable<Text>,
+        last_error_datetime -> Nullable<Timestamp>,
+        next_retry_datetime -> Nullable<Timestamp>,
+    }
+}
+
+/// State of a changelog processor, `id` is the processor code
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = changelog_processor)]
+pub struct ChangelogProcessorRow {
+    pub id: String,
+    /// Next changelog cursor to process
+    pub cursor: i64,
+    pub last_run_datetime: Option<NaiveDateTime>,
+    pub last_success_datetime: Option<NaiveDateTime>,
+    /// Number of consecutive failed attempts to process the record at `cursor`
+    pub error_count: i32,
+    pub last_error: Option<String>,
+    pub last_error_datetime: Option<NaiveDateTime>,
+    /// Processor is not run again before this time after an error
+    pub next_retry_datetime: Option<NaiveDateTime>,
+}
+
+pub struct ChangelogProcessorRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ChangelogProcessorRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ChangelogProcessorRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &ChangelogProcessorRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(changelog_processor_dsl::changelog_processor)
+            .values(row)
+            .on_conflict(changelog_processor_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<ChangelogProcessorRow>, RepositoryError> {
+        let result = changelog_processor_dsl::changelog_processor
+            .filter(changelog_processor_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<ChangelogProcessorRow>, RepositoryError> {
+        let result = changelog_processor_dsl::changelog_processor
+            .order(changelog_processor_dsl::id.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,145 @@
+use super::{
+    cycle_count_plan_row::cycle_count_plan::dsl as cycle_count_plan_dsl, store_row::store,
+    StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, Delete, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    cycle_count_plan (id) {
+        id -> Text,
+        store_id -> Text,
+        user_id -> Text,
+        name -> Text,
+        master_list_id -> Nullable<Text>,
+        a_class_percentage -> Double,
+        b_class_percentage -> Double,
+        a_class_interval_days -> Integer,
+        b_class_interval_days -> Integer,
+        c_class_interval_days -> Integer,
+        run_interval_days -> Integer,
+        consumption_look_back_months -> Integer,
+        is_active -> Bool,
+        created_datetime -> Timestamp,
+    }
+}
+
+joinable!(cycle_count_plan -> store (store_id));
+
+/// Rolling partial stocktakes for a store. Items are classified by consumption value (ABC),
+/// every `run_interval_days` a stocktake is generated with the items that need to be counted so
+/// that each item is counted once per interval of its class
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = cycle_count_plan)]
+pub struct CycleCountPlanRow {
+    pub id: String,
+    pub store_id: String,
+    /// User the stocktakes are created as
+    pub user_id: String,
+    pub name: String,
+    /// Only count items of this master list, otherwise items with stock or consumption in the store
+    pub master_list_id: Option<String>,
+    /// Items making up this cumulative percentage of the consumption value are class A
+    pub a_class_percentage: f64,
+    /// Items making up this cumulative percentage of the consumption value (after A) are class B,
+    /// the remaining items are class C
+    pub b_class_percentage: f64,
+    pub a_class_interval_days: i32,
+    pub b_class_interval_days: i32,
+    pub c_class_interval_days: i32,
+    /// How often a stocktake is generated
+    pub run_interval_days: i32,
+    /// Period the consumption value is calculated for
+    pub consumption_look_back_months: i32,
+    pub is_active: bool,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct CycleCountPlanRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> CycleCountPlanRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        CycleCountPlanRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &CycleCountPlanRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(cycle_count_plan_dsl::cycle_count_plan)
+            .values(row)
+            .on_conflict(cycle_count_plan_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<CycleCountPlanRow>, RepositoryError> {
+        let result = cycle_count_plan_dsl::cycle_count_plan
+            .filter(cycle_count_plan_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+        let result = cycle_count_plan_dsl::cycle_count_plan
+            .filter(cycle_count_plan_dsl::store_id.eq(store_id))
+            .order(cycle_count_plan_dsl::created_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_active(&self) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+        let result = cycle_count_plan_dsl::cycle_count_plan
+            .filter(cycle_count_plan_dsl::is_active.eq(true))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            cycle_count_plan_dsl::cycle_count_plan.filter(cycle_count_plan_dsl::id.eq(id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CycleCountPlanRowDelete(pub String);
+impl Delete for CycleCountPlanRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        CycleCountPlanRowRepository::new(con).delete(&self.0)?;
+        Ok(None) // Table not in Changelog
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            CycleCountPlanRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for CycleCountPlanRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        CycleCountPlanRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            CycleCountPlanRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
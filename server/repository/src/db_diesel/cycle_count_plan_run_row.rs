@@ -0,0 +1,100 @@
+use super::{
+    cycle_count_plan_row::cycle_count_plan,
+    cycle_count_plan_run_row::cycle_count_plan_run::dsl as cycle_count_plan_run_dsl,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    cycle_count_plan_run (id) {
+        id -> Text,
+        cycle_count_plan_id -> Text,
+        run_datetime -> Timestamp,
+        stocktake_id -> Nullable<Text>,
+        item_count -> Integer,
+    }
+}
+
+joinable!(cycle_count_plan_run -> cycle_count_plan (cycle_count_plan_id));
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = cycle_count_plan_run)]
+pub struct CycleCountPlanRunRow {
+    pub id: String,
+    pub cycle_count_plan_id: String,
+    pub run_datetime: NaiveDateTime,
+    /// Generated stocktake, None if no items were due
+    pub stocktake_id: Option<String>,
+    pub item_count: i32,
+}
+
+pub struct CycleCountPlanRunRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> CycleCountPlanRunRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        CycleCountPlanRunRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &CycleCountPlanRunRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(cycle_count_plan_run_dsl::cycle_count_plan_run)
+            .values(row)
+            .on_conflict(cycle_count_plan_run_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<CycleCountPlanRunRow>, RepositoryError> {
+        let result = cycle_count_plan_run_dsl::cycle_count_plan_run
+            .filter(cycle_count_plan_run_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Runs of a plan, latest first
+    pub fn find_many_by_plan_id(
+        &self,
+        cycle_count_plan_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<CycleCountPlanRunRow>, RepositoryError> {
+        let mut query = cycle_count_plan_run_dsl::cycle_count_plan_run
+            .filter(cycle_count_plan_run_dsl::cycle_count_plan_id.eq(cycle_count_plan_id))
+            .order(cycle_count_plan_run_dsl::run_datetime.desc())
+            .into_boxed();
+        if let Some(limit) = limit {
+            query = query.limit(limit as i64);
+        }
+        let result = query.load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_latest_by_plan_id(
+        &self,
+        cycle_count_plan_id: &str,
+    ) -> Result<Option<CycleCountPlanRunRow>, RepositoryError> {
+        Ok(self
+            .find_many_by_plan_id(cycle_count_plan_id, Some(1))?
+            .pop())
+    }
+
+    pub fn delete_by_plan_id(&self, cycle_count_plan_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            cycle_count_plan_run_dsl::cycle_count_plan_run
+                .filter(cycle_count_plan_run_dsl::cycle_count_plan_id.eq(cycle_count_plan_id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use super::{
+    document_conflict_row::document_conflict::dsl as document_conflict_dsl, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    document_conflict (id) {
+        id -> Text,
+        document_name -> Text,
+        context_id -> Text,
+        base_document_id -> Nullable<Text>,
+        left_document_id -> Text,
+        right_document_id -> Text,
+        conflicting_paths -> Text,
+        created_datetime -> Timestamp,
+        resolved_datetime -> Nullable<Timestamp>,
+        resolved_document_id -> Nullable<Text>,
+    }
+}
+
+/// Two versions (heads) of a document that have been edited concurrently, e.g. offline on
+/// different sites, and couldn't be merged automatically
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = document_conflict)]
+pub struct DocumentConflictRow {
+    pub id: String,
+    pub document_name: String,
+    pub context_id: String,
+    /// Latest common ancestor of the two versions
+    pub base_document_id: Option<String>,
+    pub left_document_id: String,
+    pub right_document_id: String,
+    /// Stringified array of JSON pointers to the values changed differently in both versions
+    pub conflicting_paths: String,
+    pub created_datetime: NaiveDateTime,
+    pub resolved_datetime: Option<NaiveDateTime>,
+    /// Document version that has both conflicting versions as ancestors
+    pub resolved_document_id: Option<String>,
+}
+
+pub struct DocumentConflictRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> DocumentConflictRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        DocumentConflictRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &DocumentConflictRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(document_conflict_dsl::document_conflict)
+            .values(row)
+            .on_conflict(document_conflict_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<DocumentConflictRow>, RepositoryError> {
+        let result = document_conflict_dsl::document_conflict
+            .filter(document_conflict_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_unresolved_by_document_name(
+        &self,
+        document_name: &str,
+    ) -> Result<Vec<DocumentConflictRow>, RepositoryError> {
+        let result = document_conflict_dsl::document_conflict
+            .filter(document_conflict_dsl::document_name.eq(document_name))
+            .filter(document_conflict_dsl::resolved_datetime.is_null())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Unresolved conflicts of documents in the given contexts, oldest first
+    pub fn find_unresolved_by_context_ids(
+        &self,
+        context_ids: &[String],
+    ) -> Result<Vec<DocumentConflictRow>, RepositoryError> {
+        let result = document_conflict_dsl::document_conflict
+            .filter(document_conflict_dsl::context_id.eq_any(context_ids))
+            .filter(document_conflict_dsl::resolved_datetime.is_null())
+            .order(document_conflict_dsl::created_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
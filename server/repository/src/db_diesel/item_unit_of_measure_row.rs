@@ -0,0 +1,190 @@
+use super::{
+    item_link_row::{item_link, item_link::dsl as item_link_dsl},
+    item_unit_of_measure_row::item_unit_of_measure::dsl as item_unit_of_measure_dsl,
+    StorageConnection,
+};
+
+use crate::{
+    repository_error::RepositoryError, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName,
+    Delete, RowActionType, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    item_unit_of_measure (id) {
+        id -> Text,
+        item_link_id -> Text,
+        name -> Text,
+        #[sql_name = "type"] type_ -> crate::db_diesel::item_unit_of_measure_row::UnitOfMeasureTypeMapping,
+        conversion_factor -> Double,
+        deleted_datetime -> Nullable<Timestamp>,
+    }
+}
+
+joinable!(item_unit_of_measure -> item_link (item_link_id));
+allow_tables_to_appear_in_same_query!(item_unit_of_measure, item_link);
+
+/// The item's unit (e.g. tablet) is the base unit, quantities of stock lines are
+/// `number_of_packs * pack_size` base units
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UnitOfMeasureType {
+    /// Unit the item is dispensed in, e.g. a strip of 10 tablets. An item has at most one
+    #[default]
+    Dispensing,
+    /// Packaging the item is ordered and shipped in, e.g. a box of 100 tablets
+    OuterPack,
+}
+
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = item_unit_of_measure)]
+pub struct ItemUnitOfMeasureRow {
+    pub id: String,
+    pub item_link_id: String,
+    pub name: String,
+    #[diesel(column_name = type_)]
+    pub r#type: UnitOfMeasureType,
+    /// Number of base units in one of this unit
+    pub conversion_factor: f64,
+    pub deleted_datetime: Option<NaiveDateTime>,
+}
+
+pub struct ItemUnitOfMeasureRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ItemUnitOfMeasureRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ItemUnitOfMeasureRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &ItemUnitOfMeasureRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(item_unit_of_measure_dsl::item_unit_of_measure)
+            .values(row)
+            .on_conflict(item_unit_of_measure_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::ItemUnitOfMeasure,
+            record_id: row_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<ItemUnitOfMeasureRow>, RepositoryError> {
+        let result = item_unit_of_measure_dsl::item_unit_of_measure
+            .filter(item_unit_of_measure_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Units of measure of the item that are not deleted, including units linked to items merged
+    /// into it, smallest unit first
+    pub fn find_many_by_item_id(
+        &self,
+        item_id: &str,
+    ) -> Result<Vec<ItemUnitOfMeasureRow>, RepositoryError> {
+        let result = item_unit_of_measure_dsl::item_unit_of_measure
+            .inner_join(item_link_dsl::item_link)
+            .filter(item_link_dsl::item_id.eq(item_id))
+            .filter(item_unit_of_measure_dsl::deleted_datetime.is_null())
+            .order(item_unit_of_measure_dsl::conversion_factor.asc())
+            .select(item_unit_of_measure_dsl::item_unit_of_measure::all_columns())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Same as find_many_by_item_id for many items, returned with the id of the item they belong to
+    pub fn find_many_by_item_ids(
+        &self,
+        item_ids: &[String],
+    ) -> Result<Vec<(String, ItemUnitOfMeasureRow)>, RepositoryError> {
+        let result = item_unit_of_measure_dsl::item_unit_of_measure
+            .inner_join(item_link_dsl::item_link)
+            .filter(item_link_dsl::item_id.eq_any(item_ids))
+            .filter(item_unit_of_measure_dsl::deleted_datetime.is_null())
+            .order(item_unit_of_measure_dsl::conversion_factor.asc())
+            .select((
+                item_link_dsl::item_id,
+                item_unit_of_measure_dsl::item_unit_of_measure::all_columns(),
+            ))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn mark_deleted(&self, id: &str) -> Result<i64, RepositoryError> {
+        diesel::update(
+            item_unit_of_measure_dsl::item_unit_of_measure
+                .filter(item_unit_of_measure_dsl::id.eq(id)),
+        )
+        .set(item_unit_of_measure_dsl::deleted_datetime.eq(Some(chrono::Utc::now().naive_utc())))
+        .execute(self.connection.lock().connection())?;
+        // Soft deleted rows are synced as upserts
+        self.insert_changelog(id.to_owned(), RowActionType::Upsert)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<i64, RepositoryError> {
+        diesel::delete(
+            item_unit_of_measure_dsl::item_unit_of_measure
+                .filter(item_unit_of_measure_dsl::id.eq(id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        self.insert_changelog(id.to_owned(), RowActionType::Delete)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ItemUnitOfMeasureRowDelete(pub String);
+impl Delete for ItemUnitOfMeasureRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = ItemUnitOfMeasureRowRepository::new(con).delete(&self.0)?;
+        Ok(Some(cursor_id))
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            ItemUnitOfMeasureRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for ItemUnitOfMeasureRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = ItemUnitOfMeasureRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            ItemUnitOfMeasureRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
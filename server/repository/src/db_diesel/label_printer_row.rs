@@ -0,0 +1,100 @@
+use super::{
+    label_printer_row::label_printer::dsl as label_printer_dsl, PrinterLanguage, StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, Delete, Upsert};
+
+use diesel::prelude::*;
+
+table! {
+    label_printer (id) {
+        id -> Text,
+        name -> Text,
+        address -> Text,
+        port -> Integer,
+        printer_language -> crate::db_diesel::label_template_row::PrinterLanguageMapping,
+    }
+}
+
+/// Network label printer, printed to with the Jetdirect (raw TCP) protocol
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(table_name = label_printer)]
+pub struct LabelPrinterRow {
+    pub id: String,
+    pub name: String,
+    pub address: String,
+    pub port: i32,
+    pub printer_language: PrinterLanguage,
+}
+
+pub struct LabelPrinterRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> LabelPrinterRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        LabelPrinterRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &LabelPrinterRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(label_printer_dsl::label_printer)
+            .values(row)
+            .on_conflict(label_printer_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<LabelPrinterRow>, RepositoryError> {
+        let result = label_printer_dsl::label_printer
+            .filter(label_printer_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<LabelPrinterRow>, RepositoryError> {
+        let result = label_printer_dsl::label_printer
+            .order(label_printer_dsl::name.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(label_printer_dsl::label_printer.filter(label_printer_dsl::id.eq(id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LabelPrinterRowDelete(pub String);
+impl Delete for LabelPrinterRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        LabelPrinterRowRepository::new(con).delete(&self.0)?;
+        Ok(None) // Table not in Changelog
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            LabelPrinterRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for LabelPrinterRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        LabelPrinterRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            LabelPrinterRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
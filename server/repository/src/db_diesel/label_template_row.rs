@@ -0,0 +1,133 @@
+use super::{label_template_row::label_template::dsl as label_template_dsl, StorageConnection};
+
+use crate::{repository_error::RepositoryError, Delete, Upsert};
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    label_template (id) {
+        id -> Text,
+        name -> Text,
+        template_type -> crate::db_diesel::label_template_row::LabelTemplateTypeMapping,
+        printer_language -> crate::db_diesel::label_template_row::PrinterLanguageMapping,
+        template -> Text,
+        label_width -> Integer,
+        label_height -> Integer,
+    }
+}
+
+/// Records a label template prints
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum LabelTemplateType {
+    #[default]
+    StockLine,
+    Location,
+    Asset,
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum PrinterLanguage {
+    #[default]
+    Zpl,
+    Epl,
+}
+
+/// Tera template rendering the printer commands of a single label
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(table_name = label_template)]
+pub struct LabelTemplateRow {
+    pub id: String,
+    pub name: String,
+    pub template_type: LabelTemplateType,
+    pub printer_language: PrinterLanguage,
+    pub template: String,
+    /// Label width in dots
+    pub label_width: i32,
+    /// Label height in dots
+    pub label_height: i32,
+}
+
+pub struct LabelTemplateRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> LabelTemplateRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        LabelTemplateRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &LabelTemplateRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(label_template_dsl::label_template)
+            .values(row)
+            .on_conflict(label_template_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<LabelTemplateRow>, RepositoryError> {
+        let result = label_template_dsl::label_template
+            .filter(label_template_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many(
+        &self,
+        template_type: Option<LabelTemplateType>,
+    ) -> Result<Vec<LabelTemplateRow>, RepositoryError> {
+        let mut query = label_template_dsl::label_template.into_boxed();
+        if let Some(template_type) = template_type {
+            query = query.filter(label_template_dsl::template_type.eq(template_type));
+        }
+        let result = query
+            .order(label_template_dsl::name.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(label_template_dsl::label_template.filter(label_template_dsl::id.eq(id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LabelTemplateRowDelete(pub String);
+impl Delete for LabelTemplateRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        LabelTemplateRowRepository::new(con).delete(&self.0)?;
+        Ok(None) // Table not in Changelog
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            LabelTemplateRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for LabelTemplateRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        LabelTemplateRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            LabelTemplateRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -1,12 +1,15 @@
 use crate::{
-    diesel_macros::{apply_equal_filter, apply_sort, apply_sort_no_case},
-    EqualFilter, InvoiceType, Pagination, RepositoryError, Sort,
+    diesel_macros::{apply_date_time_filter, apply_equal_filter, apply_sort, apply_sort_no_case},
+    DatetimeFilter, EqualFilter, InvoiceType, Pagination, RepositoryError, Sort,
 };
 
-use super::{ledger::ledger::dsl as ledger_dsl, StorageConnection};
+use super::{
+    item_link_row::item_link::dsl as item_link_dsl, ledger::ledger::dsl as ledger_dsl, DBType,
+    StorageConnection,
+};
 
 use chrono::NaiveDateTime;
-use diesel::prelude::*;
+use diesel::{dsl::sum, prelude::*};
 
 table! {
     #[sql_name = "stock_movement"]
@@ -43,6 +46,10 @@ pub struct LedgerRow {
 #[derive(Clone, Default)]
 pub struct LedgerFilter {
     pub stock_line_id: Option<EqualFilter<String>>,
+    pub item_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub datetime: Option<DatetimeFilter>,
+    pub invoice_type: Option<EqualFilter<InvoiceType>>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -67,6 +74,26 @@ impl LedgerFilter {
         self.stock_line_id = Some(filter);
         self
     }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.datetime = Some(filter);
+        self
+    }
+
+    pub fn invoice_type(mut self, filter: EqualFilter<InvoiceType>) -> Self {
+        self.invoice_type = Some(filter);
+        self
+    }
 }
 
 pub struct LedgerRepository<'a> {
@@ -84,15 +111,7 @@ impl<'a> LedgerRepository<'a> {
         filter: Option<LedgerFilter>,
         sort: Option<LedgerSort>,
     ) -> Result<Vec<LedgerRow>, RepositoryError> {
-        let mut query = ledger_dsl::ledger.into_boxed();
-
-        query = query.filter(ledger_dsl::datetime.is_not_null());
-
-        if let Some(f) = filter {
-            let LedgerFilter { stock_line_id } = f;
-
-            apply_equal_filter!(query, stock_line_id, ledger_dsl::stock_line_id);
-        }
+        let mut query = create_filtered_query(filter);
 
         if let Some(sort) = sort {
             match sort.key {
@@ -118,6 +137,8 @@ impl<'a> LedgerRepository<'a> {
                     apply_sort!(query, sort, ledger_dsl::item_id);
                 }
             }
+            // Movements at the same time are listed in a stable order, e.g. for running balances
+            query = query.then_order_by(ledger_dsl::id.asc());
         }
 
         let final_query = query;
@@ -135,6 +156,115 @@ impl<'a> LedgerRepository<'a> {
 
         Ok(result)
     }
+
+    pub fn count(&self, filter: Option<LedgerFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    /// Sum of the quantities of all matching movements
+    pub fn quantity_sum(&self, filter: Option<LedgerFilter>) -> Result<f64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        let result = query
+            .select(sum(ledger_dsl::quantity))
+            .first::<Option<f64>>(self.connection.lock().connection())?;
+
+        Ok(result.unwrap_or_default())
+    }
+
+    /// Sum of the quantities of matching movements that come before the movement with `id` at
+    /// `datetime`, when sorted by datetime and id (the order used for running balances).
+    /// Use an empty `id` to sum all movements before `datetime`
+    pub fn quantity_sum_before(
+        &self,
+        filter: Option<LedgerFilter>,
+        datetime: NaiveDateTime,
+        id: &str,
+    ) -> Result<f64, RepositoryError> {
+        let query = create_filtered_query(filter).filter(
+            ledger_dsl::datetime.lt(datetime).or(ledger_dsl::datetime
+                .eq(datetime)
+                .and(ledger_dsl::id.lt(id.to_string()))),
+        );
+
+        let result = query
+            .select(sum(ledger_dsl::quantity))
+            .first::<Option<f64>>(self.connection.lock().connection())?;
+
+        Ok(result.unwrap_or_default())
+    }
+
+    /// Matching movements from movement `first` to movement `last` (inclusive), sorted by
+    /// datetime and id. Movements are identified by (datetime, id)
+    pub fn query_range(
+        &self,
+        filter: Option<LedgerFilter>,
+        first: (NaiveDateTime, &str),
+        last: (NaiveDateTime, &str),
+    ) -> Result<Vec<LedgerRow>, RepositoryError> {
+        let (first_datetime, first_id) = first;
+        let (last_datetime, last_id) = last;
+        let query = create_filtered_query(filter)
+            .filter(
+                ledger_dsl::datetime
+                    .gt(first_datetime)
+                    .or(ledger_dsl::datetime
+                        .eq(first_datetime)
+                        .and(ledger_dsl::id.ge(first_id.to_string()))),
+            )
+            .filter(
+                ledger_dsl::datetime
+                    .lt(last_datetime)
+                    .or(ledger_dsl::datetime
+                        .eq(last_datetime)
+                        .and(ledger_dsl::id.le(last_id.to_string()))),
+            )
+            .order(ledger_dsl::datetime.asc())
+            .then_order_by(ledger_dsl::id.asc());
+
+        let result = query.load::<LedgerRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+}
+
+type BoxedLedgerQuery = ledger::BoxedQuery<'static, DBType>;
+
+fn create_filtered_query(filter: Option<LedgerFilter>) -> BoxedLedgerQuery {
+    let mut query = ledger_dsl::ledger.into_boxed();
+
+    query = query.filter(ledger_dsl::datetime.is_not_null());
+
+    if let Some(f) = filter {
+        let LedgerFilter {
+            stock_line_id,
+            item_id,
+            store_id,
+            datetime,
+            invoice_type,
+        } = f;
+
+        apply_equal_filter!(query, stock_line_id, ledger_dsl::stock_line_id);
+        apply_equal_filter!(query, store_id, ledger_dsl::store_id);
+        apply_date_time_filter!(query, datetime, ledger_dsl::datetime);
+        apply_equal_filter!(query, invoice_type, ledger_dsl::invoice_type);
+
+        if item_id.is_some() {
+            // Item id column of the view is the item link id
+            let mut item_link_query = item_link_dsl::item_link
+                .select(item_link_dsl::id)
+                .into_boxed::<DBType>();
+            apply_equal_filter!(item_link_query, item_id, item_link_dsl::item_id);
+
+            query = query.filter(ledger_dsl::item_id.eq_any(item_link_query));
+        }
+    }
+
+    query
 }
 
 #[cfg(test)]
@@ -163,5 +293,12 @@ mod tests {
         assert!(repo
             .query(Pagination::all(), Some(filter), Some(sort))
             .is_ok());
+
+        let filter = LedgerFilter::new()
+            .item_id(EqualFilter::equal_to(&mock_stock_line_a().item_link_id))
+            .store_id(EqualFilter::equal_to(&mock_stock_line_a().store_id))
+            .invoice_type(InvoiceType::InboundShipment.equal_to());
+        assert!(repo.count(Some(filter.clone())).is_ok());
+        assert!(repo.quantity_sum(Some(filter)).is_ok());
     }
 }
@@ -7,6 +7,7 @@ pub mod assets;
 pub mod barcode;
 mod barcode_row;
 pub mod changelog;
+mod changelog_processor_row;
 pub mod clinician;
 mod clinician_link_row;
 mod clinician_row;
@@ -19,6 +20,8 @@ pub mod contact_trace_row;
 mod context_row;
 pub mod currency;
 mod currency_row;
+mod cycle_count_plan_row;
+mod cycle_count_plan_run_row;
 pub mod demographic;
 pub mod demographic_indicator;
 pub mod demographic_indicator_row;
@@ -27,6 +30,7 @@ pub mod demographic_projection_row;
 pub mod demographic_row;
 pub mod diesel_schema;
 pub mod document;
+mod document_conflict_row;
 pub mod document_registry;
 mod document_registry_config;
 mod document_registry_row;
@@ -51,8 +55,11 @@ mod invoice_row;
 pub mod item;
 mod item_link_row;
 mod item_row;
+mod item_unit_of_measure_row;
 pub mod item_variant;
 pub mod key_value_store;
+mod label_printer_row;
+mod label_template_row;
 pub mod ledger;
 pub mod location;
 pub mod location_movement;
@@ -76,8 +83,12 @@ mod name_tag_row;
 mod number_row;
 mod patient;
 pub mod period;
+mod period_close_row;
 pub mod plugin_data;
 mod plugin_data_row;
+mod price_list_line_row;
+mod price_list_name_join_row;
+mod price_list_row;
 pub mod program_enrolment;
 mod program_enrolment_row;
 pub mod program_event;
@@ -87,12 +98,15 @@ mod program_indicator_row;
 mod program_requisition;
 pub mod property;
 pub mod property_row;
+mod read_only_query;
 pub mod reason_option;
 pub mod reason_option_row;
 pub mod replenishment;
 pub mod report;
 mod report_query;
 mod report_row;
+mod report_schedule_row;
+mod report_schedule_run_row;
 pub mod requisition;
 pub mod requisition_line;
 pub mod return_reason;
@@ -103,6 +117,7 @@ pub mod rnr_form_line_row;
 pub mod rnr_form_row;
 pub mod sensor;
 mod sensor_row;
+mod shelf_life_rule_row;
 pub mod stock_line;
 mod stock_line_row;
 pub mod stock_movement;
@@ -111,6 +126,9 @@ pub mod stocktake;
 pub mod stocktake_line;
 mod stocktake_line_row;
 mod stocktake_row;
+mod stocktake_blind_count_row;
+mod stocktake_line_recount_row;
+mod stocktake_variance_approval_row;
 mod storage_connection;
 pub mod store;
 mod store_preference_row;
@@ -127,6 +145,9 @@ mod temperature_breach_row;
 mod temperature_excursion;
 pub mod temperature_log;
 mod temperature_log_row;
+mod temperature_notification_row;
+mod temperature_notification_rule_row;
+mod transfer_dead_letter_row;
 mod unit_row;
 mod user;
 pub mod user_permission;
@@ -143,6 +164,7 @@ pub use adjustment::*;
 pub use assets::*;
 pub use barcode_row::*;
 pub use changelog::*;
+pub use changelog_processor_row::*;
 pub use clinician::*;
 pub use clinician_link_row::*;
 pub use clinician_row::*;
@@ -153,11 +175,14 @@ pub use consumption::*;
 pub use context_row::*;
 pub use currency::*;
 pub use currency_row::*;
+pub use cycle_count_plan_row::*;
+pub use cycle_count_plan_run_row::*;
 pub use demographic_indicator::*;
 pub use demographic_indicator_row::*;
 pub use demographic_projection_row::*;
 pub use demographic_row::*;
 pub use document::*;
+pub use document_conflict_row::*;
 pub use document_registry::*;
 pub use document_registry_config::*;
 pub use document_registry_row::*;
@@ -177,7 +202,10 @@ pub use invoice_row::*;
 pub use item::*;
 pub use item_link_row::*;
 pub use item_row::*;
+pub use item_unit_of_measure_row::*;
 pub use key_value_store::*;
+pub use label_printer_row::*;
+pub use label_template_row::*;
 pub use location_movement_row::*;
 pub use location_row::*;
 pub use master_list::*;
@@ -198,8 +226,12 @@ pub use name_tag_row::*;
 pub use number_row::*;
 pub use patient::*;
 pub use period::*;
+pub use period_close_row::*;
 pub use plugin_data::*;
 pub use plugin_data_row::*;
+pub use price_list_line_row::*;
+pub use price_list_name_join_row::*;
+pub use price_list_row::*;
 pub use program_enrolment::*;
 pub use program_enrolment_row::*;
 pub use program_event::*;
@@ -208,11 +240,14 @@ pub use program_indicator::*;
 pub use program_indicator_row::*;
 pub use program_requisition::*;
 pub use property_row::*;
+pub use read_only_query::*;
 pub use reason_option::*;
 pub use replenishment::*;
 pub use report::*;
 pub use report_query::*;
 pub use report_row::*;
+pub use report_schedule_row::*;
+pub use report_schedule_run_row::*;
 pub use requisition::*;
 pub use requisition_line::*;
 pub use return_reason_row::*;
@@ -222,6 +257,7 @@ pub use rnr_form_line_row::*;
 pub use rnr_form_row::*;
 pub use sensor::*;
 pub use sensor_row::*;
+pub use shelf_life_rule_row::*;
 pub use stock_line::*;
 pub use stock_line_row::*;
 pub use stock_movement::*;
@@ -230,6 +266,9 @@ pub use stocktake::*;
 pub use stocktake_line::*;
 pub use stocktake_line_row::*;
 pub use stocktake_row::*;
+pub use stocktake_blind_count_row::*;
+pub use stocktake_line_recount_row::*;
+pub use stocktake_variance_approval_row::*;
 pub use storage_connection::*;
 pub use store::*;
 pub use store_preference_row::*;
@@ -246,6 +285,9 @@ pub use temperature_breach_row::*;
 pub use temperature_excursion::*;
 pub use temperature_log::*;
 pub use temperature_log_row::*;
+pub use temperature_notification_row::*;
+pub use temperature_notification_rule_row::*;
+pub use transfer_dead_letter_row::*;
 pub use unit_row::*;
 pub use user::*;
 pub use user_permission::*;
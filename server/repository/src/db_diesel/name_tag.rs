@@ -20,6 +20,7 @@ pub type NameTag = NameTagRow;
 #[derive(Clone, PartialEq, Debug, Default)]
 pub struct NameTagFilter {
     pub store_id: Option<EqualFilter<String>>,
+    pub name_id: Option<EqualFilter<String>>,
 }
 
 pub struct NameTagRepository<'a> {
@@ -44,11 +45,11 @@ impl<'a> NameTagRepository<'a> {
     pub fn create_filtered_query(filter: Option<NameTagFilter>) -> BoxedNameTagQuery {
         let mut query = name_tag::table.into_boxed();
 
-        let Some(NameTagFilter { store_id }) = filter else {
+        let Some(NameTagFilter { store_id, name_id }) = filter else {
             return query;
         };
 
-        if store_id.is_some() {
+        if store_id.is_some() || name_id.is_some() {
             let mut name_tag_query = name_tag_join_dsl::name_tag_join
                 .left_join(
                     name_link_dsl::name_link
@@ -58,6 +59,7 @@ impl<'a> NameTagRepository<'a> {
                 .into_boxed();
 
             apply_equal_filter!(name_tag_query, store_id, store_dsl::id);
+            apply_equal_filter!(name_tag_query, name_id, name_dsl::id);
 
             query = query.filter(
                 name_tag_dsl::id.eq_any(name_tag_query.select(name_tag_join_dsl::name_tag_id)),
@@ -77,4 +79,9 @@ impl NameTagFilter {
         self.store_id = Some(filter);
         self
     }
+
+    pub fn name_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.name_id = Some(filter);
+        self
+    }
 }
@@ -40,6 +40,8 @@ pub enum NumberRowType {
     SupplierReturn,
     CustomerReturn,
     Program(String),
+    /// Patient codes, per code prefix
+    Patient(String),
 }
 
 impl fmt::Display for NumberRowType {
@@ -57,6 +59,7 @@ impl fmt::Display for NumberRowType {
             NumberRowType::SupplierReturn => write!(f, "SUPPLIER_RETURN"),
             NumberRowType::CustomerReturn => write!(f, "CUSTOMER_RETURN"),
             NumberRowType::Program(custom_string) => write!(f, "PROGRAM_{}", custom_string),
+            NumberRowType::Patient(prefix) => write!(f, "PATIENT_{}", prefix),
         }
     }
 }
@@ -77,13 +80,11 @@ impl TryFrom<String> for NumberRowType {
             "SUPPLIER_RETURN" => Ok(NumberRowType::SupplierReturn),
             "CUSTOMER_RETURN" => Ok(NumberRowType::CustomerReturn),
             _ => match s.split_once('_') {
-                Some((prefix, custom_string)) => {
-                    if prefix == "PROGRAM" {
-                        Ok(NumberRowType::Program(custom_string.to_string()))
-                    } else {
-                        Err(NumberRowTypeError::UnknownTypePrefix(prefix.to_string()))
-                    }
-                }
+                Some((prefix, custom_string)) => match prefix {
+                    "PROGRAM" => Ok(NumberRowType::Program(custom_string.to_string())),
+                    "PATIENT" => Ok(NumberRowType::Patient(custom_string.to_string())),
+                    _ => Err(NumberRowTypeError::UnknownTypePrefix(prefix.to_string())),
+                },
                 None => Err(NumberRowTypeError::MissingTypePrefix),
             },
         }
@@ -244,6 +245,7 @@ mod number_row_mapping_test {
 
         for number_row_type in [
             NumberRowType::Program("EXAMPLE_TEST".to_string()),
+            NumberRowType::Patient("12".to_string()),
             NumberRowType::SupplierReturn,
             NumberRowType::CustomerReturn,
         ] {
@@ -309,6 +311,13 @@ mod number_row_mapping_test {
                             == NumberRowType::Program(s)
                     )
                 }
+                NumberRowType::Patient(s) => {
+                    assert!(
+                        NumberRowType::try_from(NumberRowType::Patient(s.clone()).to_string())
+                            .unwrap()
+                            == NumberRowType::Patient(s)
+                    )
+                }
                 NumberRowType::Repack => {
                     assert!(
                         NumberRowType::try_from(NumberRowType::Repack.to_string()).unwrap()
@@ -0,0 +1,127 @@
+use super::{
+    period_close_row::period_close::dsl as period_close_dsl, store_row::store, StorageConnection,
+};
+
+use crate::{
+    repository_error::RepositoryError, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName,
+    RowActionType, Upsert,
+};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    period_close (id) {
+        id -> Text,
+        store_id -> Text,
+        period_start -> Date,
+        period_end -> Date,
+        closed_datetime -> Timestamp,
+        user_id -> Text,
+    }
+}
+
+joinable!(period_close -> store (store_id));
+
+/// Closed (accounting) period of a store, transactions can't be dated within a closed period
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = period_close)]
+pub struct PeriodCloseRow {
+    pub id: String,
+    pub store_id: String,
+    /// First day of the period
+    pub period_start: NaiveDate,
+    /// Last day of the period
+    pub period_end: NaiveDate,
+    pub closed_datetime: NaiveDateTime,
+    pub user_id: String,
+}
+
+pub struct PeriodCloseRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PeriodCloseRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PeriodCloseRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PeriodCloseRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(period_close_dsl::period_close)
+            .values(row)
+            .on_conflict(period_close_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PeriodCloseRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PeriodClose,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<PeriodCloseRow>, RepositoryError> {
+        let result = period_close_dsl::period_close
+            .filter(period_close_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Closed period of the store containing the date
+    pub fn find_one_by_store_and_date(
+        &self,
+        store_id: &str,
+        date: NaiveDate,
+    ) -> Result<Option<PeriodCloseRow>, RepositoryError> {
+        let result = period_close_dsl::period_close
+            .filter(period_close_dsl::store_id.eq(store_id))
+            .filter(period_close_dsl::period_start.le(date))
+            .filter(period_close_dsl::period_end.ge(date))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Most recent period first
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<PeriodCloseRow>, RepositoryError> {
+        let result = period_close_dsl::period_close
+            .filter(period_close_dsl::store_id.eq(store_id))
+            .order(period_close_dsl::period_start.desc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for PeriodCloseRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PeriodCloseRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PeriodCloseRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
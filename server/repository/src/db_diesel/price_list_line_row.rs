@@ -0,0 +1,159 @@
+use super::{
+    item_link_row::{item_link, item_link::dsl as item_link_dsl},
+    price_list_line_row::price_list_line::dsl as price_list_line_dsl,
+    price_list_row::price_list,
+    PriceListRowRepository, StorageConnection,
+};
+
+use crate::{
+    repository_error::RepositoryError, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName,
+    Delete, RowActionType, Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    price_list_line (id) {
+        id -> Text,
+        price_list_id -> Text,
+        item_link_id -> Text,
+        price_per_unit -> Double,
+    }
+}
+
+joinable!(price_list_line -> price_list (price_list_id));
+joinable!(price_list_line -> item_link (item_link_id));
+allow_tables_to_appear_in_same_query!(price_list_line, item_link);
+allow_tables_to_appear_in_same_query!(price_list_line, price_list);
+
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = price_list_line)]
+pub struct PriceListLineRow {
+    pub id: String,
+    pub price_list_id: String,
+    pub item_link_id: String,
+    pub price_per_unit: f64,
+}
+
+pub struct PriceListLineRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PriceListLineRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PriceListLineRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PriceListLineRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(price_list_line_dsl::price_list_line)
+            .values(row)
+            .on_conflict(price_list_line_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PriceListLineRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        // Get store id via price list
+        let store_id = PriceListRowRepository::new(self.connection)
+            .find_one_by_id(&row.price_list_id)?
+            .map(|price_list| price_list.store_id);
+
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PriceListLine,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<PriceListLineRow>, RepositoryError> {
+        let result = price_list_line_dsl::price_list_line
+            .filter(price_list_line_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_price_list_id(
+        &self,
+        price_list_id: &str,
+    ) -> Result<Vec<PriceListLineRow>, RepositoryError> {
+        let result = price_list_line_dsl::price_list_line
+            .filter(price_list_line_dsl::price_list_id.eq(price_list_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Lines for the item, including lines linked to items merged into it
+    pub fn find_many_by_item_id(
+        &self,
+        item_id: &str,
+    ) -> Result<Vec<PriceListLineRow>, RepositoryError> {
+        let result = price_list_line_dsl::price_list_line
+            .inner_join(item_link_dsl::item_link)
+            .filter(item_link_dsl::item_id.eq(item_id))
+            .select(price_list_line_dsl::price_list_line::all_columns())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<Option<i64>, RepositoryError> {
+        let Some(row) = self.find_one_by_id(id)? else {
+            return Ok(None);
+        };
+        // Changelog first, store id is looked up via the price list
+        let cursor_id = self.insert_changelog(&row, RowActionType::Delete)?;
+        diesel::delete(price_list_line_dsl::price_list_line.filter(price_list_line_dsl::id.eq(id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(cursor_id))
+    }
+
+    pub fn delete_by_price_list_id(&self, price_list_id: &str) -> Result<(), RepositoryError> {
+        for row in self.find_many_by_price_list_id(price_list_id)? {
+            self.delete(&row.id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceListLineRowDelete(pub String);
+impl Delete for PriceListLineRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        PriceListLineRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListLineRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for PriceListLineRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PriceListLineRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListLineRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,153 @@
+use super::{
+    name_link_row::name_link, name_tag_row::name_tag,
+    price_list_name_join_row::price_list_name_join::dsl as price_list_name_join_dsl,
+    price_list_row::price_list, PriceListRowRepository, StorageConnection,
+};
+
+use crate::{
+    repository_error::RepositoryError, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName,
+    Delete, RowActionType, Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    price_list_name_join (id) {
+        id -> Text,
+        price_list_id -> Text,
+        name_link_id -> Nullable<Text>,
+        name_tag_id -> Nullable<Text>,
+    }
+}
+
+joinable!(price_list_name_join -> price_list (price_list_id));
+joinable!(price_list_name_join -> name_link (name_link_id));
+joinable!(price_list_name_join -> name_tag (name_tag_id));
+
+/// Links a price list to a customer, or to all customers with a name tag. Exactly one of
+/// `name_link_id` and `name_tag_id` is set
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = price_list_name_join)]
+pub struct PriceListNameJoinRow {
+    pub id: String,
+    pub price_list_id: String,
+    pub name_link_id: Option<String>,
+    pub name_tag_id: Option<String>,
+}
+
+pub struct PriceListNameJoinRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PriceListNameJoinRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PriceListNameJoinRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PriceListNameJoinRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(price_list_name_join_dsl::price_list_name_join)
+            .values(row)
+            .on_conflict(price_list_name_join_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PriceListNameJoinRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        // Get store id via price list
+        let store_id = PriceListRowRepository::new(self.connection)
+            .find_one_by_id(&row.price_list_id)?
+            .map(|price_list| price_list.store_id);
+
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PriceListNameJoin,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id,
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<PriceListNameJoinRow>, RepositoryError> {
+        let result = price_list_name_join_dsl::price_list_name_join
+            .filter(price_list_name_join_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_price_list_ids(
+        &self,
+        price_list_ids: &[String],
+    ) -> Result<Vec<PriceListNameJoinRow>, RepositoryError> {
+        let result = price_list_name_join_dsl::price_list_name_join
+            .filter(price_list_name_join_dsl::price_list_id.eq_any(price_list_ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<Option<i64>, RepositoryError> {
+        let Some(row) = self.find_one_by_id(id)? else {
+            return Ok(None);
+        };
+        // Changelog first, store id is looked up via the price list
+        let cursor_id = self.insert_changelog(&row, RowActionType::Delete)?;
+        diesel::delete(
+            price_list_name_join_dsl::price_list_name_join
+                .filter(price_list_name_join_dsl::id.eq(id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(Some(cursor_id))
+    }
+
+    pub fn delete_by_price_list_id(&self, price_list_id: &str) -> Result<(), RepositoryError> {
+        for row in self.find_many_by_price_list_ids(&[price_list_id.to_string()])? {
+            self.delete(&row.id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceListNameJoinRowDelete(pub String);
+impl Delete for PriceListNameJoinRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        PriceListNameJoinRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListNameJoinRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for PriceListNameJoinRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PriceListNameJoinRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListNameJoinRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
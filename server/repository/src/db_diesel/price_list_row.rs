@@ -0,0 +1,157 @@
+use super::{
+    price_list_row::price_list::dsl as price_list_dsl, store_row::store, StorageConnection,
+};
+
+use crate::{
+    repository_error::RepositoryError, ChangeLogInsertRow, ChangelogRepository, ChangelogTableName,
+    Delete, RowActionType, Upsert,
+};
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    price_list (id) {
+        id -> Text,
+        store_id -> Text,
+        name -> Text,
+        priority -> Integer,
+        valid_from -> Nullable<Date>,
+        valid_to -> Nullable<Date>,
+        is_active -> Bool,
+    }
+}
+
+joinable!(price_list -> store (store_id));
+
+/// Item prices for customers linked to the list through `price_list_name_join` (names or name
+/// tags), or for all customers if the list has no links. Prices are only used between
+/// `valid_from` and `valid_to` (inclusive, open ended if not set)
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = price_list)]
+pub struct PriceListRow {
+    pub id: String,
+    /// Store the price list is used by
+    pub store_id: String,
+    pub name: String,
+    /// Higher priority lists are used first when several lists at the same level apply
+    pub priority: i32,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+    pub is_active: bool,
+}
+
+impl PriceListRow {
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.is_active
+            && self
+                .valid_from
+                .map_or(true, |valid_from| valid_from <= date)
+            && self.valid_to.map_or(true, |valid_to| date <= valid_to)
+    }
+}
+
+pub struct PriceListRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PriceListRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PriceListRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PriceListRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(price_list_dsl::price_list)
+            .values(row)
+            .on_conflict(price_list_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PriceListRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PriceList,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<PriceListRow>, RepositoryError> {
+        let result = price_list_dsl::price_list
+            .filter(price_list_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<PriceListRow>, RepositoryError> {
+        let result = price_list_dsl::price_list
+            .filter(price_list_dsl::store_id.eq(store_id))
+            .order(price_list_dsl::name.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_ids(&self, ids: &[String]) -> Result<Vec<PriceListRow>, RepositoryError> {
+        let result = price_list_dsl::price_list
+            .filter(price_list_dsl::id.eq_any(ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<Option<i64>, RepositoryError> {
+        let Some(row) = self.find_one_by_id(id)? else {
+            return Ok(None);
+        };
+        diesel::delete(price_list_dsl::price_list.filter(price_list_dsl::id.eq(id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(self.insert_changelog(&row, RowActionType::Delete)?))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceListRowDelete(pub String);
+impl Delete for PriceListRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        PriceListRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for PriceListRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PriceListRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
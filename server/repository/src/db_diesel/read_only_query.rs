@@ -0,0 +1,421 @@
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use diesel::{
+    connection::SimpleConnection,
+    query_builder::BoxedSqlQuery,
+    result::Error as DieselError,
+    sql_query,
+    sql_types::{BigInt, Bool, Double, Nullable, Text},
+    RunQueryDsl,
+};
+
+use crate::{DBType, JsonRawRow, RepositoryError, StorageConnection};
+
+pub struct ReadOnlyQueryLimits {
+    /// Query fails if it returns more rows
+    pub max_rows: usize,
+    /// On Postgres the query is cancelled after this duration. Sqlite queries can't be
+    /// cancelled, the duration is checked once the query completed.
+    pub timeout: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReadOnlyQueryError {
+    /// Statement is not a single SELECT (or WITH ... SELECT) statement or attempted to write
+    NotReadOnly,
+    InvalidParameters(String),
+    RowLimitExceeded(usize),
+    Timeout,
+    /// Row didn't have a `json_row` column containing valid json
+    InvalidRow(String),
+    DatabaseError(RepositoryError),
+}
+
+/// Runs a statement with positional bind parameters (`$1`, `$2`, ... for both Sqlite and
+/// Postgres) in read only mode.
+///
+/// The statement must return a single `json_row` text column, e.g. for Sqlite:
+/// `SELECT json_object('id', id, 'name', name) AS json_row FROM store WHERE id = $1`
+pub fn read_only_json_query(
+    connection: &StorageConnection,
+    statement: &str,
+    parameters: &[serde_json::Value],
+    limits: &ReadOnlyQueryLimits,
+) -> Result<Vec<serde_json::Value>, ReadOnlyQueryError> {
+    if !is_select_statement(statement) {
+        return Err(ReadOnlyQueryError::NotReadOnly);
+    }
+
+    let statement = statement.trim().trim_end_matches(';');
+    let statement = positional_parameters(statement, parameters.len())?;
+    // Sub query also makes sure only one statement can be run
+    let sql = format!(
+        "SELECT json_row FROM ({}) AS read_only_query LIMIT {}",
+        statement,
+        limits.max_rows + 1
+    );
+
+    let mut query = sql_query(sql).into_boxed::<DBType>();
+    for (index, parameter) in parameters.iter().enumerate() {
+        query = bind_parameter(query, index, parameter)?;
+    }
+
+    let start = Instant::now();
+    let rows = run_read_only(connection, query, limits)?;
+    if start.elapsed() > limits.timeout {
+        return Err(ReadOnlyQueryError::Timeout);
+    }
+    if rows.len() > limits.max_rows {
+        return Err(ReadOnlyQueryError::RowLimitExceeded(limits.max_rows));
+    }
+
+    rows.into_iter()
+        .map(|JsonRawRow { json_row }| {
+            serde_json::from_str(&json_row)
+                .map_err(|err| ReadOnlyQueryError::InvalidRow(format!("{}: {}", err, json_row)))
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "postgres"))]
+fn run_read_only(
+    connection: &StorageConnection,
+    query: BoxedSqlQuery<'_, DBType, diesel::query_builder::SqlQuery>,
+    _limits: &ReadOnlyQueryLimits,
+) -> Result<Vec<JsonRawRow>, ReadOnlyQueryError> {
+    let mut guard = connection.lock();
+    let con = guard.connection();
+
+    con.batch_execute("PRAGMA query_only = ON;")
+        .map_err(map_diesel_error)?;
+    let result = query.load::<JsonRawRow>(con);
+    // Always reset, connection is returned to the pool
+    con.batch_execute("PRAGMA query_only = OFF;")
+        .map_err(map_diesel_error)?;
+
+    result.map_err(map_diesel_error)
+}
+
+#[cfg(feature = "postgres")]
+fn run_read_only(
+    connection: &StorageConnection,
+    query: BoxedSqlQuery<'_, DBType, diesel::query_builder::SqlQuery>,
+    limits: &ReadOnlyQueryLimits,
+) -> Result<Vec<JsonRawRow>, ReadOnlyQueryError> {
+    // SET LOCAL lasts until the end of the top level transaction, the (nested) transaction is
+    // always rolled back to undo it
+    let result = connection.transaction_sync_etc(
+        |connection| {
+            let mut guard = connection.lock();
+            let con = guard.connection();
+            let result = con
+                .batch_execute(&format!(
+                    "SET LOCAL transaction_read_only = on; SET LOCAL statement_timeout = {};",
+                    limits.timeout.as_millis()
+                ))
+                .and_then(|_| query.load::<JsonRawRow>(con));
+            Err::<(), _>(result)
+        },
+        false,
+    );
+
+    match result {
+        Err(crate::TransactionError::Inner(result)) => result.map_err(map_diesel_error),
+        Err(crate::TransactionError::Transaction { msg, level }) => Err(
+            ReadOnlyQueryError::DatabaseError(RepositoryError::TransactionError { msg, level }),
+        ),
+        Ok(_) => unreachable!("read only query transaction is always rolled back"),
+    }
+}
+
+fn map_diesel_error(error: DieselError) -> ReadOnlyQueryError {
+    if let DieselError::DatabaseError(_, info) = &error {
+        let message = info.message();
+        // Postgres: "cannot execute INSERT in a read-only transaction"
+        // Sqlite: "attempt to write a readonly database"
+        if message.contains("read-only transaction") || message.contains("readonly database") {
+            return ReadOnlyQueryError::NotReadOnly;
+        }
+        if message.contains("statement timeout") {
+            return ReadOnlyQueryError::Timeout;
+        }
+    }
+    ReadOnlyQueryError::DatabaseError(error.into())
+}
+
+/// Checks the first keyword (ignoring comments) is SELECT or WITH
+fn is_select_statement(statement: &str) -> bool {
+    let mut remaining = statement.trim_start();
+    loop {
+        if let Some(comment) = remaining.strip_prefix("--") {
+            remaining = comment
+                .split_once('\n')
+                .map(|(_, rest)| rest)
+                .unwrap_or("")
+                .trim_start();
+        } else if let Some(comment) = remaining.strip_prefix("/*") {
+            remaining = comment
+                .split_once("*/")
+                .map(|(_, rest)| rest)
+                .unwrap_or("")
+                .trim_start();
+        } else {
+            break;
+        }
+    }
+
+    let keyword: String = remaining
+        .chars()
+        .take_while(|c| c.is_ascii_alphabetic())
+        .collect();
+    keyword.eq_ignore_ascii_case("select") || keyword.eq_ignore_ascii_case("with")
+}
+
+/// Checks that `$1`..`$n` placeholders match the parameters. Sqlite treats `$1` as a named
+/// parameter (numbered in order of appearance) so they are replaced with `?1`
+fn positional_parameters(
+    statement: &str,
+    parameter_count: usize,
+) -> Result<String, ReadOnlyQueryError> {
+    let placeholders = find_placeholders(statement);
+
+    let mut max_index = 0;
+    for (range, index) in &placeholders {
+        let index: usize = index.parse().unwrap_or(0);
+        if index == 0 || index > parameter_count {
+            return Err(ReadOnlyQueryError::InvalidParameters(format!(
+                "No parameter for {}, {} parameters provided",
+                &statement[range.clone()],
+                parameter_count
+            )));
+        }
+        max_index = max_index.max(index);
+    }
+    if max_index < parameter_count {
+        return Err(ReadOnlyQueryError::InvalidParameters(format!(
+            "{} parameters provided but only {} used",
+            parameter_count, max_index
+        )));
+    }
+
+    if cfg!(feature = "postgres") {
+        return Ok(statement.to_string());
+    }
+
+    let mut result = String::with_capacity(statement.len());
+    let mut end = 0;
+    for (range, index) in placeholders {
+        result.push_str(&statement[end..range.start]);
+        result.push('?');
+        result.push_str(index);
+        end = range.end;
+    }
+    result.push_str(&statement[end..]);
+    Ok(result)
+}
+
+/// Positions and indexes of the `$n` placeholders in the statement. String literals, quoted
+/// identifiers, comments and (Postgres) dollar quoted strings are skipped, e.g. `'$1'` is not a
+/// placeholder
+fn find_placeholders(statement: &str) -> Vec<(Range<usize>, &str)> {
+    let bytes = statement.as_bytes();
+    // Position after the end delimiter, or the end of the statement if it's not terminated
+    let skip_past = |from: usize, end: &str| {
+        statement[from..]
+            .find(end)
+            .map(|position| from + position + end.len())
+            .unwrap_or(statement.len())
+    };
+
+    let mut placeholders = Vec::new();
+    let mut position = 0;
+    while position < bytes.len() {
+        position = match bytes[position] {
+            // Escaped (doubled) quotes are skipped as two adjacent literals
+            b'\'' => skip_past(position + 1, "'"),
+            b'"' => skip_past(position + 1, "\""),
+            b'-' if bytes.get(position + 1) == Some(&b'-') => skip_past(position + 2, "\n"),
+            b'/' if bytes.get(position + 1) == Some(&b'*') => skip_past(position + 2, "*/"),
+            b'$' => {
+                let start = position + 1;
+                let digits = bytes[start..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_digit())
+                    .count();
+                let tag_length = bytes[start..]
+                    .iter()
+                    .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+                    .count();
+                if digits > 0 {
+                    let end = start + digits;
+                    placeholders.push((position..end, &statement[start..end]));
+                    end
+                } else if bytes.get(start + tag_length) == Some(&b'$') {
+                    // Dollar quoted string, e.g. `$$text$$` or `$tag$text$tag$`
+                    let tag = &statement[position..=start + tag_length];
+                    skip_past(start + tag_length + 1, tag)
+                } else {
+                    start
+                }
+            }
+            _ => position + 1,
+        };
+    }
+    placeholders
+}
+
+fn bind_parameter<'a>(
+    query: BoxedSqlQuery<'a, DBType, diesel::query_builder::SqlQuery>,
+    index: usize,
+    parameter: &serde_json::Value,
+) -> Result<BoxedSqlQuery<'a, DBType, diesel::query_builder::SqlQuery>, ReadOnlyQueryError> {
+    use serde_json::Value;
+    let query = match parameter {
+        Value::Null => query.bind::<Nullable<Text>, _>(None::<String>),
+        Value::Bool(value) => query.bind::<Bool, _>(*value),
+        Value::Number(number) => match number.as_i64() {
+            Some(value) => query.bind::<BigInt, _>(value),
+            None => query.bind::<Double, _>(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(value) => query.bind::<Text, _>(value.clone()),
+        Value::Array(_) | Value::Object(_) => {
+            return Err(ReadOnlyQueryError::InvalidParameters(format!(
+                "Parameter ${} must be null, a boolean, a number or a string",
+                index + 1
+            )))
+        }
+    };
+    Ok(query)
+}
+
+impl From<RepositoryError> for ReadOnlyQueryError {
+    fn from(error: RepositoryError) -> Self {
+        ReadOnlyQueryError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use diesel::connection::SimpleConnection;
+    use serde_json::json;
+
+    use crate::{mock::MockDataInserts, test_db, StoreRowRepository};
+
+    use super::{
+        positional_parameters, read_only_json_query, ReadOnlyQueryError, ReadOnlyQueryLimits,
+    };
+
+    #[cfg(feature = "postgres")]
+    const STORE_JSON: &str = "json_build_object('id', id, 'code', code)::text";
+    #[cfg(not(feature = "postgres"))]
+    const STORE_JSON: &str = "json_object('id', id, 'code', code)";
+
+    #[actix_rt::test]
+    async fn test_read_only_json_query() {
+        let (_, connection, _, _) = test_db::setup_all(
+            "test_read_only_json_query",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+        let limits = ReadOnlyQueryLimits {
+            max_rows: 2,
+            timeout: Duration::from_secs(10),
+        };
+
+        // parameters
+        let result = read_only_json_query(
+            &connection,
+            &format!("SELECT {STORE_JSON} AS json_row FROM store WHERE id = $1 AND code = $2;"),
+            &[json!("store_a"), json!("code")],
+            &limits,
+        );
+        assert_eq!(result, Ok(vec![json!({"id": "store_a", "code": "code"})]));
+
+        // placeholders in string literals are not parameters
+        let result = read_only_json_query(
+            &connection,
+            &format!("SELECT {STORE_JSON} AS json_row FROM store WHERE id = $1 AND code <> '$2'"),
+            &[json!("store_a")],
+            &limits,
+        );
+        assert_eq!(result, Ok(vec![json!({"id": "store_a", "code": "code"})]));
+
+        // parameters not matching placeholders
+        let result = read_only_json_query(
+            &connection,
+            &format!("SELECT {STORE_JSON} AS json_row FROM store WHERE id = $2"),
+            &[json!("store_a")],
+            &limits,
+        );
+        assert!(matches!(
+            result,
+            Err(ReadOnlyQueryError::InvalidParameters(_))
+        ));
+        let result = read_only_json_query(
+            &connection,
+            &format!("SELECT {STORE_JSON} AS json_row FROM store WHERE id = $1"),
+            &[json!("store_a"), json!("unused")],
+            &limits,
+        );
+        assert!(matches!(
+            result,
+            Err(ReadOnlyQueryError::InvalidParameters(_))
+        ));
+
+        // row limit
+        let result = read_only_json_query(
+            &connection,
+            &format!("SELECT {STORE_JSON} AS json_row FROM store"),
+            &[],
+            &limits,
+        );
+        assert_eq!(result, Err(ReadOnlyQueryError::RowLimitExceeded(2)));
+
+        // writes
+        let result = read_only_json_query(
+            &connection,
+            "DELETE FROM store WHERE id = $1",
+            &[json!("store_a")],
+            &limits,
+        );
+        assert_eq!(result, Err(ReadOnlyQueryError::NotReadOnly));
+        let result = read_only_json_query(
+            &connection,
+            "/* comment */ SELECT 'x' AS json_row; DELETE FROM store",
+            &[],
+            &limits,
+        );
+        assert!(result.is_err());
+        assert!(StoreRowRepository::new(&connection)
+            .find_one_by_id("store_a")
+            .unwrap()
+            .is_some());
+
+        // connection can still be written to
+        connection
+            .lock()
+            .connection()
+            .batch_execute("UPDATE store SET code = 'updated' WHERE id = 'store_a'")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_positional_parameters() {
+        let quoted = r#"SELECT '$1', 'it''s $1', "$1", $$ $1 $$, $t$ $1 $t$ /* $1 */ -- $1"#;
+        let statement = format!("{quoted}\nFROM t WHERE a = $1 AND b = $2");
+        let expected = match cfg!(feature = "postgres") {
+            true => statement.clone(),
+            false => format!("{quoted}\nFROM t WHERE a = ?1 AND b = ?2"),
+        };
+        assert_eq!(positional_parameters(&statement, 2), Ok(expected));
+        assert!(matches!(
+            positional_parameters(quoted, 1),
+            Err(ReadOnlyQueryError::InvalidParameters(_))
+        ));
+    }
+}
@@ -0,0 +1,143 @@
+use super::{
+    report_row::report, report_schedule_row::report_schedule::dsl as report_schedule_dsl,
+    store_row::store, StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, Delete, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    report_schedule (id) {
+        id -> Text,
+        report_id -> Text,
+        store_id -> Text,
+        user_id -> Text,
+        arguments -> Nullable<Text>,
+        format -> crate::db_diesel::report_schedule_row::ReportScheduleFormatMapping,
+        schedule -> Text,
+        output_directory -> Text,
+        is_active -> Bool,
+        created_datetime -> Timestamp,
+    }
+}
+
+joinable!(report_schedule -> report (report_id));
+joinable!(report_schedule -> store (store_id));
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum ReportScheduleFormat {
+    #[default]
+    Pdf,
+    Html,
+    Excel,
+    Csv,
+}
+
+/// Report that is generated on a cron like `schedule` and written to `output_directory`
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = report_schedule)]
+pub struct ReportScheduleRow {
+    pub id: String,
+    pub report_id: String,
+    pub store_id: String,
+    /// User the report is generated as (report queries are run with this user's permissions)
+    pub user_id: String,
+    /// Report arguments (JSON)
+    pub arguments: Option<String>,
+    pub format: ReportScheduleFormat,
+    /// Cron expression: minute hour day-of-month month day-of-week
+    pub schedule: String,
+    pub output_directory: String,
+    pub is_active: bool,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct ReportScheduleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ReportScheduleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ReportScheduleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &ReportScheduleRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(report_schedule_dsl::report_schedule)
+            .values(row)
+            .on_conflict(report_schedule_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<ReportScheduleRow>, RepositoryError> {
+        let result = report_schedule_dsl::report_schedule
+            .filter(report_schedule_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<ReportScheduleRow>, RepositoryError> {
+        let result = report_schedule_dsl::report_schedule
+            .filter(report_schedule_dsl::store_id.eq(store_id))
+            .order(report_schedule_dsl::created_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_active(&self) -> Result<Vec<ReportScheduleRow>, RepositoryError> {
+        let result = report_schedule_dsl::report_schedule
+            .filter(report_schedule_dsl::is_active.eq(true))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(report_schedule_dsl::report_schedule.filter(report_schedule_dsl::id.eq(id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportScheduleRowDelete(pub String);
+impl Delete for ReportScheduleRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        ReportScheduleRowRepository::new(con).delete(&self.0)?;
+        Ok(None) // Table not in Changelog
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            ReportScheduleRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for ReportScheduleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        ReportScheduleRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            ReportScheduleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,119 @@
+use super::{
+    report_schedule_row::report_schedule,
+    report_schedule_run_row::report_schedule_run::dsl as report_schedule_run_dsl,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    report_schedule_run (id) {
+        id -> Text,
+        report_schedule_id -> Text,
+        scheduled_datetime -> Timestamp,
+        started_datetime -> Timestamp,
+        finished_datetime -> Nullable<Timestamp>,
+        status -> crate::db_diesel::report_schedule_run_row::ReportScheduleRunStatusMapping,
+        output_path -> Nullable<Text>,
+        error -> Nullable<Text>,
+    }
+}
+
+joinable!(report_schedule_run -> report_schedule (report_schedule_id));
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum ReportScheduleRunStatus {
+    #[default]
+    Running,
+    Success,
+    Error,
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = report_schedule_run)]
+pub struct ReportScheduleRunRow {
+    pub id: String,
+    pub report_schedule_id: String,
+    /// Time the run was due according to the schedule
+    pub scheduled_datetime: NaiveDateTime,
+    pub started_datetime: NaiveDateTime,
+    pub finished_datetime: Option<NaiveDateTime>,
+    pub status: ReportScheduleRunStatus,
+    /// Path of the generated file in the output directory
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+}
+
+pub struct ReportScheduleRunRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ReportScheduleRunRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ReportScheduleRunRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &ReportScheduleRunRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(report_schedule_run_dsl::report_schedule_run)
+            .values(row)
+            .on_conflict(report_schedule_run_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<ReportScheduleRunRow>, RepositoryError> {
+        let result = report_schedule_run_dsl::report_schedule_run
+            .filter(report_schedule_run_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Runs of a schedule, latest first
+    pub fn find_many_by_schedule_id(
+        &self,
+        report_schedule_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<ReportScheduleRunRow>, RepositoryError> {
+        let mut query = report_schedule_run_dsl::report_schedule_run
+            .filter(report_schedule_run_dsl::report_schedule_id.eq(report_schedule_id))
+            .order(report_schedule_run_dsl::scheduled_datetime.desc())
+            .into_boxed();
+        if let Some(limit) = limit {
+            query = query.limit(limit as i64);
+        }
+        let result = query.load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_latest_by_schedule_id(
+        &self,
+        report_schedule_id: &str,
+    ) -> Result<Option<ReportScheduleRunRow>, RepositoryError> {
+        Ok(self
+            .find_many_by_schedule_id(report_schedule_id, Some(1))?
+            .pop())
+    }
+
+    pub fn delete_by_schedule_id(&self, report_schedule_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            report_schedule_run_dsl::report_schedule_run
+                .filter(report_schedule_run_dsl::report_schedule_id.eq(report_schedule_id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
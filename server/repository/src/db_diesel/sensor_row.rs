@@ -35,6 +35,9 @@ pub enum SensorType {
     BlueMaestro,
     Laird,
     Berlinger,
+    LogTag,
+    ThirtyDayRecorder,
+    Generic,
 }
 
 // TODO put this somewhere more sensible
@@ -44,6 +47,9 @@ pub fn get_sensor_type(serial: &str) -> SensorType {
         Some("BLUE_MAESTRO") => SensorType::BlueMaestro,
         Some("LAIRD") => SensorType::Laird,
         Some("BERLINGER") => SensorType::Berlinger,
+        Some("LOG_TAG") => SensorType::LogTag,
+        Some("THIRTY_DAY_RECORDER") => SensorType::ThirtyDayRecorder,
+        Some("GENERIC") => SensorType::Generic,
         _ => SensorType::BlueMaestro,
     }
 }
@@ -0,0 +1,112 @@
+use super::{
+    item_link_row::item_link, name_link_row::name_link,
+    shelf_life_rule_row::shelf_life_rule::dsl as shelf_life_rule_dsl, store_row::store,
+    StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, Delete, Upsert};
+
+use diesel::prelude::*;
+
+table! {
+    shelf_life_rule (id) {
+        id -> Text,
+        store_id -> Text,
+        name_link_id -> Nullable<Text>,
+        item_link_id -> Nullable<Text>,
+        minimum_remaining_days -> Integer,
+    }
+}
+
+joinable!(shelf_life_rule -> store (store_id));
+joinable!(shelf_life_rule -> name_link (name_link_id));
+joinable!(shelf_life_rule -> item_link (item_link_id));
+
+/// Minimum remaining shelf life stock must have to be allocated to a customer in a store.
+/// Rule applies to all customers if `name_link_id` is not set, and to all items if `item_link_id`
+/// is not set
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = shelf_life_rule)]
+pub struct ShelfLifeRuleRow {
+    pub id: String,
+    pub store_id: String,
+    pub name_link_id: Option<String>,
+    pub item_link_id: Option<String>,
+    pub minimum_remaining_days: i32,
+}
+
+pub struct ShelfLifeRuleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ShelfLifeRuleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ShelfLifeRuleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &ShelfLifeRuleRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(shelf_life_rule_dsl::shelf_life_rule)
+            .values(row)
+            .on_conflict(shelf_life_rule_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<ShelfLifeRuleRow>, RepositoryError> {
+        let result = shelf_life_rule_dsl::shelf_life_rule
+            .filter(shelf_life_rule_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<ShelfLifeRuleRow>, RepositoryError> {
+        let result = shelf_life_rule_dsl::shelf_life_rule
+            .filter(shelf_life_rule_dsl::store_id.eq(store_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(shelf_life_rule_dsl::shelf_life_rule.filter(shelf_life_rule_dsl::id.eq(id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShelfLifeRuleRowDelete(pub String);
+impl Delete for ShelfLifeRuleRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        ShelfLifeRuleRowRepository::new(con).delete(&self.0)?;
+        Ok(None) // Table not in Changelog
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            ShelfLifeRuleRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for ShelfLifeRuleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        ShelfLifeRuleRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            ShelfLifeRuleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -7,6 +7,8 @@ use crate::{db_diesel::barcode_row::barcode, repository_error::RepositoryError,
 use crate::{ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType};
 
 use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 
 use chrono::NaiveDate;
 
@@ -28,6 +30,7 @@ table! {
         supplier_link_id -> Nullable<Text>,
         barcode_id -> Nullable<Text>,
         item_variant_id -> Nullable<Text>,
+        vvm_status -> Nullable<crate::db_diesel::stock_line_row::VvmStatusMapping>,
     }
 }
 
@@ -39,6 +42,23 @@ joinable!(stock_line -> barcode (barcode_id));
 allow_tables_to_appear_in_same_query!(stock_line, item_link);
 allow_tables_to_appear_in_same_query!(stock_line, name_link);
 
+/// Vaccine vial monitor stage, stock past the discard point (stage 3 or 4) must not be used
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum VvmStatus {
+    Stage1,
+    Stage2,
+    Stage3,
+    Stage4,
+}
+
+impl VvmStatus {
+    pub fn is_failed(&self) -> bool {
+        matches!(self, VvmStatus::Stage3 | VvmStatus::Stage4)
+    }
+}
+
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
 #[diesel(treat_none_as_null = true)]
 #[diesel(table_name = stock_line)]
@@ -59,6 +79,7 @@ pub struct StockLineRow {
     pub supplier_link_id: Option<String>,
     pub barcode_id: Option<String>,
     pub item_variant_id: Option<String>,
+    pub vvm_status: Option<VvmStatus>,
 }
 
 pub struct StockLineRowRepository<'a> {
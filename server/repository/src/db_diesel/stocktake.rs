@@ -13,6 +13,7 @@ use crate::{
 };
 
 use diesel::{dsl::IntoBoxed, prelude::*};
+use util::inline_init;
 
 #[derive(Clone, Default)]
 pub struct StocktakeFilter {
@@ -209,3 +210,9 @@ impl<'a> StocktakeRepository<'a> {
             .optional()?)
     }
 }
+
+impl StocktakeStatus {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+}
@@ -0,0 +1,107 @@
+use super::{
+    stocktake_blind_count_row::stocktake_blind_count::dsl as stocktake_blind_count_dsl,
+    StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    stocktake_blind_count (id) {
+        id -> Text,
+        store_id -> Text,
+        recount_tolerance_percentage -> Nullable<Double>,
+        submitted_datetime -> Nullable<Timestamp>,
+        submitted_by_user_id -> Nullable<Text>,
+    }
+}
+
+/// Stocktake counted in blind count mode, snapshot quantities are hidden from users without the
+/// stocktake supervise permission until the count is submitted.
+/// Blind counts are local to the site the stocktake is counted on
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = stocktake_blind_count)]
+pub struct StocktakeBlindCountRow {
+    /// Same as the stocktake id
+    pub id: String,
+    pub store_id: String,
+    /// Counted lines with a variance above this percentage of the snapshot need to be recounted,
+    /// None if recounts are disabled
+    pub recount_tolerance_percentage: Option<f64>,
+    pub submitted_datetime: Option<NaiveDateTime>,
+    pub submitted_by_user_id: Option<String>,
+}
+
+impl StocktakeBlindCountRow {
+    pub fn is_submitted(&self) -> bool {
+        self.submitted_datetime.is_some()
+    }
+}
+
+pub struct StocktakeBlindCountRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StocktakeBlindCountRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StocktakeBlindCountRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &StocktakeBlindCountRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(stocktake_blind_count_dsl::stocktake_blind_count)
+            .values(row)
+            .on_conflict(stocktake_blind_count_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<StocktakeBlindCountRow>, RepositoryError> {
+        let result = stocktake_blind_count_dsl::stocktake_blind_count
+            .filter(stocktake_blind_count_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_ids(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<StocktakeBlindCountRow>, RepositoryError> {
+        let result = stocktake_blind_count_dsl::stocktake_blind_count
+            .filter(stocktake_blind_count_dsl::id.eq_any(ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            stocktake_blind_count_dsl::stocktake_blind_count
+                .filter(stocktake_blind_count_dsl::id.eq(id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for StocktakeBlindCountRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        StocktakeBlindCountRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            StocktakeBlindCountRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,103 @@
+use super::{
+    stocktake_line_recount_row::stocktake_line_recount::dsl as stocktake_line_recount_dsl,
+    StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    stocktake_line_recount (id) {
+        id -> Text,
+        stocktake_id -> Text,
+        stocktake_line_id -> Text,
+        first_counted_number_of_packs -> Double,
+        snapshot_number_of_packs -> Double,
+        requested_datetime -> Timestamp,
+        recounted_datetime -> Nullable<Timestamp>,
+    }
+}
+
+/// Recount of a blind count stocktake line whose first count had a variance above the
+/// tolerance, the recounted number of packs is the counted number of packs of the line
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = stocktake_line_recount)]
+pub struct StocktakeLineRecountRow {
+    pub id: String,
+    pub stocktake_id: String,
+    pub stocktake_line_id: String,
+    pub first_counted_number_of_packs: f64,
+    pub snapshot_number_of_packs: f64,
+    pub requested_datetime: NaiveDateTime,
+    /// Set when the count with the recounted line is submitted
+    pub recounted_datetime: Option<NaiveDateTime>,
+}
+
+pub struct StocktakeLineRecountRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StocktakeLineRecountRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StocktakeLineRecountRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &StocktakeLineRecountRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(stocktake_line_recount_dsl::stocktake_line_recount)
+            .values(row)
+            .on_conflict(stocktake_line_recount_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<StocktakeLineRecountRow>, RepositoryError> {
+        let result = stocktake_line_recount_dsl::stocktake_line_recount
+            .filter(stocktake_line_recount_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_stocktake_ids(
+        &self,
+        stocktake_ids: &[String],
+    ) -> Result<Vec<StocktakeLineRecountRow>, RepositoryError> {
+        let result = stocktake_line_recount_dsl::stocktake_line_recount
+            .filter(stocktake_line_recount_dsl::stocktake_id.eq_any(stocktake_ids))
+            .order(stocktake_line_recount_dsl::requested_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete_by_stocktake_id(&self, stocktake_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            stocktake_line_recount_dsl::stocktake_line_recount
+                .filter(stocktake_line_recount_dsl::stocktake_id.eq(stocktake_id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for StocktakeLineRecountRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        StocktakeLineRecountRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            StocktakeLineRecountRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,106 @@
+use super::{
+    stocktake_variance_approval_row::stocktake_variance_approval::dsl as stocktake_variance_approval_dsl,
+    StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    stocktake_variance_approval (id) {
+        id -> Text,
+        stocktake_id -> Text,
+        stocktake_line_id -> Text,
+        counted_number_of_packs -> Double,
+        approved_by_user_id -> Text,
+        approved_datetime -> Timestamp,
+        comment -> Nullable<Text>,
+    }
+}
+
+/// Approval of the variance of a stocktake line that exceeds the store's variance thresholds.
+/// Approvals are local to the site the stocktake is finalised on, the activity log records them
+/// for other sites
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = stocktake_variance_approval)]
+pub struct StocktakeVarianceApprovalRow {
+    pub id: String,
+    pub stocktake_id: String,
+    pub stocktake_line_id: String,
+    /// Counted number of packs that was approved, the approval no longer applies if the count
+    /// is changed
+    pub counted_number_of_packs: f64,
+    pub approved_by_user_id: String,
+    pub approved_datetime: NaiveDateTime,
+    pub comment: Option<String>,
+}
+
+pub struct StocktakeVarianceApprovalRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StocktakeVarianceApprovalRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StocktakeVarianceApprovalRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &StocktakeVarianceApprovalRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(stocktake_variance_approval_dsl::stocktake_variance_approval)
+            .values(row)
+            .on_conflict(stocktake_variance_approval_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<StocktakeVarianceApprovalRow>, RepositoryError> {
+        let result = stocktake_variance_approval_dsl::stocktake_variance_approval
+            .filter(stocktake_variance_approval_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Approvals of a stocktake, latest first
+    pub fn find_many_by_stocktake_id(
+        &self,
+        stocktake_id: &str,
+    ) -> Result<Vec<StocktakeVarianceApprovalRow>, RepositoryError> {
+        let result = stocktake_variance_approval_dsl::stocktake_variance_approval
+            .filter(stocktake_variance_approval_dsl::stocktake_id.eq(stocktake_id))
+            .order(stocktake_variance_approval_dsl::approved_datetime.desc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete_by_stocktake_id(&self, stocktake_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            stocktake_variance_approval_dsl::stocktake_variance_approval
+                .filter(stocktake_variance_approval_dsl::stocktake_id.eq(stocktake_id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for StocktakeVarianceApprovalRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        StocktakeVarianceApprovalRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            StocktakeVarianceApprovalRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -27,6 +27,13 @@ table! {
         months_items_expire -> Double,
         stocktake_frequency -> Double,
         extra_fields_in_requisition -> Bool,
+        allocation_strategy -> crate::db_diesel::store_preference_row::AllocationStrategyMapping,
+        allocation_preferred_location_id -> Nullable<Text>,
+        forecast_method -> crate::db_diesel::store_preference_row::ForecastMethodMapping,
+        stocktake_variance_value_threshold -> Double,
+        stocktake_variance_percentage_threshold -> Double,
+        patient_code_scheme -> crate::db_diesel::store_preference_row::PatientCodeSchemeMapping,
+        patient_code_prefix -> Nullable<Text>,
     }
 }
 
@@ -43,7 +50,55 @@ pub enum StorePreferenceType {
     StorePreferences,
 }
 
+/// Order in which stock lines are used when allocating outbound shipment unallocated lines.
+/// Stock lines are always ordered by expiry date (FEFO) first, this order is then used to break
+/// ties between stock lines considered equal by the strategy
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AllocationStrategy {
+    /// First expiry first out, stock lines without an expiry date are used last
+    #[default]
+    Fefo,
+    /// First in first out, by the date stock line was received in store
+    Fifo,
+    /// Stock lines in `allocation_preferred_location_id` are used first
+    PreferLocation,
+    /// Stock lines that can be issued in whole packs are used first, then the ones leaving the
+    /// smallest remainder of a broken pack
+    AvoidBreakingPacks,
+    /// Use as few stock lines as possible, a single stock line that can fulfil the remaining
+    /// quantity is used if one exists, otherwise the stock line with the largest available quantity
+    MinimiseBatches,
+}
+
+/// Method used to forecast monthly consumption, e.g. for request requisition suggested quantities
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum ForecastMethod {
+    /// Average monthly consumption over the look back period
+    #[default]
+    AverageMonthlyConsumption,
+    /// Average of monthly consumption adjusted for stock out days, recent months weigh more
+    WeightedMovingAverage,
+    /// Consumption adjusted for stock out days in the same month last year
+    SeasonalNaive,
+}
+
+/// How codes of patients created in the store are generated and validated
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum PatientCodeScheme {
+    /// Codes are entered by the user and not validated
+    #[default]
+    Manual,
+    /// `patient_code_prefix` (or the site id) followed by a sequence and a Luhn check digit
+    PrefixSequenceLuhn,
+    /// `patient_code_prefix` (or the site id) followed by a sequence and a Verhoeff check digit
+    PrefixSequenceVerhoeff,
+}
+
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
 #[diesel(table_name = store_preference)]
 pub struct StorePreferenceRow {
     pub id: String, // store_id
@@ -62,6 +117,19 @@ pub struct StorePreferenceRow {
     pub months_items_expire: f64,
     pub stocktake_frequency: f64,
     pub extra_fields_in_requisition: bool,
+    pub allocation_strategy: AllocationStrategy,
+    pub allocation_preferred_location_id: Option<String>,
+    pub forecast_method: ForecastMethod,
+    /// Stocktake lines with an adjustment worth more than this (at cost price) need to be
+    /// approved before finalising, 0 to disable
+    pub stocktake_variance_value_threshold: f64,
+    /// Stocktake lines with an adjustment of more than this percentage of the snapshot quantity
+    /// need to be approved before finalising, 0 to disable
+    pub stocktake_variance_percentage_threshold: f64,
+    pub patient_code_scheme: PatientCodeScheme,
+    /// Digits prepended to generated patient codes, needs to be unique per site. Defaults to the
+    /// site id when not set
+    pub patient_code_prefix: Option<String>,
 }
 
 pub struct StorePreferenceRowRepository<'a> {
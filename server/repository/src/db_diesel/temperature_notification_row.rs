@@ -0,0 +1,134 @@
+use super::{
+    temperature_breach_row::temperature_breach,
+    temperature_notification_row::temperature_notification::dsl as temperature_notification_dsl,
+    temperature_notification_rule_row::temperature_notification_rule, StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    temperature_notification (id) {
+        id -> Text,
+        rule_id -> Text,
+        temperature_breach_id -> Text,
+        level -> crate::db_diesel::temperature_notification_row::TemperatureNotificationLevelMapping,
+        status -> crate::db_diesel::temperature_notification_row::TemperatureNotificationStatusMapping,
+        attempts -> Integer,
+        created_datetime -> Timestamp,
+        sent_datetime -> Nullable<Timestamp>,
+        error -> Nullable<Text>,
+    }
+}
+
+joinable!(temperature_notification -> temperature_notification_rule (rule_id));
+joinable!(temperature_notification -> temperature_breach (temperature_breach_id));
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, Hash)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum TemperatureNotificationLevel {
+    #[default]
+    Initial,
+    /// Sent when the breach is still unacknowledged after the rule's escalation period
+    Escalation,
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum TemperatureNotificationStatus {
+    #[default]
+    Sent,
+    /// Sending failed, it's retried until the maximum number of attempts
+    Failed,
+}
+
+/// Notification of a temperature breach (one per rule, breach and level)
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = temperature_notification)]
+pub struct TemperatureNotificationRow {
+    pub id: String,
+    pub rule_id: String,
+    pub temperature_breach_id: String,
+    pub level: TemperatureNotificationLevel,
+    pub status: TemperatureNotificationStatus,
+    pub attempts: i32,
+    pub created_datetime: NaiveDateTime,
+    pub sent_datetime: Option<NaiveDateTime>,
+    /// Error of the last failed attempt
+    pub error: Option<String>,
+}
+
+pub struct TemperatureNotificationRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureNotificationRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureNotificationRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &TemperatureNotificationRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(temperature_notification_dsl::temperature_notification)
+            .values(row)
+            .on_conflict(temperature_notification_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<TemperatureNotificationRow>, RepositoryError> {
+        let result = temperature_notification_dsl::temperature_notification
+            .filter(temperature_notification_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_breach_ids(
+        &self,
+        temperature_breach_ids: &[String],
+    ) -> Result<Vec<TemperatureNotificationRow>, RepositoryError> {
+        let result = temperature_notification_dsl::temperature_notification
+            .filter(
+                temperature_notification_dsl::temperature_breach_id.eq_any(temperature_breach_ids),
+            )
+            .order(temperature_notification_dsl::created_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete_by_rule_id(&self, rule_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            temperature_notification_dsl::temperature_notification
+                .filter(temperature_notification_dsl::rule_id.eq(rule_id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for TemperatureNotificationRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        TemperatureNotificationRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            TemperatureNotificationRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,154 @@
+use super::{
+    location_row::location, store_row::store,
+    temperature_notification_rule_row::temperature_notification_rule::dsl as temperature_notification_rule_dsl,
+    StorageConnection, TemperatureBreachType,
+};
+
+use crate::{repository_error::RepositoryError, Delete, Upsert};
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    temperature_notification_rule (id) {
+        id -> Text,
+        store_id -> Text,
+        location_id -> Nullable<Text>,
+        breach_type -> Nullable<crate::db_diesel::temperature_breach_row::TemperatureBreachTypeMapping>,
+        minimum_duration_milliseconds -> Integer,
+        channel -> crate::db_diesel::temperature_notification_rule_row::TemperatureNotificationChannelMapping,
+        recipient -> Text,
+        escalation_after_milliseconds -> Nullable<Integer>,
+        escalation_recipient -> Nullable<Text>,
+        is_active -> Bool,
+    }
+}
+
+joinable!(temperature_notification_rule -> store (store_id));
+joinable!(temperature_notification_rule -> location (location_id));
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum TemperatureNotificationChannel {
+    /// Recipient is an email address, sent through the SMTP server in the mail settings
+    #[default]
+    Email,
+    /// Recipient is a URL the notification is posted to as JSON
+    Webhook,
+    /// Recipient is a file path notifications are appended to, or empty to only log them
+    Log,
+}
+
+/// Who to notify about temperature breaches of a store. Rules are local to the site and are not
+/// synced
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = temperature_notification_rule)]
+pub struct TemperatureNotificationRuleRow {
+    pub id: String,
+    pub store_id: String,
+    /// Only breaches at this location, all locations if None
+    pub location_id: Option<String>,
+    /// Only breaches of this type, all types if None
+    pub breach_type: Option<TemperatureBreachType>,
+    /// Breaches shorter than this are not notified
+    pub minimum_duration_milliseconds: i32,
+    pub channel: TemperatureNotificationChannel,
+    pub recipient: String,
+    /// Notify again if the breach is still unacknowledged this long after the first notification
+    pub escalation_after_milliseconds: Option<i32>,
+    /// Recipient of the escalation, `recipient` if None
+    pub escalation_recipient: Option<String>,
+    pub is_active: bool,
+}
+
+pub struct TemperatureNotificationRuleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TemperatureNotificationRuleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TemperatureNotificationRuleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &TemperatureNotificationRuleRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(temperature_notification_rule_dsl::temperature_notification_rule)
+            .values(row)
+            .on_conflict(temperature_notification_rule_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<TemperatureNotificationRuleRow>, RepositoryError> {
+        let result = temperature_notification_rule_dsl::temperature_notification_rule
+            .filter(temperature_notification_rule_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<TemperatureNotificationRuleRow>, RepositoryError> {
+        let result = temperature_notification_rule_dsl::temperature_notification_rule
+            .filter(temperature_notification_rule_dsl::store_id.eq(store_id))
+            .order(temperature_notification_rule_dsl::id.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_active(&self) -> Result<Vec<TemperatureNotificationRuleRow>, RepositoryError> {
+        let result = temperature_notification_rule_dsl::temperature_notification_rule
+            .filter(temperature_notification_rule_dsl::is_active.eq(true))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            temperature_notification_rule_dsl::temperature_notification_rule
+                .filter(temperature_notification_rule_dsl::id.eq(id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TemperatureNotificationRuleRowDelete(pub String);
+impl Delete for TemperatureNotificationRuleRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        TemperatureNotificationRuleRowRepository::new(con).delete(&self.0)?;
+        Ok(None) // Table not in Changelog
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            TemperatureNotificationRuleRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for TemperatureNotificationRuleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        TemperatureNotificationRuleRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            TemperatureNotificationRuleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
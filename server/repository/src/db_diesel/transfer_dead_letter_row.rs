@@ -0,0 +1,143 @@
+use super::{
+    transfer_dead_letter_row::transfer_dead_letter::dsl as transfer_dead_letter_dsl,
+    StorageConnection,
+};
+
+use crate::{repository_error::RepositoryError, RowActionType};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    transfer_dead_letter (id) {
+        id -> Text,
+        processor_type -> crate::db_diesel::transfer_dead_letter_row::TransferProcessorTypeMapping,
+        record_id -> Text,
+        row_action -> crate::db_diesel::changelog::RowActionTypeMapping,
+        changelog_cursor -> BigInt,
+        name_id -> Nullable<Text>,
+        store_id -> Nullable<Text>,
+        error -> Text,
+        attempt_count -> Integer,
+        first_attempt_datetime -> Timestamp,
+        last_attempt_datetime -> Timestamp,
+        retry_requested -> Bool,
+        next_retry_datetime -> Nullable<Timestamp>,
+        resolved_datetime -> Nullable<Timestamp>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum TransferProcessorType {
+    #[default]
+    RequisitionTransfer,
+    InvoiceTransfer,
+}
+
+/// Changelog record a transfer processor failed to process, the changelog fields are kept so the
+/// record can be processed again
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = transfer_dead_letter)]
+pub struct TransferDeadLetterRow {
+    pub id: String,
+    pub processor_type: TransferProcessorType,
+    pub record_id: String,
+    pub row_action: RowActionType,
+    /// Cursor of the (latest) changelog that failed
+    pub changelog_cursor: i64,
+    pub name_id: Option<String>,
+    pub store_id: Option<String>,
+    /// Latest error
+    pub error: String,
+    pub attempt_count: i32,
+    pub first_attempt_datetime: NaiveDateTime,
+    pub last_attempt_datetime: NaiveDateTime,
+    /// Record will be processed again on the next run of the transfer processor
+    pub retry_requested: bool,
+    /// Record is retried automatically from this time, not set once automatic retries are
+    /// exhausted (record is then only processed again for a new change or a requested retry)
+    pub next_retry_datetime: Option<NaiveDateTime>,
+    pub resolved_datetime: Option<NaiveDateTime>,
+}
+
+pub struct TransferDeadLetterRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TransferDeadLetterRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TransferDeadLetterRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &TransferDeadLetterRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(transfer_dead_letter_dsl::transfer_dead_letter)
+            .values(row)
+            .on_conflict(transfer_dead_letter_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<TransferDeadLetterRow>, RepositoryError> {
+        let result = transfer_dead_letter_dsl::transfer_dead_letter
+            .filter(transfer_dead_letter_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_unresolved_by_record_id(
+        &self,
+        processor_type: TransferProcessorType,
+        record_id: &str,
+    ) -> Result<Option<TransferDeadLetterRow>, RepositoryError> {
+        let result = transfer_dead_letter_dsl::transfer_dead_letter
+            .filter(transfer_dead_letter_dsl::processor_type.eq(processor_type))
+            .filter(transfer_dead_letter_dsl::record_id.eq(record_id))
+            .filter(transfer_dead_letter_dsl::resolved_datetime.is_null())
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Unresolved dead letters that were requested to be retried or are due for an automatic retry
+    pub fn find_due_for_retry(
+        &self,
+        processor_type: TransferProcessorType,
+        now: NaiveDateTime,
+    ) -> Result<Vec<TransferDeadLetterRow>, RepositoryError> {
+        let result = transfer_dead_letter_dsl::transfer_dead_letter
+            .filter(transfer_dead_letter_dsl::processor_type.eq(processor_type))
+            .filter(
+                transfer_dead_letter_dsl::retry_requested
+                    .eq(true)
+                    .or(transfer_dead_letter_dsl::next_retry_datetime.le(now)),
+            )
+            .filter(transfer_dead_letter_dsl::resolved_datetime.is_null())
+            .order(transfer_dead_letter_dsl::changelog_cursor.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Most recently failed first
+    pub fn find_many(
+        &self,
+        include_resolved: bool,
+    ) -> Result<Vec<TransferDeadLetterRow>, RepositoryError> {
+        let mut query = transfer_dead_letter_dsl::transfer_dead_letter.into_boxed();
+        if !include_resolved {
+            query = query.filter(transfer_dead_letter_dsl::resolved_datetime.is_null());
+        }
+        let result = query
+            .order(transfer_dead_letter_dsl::last_attempt_datetime.desc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
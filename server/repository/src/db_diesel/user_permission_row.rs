@@ -41,6 +41,10 @@ pub enum PermissionType {
     // stocktake
     StocktakeQuery,
     StocktakeMutate,
+    /// Approve stocktake line variances above the store's thresholds
+    StocktakeVarianceApprove,
+    /// See snapshot quantities of blind count stocktakes before the count is submitted
+    StocktakeSupervise,
     // inventory adjustment
     InventoryAdjustmentMutate,
     // requisition
@@ -72,6 +76,8 @@ pub enum PermissionType {
     // items
     ItemMutate,
     ItemNamesCodesAndUnitsMutate,
+    /// Create and edit the store's customer price lists
+    PriceListMutate,
     PatientQuery,
     PatientMutate,
     // Document
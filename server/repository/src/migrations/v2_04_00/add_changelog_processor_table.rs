@@ -0,0 +1,29 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_changelog_processor_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE changelog_processor (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    cursor BIGINT NOT NULL DEFAULT 0,
+                    last_run_datetime {DATETIME},
+                    last_success_datetime {DATETIME},
+                    error_count INTEGER NOT NULL DEFAULT 0,
+                    last_error TEXT,
+                    last_error_datetime {DATETIME},
+                    next_retry_datetime {DATETIME}
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_cycle_count_plan_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE cycle_count_plan (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    user_id TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    master_list_id TEXT,
+                    a_class_percentage {DOUBLE} NOT NULL DEFAULT 80,
+                    b_class_percentage {DOUBLE} NOT NULL DEFAULT 95,
+                    a_class_interval_days INTEGER NOT NULL DEFAULT 30,
+                    b_class_interval_days INTEGER NOT NULL DEFAULT 90,
+                    c_class_interval_days INTEGER NOT NULL DEFAULT 365,
+                    run_interval_days INTEGER NOT NULL DEFAULT 7,
+                    consumption_look_back_months INTEGER NOT NULL DEFAULT 12,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+                    created_datetime {DATETIME} NOT NULL
+                );
+                CREATE TABLE cycle_count_plan_run (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    cycle_count_plan_id TEXT NOT NULL REFERENCES cycle_count_plan(id),
+                    run_datetime {DATETIME} NOT NULL,
+                    stocktake_id TEXT,
+                    item_count INTEGER NOT NULL DEFAULT 0
+                );
+                CREATE INDEX index_cycle_count_plan_run_cycle_count_plan_id ON cycle_count_plan_run (cycle_count_plan_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,32 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_document_conflict_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE document_conflict (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    document_name TEXT NOT NULL,
+                    context_id TEXT NOT NULL,
+                    base_document_id TEXT,
+                    left_document_id TEXT NOT NULL,
+                    right_document_id TEXT NOT NULL,
+                    conflicting_paths TEXT NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    resolved_datetime {DATETIME},
+                    resolved_document_id TEXT
+                );
+                CREATE INDEX index_document_conflict_document_name ON document_conflict (document_name);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_item_unit_of_measure_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE unit_of_measure_type AS ENUM (
+                    'DISPENSING',
+                    'OUTER_PACK'
+                );
+            "#
+            )?;
+        }
+
+        const UNIT_OF_MEASURE_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "unit_of_measure_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE item_unit_of_measure (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    name TEXT NOT NULL,
+                    type {UNIT_OF_MEASURE_TYPE_ENUM} NOT NULL,
+                    conversion_factor {DOUBLE} NOT NULL,
+                    deleted_datetime {DATETIME}
+                );
+
+                CREATE INDEX index_item_unit_of_measure_item_link_id ON item_unit_of_measure (item_link_id);
+            "#
+        )?;
+
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'item_unit_of_measure';
+                "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_label_template_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE label_template_type AS ENUM (
+                    'STOCK_LINE',
+                    'LOCATION',
+                    'ASSET'
+                );
+                CREATE TYPE printer_language AS ENUM (
+                    'ZPL',
+                    'EPL'
+                );
+            "#
+            )?;
+        }
+
+        const LABEL_TEMPLATE_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "label_template_type"
+        } else {
+            "TEXT"
+        };
+        const PRINTER_LANGUAGE_ENUM: &str = if cfg!(feature = "postgres") {
+            "printer_language"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE label_template (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    template_type {LABEL_TEMPLATE_TYPE_ENUM} NOT NULL,
+                    printer_language {PRINTER_LANGUAGE_ENUM} NOT NULL,
+                    template TEXT NOT NULL,
+                    label_width INTEGER NOT NULL,
+                    label_height INTEGER NOT NULL
+                );
+                CREATE TABLE label_printer (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    address TEXT NOT NULL,
+                    port INTEGER NOT NULL,
+                    printer_language {PRINTER_LANGUAGE_ENUM} NOT NULL
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_logger_file_sensor_types"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE sensor_type ADD VALUE IF NOT EXISTS 'LOG_TAG';
+                ALTER TYPE sensor_type ADD VALUE IF NOT EXISTS 'THIRTY_DAY_RECORDER';
+                ALTER TYPE sensor_type ADD VALUE IF NOT EXISTS 'GENERIC';
+            "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_period_close_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE period_close (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    period_start {DATE} NOT NULL,
+                    period_end {DATE} NOT NULL,
+                    closed_datetime {DATETIME} NOT NULL,
+                    user_id TEXT NOT NULL
+                );
+                CREATE INDEX index_period_close_store_id ON period_close (store_id);
+            "#
+        )?;
+
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'period_close';
+                "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
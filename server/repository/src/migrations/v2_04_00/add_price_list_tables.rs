@@ -0,0 +1,56 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_price_list_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE price_list (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    name TEXT NOT NULL,
+                    priority INTEGER NOT NULL DEFAULT 0,
+                    valid_from DATE,
+                    valid_to DATE,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE
+                );
+                CREATE TABLE price_list_line (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    price_list_id TEXT NOT NULL REFERENCES price_list(id),
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    price_per_unit {DOUBLE} NOT NULL
+                );
+                CREATE TABLE price_list_name_join (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    price_list_id TEXT NOT NULL REFERENCES price_list(id),
+                    name_link_id TEXT REFERENCES name_link(id),
+                    name_tag_id TEXT REFERENCES name_tag(id)
+                );
+                CREATE INDEX index_price_list_line_item_link_id ON price_list_line (item_link_id);
+                CREATE INDEX index_price_list_line_price_list_id ON price_list_line (price_list_id);
+                CREATE INDEX index_price_list_name_join_price_list_id ON price_list_name_join (price_list_id);
+            "#
+        )?;
+
+        if cfg!(feature = "postgres") {
+            // Postgres changelog and permission variants
+            sql!(
+                connection,
+                r#"
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'price_list';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'price_list_line';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'price_list_name_join';
+                    ALTER TYPE permission_type ADD VALUE IF NOT EXISTS 'PRICE_LIST_MUTATE';
+                "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_report_schedule_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE report_schedule_format AS ENUM (
+                    'PDF',
+                    'HTML',
+                    'EXCEL',
+                    'CSV'
+                );
+                CREATE TYPE report_schedule_run_status AS ENUM (
+                    'RUNNING',
+                    'SUCCESS',
+                    'ERROR'
+                );
+            "#
+            )?;
+        }
+
+        const REPORT_SCHEDULE_FORMAT_ENUM: &str = if cfg!(feature = "postgres") {
+            "report_schedule_format"
+        } else {
+            "TEXT"
+        };
+        const REPORT_SCHEDULE_RUN_STATUS_ENUM: &str = if cfg!(feature = "postgres") {
+            "report_schedule_run_status"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE report_schedule (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    report_id TEXT NOT NULL REFERENCES report(id),
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    user_id TEXT NOT NULL,
+                    arguments TEXT,
+                    format {REPORT_SCHEDULE_FORMAT_ENUM} NOT NULL,
+                    schedule TEXT NOT NULL,
+                    output_directory TEXT NOT NULL,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+                    created_datetime {DATETIME} NOT NULL
+                );
+                CREATE TABLE report_schedule_run (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    report_schedule_id TEXT NOT NULL REFERENCES report_schedule(id),
+                    scheduled_datetime {DATETIME} NOT NULL,
+                    started_datetime {DATETIME} NOT NULL,
+                    finished_datetime {DATETIME},
+                    status {REPORT_SCHEDULE_RUN_STATUS_ENUM} NOT NULL,
+                    output_path TEXT,
+                    error TEXT
+                );
+                CREATE INDEX index_report_schedule_run_report_schedule_id ON report_schedule_run (report_schedule_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
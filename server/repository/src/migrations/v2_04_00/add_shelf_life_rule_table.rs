@@ -0,0 +1,27 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_shelf_life_rule_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE shelf_life_rule (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    name_link_id TEXT REFERENCES name_link(id),
+                    item_link_id TEXT REFERENCES item_link(id),
+                    minimum_remaining_days INTEGER NOT NULL
+                );
+                CREATE INDEX index_shelf_life_rule_store_id ON shelf_life_rule (store_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_stocktake_blind_count_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE permission_type ADD VALUE IF NOT EXISTS 'STOCKTAKE_SUPERVISE';
+            "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE stocktake_blind_count (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    recount_tolerance_percentage {DOUBLE},
+                    submitted_datetime {DATETIME},
+                    submitted_by_user_id TEXT
+                );
+
+                CREATE TABLE stocktake_line_recount (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    stocktake_id TEXT NOT NULL,
+                    stocktake_line_id TEXT NOT NULL,
+                    first_counted_number_of_packs {DOUBLE} NOT NULL,
+                    snapshot_number_of_packs {DOUBLE} NOT NULL,
+                    requested_datetime {DATETIME} NOT NULL,
+                    recounted_datetime {DATETIME}
+                );
+
+                CREATE INDEX index_stocktake_line_recount_stocktake_id ON stocktake_line_recount (stocktake_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_stocktake_variance_approval"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE permission_type ADD VALUE IF NOT EXISTS 'STOCKTAKE_VARIANCE_APPROVE';
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'STOCKTAKE_VARIANCE_APPROVED' AFTER 'DEMOGRAPHIC_PROJECTION_UPDATED';
+            "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE store_preference ADD stocktake_variance_value_threshold {DOUBLE} NOT NULL DEFAULT 0;
+                ALTER TABLE store_preference ADD stocktake_variance_percentage_threshold {DOUBLE} NOT NULL DEFAULT 0;
+
+                CREATE TABLE stocktake_variance_approval (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    stocktake_id TEXT NOT NULL,
+                    stocktake_line_id TEXT NOT NULL,
+                    counted_number_of_packs {DOUBLE} NOT NULL,
+                    approved_by_user_id TEXT NOT NULL,
+                    approved_datetime {DATETIME} NOT NULL,
+                    comment TEXT
+                );
+
+                CREATE INDEX index_stocktake_variance_approval_stocktake_id ON stocktake_variance_approval (stocktake_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_store_pref_allocation_strategy"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE allocation_strategy AS ENUM (
+                    'FEFO',
+                    'FIFO',
+                    'PREFER_LOCATION',
+                    'AVOID_BREAKING_PACKS',
+                    'MINIMISE_BATCHES'
+                );
+            "#
+            )?;
+        }
+
+        const ALLOCATION_STRATEGY_ENUM: &str = if cfg!(feature = "postgres") {
+            "allocation_strategy"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE store_preference ADD allocation_strategy {ALLOCATION_STRATEGY_ENUM} NOT NULL DEFAULT 'FEFO';
+                ALTER TABLE store_preference ADD allocation_preferred_location_id TEXT;
+            "#
+        )?;
+
+        Ok(())
+    }
+}
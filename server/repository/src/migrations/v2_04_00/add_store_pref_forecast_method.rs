@@ -0,0 +1,39 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_store_pref_forecast_method"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE forecast_method AS ENUM (
+                    'AVERAGE_MONTHLY_CONSUMPTION',
+                    'WEIGHTED_MOVING_AVERAGE',
+                    'SEASONAL_NAIVE'
+                );
+            "#
+            )?;
+        }
+
+        const FORECAST_METHOD_ENUM: &str = if cfg!(feature = "postgres") {
+            "forecast_method"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE store_preference ADD forecast_method {FORECAST_METHOD_ENUM} NOT NULL DEFAULT 'AVERAGE_MONTHLY_CONSUMPTION';
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_store_pref_patient_code_scheme"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE patient_code_scheme AS ENUM (
+                    'MANUAL',
+                    'PREFIX_SEQUENCE_LUHN',
+                    'PREFIX_SEQUENCE_VERHOEFF'
+                );
+            "#
+            )?;
+        }
+
+        const PATIENT_CODE_SCHEME_ENUM: &str = if cfg!(feature = "postgres") {
+            "patient_code_scheme"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE store_preference ADD patient_code_scheme {PATIENT_CODE_SCHEME_ENUM} NOT NULL DEFAULT 'MANUAL';
+                ALTER TABLE store_preference ADD patient_code_prefix TEXT;
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_temperature_notification_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE temperature_notification_channel AS ENUM (
+                    'EMAIL',
+                    'WEBHOOK',
+                    'LOG'
+                );
+                CREATE TYPE temperature_notification_level AS ENUM (
+                    'INITIAL',
+                    'ESCALATION'
+                );
+                CREATE TYPE temperature_notification_status AS ENUM (
+                    'SENT',
+                    'FAILED'
+                );
+            "#
+            )?;
+        }
+
+        const BREACH_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "temperature_breach_type"
+        } else {
+            "TEXT"
+        };
+        const CHANNEL_ENUM: &str = if cfg!(feature = "postgres") {
+            "temperature_notification_channel"
+        } else {
+            "TEXT"
+        };
+        const LEVEL_ENUM: &str = if cfg!(feature = "postgres") {
+            "temperature_notification_level"
+        } else {
+            "TEXT"
+        };
+        const STATUS_ENUM: &str = if cfg!(feature = "postgres") {
+            "temperature_notification_status"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE temperature_notification_rule (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    location_id TEXT REFERENCES location(id),
+                    breach_type {BREACH_TYPE_ENUM},
+                    minimum_duration_milliseconds INTEGER NOT NULL DEFAULT 0,
+                    channel {CHANNEL_ENUM} NOT NULL,
+                    recipient TEXT NOT NULL,
+                    escalation_after_milliseconds INTEGER,
+                    escalation_recipient TEXT,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE
+                );
+                CREATE TABLE temperature_notification (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    rule_id TEXT NOT NULL REFERENCES temperature_notification_rule(id),
+                    temperature_breach_id TEXT NOT NULL REFERENCES temperature_breach(id),
+                    level {LEVEL_ENUM} NOT NULL,
+                    status {STATUS_ENUM} NOT NULL,
+                    attempts INTEGER NOT NULL DEFAULT 0,
+                    created_datetime {DATETIME} NOT NULL,
+                    sent_datetime {DATETIME},
+                    error TEXT
+                );
+                CREATE UNIQUE INDEX index_temperature_notification_rule_breach_level ON temperature_notification (rule_id, temperature_breach_id, level);
+                CREATE INDEX index_temperature_notification_temperature_breach_id ON temperature_notification (temperature_breach_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_transfer_dead_letter_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE transfer_processor_type AS ENUM (
+                    'REQUISITION_TRANSFER',
+                    'INVOICE_TRANSFER'
+                );
+            "#
+            )?;
+        }
+
+        const TRANSFER_PROCESSOR_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "transfer_processor_type"
+        } else {
+            "TEXT"
+        };
+
+        const ROW_ACTION_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "row_action_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE transfer_dead_letter (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    processor_type {TRANSFER_PROCESSOR_TYPE_ENUM} NOT NULL,
+                    record_id TEXT NOT NULL,
+                    row_action {ROW_ACTION_TYPE_ENUM} NOT NULL,
+                    changelog_cursor BIGINT NOT NULL,
+                    name_id TEXT,
+                    store_id TEXT,
+                    error TEXT NOT NULL,
+                    attempt_count INTEGER NOT NULL DEFAULT 1,
+                    first_attempt_datetime {DATETIME} NOT NULL,
+                    last_attempt_datetime {DATETIME} NOT NULL,
+                    retry_requested BOOLEAN NOT NULL DEFAULT FALSE,
+                    next_retry_datetime {DATETIME},
+                    resolved_datetime {DATETIME}
+                );
+                CREATE INDEX index_transfer_dead_letter_record_id ON transfer_dead_letter (record_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
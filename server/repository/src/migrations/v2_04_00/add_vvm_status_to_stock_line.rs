@@ -0,0 +1,40 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_vvm_status_to_stock_line"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE vvm_status AS ENUM (
+                    'STAGE1',
+                    'STAGE2',
+                    'STAGE3',
+                    'STAGE4'
+                );
+            "#
+            )?;
+        }
+
+        const VVM_STATUS_ENUM: &str = if cfg!(feature = "postgres") {
+            "vvm_status"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE stock_line ADD COLUMN vvm_status {VVM_STATUS_ENUM};
+            "#
+        )?;
+
+        Ok(())
+    }
+}
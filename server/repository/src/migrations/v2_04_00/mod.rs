@@ -1,14 +1,32 @@
 use super::{version::Version, Migration, MigrationFragment};
 
 mod add_bundled_item_table;
+mod add_changelog_processor_table;
 mod add_cold_storage_type_table;
+mod add_cycle_count_plan_tables;
 mod add_demographic_indicator_types_to_activity_log;
+mod add_document_conflict_table;
 mod add_expected_lifespan_to_assets;
+mod add_item_unit_of_measure_table;
 mod add_item_variant_id_to_stock_line_and_invoice_line;
+mod add_label_template_tables;
+mod add_logger_file_sensor_types;
 mod add_manual_requisition_line_fields;
+mod add_period_close_table;
+mod add_price_list_tables;
 mod add_reason_option_table;
+mod add_report_schedule_tables;
+mod add_shelf_life_rule_table;
+mod add_stocktake_blind_count_tables;
+mod add_stocktake_variance_approval;
+mod add_store_pref_allocation_strategy;
+mod add_store_pref_forecast_method;
+mod add_store_pref_patient_code_scheme;
 mod add_store_pref_use_extra_fields;
+mod add_temperature_notification_tables;
+mod add_transfer_dead_letter_table;
 mod add_unserviceable_status_to_asset_status_enum;
+mod add_vvm_status_to_stock_line;
 mod delete_pack_variant;
 mod indicator_indexes;
 mod indicator_line_column_create_tables;
@@ -46,6 +64,24 @@ impl Migration for V2_04_00 {
             Box::new(add_demographic_indicator_types_to_activity_log::Migrate),
             Box::new(indicator_indexes::Migrate),
             Box::new(add_store_pref_use_extra_fields::Migrate),
+            Box::new(add_store_pref_allocation_strategy::Migrate),
+            Box::new(add_shelf_life_rule_table::Migrate),
+            Box::new(add_report_schedule_tables::Migrate),
+            Box::new(add_price_list_tables::Migrate),
+            Box::new(add_store_pref_forecast_method::Migrate),
+            Box::new(add_changelog_processor_table::Migrate),
+            Box::new(add_transfer_dead_letter_table::Migrate),
+            Box::new(add_logger_file_sensor_types::Migrate),
+            Box::new(add_temperature_notification_tables::Migrate),
+            Box::new(add_stocktake_variance_approval::Migrate),
+            Box::new(add_cycle_count_plan_tables::Migrate),
+            Box::new(add_stocktake_blind_count_tables::Migrate),
+            Box::new(add_item_unit_of_measure_table::Migrate),
+            Box::new(add_store_pref_patient_code_scheme::Migrate),
+            Box::new(add_document_conflict_table::Migrate),
+            Box::new(add_period_close_table::Migrate),
+            Box::new(add_label_template_tables::Migrate),
+            Box::new(add_vvm_status_to_stock_line::Migrate),
         ]
     }
 }
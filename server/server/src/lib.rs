@@ -16,18 +16,21 @@ use extism::set_log_callback;
 use graphql_core::loader::{get_loaders, LoaderRegistry};
 
 use graphql::{
-    attach_discovery_graphql_schema, attach_graphql_schema, GraphSchemaData, GraphqlSchema,
+    attach_discovery_graphql_schema, attach_graphql_schema, report_scheduler, GraphSchemaData,
+    GraphqlSchema,
 };
 use log::info;
 use repository::{get_storage_connection_manager, migrations::migrate};
 
 use service::{
     auth_data::AuthData,
+    cold_chain::notification::dispatch::TemperatureNotificationDispatcher,
     plugin::validation::ValidatedPluginBucket,
     processors::Processors,
     service_provider::ServiceProvider,
     settings::{is_develop, ServerSettings, Settings},
     standard_reports::StandardReports,
+    stocktake::cycle_count::scheduler::CycleCountScheduler,
     sync::{
         file_sync_driver::FileSyncDriver,
         synchroniser_driver::{SiteIsInitialisedCallback, SynchroniserDriver},
@@ -232,17 +235,16 @@ pub async fn start_server(
     let validated_plugins = ValidatedPluginBucket::new(&settings.server.base_dir).unwrap();
     let validated_plugins = Data::new(Mutex::new(validated_plugins));
 
-    let graphql_schema = Data::new(GraphqlSchema::new(
-        GraphSchemaData {
-            connection_manager: Data::new(connection_manager),
-            loader_registry: Data::new(LoaderRegistry { loaders }),
-            service_provider: service_provider.clone(),
-            settings: Data::new(settings.clone()),
-            auth: auth.clone(),
-            validated_plugins: validated_plugins.clone(),
-        },
-        is_operational,
-    ));
+    let graphql_schema_data = GraphSchemaData {
+        connection_manager: Data::new(connection_manager),
+        loader_registry: Data::new(LoaderRegistry { loaders }),
+        service_provider: service_provider.clone(),
+        settings: Data::new(settings.clone()),
+        auth: auth.clone(),
+        validated_plugins: validated_plugins.clone(),
+    };
+    let report_scheduler = report_scheduler(&graphql_schema_data);
+    let graphql_schema = Data::new(GraphqlSchema::new(graphql_schema_data, is_operational));
     // Bind trigger to change schema when site is initialised
     if !is_operational {
         let graphql_schema = graphql_schema.clone();
@@ -284,6 +286,10 @@ pub async fn start_server(
         force_trigger_sync_on_startup,
     );
     let file_sync_task = file_sync_driver.run(service_provider.clone().into_inner());
+    let report_scheduler_task = report_scheduler.run();
+    let temperature_notification_task = TemperatureNotificationDispatcher::new(&settings)
+        .run(service_provider.clone().into_inner());
+    let cycle_count_task = CycleCountScheduler::run(service_provider.clone().into_inner());
 
     let closure_settings = settings.clone();
     let mut http_server = HttpServer::new(move || {
@@ -333,6 +339,9 @@ pub async fn start_server(
         Some(_) = off_switch.recv() => {},
         _ = synchroniser_task => unreachable!("Synchroniser unexpectedly stopped"),
         _ = file_sync_task => unreachable!("File sync unexpectedly stopped"),
+        _ = report_scheduler_task => unreachable!("Report scheduler unexpectedly stopped"),
+        _ = temperature_notification_task => unreachable!("Temperature notifications unexpectedly stopped"),
+        _ = cycle_count_task => unreachable!("Cycle count scheduler unexpectedly stopped"),
         result = processors_task => unreachable!("Processor terminated ({:?})", result)
     };
 
@@ -10,6 +10,7 @@ use self::query_asset_property::get_asset_properties;
 use self::query_log::{get_asset_log, get_asset_logs};
 use self::query_log_reason::{get_asset_log_reason, get_asset_log_reasons};
 use self::update::{update_asset, UpdateAsset, UpdateAssetError};
+use self::uptime::{get_asset_uptime, AssetUptimeError, AssetUptimeInput, AssetUptimeReport};
 
 use super::{ListError, ListResult};
 use crate::{service_provider::ServiceContext, SingleRecordError};
@@ -35,6 +36,7 @@ pub mod query_asset_property;
 pub mod query_log;
 pub mod query_log_reason;
 pub mod update;
+pub mod uptime;
 mod validate;
 
 pub trait AssetServiceTrait: Sync + Send {
@@ -147,6 +149,14 @@ pub trait AssetServiceTrait: Sync + Send {
     ) -> Result<Asset, AssetFromGs1Error> {
         parse::get_or_create_from_gs1_data(ctx, gs1_data)
     }
+
+    fn get_asset_uptime(
+        &self,
+        ctx: &ServiceContext,
+        input: AssetUptimeInput,
+    ) -> Result<AssetUptimeReport, AssetUptimeError> {
+        get_asset_uptime(ctx, input)
+    }
 }
 
 pub struct AssetService {}
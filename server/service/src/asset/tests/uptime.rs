@@ -0,0 +1,211 @@
+#[cfg(test)]
+mod query {
+    use chrono::{Duration, NaiveDate, NaiveDateTime};
+    use repository::{
+        asset_internal_location_row::{
+            AssetInternalLocationRow, AssetInternalLocationRowRepository,
+        },
+        asset_log_row::{AssetLogRow, AssetLogRowRepository, AssetLogStatus},
+        asset_row::{AssetRow, AssetRowRepository},
+        mock::{
+            mock_asset_b, mock_location_1, mock_store_a, mock_temperature_breach_1,
+            mock_user_account_a, MockDataInserts,
+        },
+        test_db::setup_all,
+        TemperatureBreachRow, TemperatureBreachRowRepository,
+    };
+
+    use crate::{
+        asset::uptime::{AssetUptimeError, AssetUptimeInput},
+        service_provider::ServiceProvider,
+    };
+
+    fn date(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn asset_uptime() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "test_asset_uptime",
+            MockDataInserts::none()
+                .names()
+                .stores()
+                .user_accounts()
+                .locations()
+                .sensors(),
+        )
+        .await;
+
+        let category_id = mock_asset_b().asset_category_id;
+        let asset_repo = AssetRowRepository::new(&connection);
+        for (id, category_id) in [("asset_1", category_id.clone()), ("asset_2", None)] {
+            asset_repo
+                .upsert_one(&AssetRow {
+                    id: id.to_string(),
+                    store_id: Some(mock_store_a().id),
+                    asset_category_id: category_id,
+                    created_datetime: date(1) - Duration::days(30),
+                    modified_datetime: date(1) - Duration::days(30),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let log_repo = AssetLogRowRepository::new(&connection);
+        let logs = [
+            (
+                "asset_1",
+                date(1) - Duration::days(2),
+                Some(AssetLogStatus::Functioning),
+            ),
+            ("asset_1", date(3), Some(AssetLogStatus::NotFunctioning)),
+            // Comment only, doesn't change the status
+            ("asset_1", date(4), None),
+            ("asset_1", date(5), Some(AssetLogStatus::Functioning)),
+            (
+                "asset_1",
+                date(9),
+                Some(AssetLogStatus::FunctioningButNeedsAttention),
+            ),
+            // After the period
+            ("asset_1", date(20), Some(AssetLogStatus::NotFunctioning)),
+            // Broken before the period
+            (
+                "asset_2",
+                date(1) - Duration::days(7),
+                Some(AssetLogStatus::NotFunctioning),
+            ),
+            ("asset_2", date(6), Some(AssetLogStatus::Functioning)),
+            ("asset_2", date(10), Some(AssetLogStatus::NotFunctioning)),
+        ];
+        for (index, (asset_id, log_datetime, status)) in logs.into_iter().enumerate() {
+            log_repo
+                .upsert_one(&AssetLogRow {
+                    id: format!("log_{index}"),
+                    asset_id: asset_id.to_string(),
+                    user_id: mock_user_account_a().id,
+                    status,
+                    log_datetime,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        AssetInternalLocationRowRepository::new(&connection)
+            .upsert_one(&AssetInternalLocationRow {
+                id: "asset_1_location_1".to_string(),
+                asset_id: "asset_1".to_string(),
+                location_id: mock_location_1().id,
+            })
+            .unwrap();
+        let breach_repo = TemperatureBreachRowRepository::new(&connection);
+        for (id, start_datetime) in [
+            ("in_period", date(4)),
+            ("before_period", date(1) - Duration::days(1)),
+        ] {
+            breach_repo
+                .upsert_one(&TemperatureBreachRow {
+                    id: id.to_string(),
+                    location_id: Some(mock_location_1().id),
+                    start_datetime,
+                    end_datetime: Some(start_datetime + Duration::hours(2)),
+                    ..mock_temperature_breach_1()
+                })
+                .unwrap();
+        }
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.asset_service;
+
+        assert_eq!(
+            service.get_asset_uptime(
+                &context,
+                AssetUptimeInput {
+                    from: date(11),
+                    to: date(1),
+                    ..Default::default()
+                }
+            ),
+            Err(AssetUptimeError::InvalidPeriod)
+        );
+
+        let report = service
+            .get_asset_uptime(
+                &context,
+                AssetUptimeInput {
+                    from: date(1),
+                    to: date(11),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let day = Duration::days(1).num_milliseconds();
+
+        let asset_1 = report
+            .assets
+            .iter()
+            .find(|uptime| uptime.asset.id == "asset_1")
+            .unwrap();
+        assert_eq!(
+            asset_1.status,
+            Some(AssetLogStatus::FunctioningButNeedsAttention)
+        );
+        assert_eq!(asset_1.statistics.observed_milliseconds, 10 * day);
+        assert_eq!(asset_1.statistics.functional_milliseconds, 8 * day);
+        assert_eq!(asset_1.statistics.functional_percentage(), Some(80.0));
+        assert_eq!(asset_1.statistics.breakdown_count, 1);
+        assert_eq!(
+            asset_1.statistics.mean_time_to_repair_milliseconds(),
+            Some(2 * day)
+        );
+        assert_eq!(asset_1.statistics.temperature_breach_count, 1);
+
+        let asset_2 = report
+            .assets
+            .iter()
+            .find(|uptime| uptime.asset.id == "asset_2")
+            .unwrap();
+        assert_eq!(asset_2.status, Some(AssetLogStatus::NotFunctioning));
+        assert_eq!(asset_2.statistics.functional_percentage(), Some(40.0));
+        // Breakdown before the period is not counted, but its repair is
+        assert_eq!(asset_2.statistics.breakdown_count, 1);
+        assert_eq!(asset_2.statistics.repair_count, 1);
+        assert_eq!(
+            asset_2.statistics.mean_time_to_repair_milliseconds(),
+            Some(12 * day)
+        );
+        assert_eq!(asset_2.statistics.temperature_breach_count, 0);
+
+        assert_eq!(report.categories.len(), 2);
+        assert_eq!(report.store.asset_count, 2);
+        assert_eq!(report.store.functional_asset_count, 1);
+        assert_eq!(report.store.functional_percentage(), Some(60.0));
+        assert_eq!(report.store.breakdown_count, 2);
+        assert_eq!(
+            report.store.mean_time_to_repair_milliseconds(),
+            Some(7 * day)
+        );
+
+        // Filter by category
+        let report = service
+            .get_asset_uptime(
+                &context,
+                AssetUptimeInput {
+                    from: date(1),
+                    to: date(11),
+                    category_id,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(report.assets.len(), 1);
+        assert_eq!(report.assets[0].asset.id, "asset_1");
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Duration, NaiveDateTime};
+use repository::{
+    asset_internal_location::{AssetInternalLocationFilter, AssetInternalLocationRepository},
+    asset_log_row::AssetLogStatus,
+    assets::{
+        asset::{Asset, AssetFilter, AssetRepository},
+        asset_log::{AssetLogFilter, AssetLogRepository, AssetLogSortField},
+    },
+    temperature_breach::{TemperatureBreachFilter, TemperatureBreachRepository},
+    DatetimeFilter, EqualFilter, LocationFilter, Pagination, RepositoryError, Sort,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AssetUptimeInput {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    /// Only assets of this category
+    pub category_id: Option<String>,
+    /// Only this asset
+    pub asset_id: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AssetUptimeError {
+    InvalidPeriod,
+    DatabaseError(RepositoryError),
+}
+
+/// Functional status statistics over a period, for an asset or aggregated for a category/store
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AssetUptimeStatistics {
+    pub asset_count: u32,
+    /// Assets that were functioning at the end of the period
+    pub functional_asset_count: u32,
+    /// Time the assets were functioning (including functioning but needs attention)
+    pub functional_milliseconds: i64,
+    /// Time the assets had a known status other than decommissioned, the base of the percentage
+    pub observed_milliseconds: i64,
+    /// Number of times an asset changed to not functioning during the period
+    pub breakdown_count: u32,
+    /// Number of breakdowns that were repaired during the period
+    pub repair_count: u32,
+    /// Sum of the time from breakdown to functioning again, for the repairs in the period
+    pub repair_milliseconds: i64,
+    /// Temperature breaches that started during the period at a location linked to the assets
+    pub temperature_breach_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetUptime {
+    pub asset: Asset,
+    /// Status at the end of the period
+    pub status: Option<AssetLogStatus>,
+    pub statistics: AssetUptimeStatistics,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetCategoryUptime {
+    pub category_id: Option<String>,
+    pub statistics: AssetUptimeStatistics,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetUptimeReport {
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub assets: Vec<AssetUptime>,
+    pub categories: Vec<AssetCategoryUptime>,
+    /// Totals for all assets of the store
+    pub store: AssetUptimeStatistics,
+}
+
+impl AssetUptimeStatistics {
+    /// Percentage of the observed time the assets were functioning, None if there is no status
+    /// information for the period
+    pub fn functional_percentage(&self) -> Option<f64> {
+        (self.observed_milliseconds > 0).then(|| {
+            self.functional_milliseconds as f64 / self.observed_milliseconds as f64 * 100.0
+        })
+    }
+
+    pub fn mean_time_to_repair_milliseconds(&self) -> Option<i64> {
+        (self.repair_count > 0).then(|| self.repair_milliseconds / self.repair_count as i64)
+    }
+
+    fn add(&mut self, other: &AssetUptimeStatistics) {
+        self.asset_count += other.asset_count;
+        self.functional_asset_count += other.functional_asset_count;
+        self.functional_milliseconds += other.functional_milliseconds;
+        self.observed_milliseconds += other.observed_milliseconds;
+        self.breakdown_count += other.breakdown_count;
+        self.repair_count += other.repair_count;
+        self.repair_milliseconds += other.repair_milliseconds;
+        self.temperature_breach_count += other.temperature_breach_count;
+    }
+}
+
+/// Computes functional status statistics of the assets of the current store from the status
+/// changes in the asset logs
+pub fn get_asset_uptime(
+    ctx: &ServiceContext,
+    input: AssetUptimeInput,
+) -> Result<AssetUptimeReport, AssetUptimeError> {
+    let AssetUptimeInput {
+        from,
+        to,
+        category_id,
+        asset_id,
+    } = input;
+    if from >= to {
+        return Err(AssetUptimeError::InvalidPeriod);
+    }
+    let connection = &ctx.connection;
+
+    let mut filter = AssetFilter::new().store_id(EqualFilter::equal_to(&ctx.store_id));
+    if let Some(category_id) = category_id {
+        filter = filter.category_id(EqualFilter::equal_to(&category_id));
+    }
+    if let Some(asset_id) = asset_id {
+        filter = filter.id(EqualFilter::equal_to(&asset_id));
+    }
+    let assets = AssetRepository::new(connection).query_by_filter(filter)?;
+    let asset_ids: Vec<String> = assets.iter().map(|asset| asset.id.clone()).collect();
+
+    // Logs without a status are comments or other events, they don't change the status
+    let logs = AssetLogRepository::new(connection).query(
+        Pagination::all(),
+        Some(
+            AssetLogFilter::new()
+                .asset_id(EqualFilter::equal_any(asset_ids.clone()))
+                .log_datetime(DatetimeFilter::before_or_equal_to(to)),
+        ),
+        Some(Sort {
+            key: AssetLogSortField::LogDatetime,
+            desc: Some(false),
+        }),
+    )?;
+    let mut status_changes: HashMap<String, Vec<(NaiveDateTime, AssetLogStatus)>> = HashMap::new();
+    for log in logs {
+        if let Some(status) = log.status {
+            status_changes
+                .entry(log.asset_id)
+                .or_default()
+                .push((log.log_datetime, status));
+        }
+    }
+
+    let breach_counts = count_temperature_breaches(ctx, &asset_ids, from, to)?;
+
+    let mut categories: BTreeMap<Option<String>, AssetUptimeStatistics> = BTreeMap::new();
+    let mut store = AssetUptimeStatistics::default();
+    let assets = assets
+        .into_iter()
+        .map(|asset| {
+            let changes = status_changes.remove(&asset.id).unwrap_or_default();
+            let (status, mut statistics) = calculate_uptime(&changes, from, to);
+            statistics.temperature_breach_count =
+                breach_counts.get(&asset.id).copied().unwrap_or_default();
+
+            categories
+                .entry(asset.asset_category_id.clone())
+                .or_default()
+                .add(&statistics);
+            store.add(&statistics);
+
+            AssetUptime {
+                asset,
+                status,
+                statistics,
+            }
+        })
+        .collect();
+
+    Ok(AssetUptimeReport {
+        from,
+        to,
+        assets,
+        categories: categories
+            .into_iter()
+            .map(|(category_id, statistics)| AssetCategoryUptime {
+                category_id,
+                statistics,
+            })
+            .collect(),
+        store,
+    })
+}
+
+fn is_functional(status: &AssetLogStatus) -> bool {
+    matches!(
+        status,
+        AssetLogStatus::Functioning | AssetLogStatus::FunctioningButNeedsAttention
+    )
+}
+
+/// Walks the status changes (sorted by time, up to `to`) of an asset and returns its status at
+/// the end of the period with its statistics
+fn calculate_uptime(
+    changes: &[(NaiveDateTime, AssetLogStatus)],
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> (Option<AssetLogStatus>, AssetUptimeStatistics) {
+    let mut statistics = AssetUptimeStatistics {
+        asset_count: 1,
+        ..Default::default()
+    };
+    let mut current: Option<(NaiveDateTime, &AssetLogStatus)> = None;
+    let mut broken_since: Option<NaiveDateTime> = None;
+
+    let mut add_time = |status: &AssetLogStatus, start: NaiveDateTime, end: NaiveDateTime| {
+        let milliseconds = (end.min(to) - start.max(from)).num_milliseconds();
+        if milliseconds <= 0 || *status == AssetLogStatus::Decommissioned {
+            return;
+        }
+        statistics.observed_milliseconds += milliseconds;
+        if is_functional(status) {
+            statistics.functional_milliseconds += milliseconds;
+        }
+    };
+
+    let mut breakdowns = 0;
+    let mut repairs: Vec<Duration> = Vec::new();
+    for (datetime, status) in changes {
+        if let Some((since, previous)) = current {
+            if previous == status {
+                continue;
+            }
+            add_time(previous, since, *datetime);
+        }
+
+        if *status == AssetLogStatus::NotFunctioning {
+            broken_since = Some(*datetime);
+            if *datetime >= from {
+                breakdowns += 1;
+            }
+        } else if is_functional(status) {
+            if let Some(broken_since) = broken_since.take() {
+                if *datetime >= from {
+                    repairs.push(*datetime - broken_since);
+                }
+            }
+        }
+        current = Some((*datetime, status));
+    }
+    if let Some((since, status)) = current {
+        add_time(status, since, to);
+    }
+
+    let status = current.map(|(_, status)| status.clone());
+    statistics.functional_asset_count = status.as_ref().is_some_and(is_functional) as u32;
+    statistics.breakdown_count = breakdowns;
+    statistics.repair_count = repairs.len() as u32;
+    statistics.repair_milliseconds = repairs
+        .iter()
+        .map(|duration| duration.num_milliseconds())
+        .sum();
+
+    (status, statistics)
+}
+
+/// Temperature breaches that started in the period, by asset, via the locations linked to the
+/// assets
+fn count_temperature_breaches(
+    ctx: &ServiceContext,
+    asset_ids: &[String],
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<HashMap<String, u32>, RepositoryError> {
+    let asset_locations = AssetInternalLocationRepository::new(&ctx.connection).query_by_filter(
+        AssetInternalLocationFilter::new().asset_id(EqualFilter::equal_any(asset_ids.to_vec())),
+    )?;
+    let mut assets_by_location: HashMap<String, Vec<String>> = HashMap::new();
+    for asset_location in asset_locations {
+        assets_by_location
+            .entry(asset_location.location_id)
+            .or_default()
+            .push(asset_location.asset_id);
+    }
+    if assets_by_location.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let breaches = TemperatureBreachRepository::new(&ctx.connection).query_by_filter(
+        TemperatureBreachFilter::new()
+            .store_id(EqualFilter::equal_to(&ctx.store_id))
+            .start_datetime(DatetimeFilter::date_range(from, to))
+            .location(LocationFilter::new().id(EqualFilter::equal_any(
+                assets_by_location.keys().cloned().collect(),
+            ))),
+    )?;
+
+    let mut counts: HashMap<String, u32> = HashMap::new();
+    for breach in breaches {
+        let Some(location_id) = &breach.temperature_breach_row.location_id else {
+            continue;
+        };
+        for asset_id in assets_by_location.get(location_id).into_iter().flatten() {
+            *counts.entry(asset_id.clone()).or_default() += 1;
+        }
+    }
+    Ok(counts)
+}
+
+impl From<RepositoryError> for AssetUptimeError {
+    fn from(error: RepositoryError) -> Self {
+        AssetUptimeError::DatabaseError(error)
+    }
+}
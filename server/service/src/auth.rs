@@ -51,6 +51,9 @@ pub enum Resource {
     QueryItems,
     MutateItems,
     MutateItemNamesCodesAndUnits,
+    // price lists
+    QueryPriceList,
+    MutatePriceList,
     // stock
     StockCount,
     QueryStockLine,
@@ -59,6 +62,8 @@ pub enum Resource {
     // stocktake
     QueryStocktake,
     MutateStocktake,
+    ApproveStocktakeVariance,
+    SuperviseStocktake,
     // inventory adjustment
     MutateInventoryAdjustment,
     // requisition
@@ -76,6 +81,8 @@ pub enum Resource {
     QueryInvoice,
     // outbound shipment
     MutateOutboundShipment,
+    QueryShelfLifeRule,
+    MutateShelfLifeRule,
     // inbound shipment
     MutateInboundShipment,
     // supplier return
@@ -87,7 +94,11 @@ pub enum Resource {
     // reporting
     Report,
     ReportDev,
+    MutateReportSchedule,
+    ClosePeriod,
     QueryLog,
+    // label printing
+    PrintLabels,
     // view/edit server setting
     ServerAdmin,
     // clinician
@@ -223,6 +234,16 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
         ]),
     );
 
+    // price lists
+    map.insert(Resource::QueryPriceList, PermissionDSL::HasStoreAccess);
+    map.insert(
+        Resource::MutatePriceList,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(PermissionType::PriceListMutate),
+        ]),
+    );
+
     // stock
     map.insert(
         Resource::StockCount,
@@ -274,6 +295,20 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(PermissionType::StocktakeMutate),
         ]),
     );
+    map.insert(
+        Resource::ApproveStocktakeVariance,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(PermissionType::StocktakeVarianceApprove),
+        ]),
+    );
+    map.insert(
+        Resource::SuperviseStocktake,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(PermissionType::StocktakeSupervise),
+        ]),
+    );
     // stock take line
     map.insert(
         Resource::InsertStocktakeLine,
@@ -375,6 +410,15 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(PermissionType::OutboundShipmentMutate),
         ]),
     );
+    map.insert(Resource::QueryShelfLifeRule, PermissionDSL::HasStoreAccess);
+    map.insert(
+        Resource::MutateShelfLifeRule,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            // Rules restrict which stock outbound shipments can be allocated from
+            PermissionDSL::HasPermission(PermissionType::OutboundShipmentMutate),
+        ]),
+    );
     // inbound shipment
     map.insert(
         Resource::MutateInboundShipment,
@@ -425,6 +469,26 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(PermissionType::ServerAdmin),
         ]),
     );
+    // report schedule
+    map.insert(
+        Resource::MutateReportSchedule,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            // Reports are written to directories on the server
+            PermissionDSL::HasPermission(PermissionType::ServerAdmin),
+        ]),
+    );
+    // label printing
+    map.insert(Resource::PrintLabels, PermissionDSL::HasStoreAccess);
+    // period close
+    map.insert(
+        Resource::ClosePeriod,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            // Closing locks the store's stock transactions of the period for finance reporting
+            PermissionDSL::HasPermission(PermissionType::ServerAdmin),
+        ]),
+    );
 
     map.insert(
         Resource::QueryLog,
@@ -1,9 +1,10 @@
+use chrono::NaiveDate;
 use repository::{
     barcode::{Barcode, BarcodeFilter, BarcodeRepository, BarcodeSort},
     BarcodeRow, BarcodeRowRepository, EqualFilter, PaginationOption, RepositoryError,
     StorageConnection, StorageConnectionManager,
 };
-use util::uuid::uuid;
+use util::{gtin_14, uuid::uuid, GS1ParseError, GS1};
 
 use crate::{item::item::check_item_exists, service_provider::ServiceContext};
 
@@ -37,6 +38,31 @@ impl From<RepositoryError> for InsertBarcodeError {
     }
 }
 
+/// Item and batch details read from a scanned barcode
+#[derive(Debug, PartialEq, Default)]
+pub struct ScannedBarcode {
+    /// GTIN padded to 14 digits
+    pub gtin: Option<String>,
+    /// Barcode (and so item) matching the GTIN
+    pub barcode: Option<Barcode>,
+    pub batch: Option<String>,
+    pub expiry_date: Option<NaiveDate>,
+    pub serial_number: Option<String>,
+    /// Number of units in a variable count trade item
+    pub count: Option<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ScanBarcodeError {
+    InvalidBarcode(GS1ParseError),
+    DatabaseError(RepositoryError),
+}
+impl From<RepositoryError> for ScanBarcodeError {
+    fn from(error: RepositoryError) -> Self {
+        ScanBarcodeError::DatabaseError(error)
+    }
+}
+
 pub trait BarcodeServiceTrait: Sync + Send {
     fn get_barcode(
         &self,
@@ -79,6 +105,15 @@ pub trait BarcodeServiceTrait: Sync + Send {
             .pop())
     }
 
+    /// Reads a plain GTIN (EAN/UPC) or GS1 barcode, either raw scanner input or human readable
+    fn scan_barcode(
+        &self,
+        ctx: &ServiceContext,
+        scanned: &str,
+    ) -> Result<ScannedBarcode, ScanBarcodeError> {
+        scan_barcode(&ctx.connection, scanned)
+    }
+
     fn upsert_barcode(
         &self,
         ctx: &ServiceContext,
@@ -125,6 +160,57 @@ pub(crate) fn generate(
     })
 }
 
+pub fn scan_barcode(
+    connection: &StorageConnection,
+    scanned: &str,
+) -> Result<ScannedBarcode, ScanBarcodeError> {
+    let input = scanned.trim();
+    // ]E0 and ]E4 are the symbology identifiers of EAN-13/UPC-A and EAN-8
+    let plain = input
+        .strip_prefix("]E0")
+        .or_else(|| input.strip_prefix("]E4"))
+        .unwrap_or(input);
+
+    let gs1 = match gtin_14(plain) {
+        Some(gtin) => {
+            let mut gs1 = GS1::new();
+            gs1.insert("01", gtin);
+            gs1
+        }
+        None => GS1::parse(input).map_err(ScanBarcodeError::InvalidBarcode)?,
+    };
+
+    let barcode = match gs1.gtin() {
+        Some(gtin) => find_barcode_by_gtin_14(connection, &gtin)?,
+        None => None,
+    };
+
+    Ok(ScannedBarcode {
+        gtin: gs1.gtin(),
+        barcode,
+        batch: gs1.batch(),
+        expiry_date: gs1.expiry_date(),
+        serial_number: gs1.serial_number(),
+        count: gs1.count(),
+    })
+}
+
+// Barcodes can be stored as GTIN-8, 12 or 13, which are zero padded in GS1 barcodes
+fn find_barcode_by_gtin_14(
+    connection: &StorageConnection,
+    gtin: &str,
+) -> Result<Option<Barcode>, RepositoryError> {
+    let gtins: Vec<String> = [0, 1, 2, 6]
+        .iter()
+        .filter(|padding| gtin.len() > **padding && gtin[..**padding].chars().all(|c| c == '0'))
+        .map(|padding| gtin[*padding..].to_string())
+        .collect();
+
+    Ok(BarcodeRepository::new(connection)
+        .query_by_filter(BarcodeFilter::new().gtin(EqualFilter::equal_any(gtins)))?
+        .pop())
+}
+
 pub struct BarcodeService {}
 impl BarcodeServiceTrait for BarcodeService {}
 
@@ -139,3 +225,64 @@ fn validate(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        BarcodeRow,
+    };
+    use util::{inline_init, GS1ParseError};
+
+    use super::{scan_barcode, ScanBarcodeError};
+
+    #[actix_rt::test]
+    async fn scan_barcode_gs1() {
+        let (_, connection, _, _) = setup_all_with_data(
+            "scan_barcode_gs1",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.barcodes = vec![inline_init(|r: &mut BarcodeRow| {
+                    r.id = "gtin_13_barcode".to_string();
+                    r.gtin = "9501101020917".to_string();
+                    r.item_id = mock_item_a().id;
+                })];
+            }),
+        )
+        .await;
+
+        // Raw GS1 DataMatrix, barcode stored as GTIN-13
+        let scanned = scan_barcode(
+            &connection,
+            "]d201095011010209171725063010ABC123\u{1D}2112345",
+        )
+        .unwrap();
+        assert_eq!(scanned.gtin, Some("09501101020917".to_string()));
+        assert_eq!(
+            scanned.barcode.map(|barcode| barcode.barcode_row.item_id),
+            Some(mock_item_a().id)
+        );
+        assert_eq!(scanned.batch, Some("ABC123".to_string()));
+        assert_eq!(scanned.expiry_date, NaiveDate::from_ymd_opt(2025, 6, 30));
+        assert_eq!(scanned.serial_number, Some("12345".to_string()));
+
+        // Plain EAN-13
+        let scanned = scan_barcode(&connection, "9501101020917").unwrap();
+        assert_eq!(scanned.gtin, Some("09501101020917".to_string()));
+        assert!(scanned.barcode.is_some());
+        assert_eq!(scanned.batch, None);
+
+        // Unknown GTIN
+        let scanned = scan_barcode(&connection, "(01)00012345600012(10)B1").unwrap();
+        assert_eq!(scanned.barcode, None);
+
+        assert_eq!(
+            scan_barcode(&connection, "(01)00012345600013"),
+            Err(ScanBarcodeError::InvalidBarcode(
+                GS1ParseError::InvalidCheckDigit("01".to_string())
+            ))
+        );
+    }
+}
@@ -0,0 +1,611 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    convert::TryInto,
+};
+
+use chrono::{Duration, NaiveDateTime, NaiveTime};
+use repository::{
+    DatetimeFilter, EqualFilter, Pagination, RepositoryError, SensorFilter, SensorRowRepository,
+    SensorType, Sort, StorageConnection, TemperatureBreachConfigFilter,
+    TemperatureBreachConfigRepository, TemperatureBreachConfigRow, TemperatureBreachFilter,
+    TemperatureBreachRepository, TemperatureBreachRow, TemperatureBreachRowRepository,
+    TemperatureBreachType, TemperatureLogFilter, TemperatureLogRepository, TemperatureLogRow,
+    TemperatureLogRowRepository, TemperatureLogSortField,
+};
+use util::uuid::uuid;
+
+/// Logs within this many hours of the changed log are evaluated (cumulative breaches need the
+/// whole day), the window is then extended to the nearest log within range on either side so that
+/// consecutive breaches are never cut short
+const EVALUATION_WINDOW_HOURS: i64 = 24;
+const BOUNDARY_SEARCH_BATCH_SIZE: u32 = 500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DetectedBreach {
+    pub(crate) config: TemperatureBreachConfigRow,
+    pub(crate) start_datetime: NaiveDateTime,
+    /// None while temperature is still out of range
+    pub(crate) end_datetime: Option<NaiveDateTime>,
+    /// Time out of range, for cumulative breaches this excludes time back in range
+    pub(crate) duration: Duration,
+    pub(crate) log_ids: Vec<String>,
+}
+
+/// Evaluates logs of the sensor around the changed `temperature_log` against the active breach
+/// configs of the store, creates new breaches and extends or closes existing ones (including
+/// breaches supplied by sensor imports). Returns the created or updated breaches
+pub fn detect_temperature_breaches(
+    connection: &StorageConnection,
+    temperature_log: &TemperatureLogRow,
+) -> Result<Vec<TemperatureBreachRow>, RepositoryError> {
+    // 30 day recorders only log daily minimum and maximum, their breaches come from the recorder
+    let sensor = SensorRowRepository::new(connection).find_one_by_id(&temperature_log.sensor_id)?;
+    if sensor.is_some_and(|sensor| sensor.r#type == SensorType::ThirtyDayRecorder) {
+        return Ok(Vec::new());
+    }
+
+    let configs: Vec<TemperatureBreachConfigRow> =
+        TemperatureBreachConfigRepository::new(connection)
+            .query_by_filter(
+                TemperatureBreachConfigFilter::new()
+                    .store_id(EqualFilter::equal_to(&temperature_log.store_id))
+                    .is_active(true)
+                    .r#type(EqualFilter {
+                        equal_any: Some(vec![
+                            TemperatureBreachType::ColdConsecutive,
+                            TemperatureBreachType::ColdCumulative,
+                            TemperatureBreachType::HotConsecutive,
+                            TemperatureBreachType::HotCumulative,
+                        ]),
+                        ..Default::default()
+                    }),
+            )?
+            .into_iter()
+            .map(|config| config.temperature_breach_config_row)
+            .collect();
+
+    if configs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let window = Duration::hours(EVALUATION_WINDOW_HOURS);
+    let is_in_range = |log: &TemperatureLogRow| {
+        configs
+            .iter()
+            .all(|config| !is_out_of_range(config, log.temperature))
+    };
+    let from = boundary_log_datetime(
+        connection,
+        &temperature_log.sensor_id,
+        DatetimeFilter::before_or_equal_to(temperature_log.datetime - window),
+        true,
+        &is_in_range,
+    )?;
+    let to = boundary_log_datetime(
+        connection,
+        &temperature_log.sensor_id,
+        DatetimeFilter::after_or_equal_to(temperature_log.datetime + window),
+        false,
+        &is_in_range,
+    )?;
+
+    let logs = sensor_logs(connection, &temperature_log.sensor_id, from, to)?;
+    let detected = evaluate_breaches(&configs, &logs, from, to);
+
+    let breaches = reconcile_breaches(connection, temperature_log, &detected, from, to)?;
+    link_logs(connection, &logs, &detected, &breaches)?;
+
+    let changed = breaches
+        .into_iter()
+        .filter_map(|(breach, changed)| changed.then_some(breach))
+        .collect();
+    Ok(changed)
+}
+
+/// Hot breaches are above the maximum temperature, cold breaches below the minimum temperature
+fn is_out_of_range(config: &TemperatureBreachConfigRow, temperature: f64) -> bool {
+    use TemperatureBreachType::*;
+    match config.r#type {
+        HotConsecutive | HotCumulative => temperature > config.maximum_temperature,
+        ColdConsecutive | ColdCumulative => temperature < config.minimum_temperature,
+        Excursion => {
+            temperature < config.minimum_temperature || temperature > config.maximum_temperature
+        }
+    }
+}
+
+fn is_cumulative(r#type: &TemperatureBreachType) -> bool {
+    matches!(
+        r#type,
+        TemperatureBreachType::HotCumulative | TemperatureBreachType::ColdCumulative
+    )
+}
+
+/// Datetime of the nearest log (searching backwards or forwards from the filter) that is within
+/// range for all configs, None if there is no such log
+fn boundary_log_datetime(
+    connection: &StorageConnection,
+    sensor_id: &str,
+    datetime_filter: DatetimeFilter,
+    backwards: bool,
+    is_in_range: &dyn Fn(&TemperatureLogRow) -> bool,
+) -> Result<Option<NaiveDateTime>, RepositoryError> {
+    let repo = TemperatureLogRepository::new(connection);
+    let filter = TemperatureLogFilter::new()
+        .sensor(SensorFilter::new().id(EqualFilter::equal_to(sensor_id)))
+        .datetime(datetime_filter);
+
+    let mut offset = 0;
+    loop {
+        let logs = repo.query(
+            Pagination {
+                offset,
+                limit: BOUNDARY_SEARCH_BATCH_SIZE,
+            },
+            Some(filter.clone()),
+            Some(Sort {
+                key: TemperatureLogSortField::Datetime,
+                desc: Some(backwards),
+            }),
+        )?;
+        if logs.is_empty() {
+            return Ok(None);
+        }
+        if let Some(log) = logs
+            .iter()
+            .map(|log| &log.temperature_log_row)
+            .find(|log| is_in_range(log))
+        {
+            return Ok(Some(log.datetime));
+        }
+        offset += BOUNDARY_SEARCH_BATCH_SIZE;
+    }
+}
+
+/// Logs of the sensor between `from` and `to` (inclusive), oldest first
+fn sensor_logs(
+    connection: &StorageConnection,
+    sensor_id: &str,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Result<Vec<TemperatureLogRow>, RepositoryError> {
+    let mut filter = TemperatureLogFilter::new()
+        .sensor(SensorFilter::new().id(EqualFilter::equal_to(sensor_id)));
+    let datetime_filter = match (from, to) {
+        (Some(from), Some(to)) => Some(DatetimeFilter::date_range(from, to)),
+        (Some(from), None) => Some(DatetimeFilter::after_or_equal_to(from)),
+        (None, Some(to)) => Some(DatetimeFilter::before_or_equal_to(to)),
+        (None, None) => None,
+    };
+    if let Some(datetime_filter) = datetime_filter {
+        filter = filter.datetime(datetime_filter);
+    }
+
+    Ok(TemperatureLogRepository::new(connection)
+        .query(
+            Pagination::all(),
+            Some(filter),
+            Some(Sort {
+                key: TemperatureLogSortField::Datetime,
+                desc: Some(false),
+            }),
+        )?
+        .into_iter()
+        .map(|log| log.temperature_log_row)
+        .collect())
+}
+
+/// Breaches in the logs (sorted oldest first) for each config. Logs before `from` and after `to`
+/// are not known, so cumulative breaches are only reported for days fully within the logs
+pub(crate) fn evaluate_breaches(
+    configs: &[TemperatureBreachConfigRow],
+    logs: &[TemperatureLogRow],
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Vec<DetectedBreach> {
+    let mut breaches = Vec::new();
+    for config in configs {
+        let threshold = Duration::milliseconds(config.duration_milliseconds as i64);
+        let config_breaches = if is_cumulative(&config.r#type) {
+            cumulative_breaches(config, logs, from, to)
+        } else {
+            consecutive_breaches(config, logs)
+        };
+        breaches.extend(
+            config_breaches
+                .into_iter()
+                .filter(|breach| breach.duration >= threshold),
+        );
+    }
+    breaches
+}
+
+/// Each run of logs out of range, lasting until the next log within range
+fn consecutive_breaches(
+    config: &TemperatureBreachConfigRow,
+    logs: &[TemperatureLogRow],
+) -> Vec<DetectedBreach> {
+    let mut breaches = Vec::new();
+    let mut current: Option<DetectedBreach> = None;
+
+    for log in logs {
+        if is_out_of_range(config, log.temperature) {
+            let breach = current.get_or_insert_with(|| DetectedBreach {
+                config: config.clone(),
+                start_datetime: log.datetime,
+                end_datetime: None,
+                duration: Duration::zero(),
+                log_ids: Vec::new(),
+            });
+            breach.duration = log.datetime - breach.start_datetime;
+            breach.log_ids.push(log.id.clone());
+            continue;
+        }
+
+        if let Some(mut breach) = current.take() {
+            breach.end_datetime = Some(log.datetime);
+            breach.duration = log.datetime - breach.start_datetime;
+            breaches.push(breach);
+        }
+    }
+    breaches.extend(current);
+
+    breaches
+}
+
+/// Time out of range is added up per calendar day, each log out of range counts until the next
+/// log (or the end of the day)
+fn cumulative_breaches(
+    config: &TemperatureBreachConfigRow,
+    logs: &[TemperatureLogRow],
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Vec<DetectedBreach> {
+    let mut days = BTreeMap::new();
+
+    for (index, log) in logs.iter().enumerate() {
+        if !is_out_of_range(config, log.temperature) {
+            continue;
+        }
+        let day = log.datetime.date();
+        let day_end = (day + Duration::days(1)).and_time(NaiveTime::MIN);
+        let next_datetime = logs.get(index + 1).map(|next| next.datetime);
+
+        let breach = days.entry(day).or_insert_with(|| DetectedBreach {
+            config: config.clone(),
+            start_datetime: log.datetime,
+            end_datetime: None,
+            duration: Duration::zero(),
+            log_ids: Vec::new(),
+        });
+        if let Some(next_datetime) = next_datetime {
+            breach.duration = breach.duration + (next_datetime.min(day_end) - log.datetime);
+        }
+        breach.end_datetime = next_datetime.map(|next_datetime| next_datetime.min(day_end));
+        breach.log_ids.push(log.id.clone());
+    }
+
+    days.into_iter()
+        .filter(|(day, _)| {
+            let day_start = day.and_time(NaiveTime::MIN);
+            let day_end = day_start + Duration::days(1);
+            from.map_or(true, |from| from <= day_start) && to.map_or(true, |to| day_end <= to)
+        })
+        .map(|(_, breach)| breach)
+        .collect()
+}
+
+fn overlaps(breach: &TemperatureBreachRow, detected: &DetectedBreach) -> bool {
+    let starts_before_detected_end = detected
+        .end_datetime
+        .map_or(true, |end| breach.start_datetime <= end);
+    let ends_after_detected_start = breach
+        .end_datetime
+        .map_or(true, |end| end >= detected.start_datetime);
+    starts_before_detected_end && ends_after_detected_start
+}
+
+fn matches_config(breach: &TemperatureBreachRow, config: &TemperatureBreachConfigRow) -> bool {
+    breach.r#type == config.r#type
+        && breach.threshold_minimum == config.minimum_temperature
+        && breach.threshold_maximum == config.maximum_temperature
+        && breach.threshold_duration_milliseconds == config.duration_milliseconds
+}
+
+fn duration_milliseconds(duration: Duration) -> i32 {
+    duration.num_milliseconds().try_into().unwrap_or(i32::MAX)
+}
+
+/// Matches detected breaches with existing breaches of the sensor (same config thresholds and
+/// overlapping time), returns breach rows for each detected breach and whether it was upserted
+fn reconcile_breaches(
+    connection: &StorageConnection,
+    temperature_log: &TemperatureLogRow,
+    detected: &[DetectedBreach],
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+) -> Result<Vec<(TemperatureBreachRow, bool)>, RepositoryError> {
+    let mut filter = TemperatureBreachFilter::new()
+        .sensor(SensorFilter::new().id(EqualFilter::equal_to(&temperature_log.sensor_id)));
+    if let Some(to) = to {
+        filter = filter.start_datetime(DatetimeFilter::before_or_equal_to(to));
+    }
+    let existing: Vec<TemperatureBreachRow> = TemperatureBreachRepository::new(connection)
+        .query_by_filter(filter)?
+        .into_iter()
+        .map(|breach| breach.temperature_breach_row)
+        .filter(|breach| match (from, breach.end_datetime) {
+            (Some(from), Some(end)) => end >= from,
+            _ => true,
+        })
+        .collect();
+
+    let repo = TemperatureBreachRowRepository::new(connection);
+    let mut matched_ids = HashSet::new();
+    let mut result = Vec::new();
+    for breach in detected {
+        let existing_breach = existing.iter().find(|existing| {
+            !matched_ids.contains(&existing.id)
+                && matches_config(existing, &breach.config)
+                && overlaps(existing, breach)
+        });
+
+        let row = match existing_breach {
+            Some(existing) => {
+                matched_ids.insert(existing.id.clone());
+                TemperatureBreachRow {
+                    start_datetime: breach.start_datetime,
+                    end_datetime: breach.end_datetime,
+                    duration_milliseconds: duration_milliseconds(breach.duration),
+                    ..existing.clone()
+                }
+            }
+            None => TemperatureBreachRow {
+                id: uuid(),
+                duration_milliseconds: duration_milliseconds(breach.duration),
+                r#type: breach.config.r#type.clone(),
+                sensor_id: temperature_log.sensor_id.clone(),
+                location_id: temperature_log.location_id.clone(),
+                store_id: temperature_log.store_id.clone(),
+                start_datetime: breach.start_datetime,
+                end_datetime: breach.end_datetime,
+                unacknowledged: true,
+                threshold_minimum: breach.config.minimum_temperature,
+                threshold_maximum: breach.config.maximum_temperature,
+                threshold_duration_milliseconds: breach.config.duration_milliseconds,
+                comment: None,
+            },
+        };
+
+        let changed = existing_breach != Some(&row);
+        if changed {
+            repo.upsert_one(&row)?;
+        }
+        result.push((row, changed));
+    }
+
+    Ok(result)
+}
+
+/// Links logs without a breach to the breach they are part of, consecutive breaches first (same
+/// as sensor imports)
+fn link_logs(
+    connection: &StorageConnection,
+    logs: &[TemperatureLogRow],
+    detected: &[DetectedBreach],
+    breaches: &[(TemperatureBreachRow, bool)],
+) -> Result<(), RepositoryError> {
+    let unlinked: HashSet<&str> = logs
+        .iter()
+        .filter(|log| log.temperature_breach_id.is_none())
+        .map(|log| log.id.as_str())
+        .collect();
+
+    let mut ordered: Vec<(&DetectedBreach, &TemperatureBreachRow)> = detected
+        .iter()
+        .zip(breaches.iter().map(|(breach, _)| breach))
+        .collect();
+    ordered.sort_by_key(|(detected, _)| is_cumulative(&detected.config.r#type));
+
+    let mut log_ids_by_breach: HashMap<&str, Vec<String>> = HashMap::new();
+    let mut linked = HashSet::new();
+    for (detected, breach) in ordered {
+        for log_id in &detected.log_ids {
+            if unlinked.contains(log_id.as_str()) && linked.insert(log_id.as_str()) {
+                log_ids_by_breach
+                    .entry(breach.id.as_str())
+                    .or_default()
+                    .push(log_id.clone());
+            }
+        }
+    }
+
+    let repo = TemperatureLogRowRepository::new(connection);
+    for (breach_id, log_ids) in log_ids_by_breach {
+        repo.update_breach_id(breach_id, &log_ids)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        SensorRow, SensorRowRepository, TemperatureBreachConfigRowRepository,
+    };
+
+    use super::*;
+
+    fn datetime(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn log(id: &str, datetime: NaiveDateTime, temperature: f64) -> TemperatureLogRow {
+        TemperatureLogRow {
+            id: id.to_string(),
+            temperature,
+            sensor_id: "sensor".to_string(),
+            store_id: mock_store_a().id,
+            datetime,
+            ..Default::default()
+        }
+    }
+
+    fn config(
+        id: &str,
+        r#type: TemperatureBreachType,
+        duration_minutes: i32,
+    ) -> TemperatureBreachConfigRow {
+        TemperatureBreachConfigRow {
+            id: id.to_string(),
+            duration_milliseconds: duration_minutes * 60 * 1000,
+            r#type,
+            description: id.to_string(),
+            is_active: true,
+            store_id: mock_store_a().id,
+            minimum_temperature: 2.0,
+            maximum_temperature: 8.0,
+        }
+    }
+
+    #[test]
+    fn test_evaluate_breaches() {
+        let hot_consecutive = config("hot", TemperatureBreachType::HotConsecutive, 30);
+        let hot_cumulative = config("hot_cum", TemperatureBreachType::HotCumulative, 45);
+        let cold_consecutive = config("cold", TemperatureBreachType::ColdConsecutive, 30);
+        let logs = vec![
+            log("1", datetime(0, 0), 5.0),
+            // Hot for 20 minutes, too short for consecutive breach
+            log("2", datetime(1, 0), 9.0),
+            log("3", datetime(1, 20), 5.0),
+            // Hot for 40 minutes
+            log("4", datetime(2, 0), 10.0),
+            log("5", datetime(2, 20), 10.0),
+            log("6", datetime(2, 40), 5.0),
+            // Cold from 23:50 and still ongoing
+            log("7", datetime(23, 50), 1.0),
+        ];
+        let configs = vec![
+            hot_consecutive.clone(),
+            hot_cumulative.clone(),
+            cold_consecutive.clone(),
+        ];
+
+        let breaches = evaluate_breaches(&configs, &logs, None, None);
+        assert_eq!(
+            breaches,
+            vec![
+                DetectedBreach {
+                    config: hot_consecutive,
+                    start_datetime: datetime(2, 0),
+                    end_datetime: Some(datetime(2, 40)),
+                    duration: Duration::minutes(40),
+                    log_ids: vec!["4".to_string(), "5".to_string()],
+                },
+                // 20 + 40 minutes hot during the day
+                DetectedBreach {
+                    config: hot_cumulative.clone(),
+                    start_datetime: datetime(1, 0),
+                    end_datetime: Some(datetime(2, 40)),
+                    duration: Duration::minutes(60),
+                    log_ids: vec!["2".to_string(), "4".to_string(), "5".to_string()],
+                },
+            ]
+        );
+
+        // Day is only partially known
+        let breaches = evaluate_breaches(&[hot_cumulative], &logs, Some(datetime(0, 30)), None);
+        assert_eq!(breaches, vec![]);
+
+        // Ongoing cold breach
+        let mut logs = logs;
+        logs.push(log("8", datetime(23, 55), 0.0));
+        logs.push(log("9", datetime(23, 59), 0.0));
+        let later = |minutes| datetime(23, 50) + Duration::minutes(minutes);
+        logs.push(log("10", later(40), 0.0));
+        let breaches = evaluate_breaches(&[cold_consecutive.clone()], &logs, None, None);
+        assert_eq!(
+            breaches,
+            vec![DetectedBreach {
+                config: cold_consecutive,
+                start_datetime: datetime(23, 50),
+                end_datetime: None,
+                duration: Duration::minutes(40),
+                log_ids: vec![
+                    "7".to_string(),
+                    "8".to_string(),
+                    "9".to_string(),
+                    "10".to_string()
+                ],
+            }]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_detect_temperature_breaches() {
+        let (_, connection, _, _) = setup_all(
+            "test_detect_temperature_breaches",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+
+        SensorRowRepository::new(&connection)
+            .upsert_one(&SensorRow {
+                id: "sensor".to_string(),
+                serial: "sensor".to_string(),
+                store_id: mock_store_a().id,
+                is_active: true,
+                ..Default::default()
+            })
+            .unwrap();
+        TemperatureBreachConfigRowRepository::new(&connection)
+            .upsert_one(&config("hot", TemperatureBreachType::HotConsecutive, 30))
+            .unwrap();
+
+        let log_repo = TemperatureLogRowRepository::new(&connection);
+        let add_log = |log: TemperatureLogRow| {
+            log_repo.upsert_one(&log).unwrap();
+            detect_temperature_breaches(&connection, &log).unwrap()
+        };
+
+        assert_eq!(add_log(log("1", datetime(1, 0), 5.0)), vec![]);
+        assert_eq!(add_log(log("2", datetime(2, 0), 9.0)), vec![]);
+
+        // Breach is created once it's been hot long enough
+        let created = add_log(log("3", datetime(2, 30), 9.5));
+        assert_eq!(created.len(), 1);
+        let breach = created[0].clone();
+        assert_eq!(breach.start_datetime, datetime(2, 0));
+        assert_eq!(breach.end_datetime, None);
+        assert_eq!(breach.duration_milliseconds, 30 * 60 * 1000);
+        assert!(breach.unacknowledged);
+
+        // Extended
+        let extended = add_log(log("4", datetime(3, 0), 10.0));
+        assert_eq!(extended.len(), 1);
+        assert_eq!(extended[0].id, breach.id);
+        assert_eq!(extended[0].duration_milliseconds, 60 * 60 * 1000);
+
+        // Closed when back in range
+        let closed = add_log(log("5", datetime(3, 10), 6.0));
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].id, breach.id);
+        assert_eq!(closed[0].end_datetime, Some(datetime(3, 10)));
+
+        // Nothing changes when evaluated again
+        let log_3 = log_repo.find_one_by_id("3").unwrap().unwrap();
+        assert_eq!(
+            detect_temperature_breaches(&connection, &log_3).unwrap(),
+            vec![]
+        );
+        assert_eq!(log_3.temperature_breach_id, Some(breach.id.clone()));
+        let log_5 = log_repo.find_one_by_id("5").unwrap().unwrap();
+        assert_eq!(log_5.temperature_breach_id, None);
+    }
+}
@@ -38,6 +38,9 @@ pub fn insert_temperature_log(
                 .map_err(InsertTemperatureLogError::from)
         })
         .map_err(|error| error.to_inner_error())?;
+
+    // Breaches are detected by changelog processor
+    ctx.processors_trigger.trigger_changelog_processors();
     Ok(temperature_log)
 }
 
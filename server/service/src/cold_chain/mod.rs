@@ -22,8 +22,10 @@ use repository::temperature_breach::{
 use repository::temperature_log::{TemperatureLog, TemperatureLogFilter, TemperatureLogSort};
 use repository::{PaginationOption, StorageConnection};
 
+pub mod breach_detection;
 pub mod insert_temperature_breach;
 pub mod insert_temperature_log;
+pub mod notification;
 pub mod query_temperature_breach;
 pub mod query_temperature_log;
 pub mod update_temperature_breach;
@@ -0,0 +1,274 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use lettre::{
+    message::header::ContentType, transport::smtp::authentication::Credentials, AsyncSmtpTransport,
+    AsyncTransport, Message, Tokio1Executor,
+};
+use repository::{
+    TemperatureBreachType, TemperatureNotificationChannel, TemperatureNotificationLevel,
+};
+use serde::Serialize;
+
+use crate::settings::MailSettings;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(30);
+/// Directory within the server base dir the log channel writes files to
+const LOG_DIR: &str = "temperature_notifications";
+
+/// Temperature breach notification, posted as JSON by the webhook channel
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemperatureNotificationMessage {
+    /// Id to acknowledge the breach with (`acknowledgeTemperatureNotification`)
+    pub notification_id: String,
+    pub level: TemperatureNotificationLevel,
+    pub store_id: String,
+    pub temperature_breach_id: String,
+    pub breach_type: TemperatureBreachType,
+    pub sensor_name: String,
+    pub location_name: Option<String>,
+    /// UTC
+    pub start_datetime: NaiveDateTime,
+    /// UTC, None while the breach is ongoing
+    pub end_datetime: Option<NaiveDateTime>,
+    pub duration_minutes: i64,
+    pub threshold_minimum: f64,
+    pub threshold_maximum: f64,
+}
+
+impl TemperatureNotificationMessage {
+    pub fn subject(&self) -> String {
+        let prefix = match self.level {
+            TemperatureNotificationLevel::Initial => "Temperature breach",
+            TemperatureNotificationLevel::Escalation => "Unacknowledged temperature breach",
+        };
+        match &self.location_name {
+            Some(location_name) => format!("{prefix}: {} ({location_name})", self.sensor_name),
+            None => format!("{prefix}: {}", self.sensor_name),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        let breach_type = match self.breach_type {
+            TemperatureBreachType::ColdConsecutive => "Cold consecutive",
+            TemperatureBreachType::ColdCumulative => "Cold cumulative",
+            TemperatureBreachType::HotConsecutive => "Hot consecutive",
+            TemperatureBreachType::HotCumulative => "Hot cumulative",
+            TemperatureBreachType::Excursion => "Excursion",
+        };
+        let end = match self.end_datetime {
+            Some(end_datetime) => format!("{} UTC", end_datetime.format("%Y-%m-%d %H:%M")),
+            None => "ongoing".to_string(),
+        };
+
+        format!(
+            "{breach_type} breach of sensor {}{}\n\
+             Start: {} UTC\n\
+             End: {end}\n\
+             Duration: {} minutes\n\
+             Thresholds: {} to {} °C\n\n\
+             Acknowledge the breach in mSupply (notification {}).",
+            self.sensor_name,
+            self.location_name
+                .as_ref()
+                .map(|location_name| format!(" at {location_name}"))
+                .unwrap_or_default(),
+            self.start_datetime.format("%Y-%m-%d %H:%M"),
+            self.duration_minutes,
+            self.threshold_minimum,
+            self.threshold_maximum,
+            self.notification_id,
+        )
+    }
+}
+
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    async fn send(
+        &self,
+        recipient: &str,
+        message: &TemperatureNotificationMessage,
+    ) -> Result<(), String>;
+}
+
+/// Channels notifications are sent through, by channel type of the rule
+pub struct NotificationChannels {
+    email: Option<Box<dyn NotificationChannel>>,
+    webhook: Box<dyn NotificationChannel>,
+    log: Box<dyn NotificationChannel>,
+}
+
+impl NotificationChannels {
+    pub fn new(mail_settings: Option<&MailSettings>, base_dir: &Option<String>) -> Self {
+        let email = mail_settings.and_then(|settings| match EmailChannel::new(settings) {
+            Ok(channel) => Some(Box::new(channel) as Box<dyn NotificationChannel>),
+            Err(error) => {
+                log::error!("Invalid mail settings, email notifications are disabled: {error}");
+                None
+            }
+        });
+
+        NotificationChannels {
+            email,
+            webhook: Box::new(WebhookChannel::default()),
+            log: Box::new(LogChannel::new(base_dir)),
+        }
+    }
+
+    pub fn get(
+        &self,
+        channel: &TemperatureNotificationChannel,
+    ) -> Result<&dyn NotificationChannel, String> {
+        match channel {
+            TemperatureNotificationChannel::Email => self
+                .email
+                .as_deref()
+                .ok_or_else(|| "Mail settings are not configured".to_string()),
+            TemperatureNotificationChannel::Webhook => Ok(self.webhook.as_ref()),
+            TemperatureNotificationChannel::Log => Ok(self.log.as_ref()),
+        }
+    }
+}
+
+pub struct EmailChannel {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: String,
+}
+
+impl EmailChannel {
+    pub fn new(settings: &MailSettings) -> Result<Self, String> {
+        let builder = if settings.danger_allow_insecure {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&settings.host)
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&settings.host)
+                .map_err(|error| error.to_string())?
+        };
+        let builder = match (&settings.username, &settings.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username.clone(), password.clone()))
+            }
+            _ => builder,
+        };
+
+        Ok(EmailChannel {
+            transport: builder.port(settings.port).build(),
+            from: settings.from.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    async fn send(
+        &self,
+        recipient: &str,
+        message: &TemperatureNotificationMessage,
+    ) -> Result<(), String> {
+        let email = Message::builder()
+            .from(
+                self.from
+                    .parse()
+                    .map_err(|error| format!("Invalid sender: {error}"))?,
+            )
+            .to(recipient
+                .parse()
+                .map_err(|error| format!("Invalid recipient: {error}"))?)
+            .subject(message.subject())
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body())
+            .map_err(|error| error.to_string())?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|error| error.to_string())?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct WebhookChannel {
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl NotificationChannel for WebhookChannel {
+    async fn send(
+        &self,
+        recipient: &str,
+        message: &TemperatureNotificationMessage,
+    ) -> Result<(), String> {
+        self.client
+            .post(recipient)
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(message)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|error| error.to_string())?;
+        Ok(())
+    }
+}
+
+/// Appends notifications as JSON lines to the recipient file in the log directory of the server,
+/// or only logs them if the recipient is empty. Meant for testing rules
+pub struct LogChannel {
+    dir: PathBuf,
+}
+
+impl LogChannel {
+    pub fn new(base_dir: &Option<String>) -> Self {
+        let base_dir = match base_dir {
+            Some(base_dir) => PathBuf::from(base_dir),
+            None => std::env::current_dir().unwrap_or_default(),
+        };
+        LogChannel {
+            dir: base_dir.join(LOG_DIR),
+        }
+    }
+}
+
+/// Log recipients are file names within the log directory, paths are not allowed
+pub fn is_log_file_name(recipient: &str) -> bool {
+    if recipient.contains(['/', '\\']) {
+        return false;
+    }
+    let mut components = Path::new(recipient).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+#[async_trait]
+impl NotificationChannel for LogChannel {
+    async fn send(
+        &self,
+        recipient: &str,
+        message: &TemperatureNotificationMessage,
+    ) -> Result<(), String> {
+        log::info!("{}\n{}", message.subject(), message.body());
+        if recipient.is_empty() {
+            return Ok(());
+        }
+        if !is_log_file_name(recipient) {
+            return Err(format!("Invalid log file name: {recipient}"));
+        }
+
+        let line = serde_json::to_string(message).map_err(|error| error.to_string())?;
+        std::fs::create_dir_all(&self.dir).map_err(|error| error.to_string())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(recipient))
+            .map_err(|error| format!("Cannot open {recipient}: {error}"))?;
+        writeln!(file, "{line}").map_err(|error| error.to_string())
+    }
+}
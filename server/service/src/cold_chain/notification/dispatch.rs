@@ -0,0 +1,435 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use repository::{
+    EqualFilter, LocationRowRepository, RepositoryError, SensorRowRepository, StorageConnection,
+    TemperatureBreachFilter, TemperatureBreachRepository, TemperatureBreachRow,
+    TemperatureBreachType, TemperatureNotificationLevel, TemperatureNotificationRow,
+    TemperatureNotificationRowRepository, TemperatureNotificationRuleRow,
+    TemperatureNotificationRuleRowRepository, TemperatureNotificationStatus,
+};
+use util::uuid::uuid;
+
+use crate::{service_provider::ServiceProvider, settings::Settings};
+
+use super::channel::{NotificationChannels, TemperatureNotificationMessage};
+
+/// How often to check for breaches to notify about
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+/// Breaches that ended before this are not notified, e.g. when a rule is added
+const NOTIFICATION_LOOKBACK_HOURS: i64 = 24;
+/// Failed notifications are retried on every check until they reached this many attempts
+const MAX_ATTEMPTS: i32 = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueTemperatureNotification {
+    pub rule: TemperatureNotificationRuleRow,
+    pub breach: TemperatureBreachRow,
+    pub level: TemperatureNotificationLevel,
+    pub recipient: String,
+    /// Previous failed attempt
+    pub previous: Option<TemperatureNotificationRow>,
+}
+
+/// Notifications to send for unacknowledged breaches matching the active rules:
+/// - initial notification once the breach lasted the rule's minimum duration
+/// - escalation once the breach is still unacknowledged the rule's escalation period after the
+///   initial notification was sent
+pub fn get_due_temperature_notifications(
+    connection: &StorageConnection,
+    now: NaiveDateTime,
+) -> Result<Vec<DueTemperatureNotification>, RepositoryError> {
+    let rules = TemperatureNotificationRuleRowRepository::new(connection).find_many_active()?;
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let store_ids = rules.iter().map(|rule| rule.store_id.clone()).collect();
+    let lookback = now - Duration::hours(NOTIFICATION_LOOKBACK_HOURS);
+    let breaches: Vec<TemperatureBreachRow> = TemperatureBreachRepository::new(connection)
+        .query_by_filter(
+            TemperatureBreachFilter::new()
+                .store_id(EqualFilter::equal_any(store_ids))
+                .unacknowledged(true),
+        )?
+        .into_iter()
+        .map(|breach| breach.temperature_breach_row)
+        .filter(|breach| breach.end_datetime.map_or(true, |end| end >= lookback))
+        .collect();
+    if breaches.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let breach_ids: Vec<String> = breaches.iter().map(|breach| breach.id.clone()).collect();
+    let notifications: HashMap<(String, String, TemperatureNotificationLevel), _> =
+        TemperatureNotificationRowRepository::new(connection)
+            .find_many_by_breach_ids(&breach_ids)?
+            .into_iter()
+            .map(|notification| {
+                (
+                    (
+                        notification.rule_id.clone(),
+                        notification.temperature_breach_id.clone(),
+                        notification.level.clone(),
+                    ),
+                    notification,
+                )
+            })
+            .collect();
+    let notification = |rule: &TemperatureNotificationRuleRow,
+                        breach: &TemperatureBreachRow,
+                        level: TemperatureNotificationLevel| {
+        notifications.get(&(rule.id.clone(), breach.id.clone(), level))
+    };
+
+    let mut result = Vec::new();
+    for rule in &rules {
+        for breach in breaches.iter().filter(|breach| rule_matches(rule, breach)) {
+            match notification(rule, breach, TemperatureNotificationLevel::Initial) {
+                Some(initial) if initial.status == TemperatureNotificationStatus::Sent => {
+                    let Some(escalation_after) = rule.escalation_after_milliseconds else {
+                        continue;
+                    };
+                    let sent_datetime = initial.sent_datetime.unwrap_or(initial.created_datetime);
+                    if now - sent_datetime < Duration::milliseconds(escalation_after as i64) {
+                        continue;
+                    }
+
+                    let escalation =
+                        notification(rule, breach, TemperatureNotificationLevel::Escalation);
+                    if is_due(escalation) {
+                        result.push(DueTemperatureNotification {
+                            rule: rule.clone(),
+                            breach: breach.clone(),
+                            level: TemperatureNotificationLevel::Escalation,
+                            recipient: rule
+                                .escalation_recipient
+                                .clone()
+                                .unwrap_or_else(|| rule.recipient.clone()),
+                            previous: escalation.cloned(),
+                        });
+                    }
+                }
+                initial => {
+                    let minimum_duration =
+                        Duration::milliseconds(rule.minimum_duration_milliseconds as i64);
+                    if breach_duration(breach, now) >= minimum_duration && is_due(initial) {
+                        result.push(DueTemperatureNotification {
+                            rule: rule.clone(),
+                            breach: breach.clone(),
+                            level: TemperatureNotificationLevel::Initial,
+                            recipient: rule.recipient.clone(),
+                            previous: initial.cloned(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn rule_matches(rule: &TemperatureNotificationRuleRow, breach: &TemperatureBreachRow) -> bool {
+    rule.store_id == breach.store_id
+        && rule.location_id.as_ref().map_or(true, |location_id| {
+            breach.location_id.as_ref() == Some(location_id)
+        })
+        && rule
+            .breach_type
+            .as_ref()
+            .map_or(true, |breach_type| *breach_type == breach.r#type)
+}
+
+/// Duration of the breach so far, ongoing consecutive breaches last until now
+fn breach_duration(breach: &TemperatureBreachRow, now: NaiveDateTime) -> Duration {
+    let recorded = Duration::milliseconds(breach.duration_milliseconds as i64);
+    match (breach.end_datetime, &breach.r#type) {
+        (None, TemperatureBreachType::HotConsecutive | TemperatureBreachType::ColdConsecutive) => {
+            recorded.max(now - breach.start_datetime)
+        }
+        _ => recorded,
+    }
+}
+
+fn is_due(notification: Option<&TemperatureNotificationRow>) -> bool {
+    match notification {
+        None => true,
+        Some(notification) => {
+            notification.status == TemperatureNotificationStatus::Failed
+                && notification.attempts < MAX_ATTEMPTS
+        }
+    }
+}
+
+/// Sends due notifications through the rule's channel and records the result, failed
+/// notifications are retried on the next call
+pub async fn send_due_temperature_notifications(
+    connection: &StorageConnection,
+    channels: &NotificationChannels,
+    now: NaiveDateTime,
+) -> Result<(), RepositoryError> {
+    let repository = TemperatureNotificationRowRepository::new(connection);
+
+    for due in get_due_temperature_notifications(connection, now)? {
+        let id = due
+            .previous
+            .as_ref()
+            .map(|previous| previous.id.clone())
+            .unwrap_or_else(uuid);
+        let message = generate_message(connection, &id, &due)?;
+
+        let result = match channels.get(&due.rule.channel) {
+            Ok(channel) => channel.send(&due.recipient, &message).await,
+            Err(error) => Err(error),
+        };
+        if let Err(error) = &result {
+            log::error!(
+                "Problem sending temperature notification {} ({}): {}",
+                id,
+                due.recipient,
+                error
+            );
+        }
+
+        let (attempts, created_datetime) = match &due.previous {
+            Some(previous) => (previous.attempts, previous.created_datetime),
+            None => (0, now),
+        };
+        repository.upsert_one(&TemperatureNotificationRow {
+            id,
+            rule_id: due.rule.id,
+            temperature_breach_id: due.breach.id,
+            level: due.level,
+            status: match result {
+                Ok(_) => TemperatureNotificationStatus::Sent,
+                Err(_) => TemperatureNotificationStatus::Failed,
+            },
+            attempts: attempts + 1,
+            created_datetime,
+            sent_datetime: result.is_ok().then_some(now),
+            error: result.err(),
+        })?;
+    }
+
+    Ok(())
+}
+
+fn generate_message(
+    connection: &StorageConnection,
+    notification_id: &str,
+    DueTemperatureNotification { breach, level, .. }: &DueTemperatureNotification,
+) -> Result<TemperatureNotificationMessage, RepositoryError> {
+    let sensor_name = SensorRowRepository::new(connection)
+        .find_one_by_id(&breach.sensor_id)?
+        .map(|sensor| sensor.name)
+        .unwrap_or_else(|| breach.sensor_id.clone());
+    let location_name = match &breach.location_id {
+        Some(location_id) => LocationRowRepository::new(connection)
+            .find_one_by_id(location_id)?
+            .map(|location| location.name),
+        None => None,
+    };
+
+    Ok(TemperatureNotificationMessage {
+        notification_id: notification_id.to_string(),
+        level: level.clone(),
+        store_id: breach.store_id.clone(),
+        temperature_breach_id: breach.id.clone(),
+        breach_type: breach.r#type.clone(),
+        sensor_name,
+        location_name,
+        start_datetime: breach.start_datetime,
+        end_datetime: breach.end_datetime,
+        duration_minutes: Duration::milliseconds(breach.duration_milliseconds as i64).num_minutes(),
+        threshold_minimum: breach.threshold_minimum,
+        threshold_maximum: breach.threshold_maximum,
+    })
+}
+
+/// Periodically sends notifications for temperature breaches
+pub struct TemperatureNotificationDispatcher {
+    channels: NotificationChannels,
+}
+
+impl TemperatureNotificationDispatcher {
+    pub fn new(settings: &Settings) -> Self {
+        TemperatureNotificationDispatcher {
+            channels: NotificationChannels::new(settings.mail.as_ref(), &settings.server.base_dir),
+        }
+    }
+
+    /// TemperatureNotificationDispatcher entry point, this method is meant to be run within main
+    /// `select!` macro
+    pub async fn run(self, service_provider: Arc<ServiceProvider>) {
+        loop {
+            if let Err(error) = self.dispatch(&service_provider).await {
+                log::error!("Problem sending temperature notifications: {:#}", error);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    }
+
+    async fn dispatch(&self, service_provider: &ServiceProvider) -> anyhow::Result<()> {
+        let connection = service_provider.connection()?;
+        send_due_temperature_notifications(&connection, &self.channels, Utc::now().naive_utc())
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_temperature_breach_1, MockDataInserts},
+        test_db::setup_all,
+        TemperatureBreachRowRepository, TemperatureNotificationChannel,
+    };
+
+    use crate::{
+        cold_chain::notification::acknowledge_temperature_notification,
+        service_provider::ServiceProvider,
+    };
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_temperature_notifications() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "test_temperature_notifications",
+            MockDataInserts::none()
+                .names()
+                .stores()
+                .locations()
+                .sensors()
+                .temperature_breaches(),
+        )
+        .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let base_dir = Some(dir.path().to_string_lossy().to_string());
+        let log_file = dir
+            .path()
+            .join("temperature_notifications")
+            .join("notifications.log");
+        let breach = mock_temperature_breach_1();
+        let now = breach.end_datetime.unwrap() + Duration::hours(1);
+
+        let rule = |id: &str| TemperatureNotificationRuleRow {
+            id: id.to_string(),
+            store_id: breach.store_id.clone(),
+            channel: TemperatureNotificationChannel::Log,
+            recipient: "notifications.log".to_string(),
+            is_active: true,
+            ..Default::default()
+        };
+        let rule_repo = TemperatureNotificationRuleRowRepository::new(&connection);
+        for rule in [
+            TemperatureNotificationRuleRow {
+                escalation_after_milliseconds: Some(30 * 60 * 1000),
+                ..rule("log")
+            },
+            // Fails, mail is not configured
+            TemperatureNotificationRuleRow {
+                channel: TemperatureNotificationChannel::Email,
+                recipient: "cold-chain@example.org".to_string(),
+                breach_type: Some(TemperatureBreachType::HotConsecutive),
+                ..rule("email")
+            },
+            // Not matching: breach is too short, different type or inactive
+            TemperatureNotificationRuleRow {
+                minimum_duration_milliseconds: 60 * 60 * 1000,
+                ..rule("too_short")
+            },
+            TemperatureNotificationRuleRow {
+                breach_type: Some(TemperatureBreachType::ColdConsecutive),
+                ..rule("cold")
+            },
+            TemperatureNotificationRuleRow {
+                is_active: false,
+                ..rule("inactive")
+            },
+        ] {
+            rule_repo.upsert_one(&rule).unwrap();
+        }
+
+        let due_rule_ids = |now| {
+            let mut due: Vec<(String, TemperatureNotificationLevel)> =
+                get_due_temperature_notifications(&connection, now)
+                    .unwrap()
+                    .into_iter()
+                    .map(|due| (due.rule.id, due.level))
+                    .collect();
+            due.sort_by(|a, b| a.0.cmp(&b.0));
+            due
+        };
+        assert_eq!(
+            due_rule_ids(now),
+            vec![
+                ("email".to_string(), TemperatureNotificationLevel::Initial),
+                ("log".to_string(), TemperatureNotificationLevel::Initial)
+            ]
+        );
+
+        let channels = NotificationChannels::new(None, &base_dir);
+        send_due_temperature_notifications(&connection, &channels, now)
+            .await
+            .unwrap();
+        let logged = std::fs::read_to_string(&log_file).unwrap();
+        assert_eq!(logged.lines().count(), 1);
+        assert!(logged.contains(&breach.id));
+
+        let notifications = TemperatureNotificationRowRepository::new(&connection)
+            .find_many_by_breach_ids(&[breach.id.clone()])
+            .unwrap();
+        let email = notifications
+            .iter()
+            .find(|notification| notification.rule_id == "email")
+            .unwrap();
+        assert_eq!(email.status, TemperatureNotificationStatus::Failed);
+        assert_eq!(email.attempts, 1);
+        assert_eq!(
+            email.error,
+            Some("Mail settings are not configured".to_string())
+        );
+
+        // Failed notification is retried, sent one is escalated after 30 minutes
+        assert_eq!(
+            due_rule_ids(now),
+            vec![("email".to_string(), TemperatureNotificationLevel::Initial)]
+        );
+        assert_eq!(
+            due_rule_ids(now + Duration::minutes(31)),
+            vec![
+                ("email".to_string(), TemperatureNotificationLevel::Initial),
+                ("log".to_string(), TemperatureNotificationLevel::Escalation)
+            ]
+        );
+
+        // Acknowledged breaches are not notified anymore
+        let service_provider = ServiceProvider::new(connection_manager, "");
+        let ctx = service_provider
+            .context(breach.store_id.clone(), "user".to_string())
+            .unwrap();
+        let log_notification = notifications
+            .iter()
+            .find(|notification| notification.rule_id == "log")
+            .unwrap();
+        acknowledge_temperature_notification(
+            &ctx,
+            &log_notification.id,
+            Some("Fridge door was open".to_string()),
+        )
+        .unwrap();
+        let acknowledged = TemperatureBreachRowRepository::new(&connection)
+            .find_one_by_id(&breach.id)
+            .unwrap()
+            .unwrap();
+        assert!(!acknowledged.unacknowledged);
+        assert_eq!(
+            acknowledged.comment,
+            Some("Fridge door was open".to_string())
+        );
+        assert_eq!(due_rule_ids(now + Duration::minutes(31)), vec![]);
+    }
+}
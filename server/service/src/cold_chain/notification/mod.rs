@@ -0,0 +1,315 @@
+use repository::{
+    temperature_breach::TemperatureBreach, LocationRowRepository, RepositoryError,
+    StorageConnection, TemperatureBreachRow, TemperatureBreachRowRepository, TemperatureBreachType,
+    TemperatureNotificationChannel, TemperatureNotificationRowRepository,
+    TemperatureNotificationRuleRow, TemperatureNotificationRuleRowRepository,
+};
+
+use crate::{service_provider::ServiceContext, validate::check_store_id_matches};
+
+use self::channel::is_log_file_name;
+use super::update_temperature_breach::{
+    update_temperature_breach, UpdateTemperatureBreach, UpdateTemperatureBreachError,
+};
+
+pub mod channel;
+pub mod dispatch;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpsertTemperatureNotificationRule {
+    pub id: String,
+    pub location_id: Option<String>,
+    pub breach_type: Option<TemperatureBreachType>,
+    pub minimum_duration_milliseconds: i32,
+    pub channel: TemperatureNotificationChannel,
+    pub recipient: String,
+    pub escalation_after_milliseconds: Option<i32>,
+    pub escalation_recipient: Option<String>,
+    pub is_active: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertTemperatureNotificationRuleError {
+    RuleDoesNotBelongToCurrentStore,
+    LocationDoesNotExist,
+    InvalidDuration,
+    InvalidRecipient(String),
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeleteTemperatureNotificationRuleError {
+    RuleDoesNotExist,
+    RuleDoesNotBelongToCurrentStore,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AcknowledgeTemperatureNotificationError {
+    NotificationDoesNotExist,
+    TemperatureBreachDoesNotExist,
+    UpdateTemperatureBreach(UpdateTemperatureBreachError),
+    DatabaseError(RepositoryError),
+}
+
+/// Creates or updates a notification rule for breaches of the current store
+pub fn upsert_temperature_notification_rule(
+    ctx: &ServiceContext,
+    input: UpsertTemperatureNotificationRule,
+) -> Result<TemperatureNotificationRuleRow, UpsertTemperatureNotificationRuleError> {
+    let rule = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate_upsert(connection, &ctx.store_id, &input)?;
+
+            let UpsertTemperatureNotificationRule {
+                id,
+                location_id,
+                breach_type,
+                minimum_duration_milliseconds,
+                channel,
+                recipient,
+                escalation_after_milliseconds,
+                escalation_recipient,
+                is_active,
+            } = input;
+
+            let rule = TemperatureNotificationRuleRow {
+                id,
+                store_id: ctx.store_id.clone(),
+                location_id,
+                breach_type,
+                minimum_duration_milliseconds,
+                channel,
+                recipient: recipient.trim().to_string(),
+                escalation_after_milliseconds,
+                escalation_recipient: escalation_recipient
+                    .map(|recipient| recipient.trim().to_string())
+                    .filter(|recipient| !recipient.is_empty()),
+                is_active,
+            };
+            TemperatureNotificationRuleRowRepository::new(connection).upsert_one(&rule)?;
+
+            Ok(rule)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(rule)
+}
+
+fn validate_upsert(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpsertTemperatureNotificationRule,
+) -> Result<(), UpsertTemperatureNotificationRuleError> {
+    use UpsertTemperatureNotificationRuleError::*;
+
+    if let Some(existing) =
+        TemperatureNotificationRuleRowRepository::new(connection).find_one_by_id(&input.id)?
+    {
+        if !check_store_id_matches(&existing.store_id, store_id) {
+            return Err(RuleDoesNotBelongToCurrentStore);
+        }
+    }
+
+    if let Some(location_id) = &input.location_id {
+        LocationRowRepository::new(connection)
+            .find_one_by_id(location_id)?
+            .filter(|location| check_store_id_matches(&location.store_id, store_id))
+            .ok_or(LocationDoesNotExist)?;
+    }
+
+    if input.minimum_duration_milliseconds < 0
+        || input
+            .escalation_after_milliseconds
+            .is_some_and(|escalation_after| escalation_after <= 0)
+    {
+        return Err(InvalidDuration);
+    }
+
+    validate_recipient(&input.channel, &input.recipient).map_err(InvalidRecipient)?;
+    if let Some(escalation_recipient) = &input.escalation_recipient {
+        if !escalation_recipient.trim().is_empty() {
+            validate_recipient(&input.channel, escalation_recipient).map_err(InvalidRecipient)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_recipient(
+    channel: &TemperatureNotificationChannel,
+    recipient: &str,
+) -> Result<(), String> {
+    let recipient = recipient.trim();
+    match channel {
+        TemperatureNotificationChannel::Email => recipient
+            .parse::<lettre::Address>()
+            .map(|_| ())
+            .map_err(|error| format!("{recipient}: {error}")),
+        TemperatureNotificationChannel::Webhook => match url::Url::parse(recipient) {
+            Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Ok(()),
+            Ok(_) => Err(format!("{recipient}: must be a http or https URL")),
+            Err(error) => Err(format!("{recipient}: {error}")),
+        },
+        // Empty recipient only logs notifications
+        TemperatureNotificationChannel::Log => {
+            if recipient.is_empty() || is_log_file_name(recipient) {
+                Ok(())
+            } else {
+                Err(format!("{recipient}: must be a file name without a path"))
+            }
+        }
+    }
+}
+
+/// Deletes a notification rule and its notification history
+pub fn delete_temperature_notification_rule(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<String, DeleteTemperatureNotificationRuleError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = TemperatureNotificationRuleRowRepository::new(connection);
+            let rule = repository
+                .find_one_by_id(&id)?
+                .ok_or(DeleteTemperatureNotificationRuleError::RuleDoesNotExist)?;
+            if !check_store_id_matches(&rule.store_id, &ctx.store_id) {
+                return Err(
+                    DeleteTemperatureNotificationRuleError::RuleDoesNotBelongToCurrentStore,
+                );
+            }
+            TemperatureNotificationRowRepository::new(connection).delete_by_rule_id(&id)?;
+            repository.delete(&id)?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(id)
+}
+
+pub fn get_temperature_notification_rules(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<Vec<TemperatureNotificationRuleRow>, RepositoryError> {
+    TemperatureNotificationRuleRowRepository::new(&ctx.connection).find_many_by_store_id(store_id)
+}
+
+/// Acknowledges the breach a notification was sent for, the breach is updated through
+/// `update_temperature_breach`
+pub fn acknowledge_temperature_notification(
+    ctx: &ServiceContext,
+    notification_id: &str,
+    comment: Option<String>,
+) -> Result<TemperatureBreach, AcknowledgeTemperatureNotificationError> {
+    use AcknowledgeTemperatureNotificationError::*;
+
+    let notification = TemperatureNotificationRowRepository::new(&ctx.connection)
+        .find_one_by_id(notification_id)?
+        .ok_or(NotificationDoesNotExist)?;
+    let TemperatureBreachRow {
+        id,
+        duration_milliseconds,
+        r#type,
+        sensor_id,
+        location_id,
+        store_id: _,
+        start_datetime,
+        end_datetime,
+        unacknowledged: _,
+        threshold_minimum,
+        threshold_maximum,
+        threshold_duration_milliseconds,
+        comment: existing_comment,
+    } = TemperatureBreachRowRepository::new(&ctx.connection)
+        .find_one_by_id(&notification.temperature_breach_id)?
+        .ok_or(TemperatureBreachDoesNotExist)?;
+
+    update_temperature_breach(
+        ctx,
+        UpdateTemperatureBreach {
+            id,
+            duration_milliseconds,
+            r#type,
+            sensor_id,
+            location_id,
+            start_datetime,
+            end_datetime,
+            unacknowledged: false,
+            threshold_minimum,
+            threshold_maximum,
+            threshold_duration_milliseconds,
+            comment: comment.or(existing_comment),
+        },
+    )
+    .map_err(UpdateTemperatureBreach)
+}
+
+impl From<RepositoryError> for UpsertTemperatureNotificationRuleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertTemperatureNotificationRuleError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeleteTemperatureNotificationRuleError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteTemperatureNotificationRuleError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for AcknowledgeTemperatureNotificationError {
+    fn from(error: RepositoryError) -> Self {
+        AcknowledgeTemperatureNotificationError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        TemperatureNotificationChannel,
+    };
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn upsert_temperature_notification_rule_log_recipient() {
+        let (_, _, connection_manager, _) = setup_all(
+            "upsert_temperature_notification_rule_log_recipient",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+
+        let input = |recipient: &str| UpsertTemperatureNotificationRule {
+            id: "log_rule".to_string(),
+            channel: TemperatureNotificationChannel::Log,
+            recipient: recipient.to_string(),
+            is_active: true,
+            ..Default::default()
+        };
+
+        for recipient in [
+            "/etc/passwd",
+            "../notifications.log",
+            "logs\\notifications.log",
+        ] {
+            assert!(matches!(
+                upsert_temperature_notification_rule(&context, input(recipient)),
+                Err(UpsertTemperatureNotificationRuleError::InvalidRecipient(_))
+            ));
+        }
+
+        let rule =
+            upsert_temperature_notification_rule(&context, input("notifications.log")).unwrap();
+        assert_eq!(rule.recipient, "notifications.log");
+        assert!(upsert_temperature_notification_rule(&context, input("")).is_ok());
+    }
+}
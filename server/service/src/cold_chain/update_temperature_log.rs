@@ -40,6 +40,9 @@ pub fn update_temperature_log(
                 .map_err(UpdateTemperatureLogError::from)
         })
         .map_err(|error| error.to_inner_error())?;
+
+    // Breaches are detected by changelog processor
+    ctx.processors_trigger.trigger_changelog_processors();
     Ok(temperature_log)
 }
 
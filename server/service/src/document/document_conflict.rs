@@ -0,0 +1,155 @@
+use chrono::Utc;
+use repository::{
+    Document, DocumentConflictRow, DocumentConflictRowRepository, DocumentRepository,
+    RepositoryError,
+};
+
+use crate::{service_provider::ServiceContext, sync::integrate_document::sync_upsert_document};
+
+use super::{
+    document_service::{json_validator, validate_json, DocumentInsertError},
+    raw_document::RawDocument,
+};
+
+pub struct DocumentConflict {
+    pub row: DocumentConflictRow,
+    /// JSON pointers to the values changed differently in both versions
+    pub conflicting_paths: Vec<String>,
+    pub base: Option<Document>,
+    pub left: Document,
+    pub right: Document,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ResolveDocumentConflict {
+    pub id: String,
+    /// Data of the resolved document version
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ResolveDocumentConflictError {
+    ConflictDoesNotExist,
+    ConflictAlreadyResolved,
+    NotAllowedToMutateDocument,
+    /// Input data doesn't match the json schema of the document
+    InvalidDataSchema(Vec<String>),
+    DataSchemaDoesNotExist,
+    DatabaseError(RepositoryError),
+    InternalError(String),
+}
+
+impl From<RepositoryError> for ResolveDocumentConflictError {
+    fn from(err: RepositoryError) -> Self {
+        ResolveDocumentConflictError::DatabaseError(err)
+    }
+}
+
+impl From<DocumentInsertError> for ResolveDocumentConflictError {
+    fn from(err: DocumentInsertError) -> Self {
+        match err {
+            DocumentInsertError::NotAllowedToMutateDocument => {
+                ResolveDocumentConflictError::NotAllowedToMutateDocument
+            }
+            DocumentInsertError::InvalidDataSchema(errors) => {
+                ResolveDocumentConflictError::InvalidDataSchema(errors)
+            }
+            DocumentInsertError::DataSchemaDoesNotExist => {
+                ResolveDocumentConflictError::DataSchemaDoesNotExist
+            }
+            DocumentInsertError::DatabaseError(err) => {
+                ResolveDocumentConflictError::DatabaseError(err)
+            }
+            DocumentInsertError::InvalidParent(err) | DocumentInsertError::InternalError(err) => {
+                ResolveDocumentConflictError::InternalError(err)
+            }
+        }
+    }
+}
+
+fn find_document(repo: &DocumentRepository, id: &str) -> Result<Document, RepositoryError> {
+    repo.find_one_by_id(id)?
+        .ok_or_else(|| RepositoryError::as_db_error("Conflicting document not found", id))
+}
+
+/// Unresolved conflicts of documents in the allowed contexts
+pub(crate) fn document_conflicts(
+    ctx: &ServiceContext,
+    allowed_ctx: &[String],
+) -> Result<Vec<DocumentConflict>, RepositoryError> {
+    let repo = DocumentRepository::new(&ctx.connection);
+    DocumentConflictRowRepository::new(&ctx.connection)
+        .find_unresolved_by_context_ids(allowed_ctx)?
+        .into_iter()
+        .map(|row| -> Result<DocumentConflict, RepositoryError> {
+            let base = match &row.base_document_id {
+                Some(id) => repo.find_one_by_id(id)?,
+                None => None,
+            };
+            Ok(DocumentConflict {
+                conflicting_paths: serde_json::from_str(&row.conflicting_paths).unwrap_or_default(),
+                base,
+                left: find_document(&repo, &row.left_document_id)?,
+                right: find_document(&repo, &row.right_document_id)?,
+                row,
+            })
+        })
+        .collect()
+}
+
+/// Creates a document version with both conflicting versions as parents
+pub(crate) fn resolve_document_conflict(
+    ctx: &ServiceContext,
+    user_id: &str,
+    input: ResolveDocumentConflict,
+    allowed_ctx: &[String],
+) -> Result<Document, ResolveDocumentConflictError> {
+    let document = ctx
+        .connection
+        .transaction_sync(|con| {
+            let conflict = DocumentConflictRowRepository::new(con)
+                .find_one_by_id(&input.id)?
+                .ok_or(ResolveDocumentConflictError::ConflictDoesNotExist)?;
+            if conflict.resolved_datetime.is_some() {
+                return Err(ResolveDocumentConflictError::ConflictAlreadyResolved);
+            }
+            if !allowed_ctx.contains(&conflict.context_id) {
+                return Err(ResolveDocumentConflictError::NotAllowedToMutateDocument);
+            }
+
+            let repo = DocumentRepository::new(con);
+            let left = find_document(&repo, &conflict.left_document_id)?;
+            let right = find_document(&repo, &conflict.right_document_id)?;
+            let later = if left.datetime > right.datetime {
+                &left
+            } else {
+                &right
+            };
+
+            let raw = RawDocument {
+                name: conflict.document_name.clone(),
+                parents: vec![left.id.clone(), right.id.clone()],
+                author: user_id.to_string(),
+                datetime: Utc::now(),
+                r#type: later.r#type.clone(),
+                data: input.data,
+                form_schema_id: later.form_schema_id.clone(),
+                status: later.status.clone(),
+                owner_name_id: later.owner_name_id.clone(),
+                context_id: later.context_id.clone(),
+            };
+            if let Some(validator) = json_validator(con, &raw)? {
+                validate_json(&validator, &raw.data)
+                    .map_err(ResolveDocumentConflictError::InvalidDataSchema)?;
+            }
+            let document = raw
+                .finalise()
+                .map_err(ResolveDocumentConflictError::InternalError)?;
+
+            // Also marks the conflict as resolved and updates the records derived from the document
+            sync_upsert_document(con, &document)?;
+            Ok(document)
+        })
+        .map_err(|err| err.to_inner_error())?;
+    Ok(document)
+}
@@ -9,7 +9,14 @@ use crate::{
     get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
 };
 
-use super::raw_document::RawDocument;
+use super::{
+    document_conflict::{
+        document_conflicts, resolve_document_conflict, DocumentConflict, ResolveDocumentConflict,
+        ResolveDocumentConflictError,
+    },
+    merge::resolve_document_conflicts,
+    raw_document::RawDocument,
+};
 
 pub const MAX_LIMIT: u32 = 1000;
 pub const MIN_LIMIT: u32 = 1;
@@ -161,6 +168,24 @@ pub trait DocumentServiceTrait: Sync + Send {
             .map_err(|err| err.to_inner_error())?;
         Ok(document)
     }
+
+    fn document_conflicts(
+        &self,
+        ctx: &ServiceContext,
+        allowed_ctx: &[String],
+    ) -> Result<Vec<DocumentConflict>, RepositoryError> {
+        document_conflicts(ctx, allowed_ctx)
+    }
+
+    fn resolve_document_conflict(
+        &self,
+        ctx: &ServiceContext,
+        user_id: &str,
+        input: ResolveDocumentConflict,
+        allowed_ctx: &[String],
+    ) -> Result<Document, ResolveDocumentConflictError> {
+        resolve_document_conflict(ctx, user_id, input, allowed_ctx)
+    }
 }
 
 pub struct DocumentService {}
@@ -172,7 +197,7 @@ impl From<RepositoryError> for DocumentInsertError {
     }
 }
 
-fn json_validator(
+pub(super) fn json_validator(
     connection: &StorageConnection,
     doc: &RawDocument,
 ) -> Result<Option<JSONSchema>, DocumentInsertError> {
@@ -195,7 +220,10 @@ fn json_validator(
     Ok(Some(compiled))
 }
 
-fn validate_json(validator: &JSONSchema, data: &serde_json::Value) -> Result<(), Vec<String>> {
+pub(super) fn validate_json(
+    validator: &JSONSchema,
+    data: &serde_json::Value,
+) -> Result<(), Vec<String>> {
     validator.validate(data).map_err(|errors| {
         let errors: Vec<String> = errors.map(|err| format!("{}", err)).collect();
         errors
@@ -224,6 +252,7 @@ fn insert_document(
     let doc = doc.finalise().map_err(DocumentInsertError::InternalError)?;
     let repo = DocumentRepository::new(connection);
     repo.insert(&doc)?;
+    resolve_document_conflicts(connection, &doc)?;
     Ok(doc)
 }
 
@@ -0,0 +1,392 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use chrono::{Duration, Utc};
+use repository::{
+    Document, DocumentConflictRow, DocumentConflictRowRepository, DocumentFilter,
+    DocumentRepository, RepositoryError, StorageConnection, StringFilter,
+};
+use serde_json::{Map, Value};
+use util::{constants::SYSTEM_USER_ID, uuid::uuid};
+
+use super::raw_document::RawDocument;
+
+/// Merges the changes made in `left` and `right` relative to their common ancestor `base`.
+///
+/// Objects are merged key by key, any other value (including arrays) is replaced as a whole, i.e.
+/// a value changed differently in both versions is a conflict. Returns the JSON pointers of the
+/// conflicting values if the versions can't be merged.
+pub fn three_way_merge(base: &Value, left: &Value, right: &Value) -> Result<Value, Vec<String>> {
+    let mut conflicts = Vec::new();
+    let merged = merge_value("", Some(base), Some(left), Some(right), &mut conflicts);
+    if !conflicts.is_empty() {
+        return Err(conflicts);
+    }
+    Ok(merged.unwrap_or(Value::Null))
+}
+
+fn merge_value(
+    path: &str,
+    base: Option<&Value>,
+    left: Option<&Value>,
+    right: Option<&Value>,
+    conflicts: &mut Vec<String>,
+) -> Option<Value> {
+    if left == right || right == base {
+        return left.cloned();
+    }
+    if left == base {
+        return right.cloned();
+    }
+
+    let empty = Map::new();
+    let (Some(Value::Object(left)), Some(Value::Object(right))) = (left, right) else {
+        conflicts.push(path.to_string());
+        return left.cloned();
+    };
+    // A key added concurrently in both versions is merged as if it was empty before
+    let base = match base {
+        Some(Value::Object(base)) => base,
+        _ => &empty,
+    };
+
+    let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut merged = Map::new();
+    for key in keys {
+        let key_path = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+        if let Some(value) = merge_value(
+            &key_path,
+            base.get(key),
+            left.get(key),
+            right.get(key),
+            conflicts,
+        ) {
+            merged.insert(key.clone(), value);
+        }
+    }
+    Some(Value::Object(merged))
+}
+
+struct DocumentVersions {
+    versions: HashMap<String, Document>,
+}
+
+impl DocumentVersions {
+    fn load(connection: &StorageConnection, name: &str) -> Result<Self, RepositoryError> {
+        let versions = DocumentRepository::new(connection)
+            .document_history(Some(
+                DocumentFilter::new().name(StringFilter::equal_to(name)),
+            ))?
+            .into_iter()
+            .map(|document| (document.id.clone(), document))
+            .collect();
+        Ok(DocumentVersions { versions })
+    }
+
+    /// Versions that are not the parent of any other version, newest first
+    fn heads(&self) -> Vec<&Document> {
+        let parents: HashSet<&String> = self
+            .versions
+            .values()
+            .flat_map(|document| document.parent_ids.iter())
+            .collect();
+        let mut heads: Vec<&Document> = self
+            .versions
+            .values()
+            .filter(|document| !parents.contains(&document.id))
+            .collect();
+        heads.sort_by(|a, b| b.datetime.cmp(&a.datetime).then(a.id.cmp(&b.id)));
+        heads
+    }
+
+    /// Ids of the document and all its ancestors
+    fn ancestors(&self, document: &Document) -> HashSet<String> {
+        let mut ancestors = HashSet::from([document.id.clone()]);
+        let mut queue: VecDeque<&String> = document.parent_ids.iter().collect();
+        while let Some(id) = queue.pop_front() {
+            if !ancestors.insert(id.clone()) {
+                continue;
+            }
+            if let Some(parent) = self.versions.get(id) {
+                queue.extend(parent.parent_ids.iter());
+            }
+        }
+        ancestors
+    }
+
+    /// Latest version that is an ancestor of both documents
+    fn common_ancestor(&self, left: &Document, right: &Document) -> Option<&Document> {
+        let left_ancestors = self.ancestors(left);
+        self.ancestors(right)
+            .intersection(&left_ancestors)
+            .filter_map(|id| self.versions.get(id))
+            .max_by(|a, b| a.datetime.cmp(&b.datetime).then(b.id.cmp(&a.id)))
+    }
+}
+
+/// Detects concurrently edited versions (forked heads) of the document and merges them.
+///
+/// Merging is deterministic, i.e. every site merging the same versions creates the same merge
+/// document (with the same id). Versions without a common ancestor are not merged, in this case
+/// the newest version stays the latest. Versions that can't be merged automatically are recorded
+/// as a `DocumentConflict`.
+/// Returns the latest merge document if any has been created.
+pub(crate) fn merge_document_heads(
+    connection: &StorageConnection,
+    name: &str,
+) -> Result<Option<Document>, RepositoryError> {
+    let mut versions = DocumentVersions::load(connection, name)?;
+    let heads: Vec<Document> = versions.heads().into_iter().cloned().collect();
+    let Some((newest, others)) = heads.split_first() else {
+        return Ok(None);
+    };
+
+    let repo = DocumentRepository::new(connection);
+    let conflict_repo = DocumentConflictRowRepository::new(connection);
+    let mut current = newest.clone();
+    let mut merged_document = None;
+    for other in others {
+        let Some(base) = versions.common_ancestor(&current, other).cloned() else {
+            continue;
+        };
+        // Order the versions by id to get the same result on every site
+        let (left, right) = if current.id < other.id {
+            (&current, other)
+        } else {
+            (other, &current)
+        };
+
+        let data = match three_way_merge(&base.data, &left.data, &right.data) {
+            Ok(data) => data,
+            Err(conflicting_paths) => {
+                let already_recorded = conflict_repo
+                    .find_unresolved_by_document_name(name)?
+                    .iter()
+                    .any(|conflict| {
+                        conflict.left_document_id == left.id
+                            && conflict.right_document_id == right.id
+                    });
+                if !already_recorded {
+                    conflict_repo.upsert_one(&DocumentConflictRow {
+                        id: uuid(),
+                        document_name: name.to_string(),
+                        context_id: current.context_id.clone(),
+                        base_document_id: Some(base.id.clone()),
+                        left_document_id: left.id.clone(),
+                        right_document_id: right.id.clone(),
+                        conflicting_paths: serde_json::to_string(&conflicting_paths)
+                            .unwrap_or_default(),
+                        created_datetime: Utc::now().naive_utc(),
+                        resolved_datetime: None,
+                        resolved_document_id: None,
+                    })?;
+                }
+                continue;
+            }
+        };
+
+        let later = if left.datetime > right.datetime {
+            left
+        } else {
+            right
+        };
+        let merged = RawDocument {
+            name: name.to_string(),
+            parents: vec![left.id.clone(), right.id.clone()],
+            author: SYSTEM_USER_ID.to_string(),
+            // Needs to be later than both versions to become the latest version
+            datetime: later.datetime + Duration::milliseconds(1),
+            r#type: later.r#type.clone(),
+            data,
+            form_schema_id: later.form_schema_id.clone(),
+            status: later.status.clone(),
+            owner_name_id: later.owner_name_id.clone(),
+            context_id: later.context_id.clone(),
+        }
+        .finalise()
+        .map_err(|err| RepositoryError::as_db_error("Failed to create merge document", err))?;
+
+        if repo.find_one_by_id(&merged.id)?.is_none() {
+            repo.insert(&merged)?;
+        }
+        versions.versions.insert(merged.id.clone(), merged.clone());
+        current = merged.clone();
+        merged_document = Some(merged);
+    }
+
+    Ok(merged_document)
+}
+
+/// Marks the conflicts resolved by the document, i.e. conflicts of which both versions are
+/// ancestors of the document
+pub(crate) fn resolve_document_conflicts(
+    connection: &StorageConnection,
+    document: &Document,
+) -> Result<(), RepositoryError> {
+    let conflict_repo = DocumentConflictRowRepository::new(connection);
+    let conflicts = conflict_repo.find_unresolved_by_document_name(&document.name)?;
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+
+    let ancestors = DocumentVersions::load(connection, &document.name)?.ancestors(document);
+    for conflict in conflicts {
+        if ancestors.contains(&conflict.left_document_id)
+            && ancestors.contains(&conflict.right_document_id)
+        {
+            conflict_repo.upsert_one(&DocumentConflictRow {
+                resolved_datetime: Some(Utc::now().naive_utc()),
+                resolved_document_id: Some(document.id.clone()),
+                ..conflict
+            })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{DateTime, Utc};
+    use repository::{
+        mock::{context_program_a, MockDataInserts},
+        test_db::setup_all,
+        DocumentFilter, DocumentRepository, DocumentStatus, StringFilter,
+    };
+    use serde_json::{json, Value};
+    use util::constants::SYSTEM_USER_ID;
+
+    use crate::{
+        document::{
+            document_conflict::ResolveDocumentConflict, merge::three_way_merge,
+            raw_document::RawDocument,
+        },
+        service_provider::ServiceProvider,
+        sync::integrate_document::sync_upsert_document,
+    };
+
+    #[actix_rt::test]
+    async fn test_merge_forked_documents() {
+        assert_eq!(
+            three_way_merge(
+                &json!({"a": 1, "b": {"c": 1, "d": 1}, "e": [1]}),
+                &json!({"a": 2, "b": {"c": 1, "d": 1}, "e": [1]}),
+                &json!({"a": 1, "b": {"c": 1, "d": 2}, "f": true}),
+            ),
+            Ok(json!({"a": 2, "b": {"c": 1, "d": 2}, "f": true}))
+        );
+        assert_eq!(
+            three_way_merge(
+                &json!({"a": 1, "b": [1]}),
+                &json!({"a": 2, "b": [1, 2]}),
+                &json!({"a": 3, "b": [1, 3]}),
+            ),
+            Err(vec!["/a".to_string(), "/b".to_string()])
+        );
+
+        let (_, _, connection_manager, _) = setup_all(
+            "test_merge_forked_documents",
+            MockDataInserts::none().form_schemas().contexts(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "");
+        let context = service_provider.basic_context().unwrap();
+        let service = &service_provider.document_service;
+        let repo = DocumentRepository::new(&context.connection);
+
+        let doc_name = "test/forked";
+        let version = |parents: Vec<&str>, author: &str, timestamp: i64, data: Value| {
+            RawDocument {
+                name: doc_name.to_string(),
+                parents: parents.into_iter().map(str::to_string).collect(),
+                author: author.to_string(),
+                datetime: DateTime::<Utc>::from_timestamp(timestamp, 0).unwrap(),
+                r#type: "test_data".to_string(),
+                data,
+                form_schema_id: None,
+                status: DocumentStatus::Active,
+                owner_name_id: None,
+                context_id: context_program_a().id,
+            }
+            .finalise()
+            .unwrap()
+        };
+        let latest = || {
+            repo.query_by_filter(DocumentFilter::new().name(StringFilter::equal_to(doc_name)))
+                .unwrap()
+                .pop()
+                .unwrap()
+        };
+
+        // Edited concurrently on two sites
+        let base = version(vec![], "me", 1000, json!({"a": 1, "b": 1}));
+        let site_a = version(
+            vec![base.id.as_str()],
+            "site_a",
+            2000,
+            json!({"a": 2, "b": 1}),
+        );
+        let site_b = version(
+            vec![base.id.as_str()],
+            "site_b",
+            3000,
+            json!({"a": 1, "b": 2}),
+        );
+        for document in [&base, &site_a, &site_b] {
+            sync_upsert_document(&context.connection, document).unwrap();
+        }
+
+        let merged = latest();
+        assert_eq!(merged.data, json!({"a": 2, "b": 2}));
+        assert_eq!(merged.user_id, SYSTEM_USER_ID);
+        let mut parents = vec![site_a.id.clone(), site_b.id.clone()];
+        parents.sort();
+        assert_eq!(merged.parent_ids, parents);
+
+        // Same merge received from another site
+        assert_eq!(sync_upsert_document(&context.connection, &merged), Ok(None));
+
+        // Conflicting edits
+        let site_a = version(
+            vec![merged.id.as_str()],
+            "site_a",
+            4000,
+            json!({"a": 3, "b": 2}),
+        );
+        let site_b = version(
+            vec![merged.id.as_str()],
+            "site_b",
+            5000,
+            json!({"a": 4, "b": 2}),
+        );
+        for document in [&site_a, &site_b] {
+            sync_upsert_document(&context.connection, document).unwrap();
+        }
+        assert_eq!(latest().id, site_b.id);
+
+        let allowed_ctx = [context_program_a().id];
+        let conflicts = service.document_conflicts(&context, &allowed_ctx).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].conflicting_paths, vec!["/a".to_string()]);
+        assert_eq!(conflicts[0].base.as_ref().map(|d| &d.id), Some(&merged.id));
+
+        let resolved = service
+            .resolve_document_conflict(
+                &context,
+                "me",
+                ResolveDocumentConflict {
+                    id: conflicts[0].row.id.clone(),
+                    data: json!({"a": 5, "b": 2}),
+                },
+                &allowed_ctx,
+            )
+            .unwrap();
+        assert_eq!(latest().id, resolved.id);
+        assert!(service
+            .document_conflicts(&context, &allowed_ctx)
+            .unwrap()
+            .is_empty());
+    }
+}
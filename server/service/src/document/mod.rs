@@ -1,11 +1,13 @@
 use chrono::{DateTime, Utc};
 use repository::{
-    DocumentFilter, DocumentRepository, RepositoryError, StorageConnection, StringFilter,
+    Document, DocumentFilter, DocumentRepository, RepositoryError, StorageConnection, StringFilter,
 };
 
+pub mod document_conflict;
 pub mod document_registry;
 pub mod document_service;
 pub mod form_schema_service;
+pub mod merge;
 pub mod raw_document;
 
 /// Checks that there is no document in the DB with a datetime greater than the provided `datetime`.
@@ -26,3 +28,22 @@ pub(crate) fn is_latest_doc(
 
     Ok(new_doc_is_latest)
 }
+
+/// Names of the document, including the names of its parents if the document has been renamed
+/// (e.g. documents of a merged patient are moved under the surviving patient's name). Used to find
+/// the records derived from the document before it was renamed
+pub(crate) fn document_names(
+    connection: &StorageConnection,
+    document: &Document,
+) -> Result<Vec<String>, RepositoryError> {
+    let repo = DocumentRepository::new(connection);
+    let mut names = vec![document.name.clone()];
+    for parent_id in &document.parent_ids {
+        if let Some(parent) = repo.find_one_by_id(parent_id)? {
+            if !names.contains(&parent.name) {
+                names.push(parent.name);
+            }
+        }
+    }
+    Ok(names)
+}
@@ -0,0 +1,72 @@
+use util::constants::NUMBER_OF_DAYS_IN_A_MONTH;
+
+/// Monthly consumption rate if stock had been available on every day of the period, `None` if the
+/// item was out of stock for the whole period (consumption is unknown)
+pub fn adjust_for_stock_out(
+    consumption: f64,
+    days_in_period: u32,
+    days_out_of_stock: u32,
+) -> Option<f64> {
+    let days_in_stock = days_in_period.saturating_sub(days_out_of_stock);
+    if days_in_stock == 0 {
+        return None;
+    }
+    Some(consumption * NUMBER_OF_DAYS_IN_A_MONTH / days_in_stock as f64)
+}
+
+/// Weighted average of monthly values (most recent first), the most recent month has a weight of
+/// `n`, the oldest a weight of `1`. Months without a value (out of stock the whole month) are
+/// skipped
+pub fn weighted_moving_average(monthly_values: &[Option<f64>]) -> Option<f64> {
+    let number_of_months = monthly_values.len();
+    let (weighted_total, total_weight) = monthly_values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| {
+            let weight = (number_of_months - index) as f64;
+            value.map(|value| (value * weight, weight))
+        })
+        .fold(
+            (0.0, 0.0),
+            |(weighted_total, total_weight), (value, weight)| {
+                (weighted_total + value, total_weight + weight)
+            },
+        );
+
+    if total_weight == 0.0 {
+        return None;
+    }
+    Some(weighted_total / total_weight)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_adjust_for_stock_out() {
+        assert_eq!(adjust_for_stock_out(30.0, 30, 0), Some(30.0));
+        // In stock for 10 of 30 days
+        assert_eq!(adjust_for_stock_out(30.0, 30, 20), Some(90.0));
+        // Shorter period is scaled to a month
+        assert_eq!(adjust_for_stock_out(15.0, 15, 0), Some(30.0));
+        assert_eq!(adjust_for_stock_out(0.0, 30, 30), None);
+    }
+
+    #[test]
+    fn test_weighted_moving_average() {
+        assert_eq!(weighted_moving_average(&[]), None);
+        assert_eq!(weighted_moving_average(&[None, None]), None);
+        assert_eq!(weighted_moving_average(&[Some(10.0)]), Some(10.0));
+        // (3 * 30 + 2 * 20 + 1 * 10) / 6
+        assert_eq!(
+            weighted_moving_average(&[Some(30.0), Some(20.0), Some(10.0)]),
+            Some(140.0 / 6.0)
+        );
+        // Missing month is skipped: (3 * 30 + 1 * 10) / 4
+        assert_eq!(
+            weighted_moving_average(&[Some(30.0), None, Some(10.0)]),
+            Some(25.0)
+        );
+    }
+}
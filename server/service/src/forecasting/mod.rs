@@ -0,0 +1,458 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, NaiveDate};
+use repository::{
+    ConsumptionFilter, ConsumptionRepository, DateFilter, DatetimeFilter, EqualFilter,
+    ForecastMethod, RepositoryError, StockMovementFilter, StockMovementRepository,
+    StockOnHandFilter, StockOnHandRepository, StorageConnection, StorePreferenceRowRepository,
+};
+use util::{
+    constants::{DEFAULT_AMC_LOOKBACK_MONTHS, NUMBER_OF_DAYS_IN_A_MONTH},
+    date_now,
+};
+
+use crate::service_provider::ServiceContext;
+
+use methods::{adjust_for_stock_out, weighted_moving_average};
+
+pub mod methods;
+
+const DAYS_IN_PERIOD: u32 = NUMBER_OF_DAYS_IN_A_MONTH as u32;
+const DAYS_IN_YEAR: i64 = 365;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ForecastOptions {
+    /// Defaults to the store preference forecast method
+    pub method: Option<ForecastMethod>,
+    /// Number of months used for average monthly consumption and weighted moving average,
+    /// defaults to the store preference AMC look back period
+    pub lookback_months: Option<u32>,
+}
+
+/// Consumption of an item over a period of (up to) a month
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsumptionPeriod {
+    pub start_date: NaiveDate,
+    /// Inclusive
+    pub end_date: NaiveDate,
+    pub consumption: f64,
+    /// Days ending with no stock on hand
+    pub days_out_of_stock: u32,
+    /// Monthly consumption if stock had been available for the whole period, `None` if the item
+    /// was out of stock for the whole period
+    pub adjusted_monthly_consumption: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemForecast {
+    pub item_id: String,
+    /// Method used for the forecast, seasonal naive falls back to weighted moving average when
+    /// there is no consumption data for the same period last year
+    pub method: ForecastMethod,
+    pub forecast_monthly_consumption: f64,
+    /// Consumption for each of the look back months, most recent first
+    pub history: Vec<ConsumptionPeriod>,
+    /// Period a year before the forecast month, only populated for seasonal naive
+    pub same_period_last_year: Option<ConsumptionPeriod>,
+}
+
+pub trait ForecastingServiceTrait: Sync + Send {
+    fn get_item_forecasts(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        item_ids: Vec<String>,
+        options: ForecastOptions,
+    ) -> Result<Vec<ItemForecast>, RepositoryError> {
+        get_item_forecasts(&ctx.connection, store_id, item_ids, options)
+    }
+}
+
+pub struct ForecastingService {}
+impl ForecastingServiceTrait for ForecastingService {}
+
+/// Forecasts monthly consumption for the next month of each item in the store
+pub fn get_item_forecasts(
+    connection: &StorageConnection,
+    store_id: &str,
+    item_ids: Vec<String>,
+    ForecastOptions {
+        method,
+        lookback_months,
+    }: ForecastOptions,
+) -> Result<Vec<ItemForecast>, RepositoryError> {
+    let store_preference =
+        StorePreferenceRowRepository::new(connection).find_one_by_id(store_id)?;
+    let method = method.unwrap_or_else(|| {
+        store_preference
+            .as_ref()
+            .map(|preference| preference.forecast_method)
+            .unwrap_or_default()
+    });
+    let lookback_months = lookback_months
+        .or_else(|| {
+            store_preference
+                .as_ref()
+                .map(|preference| preference.monthly_consumption_look_back_period.round() as u32)
+        })
+        .filter(|months| *months > 0)
+        .unwrap_or(DEFAULT_AMC_LOOKBACK_MONTHS);
+
+    let today = date_now();
+    let history_periods: Vec<(NaiveDate, NaiveDate)> = (0..lookback_months)
+        .map(|index| {
+            let end_date = today - Duration::days((index * DAYS_IN_PERIOD) as i64);
+            let start_date = end_date - Duration::days(DAYS_IN_PERIOD as i64 - 1);
+            (start_date, end_date)
+        })
+        .collect();
+    let last_year_start_date = today + Duration::days(1) - Duration::days(DAYS_IN_YEAR);
+    let same_period_last_year = (
+        last_year_start_date,
+        last_year_start_date + Duration::days(DAYS_IN_PERIOD as i64 - 1),
+    );
+
+    let mut earliest_date = history_periods
+        .last()
+        .map(|(start_date, _)| *start_date)
+        .unwrap_or(today);
+    if method == ForecastMethod::SeasonalNaive {
+        earliest_date = earliest_date.min(same_period_last_year.0);
+    }
+
+    let histories = get_daily_histories(connection, store_id, &item_ids, earliest_date, today)?;
+
+    let forecasts = item_ids
+        .into_iter()
+        .map(|item_id| {
+            let daily_history = histories.get(&item_id);
+            let history: Vec<ConsumptionPeriod> = history_periods
+                .iter()
+                .map(|(start_date, end_date)| {
+                    consumption_period(daily_history, *start_date, *end_date)
+                })
+                .collect();
+
+            let weighted_forecast = || {
+                weighted_moving_average(
+                    &history
+                        .iter()
+                        .map(|period| period.adjusted_monthly_consumption)
+                        .collect::<Vec<_>>(),
+                )
+                .unwrap_or_default()
+            };
+
+            let (method, forecast_monthly_consumption, same_period_last_year) = match method {
+                ForecastMethod::AverageMonthlyConsumption => {
+                    let total_consumption: f64 =
+                        history.iter().map(|period| period.consumption).sum();
+                    (method, total_consumption / lookback_months as f64, None)
+                }
+                ForecastMethod::WeightedMovingAverage => (method, weighted_forecast(), None),
+                ForecastMethod::SeasonalNaive => {
+                    let last_year = consumption_period(
+                        daily_history,
+                        same_period_last_year.0,
+                        same_period_last_year.1,
+                    );
+                    match last_year.adjusted_monthly_consumption {
+                        Some(consumption) => (method, consumption, Some(last_year)),
+                        None => (
+                            ForecastMethod::WeightedMovingAverage,
+                            weighted_forecast(),
+                            Some(last_year),
+                        ),
+                    }
+                }
+            };
+
+            ItemForecast {
+                item_id,
+                method,
+                forecast_monthly_consumption,
+                history,
+                same_period_last_year,
+            }
+        })
+        .collect();
+
+    Ok(forecasts)
+}
+
+#[derive(Default)]
+struct DailyHistory {
+    consumption: HashMap<NaiveDate, f64>,
+    out_of_stock_days: HashSet<NaiveDate>,
+}
+
+/// Daily consumption and days ending with no stock on hand for each item between `start_date`
+/// and `end_date` (inclusive). Stock on hand at the end of each day is calculated by taking the
+/// stock movements after that day away from the current stock on hand
+fn get_daily_histories(
+    connection: &StorageConnection,
+    store_id: &str,
+    item_ids: &[String],
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<HashMap<String, DailyHistory>, RepositoryError> {
+    let item_id_filter = EqualFilter::equal_any(item_ids.to_vec());
+    let mut histories: HashMap<String, DailyHistory> = HashMap::new();
+
+    let consumption_rows =
+        ConsumptionRepository::new(connection).query(Some(ConsumptionFilter {
+            item_id: Some(item_id_filter.clone()),
+            store_id: Some(EqualFilter::equal_to(store_id)),
+            date: Some(DateFilter::date_range(&start_date, &end_date)),
+        }))?;
+    for row in consumption_rows {
+        *histories
+            .entry(row.item_id)
+            .or_default()
+            .consumption
+            .entry(row.date)
+            .or_default() += row.quantity;
+    }
+
+    let mut stock_on_hand: HashMap<String, f64> = StockOnHandRepository::new(connection)
+        .query(Some(
+            StockOnHandFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .item_id(item_id_filter.clone()),
+        ))?
+        .into_iter()
+        .map(|row| (row.item_id, row.total_stock_on_hand))
+        .collect();
+
+    // Movements after the start date, up to now (including any made today)
+    let start_datetime = start_date
+        .and_hms_opt(0, 0, 0)
+        .ok_or(RepositoryError::as_db_error(
+            "Invalid forecast start date",
+            "",
+        ))?;
+    let mut movements: HashMap<String, HashMap<NaiveDate, f64>> = HashMap::new();
+    for row in StockMovementRepository::new(connection).query(Some(
+        StockMovementFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .item_id(item_id_filter)
+            .datetime(DatetimeFilter::after_or_equal_to(start_datetime)),
+    ))? {
+        *movements
+            .entry(row.item_id)
+            .or_default()
+            .entry(row.datetime.date())
+            .or_default() += row.quantity;
+    }
+
+    for item_id in item_ids {
+        let item_movements = movements.remove(item_id).unwrap_or_default();
+        let mut end_of_day_stock_on_hand = stock_on_hand.remove(item_id).unwrap_or_default();
+        // Movements dated after the end date (e.g. in a future timezone) are already in stock on hand
+        end_of_day_stock_on_hand -= item_movements
+            .iter()
+            .filter(|(date, _)| **date > end_date)
+            .map(|(_, quantity)| quantity)
+            .sum::<f64>();
+
+        let history = histories.entry(item_id.clone()).or_default();
+        let mut date = end_date;
+        while date >= start_date {
+            if end_of_day_stock_on_hand <= 0.0 {
+                history.out_of_stock_days.insert(date);
+            }
+            end_of_day_stock_on_hand -= item_movements.get(&date).copied().unwrap_or_default();
+            date = date - Duration::days(1);
+        }
+    }
+
+    Ok(histories)
+}
+
+fn consumption_period(
+    daily_history: Option<&DailyHistory>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> ConsumptionPeriod {
+    let days_in_period = (end_date - start_date).num_days() as u32 + 1;
+    let (consumption, days_out_of_stock) = match daily_history {
+        Some(history) => (
+            history
+                .consumption
+                .iter()
+                .filter(|(date, _)| (start_date..=end_date).contains(*date))
+                .map(|(_, quantity)| quantity)
+                .sum(),
+            history
+                .out_of_stock_days
+                .iter()
+                .filter(|date| (start_date..=end_date).contains(*date))
+                .count() as u32,
+        ),
+        None => (0.0, days_in_period),
+    };
+
+    ConsumptionPeriod {
+        start_date,
+        end_date,
+        consumption,
+        days_out_of_stock,
+        adjusted_monthly_consumption: adjust_for_stock_out(
+            consumption,
+            days_in_period,
+            days_out_of_stock,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, NaiveDateTime};
+    use repository::{
+        mock::{mock_name_store_b, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        ForecastMethod, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType, InvoiceRow,
+        InvoiceRowRepository, InvoiceStatus, InvoiceType, ItemRow, ItemRowRepository, ItemType,
+        StockLineRow, StockLineRowRepository,
+    };
+    use util::date_now;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::ForecastOptions;
+
+    #[actix_rt::test]
+    async fn test_item_forecasts() {
+        let (_, _, connection_manager, _) =
+            setup_all("test_item_forecasts", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let service = service_provider.forecasting_service;
+        let store_id = mock_store_a().id;
+        let item_id = "forecast_item".to_string();
+
+        ItemRowRepository::new(&context.connection)
+            .upsert_one(&ItemRow {
+                id: item_id.clone(),
+                name: item_id.clone(),
+                r#type: ItemType::Stock,
+                ..Default::default()
+            })
+            .unwrap();
+        // Current stock on hand
+        StockLineRowRepository::new(&context.connection)
+            .upsert_one(&StockLineRow {
+                id: "forecast_item_stock_line".to_string(),
+                item_link_id: item_id.clone(),
+                store_id: store_id.clone(),
+                pack_size: 1.0,
+                available_number_of_packs: 20.0,
+                total_number_of_packs: 20.0,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let today = date_now().and_hms_opt(12, 0, 0).unwrap();
+        let days_ago = |days| today - Duration::days(days);
+
+        // Receive 100 on the first day of the 3 month history and issue 40, issue 60 half way
+        // through the second month (out of stock for the last 15 days of the second month) then
+        // receive 30 and issue 10 in the latest month, leaving 20 in stock
+        let mut lines = Vec::new();
+        let mut invoice = |id: &str,
+                           r#type: InvoiceType,
+                           datetime: NaiveDateTime,
+                           quantity: f64| {
+            let (status, line_type) = match r#type {
+                InvoiceType::InboundShipment => (InvoiceStatus::Verified, InvoiceLineType::StockIn),
+                _ => (InvoiceStatus::Shipped, InvoiceLineType::StockOut),
+            };
+            lines.push((
+                InvoiceRow {
+                    id: id.to_string(),
+                    name_link_id: mock_name_store_b().id,
+                    store_id: store_id.clone(),
+                    r#type,
+                    status,
+                    created_datetime: datetime,
+                    picked_datetime: Some(datetime),
+                    shipped_datetime: Some(datetime),
+                    delivered_datetime: Some(datetime),
+                    verified_datetime: Some(datetime),
+                    ..Default::default()
+                },
+                InvoiceLineRow {
+                    id: format!("{id}_line"),
+                    invoice_id: id.to_string(),
+                    item_link_id: item_id.clone(),
+                    r#type: line_type,
+                    pack_size: 1.0,
+                    number_of_packs: quantity,
+                    ..Default::default()
+                },
+            ));
+        };
+        invoice("in_1", InvoiceType::InboundShipment, days_ago(89), 100.0);
+        invoice("out_1", InvoiceType::OutboundShipment, days_ago(85), 40.0);
+        invoice("out_2", InvoiceType::OutboundShipment, days_ago(44), 60.0);
+        invoice("in_2", InvoiceType::InboundShipment, days_ago(29), 30.0);
+        invoice("out_3", InvoiceType::OutboundShipment, days_ago(10), 10.0);
+        for (invoice, line) in lines {
+            InvoiceRowRepository::new(&context.connection)
+                .upsert_one(&invoice)
+                .unwrap();
+            InvoiceLineRowRepository::new(&context.connection)
+                .upsert_one(&line)
+                .unwrap();
+        }
+
+        let forecast = |method| {
+            service
+                .get_item_forecasts(
+                    &context,
+                    &store_id,
+                    vec![item_id.clone()],
+                    ForecastOptions {
+                        method: Some(method),
+                        lookback_months: Some(3),
+                    },
+                )
+                .unwrap()
+                .pop()
+                .unwrap()
+        };
+
+        let amc = forecast(ForecastMethod::AverageMonthlyConsumption);
+        assert_eq!(amc.forecast_monthly_consumption, 110.0 / 3.0);
+
+        let weighted = forecast(ForecastMethod::WeightedMovingAverage);
+        let history: Vec<(f64, u32)> = weighted
+            .history
+            .iter()
+            .map(|period| (period.consumption, period.days_out_of_stock))
+            .collect();
+        // Most recent first, out of stock from 44 days ago until 30 days ago
+        assert_eq!(history, vec![(10.0, 0), (60.0, 15), (40.0, 0)]);
+        assert_eq!(
+            weighted.history[1].adjusted_monthly_consumption,
+            Some(120.0)
+        );
+        // (3 * 10 + 2 * 120 + 1 * 40) / 6
+        assert_eq!(weighted.forecast_monthly_consumption, 310.0 / 6.0);
+
+        // No history a year ago, falls back to weighted moving average
+        let seasonal = forecast(ForecastMethod::SeasonalNaive);
+        assert_eq!(seasonal.method, ForecastMethod::WeightedMovingAverage);
+        assert_eq!(
+            seasonal.forecast_monthly_consumption,
+            weighted.forecast_monthly_consumption
+        );
+        assert_eq!(
+            seasonal
+                .same_period_last_year
+                .map(|period| period.adjusted_monthly_consumption),
+            Some(None)
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use chrono::NaiveDateTime;
+use repository::{
+    ledger::{LedgerFilter, LedgerRepository, LedgerSort, LedgerSortField},
+    DatetimeFilter, EqualFilter, InvoiceLineRowRepository, InvoiceType, ItemLinkRowRepository,
+    Pagination, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+/// Max number of ids per `eq_any` query, to stay below the database parameter limit
+const ID_CHUNK_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ValuationMethod {
+    /// Stock on hand is made of the most recent receipts, issues use the oldest stock first
+    #[default]
+    Fifo,
+    /// Stock on hand is valued at the average cost of all receipts, weighted by quantity
+    WeightedAverageCost,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemValuation {
+    pub item_id: String,
+    /// Quantity in units (not packs)
+    pub quantity: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InventoryValuation {
+    pub store_id: String,
+    pub datetime: NaiveDateTime,
+    pub method: ValuationMethod,
+    pub total_value: f64,
+    /// Items with stock movements up to `datetime`, sorted by item id
+    pub items: Vec<ItemValuation>,
+}
+
+struct CostLayer {
+    quantity: f64,
+    unit_cost: f64,
+}
+
+#[derive(Default)]
+struct ItemCosting {
+    quantity: f64,
+    average_cost: f64,
+    /// Received stock that hasn't been issued yet, oldest first
+    layers: VecDeque<CostLayer>,
+}
+
+impl ItemCosting {
+    fn receive(&mut self, quantity: f64, unit_cost: Option<f64>) {
+        // Stock received without a purchase cost, e.g. inventory additions or customer returns, is
+        // valued at the current average cost
+        let unit_cost = unit_cost.unwrap_or(self.average_cost);
+
+        self.average_cost = if self.quantity > 0.0 {
+            (self.quantity * self.average_cost + quantity * unit_cost) / (self.quantity + quantity)
+        } else {
+            unit_cost
+        };
+
+        // Part of the receipt covers previously issued stock that was never received (negative
+        // stock on hand)
+        let layer_quantity = quantity + self.quantity.min(0.0);
+        if layer_quantity > 0.0 {
+            self.layers.push_back(CostLayer {
+                quantity: layer_quantity,
+                unit_cost,
+            });
+        }
+        self.quantity += quantity;
+    }
+
+    fn issue(&mut self, quantity: f64) {
+        self.quantity -= quantity;
+
+        let mut remaining = quantity;
+        while remaining > 0.0 {
+            let Some(layer) = self.layers.front_mut() else {
+                break;
+            };
+            if layer.quantity > remaining {
+                layer.quantity -= remaining;
+                break;
+            }
+            remaining -= layer.quantity;
+            self.layers.pop_front();
+        }
+    }
+
+    fn value(&self, method: ValuationMethod) -> f64 {
+        match method {
+            ValuationMethod::Fifo => self
+                .layers
+                .iter()
+                .map(|layer| layer.quantity * layer.unit_cost)
+                .sum(),
+            ValuationMethod::WeightedAverageCost => self.quantity.max(0.0) * self.average_cost,
+        }
+    }
+}
+
+/// Value of the stock of the store at `datetime`, using the cost price of inbound shipment lines.
+/// Stock is reconstructed from the stock movements up to `datetime`
+pub fn get_inventory_valuation(
+    ctx: &ServiceContext,
+    store_id: &str,
+    datetime: NaiveDateTime,
+    method: ValuationMethod,
+    item_ids: Option<Vec<String>>,
+) -> Result<InventoryValuation, RepositoryError> {
+    let connection = &ctx.connection;
+
+    let mut filter = LedgerFilter::new()
+        .store_id(EqualFilter::equal_to(store_id))
+        .datetime(DatetimeFilter::before_or_equal_to(datetime));
+    if let Some(item_ids) = item_ids {
+        filter = filter.item_id(EqualFilter::equal_any(item_ids));
+    }
+    let movements = LedgerRepository::new(connection).query(
+        Pagination::all(),
+        Some(filter),
+        Some(LedgerSort {
+            key: LedgerSortField::Datetime,
+            desc: Some(false),
+        }),
+    )?;
+
+    // Movement ids are invoice line ids
+    let receipt_line_ids: Vec<String> = movements
+        .iter()
+        .filter(|movement| {
+            movement.invoice_type == InvoiceType::InboundShipment && movement.quantity > 0.0
+        })
+        .map(|movement| movement.id.clone())
+        .collect();
+    let mut unit_costs = HashMap::new();
+    for ids in receipt_line_ids.chunks(ID_CHUNK_SIZE) {
+        for line in InvoiceLineRowRepository::new(connection).find_many_by_id(ids)? {
+            if line.pack_size > 0.0 {
+                unit_costs.insert(line.id, line.cost_price_per_pack / line.pack_size);
+            }
+        }
+    }
+
+    // Item id of the movements is the item link id, merged items are valued together
+    let mut item_link_ids: Vec<String> = movements
+        .iter()
+        .map(|movement| movement.item_id.clone())
+        .collect();
+    item_link_ids.sort();
+    item_link_ids.dedup();
+    let mut item_ids = HashMap::new();
+    for ids in item_link_ids.chunks(ID_CHUNK_SIZE) {
+        for item_link in ItemLinkRowRepository::new(connection).find_many_by_id(ids)? {
+            item_ids.insert(item_link.id, item_link.item_id);
+        }
+    }
+
+    let mut costings: BTreeMap<String, ItemCosting> = BTreeMap::new();
+    for movement in movements {
+        let item_id = item_ids
+            .get(&movement.item_id)
+            .cloned()
+            .unwrap_or(movement.item_id);
+        let costing = costings.entry(item_id).or_default();
+
+        if movement.quantity > 0.0 {
+            costing.receive(movement.quantity, unit_costs.get(&movement.id).copied());
+        } else {
+            costing.issue(-movement.quantity);
+        }
+    }
+
+    let items: Vec<ItemValuation> = costings
+        .into_iter()
+        .map(|(item_id, costing)| ItemValuation {
+            item_id,
+            quantity: costing.quantity,
+            value: costing.value(method),
+        })
+        .collect();
+
+    Ok(InventoryValuation {
+        store_id: store_id.to_string(),
+        datetime,
+        method,
+        total_value: items.iter().map(|item| item.value).sum(),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use repository::{
+        mock::{mock_item_a, mock_item_b, mock_name_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceType, NameRow, StoreRow,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::{get_inventory_valuation, ItemValuation, ValuationMethod};
+
+    fn date(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2021, 1, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn name() -> NameRow {
+        inline_init(|r: &mut NameRow| {
+            r.id = "valuation_name".to_string();
+        })
+    }
+
+    fn store() -> StoreRow {
+        inline_init(|s: &mut StoreRow| {
+            s.id = "valuation_store".to_string();
+            s.name_link_id = name().id;
+            s.code = "n/a".to_string();
+        })
+    }
+
+    fn movement(
+        id: &str,
+        item_id: &str,
+        r#type: InvoiceType,
+        day: u32,
+        number_of_packs: f64,
+        cost_price_per_pack: f64,
+    ) -> MockData {
+        let datetime = Some(date(day));
+        inline_init(|r: &mut MockData| {
+            r.invoices = vec![inline_init(|r: &mut InvoiceRow| {
+                r.id = id.to_string();
+                r.store_id = store().id;
+                r.name_link_id = mock_name_a().id;
+                match r#type {
+                    InvoiceType::InboundShipment => r.delivered_datetime = datetime,
+                    InvoiceType::OutboundShipment => r.picked_datetime = datetime,
+                    _ => r.verified_datetime = datetime,
+                }
+                r.r#type = r#type;
+            })];
+            r.invoice_lines = vec![inline_init(|r: &mut InvoiceLineRow| {
+                r.id = format!("{}_line", id);
+                r.invoice_id = id.to_string();
+                r.item_link_id = item_id.to_string();
+                r.r#type = if number_of_packs > 0.0 {
+                    InvoiceLineType::StockIn
+                } else {
+                    InvoiceLineType::StockOut
+                };
+                r.number_of_packs = number_of_packs.abs();
+                r.pack_size = 10.0;
+                r.cost_price_per_pack = cost_price_per_pack;
+            })];
+        })
+    }
+
+    #[actix_rt::test]
+    async fn inventory_valuation() {
+        let item_a = mock_item_a().id;
+        let item_b = mock_item_b().id;
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "inventory_valuation",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.names = vec![name()];
+                r.stores = vec![store()];
+            })
+            // 100 units at 1.0 and 100 units at 2.0
+            .join(movement(
+                "a1",
+                &item_a,
+                InvoiceType::InboundShipment,
+                1,
+                10.0,
+                10.0,
+            ))
+            .join(movement(
+                "a2",
+                &item_a,
+                InvoiceType::InboundShipment,
+                2,
+                10.0,
+                20.0,
+            ))
+            // 150 units issued, 50 units at 2.0 left with FIFO
+            .join(movement(
+                "a3",
+                &item_a,
+                InvoiceType::OutboundShipment,
+                3,
+                -15.0,
+                0.0,
+            ))
+            // Valued at the current average cost of 1.5
+            .join(movement(
+                "a4",
+                &item_a,
+                InvoiceType::InventoryAddition,
+                4,
+                5.0,
+                0.0,
+            ))
+            // After the valuation date
+            .join(movement(
+                "a5",
+                &item_a,
+                InvoiceType::InboundShipment,
+                20,
+                10.0,
+                30.0,
+            ))
+            .join(movement(
+                "b1",
+                &item_b,
+                InvoiceType::InboundShipment,
+                1,
+                2.0,
+                5.0,
+            )),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "");
+        let context = service_provider.basic_context().unwrap();
+
+        let valuation =
+            get_inventory_valuation(&context, &store().id, date(10), ValuationMethod::Fifo, None)
+                .unwrap();
+        assert_eq!(
+            valuation.items,
+            vec![
+                ItemValuation {
+                    item_id: item_a.clone(),
+                    quantity: 100.0,
+                    value: 175.0,
+                },
+                ItemValuation {
+                    item_id: item_b.clone(),
+                    quantity: 20.0,
+                    value: 10.0,
+                }
+            ]
+        );
+        assert_eq!(valuation.total_value, 185.0);
+
+        let valuation = get_inventory_valuation(
+            &context,
+            &store().id,
+            date(10),
+            ValuationMethod::WeightedAverageCost,
+            Some(vec![item_a.clone()]),
+        )
+        .unwrap();
+        assert_eq!(
+            valuation.items,
+            vec![ItemValuation {
+                item_id: item_a,
+                quantity: 100.0,
+                value: 150.0,
+            }]
+        );
+    }
+}
@@ -30,6 +30,7 @@ pub struct InsertCustomerReturn {
 #[derive(Clone, Debug, PartialEq)]
 pub enum InsertCustomerReturnError {
     InvoiceAlreadyExists,
+    DateInClosedPeriod,
     // Original invoice/shipment validation
     OutboundShipmentDoesNotExist,
     OutboundShipmentDoesNotBelongToCurrentStore,
@@ -147,6 +148,7 @@ mod test {
             stock_in_line::InsertStockInLineError,
             update_return_reason_id::UpdateLineReturnReasonError,
         },
+        period_close::close_period_for_today,
         service_provider::ServiceProvider,
     };
 
@@ -333,6 +335,20 @@ mod test {
                 error: UpdateLineReturnReasonError::ReasonDoesNotExist,
             }),
         );
+
+        // DateInClosedPeriod
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        assert_eq!(
+            service_provider.invoice_service.insert_customer_return(
+                &context,
+                InsertCustomerReturn {
+                    id: "some_new_id".to_string(),
+                    other_party_id: mock_name_customer_a().id,
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::DateInClosedPeriod),
+        );
     }
 
     #[actix_rt::test]
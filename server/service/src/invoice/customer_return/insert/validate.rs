@@ -1,6 +1,8 @@
+use chrono::Utc;
 use repository::{InvoiceRow, InvoiceStatus, InvoiceType, Name, StorageConnection};
 
 use crate::invoice::{check_invoice_exists, check_invoice_type, check_store};
+use crate::period_close::validate_not_in_closed_period;
 use crate::validate::{check_other_party, CheckOtherPartyType, OtherPartyErrors};
 
 use super::{InsertCustomerReturn, InsertCustomerReturnError};
@@ -14,6 +16,9 @@ pub fn validate(
     if (check_invoice_exists(&input.id, connection)?).is_some() {
         return Err(InvoiceAlreadyExists);
     }
+    if !validate_not_in_closed_period(connection, store_id, Utc::now().naive_utc())? {
+        return Err(DateInClosedPeriod);
+    }
 
     if let Some(outbound_shipment_id) = &input.outbound_shipment_id {
         let outbound_shipment = check_invoice_exists(outbound_shipment_id, connection)?
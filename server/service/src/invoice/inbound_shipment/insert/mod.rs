@@ -55,6 +55,7 @@ pub fn insert_inbound_shipment(
 #[derive(Debug, PartialEq)]
 pub enum InsertInboundShipmentError {
     InvoiceAlreadyExists,
+    DateInClosedPeriod,
     // Name validation
     OtherPartyDoesNotExist,
     OtherPartyNotVisible,
@@ -96,7 +97,8 @@ mod test {
     use util::{inline_edit, inline_init};
 
     use crate::{
-        invoice::inbound_shipment::InsertInboundShipment, service_provider::ServiceProvider,
+        invoice::inbound_shipment::InsertInboundShipment, period_close::close_period_for_today,
+        service_provider::ServiceProvider,
     };
 
     use super::InsertInboundShipmentError;
@@ -186,6 +188,18 @@ mod test {
             ),
             Err(ServiceError::OtherPartyNotASupplier)
         );
+        // DateInClosedPeriod
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        assert_eq!(
+            service.insert_inbound_shipment(
+                &context,
+                inline_init(|r: &mut InsertInboundShipment| {
+                    r.id = "new_id".to_string();
+                    r.other_party_id.clone_from(&mock_name_a().id);
+                })
+            ),
+            Err(ServiceError::DateInClosedPeriod)
+        );
 
         // NewlyCreatedInvoiceDoesNotExist
     }
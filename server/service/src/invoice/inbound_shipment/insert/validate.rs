@@ -1,5 +1,7 @@
 use crate::invoice::check_invoice_exists;
+use crate::period_close::validate_not_in_closed_period;
 use crate::validate::{check_other_party, CheckOtherPartyType, OtherPartyErrors};
+use chrono::Utc;
 use repository::Name;
 use repository::StorageConnection;
 
@@ -14,6 +16,9 @@ pub fn validate(
     if (check_invoice_exists(&input.id, connection)?).is_some() {
         return Err(InvoiceAlreadyExists);
     }
+    if !validate_not_in_closed_period(connection, store_id, Utc::now().naive_utc())? {
+        return Err(DateInClosedPeriod);
+    }
 
     let other_party = check_other_party(
         connection,
@@ -137,6 +137,7 @@ pub enum UpdateInboundShipmentError {
     CannotEditFinalised,
     CannotChangeStatusOfInvoiceOnHold,
     CannotIssueForeignCurrencyForInternalSuppliers,
+    DateInClosedPeriod,
     // Name validation
     OtherPartyDoesNotExist,
     OtherPartyNotVisible,
@@ -200,6 +201,7 @@ mod test {
     use crate::{
         invoice::inbound_shipment::{UpdateInboundShipment, UpdateInboundShipmentStatus},
         invoice_line::{query::get_invoice_lines, ShipmentTaxUpdate},
+        period_close::close_period_for_today,
         service_provider::ServiceProvider,
     };
 
@@ -323,6 +325,18 @@ mod test {
             ),
             Err(ServiceError::OtherPartyNotASupplier)
         );
+        // DateInClosedPeriod
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        assert_eq!(
+            service.update_inbound_shipment(
+                &context,
+                inline_init(|r: &mut UpdateInboundShipment| {
+                    r.id = mock_inbound_shipment_c().id;
+                    r.status = Some(UpdateInboundShipmentStatus::Delivered);
+                })
+            ),
+            Err(ServiceError::DateInClosedPeriod)
+        );
         //NotThisStoreInvoice
         context.store_id = mock_store_b().id;
         assert_eq!(
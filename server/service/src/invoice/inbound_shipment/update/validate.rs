@@ -3,7 +3,10 @@ use crate::invoice::{
     check_invoice_exists, check_invoice_is_editable, check_invoice_status, check_invoice_type,
     check_status_change, check_store, InvoiceRowStatusError,
 };
+use crate::invoice_line::stock_out_line::invoice_backdated_date;
+use crate::period_close::validate_not_in_closed_period;
 use crate::validate::{check_other_party, CheckOtherPartyType, OtherPartyErrors};
+use chrono::Utc;
 use repository::{InvoiceRow, InvoiceType, Name, StorageConnection};
 
 use super::{UpdateInboundShipment, UpdateInboundShipmentError};
@@ -37,6 +40,12 @@ pub fn validate(
                 InvoiceRowStatusError::CannotReverseInvoiceStatus => CannotReverseInvoiceStatus,
             },
         )?;
+
+        // Stock is received with the status change
+        let datetime = invoice_backdated_date(&invoice).unwrap_or_else(|| Utc::now().naive_utc());
+        if !validate_not_in_closed_period(connection, store_id, datetime)? {
+            return Err(DateInClosedPeriod);
+        }
     }
 
     // Other party check
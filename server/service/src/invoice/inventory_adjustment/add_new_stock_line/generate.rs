@@ -100,6 +100,7 @@ pub fn generate(
         tax_percentage: None,
         barcode,
         item_variant_id,
+        quantity_in_unit: None,
     };
 
     let update_inventory_adjustment_reason = UpdateInventoryAdjustmentReason {
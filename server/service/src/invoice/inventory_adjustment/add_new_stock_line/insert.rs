@@ -33,6 +33,7 @@ pub struct AddNewStockLine {
 #[derive(Clone, Debug, PartialEq)]
 pub enum AddNewStockLineError {
     StockLineAlreadyExists,
+    DateInClosedPeriod,
     AdjustmentReasonNotValid,
     AdjustmentReasonNotProvided,
     NewlyCreatedStockLineDoesNotExist,
@@ -51,7 +52,7 @@ pub fn add_new_stock_line(
             // Needed for query below, input is moved
             let stock_line_id = input.stock_line_id.clone();
 
-            validate(connection, &input)?;
+            validate(connection, &ctx.store_id, &input)?;
             let GenerateResult {
                 invoice,
                 stock_in_line,
@@ -128,8 +129,8 @@ mod test {
 
     use crate::{
         invoice::inventory_adjustment::add_new_stock_line::AddNewStockLine,
-        invoice_line::stock_in_line::InsertStockInLineError, service_provider::ServiceProvider,
-        NullableUpdate,
+        invoice_line::stock_in_line::InsertStockInLineError, period_close::close_period_for_today,
+        service_provider::ServiceProvider, NullableUpdate,
     };
 
     use super::AddNewStockLineError;
@@ -216,6 +217,19 @@ mod test {
                 InsertStockInLineError::PackSizeBelowOne,
             ))
         );
+
+        // Date in closed period
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        assert_eq!(
+            service.add_new_stock_line(
+                &context,
+                AddNewStockLine {
+                    stock_line_id: "new".to_string(),
+                    ..Default::default()
+                }
+            ),
+            Err(ServiceError::DateInClosedPeriod)
+        );
     }
 
     #[actix_rt::test]
@@ -1,6 +1,8 @@
+use chrono::Utc;
 use repository::StorageConnection;
 
 use crate::common_stock::check_stock_line_does_not_exist;
+use crate::period_close::validate_not_in_closed_period;
 
 use crate::stocktake_line::validate::{check_active_adjustment_reasons, check_reason_is_valid};
 
@@ -8,12 +10,16 @@ use super::insert::{AddNewStockLine, AddNewStockLineError};
 
 pub fn validate(
     connection: &StorageConnection,
+    store_id: &str,
     input: &AddNewStockLine,
 ) -> Result<(), AddNewStockLineError> {
     use AddNewStockLineError::*;
     if !check_stock_line_does_not_exist(&input.stock_line_id, connection)? {
         return Err(StockLineAlreadyExists);
     }
+    if !validate_not_in_closed_period(connection, store_id, Utc::now().naive_utc())? {
+        return Err(DateInClosedPeriod);
+    }
 
     let reduction_amount = -input.number_of_packs;
 
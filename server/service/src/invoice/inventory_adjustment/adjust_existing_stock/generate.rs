@@ -126,6 +126,7 @@ pub fn generate(
             barcode: None,
             total_before_tax: None,
             tax_percentage: None,
+            quantity_in_unit: None,
         }),
         AdjustmentType::Reduction => InsertStockInOrOutLine::StockOut(InsertStockOutLine {
             r#type: StockOutType::InventoryReduction,
@@ -143,6 +144,7 @@ pub fn generate(
             expiry_date: None,
             cost_price_per_pack: None,
             sell_price_per_pack: None,
+            quantity_in_unit: None,
         }),
     };
 
@@ -39,6 +39,7 @@ pub struct InsertInventoryAdjustment {
 pub enum InsertInventoryAdjustmentError {
     InvalidStore,
     StockLineDoesNotExist,
+    DateInClosedPeriod,
     StockLineReducedBelowZero(StockLine),
     InvalidAdjustment,
     AdjustmentReasonNotValid,
@@ -137,6 +138,7 @@ mod test {
         invoice::inventory_adjustment::{
             adjust_existing_stock::InsertInventoryAdjustment, AdjustmentType,
         },
+        period_close::close_period_for_today,
         service_provider::ServiceProvider,
     };
 
@@ -273,6 +275,20 @@ mod test {
             ),
             Err(ServiceError::StockLineReducedBelowZero(stock_line))
         );
+
+        // Date in closed period
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        assert_eq!(
+            service.insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    stock_line_id: mock_stock_line_a().id,
+                    adjustment: 2.0,
+                    ..Default::default()
+                }
+            ),
+            Err(ServiceError::DateInClosedPeriod)
+        );
     }
 
     #[actix_rt::test]
@@ -1,7 +1,9 @@
+use chrono::Utc;
 use repository::{RepositoryError, StockLine, StorageConnection};
 
 use crate::common_stock::{check_stock_line_exists, CommonStockLineError};
 use crate::invoice::inventory_adjustment::adjust_existing_stock::AdjustmentType;
+use crate::period_close::validate_not_in_closed_period;
 
 use crate::stocktake_line::validate::{check_active_adjustment_reasons, check_reason_is_valid};
 
@@ -21,6 +23,10 @@ pub fn validate(
         },
     )?;
 
+    if !validate_not_in_closed_period(connection, store_id, Utc::now().naive_utc())? {
+        return Err(DateInClosedPeriod);
+    }
+
     if input.adjustment <= 0.0 {
         return Err(InvalidAdjustment);
     };
@@ -24,6 +24,7 @@ pub struct InsertOutboundShipment {
 #[derive(Clone, Debug, PartialEq)]
 pub enum InsertOutboundShipmentError {
     InvoiceAlreadyExists,
+    DateInClosedPeriod,
     // Name validation
     OtherPartyNotACustomer,
     OtherPartyNotVisible,
@@ -101,7 +102,7 @@ mod test {
 
     use crate::{
         invoice::outbound_shipment::insert::InsertOutboundShipment,
-        service_provider::ServiceProvider,
+        period_close::close_period_for_today, service_provider::ServiceProvider,
     };
 
     use super::InsertOutboundShipmentError;
@@ -190,6 +191,19 @@ mod test {
             ),
             Err(ServiceError::OtherPartyNotACustomer)
         );
+        // DateInClosedPeriod
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        assert_eq!(
+            service.insert_outbound_shipment(
+                &context,
+                inline_init(|r: &mut InsertOutboundShipment| {
+                    r.id = "new_id".to_string();
+                    r.other_party_id
+                        .clone_from(&mock_name_linked_to_store_join().name_link_id);
+                })
+            ),
+            Err(ServiceError::DateInClosedPeriod)
+        );
 
         // TODO NewlyCreatedInvoiceDoesNotExist
     }
@@ -1,7 +1,9 @@
+use chrono::Utc;
 use repository::Name;
 use repository::StorageConnection;
 
 use crate::invoice::check_invoice_exists;
+use crate::period_close::validate_not_in_closed_period;
 use crate::validate::{check_other_party, CheckOtherPartyType, OtherPartyErrors};
 
 use super::{InsertOutboundShipment, InsertOutboundShipmentError};
@@ -15,6 +17,9 @@ pub fn validate(
     if (check_invoice_exists(&input.id, connection)?).is_some() {
         return Err(InvoiceAlreadyExists);
     }
+    if !validate_not_in_closed_period(connection, store_id, Utc::now().naive_utc())? {
+        return Err(DateInClosedPeriod);
+    }
 
     let other_party = check_other_party(
         connection,
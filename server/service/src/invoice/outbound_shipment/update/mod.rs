@@ -45,6 +45,7 @@ pub enum UpdateOutboundShipmentError {
     NotThisStoreInvoice,
     CannotIssueInForeignCurrency,
     OtherPartyDoesNotExist,
+    DateInClosedPeriod,
     // Error applies to unallocated lines with above zero quantity
     CanOnlyChangeToAllocatedWhenNoUnallocatedLines(Vec<InvoiceLine>),
     // Internal
@@ -184,6 +185,7 @@ mod test {
             UpdateOutboundShipment, UpdateOutboundShipmentStatus,
         },
         invoice_line::ShipmentTaxUpdate,
+        period_close::close_period_for_today,
         service_provider::ServiceProvider,
     };
 
@@ -318,6 +320,18 @@ mod test {
             ),
             Err(ServiceError::NotThisStoreInvoice)
         );
+        // DateInClosedPeriod
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        assert_eq!(
+            service.update_outbound_shipment(
+                &context,
+                inline_init(|r: &mut UpdateOutboundShipment| {
+                    r.id = outbound_shipment_no_stock().id;
+                    r.status = Some(UpdateOutboundShipmentStatus::Picked);
+                })
+            ),
+            Err(ServiceError::DateInClosedPeriod)
+        );
 
         // TODO CanOnlyChangeToAllocatedWhenNoUnallocatedLines
     }
@@ -2,6 +2,7 @@ use crate::invoice::{
     check_invoice_exists, check_invoice_is_editable, check_invoice_type, check_status_change,
     check_store,
 };
+use crate::period_close::validate_not_in_closed_period;
 use crate::validate::check_patient_exists;
 use repository::{
     ClinicianRowRepository, EqualFilter, InvoiceLineFilter, InvoiceLineRepository, RepositoryError,
@@ -37,6 +38,15 @@ pub fn validate(
         check_patient_exists(connection, patient_id)?.ok_or(PatientDoesNotExist)?;
     }
 
+    // Stock movements of the prescription are dated at the backdated datetime
+    if let Some(backdated_datetime) = patch.backdated_datetime.or(invoice.backdated_datetime) {
+        if !validate_not_in_closed_period(connection, store_id, backdated_datetime)? {
+            return Err(CantBackDate(
+                "Can't backdate into a closed period".to_string(),
+            ));
+        }
+    }
+
     if patch.backdated_datetime.is_some() {
         // Check if we have any lines allocated to this invoice, if so we can't backdate
         let line_count = InvoiceLineRepository::new(connection).count(Some(
@@ -91,6 +91,7 @@ pub fn generate(
             expiry_date: None,
             cost_price_per_pack: None,
             sell_price_per_pack: None,
+            quantity_in_unit: None,
         })
         .collect();
 
@@ -29,6 +29,7 @@ pub struct InsertSupplierReturn {
 #[derive(Clone, Debug, PartialEq)]
 pub enum InsertSupplierReturnError {
     InvoiceAlreadyExists,
+    DateInClosedPeriod,
     // Original invoice/shipment validation
     InboundShipmentDoesNotExist,
     InboundShipmentDoesNotBelongToCurrentStore,
@@ -145,6 +146,7 @@ mod test {
             stock_out_line::InsertStockOutLineError,
             update_return_reason_id::UpdateLineReturnReasonError,
         },
+        period_close::close_period_for_today,
         service_provider::ServiceProvider,
     };
 
@@ -330,6 +332,20 @@ mod test {
                 error: UpdateLineReturnReasonError::ReasonDoesNotExist,
             }),
         );
+
+        // DateInClosedPeriod
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        assert_eq!(
+            service_provider.invoice_service.insert_supplier_return(
+                &context,
+                InsertSupplierReturn {
+                    id: "some_new_id".to_string(),
+                    other_party_id: mock_name_a().id,
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::DateInClosedPeriod),
+        );
     }
 
     #[actix_rt::test]
@@ -52,6 +52,7 @@ pub fn generate(
             expiry_date: None,
             cost_price_per_pack: None,
             sell_price_per_pack: None,
+            quantity_in_unit: None,
         })
         .collect();
 
@@ -67,6 +68,7 @@ pub fn generate(
             r#type: Some(StockOutType::SupplierReturn),
             tax: None,
             total_before_tax: None,
+            quantity_in_unit: None,
         })
         .collect();
 
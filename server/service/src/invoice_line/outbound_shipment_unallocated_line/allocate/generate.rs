@@ -1,22 +1,27 @@
 use std::cmp::Ordering;
 
+use chrono::{Duration, NaiveDate};
 use repository::{
     EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
     Pagination, RepositoryError, StockLine, StockLineFilter, StockLineRepository, StockLineSort,
     StockLineSortField, StorageConnection,
 };
-use util::{
-    constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset,
-    fraction_is_integer, uuid,
-};
+use util::{constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset, uuid};
 
-use crate::invoice_line::{
-    outbound_shipment_unallocated_line::{
-        DeleteOutboundShipmentUnallocatedLine, UpdateOutboundShipmentUnallocatedLine,
+use crate::{
+    invoice_line::{
+        outbound_shipment_unallocated_line::{
+            DeleteOutboundShipmentUnallocatedLine, UpdateOutboundShipmentUnallocatedLine,
+        },
+        stock_out_line::{InsertStockOutLine, StockOutType, UpdateStockOutLine},
     },
-    stock_out_line::{InsertStockOutLine, StockOutType, UpdateStockOutLine},
+    item::unit_of_measure::{round_number_of_packs, PackRounding},
+    shelf_life_rule::get_minimum_remaining_shelf_life_days,
+    store_preference::get_store_preferences,
 };
 
+use super::strategy::StockLineAllocator;
+
 #[derive(Default)]
 pub struct GenerateOutput {
     pub update_lines: Vec<UpdateStockOutLine>,
@@ -25,6 +30,8 @@ pub struct GenerateOutput {
     pub delete_unallocated_line: Option<DeleteOutboundShipmentUnallocatedLine>,
     pub skipped_expired_stock_lines: Vec<StockLine>,
     pub skipped_on_hold_stock_lines: Vec<StockLine>,
+    pub skipped_short_shelf_life_stock_lines: Vec<StockLine>,
+    pub skipped_vvm_failed_stock_lines: Vec<StockLine>,
     pub issued_expiring_soon_stock_lines: Vec<StockLine>,
 }
 
@@ -47,29 +54,47 @@ pub fn generate(
     // Asc, by expiry date, nulls last
     let sorted_available_stock_lines =
         get_sorted_available_stock_lines(connection, store_id, &unallocated_line)?;
-    // Use FEFO to allocate
+
+    // Stock with less than minimum remaining shelf life for this customer and item is not issued
+    let minimum_expiry_date = get_minimum_remaining_shelf_life_days(
+        connection,
+        store_id,
+        &unallocated_line.invoice_row.name_link_id,
+        &unallocated_line.item_row.id,
+    )?
+    .map(|days| date_now_with_offset(Duration::days(days as i64)));
+
+    let mut eligible_stock_lines = Vec::new();
     for stock_line in sorted_available_stock_lines {
-        let can_use = get_stock_line_eligibility(&stock_line)
-            .map(|eligibility| match eligibility {
-                StockLineAlert::OnHold => {
-                    result.skipped_on_hold_stock_lines.push(stock_line.clone());
-                    false
-                }
-                StockLineAlert::Expired => {
-                    result.skipped_expired_stock_lines.push(stock_line.clone());
-                    false
-                }
-                StockLineAlert::ExpiringSoon => {
-                    result
-                        .issued_expiring_soon_stock_lines
-                        .push(stock_line.clone());
-                    true
-                }
-            })
-            .unwrap_or(true);
-
-        if !can_use {
-            continue;
+        match get_stock_line_eligibility(&stock_line, minimum_expiry_date) {
+            Some(StockLineAlert::OnHold) => result.skipped_on_hold_stock_lines.push(stock_line),
+            Some(StockLineAlert::VvmFailed) => {
+                result.skipped_vvm_failed_stock_lines.push(stock_line)
+            }
+            Some(StockLineAlert::Expired) => result.skipped_expired_stock_lines.push(stock_line),
+            Some(StockLineAlert::ShortShelfLife) => {
+                result.skipped_short_shelf_life_stock_lines.push(stock_line)
+            }
+            Some(StockLineAlert::ExpiringSoon) | None => eligible_stock_lines.push(stock_line),
+        }
+    }
+
+    // Use store allocation strategy (FEFO by default) to allocate
+    let allocator = StockLineAllocator::new(
+        connection,
+        get_store_preferences(connection, store_id)?,
+        &eligible_stock_lines,
+    )?;
+
+    while let Some(stock_line) =
+        allocator.next_stock_line(remaining_to_allocate, &mut eligible_stock_lines)
+    {
+        if let Some(StockLineAlert::ExpiringSoon) =
+            get_stock_line_eligibility(&stock_line, minimum_expiry_date)
+        {
+            result
+                .issued_expiring_soon_stock_lines
+                .push(stock_line.clone());
         }
 
         let packs_to_allocate =
@@ -113,11 +138,16 @@ pub fn generate(
 
 enum StockLineAlert {
     OnHold,
+    VvmFailed,
     Expired,
+    ShortShelfLife,
     ExpiringSoon,
 }
 
-fn get_stock_line_eligibility(stock_line: &StockLine) -> Option<StockLineAlert> {
+fn get_stock_line_eligibility(
+    stock_line: &StockLine,
+    minimum_expiry_date: Option<NaiveDate>,
+) -> Option<StockLineAlert> {
     use StockLineAlert::*;
     let stock_line_row = &stock_line.stock_line_row;
     // Expired
@@ -125,6 +155,13 @@ fn get_stock_line_eligibility(stock_line: &StockLine) -> Option<StockLineAlert>
         return Some(OnHold);
     }
 
+    if stock_line_row
+        .vvm_status
+        .is_some_and(|vvm_status| vvm_status.is_failed())
+    {
+        return Some(VvmFailed);
+    }
+
     let expiry_date = match &stock_line_row.expiry_date {
         Some(expiry_date) => expiry_date,
         None => return None,
@@ -134,6 +171,12 @@ fn get_stock_line_eligibility(stock_line: &StockLine) -> Option<StockLineAlert>
         return Some(Expired);
     }
 
+    if let Some(minimum_expiry_date) = minimum_expiry_date {
+        if let Ordering::Less = expiry_date.cmp(&minimum_expiry_date) {
+            return Some(ShortShelfLife);
+        }
+    }
+
     if let Ordering::Less =
         expiry_date.cmp(&date_now_with_offset(stock_line_expiring_soon_offset()))
     {
@@ -165,6 +208,7 @@ fn generate_new_line(
         expiry_date: None,
         cost_price_per_pack: None,
         sell_price_per_pack: None,
+        quantity_in_unit: None,
     }
 }
 
@@ -186,6 +230,7 @@ fn try_allocate_existing_line(
                 total_before_tax: None,
                 tax: None,
                 note: None,
+                quantity_in_unit: None,
             }
         })
 }
@@ -196,14 +241,11 @@ fn packs_to_allocate_from_stock_line(remaining_to_allocate: f64, line: &StockLin
     if available_quantity < remaining_to_allocate {
         return line_row.available_number_of_packs;
     }
-    // We don't want to use fractions for number_of_packs (issue here) - to discuss
-    let fractional_number_of_packs = remaining_to_allocate / line_row.pack_size;
-
-    if fraction_is_integer(fractional_number_of_packs) {
-        return fractional_number_of_packs;
-    }
-
-    fractional_number_of_packs.floor() + 1.0
+    // Packs are not split when allocating, at least the remaining quantity is allocated
+    round_number_of_packs(
+        remaining_to_allocate / line_row.pack_size,
+        PackRounding::UpToWholePacks,
+    )
 }
 
 fn get_sorted_available_stock_lines(
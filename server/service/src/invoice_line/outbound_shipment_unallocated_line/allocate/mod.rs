@@ -17,6 +17,7 @@ use super::{
 };
 
 mod generate;
+mod strategy;
 mod test;
 use generate::{generate, GenerateOutput};
 
@@ -62,6 +63,10 @@ pub struct AllocateLineResult {
     pub updates: Vec<InvoiceLine>,
     pub skipped_expired_stock_lines: Vec<StockLine>,
     pub skipped_on_hold_stock_lines: Vec<StockLine>,
+    /// Stock lines with less than the minimum remaining shelf life for the customer or item
+    pub skipped_short_shelf_life_stock_lines: Vec<StockLine>,
+    /// Stock lines with a vaccine vial monitor past the discard point
+    pub skipped_vvm_failed_stock_lines: Vec<StockLine>,
     pub issued_expiring_soon_stock_lines: Vec<StockLine>,
 }
 
@@ -82,6 +87,8 @@ pub fn allocate_outbound_shipment_unallocated_line(
                 delete_unallocated_line,
                 skipped_expired_stock_lines,
                 skipped_on_hold_stock_lines,
+                skipped_short_shelf_life_stock_lines,
+                skipped_vvm_failed_stock_lines,
                 issued_expiring_soon_stock_lines,
             } = generate(connection, &ctx.store_id, unallocated_line)?;
 
@@ -91,6 +98,8 @@ pub fn allocate_outbound_shipment_unallocated_line(
                 updates: vec![],
                 skipped_expired_stock_lines,
                 skipped_on_hold_stock_lines,
+                skipped_short_shelf_life_stock_lines,
+                skipped_vvm_failed_stock_lines,
                 issued_expiring_soon_stock_lines,
             };
 
@@ -0,0 +1,163 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use chrono::NaiveDateTime;
+use repository::{
+    AllocationStrategy, EqualFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
+    RepositoryError, StockLine, StorageConnection, StorePreferenceRow,
+};
+
+/// Picks the next stock line to allocate from, based on store's `AllocationStrategy`.
+/// Stock lines given to `next_stock_line` are expected to be sorted by expiry date (FEFO), when
+/// a strategy considers two stock lines to be equal the earlier one is used
+pub(crate) struct StockLineAllocator {
+    strategy: AllocationStrategy,
+    preferred_location_id: Option<String>,
+    // stock_line_id -> date stock line was received in store, only populated for FIFO
+    received_datetimes: HashMap<String, NaiveDateTime>,
+}
+
+impl StockLineAllocator {
+    pub(crate) fn new(
+        connection: &StorageConnection,
+        store_preferences: StorePreferenceRow,
+        stock_lines: &[StockLine],
+    ) -> Result<Self, RepositoryError> {
+        let StorePreferenceRow {
+            allocation_strategy: strategy,
+            allocation_preferred_location_id: preferred_location_id,
+            ..
+        } = store_preferences;
+
+        let received_datetimes = match strategy {
+            AllocationStrategy::Fifo => get_received_datetimes(connection, stock_lines)?,
+            _ => HashMap::new(),
+        };
+
+        Ok(StockLineAllocator {
+            strategy,
+            preferred_location_id,
+            received_datetimes,
+        })
+    }
+
+    /// Removes and returns the stock line that should be allocated from next
+    pub(crate) fn next_stock_line(
+        &self,
+        remaining_to_allocate: f64,
+        stock_lines: &mut Vec<StockLine>,
+    ) -> Option<StockLine> {
+        if stock_lines.is_empty() {
+            return None;
+        }
+
+        let index = match self.strategy {
+            AllocationStrategy::Fefo => 0,
+            AllocationStrategy::Fifo => first_min_by(stock_lines, |a, b| {
+                // Stock lines without received date are used last
+                match (self.received_datetime(a), self.received_datetime(b)) {
+                    (Some(a), Some(b)) => a.cmp(b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            }),
+            AllocationStrategy::PreferLocation => stock_lines
+                .iter()
+                .position(|stock_line| {
+                    self.preferred_location_id.is_some()
+                        && stock_line.stock_line_row.location_id == self.preferred_location_id
+                })
+                .unwrap_or(0),
+            AllocationStrategy::AvoidBreakingPacks => first_min_by(stock_lines, |a, b| {
+                let a = broken_pack_remainder(remaining_to_allocate, a);
+                let b = broken_pack_remainder(remaining_to_allocate, b);
+                a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+            }),
+            AllocationStrategy::MinimiseBatches => stock_lines
+                .iter()
+                .position(|stock_line| stock_line.available_quantity() >= remaining_to_allocate)
+                .unwrap_or_else(|| {
+                    // Largest available quantity first
+                    first_min_by(stock_lines, |a, b| {
+                        b.available_quantity()
+                            .partial_cmp(&a.available_quantity())
+                            .unwrap_or(Ordering::Equal)
+                    })
+                }),
+        };
+
+        Some(stock_lines.remove(index))
+    }
+
+    fn received_datetime(&self, stock_line: &StockLine) -> Option<&NaiveDateTime> {
+        self.received_datetimes.get(&stock_line.stock_line_row.id)
+    }
+}
+
+/// Index of the first smallest element (`Iterator::min_by` returns the first of equal elements)
+fn first_min_by<F>(stock_lines: &[StockLine], compare: F) -> usize
+where
+    F: Fn(&StockLine, &StockLine) -> Ordering,
+{
+    stock_lines
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| compare(a, b))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Quantity of the last pack that would need to be broken if allocating from this stock line,
+/// 0 if stock line can be allocated in whole packs
+fn broken_pack_remainder(remaining_to_allocate: f64, stock_line: &StockLine) -> f64 {
+    // All available packs would be used
+    if stock_line.available_quantity() <= remaining_to_allocate {
+        return 0.0;
+    }
+
+    let pack_size = stock_line.stock_line_row.pack_size;
+    if pack_size <= 0.0 {
+        return 0.0;
+    }
+
+    match remaining_to_allocate % pack_size {
+        remainder if remainder == 0.0 => 0.0,
+        remainder => pack_size - remainder,
+    }
+}
+
+/// Stock lines are received via stock in lines (inbound shipments, inventory additions etc..),
+/// earliest delivered (or created if not delivered) datetime of those invoices is used
+fn get_received_datetimes(
+    connection: &StorageConnection,
+    stock_lines: &[StockLine],
+) -> Result<HashMap<String, NaiveDateTime>, RepositoryError> {
+    let stock_line_ids: Vec<String> = stock_lines
+        .iter()
+        .map(|stock_line| stock_line.stock_line_row.id.clone())
+        .collect();
+
+    let stock_in_lines = InvoiceLineRepository::new(connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .stock_line_id(EqualFilter::equal_any(stock_line_ids))
+            .r#type(InvoiceLineType::StockIn.equal_to()),
+    )?;
+
+    let mut result: HashMap<String, NaiveDateTime> = HashMap::new();
+    for line in stock_in_lines {
+        let Some(stock_line_id) = line.invoice_line_row.stock_line_id else {
+            continue;
+        };
+        let received_datetime = line
+            .invoice_row
+            .delivered_datetime
+            .unwrap_or(line.invoice_row.created_datetime);
+
+        result
+            .entry(stock_line_id)
+            .and_modify(|existing| *existing = (*existing).min(received_datetime))
+            .or_insert(received_datetime);
+    }
+
+    Ok(result)
+}
@@ -4,12 +4,14 @@ mod test {
     use chrono::{Duration, NaiveDate};
     use repository::{
         mock::{
-            mock_item_a, mock_item_b, mock_name_a, mock_outbound_shipment_a_invoice_lines,
-            mock_store_a, mock_store_b, MockData, MockDataInserts,
+            mock_item_a, mock_item_b, mock_location_1, mock_name_a,
+            mock_outbound_shipment_a_invoice_lines, mock_store_a, mock_store_b, MockData,
+            MockDataInserts,
         },
         test_db::{setup_all, setup_all_with_data},
-        InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType, InvoiceRow, InvoiceType,
-        StockLine, StockLineRow,
+        AllocationStrategy, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType, InvoiceRow,
+        InvoiceType, ShelfLifeRuleRowRepository, StockLine, StockLineRow, StorePreferenceRow,
+        StorePreferenceRowRepository, VvmStatus,
     };
     use util::{
         constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset, inline_edit,
@@ -19,6 +21,7 @@ mod test {
     use crate::{
         invoice_line::AllocateOutboundShipmentUnallocatedLineError as ServiceError,
         service_provider::ServiceProvider,
+        shelf_life_rule::{UpsertShelfLifeRule, UpsertShelfLifeRuleError},
     };
 
     #[actix_rt::test]
@@ -665,4 +668,427 @@ mod test {
             })
         );
     }
+
+    fn strategy_invoice() -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = "invoice".to_string();
+            r.store_id = mock_store_a().id;
+            r.name_link_id = mock_name_a().id;
+            r.r#type = InvoiceType::OutboundShipment;
+        })
+    }
+
+    fn strategy_unallocated_line(number_of_packs: f64) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = "line".to_string();
+            r.invoice_id = strategy_invoice().id;
+            r.item_link_id = mock_item_a().id;
+            r.r#type = InvoiceLineType::UnallocatedStock;
+            r.number_of_packs = number_of_packs;
+            r.pack_size = 1.0;
+        })
+    }
+
+    fn strategy_stock_line(
+        id: &str,
+        pack_size: f64,
+        available_number_of_packs: f64,
+        expiry_date: NaiveDate,
+    ) -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = id.to_string();
+            r.store_id = mock_store_a().id;
+            r.item_link_id = mock_item_a().id;
+            r.pack_size = pack_size;
+            r.available_number_of_packs = available_number_of_packs;
+            r.total_number_of_packs = available_number_of_packs;
+            r.expiry_date = Some(expiry_date);
+        })
+    }
+
+    /// Allocates `unallocated_line` with store preference set to `strategy`, returns stock line ids
+    /// and number of packs of inserted lines in order of allocation
+    async fn allocate_with_strategy(
+        db_name: &str,
+        preferences: StorePreferenceRow,
+        unallocated_line: InvoiceLineRow,
+        extra_data: MockData,
+    ) -> Vec<(String, f64)> {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            db_name,
+            MockDataInserts::none()
+                .stores()
+                .items()
+                .names()
+                .units()
+                .currencies()
+                .locations(),
+            extra_data.join(inline_init(|r: &mut MockData| {
+                r.invoices = vec![strategy_invoice()];
+                r.invoice_lines = vec![unallocated_line.clone()];
+            })),
+        )
+        .await;
+
+        StorePreferenceRowRepository::new(&connection)
+            .upsert_one(&StorePreferenceRow {
+                id: mock_store_a().id,
+                ..preferences
+            })
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+
+        let result = service_provider
+            .invoice_line_service
+            .allocate_outbound_shipment_unallocated_line(&context, unallocated_line.id)
+            .unwrap();
+
+        result
+            .inserts
+            .into_iter()
+            .map(|line| {
+                (
+                    line.invoice_line_row.stock_line_id.unwrap(),
+                    line.invoice_line_row.number_of_packs,
+                )
+            })
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_fifo() {
+        fn inbound(id: &str, delivered_day: u32) -> InvoiceRow {
+            inline_init(|r: &mut InvoiceRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.name_link_id = mock_name_a().id;
+                r.r#type = InvoiceType::InboundShipment;
+                r.delivered_datetime = NaiveDate::from_ymd_opt(2024, 1, delivered_day)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0);
+            })
+        }
+
+        fn stock_in_line(invoice_id: &str, stock_line_id: &str) -> InvoiceLineRow {
+            inline_init(|r: &mut InvoiceLineRow| {
+                r.id = format!("{invoice_id}_line");
+                r.invoice_id = invoice_id.to_string();
+                r.item_link_id = mock_item_a().id;
+                r.stock_line_id = Some(stock_line_id.to_string());
+                r.r#type = InvoiceLineType::StockIn;
+                r.pack_size = 1.0;
+                r.number_of_packs = 10.0;
+            })
+        }
+
+        let expiry = NaiveDate::from_ymd_opt(3021, 1, 1).unwrap();
+        let result = allocate_with_strategy(
+            "allocate_unallocated_line_fifo",
+            inline_init(|r: &mut StorePreferenceRow| {
+                r.allocation_strategy = AllocationStrategy::Fifo;
+            }),
+            strategy_unallocated_line(15.0),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    // Expires first but received last
+                    strategy_stock_line("received_last", 1.0, 10.0, expiry),
+                    strategy_stock_line("received_first", 1.0, 10.0, expiry + Duration::days(1)),
+                    // Never received, used last
+                    strategy_stock_line("not_received", 1.0, 10.0, expiry),
+                ];
+                r.invoices = vec![inbound("inbound_a", 2), inbound("inbound_b", 1)];
+                r.invoice_lines = vec![
+                    stock_in_line("inbound_a", "received_last"),
+                    stock_in_line("inbound_b", "received_first"),
+                ];
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            vec![
+                ("received_first".to_string(), 10.0),
+                ("received_last".to_string(), 5.0)
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_prefer_location() {
+        let expiry = NaiveDate::from_ymd_opt(3021, 1, 1).unwrap();
+        let in_preferred_location = inline_edit(
+            &strategy_stock_line("in_location", 1.0, 10.0, expiry + Duration::days(1)),
+            |mut u| {
+                u.location_id = Some(mock_location_1().id);
+                u
+            },
+        );
+
+        let result = allocate_with_strategy(
+            "allocate_unallocated_line_prefer_location",
+            inline_init(|r: &mut StorePreferenceRow| {
+                r.allocation_strategy = AllocationStrategy::PreferLocation;
+                r.allocation_preferred_location_id = Some(mock_location_1().id);
+            }),
+            strategy_unallocated_line(15.0),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    strategy_stock_line("no_location", 1.0, 10.0, expiry),
+                    in_preferred_location,
+                ];
+            }),
+        )
+        .await;
+
+        assert_eq!(
+            result,
+            vec![
+                ("in_location".to_string(), 10.0),
+                ("no_location".to_string(), 5.0)
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_avoid_breaking_packs() {
+        let expiry = NaiveDate::from_ymd_opt(3021, 1, 1).unwrap();
+        let result = allocate_with_strategy(
+            "allocate_unallocated_line_avoid_breaking_packs",
+            inline_init(|r: &mut StorePreferenceRow| {
+                r.allocation_strategy = AllocationStrategy::AvoidBreakingPacks;
+            }),
+            strategy_unallocated_line(20.0),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    // Would need to break a pack of 6 (3 packs = 18, 4 packs = 24)
+                    strategy_stock_line("pack_of_6", 6.0, 10.0, expiry),
+                    // Would need to break a pack of 3 (only 1 unit over)
+                    strategy_stock_line("pack_of_3", 3.0, 10.0, expiry + Duration::days(1)),
+                    // 4 whole packs
+                    strategy_stock_line("pack_of_5", 5.0, 10.0, expiry + Duration::days(2)),
+                ];
+            }),
+        )
+        .await;
+
+        assert_eq!(result, vec![("pack_of_5".to_string(), 4.0)]);
+    }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_minimise_batches() {
+        let expiry = NaiveDate::from_ymd_opt(3021, 1, 1).unwrap();
+        let result = allocate_with_strategy(
+            "allocate_unallocated_line_minimise_batches",
+            inline_init(|r: &mut StorePreferenceRow| {
+                r.allocation_strategy = AllocationStrategy::MinimiseBatches;
+            }),
+            strategy_unallocated_line(25.0),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    strategy_stock_line("small", 1.0, 5.0, expiry),
+                    strategy_stock_line("medium", 1.0, 10.0, expiry + Duration::days(1)),
+                    strategy_stock_line("large", 1.0, 20.0, expiry + Duration::days(2)),
+                ];
+            }),
+        )
+        .await;
+
+        // Largest first (20), then first stock line that can fulfil the remaining 5
+        assert_eq!(
+            result,
+            vec![("large".to_string(), 20.0), ("small".to_string(), 5.0)]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_minimum_shelf_life() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "allocate_unallocated_line_minimum_shelf_life",
+            MockDataInserts::none()
+                .stores()
+                .items()
+                .names()
+                .units()
+                .currencies(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![strategy_invoice()];
+                r.invoice_lines = vec![strategy_unallocated_line(10.0)];
+                r.stock_lines = vec![
+                    // Not expiring soon, but less than 3 months shelf life
+                    strategy_stock_line(
+                        "two_months",
+                        1.0,
+                        10.0,
+                        date_now_with_offset(Duration::days(60)),
+                    ),
+                    strategy_stock_line(
+                        "six_months",
+                        1.0,
+                        10.0,
+                        date_now_with_offset(Duration::days(180)),
+                    ),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+
+        // Customer refuses stock with less than 3 months shelf life
+        service_provider
+            .shelf_life_rule_service
+            .upsert_shelf_life_rule(
+                &context,
+                UpsertShelfLifeRule {
+                    id: "customer_rule".to_string(),
+                    name_id: Some(mock_name_a().id),
+                    item_id: None,
+                    minimum_remaining_days: 90,
+                },
+            )
+            .unwrap();
+        // Rule for another item should not apply
+        service_provider
+            .shelf_life_rule_service
+            .upsert_shelf_life_rule(
+                &context,
+                UpsertShelfLifeRule {
+                    id: "item_b_rule".to_string(),
+                    name_id: None,
+                    item_id: Some(mock_item_b().id),
+                    minimum_remaining_days: 365,
+                },
+            )
+            .unwrap();
+
+        let result = service_provider
+            .invoice_line_service
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                strategy_unallocated_line(10.0).id,
+            )
+            .unwrap();
+
+        assert_eq!(result.skipped_short_shelf_life_stock_lines.len(), 1);
+        assert_eq!(
+            result.skipped_short_shelf_life_stock_lines[0]
+                .stock_line_row
+                .id,
+            "two_months"
+        );
+        assert_eq!(result.inserts.len(), 1);
+        assert_eq!(
+            result.inserts[0].invoice_line_row.stock_line_id,
+            Some("six_months".to_string())
+        );
+
+        // Negative minimum remaining days
+        assert_eq!(
+            service_provider
+                .shelf_life_rule_service
+                .upsert_shelf_life_rule(
+                    &context,
+                    UpsertShelfLifeRule {
+                        id: "invalid_rule".to_string(),
+                        minimum_remaining_days: -1,
+                        ..Default::default()
+                    },
+                ),
+            Err(UpsertShelfLifeRuleError::MinimumRemainingDaysCannotBeNegative)
+        );
+
+        assert_eq!(
+            service_provider
+                .shelf_life_rule_service
+                .get_shelf_life_rules(&context)
+                .unwrap()
+                .len(),
+            2
+        );
+        service_provider
+            .shelf_life_rule_service
+            .delete_shelf_life_rule(&context, "customer_rule".to_string())
+            .unwrap();
+        assert_eq!(
+            ShelfLifeRuleRowRepository::new(&connection).find_one_by_id("customer_rule"),
+            Ok(None)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_vvm_failed() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "allocate_unallocated_line_vvm_failed",
+            MockDataInserts::none()
+                .stores()
+                .items()
+                .names()
+                .units()
+                .currencies(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![strategy_invoice()];
+                r.invoice_lines = vec![strategy_unallocated_line(10.0)];
+                r.stock_lines = vec![
+                    // Would be allocated first (FEFO) if the vial monitor hadn't failed
+                    inline_edit(
+                        &strategy_stock_line(
+                            "stage_3",
+                            1.0,
+                            10.0,
+                            date_now_with_offset(Duration::days(180)),
+                        ),
+                        |mut r| {
+                            r.vvm_status = Some(VvmStatus::Stage3);
+                            r
+                        },
+                    ),
+                    inline_edit(
+                        &strategy_stock_line(
+                            "stage_2",
+                            1.0,
+                            10.0,
+                            date_now_with_offset(Duration::days(365)),
+                        ),
+                        |mut r| {
+                            r.vvm_status = Some(VvmStatus::Stage2);
+                            r
+                        },
+                    ),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+
+        let result = service_provider
+            .invoice_line_service
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                strategy_unallocated_line(10.0).id,
+            )
+            .unwrap();
+
+        assert_eq!(result.skipped_vvm_failed_stock_lines.len(), 1);
+        assert_eq!(
+            result.skipped_vvm_failed_stock_lines[0].stock_line_row.id,
+            "stage_3"
+        );
+        assert_eq!(result.inserts.len(), 1);
+        assert_eq!(
+            result.inserts[0].invoice_line_row.stock_line_id,
+            Some("stage_2".to_string())
+        );
+    }
 }
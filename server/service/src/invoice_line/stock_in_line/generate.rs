@@ -80,14 +80,15 @@ pub fn generate_batch(
         overwrite_stock_levels,
     );
 
-    let (barcode_id, supplier_link_id) = match existing_stock_line {
+    let (barcode_id, supplier_link_id, vvm_status) = match existing_stock_line {
         Some(stock_line) => (
             // if no new barcode, use the existing one if exists
             barcode_id.or(stock_line.barcode_id),
             // if stock_line already has supplier, use that
             stock_line.supplier_link_id.or(Some(supplier_link_id)),
+            stock_line.vvm_status,
         ),
-        None => (barcode_id, Some(supplier_link_id)),
+        None => (barcode_id, Some(supplier_link_id), None),
     };
 
     let stock_line_row = StockLineRow {
@@ -107,6 +108,7 @@ pub fn generate_batch(
         on_hold,
         barcode_id,
         item_variant_id,
+        vvm_status,
     };
 
     Ok(stock_line_row)
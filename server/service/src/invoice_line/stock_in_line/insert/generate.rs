@@ -90,6 +90,7 @@ fn generate_line(
         stock_on_hold: _,
         tax_percentage: _,
         r#type: _,
+        quantity_in_unit: _,
     }: InsertStockInLine,
     ItemRow {
         name: item_name,
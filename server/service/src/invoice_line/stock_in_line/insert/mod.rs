@@ -1,11 +1,15 @@
 use crate::{
-    invoice_line::query::get_invoice_line, service_provider::ServiceContext, NullableUpdate,
-    WithDBError,
+    invoice_line::query::get_invoice_line,
+    item::unit_of_measure::{
+        quantity_to_number_of_packs, ConvertQuantityError, PackRounding, QuantityInUnit,
+    },
+    service_provider::ServiceContext,
+    NullableUpdate, WithDBError,
 };
 use chrono::NaiveDate;
 use repository::{
     BarcodeRowRepository, InvoiceLine, InvoiceLineRowRepository, InvoiceRowRepository,
-    RepositoryError, StockLineRowRepository,
+    RepositoryError, StockLineRowRepository, StorageConnection,
 };
 
 mod generate;
@@ -43,6 +47,8 @@ pub struct InsertStockInLine {
     pub barcode: Option<String>,
     pub stock_on_hold: bool,
     pub item_variant_id: Option<String>,
+    /// Quantity in one of the item's units of measure, replaces number_of_packs when set
+    pub quantity_in_unit: Option<QuantityInUnit>,
 }
 
 type OutError = InsertStockInLineError;
@@ -54,6 +60,7 @@ pub fn insert_stock_in_line(
     let new_line = ctx
         .connection
         .transaction_sync(|connection| {
+            let input = resolve_quantity_in_unit(connection, input)?;
             let (item, invoice) = validate(&input, &ctx.store_id, connection)?;
             let GenerateResult {
                 invoice: invoice_user_update,
@@ -83,6 +90,30 @@ pub fn insert_stock_in_line(
     Ok(new_line)
 }
 
+fn resolve_quantity_in_unit(
+    connection: &StorageConnection,
+    mut input: InsertStockInLine,
+) -> Result<InsertStockInLine, OutError> {
+    let Some(quantity_in_unit) = input.quantity_in_unit.take() else {
+        return Ok(input);
+    };
+    // Invalid pack size is reported by validation
+    if input.pack_size <= 0.0 {
+        return Ok(input);
+    }
+
+    input.number_of_packs = quantity_to_number_of_packs(
+        connection,
+        &input.item_id,
+        &quantity_in_unit,
+        input.pack_size,
+        PackRounding::Fractional,
+    )
+    .map_err(OutError::InvalidQuantityInUnit)?;
+
+    Ok(input)
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum InsertStockInLineError {
     LineAlreadyExists,
@@ -97,6 +128,7 @@ pub enum InsertStockInLineError {
     PackSizeBelowOne,
     NumberOfPacksBelowZero,
     NewlyCreatedLineDoesNotExist,
+    InvalidQuantityInUnit(ConvertQuantityError),
 }
 
 impl From<RepositoryError> for InsertStockInLineError {
@@ -114,6 +114,7 @@ fn generate_line(
         tax_percentage,
         r#type: _,
         item_variant_id,
+        quantity_in_unit: _,
     }: UpdateStockInLine,
     current_line: InvoiceLineRow,
     new_item_option: Option<ItemRow>,
@@ -1,12 +1,15 @@
 use crate::{
     invoice_line::{query::get_invoice_line, ShipmentTaxUpdate},
+    item::unit_of_measure::{
+        quantity_to_number_of_packs, ConvertQuantityError, PackRounding, QuantityInUnit,
+    },
     service_provider::ServiceContext,
     NullableUpdate, WithDBError,
 };
 use chrono::NaiveDate;
 use repository::{
-    InvoiceLine, InvoiceLineRowRepository, InvoiceRowRepository, RepositoryError,
-    StockLineRowRepository,
+    EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRowRepository,
+    InvoiceRowRepository, RepositoryError, StockLineRowRepository, StorageConnection,
 };
 
 mod generate;
@@ -33,6 +36,8 @@ pub struct UpdateStockInLine {
     pub tax_percentage: Option<ShipmentTaxUpdate>,
     pub r#type: StockInType,
     pub item_variant_id: Option<NullableUpdate<String>>,
+    /// Quantity in one of the item's units of measure, replaces number_of_packs when set
+    pub quantity_in_unit: Option<QuantityInUnit>,
 }
 
 type OutError = UpdateStockInLineError;
@@ -44,6 +49,7 @@ pub fn update_stock_in_line(
     let updated_line = ctx
         .connection
         .transaction_sync(|connection| {
+            let input = resolve_quantity_in_unit(connection, input)?;
             let (line, item, invoice) = validate(&input, &ctx.store_id, connection)?;
 
             let GenerateResult {
@@ -78,6 +84,40 @@ pub fn update_stock_in_line(
     Ok(updated_line)
 }
 
+fn resolve_quantity_in_unit(
+    connection: &StorageConnection,
+    mut input: UpdateStockInLine,
+) -> Result<UpdateStockInLine, OutError> {
+    let Some(quantity_in_unit) = input.quantity_in_unit.take() else {
+        return Ok(input);
+    };
+    // Missing line and invalid pack size are reported by validation
+    let Some(line) = InvoiceLineRepository::new(connection)
+        .query_by_filter(InvoiceLineFilter::new().id(EqualFilter::equal_to(&input.id)))?
+        .pop()
+    else {
+        return Ok(input);
+    };
+    let pack_size = input.pack_size.unwrap_or(line.invoice_line_row.pack_size);
+    if pack_size <= 0.0 {
+        return Ok(input);
+    }
+    let item_id = input.item_id.clone().unwrap_or(line.item_row.id);
+
+    input.number_of_packs = Some(
+        quantity_to_number_of_packs(
+            connection,
+            &item_id,
+            &quantity_in_unit,
+            pack_size,
+            PackRounding::Fractional,
+        )
+        .map_err(OutError::InvalidQuantityInUnit)?,
+    );
+
+    Ok(input)
+}
+
 #[derive(Debug, PartialEq)]
 pub enum UpdateStockInLineError {
     LineDoesNotExist,
@@ -94,6 +134,7 @@ pub enum UpdateStockInLineError {
     BatchIsReserved,
     UpdatedLineDoesNotExist,
     NotThisInvoiceLine(String),
+    InvalidQuantityInUnit(ConvertQuantityError),
 }
 
 impl From<RepositoryError> for UpdateStockInLineError {
@@ -31,12 +31,19 @@ pub fn generate(
         adjust_total_number_of_packs,
     );
 
-    // Check if we need to override the pricing with a default
+    // Check if we need to override the pricing with a default, prices are looked up for the
+    // invoice date (e.g. back dated prescriptions)
     let pricing = get_pricing_for_item(
         ctx,
         ItemPriceLookup {
             item_id: item_row.id.clone(),
             customer_name_id: Some(invoice.name_link_id.clone()),
+            date: Some(
+                invoice
+                    .backdated_datetime
+                    .unwrap_or(invoice.created_datetime)
+                    .date(),
+            ),
         },
     )?;
     let new_line = generate_line(
@@ -67,6 +74,7 @@ fn generate_batch_update(
         stock_line_id: _,
         total_before_tax: _,
         tax_percentage: _,
+        quantity_in_unit: _,
     }: InsertStockOutLine,
     batch: StockLineRow,
     adjust_total_number_of_packs: bool,
@@ -108,6 +116,7 @@ fn generate_line(
         expiry_date: _,
         cost_price_per_pack: _,
         sell_price_per_pack: _,
+        quantity_in_unit: _,
     }: InsertStockOutLine,
     ItemRow {
         id: item_id,
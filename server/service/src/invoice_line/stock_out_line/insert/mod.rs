@@ -1,6 +1,16 @@
-use crate::{invoice_line::query::get_invoice_line, service_provider::ServiceContext, WithDBError};
+use crate::{
+    invoice_line::query::get_invoice_line,
+    item::unit_of_measure::{
+        quantity_to_number_of_packs, ConvertQuantityError, PackRounding, QuantityInUnit,
+    },
+    service_provider::ServiceContext,
+    WithDBError,
+};
 use chrono::NaiveDate;
-use repository::{InvoiceLine, InvoiceLineRowRepository, RepositoryError, StockLineRowRepository};
+use repository::{
+    EqualFilter, InvoiceLine, InvoiceLineRowRepository, RepositoryError, StockLineFilter,
+    StockLineRepository, StockLineRowRepository, StorageConnection,
+};
 
 mod generate;
 use generate::generate;
@@ -25,6 +35,8 @@ pub struct InsertStockOutLine {
     pub expiry_date: Option<NaiveDate>,
     pub cost_price_per_pack: Option<f64>,
     pub sell_price_per_pack: Option<f64>,
+    /// Quantity in one of the item's units of measure, replaces number_of_packs when set
+    pub quantity_in_unit: Option<QuantityInUnit>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -35,6 +47,7 @@ pub enum InsertStockOutLineError {
     InvoiceTypeDoesNotMatch,
     NotThisStoreInvoice,
     CannotEditFinalised,
+    DateInClosedPeriod,
     StockLineNotFound,
     NumberOfPacksBelowZero,
     LocationIsOnHold,
@@ -43,6 +56,7 @@ pub enum InsertStockOutLineError {
     NewlyCreatedLineDoesNotExist,
     BatchIsOnHold,
     ReductionBelowZero { stock_line_id: String },
+    InvalidQuantityInUnit(ConvertQuantityError),
 }
 
 impl From<RepositoryError> for InsertStockOutLineError {
@@ -72,6 +86,7 @@ pub fn insert_stock_out_line(
     let new_line = ctx
         .connection
         .transaction_sync(|connection| {
+            let input = resolve_quantity_in_unit(connection, input)?;
             let (item, invoice, batch) = validate(&connection, &input, &ctx.store_id)?;
             let (new_line, update_batch) = generate(ctx, input, item, batch, invoice)?;
             InvoiceLineRowRepository::new(connection).upsert_one(&new_line)?;
@@ -84,6 +99,38 @@ pub fn insert_stock_out_line(
     Ok(new_line)
 }
 
+fn resolve_quantity_in_unit(
+    connection: &StorageConnection,
+    mut input: InsertStockOutLine,
+) -> Result<InsertStockOutLine, OutError> {
+    let Some(quantity_in_unit) = input.quantity_in_unit.take() else {
+        return Ok(input);
+    };
+    // Missing stock line is reported by validation
+    let Some(stock_line) = StockLineRepository::new(connection)
+        .query_by_filter(
+            StockLineFilter::new().id(EqualFilter::equal_to(&input.stock_line_id)),
+            None,
+        )?
+        .pop()
+    else {
+        return Ok(input);
+    };
+
+    input.number_of_packs = quantity_to_number_of_packs(
+        connection,
+        &stock_line.item_row.id,
+        &quantity_in_unit,
+        input
+            .pack_size
+            .unwrap_or(stock_line.stock_line_row.pack_size),
+        PackRounding::Fractional,
+    )
+    .map_err(OutError::InvalidQuantityInUnit)?;
+
+    Ok(input)
+}
+
 #[cfg(test)]
 mod test {
     use repository::{
@@ -109,6 +156,7 @@ mod test {
             stock_out_line::InsertStockOutLine,
             stock_out_line::{InsertStockOutLineError as ServiceError, StockOutType},
         },
+        period_close::close_period_for_date,
         service_provider::ServiceProvider,
     };
 
@@ -608,11 +656,25 @@ mod test {
             inline_init(|r: &mut InsertStockOutLine| {
                 r.id = "new prescription line id".to_string();
                 r.r#type = StockOutType::Prescription;
-                r.invoice_id = prescription_id;
+                r.invoice_id = prescription_id.clone();
                 r.stock_line_id = stock_line_id.clone();
                 r.number_of_packs = 10.0;
             }),
         );
         assert!(result.is_ok());
+
+        // Check we can't add stock to the backdated prescription once its period is closed
+        close_period_for_date(&context.connection, &context.store_id, datetime.date());
+        let result = service.insert_stock_out_line(
+            &context,
+            inline_init(|r: &mut InsertStockOutLine| {
+                r.id = "closed period prescription line id".to_string();
+                r.r#type = StockOutType::Prescription;
+                r.invoice_id = prescription_id;
+                r.stock_line_id = stock_line_id.clone();
+                r.number_of_packs = 1.0;
+            }),
+        );
+        assert_eq!(result, Err(ServiceError::DateInClosedPeriod));
     }
 }
@@ -1,3 +1,4 @@
+use chrono::Utc;
 use repository::{InvoiceRow, InvoiceStatus, ItemRow, StockLine, StorageConnection};
 
 use crate::{
@@ -8,6 +9,7 @@ use crate::{
         validate::{check_line_exists, check_number_of_packs},
         LocationIsOnHoldError,
     },
+    period_close::validate_not_in_closed_period,
     stock_line::historical_stock::get_historical_stock_line_available_quantity,
 };
 
@@ -57,6 +59,11 @@ pub fn validate(
     if !check_invoice_is_editable(&invoice) {
         return Err(CannotEditFinalised);
     }
+    // Stock movement is dated at the backdated datetime of the invoice
+    let datetime = invoice_backdated_date(&invoice).unwrap_or_else(|| Utc::now().naive_utc());
+    if !validate_not_in_closed_period(connection, store_id, datetime)? {
+        return Err(DateInClosedPeriod);
+    }
     if !check_batch_on_hold(&batch) {
         return Err(BatchIsOnHold);
     }
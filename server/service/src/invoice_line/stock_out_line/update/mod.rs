@@ -1,10 +1,14 @@
 use repository::{
-    InvoiceLine, InvoiceLineRow, InvoiceLineRowRepository, RepositoryError, StockLine,
-    StockLineRowRepository,
+    EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRow,
+    InvoiceLineRowRepository, RepositoryError, StockLine, StockLineRowRepository,
+    StorageConnection,
 };
 
 use crate::{
     invoice_line::{query::get_invoice_line, ShipmentTaxUpdate},
+    item::unit_of_measure::{
+        quantity_to_number_of_packs, ConvertQuantityError, PackRounding, QuantityInUnit,
+    },
     service_provider::ServiceContext,
 };
 
@@ -24,6 +28,8 @@ pub struct UpdateStockOutLine {
     pub total_before_tax: Option<f64>,
     pub tax: Option<ShipmentTaxUpdate>,
     pub note: Option<String>,
+    /// Quantity in one of the item's units of measure, replaces number_of_packs when set
+    pub quantity_in_unit: Option<QuantityInUnit>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -36,6 +42,7 @@ pub enum UpdateStockOutLineError {
     NotThisStoreInvoice,
     NotThisInvoiceLine(String),
     CannotEditFinalised,
+    DateInClosedPeriod,
     ItemNotFound,
     StockLineNotFound,
     NumberOfPacksBelowZero,
@@ -50,6 +57,7 @@ pub enum UpdateStockOutLineError {
         stock_line_id: String,
         line_id: String,
     },
+    InvalidQuantityInUnit(ConvertQuantityError),
 }
 
 type OutError = UpdateStockOutLineError;
@@ -61,6 +69,7 @@ pub fn update_stock_out_line(
     let updated_line = ctx
         .connection
         .transaction_sync(|connection| {
+            let input = resolve_quantity_in_unit(connection, input)?;
             let (line, item, batch_pair, invoice) = validate(ctx, &input, &ctx.store_id)?;
 
             let (update_line, batch_pair) = generate(input, line, item, batch_pair, invoice)?;
@@ -80,6 +89,44 @@ pub fn update_stock_out_line(
     Ok(updated_line)
 }
 
+fn resolve_quantity_in_unit(
+    connection: &StorageConnection,
+    mut input: UpdateStockOutLine,
+) -> Result<UpdateStockOutLine, OutError> {
+    let Some(quantity_in_unit) = input.quantity_in_unit.take() else {
+        return Ok(input);
+    };
+    // Missing line or stock line is reported by validation
+    let Some(line) = InvoiceLineRepository::new(connection)
+        .query_by_filter(InvoiceLineFilter::new().id(EqualFilter::equal_to(&input.id)))?
+        .pop()
+    else {
+        return Ok(input);
+    };
+    let pack_size = match &input.stock_line_id {
+        Some(stock_line_id) => {
+            match StockLineRowRepository::new(connection).find_one_by_id(stock_line_id)? {
+                Some(stock_line) => stock_line.pack_size,
+                None => return Ok(input),
+            }
+        }
+        None => line.invoice_line_row.pack_size,
+    };
+
+    input.number_of_packs = Some(
+        quantity_to_number_of_packs(
+            connection,
+            &line.item_row.id,
+            &quantity_in_unit,
+            pack_size,
+            PackRounding::Fractional,
+        )
+        .map_err(OutError::InvalidQuantityInUnit)?,
+    );
+
+    Ok(input)
+}
+
 /// During outbound shipment line / prescription line update, stock line may change thus
 /// validation and updates need to apply to both batches
 pub struct BatchPair {
@@ -147,6 +194,7 @@ mod test {
             InsertStockOutLine,
         },
         item_stats::ItemStatsFilter,
+        period_close::close_period_for_date,
         service_provider::ServiceProvider,
     };
 
@@ -713,5 +761,19 @@ mod test {
                 line_id: "prescription_stock_out_line1".to_string()
             })
         );
+
+        // Check that we can't update the stock line once the backdated period is closed
+        close_period_for_date(&context.connection, &context.store_id, datetime.date());
+        assert_eq!(
+            invoice_line_service.update_stock_out_line(
+                &context,
+                inline_init(|r: &mut UpdateStockOutLine| {
+                    r.id = "prescription_stock_out_line1".to_string();
+                    r.r#type = Some(StockOutType::Prescription);
+                    r.number_of_packs = Some(5.0);
+                })
+            ),
+            Err(ServiceError::DateInClosedPeriod)
+        );
     }
 }
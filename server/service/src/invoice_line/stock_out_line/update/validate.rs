@@ -1,3 +1,4 @@
+use chrono::Utc;
 use repository::{InvoiceLineRow, InvoiceRow, InvoiceStatus, ItemRow, StorageConnection};
 
 use crate::{
@@ -9,6 +10,7 @@ use crate::{
         validate::{check_line_belongs_to_invoice, check_line_exists, check_number_of_packs},
         LocationIsOnHoldError,
     },
+    period_close::validate_not_in_closed_period,
     service_provider::ServiceContext,
     stock_line::historical_stock::get_historical_stock_line_available_quantity,
 };
@@ -50,6 +52,11 @@ pub fn validate(
     if !check_invoice_is_editable(&invoice) {
         return Err(CannotEditFinalised);
     }
+    // Stock movement is dated at the backdated datetime of the invoice
+    let datetime = invoice_backdated_date(&invoice).unwrap_or_else(|| Utc::now().naive_utc());
+    if !validate_not_in_closed_period(connection, store_id, datetime)? {
+        return Err(DateInClosedPeriod);
+    }
     if !check_line_belongs_to_invoice(line_row, &invoice) {
         return Err(NotThisInvoiceLine(line.invoice_line_row.invoice_id));
     }
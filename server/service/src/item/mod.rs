@@ -2,6 +2,7 @@ pub mod bundled_item;
 pub mod item;
 pub mod item_variant;
 pub mod packaging_variant;
+pub mod unit_of_measure;
 use bundled_item::{
     delete_bundled_item, get_bundled_items, upsert_bundled_item, DeleteBundledItem,
     DeleteBundledItemError, UpsertBundledItem, UpsertBundledItemError,
@@ -24,7 +25,12 @@ use repository::{
         packaging_variant::{PackagingVariantFilter, PackagingVariantSort},
         packaging_variant_row::PackagingVariantRow,
     },
-    PaginationOption,
+    ItemUnitOfMeasureRow, PaginationOption, RepositoryError,
+};
+use unit_of_measure::{
+    delete_item_unit_of_measure, get_item_units_of_measure, upsert_item_unit_of_measure,
+    DeleteItemUnitOfMeasure, DeleteItemUnitOfMeasureError, UpsertItemUnitOfMeasure,
+    UpsertItemUnitOfMeasureError,
 };
 
 use crate::{service_provider::ServiceContext, ListError, ListResult};
@@ -106,6 +112,30 @@ pub trait ItemServiceTrait: Sync + Send {
     ) -> Result<String, DeleteBundledItemError> {
         delete_bundled_item(ctx, input)
     }
+
+    fn get_item_units_of_measure(
+        &self,
+        ctx: &ServiceContext,
+        item_id: &str,
+    ) -> Result<Vec<ItemUnitOfMeasureRow>, RepositoryError> {
+        get_item_units_of_measure(&ctx.connection, item_id)
+    }
+
+    fn upsert_item_unit_of_measure(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertItemUnitOfMeasure,
+    ) -> Result<ItemUnitOfMeasureRow, UpsertItemUnitOfMeasureError> {
+        upsert_item_unit_of_measure(ctx, input)
+    }
+
+    fn delete_item_unit_of_measure(
+        &self,
+        ctx: &ServiceContext,
+        input: DeleteItemUnitOfMeasure,
+    ) -> Result<String, DeleteItemUnitOfMeasureError> {
+        delete_item_unit_of_measure(ctx, input)
+    }
 }
 
 pub struct ItemService {}
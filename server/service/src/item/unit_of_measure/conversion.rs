@@ -0,0 +1,288 @@
+use repository::{ItemUnitOfMeasureRowRepository, RepositoryError, StorageConnection};
+
+/// Quantities are converted to base units and number of packs rounded to this many decimal
+/// places, so that floating point noise (e.g. 0.1 * 3 = 0.30000000000000004) doesn't end up in
+/// stored quantities or push a whole number of packs up to the next pack
+pub const QUANTITY_DECIMAL_PLACES: i32 = 6;
+
+/// A quantity entered in one of the item's units of measure
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct QuantityInUnit {
+    pub quantity: f64,
+    /// None for the item's base unit (item.unit), which always has a conversion factor of 1
+    pub unit_of_measure_id: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PackRounding {
+    /// Round up to the next whole pack, used when stock is allocated to make sure at least the
+    /// requested quantity is issued without splitting packs
+    UpToWholePacks,
+    /// Keep fractions of packs, used for quantities entered by the user (stock in and out lines,
+    /// stocktake counts and requested quantities)
+    Fractional,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConvertQuantityError {
+    UnitOfMeasureDoesNotExist,
+    UnitOfMeasureDoesNotBelongToItem,
+    QuantityBelowZero,
+    PackSizeNotPositive,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ConvertQuantityError {
+    fn from(error: RepositoryError) -> Self {
+        ConvertQuantityError::DatabaseError(error)
+    }
+}
+
+pub fn round_quantity(quantity: f64) -> f64 {
+    let factor = 10_f64.powi(QUANTITY_DECIMAL_PLACES);
+    (quantity * factor).round() / factor
+}
+
+pub fn round_number_of_packs(number_of_packs: f64, rounding: PackRounding) -> f64 {
+    let number_of_packs = round_quantity(number_of_packs);
+    match rounding {
+        PackRounding::UpToWholePacks => number_of_packs.ceil(),
+        PackRounding::Fractional => number_of_packs,
+    }
+}
+
+/// Converts the quantity to the item's base unit
+pub fn quantity_to_base_units(
+    connection: &StorageConnection,
+    item_id: &str,
+    QuantityInUnit {
+        quantity,
+        unit_of_measure_id,
+    }: &QuantityInUnit,
+) -> Result<f64, ConvertQuantityError> {
+    if *quantity < 0.0 {
+        return Err(ConvertQuantityError::QuantityBelowZero);
+    }
+
+    let Some(unit_of_measure_id) = unit_of_measure_id else {
+        return Ok(round_quantity(*quantity));
+    };
+
+    let repo = ItemUnitOfMeasureRowRepository::new(connection);
+    let unit_of_measure = repo
+        .find_many_by_item_id(item_id)?
+        .into_iter()
+        .find(|unit| &unit.id == unit_of_measure_id);
+
+    match unit_of_measure {
+        Some(unit) => Ok(round_quantity(quantity * unit.conversion_factor)),
+        None => match repo.find_one_by_id(unit_of_measure_id)? {
+            Some(unit) if unit.deleted_datetime.is_none() => {
+                Err(ConvertQuantityError::UnitOfMeasureDoesNotBelongToItem)
+            }
+            _ => Err(ConvertQuantityError::UnitOfMeasureDoesNotExist),
+        },
+    }
+}
+
+/// Converts the quantity to number of packs of a stock line (or line) with the given pack size
+pub fn quantity_to_number_of_packs(
+    connection: &StorageConnection,
+    item_id: &str,
+    quantity: &QuantityInUnit,
+    pack_size: f64,
+    rounding: PackRounding,
+) -> Result<f64, ConvertQuantityError> {
+    if pack_size <= 0.0 {
+        return Err(ConvertQuantityError::PackSizeNotPositive);
+    }
+
+    let base_units = quantity_to_base_units(connection, item_id, quantity)?;
+    Ok(round_number_of_packs(base_units / pack_size, rounding))
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_item_a, mock_item_b, MockDataInserts},
+        test_db::setup_all,
+        UnitOfMeasureType,
+    };
+
+    use crate::{
+        item::unit_of_measure::{
+            quantity_to_base_units, quantity_to_number_of_packs, round_number_of_packs,
+            ConvertQuantityError, DeleteItemUnitOfMeasure, PackRounding, QuantityInUnit,
+            UpsertItemUnitOfMeasure, UpsertItemUnitOfMeasureError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn item_unit_of_measure_conversion() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "item_unit_of_measure_conversion",
+            MockDataInserts::none().items(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let service = service_provider.item_service;
+
+        // Strip of 10 tablets and box of 100 tablets
+        service
+            .upsert_item_unit_of_measure(
+                &context,
+                UpsertItemUnitOfMeasure {
+                    id: "strip".to_string(),
+                    item_id: mock_item_a().id,
+                    name: "Strip".to_string(),
+                    r#type: UnitOfMeasureType::Dispensing,
+                    conversion_factor: 10.0,
+                },
+            )
+            .unwrap();
+        service
+            .upsert_item_unit_of_measure(
+                &context,
+                UpsertItemUnitOfMeasure {
+                    id: "box".to_string(),
+                    item_id: mock_item_a().id,
+                    name: "Box".to_string(),
+                    r#type: UnitOfMeasureType::OuterPack,
+                    conversion_factor: 100.0,
+                },
+            )
+            .unwrap();
+
+        // Only one dispensing unit per item
+        assert_eq!(
+            service.upsert_item_unit_of_measure(
+                &context,
+                UpsertItemUnitOfMeasure {
+                    id: "blister".to_string(),
+                    item_id: mock_item_a().id,
+                    name: "Blister".to_string(),
+                    r#type: UnitOfMeasureType::Dispensing,
+                    conversion_factor: 5.0,
+                },
+            ),
+            Err(UpsertItemUnitOfMeasureError::DuplicateDispensingUnit)
+        );
+        assert_eq!(
+            service.upsert_item_unit_of_measure(
+                &context,
+                UpsertItemUnitOfMeasure {
+                    id: "box".to_string(),
+                    item_id: mock_item_a().id,
+                    name: "Box".to_string(),
+                    r#type: UnitOfMeasureType::OuterPack,
+                    conversion_factor: 0.0,
+                },
+            ),
+            Err(UpsertItemUnitOfMeasureError::ConversionFactorNotPositive)
+        );
+
+        // Base unit
+        let in_base_units = |quantity| QuantityInUnit {
+            quantity,
+            unit_of_measure_id: None,
+        };
+        assert_eq!(
+            quantity_to_base_units(&connection, &mock_item_a().id, &in_base_units(7.0)),
+            Ok(7.0)
+        );
+
+        // 3 strips of 10 into packs of 20 tablets
+        let strips = QuantityInUnit {
+            quantity: 3.0,
+            unit_of_measure_id: Some("strip".to_string()),
+        };
+        assert_eq!(
+            quantity_to_base_units(&connection, &mock_item_a().id, &strips),
+            Ok(30.0)
+        );
+        assert_eq!(
+            quantity_to_number_of_packs(
+                &connection,
+                &mock_item_a().id,
+                &strips,
+                20.0,
+                PackRounding::Fractional
+            ),
+            Ok(1.5)
+        );
+        assert_eq!(
+            quantity_to_number_of_packs(
+                &connection,
+                &mock_item_a().id,
+                &strips,
+                20.0,
+                PackRounding::UpToWholePacks
+            ),
+            Ok(2.0)
+        );
+
+        // 0.3 boxes is exactly 3 packs of 10, floating point noise doesn't add a pack
+        let boxes = QuantityInUnit {
+            quantity: 0.3,
+            unit_of_measure_id: Some("box".to_string()),
+        };
+        assert_eq!(
+            quantity_to_number_of_packs(
+                &connection,
+                &mock_item_a().id,
+                &boxes,
+                10.0,
+                PackRounding::UpToWholePacks
+            ),
+            Ok(3.0)
+        );
+        assert_eq!(
+            round_number_of_packs(0.1 * 3.0 / 0.1, PackRounding::UpToWholePacks),
+            3.0
+        );
+
+        // Errors
+        assert_eq!(
+            quantity_to_base_units(&connection, &mock_item_b().id, &strips),
+            Err(ConvertQuantityError::UnitOfMeasureDoesNotBelongToItem)
+        );
+        assert_eq!(
+            quantity_to_base_units(&connection, &mock_item_a().id, &in_base_units(-1.0)),
+            Err(ConvertQuantityError::QuantityBelowZero)
+        );
+        assert_eq!(
+            quantity_to_number_of_packs(
+                &connection,
+                &mock_item_a().id,
+                &strips,
+                0.0,
+                PackRounding::Fractional
+            ),
+            Err(ConvertQuantityError::PackSizeNotPositive)
+        );
+
+        // Deleted units can't be used
+        service
+            .delete_item_unit_of_measure(
+                &context,
+                DeleteItemUnitOfMeasure {
+                    id: "strip".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            quantity_to_base_units(&connection, &mock_item_a().id, &strips),
+            Err(ConvertQuantityError::UnitOfMeasureDoesNotExist)
+        );
+        assert_eq!(
+            service
+                .get_item_units_of_measure(&context, &mock_item_a().id)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
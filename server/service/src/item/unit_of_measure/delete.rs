@@ -0,0 +1,32 @@
+use repository::{ItemUnitOfMeasureRowRepository, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(PartialEq, Debug)]
+pub enum DeleteItemUnitOfMeasureError {
+    DatabaseError(RepositoryError),
+}
+
+pub struct DeleteItemUnitOfMeasure {
+    pub id: String,
+}
+
+pub fn delete_item_unit_of_measure(
+    ctx: &ServiceContext,
+    input: DeleteItemUnitOfMeasure,
+) -> Result<String, DeleteItemUnitOfMeasureError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            // Soft delete, quantities already converted from this unit are stored in base units
+            // so nothing references the unit after the transaction that used it
+            ItemUnitOfMeasureRowRepository::new(connection).mark_deleted(&input.id)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(input.id)
+}
+
+impl From<RepositoryError> for DeleteItemUnitOfMeasureError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteItemUnitOfMeasureError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,12 @@
+mod conversion;
+mod delete;
+mod query;
+mod upsert;
+pub use conversion::*;
+pub use delete::{
+    delete_item_unit_of_measure, DeleteItemUnitOfMeasure, DeleteItemUnitOfMeasureError,
+};
+pub use query::get_item_units_of_measure;
+pub use upsert::{
+    upsert_item_unit_of_measure, UpsertItemUnitOfMeasure, UpsertItemUnitOfMeasureError,
+};
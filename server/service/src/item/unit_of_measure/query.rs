@@ -0,0 +1,10 @@
+use repository::{
+    ItemUnitOfMeasureRow, ItemUnitOfMeasureRowRepository, RepositoryError, StorageConnection,
+};
+
+pub fn get_item_units_of_measure(
+    connection: &StorageConnection,
+    item_id: &str,
+) -> Result<Vec<ItemUnitOfMeasureRow>, RepositoryError> {
+    ItemUnitOfMeasureRowRepository::new(connection).find_many_by_item_id(item_id)
+}
@@ -0,0 +1,112 @@
+use repository::{
+    ItemRowRepository, ItemUnitOfMeasureRow, ItemUnitOfMeasureRowRepository, RepositoryError,
+    StorageConnection, UnitOfMeasureType,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(PartialEq, Debug)]
+pub enum UpsertItemUnitOfMeasureError {
+    CreatedRecordNotFound,
+    ItemDoesNotExist,
+    CantChangeItem,
+    NameCannotBeEmpty,
+    ConversionFactorNotPositive,
+    /// Only one dispensing unit can be defined per item
+    DuplicateDispensingUnit,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Default, Clone)]
+pub struct UpsertItemUnitOfMeasure {
+    pub id: String,
+    pub item_id: String,
+    pub name: String,
+    pub r#type: UnitOfMeasureType,
+    /// Number of base units (the item's unit) in one of this unit
+    pub conversion_factor: f64,
+}
+
+pub fn upsert_item_unit_of_measure(
+    ctx: &ServiceContext,
+    input: UpsertItemUnitOfMeasure,
+) -> Result<ItemUnitOfMeasureRow, UpsertItemUnitOfMeasureError> {
+    let unit_of_measure = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, &input)?;
+            let new_unit_of_measure = generate(input);
+            let repo = ItemUnitOfMeasureRowRepository::new(connection);
+            repo.upsert_one(&new_unit_of_measure)?;
+
+            repo.find_one_by_id(&new_unit_of_measure.id)?
+                .ok_or(UpsertItemUnitOfMeasureError::CreatedRecordNotFound)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(unit_of_measure)
+}
+
+impl From<RepositoryError> for UpsertItemUnitOfMeasureError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertItemUnitOfMeasureError::DatabaseError(error)
+    }
+}
+
+fn generate(
+    UpsertItemUnitOfMeasure {
+        id,
+        item_id,
+        name,
+        r#type,
+        conversion_factor,
+    }: UpsertItemUnitOfMeasure,
+) -> ItemUnitOfMeasureRow {
+    ItemUnitOfMeasureRow {
+        id,
+        item_link_id: item_id,
+        name: name.trim().to_string(),
+        r#type,
+        conversion_factor,
+        deleted_datetime: None,
+    }
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &UpsertItemUnitOfMeasure,
+) -> Result<(), UpsertItemUnitOfMeasureError> {
+    if ItemRowRepository::new(connection)
+        .find_active_by_id(&input.item_id)?
+        .is_none()
+    {
+        return Err(UpsertItemUnitOfMeasureError::ItemDoesNotExist);
+    }
+
+    let existing_units =
+        ItemUnitOfMeasureRowRepository::new(connection).find_many_by_item_id(&input.item_id)?;
+
+    let old_unit = ItemUnitOfMeasureRowRepository::new(connection).find_one_by_id(&input.id)?;
+    if let Some(old_unit) = old_unit {
+        if !existing_units.iter().any(|unit| unit.id == old_unit.id) {
+            return Err(UpsertItemUnitOfMeasureError::CantChangeItem);
+        }
+    }
+
+    if input.name.trim().is_empty() {
+        return Err(UpsertItemUnitOfMeasureError::NameCannotBeEmpty);
+    }
+
+    if input.conversion_factor <= 0.0 || !input.conversion_factor.is_finite() {
+        return Err(UpsertItemUnitOfMeasureError::ConversionFactorNotPositive);
+    }
+
+    if input.r#type == UnitOfMeasureType::Dispensing
+        && existing_units
+            .iter()
+            .any(|unit| unit.r#type == UnitOfMeasureType::Dispensing && unit.id != input.id)
+    {
+        return Err(UpsertItemUnitOfMeasureError::DuplicateDispensingUnit);
+    }
+
+    Ok(())
+}
@@ -1,14 +1,14 @@
 use repository::{
-    ledger::{LedgerFilter, LedgerRepository, LedgerRow, LedgerSort},
-    Pagination, StorageConnectionManager,
+    ledger::{LedgerFilter, LedgerRepository, LedgerRow, LedgerSort, LedgerSortField},
+    DatetimeFilter, Pagination, PaginationOption, StorageConnectionManager,
 };
 
-use crate::usize_to_u32;
+use crate::{get_default_pagination, i64_to_u32, usize_to_u32};
 
 use super::{ListError, ListResult};
 
-// pub const MAX_LIMIT: u32 = 5000;
-// pub const MIN_LIMIT: u32 = 1;
+pub const MAX_LIMIT: u32 = 5000;
+pub const MIN_LIMIT: u32 = 1;
 
 pub fn get_ledger(
     connection_manager: &StorageConnectionManager,
@@ -24,3 +24,256 @@ pub fn get_ledger(
         rows,
     })
 }
+
+#[derive(Debug, PartialEq)]
+pub struct StockCardLine {
+    pub ledger: LedgerRow,
+    /// Stock balance after the movement
+    pub running_balance: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StockCard {
+    /// Stock balance at the start of the period
+    pub opening_balance: f64,
+    /// Stock balance at the end of the period
+    pub closing_balance: f64,
+    /// Total number of movements matching the filter
+    pub count: u32,
+    pub lines: Vec<StockCardLine>,
+}
+
+/// Stock movements in chronological order with the running stock balance after each movement,
+/// e.g. for an item in a store.
+///
+/// The period is set by `filter.datetime`. Balances always include all transaction types,
+/// `filter.invoice_type` only limits which movements are listed.
+pub fn get_stock_card(
+    connection_manager: &StorageConnectionManager,
+    pagination: Option<PaginationOption>,
+    filter: LedgerFilter,
+) -> Result<StockCard, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let connection = connection_manager.connection()?;
+    let repository = LedgerRepository::new(&connection);
+
+    let (period_start, period_end) = match &filter.datetime {
+        Some(datetime) => (
+            datetime.after_or_equal_to.or(datetime.equal_to),
+            datetime.before_or_equal_to.or(datetime.equal_to),
+        ),
+        None => (None, None),
+    };
+    // All movements from the beginning up to the end of the period contribute to the balance
+    let balance_filter = LedgerFilter {
+        datetime: period_end.map(DatetimeFilter::before_or_equal_to),
+        invoice_type: None,
+        ..filter.clone()
+    };
+
+    let opening_balance = match period_start {
+        Some(start) => repository.quantity_sum_before(Some(balance_filter.clone()), start, "")?,
+        None => 0.0,
+    };
+    let closing_balance = repository.quantity_sum(Some(balance_filter.clone()))?;
+
+    let rows = repository.query(
+        pagination,
+        Some(filter.clone()),
+        Some(LedgerSort {
+            key: LedgerSortField::Datetime,
+            desc: Some(false),
+        }),
+    )?;
+    let count = i64_to_u32(repository.count(Some(filter))?);
+
+    let (Some(first), Some(last)) = (rows.first(), rows.last()) else {
+        return Ok(StockCard {
+            opening_balance,
+            closing_balance,
+            count,
+            lines: Vec::new(),
+        });
+    };
+
+    let mut balance =
+        repository.quantity_sum_before(Some(balance_filter.clone()), first.datetime, &first.id)?;
+    // Movements on the page, plus those in between that are filtered out (e.g. other transaction
+    // types) but still change the balance. Both lists are in the same order
+    let movements = repository.query_range(
+        Some(balance_filter),
+        (first.datetime, &first.id),
+        (last.datetime, &last.id),
+    )?;
+    let mut movements = movements.into_iter();
+
+    let lines = rows
+        .into_iter()
+        .map(|row| {
+            for movement in movements.by_ref() {
+                balance += movement.quantity;
+                if movement.id == row.id {
+                    break;
+                }
+            }
+            StockCardLine {
+                ledger: row,
+                running_balance: balance,
+            }
+        })
+        .collect();
+
+    Ok(StockCard {
+        opening_balance,
+        closing_balance,
+        count,
+        lines,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use repository::{
+        ledger::LedgerFilter,
+        mock::{mock_item_a, mock_name_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        DatetimeFilter, EqualFilter, InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceType,
+        NameRow, PaginationOption, StoreRow,
+    };
+    use util::inline_init;
+
+    use super::get_stock_card;
+
+    fn date(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2021, 1, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn name() -> NameRow {
+        inline_init(|r: &mut NameRow| {
+            r.id = "stock_card_name".to_string();
+        })
+    }
+
+    fn store() -> StoreRow {
+        inline_init(|s: &mut StoreRow| {
+            s.id = "stock_card_store".to_string();
+            s.name_link_id = name().id;
+            s.code = "n/a".to_string();
+        })
+    }
+
+    fn movement(id: &str, r#type: InvoiceType, day: u32, quantity: f64) -> MockData {
+        let datetime = Some(date(day));
+        inline_init(|r: &mut MockData| {
+            r.invoices = vec![inline_init(|r: &mut InvoiceRow| {
+                r.id = id.to_string();
+                r.store_id = store().id;
+                r.name_link_id = mock_name_a().id;
+                match r#type {
+                    InvoiceType::InboundShipment => r.delivered_datetime = datetime,
+                    InvoiceType::OutboundShipment => r.picked_datetime = datetime,
+                    _ => r.verified_datetime = datetime,
+                }
+                r.r#type = r#type;
+            })];
+            r.invoice_lines = vec![inline_init(|r: &mut InvoiceLineRow| {
+                r.id = format!("{}_line", id);
+                r.invoice_id = id.to_string();
+                r.item_link_id = mock_item_a().id;
+                r.r#type = if quantity > 0.0 {
+                    InvoiceLineType::StockIn
+                } else {
+                    InvoiceLineType::StockOut
+                };
+                r.number_of_packs = quantity.abs();
+                r.pack_size = 1.0;
+            })];
+        })
+    }
+
+    #[actix_rt::test]
+    async fn stock_card_balances() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "stock_card_balances",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.names = vec![name()];
+                r.stores = vec![store()];
+            })
+            .join(movement("m1", InvoiceType::InboundShipment, 1, 100.0))
+            .join(movement("m2", InvoiceType::OutboundShipment, 5, -30.0))
+            .join(movement("m3", InvoiceType::InventoryReduction, 10, -5.0))
+            .join(movement("m4", InvoiceType::OutboundShipment, 15, -20.0))
+            .join(movement("m5", InvoiceType::InboundShipment, 20, 50.0)),
+        )
+        .await;
+
+        let filter = LedgerFilter::new()
+            .item_id(EqualFilter::equal_to(&mock_item_a().id))
+            .store_id(EqualFilter::equal_to(&store().id))
+            .datetime(DatetimeFilter::date_range(date(3), date(31)));
+        let balances = |pagination: Option<PaginationOption>, filter: LedgerFilter| {
+            let stock_card = get_stock_card(&connection_manager, pagination, filter).unwrap();
+            (
+                stock_card.opening_balance,
+                stock_card.closing_balance,
+                stock_card.count,
+                stock_card
+                    .lines
+                    .into_iter()
+                    .map(|line| (line.ledger.id, line.running_balance))
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(
+            balances(None, filter.clone()),
+            (
+                100.0,
+                95.0,
+                4,
+                vec![
+                    ("m2_line".to_string(), 70.0),
+                    ("m3_line".to_string(), 65.0),
+                    ("m4_line".to_string(), 45.0),
+                    ("m5_line".to_string(), 95.0)
+                ]
+            )
+        );
+
+        // Page in the middle of the period
+        assert_eq!(
+            balances(
+                Some(PaginationOption {
+                    offset: Some(1),
+                    limit: Some(2)
+                }),
+                filter.clone()
+            ),
+            (
+                100.0,
+                95.0,
+                4,
+                vec![("m3_line".to_string(), 65.0), ("m4_line".to_string(), 45.0)]
+            )
+        );
+
+        // Balances include the movements of other transaction types
+        assert_eq!(
+            balances(
+                None,
+                filter.invoice_type(InvoiceType::OutboundShipment.equal_to())
+            ),
+            (
+                100.0,
+                95.0,
+                2,
+                vec![("m2_line".to_string(), 70.0), ("m4_line".to_string(), 45.0)]
+            )
+        );
+    }
+}
@@ -26,7 +26,9 @@ pub mod dashboard;
 pub mod demographic;
 pub mod display_settings_service;
 pub mod document;
+pub mod forecasting;
 pub mod inventory_adjustment_reason;
+pub mod inventory_valuation;
 pub mod invoice;
 pub mod invoice_line;
 pub mod item;
@@ -41,6 +43,7 @@ pub mod master_list;
 pub mod name;
 pub mod name_property;
 pub mod number;
+pub mod period_close;
 pub mod permission;
 pub mod plugin;
 pub mod plugin_data;
@@ -60,6 +63,7 @@ pub mod sensor;
 pub mod service_provider;
 pub mod settings;
 pub mod settings_service;
+pub mod shelf_life_rule;
 pub mod standard_reports;
 pub mod static_files;
 pub mod stock_line;
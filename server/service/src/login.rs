@@ -400,6 +400,10 @@ fn permissions_to_domain(permissions: Vec<Permissions>) -> HashSet<PermissionTyp
             Permissions::DeleteStocktakeLines => {
                 output.insert(PermissionType::StocktakeMutate);
             }
+            Permissions::FinaliseInventoryAdjustments => {
+                output.insert(PermissionType::StocktakeVarianceApprove);
+                output.insert(PermissionType::StocktakeSupervise);
+            }
             // inventory adjustments
             Permissions::EnterInventoryAdjustments => {
                 output.insert(PermissionType::InventoryAdjustmentMutate);
@@ -473,6 +477,9 @@ fn permissions_to_domain(permissions: Vec<Permissions>) -> HashSet<PermissionTyp
             Permissions::EditItemNamesCodesAndUnits => {
                 output.insert(PermissionType::ItemNamesCodesAndUnitsMutate);
             }
+            Permissions::EditItemDefaultPrice => {
+                output.insert(PermissionType::PriceListMutate);
+            }
             // cold chain
             Permissions::ColdChainApi => {
                 output.insert(PermissionType::ColdChainApi);
@@ -45,7 +45,7 @@ pub fn next_number(
                 .find_max_invoice_number(InvoiceType::CustomerReturn, store_id)?,
             NumberRowType::SupplierReturn => InvoiceRowRepository::new(connection_tx)
                 .find_max_invoice_number(InvoiceType::SupplierReturn, store_id)?,
-            NumberRowType::Program(_) => {
+            NumberRowType::Program(_) | NumberRowType::Patient(_) => {
                 let next_number =
                     repo.get_next_number_for_type_and_store(r#type, store_id, None)?;
                 return Ok(next_number.number);
@@ -0,0 +1,218 @@
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use repository::{PeriodCloseRow, PeriodCloseRowRepository, RepositoryError, StorageConnection};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq)]
+pub struct ClosePeriod {
+    pub id: String,
+    pub year: i32,
+    /// 1 to 12
+    pub month: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ClosePeriodError {
+    InvalidMonth,
+    PeriodNotEnded,
+    PeriodAlreadyClosed,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ClosePeriodError {
+    fn from(error: RepositoryError) -> Self {
+        ClosePeriodError::DatabaseError(error)
+    }
+}
+
+pub trait PeriodCloseServiceTrait: Sync + Send {
+    fn get_period_closes(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Vec<PeriodCloseRow>, RepositoryError> {
+        PeriodCloseRowRepository::new(&ctx.connection).find_many_by_store_id(store_id)
+    }
+
+    fn close_period(
+        &self,
+        ctx: &ServiceContext,
+        input: ClosePeriod,
+    ) -> Result<PeriodCloseRow, ClosePeriodError> {
+        close_period(ctx, input)
+    }
+}
+
+pub struct PeriodCloseService {}
+impl PeriodCloseServiceTrait for PeriodCloseService {}
+
+/// Closed period of the store containing the datetime, transactions can't be dated within it
+pub fn find_closed_period(
+    connection: &StorageConnection,
+    store_id: &str,
+    datetime: NaiveDateTime,
+) -> Result<Option<PeriodCloseRow>, RepositoryError> {
+    PeriodCloseRowRepository::new(connection).find_one_by_store_and_date(store_id, datetime.date())
+}
+
+/// False when the datetime is within a closed period of the store, used by validation of
+/// anything that moves stock
+pub fn validate_not_in_closed_period(
+    connection: &StorageConnection,
+    store_id: &str,
+    datetime: NaiveDateTime,
+) -> Result<bool, RepositoryError> {
+    Ok(find_closed_period(connection, store_id, datetime)?.is_none())
+}
+
+/// Closes a month for the store of the context, the month must have ended
+pub fn close_period(
+    ctx: &ServiceContext,
+    input: ClosePeriod,
+) -> Result<PeriodCloseRow, ClosePeriodError> {
+    let period_close = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let period_start = NaiveDate::from_ymd_opt(input.year, input.month, 1)
+                .ok_or(ClosePeriodError::InvalidMonth)?;
+            let next_period_start = match input.month {
+                12 => NaiveDate::from_ymd_opt(input.year + 1, 1, 1),
+                month => NaiveDate::from_ymd_opt(input.year, month + 1, 1),
+            }
+            .ok_or(ClosePeriodError::InvalidMonth)?;
+            let period_end = next_period_start.pred_opt().unwrap_or(period_start);
+
+            if period_end >= Utc::now().naive_utc().date() {
+                return Err(ClosePeriodError::PeriodNotEnded);
+            }
+
+            let repo = PeriodCloseRowRepository::new(connection);
+            if repo
+                .find_one_by_store_and_date(&ctx.store_id, period_start)?
+                .is_some()
+            {
+                return Err(ClosePeriodError::PeriodAlreadyClosed);
+            }
+
+            let period_close = PeriodCloseRow {
+                id: input.id,
+                store_id: ctx.store_id.clone(),
+                period_start,
+                period_end,
+                closed_datetime: Utc::now().naive_utc(),
+                user_id: ctx.user_id.clone(),
+            };
+            repo.upsert_one(&period_close)?;
+            Ok(period_close)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(period_close)
+}
+
+/// Closes a period covering the date for the store, for testing that transactions are rejected
+#[cfg(test)]
+pub(crate) fn close_period_for_date(
+    connection: &StorageConnection,
+    store_id: &str,
+    date: NaiveDate,
+) {
+    PeriodCloseRowRepository::new(connection)
+        .upsert_one(&PeriodCloseRow {
+            id: format!("closed_period_{}_{}", store_id, date),
+            store_id: store_id.to_string(),
+            period_start: date,
+            period_end: date,
+            closed_datetime: Utc::now().naive_utc(),
+            user_id: "user".to_string(),
+        })
+        .unwrap();
+}
+
+#[cfg(test)]
+pub(crate) fn close_period_for_today(connection: &StorageConnection, store_id: &str) {
+    close_period_for_date(connection, store_id, Utc::now().naive_utc().date())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Datelike, Duration, NaiveDate, Utc};
+    use repository::{
+        mock::{mock_prescription_a, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+    };
+
+    use crate::{
+        invoice::prescription::{UpdatePrescription, UpdatePrescriptionError},
+        period_close::{ClosePeriod, ClosePeriodError},
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn close_period() {
+        let (_, _, connection_manager, _) = setup_all("close_period", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "");
+        let context = service_provider
+            .context(mock_store_a().id, "user".to_string())
+            .unwrap();
+        let service = &service_provider.period_close_service;
+
+        let today = Utc::now().naive_utc().date();
+        assert_eq!(
+            service.close_period(
+                &context,
+                ClosePeriod {
+                    id: "period_close".to_string(),
+                    year: today.year(),
+                    month: 13,
+                }
+            ),
+            Err(ClosePeriodError::InvalidMonth)
+        );
+        assert_eq!(
+            service.close_period(
+                &context,
+                ClosePeriod {
+                    id: "period_close".to_string(),
+                    year: today.year(),
+                    month: today.month(),
+                }
+            ),
+            Err(ClosePeriodError::PeriodNotEnded)
+        );
+
+        // Previous month
+        let last_month = today.with_day(1).unwrap() - Duration::days(1);
+        let input = || ClosePeriod {
+            id: "period_close".to_string(),
+            year: last_month.year(),
+            month: last_month.month(),
+        };
+        let period_close = service.close_period(&context, input()).unwrap();
+        assert_eq!(
+            period_close.period_start,
+            NaiveDate::from_ymd_opt(last_month.year(), last_month.month(), 1).unwrap()
+        );
+        assert_eq!(period_close.period_end, last_month);
+        assert_eq!(
+            service.close_period(&context, input()),
+            Err(ClosePeriodError::PeriodAlreadyClosed)
+        );
+
+        // Can't backdate transactions into the closed period
+        let prescription = mock_prescription_a();
+        assert!(matches!(
+            service_provider.invoice_service.update_prescription(
+                &context,
+                UpdatePrescription {
+                    id: prescription.id.clone(),
+                    backdated_datetime: Some(last_month.and_hms_opt(12, 0, 0).unwrap()),
+                    ..Default::default()
+                }
+            ),
+            Err(UpdatePrescriptionError::CantBackDate(message))
+                if message.contains("closed period")
+        ));
+    }
+}
@@ -35,6 +35,7 @@ mod tests {
             default_price_per_unit: None,
             discount_percentage: None,
             calculated_price_per_unit: None,
+            ..Default::default()
         };
 
         let result =
@@ -48,6 +49,7 @@ mod tests {
             default_price_per_unit: Some(10.0),
             discount_percentage: None,
             calculated_price_per_unit: Some(10.0),
+            ..Default::default()
         };
 
         let result =
@@ -62,6 +64,7 @@ mod tests {
             default_price_per_unit: Some(10.0),
             discount_percentage: Some(10.0),
             calculated_price_per_unit: Some(9.0),
+            ..Default::default()
         };
 
         let result =
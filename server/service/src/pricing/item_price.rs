@@ -1,6 +1,12 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Local, NaiveDate};
 use repository::{
     EqualFilter, MasterListFilter, MasterListLineFilter, MasterListLineRepository,
-    MasterListRepository, MasterListSort, MasterListSortField, Pagination, PatientFilter,
+    MasterListRepository, MasterListSort, MasterListSortField, NameLinkRowRepository,
+    NameTagFilter, NameTagRepository, Pagination, PatientFilter, PriceListLineRow,
+    PriceListLineRowRepository, PriceListNameJoinRowRepository, PriceListRow,
+    PriceListRowRepository,
 };
 use repository::{PatientRepository, RepositoryError};
 
@@ -9,31 +15,75 @@ use crate::service_provider::ServiceContext;
 pub struct ItemPriceLookup {
     pub item_id: String,
     pub customer_name_id: Option<String>,
+    /// Date the price should be valid on, defaults to today
+    pub date: Option<NaiveDate>,
+}
+
+/// Where the price per unit for an item came from
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum ItemPriceSource {
+    /// No price found for the item
+    #[default]
+    None,
+    /// Default price list master list, with the biggest discount list discount applied
+    DefaultPriceList,
+    /// Price list not linked to any customer, applies to all customers
+    GeneralPriceList,
+    /// Price list linked to one of the customer's name tags
+    NameTagPriceList,
+    /// Price list linked to the customer
+    CustomerPriceList,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Default)]
 pub struct ItemPrice {
     pub item_id: String,
     pub default_price_per_unit: Option<f64>,
     pub discount_percentage: Option<f64>,
     pub calculated_price_per_unit: Option<f64>, // Only populated if we have a default price, without a default price we can't calculate the price
+    pub source: ItemPriceSource,
+    /// Price list (or default price list master list) that produced the price per unit
+    pub price_list_id: Option<String>,
+    /// Discount list master list that produced the discount
+    pub discount_list_id: Option<String>,
 }
 
 pub fn get_pricing_for_item(
     ctx: &ServiceContext,
     input: ItemPriceLookup,
 ) -> Result<ItemPrice, RepositoryError> {
-    // 1. Get the default price list & price per unit for the item
-    let default_price_per_unit = MasterListLineRepository::new(&ctx.connection)
+    let date = input.date.unwrap_or_else(|| Local::now().date_naive());
+
+    // 1. Price lists take precedence over the default price list, their prices are final (no
+    // discount list is applied on top)
+    if let Some((source, price_list, line)) =
+        find_price_list_price(ctx, &input.item_id, input.customer_name_id.as_deref(), date)?
+    {
+        return Ok(ItemPrice {
+            item_id: input.item_id,
+            default_price_per_unit: Some(line.price_per_unit),
+            discount_percentage: None,
+            calculated_price_per_unit: Some(line.price_per_unit),
+            source,
+            price_list_id: Some(price_list.id),
+            discount_list_id: None,
+        });
+    }
+
+    // 2. Get the default price list & price per unit for the item
+    let default_price_list_line = MasterListLineRepository::new(&ctx.connection)
         .query_by_filter(
             MasterListLineFilter::new()
                 .master_list(MasterListFilter::new().is_default_price_list(true))
                 .item_id(EqualFilter::equal_to(&input.item_id)),
         )?
         .pop()
+        .filter(|l| l.price_per_unit.is_some());
+    let default_price_per_unit = default_price_list_line
+        .as_ref()
         .and_then(|l| l.price_per_unit);
 
-    // 2. Check if we have a name, and that name is not a patient
+    // 3. Check if we have a name, and that name is not a patient
     let is_patient = match &input.customer_name_id {
         Some(customer_name_id) => {
             let num_patients = PatientRepository::new(&ctx.connection).count(
@@ -46,12 +96,12 @@ pub fn get_pricing_for_item(
         None => false,
     };
 
-    let discount_percentage = if is_patient {
+    let discount_master_list = if is_patient {
         None // Patients get no discount
     } else {
-        // 2.A Lookup the discount list
+        // 3.A Lookup the discount list
         // Always assign the biggest discount we can find
-        let discount_master_list = MasterListRepository::new(&ctx.connection)
+        MasterListRepository::new(&ctx.connection)
             .query(
                 Pagination {
                     limit: 1,
@@ -67,15 +117,14 @@ pub fn get_pricing_for_item(
                     desc: Some(true),
                 }),
             )?
-            .pop();
-
-        match discount_master_list {
-            Some(discount_master_list) => discount_master_list.discount_percentage, // We have a discount list, get the discount, item should be in the list based on query filter above
-            None => None, // No discount list found, no discount
-        }
+            .pop()
     };
+    // We have a discount list, get the discount, item should be in the list based on query filter above
+    let discount_percentage = discount_master_list
+        .as_ref()
+        .and_then(|list| list.discount_percentage);
 
-    // 3. Calculate the price if we are able to
+    // 4. Calculate the price if we are able to
     let calculated_price = match default_price_per_unit {
         Some(default_price_per_unit) => {
             let discount = discount_percentage.unwrap_or(0.0);
@@ -84,11 +133,131 @@ pub fn get_pricing_for_item(
         None => None,
     };
 
-    // 4. Return the pricing data
+    // 5. Return the pricing data
     Ok(ItemPrice {
         item_id: input.item_id,
         default_price_per_unit,
         discount_percentage,
         calculated_price_per_unit: calculated_price,
+        source: match default_price_per_unit {
+            Some(_) => ItemPriceSource::DefaultPriceList,
+            None => ItemPriceSource::None,
+        },
+        price_list_id: default_price_list_line.map(|l| l.master_list_id),
+        discount_list_id: discount_master_list.map(|list| list.id),
     })
 }
+
+/// Finds the price list price for the item that applies to the customer on the date.
+/// Lists linked to the customer are used before lists linked to one of the customer's name
+/// tags, which are used before lists that are not linked to any customer. When several lists
+/// at the same level apply, the list with the highest priority, then the most recent
+/// `valid_from`, is used. Only price lists of the current store are used
+fn find_price_list_price(
+    ctx: &ServiceContext,
+    item_id: &str,
+    customer_name_id: Option<&str>,
+    date: NaiveDate,
+) -> Result<Option<(ItemPriceSource, PriceListRow, PriceListLineRow)>, RepositoryError> {
+    let lines = PriceListLineRowRepository::new(&ctx.connection).find_many_by_item_id(item_id)?;
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
+    let price_list_ids: Vec<String> = lines
+        .iter()
+        .map(|line| line.price_list_id.clone())
+        .collect();
+    let price_lists: HashMap<String, PriceListRow> = PriceListRowRepository::new(&ctx.connection)
+        .find_many_by_ids(&price_list_ids)?
+        .into_iter()
+        .filter(|price_list| price_list.store_id == ctx.store_id && price_list.is_valid_on(date))
+        .map(|price_list| (price_list.id.clone(), price_list))
+        .collect();
+    let joins = PriceListNameJoinRowRepository::new(&ctx.connection)
+        .find_many_by_price_list_ids(&price_list_ids)?;
+
+    let (name_link_ids, name_tag_ids): (HashSet<String>, HashSet<String>) = match customer_name_id {
+        Some(customer_name_id) => {
+            // Invoices reference the customer by name link, price lists may reference
+            // different links of the same (merged) name
+            let name_link_repository = NameLinkRowRepository::new(&ctx.connection);
+            let name_id = match name_link_repository.find_one_by_id(customer_name_id)? {
+                Some(name_link) => name_link.name_id,
+                None => customer_name_id.to_string(),
+            };
+            (
+                name_link_repository
+                    .find_many_by_name_id(&name_id)?
+                    .into_iter()
+                    .map(|name_link| name_link.id)
+                    .collect(),
+                NameTagRepository::new(&ctx.connection)
+                    .query(Some(
+                        NameTagFilter::new().name_id(EqualFilter::equal_to(&name_id)),
+                    ))?
+                    .into_iter()
+                    .map(|name_tag| name_tag.id)
+                    .collect(),
+            )
+        }
+        None => Default::default(),
+    };
+
+    let price = lines
+        .into_iter()
+        .filter_map(|line| {
+            let price_list = price_lists.get(&line.price_list_id)?;
+            let mut list_joins = joins
+                .iter()
+                .filter(|join| join.price_list_id == price_list.id)
+                .peekable();
+
+            let source = if list_joins.peek().is_none() {
+                ItemPriceSource::GeneralPriceList
+            } else {
+                let (for_customer, for_name_tag) =
+                    list_joins.fold((false, false), |(for_customer, for_name_tag), join| {
+                        (
+                            for_customer
+                                || join
+                                    .name_link_id
+                                    .as_ref()
+                                    .is_some_and(|id| name_link_ids.contains(id)),
+                            for_name_tag
+                                || join
+                                    .name_tag_id
+                                    .as_ref()
+                                    .is_some_and(|id| name_tag_ids.contains(id)),
+                        )
+                    });
+                match (for_customer, for_name_tag) {
+                    (true, _) => ItemPriceSource::CustomerPriceList,
+                    (false, true) => ItemPriceSource::NameTagPriceList,
+                    // List is for other customers
+                    (false, false) => return None,
+                }
+            };
+
+            Some((source, price_list.clone(), line))
+        })
+        .max_by(|(a_source, a_list, _), (b_source, b_list, _)| {
+            source_rank(a_source)
+                .cmp(&source_rank(b_source))
+                .then(a_list.priority.cmp(&b_list.priority))
+                .then(a_list.valid_from.cmp(&b_list.valid_from))
+                // Deterministic result for otherwise equal lists
+                .then(b_list.id.cmp(&a_list.id))
+        });
+
+    Ok(price)
+}
+
+fn source_rank(source: &ItemPriceSource) -> u8 {
+    match source {
+        ItemPriceSource::None | ItemPriceSource::DefaultPriceList => 0,
+        ItemPriceSource::GeneralPriceList => 1,
+        ItemPriceSource::NameTagPriceList => 2,
+        ItemPriceSource::CustomerPriceList => 3,
+    }
+}
@@ -1,9 +1,14 @@
 use crate::service_provider::ServiceContext;
 use item_price::{get_pricing_for_item, ItemPrice, ItemPriceLookup};
-use repository::RepositoryError;
+use price_list::{
+    delete_price_list, upsert_price_list, DeletePriceListError, UpsertPriceList,
+    UpsertPriceListError,
+};
+use repository::{PriceListRow, PriceListRowRepository, RepositoryError};
 
 pub mod calculate_sell_price;
 pub mod item_price;
+pub mod price_list;
 
 pub trait PricingServiceTrait: Sync + Send {
     fn get_pricing_for_item(
@@ -13,6 +18,26 @@ pub trait PricingServiceTrait: Sync + Send {
     ) -> Result<ItemPrice, RepositoryError> {
         get_pricing_for_item(ctx, input)
     }
+
+    fn get_price_lists(&self, ctx: &ServiceContext) -> Result<Vec<PriceListRow>, RepositoryError> {
+        PriceListRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)
+    }
+
+    fn upsert_price_list(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertPriceList,
+    ) -> Result<PriceListRow, UpsertPriceListError> {
+        upsert_price_list(ctx, input)
+    }
+
+    fn delete_price_list(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<String, DeletePriceListError> {
+        delete_price_list(ctx, id)
+    }
 }
 
 pub struct PricingService {}
@@ -0,0 +1,209 @@
+use repository::{
+    ItemRowRepository, NameRowRepository, NameTagRowRepository, PriceListLineRow,
+    PriceListLineRowRepository, PriceListNameJoinRow, PriceListNameJoinRowRepository, PriceListRow,
+    PriceListRowRepository, RepositoryError, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{service_provider::ServiceContext, validate::check_store_id_matches};
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpsertPriceListLine {
+    pub item_id: String,
+    pub price_per_unit: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpsertPriceList {
+    pub id: String,
+    pub name: String,
+    pub priority: i32,
+    pub valid_from: Option<chrono::NaiveDate>,
+    pub valid_to: Option<chrono::NaiveDate>,
+    pub is_active: bool,
+    /// Replaces all existing lines of the price list
+    pub lines: Vec<UpsertPriceListLine>,
+    /// Customers the price list applies to
+    pub name_ids: Vec<String>,
+    /// Name tags of customers the price list applies to. The price list applies to all
+    /// customers if neither names nor name tags are set
+    pub name_tag_ids: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertPriceListError {
+    PriceListDoesNotBelongToCurrentStore,
+    ValidToBeforeValidFrom,
+    ItemDoesNotExist(String),
+    DuplicateItem(String),
+    PricePerUnitCannotBeNegative(String),
+    NameDoesNotExist(String),
+    NameTagDoesNotExist(String),
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeletePriceListError {
+    PriceListDoesNotExist,
+    PriceListDoesNotBelongToCurrentStore,
+    DatabaseError(RepositoryError),
+}
+
+/// Creates or updates a price list of the current store
+pub fn upsert_price_list(
+    ctx: &ServiceContext,
+    input: UpsertPriceList,
+) -> Result<PriceListRow, UpsertPriceListError> {
+    let price_list = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate_upsert(connection, &ctx.store_id, &input)?;
+
+            let UpsertPriceList {
+                id,
+                name,
+                priority,
+                valid_from,
+                valid_to,
+                is_active,
+                lines,
+                name_ids,
+                name_tag_ids,
+            } = input;
+
+            let price_list = PriceListRow {
+                id,
+                store_id: ctx.store_id.clone(),
+                name,
+                priority,
+                valid_from,
+                valid_to,
+                is_active,
+            };
+            PriceListRowRepository::new(connection).upsert_one(&price_list)?;
+
+            let line_repository = PriceListLineRowRepository::new(connection);
+            line_repository.delete_by_price_list_id(&price_list.id)?;
+            for UpsertPriceListLine {
+                item_id,
+                price_per_unit,
+            } in lines
+            {
+                line_repository.upsert_one(&PriceListLineRow {
+                    id: uuid(),
+                    price_list_id: price_list.id.clone(),
+                    item_link_id: item_id,
+                    price_per_unit,
+                })?;
+            }
+
+            let join_repository = PriceListNameJoinRowRepository::new(connection);
+            join_repository.delete_by_price_list_id(&price_list.id)?;
+            let name_joins = name_ids.into_iter().map(|name_id| PriceListNameJoinRow {
+                id: uuid(),
+                price_list_id: price_list.id.clone(),
+                name_link_id: Some(name_id),
+                name_tag_id: None,
+            });
+            let name_tag_joins = name_tag_ids
+                .into_iter()
+                .map(|name_tag_id| PriceListNameJoinRow {
+                    id: uuid(),
+                    price_list_id: price_list.id.clone(),
+                    name_link_id: None,
+                    name_tag_id: Some(name_tag_id),
+                });
+            for join in name_joins.chain(name_tag_joins) {
+                join_repository.upsert_one(&join)?;
+            }
+
+            Ok(price_list)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(price_list)
+}
+
+fn validate_upsert(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpsertPriceList,
+) -> Result<(), UpsertPriceListError> {
+    use UpsertPriceListError::*;
+
+    if let Some(existing) = PriceListRowRepository::new(connection).find_one_by_id(&input.id)? {
+        if !check_store_id_matches(&existing.store_id, store_id) {
+            return Err(PriceListDoesNotBelongToCurrentStore);
+        }
+    }
+
+    if let (Some(valid_from), Some(valid_to)) = (input.valid_from, input.valid_to) {
+        if valid_to < valid_from {
+            return Err(ValidToBeforeValidFrom);
+        }
+    }
+
+    let item_repository = ItemRowRepository::new(connection);
+    let mut item_ids = Vec::new();
+    for line in &input.lines {
+        if item_ids.contains(&line.item_id) {
+            return Err(DuplicateItem(line.item_id.clone()));
+        }
+        if line.price_per_unit < 0.0 {
+            return Err(PricePerUnitCannotBeNegative(line.item_id.clone()));
+        }
+        item_repository
+            .find_active_by_id(&line.item_id)?
+            .ok_or_else(|| ItemDoesNotExist(line.item_id.clone()))?;
+        item_ids.push(line.item_id.clone());
+    }
+
+    let name_repository = NameRowRepository::new(connection);
+    for name_id in &input.name_ids {
+        name_repository
+            .find_one_by_id(name_id)?
+            .ok_or_else(|| NameDoesNotExist(name_id.clone()))?;
+    }
+
+    let name_tag_repository = NameTagRowRepository::new(connection);
+    for name_tag_id in &input.name_tag_ids {
+        name_tag_repository
+            .find_one_by_id(name_tag_id)?
+            .ok_or_else(|| NameTagDoesNotExist(name_tag_id.clone()))?;
+    }
+
+    Ok(())
+}
+
+pub fn delete_price_list(ctx: &ServiceContext, id: String) -> Result<String, DeletePriceListError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = PriceListRowRepository::new(connection);
+            let price_list = repository
+                .find_one_by_id(&id)?
+                .ok_or(DeletePriceListError::PriceListDoesNotExist)?;
+            if !check_store_id_matches(&price_list.store_id, &ctx.store_id) {
+                return Err(DeletePriceListError::PriceListDoesNotBelongToCurrentStore);
+            }
+
+            PriceListLineRowRepository::new(connection).delete_by_price_list_id(&id)?;
+            PriceListNameJoinRowRepository::new(connection).delete_by_price_list_id(&id)?;
+            repository.delete(&id)?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(id)
+}
+
+impl From<RepositoryError> for UpsertPriceListError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertPriceListError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeletePriceListError {
+    fn from(error: RepositoryError) -> Self {
+        DeletePriceListError::DatabaseError(error)
+    }
+}
@@ -1,12 +1,20 @@
 #[cfg(test)]
 mod query {
-    use repository::mock::{mock_item_a, mock_item_b, mock_name_store_a};
+    use chrono::NaiveDate;
+    use repository::mock::{
+        mock_item_a, mock_item_b, mock_name_a, mock_name_store_a, mock_name_store_b, mock_store_a,
+        mock_store_b,
+    };
     use repository::{mock::MockDataInserts, test_db::setup_all};
     use repository::{
         MasterListLineRow, MasterListLineRowRepository, MasterListRow, MasterListRowRepository,
+        NameTagJoinRepository, NameTagJoinRow, NameTagRow, NameTagRowRepository,
     };
 
-    use crate::pricing::item_price::ItemPriceLookup;
+    use crate::pricing::item_price::{ItemPriceLookup, ItemPriceSource};
+    use crate::pricing::price_list::{
+        DeletePriceListError, UpsertPriceList, UpsertPriceListError, UpsertPriceListLine,
+    };
     use crate::service_provider::ServiceProvider;
 
     #[actix_rt::test]
@@ -53,6 +61,7 @@ mod query {
                 ItemPriceLookup {
                     item_id: mock_item_a().id.clone(),
                     customer_name_id: Some(mock_name_store_a().id.clone()),
+                    date: None,
                 },
             )
             .unwrap();
@@ -67,6 +76,7 @@ mod query {
                 ItemPriceLookup {
                     item_id: mock_item_b().id.clone(),
                     customer_name_id: Some(mock_name_store_a().id.clone()),
+                    date: None,
                 },
             )
             .unwrap();
@@ -120,6 +130,7 @@ mod query {
                 ItemPriceLookup {
                     item_id: mock_item_a().id.clone(),
                     customer_name_id: None,
+                    date: None,
                 },
             )
             .unwrap();
@@ -138,6 +149,7 @@ mod query {
                 ItemPriceLookup {
                     item_id: mock_item_b().id.clone(),
                     customer_name_id: Some(mock_name_store_a().id.clone()),
+                    date: None,
                 },
             )
             .unwrap();
@@ -218,6 +230,7 @@ mod query {
                 ItemPriceLookup {
                     item_id: mock_item_a().id.clone(),
                     customer_name_id: None,
+                    date: None,
                 },
             )
             .unwrap();
@@ -236,6 +249,7 @@ mod query {
                 ItemPriceLookup {
                     item_id: mock_item_b().id.clone(),
                     customer_name_id: Some(mock_name_store_a().id.clone()),
+                    date: None,
                 },
             )
             .unwrap();
@@ -244,4 +258,259 @@ mod query {
         assert_eq!(pricing.calculated_price_per_unit, None);
         assert_eq!(pricing.discount_percentage, None);
     }
+
+    #[actix_rt::test]
+    async fn price_lists() {
+        let (_, _, connection_manager, _) = setup_all("price_lists", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let store_b_context = service_provider
+            .context(mock_store_b().id, "".to_string())
+            .unwrap();
+        let service = service_provider.pricing_service;
+
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let lookup = |name_id: &str, day| ItemPriceLookup {
+            item_id: mock_item_a().id,
+            customer_name_id: Some(name_id.to_string()),
+            date: Some(date(day)),
+        };
+        let line = |price_per_unit| {
+            vec![UpsertPriceListLine {
+                item_id: mock_item_a().id,
+                price_per_unit,
+            }]
+        };
+
+        // Name tag for name store b
+        NameTagRowRepository::new(&context.connection)
+            .upsert_one(&NameTagRow {
+                id: "ngo".to_string(),
+                name: "NGO".to_string(),
+            })
+            .unwrap();
+        NameTagJoinRepository::new(&context.connection)
+            .upsert_one(&NameTagJoinRow {
+                id: "ngo_name_store_b".to_string(),
+                name_link_id: mock_name_store_b().id,
+                name_tag_id: "ngo".to_string(),
+            })
+            .unwrap();
+
+        // Default price list (master list) is used when no price list applies
+        MasterListRowRepository::new(&context.connection)
+            .upsert_one(&MasterListRow {
+                id: "default_price_list".to_string(),
+                name: "default_price_list".to_string(),
+                is_default_price_list: true,
+                is_active: true,
+                ..Default::default()
+            })
+            .unwrap();
+        MasterListLineRowRepository::new(&context.connection)
+            .upsert_one(&MasterListLineRow {
+                id: "default_price_list_item_a".to_string(),
+                master_list_id: "default_price_list".to_string(),
+                item_link_id: mock_item_a().id,
+                price_per_unit: Some(1.0),
+            })
+            .unwrap();
+
+        // General price list, valid from the 10th
+        service
+            .upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    id: "general".to_string(),
+                    name: "General".to_string(),
+                    valid_from: Some(date(10)),
+                    is_active: true,
+                    lines: line(2.0),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        // Name tag price list
+        service
+            .upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    id: "ngo".to_string(),
+                    name: "NGO".to_string(),
+                    is_active: true,
+                    lines: line(3.0),
+                    name_tag_ids: vec!["ngo".to_string()],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        // Customer price lists, second one has a higher priority but only until the 20th
+        service
+            .upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    id: "customer".to_string(),
+                    name: "Customer".to_string(),
+                    is_active: true,
+                    lines: line(4.0),
+                    name_ids: vec![mock_name_store_a().id],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        service
+            .upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    id: "customer_promotion".to_string(),
+                    name: "Customer promotion".to_string(),
+                    priority: 1,
+                    valid_to: Some(date(20)),
+                    is_active: true,
+                    lines: line(5.0),
+                    name_ids: vec![mock_name_store_a().id],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        // Before the general price list is valid: default price list
+        let pricing = service
+            .get_pricing_for_item(&context, lookup(&mock_name_a().id, 1))
+            .unwrap();
+        assert_eq!(pricing.source, ItemPriceSource::DefaultPriceList);
+        assert_eq!(
+            pricing.price_list_id,
+            Some("default_price_list".to_string())
+        );
+        assert_eq!(pricing.calculated_price_per_unit, Some(1.0));
+
+        // General price list
+        let pricing = service
+            .get_pricing_for_item(&context, lookup(&mock_name_a().id, 10))
+            .unwrap();
+        assert_eq!(pricing.source, ItemPriceSource::GeneralPriceList);
+        assert_eq!(pricing.price_list_id, Some("general".to_string()));
+        assert_eq!(pricing.calculated_price_per_unit, Some(2.0));
+
+        // Name tag price list is used before the general price list
+        let pricing = service
+            .get_pricing_for_item(&context, lookup(&mock_name_store_b().id, 10))
+            .unwrap();
+        assert_eq!(pricing.source, ItemPriceSource::NameTagPriceList);
+        assert_eq!(pricing.price_list_id, Some("ngo".to_string()));
+        assert_eq!(pricing.calculated_price_per_unit, Some(3.0));
+
+        // Customer price list with the highest priority
+        let pricing = service
+            .get_pricing_for_item(&context, lookup(&mock_name_store_a().id, 10))
+            .unwrap();
+        assert_eq!(pricing.source, ItemPriceSource::CustomerPriceList);
+        assert_eq!(
+            pricing.price_list_id,
+            Some("customer_promotion".to_string())
+        );
+        assert_eq!(pricing.calculated_price_per_unit, Some(5.0));
+
+        // Promotion has ended
+        let pricing = service
+            .get_pricing_for_item(&context, lookup(&mock_name_store_a().id, 21))
+            .unwrap();
+        assert_eq!(pricing.price_list_id, Some("customer".to_string()));
+        assert_eq!(pricing.calculated_price_per_unit, Some(4.0));
+
+        // Inactive price lists are not used
+        service
+            .upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    id: "customer".to_string(),
+                    name: "Customer".to_string(),
+                    is_active: false,
+                    lines: line(4.0),
+                    name_ids: vec![mock_name_store_a().id],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let pricing = service
+            .get_pricing_for_item(&context, lookup(&mock_name_store_a().id, 21))
+            .unwrap();
+        assert_eq!(pricing.price_list_id, Some("general".to_string()));
+
+        // Price lists of other stores are not used and can't be changed
+        service
+            .upsert_price_list(
+                &store_b_context,
+                UpsertPriceList {
+                    id: "store_b_customer".to_string(),
+                    name: "Store B customer".to_string(),
+                    is_active: true,
+                    lines: line(6.0),
+                    name_ids: vec![mock_name_store_a().id],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let pricing = service
+            .get_pricing_for_item(&context, lookup(&mock_name_store_a().id, 21))
+            .unwrap();
+        assert_eq!(pricing.price_list_id, Some("general".to_string()));
+        assert_eq!(
+            service.upsert_price_list(
+                &store_b_context,
+                UpsertPriceList {
+                    id: "general".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(UpsertPriceListError::PriceListDoesNotBelongToCurrentStore)
+        );
+        assert_eq!(
+            service.delete_price_list(&store_b_context, "general".to_string()),
+            Err(DeletePriceListError::PriceListDoesNotBelongToCurrentStore)
+        );
+
+        // Deleted price lists are not used
+        service
+            .delete_price_list(&context, "general".to_string())
+            .unwrap();
+        let pricing = service
+            .get_pricing_for_item(&context, lookup(&mock_name_store_a().id, 21))
+            .unwrap();
+        assert_eq!(pricing.source, ItemPriceSource::DefaultPriceList);
+
+        // Validation
+        assert_eq!(
+            service.upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    id: "invalid".to_string(),
+                    valid_from: Some(date(2)),
+                    valid_to: Some(date(1)),
+                    ..Default::default()
+                },
+            ),
+            Err(UpsertPriceListError::ValidToBeforeValidFrom)
+        );
+        assert_eq!(
+            service.upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    id: "invalid".to_string(),
+                    lines: vec![UpsertPriceListLine {
+                        item_id: "invalid".to_string(),
+                        price_per_unit: 1.0,
+                    }],
+                    ..Default::default()
+                },
+            ),
+            Err(UpsertPriceListError::ItemDoesNotExist(
+                "invalid".to_string()
+            ))
+        );
+    }
 }
@@ -0,0 +1,100 @@
+use std::convert::TryFrom;
+
+use repository::{LabelPrinterRow, LabelPrinterRowRepository, PrinterLanguage, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpsertLabelPrinter {
+    pub id: String,
+    pub name: String,
+    pub address: String,
+    pub port: i32,
+    pub printer_language: PrinterLanguage,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertLabelPrinterError {
+    NameCannotBeEmpty,
+    AddressCannotBeEmpty,
+    InvalidPort,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeleteLabelPrinterError {
+    LabelPrinterDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+pub fn upsert_label_printer(
+    ctx: &ServiceContext,
+    input: UpsertLabelPrinter,
+) -> Result<LabelPrinterRow, UpsertLabelPrinterError> {
+    validate_upsert(&input)?;
+
+    let UpsertLabelPrinter {
+        id,
+        name,
+        address,
+        port,
+        printer_language,
+    } = input;
+
+    let printer = LabelPrinterRow {
+        id,
+        name: name.trim().to_string(),
+        address: address.trim().to_string(),
+        port,
+        printer_language,
+    };
+    LabelPrinterRowRepository::new(&ctx.connection).upsert_one(&printer)?;
+
+    Ok(printer)
+}
+
+fn validate_upsert(input: &UpsertLabelPrinter) -> Result<(), UpsertLabelPrinterError> {
+    use UpsertLabelPrinterError::*;
+
+    if input.name.trim().is_empty() {
+        return Err(NameCannotBeEmpty);
+    }
+    if input.address.trim().is_empty() {
+        return Err(AddressCannotBeEmpty);
+    }
+    if u16::try_from(input.port).map_or(true, |port| port == 0) {
+        return Err(InvalidPort);
+    }
+
+    Ok(())
+}
+
+pub fn delete_label_printer(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<String, DeleteLabelPrinterError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = LabelPrinterRowRepository::new(connection);
+            repository
+                .find_one_by_id(&id)?
+                .ok_or(DeleteLabelPrinterError::LabelPrinterDoesNotExist)?;
+            repository.delete(&id)?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(id)
+}
+
+impl From<RepositoryError> for UpsertLabelPrinterError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertLabelPrinterError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeleteLabelPrinterError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteLabelPrinterError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,579 @@
+use std::convert::TryFrom;
+
+use repository::{
+    assets::{
+        asset_catalogue_item_row::AssetCatalogueItemRowRepository, asset_row::AssetRowRepository,
+    },
+    EqualFilter, LabelPrinterRowRepository, LabelTemplateRow, LabelTemplateRowRepository,
+    LabelTemplateType, LocationRowRepository, PrinterLanguage, RepositoryError, StockLine,
+    StockLineFilter, StockLineRepository, StorageConnection,
+};
+use serde_json::{json, Value};
+use util::{gtin_14, GS1};
+
+use crate::{
+    label_printer_settings_service::{
+        LabelPrinterSettingsService, LabelPrinterSettingsServiceTrait,
+    },
+    service_provider::ServiceContext,
+};
+
+use super::jetdirect::{Jetdirect, Mode};
+
+const TEMPLATE_NAME: &str = "label";
+/// Maximum number of copies of each label in a print job
+pub const MAX_LABEL_COPIES: u32 = 100;
+/// Maximum number of labels (records times copies) in a print job
+pub const MAX_LABELS_PER_PRINT: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpsertLabelTemplate {
+    pub id: String,
+    pub name: String,
+    pub template_type: LabelTemplateType,
+    pub printer_language: PrinterLanguage,
+    pub template: String,
+    pub label_width: i32,
+    pub label_height: i32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertLabelTemplateError {
+    NameCannotBeEmpty,
+    InvalidLabelSize,
+    InvalidTemplate(String),
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeleteLabelTemplateError {
+    LabelTemplateDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PrintLabels {
+    pub template_id: String,
+    /// Printer from the label printer settings is used if not set
+    pub printer_id: Option<String>,
+    /// Ids of the stock lines, locations or assets to print, depending on the template type
+    pub record_ids: Vec<String>,
+    /// Number of labels printed for each record
+    pub copies: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PrintLabelsError {
+    NoLabelsToPrint,
+    /// More than `MAX_LABEL_COPIES` copies
+    TooManyCopies,
+    /// More than `MAX_LABELS_PER_PRINT` labels in total
+    TooManyLabels,
+    LabelTemplateDoesNotExist,
+    LabelPrinterDoesNotExist,
+    NoLabelPrinterConfigured,
+    PrinterLanguageMismatch,
+    RecordDoesNotExist(String),
+    RenderError(String),
+    PrinterError(String),
+    DatabaseError(RepositoryError),
+}
+
+pub fn upsert_label_template(
+    ctx: &ServiceContext,
+    input: UpsertLabelTemplate,
+) -> Result<LabelTemplateRow, UpsertLabelTemplateError> {
+    validate_upsert(&input)?;
+
+    let UpsertLabelTemplate {
+        id,
+        name,
+        template_type,
+        printer_language,
+        template,
+        label_width,
+        label_height,
+    } = input;
+
+    let label_template = LabelTemplateRow {
+        id,
+        name: name.trim().to_string(),
+        template_type,
+        printer_language,
+        template,
+        label_width,
+        label_height,
+    };
+    LabelTemplateRowRepository::new(&ctx.connection).upsert_one(&label_template)?;
+
+    Ok(label_template)
+}
+
+fn validate_upsert(input: &UpsertLabelTemplate) -> Result<(), UpsertLabelTemplateError> {
+    use UpsertLabelTemplateError::*;
+
+    if input.name.trim().is_empty() {
+        return Err(NameCannotBeEmpty);
+    }
+    if input.label_width <= 0 || input.label_height <= 0 {
+        return Err(InvalidLabelSize);
+    }
+    compile_template(&input.template).map_err(InvalidTemplate)?;
+
+    Ok(())
+}
+
+pub fn delete_label_template(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<String, DeleteLabelTemplateError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = LabelTemplateRowRepository::new(connection);
+            repository
+                .find_one_by_id(&id)?
+                .ok_or(DeleteLabelTemplateError::LabelTemplateDoesNotExist)?;
+            repository.delete(&id)?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(id)
+}
+
+/// Renders the labels and sends them to the printer as a single job,
+/// returns the number of labels printed
+pub fn print_labels(ctx: &ServiceContext, input: PrintLabels) -> Result<u32, PrintLabelsError> {
+    let PrintLabels {
+        template_id,
+        printer_id,
+        record_ids,
+        copies,
+    } = input;
+
+    if record_ids.is_empty() || copies == 0 {
+        return Err(PrintLabelsError::NoLabelsToPrint);
+    }
+    if copies > MAX_LABEL_COPIES {
+        return Err(PrintLabelsError::TooManyCopies);
+    }
+    if record_ids.len() * copies as usize > MAX_LABELS_PER_PRINT {
+        return Err(PrintLabelsError::TooManyLabels);
+    }
+
+    let template = LabelTemplateRowRepository::new(&ctx.connection)
+        .find_one_by_id(&template_id)?
+        .ok_or(PrintLabelsError::LabelTemplateDoesNotExist)?;
+
+    let (address, port, printer_language) = match printer_id {
+        Some(printer_id) => {
+            let printer = LabelPrinterRowRepository::new(&ctx.connection)
+                .find_one_by_id(&printer_id)?
+                .ok_or(PrintLabelsError::LabelPrinterDoesNotExist)?;
+            let port = u16::try_from(printer.port)
+                .map_err(|_| PrintLabelsError::PrinterError("Invalid port".to_string()))?;
+            (printer.address, port, printer.printer_language)
+        }
+        // Printer configured in the label printer settings only supports ZPL
+        None => {
+            let settings = LabelPrinterSettingsService {}
+                .label_printer_settings(ctx)?
+                .ok_or(PrintLabelsError::NoLabelPrinterConfigured)?;
+            (settings.address, settings.port, PrinterLanguage::Zpl)
+        }
+    };
+
+    if template.printer_language != printer_language {
+        return Err(PrintLabelsError::PrinterLanguageMismatch);
+    }
+
+    let labels = render_labels(&ctx.connection, &ctx.store_id, &template, &record_ids)?;
+
+    let mut payload = Vec::new();
+    for label in &labels {
+        for _ in 0..copies {
+            payload.push(label.as_str());
+        }
+    }
+
+    Jetdirect::new(address, port)
+        .send_string(payload.join("\n"), Mode::Print)
+        .map_err(|err| PrintLabelsError::PrinterError(err.to_string()))?;
+
+    Ok(payload.len() as u32)
+}
+
+/// Renders one label per record, in the order of the record ids
+pub fn render_labels(
+    connection: &StorageConnection,
+    store_id: &str,
+    template: &LabelTemplateRow,
+    record_ids: &[String],
+) -> Result<Vec<String>, PrintLabelsError> {
+    let tera = compile_template(&template.template).map_err(PrintLabelsError::RenderError)?;
+
+    let records = match template.template_type {
+        LabelTemplateType::StockLine => {
+            stock_line_records(connection, store_id, &template.printer_language, record_ids)?
+        }
+        LabelTemplateType::Location => location_records(connection, store_id, record_ids)?,
+        LabelTemplateType::Asset => asset_records(connection, store_id, record_ids)?,
+    };
+
+    records
+        .into_iter()
+        .map(|mut record| {
+            sanitise(&template.printer_language, &mut record);
+            record["label"] = json!({
+                "width": template.label_width,
+                "height": template.label_height,
+            });
+
+            let context = tera::Context::from_value(record)
+                .map_err(|err| PrintLabelsError::RenderError(format!("{:?}", err)))?;
+            tera.render(TEMPLATE_NAME, &context)
+                .map_err(|err| PrintLabelsError::RenderError(format!("{:?}", err)))
+        })
+        .collect()
+}
+
+fn compile_template(template: &str) -> Result<tera::Tera, String> {
+    let mut tera = tera::Tera::default();
+    tera.add_raw_template(TEMPLATE_NAME, template)
+        .map_err(|err| format!("{:?}", err))?;
+    Ok(tera)
+}
+
+fn stock_line_records(
+    connection: &StorageConnection,
+    store_id: &str,
+    printer_language: &PrinterLanguage,
+    ids: &[String],
+) -> Result<Vec<Value>, PrintLabelsError> {
+    let stock_lines = StockLineRepository::new(connection).query_by_filter(
+        StockLineFilter::new()
+            .id(EqualFilter::equal_any(ids.to_vec()))
+            .store_id(EqualFilter::equal_to(store_id)),
+        Some(store_id.to_string()),
+    )?;
+
+    ids.iter()
+        .map(|id| {
+            let stock_line = stock_lines
+                .iter()
+                .find(|stock_line| &stock_line.stock_line_row.id == id)
+                .ok_or_else(|| PrintLabelsError::RecordDoesNotExist(id.clone()))?;
+            Ok(stock_line_record(printer_language, stock_line))
+        })
+        .collect()
+}
+
+fn stock_line_record(printer_language: &PrinterLanguage, stock_line: &StockLine) -> Value {
+    let StockLine {
+        stock_line_row,
+        item_row,
+        location_row,
+        supplier_name_row,
+        barcode_row,
+    } = stock_line;
+
+    let gtin = barcode_row
+        .as_ref()
+        .and_then(|barcode| gtin_14(&barcode.gtin));
+    let expiry_date = stock_line_row.expiry_date;
+    let batch = stock_line_row.batch.clone().unwrap_or_default();
+
+    let mut gs1 = GS1::new();
+    if let Some(gtin) = &gtin {
+        gs1.insert("01", gtin.clone());
+    }
+    if let Some(expiry_date) = expiry_date {
+        gs1.insert("17", expiry_date.format("%y%m%d").to_string());
+    }
+    if !batch.is_empty() {
+        gs1.insert("10", batch.clone());
+    }
+
+    let (location_code, location_name) = location_row
+        .as_ref()
+        .map(|location| (location.code.clone(), location.name.clone()))
+        .unwrap_or_default();
+    let supplier_name = supplier_name_row
+        .as_ref()
+        .map(|name| name.name.clone())
+        .unwrap_or_default();
+
+    json!({
+        "id": stock_line_row.id,
+        "item_code": item_row.code,
+        "item_name": item_row.name,
+        "batch": batch,
+        "expiry_date": expiry_date.map(|date| date.to_string()).unwrap_or_default(),
+        "pack_size": stock_line_row.pack_size,
+        "number_of_packs": stock_line_row.total_number_of_packs,
+        "location_code": location_code,
+        "location_name": location_name,
+        "supplier_name": supplier_name,
+        "gtin": gtin.unwrap_or_default(),
+        "gs1": gs1.to_element_string(),
+        "gs1_human_readable": gs1.to_human_readable_string(),
+        "gs1_datamatrix": gs1_datamatrix_data(printer_language, &gs1),
+    })
+}
+
+/// Data for a GS1 DataMatrix, for ZPL the ^BX escape character must be set to _
+/// (e.g. ^BXN,8,200,,,,_) for the FNC1 sequences
+fn gs1_datamatrix_data(printer_language: &PrinterLanguage, gs1: &GS1) -> String {
+    match printer_language {
+        PrinterLanguage::Zpl => {
+            let mut escaped = GS1::new();
+            for data_element in gs1.to_data_elements() {
+                escaped.insert(&data_element.ai, data_element.data.replace('_', "_d095"));
+            }
+            format!("_1{}", escaped.to_element_string_with_separator("_1"))
+        }
+        PrinterLanguage::Epl => gs1.to_element_string(),
+    }
+}
+
+fn location_records(
+    connection: &StorageConnection,
+    store_id: &str,
+    ids: &[String],
+) -> Result<Vec<Value>, PrintLabelsError> {
+    let locations = LocationRowRepository::new(connection).find_many_by_id(ids)?;
+
+    ids.iter()
+        .map(|id| {
+            let location = locations
+                .iter()
+                .find(|location| &location.id == id && location.store_id == store_id)
+                .ok_or_else(|| PrintLabelsError::RecordDoesNotExist(id.clone()))?;
+            Ok(json!({
+                "id": location.id,
+                "code": location.code,
+                "name": location.name,
+            }))
+        })
+        .collect()
+}
+
+fn asset_records(
+    connection: &StorageConnection,
+    store_id: &str,
+    ids: &[String],
+) -> Result<Vec<Value>, PrintLabelsError> {
+    let asset_repository = AssetRowRepository::new(connection);
+    let catalogue_item_repository = AssetCatalogueItemRowRepository::new(connection);
+
+    ids.iter()
+        .map(|id| {
+            let asset = asset_repository
+                .find_one_by_id(id)?
+                .filter(|asset| {
+                    asset.store_id.as_deref() == Some(store_id) && asset.deleted_datetime.is_none()
+                })
+                .ok_or_else(|| PrintLabelsError::RecordDoesNotExist(id.clone()))?;
+            let catalogue_item = match &asset.catalogue_item_id {
+                Some(catalogue_item_id) => {
+                    catalogue_item_repository.find_one_by_id(catalogue_item_id)?
+                }
+                None => None,
+            };
+            let (catalogue_code, manufacturer, model) = catalogue_item
+                .map(|item| (item.code, item.manufacturer.unwrap_or_default(), item.model))
+                .unwrap_or_default();
+
+            Ok(json!({
+                "id": asset.id,
+                "asset_number": asset.asset_number.unwrap_or_default(),
+                "serial_number": asset.serial_number.unwrap_or_default(),
+                "notes": asset.notes.unwrap_or_default(),
+                "installation_date": asset
+                    .installation_date
+                    .map(|date| date.to_string())
+                    .unwrap_or_default(),
+                "catalogue_code": catalogue_code,
+                "manufacturer": manufacturer,
+                "model": model,
+            }))
+        })
+        .collect()
+}
+
+/// Stops record values from being interpreted as printer commands
+fn sanitise(printer_language: &PrinterLanguage, record: &mut Value) {
+    let Value::Object(fields) = record else {
+        return;
+    };
+    for value in fields.values_mut() {
+        if let Value::String(text) = value {
+            *text = match printer_language {
+                // ^ and ~ start ZPL commands
+                PrinterLanguage::Zpl => text.replace(['^', '~'], " "),
+                // EPL text fields are double quoted
+                PrinterLanguage::Epl => text.replace('\\', "\\\\").replace('"', "\\\""),
+            };
+        }
+    }
+}
+
+impl From<RepositoryError> for UpsertLabelTemplateError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertLabelTemplateError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeleteLabelTemplateError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteLabelTemplateError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for PrintLabelsError {
+    fn from(error: RepositoryError) -> Self {
+        PrintLabelsError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        BarcodeRow, LabelTemplateRow, LabelTemplateType, PrinterLanguage, StockLineRow,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::{
+        print_labels, render_labels, PrintLabels, PrintLabelsError, UpsertLabelTemplate,
+        UpsertLabelTemplateError, MAX_LABEL_COPIES,
+    };
+
+    #[actix_rt::test]
+    async fn label_templates() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "label_templates",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.barcodes = vec![inline_init(|r: &mut BarcodeRow| {
+                    r.id = "label_barcode".to_string();
+                    r.gtin = "9501101020917".to_string();
+                    r.item_id = mock_item_a().id;
+                })];
+                r.stock_lines = vec![inline_init(|r: &mut StockLineRow| {
+                    r.id = "label_stock_line".to_string();
+                    r.store_id = mock_store_a().id;
+                    r.item_link_id = mock_item_a().id;
+                    r.batch = Some("B^1".to_string());
+                    r.expiry_date = NaiveDate::from_ymd_opt(2025, 6, 30);
+                    r.pack_size = 10.0;
+                    r.barcode_id = Some("label_barcode".to_string());
+                })];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.label_printing_service;
+
+        // Invalid template
+        assert!(matches!(
+            service.upsert_label_template(
+                &context,
+                inline_init(|r: &mut UpsertLabelTemplate| {
+                    r.id = "invalid".to_string();
+                    r.name = "Invalid".to_string();
+                    r.template = "^XA^FD{{ item_name ^FS^XZ".to_string();
+                    r.label_width = 400;
+                    r.label_height = 200;
+                })
+            ),
+            Err(UpsertLabelTemplateError::InvalidTemplate(_))
+        ));
+
+        let template = service
+            .upsert_label_template(
+                &context,
+                UpsertLabelTemplate {
+                    id: "batch_label".to_string(),
+                    name: "Batch label".to_string(),
+                    template_type: LabelTemplateType::StockLine,
+                    printer_language: PrinterLanguage::Zpl,
+                    template: concat!(
+                        "^XA^PW{{ label.width }}^FD{{ item_name }} {{ batch }}^FS",
+                        "^FD{{ gs1_datamatrix }}^FS^XZ"
+                    )
+                    .to_string(),
+                    label_width: 400,
+                    label_height: 200,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            template,
+            LabelTemplateRow {
+                id: "batch_label".to_string(),
+                name: "Batch label".to_string(),
+                template_type: LabelTemplateType::StockLine,
+                printer_language: PrinterLanguage::Zpl,
+                template: template.template.clone(),
+                label_width: 400,
+                label_height: 200,
+            }
+        );
+
+        // Batch is sanitised, GTIN padded to 14 digits
+        assert_eq!(
+            render_labels(
+                &context.connection,
+                &context.store_id,
+                &template,
+                &["label_stock_line".to_string()]
+            ),
+            Ok(vec![
+                "^XA^PW400^FDItem A B 1^FS^FD_101095011010209171725063010B 1^FS^XZ".to_string()
+            ])
+        );
+
+        assert_eq!(
+            render_labels(
+                &context.connection,
+                &context.store_id,
+                &template,
+                &["invalid".to_string()]
+            ),
+            Err(PrintLabelsError::RecordDoesNotExist("invalid".to_string()))
+        );
+
+        // Limits of the print job size, checked before anything is rendered
+        let print = |record_ids: Vec<String>, copies: u32| {
+            print_labels(
+                &context,
+                PrintLabels {
+                    template_id: template.id.clone(),
+                    printer_id: None,
+                    record_ids,
+                    copies,
+                },
+            )
+        };
+        assert_eq!(
+            print(vec!["label_stock_line".to_string()], u32::MAX),
+            Err(PrintLabelsError::TooManyCopies)
+        );
+        assert_eq!(
+            print(
+                (0..20).map(|index| format!("record_{index}")).collect(),
+                MAX_LABEL_COPIES
+            ),
+            Err(PrintLabelsError::TooManyLabels)
+        );
+    }
+}
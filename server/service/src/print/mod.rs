@@ -1,2 +1,82 @@
+use repository::{
+    LabelPrinterRow, LabelPrinterRowRepository, LabelTemplateRow, LabelTemplateRowRepository,
+    LabelTemplateType, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+use self::{
+    label_printer::{
+        delete_label_printer, upsert_label_printer, DeleteLabelPrinterError, UpsertLabelPrinter,
+        UpsertLabelPrinterError,
+    },
+    label_template::{
+        delete_label_template, print_labels, upsert_label_template, DeleteLabelTemplateError,
+        PrintLabels, PrintLabelsError, UpsertLabelTemplate, UpsertLabelTemplateError,
+    },
+};
+
 pub mod jetdirect;
 pub mod label;
+pub mod label_printer;
+pub mod label_template;
+
+pub trait LabelPrintingServiceTrait: Sync + Send {
+    fn get_label_templates(
+        &self,
+        ctx: &ServiceContext,
+        template_type: Option<LabelTemplateType>,
+    ) -> Result<Vec<LabelTemplateRow>, RepositoryError> {
+        LabelTemplateRowRepository::new(&ctx.connection).find_many(template_type)
+    }
+
+    fn upsert_label_template(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertLabelTemplate,
+    ) -> Result<LabelTemplateRow, UpsertLabelTemplateError> {
+        upsert_label_template(ctx, input)
+    }
+
+    fn delete_label_template(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<String, DeleteLabelTemplateError> {
+        delete_label_template(ctx, id)
+    }
+
+    fn get_label_printers(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<LabelPrinterRow>, RepositoryError> {
+        LabelPrinterRowRepository::new(&ctx.connection).find_all()
+    }
+
+    fn upsert_label_printer(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertLabelPrinter,
+    ) -> Result<LabelPrinterRow, UpsertLabelPrinterError> {
+        upsert_label_printer(ctx, input)
+    }
+
+    fn delete_label_printer(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<String, DeleteLabelPrinterError> {
+        delete_label_printer(ctx, id)
+    }
+
+    fn print_labels(
+        &self,
+        ctx: &ServiceContext,
+        input: PrintLabels,
+    ) -> Result<u32, PrintLabelsError> {
+        print_labels(ctx, input)
+    }
+}
+
+pub struct LabelPrintingService {}
+impl LabelPrintingServiceTrait for LabelPrintingService {}
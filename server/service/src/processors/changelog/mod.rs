@@ -0,0 +1,294 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use repository::{
+    ChangelogFilter, ChangelogProcessorRow, ChangelogProcessorRowRepository, ChangelogRepository,
+    ChangelogRow, ChangelogTableName, EqualFilter, RepositoryError, StorageConnection,
+};
+use thiserror::Error;
+
+use crate::service_provider::{ServiceContext, ServiceProvider};
+
+use self::temperature_breach::TemperatureBreachProcessor;
+
+mod temperature_breach;
+#[cfg(test)]
+mod test;
+
+const CHANGELOG_BATCH_SIZE: u32 = 20;
+
+/// Processors run by `Processors` for changes to their changelog tables, each processor keeps
+/// its own cursor, retry state and last error in the `changelog_processor` table.
+/// To add a processor implement `ChangelogProcessor` and add it to this list
+pub(crate) fn changelog_processors() -> Vec<Box<dyn ChangelogProcessor>> {
+    vec![Box::new(TemperatureBreachProcessor)]
+}
+
+#[derive(Error, Debug)]
+pub enum ChangelogProcessorError {
+    #[error("Database error {0:?}")]
+    DatabaseError(RepositoryError),
+    #[error("{0}")]
+    ProcessingError(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Wait after the first failure, doubled for every consecutive failure
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Record is skipped after this many failed attempts, retried indefinitely if not set
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            initial_backoff: Duration::seconds(30),
+            max_backoff: Duration::hours(1),
+            max_attempts: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn backoff(&self, error_count: i32) -> Duration {
+        let exponent = (error_count - 1).clamp(0, 30) as u32;
+        self.initial_backoff
+            .checked_mul(2i32.pow(exponent))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+pub trait ChangelogProcessor {
+    /// Unique code of the processor, identifies the processor state, changing it will
+    /// restart the processor from the beginning of the changelog
+    fn code(&self) -> &'static str;
+
+    fn description(&self) -> String;
+
+    /// Changelog tables the processor handles
+    fn table_names(&self) -> Vec<ChangelogTableName>;
+
+    /// Changelogs passed to the processor, all changes to `table_names` by default
+    fn changelog_filter(
+        &self,
+        _connection: &StorageConnection,
+    ) -> Result<ChangelogFilter, RepositoryError> {
+        Ok(ChangelogFilter::new().table_name(EqualFilter {
+            equal_any: Some(self.table_names()),
+            ..Default::default()
+        }))
+    }
+
+    /// When the processor is first run, only process changes made after this point rather
+    /// than the whole changelog
+    fn skip_existing_changelogs(&self) -> bool {
+        false
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// Processes a single changelog in a transaction, the cursor is only moved past the
+    /// changelog when Ok is returned. Ok(Some) result is logged
+    fn try_process_record(
+        &self,
+        ctx: &ServiceContext,
+        service_provider: &ServiceProvider,
+        changelog: &ChangelogRow,
+    ) -> Result<Option<String>, ChangelogProcessorError>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangelogProcessorStatus {
+    pub code: String,
+    pub description: String,
+    pub cursor: u64,
+    /// Number of changelogs still to be processed
+    pub lag: u64,
+    pub last_run_datetime: Option<NaiveDateTime>,
+    pub last_success_datetime: Option<NaiveDateTime>,
+    pub error_count: i32,
+    pub last_error: Option<String>,
+    pub last_error_datetime: Option<NaiveDateTime>,
+    pub next_retry_datetime: Option<NaiveDateTime>,
+}
+
+pub trait ChangelogProcessorServiceTrait: Sync + Send {
+    fn get_statuses(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<ChangelogProcessorStatus>, RepositoryError> {
+        get_changelog_processor_statuses(&ctx.connection, &changelog_processors())
+    }
+}
+
+pub struct ChangelogProcessorService {}
+impl ChangelogProcessorServiceTrait for ChangelogProcessorService {}
+
+/// Runs all processors until they are caught up with the changelog, a failing processor
+/// does not stop the other processors
+pub(crate) fn process_changelog_processors(
+    service_provider: &ServiceProvider,
+    processors: &[Box<dyn ChangelogProcessor>],
+) -> Result<(), RepositoryError> {
+    let ctx = service_provider.basic_context()?;
+
+    for processor in processors {
+        if let Err(error) = run_processor(&ctx, service_provider, processor.as_ref()) {
+            log::error!(
+                "Error running changelog processor ({}) {:?}",
+                processor.description(),
+                error
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn run_processor(
+    ctx: &ServiceContext,
+    service_provider: &ServiceProvider,
+    processor: &dyn ChangelogProcessor,
+) -> Result<(), RepositoryError> {
+    let repo = ChangelogProcessorRowRepository::new(&ctx.connection);
+    let changelog_repo = ChangelogRepository::new(&ctx.connection);
+
+    let mut state = get_state(&ctx.connection, processor)?;
+
+    let now = Utc::now().naive_utc();
+    if state
+        .next_retry_datetime
+        .is_some_and(|next_retry| now < next_retry)
+    {
+        return Ok(());
+    }
+    state.last_run_datetime = Some(now);
+
+    let filter = processor.changelog_filter(&ctx.connection)?;
+    loop {
+        let logs = changelog_repo.changelogs(
+            state.cursor as u64,
+            CHANGELOG_BATCH_SIZE,
+            Some(filter.clone()),
+        )?;
+
+        if logs.is_empty() {
+            break;
+        }
+
+        for log in logs {
+            let result = ctx
+                .connection
+                .transaction_sync(|_| processor.try_process_record(ctx, service_provider, &log))
+                .map_err(|error| error.to_inner_error());
+
+            let now = Utc::now().naive_utc();
+            match result {
+                Ok(result) => {
+                    if let Some(result) = result {
+                        log::info!("{} - {}", processor.description(), result);
+                    }
+                    state.last_success_datetime = Some(now);
+                }
+                Err(error) => {
+                    log::error!(
+                        "Error in changelog processor ({}) for {:?} {} - {}",
+                        processor.description(),
+                        log.table_name,
+                        log.record_id,
+                        error
+                    );
+                    state.error_count += 1;
+                    state.last_error = Some(error.to_string());
+                    state.last_error_datetime = Some(now);
+
+                    let retry_policy = processor.retry_policy();
+                    let attempts_exhausted = retry_policy
+                        .max_attempts
+                        .is_some_and(|max_attempts| state.error_count as u32 >= max_attempts);
+
+                    if !attempts_exhausted {
+                        state.next_retry_datetime =
+                            Some(now + retry_policy.backoff(state.error_count));
+                        repo.upsert_one(&state)?;
+                        return Ok(());
+                    }
+
+                    log::error!(
+                        "Skipping changelog {} in changelog processor ({}) after {} attempts",
+                        log.cursor,
+                        processor.description(),
+                        state.error_count
+                    );
+                }
+            }
+
+            state.cursor = log.cursor + 1;
+            state.error_count = 0;
+            state.next_retry_datetime = None;
+            repo.upsert_one(&state)?;
+        }
+    }
+
+    repo.upsert_one(&state)?;
+    Ok(())
+}
+
+fn get_state(
+    connection: &StorageConnection,
+    processor: &dyn ChangelogProcessor,
+) -> Result<ChangelogProcessorRow, RepositoryError> {
+    if let Some(state) =
+        ChangelogProcessorRowRepository::new(connection).find_one_by_id(processor.code())?
+    {
+        return Ok(state);
+    }
+
+    let cursor = match processor.skip_existing_changelogs() {
+        true => ChangelogRepository::new(connection).latest_cursor()? as i64 + 1,
+        false => 0,
+    };
+    Ok(ChangelogProcessorRow {
+        id: processor.code().to_string(),
+        cursor,
+        ..Default::default()
+    })
+}
+
+pub(crate) fn get_changelog_processor_statuses(
+    connection: &StorageConnection,
+    processors: &[Box<dyn ChangelogProcessor>],
+) -> Result<Vec<ChangelogProcessorStatus>, RepositoryError> {
+    let changelog_repo = ChangelogRepository::new(connection);
+
+    processors
+        .iter()
+        .map(|processor| {
+            let state = get_state(connection, processor.as_ref())?;
+            let cursor = state.cursor as u64;
+            let lag =
+                changelog_repo.count(cursor, Some(processor.changelog_filter(connection)?))?;
+
+            Ok(ChangelogProcessorStatus {
+                code: processor.code().to_string(),
+                description: processor.description(),
+                cursor,
+                lag,
+                last_run_datetime: state.last_run_datetime,
+                last_success_datetime: state.last_success_datetime,
+                error_count: state.error_count,
+                last_error: state.last_error,
+                last_error_datetime: state.last_error_datetime,
+                next_retry_datetime: state.next_retry_datetime,
+            })
+        })
+        .collect()
+}
+
+impl From<RepositoryError> for ChangelogProcessorError {
+    fn from(error: RepositoryError) -> Self {
+        ChangelogProcessorError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,81 @@
+use repository::{
+    ChangelogFilter, ChangelogRow, ChangelogTableName, EqualFilter, RepositoryError, RowActionType,
+    StorageConnection, TemperatureLogRowRepository,
+};
+
+use crate::{
+    cold_chain::breach_detection::detect_temperature_breaches,
+    service_provider::{ServiceContext, ServiceProvider},
+    sync::{ActiveStoresOnSite, GetActiveStoresOnSiteError},
+};
+
+use super::{ChangelogProcessor, ChangelogProcessorError};
+
+const TEMPERATURE_BREACH_PROCESSOR_CODE: &str = "temperature_breach_detection";
+
+/// Creates, extends and closes temperature breaches as temperature logs are added, for logs of
+/// stores active on this site (breaches sync to other sites)
+pub(crate) struct TemperatureBreachProcessor;
+
+impl ChangelogProcessor for TemperatureBreachProcessor {
+    fn code(&self) -> &'static str {
+        TEMPERATURE_BREACH_PROCESSOR_CODE
+    }
+
+    fn description(&self) -> String {
+        "Temperature breach detection".to_string()
+    }
+
+    fn table_names(&self) -> Vec<ChangelogTableName> {
+        vec![ChangelogTableName::TemperatureLog]
+    }
+
+    fn changelog_filter(
+        &self,
+        connection: &StorageConnection,
+    ) -> Result<ChangelogFilter, RepositoryError> {
+        let store_ids = match ActiveStoresOnSite::get(connection) {
+            Ok(active_stores) => active_stores.store_ids(),
+            // Site is not initialised yet, changelogs are processed once it is
+            Err(GetActiveStoresOnSiteError::SiteIdNotSet) => Vec::new(),
+            Err(GetActiveStoresOnSiteError::DatabaseError(error)) => return Err(error),
+        };
+
+        Ok(ChangelogFilter::new()
+            .table_name(ChangelogTableName::TemperatureLog.equal_to())
+            .action(RowActionType::Upsert.equal_to())
+            .store_id(EqualFilter {
+                equal_any: Some(store_ids),
+                ..Default::default()
+            }))
+    }
+
+    /// Existing logs already have their breaches (from sensor imports or clients)
+    fn skip_existing_changelogs(&self) -> bool {
+        true
+    }
+
+    fn try_process_record(
+        &self,
+        ctx: &ServiceContext,
+        _: &ServiceProvider,
+        changelog: &ChangelogRow,
+    ) -> Result<Option<String>, ChangelogProcessorError> {
+        let Some(temperature_log) = TemperatureLogRowRepository::new(&ctx.connection)
+            .find_one_by_id(&changelog.record_id)?
+        else {
+            return Ok(None);
+        };
+
+        let breaches = detect_temperature_breaches(&ctx.connection, &temperature_log)?;
+        if breaches.is_empty() {
+            return Ok(None);
+        }
+
+        let breach_ids: Vec<String> = breaches.into_iter().map(|breach| breach.id).collect();
+        Ok(Some(format!(
+            "Upserted temperature breaches {:?} for sensor {}",
+            breach_ids, temperature_log.sensor_id
+        )))
+    }
+}
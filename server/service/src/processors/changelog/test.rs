@@ -0,0 +1,242 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use repository::{
+    mock::{mock_store_a, MockData, MockDataInserts},
+    test_db::setup_all,
+    ChangelogProcessorRowRepository, ChangelogRow, ChangelogTableName, KeyType, KeyValueStoreRow,
+    LocationRow, LocationRowRepository, SensorRow, TemperatureBreachConfigRow,
+    TemperatureBreachType, TemperatureLogRow, TemperatureLogRowRepository,
+};
+use util::inline_init;
+
+use crate::{
+    processors::changelog::{
+        changelog_processors, get_changelog_processor_statuses, process_changelog_processors,
+        ChangelogProcessor, ChangelogProcessorError, RetryPolicy,
+    },
+    service_provider::{ServiceContext, ServiceProvider},
+    test_helpers::{setup_all_with_data_and_service_provider, ServiceTestContext},
+};
+
+const TEST_PROCESSOR_CODE: &str = "test_location_processor";
+
+struct TestLocationProcessor {
+    processed: Arc<Mutex<Vec<String>>>,
+    fail_record_id: Option<String>,
+}
+
+impl ChangelogProcessor for TestLocationProcessor {
+    fn code(&self) -> &'static str {
+        TEST_PROCESSOR_CODE
+    }
+
+    fn description(&self) -> String {
+        "Test location processor".to_string()
+    }
+
+    fn table_names(&self) -> Vec<ChangelogTableName> {
+        vec![ChangelogTableName::Location]
+    }
+
+    fn try_process_record(
+        &self,
+        _: &ServiceContext,
+        _: &ServiceProvider,
+        changelog: &ChangelogRow,
+    ) -> Result<Option<String>, ChangelogProcessorError> {
+        if self.fail_record_id.as_ref() == Some(&changelog.record_id) {
+            return Err(ChangelogProcessorError::ProcessingError(format!(
+                "Cannot process {}",
+                changelog.record_id
+            )));
+        }
+        self.processed
+            .lock()
+            .unwrap()
+            .push(changelog.record_id.clone());
+        Ok(None)
+    }
+}
+
+fn location(id: &str) -> LocationRow {
+    LocationRow {
+        id: id.to_string(),
+        name: id.to_string(),
+        code: id.to_string(),
+        store_id: mock_store_a().id,
+        ..Default::default()
+    }
+}
+
+#[actix_rt::test]
+async fn changelog_processor_retry_and_status() {
+    let (_, connection, connection_manager, _) = setup_all(
+        "changelog_processor_retry_and_status",
+        MockDataInserts::none().names().stores(),
+    )
+    .await;
+    let service_provider = ServiceProvider::new(connection_manager, "app_data");
+
+    let processed = Arc::new(Mutex::new(Vec::new()));
+    let processors = |fail_record_id: Option<&str>| -> Vec<Box<dyn ChangelogProcessor>> {
+        vec![Box::new(TestLocationProcessor {
+            processed: processed.clone(),
+            fail_record_id: fail_record_id.map(str::to_string),
+        })]
+    };
+    let location_repo = LocationRowRepository::new(&connection);
+
+    // Records are processed in changelog order
+    location_repo.upsert_one(&location("location_1")).unwrap();
+    location_repo.upsert_one(&location("location_2")).unwrap();
+    process_changelog_processors(&service_provider, &processors(None)).unwrap();
+    assert_eq!(*processed.lock().unwrap(), vec!["location_1", "location_2"]);
+
+    let status = get_changelog_processor_statuses(&connection, &processors(None))
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(status.code, TEST_PROCESSOR_CODE);
+    assert_eq!(status.lag, 0);
+    assert_eq!(status.error_count, 0);
+    assert_eq!(status.last_error, None);
+    assert!(status.last_success_datetime.is_some());
+
+    // Failing record stops the processor until the retry time
+    location_repo.upsert_one(&location("location_3")).unwrap();
+    location_repo.upsert_one(&location("location_4")).unwrap();
+    process_changelog_processors(&service_provider, &processors(Some("location_3"))).unwrap();
+    assert_eq!(processed.lock().unwrap().len(), 2);
+
+    let status = get_changelog_processor_statuses(&connection, &processors(None))
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(status.lag, 2);
+    assert_eq!(status.error_count, 1);
+    assert_eq!(
+        status.last_error,
+        Some("Cannot process location_3".to_string())
+    );
+    assert!(status.next_retry_datetime.is_some());
+
+    process_changelog_processors(&service_provider, &processors(None)).unwrap();
+    assert_eq!(processed.lock().unwrap().len(), 2);
+
+    // Retry time reached
+    let state_repo = ChangelogProcessorRowRepository::new(&connection);
+    let mut state = state_repo
+        .find_one_by_id(TEST_PROCESSOR_CODE)
+        .unwrap()
+        .unwrap();
+    state.next_retry_datetime = Some(Utc::now().naive_utc() - Duration::seconds(1));
+    state_repo.upsert_one(&state).unwrap();
+
+    process_changelog_processors(&service_provider, &processors(None)).unwrap();
+    assert_eq!(
+        *processed.lock().unwrap(),
+        vec!["location_1", "location_2", "location_3", "location_4"]
+    );
+
+    let status = get_changelog_processor_statuses(&connection, &processors(None))
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(status.lag, 0);
+    assert_eq!(status.error_count, 0);
+    assert_eq!(status.next_retry_datetime, None);
+    // Last error is kept for reporting
+    assert_eq!(
+        status.last_error,
+        Some("Cannot process location_3".to_string())
+    );
+}
+
+/// Registered processors run by the processors task when triggered, and only process changes
+/// after their cursor
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn changelog_processors_triggered() {
+    let datetime = |hour: u32| -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 1, 1)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    };
+    let log = |id: &str, hour: u32, temperature: f64| TemperatureLogRow {
+        id: id.to_string(),
+        temperature,
+        sensor_id: "sensor".to_string(),
+        store_id: mock_store_a().id,
+        datetime: datetime(hour),
+        ..Default::default()
+    };
+
+    let ServiceTestContext {
+        connection,
+        service_provider,
+        ..
+    } = setup_all_with_data_and_service_provider(
+        "changelog_processors_triggered",
+        MockDataInserts::none().names().stores(),
+        inline_init(|r: &mut MockData| {
+            r.key_value_store_rows = vec![inline_init(|r: &mut KeyValueStoreRow| {
+                r.id = KeyType::SettingsSyncSiteId;
+                r.value_int = Some(mock_store_a().site_id);
+            })];
+            r.sensors = vec![SensorRow {
+                id: "sensor".to_string(),
+                serial: "sensor".to_string(),
+                store_id: mock_store_a().id,
+                is_active: true,
+                ..Default::default()
+            }];
+            r.temperature_breach_configs = vec![TemperatureBreachConfigRow {
+                id: "hot".to_string(),
+                duration_milliseconds: 30 * 60 * 1000,
+                r#type: TemperatureBreachType::HotConsecutive,
+                description: "hot".to_string(),
+                is_active: true,
+                store_id: mock_store_a().id,
+                minimum_temperature: 2.0,
+                maximum_temperature: 8.0,
+            }];
+        }),
+    )
+    .await;
+    let ctx = service_provider.basic_context().unwrap();
+    let log_repo = TemperatureLogRowRepository::new(&connection);
+
+    // First run starts the temperature breach processor after the existing changelogs
+    ctx.processors_trigger.trigger_changelog_processors();
+    ctx.processors_trigger.await_events_processed().await;
+
+    log_repo.upsert_one(&log("1", 1, 9.0)).unwrap();
+    log_repo.upsert_one(&log("2", 2, 9.5)).unwrap();
+    ctx.processors_trigger.trigger_changelog_processors();
+    ctx.processors_trigger.await_events_processed().await;
+
+    let log_1 = log_repo.find_one_by_id("1").unwrap().unwrap();
+    let log_2 = log_repo.find_one_by_id("2").unwrap().unwrap();
+    assert!(log_1.temperature_breach_id.is_some());
+    assert_eq!(log_1.temperature_breach_id, log_2.temperature_breach_id);
+
+    let statuses = get_changelog_processor_statuses(&connection, &changelog_processors()).unwrap();
+    let status = statuses
+        .iter()
+        .find(|status| status.code == "temperature_breach_detection")
+        .unwrap();
+    assert_eq!(status.lag, 0);
+    assert_eq!(status.error_count, 0);
+    assert!(status.last_success_datetime.is_some());
+}
+
+#[test]
+fn retry_policy_backoff() {
+    let policy = RetryPolicy::default();
+    assert_eq!(policy.backoff(1), Duration::seconds(30));
+    assert_eq!(policy.backoff(2), Duration::seconds(60));
+    assert_eq!(policy.backoff(3), Duration::seconds(120));
+    assert_eq!(policy.backoff(20), Duration::hours(1));
+    assert_eq!(policy.backoff(i32::MAX), Duration::hours(1));
+}
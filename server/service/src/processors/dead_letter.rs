@@ -0,0 +1,303 @@
+use std::fmt::Display;
+
+use chrono::{Duration, Utc};
+use repository::{
+    ChangelogRow, ChangelogTableName, RepositoryError, StorageConnection, TransferDeadLetterRow,
+    TransferDeadLetterRowRepository, TransferProcessorType,
+};
+use util::uuid::uuid;
+
+use crate::{processors::changelog::RetryPolicy, service_provider::ServiceContext};
+
+/// Failed transfer records are retried automatically with back off, after `max_attempts` they
+/// are only processed again for a new change to the record or when a retry is requested
+pub(crate) fn transfer_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        initial_backoff: Duration::minutes(1),
+        max_backoff: Duration::hours(1),
+        max_attempts: Some(5),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RetryTransferDeadLetterError {
+    DeadLetterDoesNotExist,
+    DeadLetterAlreadyResolved,
+    DatabaseError(RepositoryError),
+}
+
+pub trait TransferDeadLetterServiceTrait: Sync + Send {
+    fn get_dead_letters(
+        &self,
+        ctx: &ServiceContext,
+        include_resolved: bool,
+    ) -> Result<Vec<TransferDeadLetterRow>, RepositoryError> {
+        TransferDeadLetterRowRepository::new(&ctx.connection).find_many(include_resolved)
+    }
+
+    fn retry_dead_letter(
+        &self,
+        ctx: &ServiceContext,
+        id: &str,
+    ) -> Result<TransferDeadLetterRow, RetryTransferDeadLetterError> {
+        retry_dead_letter(ctx, id)
+    }
+}
+
+pub struct TransferDeadLetterService {}
+impl TransferDeadLetterServiceTrait for TransferDeadLetterService {}
+
+/// Flags the record to be processed again and triggers the transfer processor. The record is not
+/// processed here, transfer processors must only run in the processors task
+pub fn retry_dead_letter(
+    ctx: &ServiceContext,
+    id: &str,
+) -> Result<TransferDeadLetterRow, RetryTransferDeadLetterError> {
+    let repo = TransferDeadLetterRowRepository::new(&ctx.connection);
+    let mut dead_letter = repo
+        .find_one_by_id(id)?
+        .ok_or(RetryTransferDeadLetterError::DeadLetterDoesNotExist)?;
+
+    if dead_letter.resolved_datetime.is_some() {
+        return Err(RetryTransferDeadLetterError::DeadLetterAlreadyResolved);
+    }
+
+    dead_letter.retry_requested = true;
+    repo.upsert_one(&dead_letter)?;
+
+    match dead_letter.processor_type {
+        TransferProcessorType::RequisitionTransfer => ctx
+            .processors_trigger
+            .trigger_requisition_transfer_processors(),
+        TransferProcessorType::InvoiceTransfer => {
+            ctx.processors_trigger.trigger_invoice_transfer_processors()
+        }
+    }
+
+    Ok(dead_letter)
+}
+
+/// Dead letters the transfer processor should process again before new changelogs
+pub(crate) fn get_retry_due_changelogs(
+    connection: &StorageConnection,
+    processor_type: TransferProcessorType,
+) -> Result<Vec<ChangelogRow>, RepositoryError> {
+    let table_name = match processor_type {
+        TransferProcessorType::RequisitionTransfer => ChangelogTableName::Requisition,
+        TransferProcessorType::InvoiceTransfer => ChangelogTableName::Invoice,
+    };
+
+    let dead_letters = TransferDeadLetterRowRepository::new(connection)
+        .find_due_for_retry(processor_type, Utc::now().naive_utc())?;
+
+    Ok(dead_letters
+        .into_iter()
+        .map(|dead_letter| ChangelogRow {
+            cursor: dead_letter.changelog_cursor,
+            table_name: table_name.clone(),
+            record_id: dead_letter.record_id,
+            row_action: dead_letter.row_action,
+            name_id: dead_letter.name_id,
+            store_id: dead_letter.store_id,
+            is_sync_update: false,
+            source_site_id: None,
+        })
+        .collect())
+}
+
+/// Records a failed changelog as a dead letter (or another attempt of an existing dead letter for
+/// the record), a successfully processed changelog resolves the record's dead letter
+pub(crate) fn record_transfer_result<E: Display>(
+    connection: &StorageConnection,
+    processor_type: TransferProcessorType,
+    changelog: &ChangelogRow,
+    result: Result<(), E>,
+) -> Result<(), RepositoryError> {
+    let repo = TransferDeadLetterRowRepository::new(connection);
+    let existing = repo.find_unresolved_by_record_id(processor_type, &changelog.record_id)?;
+    let now = Utc::now().naive_utc();
+
+    let dead_letter = match (result, existing) {
+        (Ok(()), None) => return Ok(()),
+        (Ok(()), Some(existing)) => TransferDeadLetterRow {
+            retry_requested: false,
+            next_retry_datetime: None,
+            resolved_datetime: Some(now),
+            ..existing
+        },
+        (Err(error), existing) => {
+            log::error!(
+                "Transfer processor ({:?}) failed for {} - {}",
+                processor_type,
+                changelog.record_id,
+                error
+            );
+
+            let (id, attempt_count, first_attempt_datetime) = match existing {
+                Some(existing) => (
+                    existing.id,
+                    existing.attempt_count + 1,
+                    existing.first_attempt_datetime,
+                ),
+                None => (uuid(), 1, now),
+            };
+
+            let retry_policy = transfer_retry_policy();
+            let attempts_exhausted = retry_policy
+                .max_attempts
+                .is_some_and(|max_attempts| attempt_count as u32 >= max_attempts);
+            let next_retry_datetime = if attempts_exhausted {
+                log::error!(
+                    "Transfer processor ({:?}) stopped retrying {} after {} attempts",
+                    processor_type,
+                    changelog.record_id,
+                    attempt_count
+                );
+                None
+            } else {
+                Some(now + retry_policy.backoff(attempt_count))
+            };
+
+            TransferDeadLetterRow {
+                id,
+                processor_type,
+                record_id: changelog.record_id.clone(),
+                row_action: changelog.row_action.clone(),
+                changelog_cursor: changelog.cursor,
+                name_id: changelog.name_id.clone(),
+                store_id: changelog.store_id.clone(),
+                error: error.to_string(),
+                attempt_count,
+                first_attempt_datetime,
+                last_attempt_datetime: now,
+                retry_requested: false,
+                next_retry_datetime,
+                resolved_datetime: None,
+            }
+        }
+    };
+
+    repo.upsert_one(&dead_letter)
+}
+
+impl From<RepositoryError> for RetryTransferDeadLetterError {
+    fn from(error: RepositoryError) -> Self {
+        RetryTransferDeadLetterError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{mock::MockDataInserts, test_db::setup_all, RowActionType};
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn transfer_dead_letter() {
+        let (_, connection, connection_manager, _) =
+            setup_all("transfer_dead_letter", MockDataInserts::none()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let repo = TransferDeadLetterRowRepository::new(&connection);
+
+        let changelog = ChangelogRow {
+            cursor: 10,
+            table_name: ChangelogTableName::Invoice,
+            record_id: "invoice_id".to_string(),
+            row_action: RowActionType::Upsert,
+            name_id: Some("name_id".to_string()),
+            ..Default::default()
+        };
+        let processor_type = TransferProcessorType::InvoiceTransfer;
+
+        // Success without dead letter does nothing
+        record_transfer_result::<String>(&connection, processor_type, &changelog, Ok(())).unwrap();
+        assert_eq!(repo.find_many(true).unwrap(), vec![]);
+
+        // Failure creates dead letter
+        record_transfer_result(&connection, processor_type, &changelog, Err("first")).unwrap();
+        let dead_letter = repo.find_many(false).unwrap().pop().unwrap();
+        assert_eq!(dead_letter.attempt_count, 1);
+        assert_eq!(dead_letter.error, "first");
+        assert_eq!(dead_letter.changelog_cursor, 10);
+
+        // Retried automatically after the back off
+        assert!(dead_letter.next_retry_datetime.unwrap() > Utc::now().naive_utc());
+        assert_eq!(
+            get_retry_due_changelogs(&connection, processor_type),
+            Ok(vec![])
+        );
+        repo.upsert_one(&TransferDeadLetterRow {
+            next_retry_datetime: Some(Utc::now().naive_utc() - Duration::seconds(1)),
+            ..dead_letter.clone()
+        })
+        .unwrap();
+        assert_eq!(
+            get_retry_due_changelogs(&connection, processor_type)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Another failure for the record is another attempt
+        let changelog = ChangelogRow {
+            cursor: 12,
+            ..changelog
+        };
+        record_transfer_result(&connection, processor_type, &changelog, Err("second")).unwrap();
+        let dead_letters = repo.find_many(false).unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].id, dead_letter.id);
+        assert_eq!(dead_letters[0].attempt_count, 2);
+        assert_eq!(dead_letters[0].error, "second");
+        assert_eq!(dead_letters[0].changelog_cursor, 12);
+        assert!(dead_letters[0].next_retry_datetime.unwrap() > Utc::now().naive_utc());
+
+        // Retry
+        assert_eq!(
+            retry_dead_letter(&context, "invalid"),
+            Err(RetryTransferDeadLetterError::DeadLetterDoesNotExist)
+        );
+        let dead_letter = retry_dead_letter(&context, &dead_letter.id).unwrap();
+        assert!(dead_letter.retry_requested);
+        let retry_changelogs = get_retry_due_changelogs(&connection, processor_type).unwrap();
+        assert_eq!(retry_changelogs, vec![changelog.clone()]);
+        assert_eq!(
+            get_retry_due_changelogs(&connection, TransferProcessorType::RequisitionTransfer),
+            Ok(vec![])
+        );
+
+        // Success resolves the dead letter
+        record_transfer_result::<String>(&connection, processor_type, &changelog, Ok(())).unwrap();
+        assert_eq!(repo.find_many(false).unwrap(), vec![]);
+        let dead_letter = repo.find_one_by_id(&dead_letter.id).unwrap().unwrap();
+        assert!(dead_letter.resolved_datetime.is_some());
+        assert!(!dead_letter.retry_requested);
+        assert_eq!(dead_letter.next_retry_datetime, None);
+        assert_eq!(
+            retry_dead_letter(&context, &dead_letter.id),
+            Err(RetryTransferDeadLetterError::DeadLetterAlreadyResolved)
+        );
+
+        // Automatic retries stop after max attempts, retry can still be requested
+        let changelog = ChangelogRow {
+            record_id: "other_invoice_id".to_string(),
+            ..changelog
+        };
+        let max_attempts = transfer_retry_policy().max_attempts.unwrap();
+        for _ in 0..max_attempts {
+            record_transfer_result(&connection, processor_type, &changelog, Err("failed")).unwrap();
+        }
+        let dead_letter = repo.find_many(false).unwrap().pop().unwrap();
+        assert_eq!(dead_letter.attempt_count, max_attempts as i32);
+        assert_eq!(dead_letter.next_retry_datetime, None);
+
+        retry_dead_letter(&context, &dead_letter.id).unwrap();
+        assert_eq!(
+            get_retry_due_changelogs(&connection, processor_type),
+            Ok(vec![changelog])
+        );
+    }
+}
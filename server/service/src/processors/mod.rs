@@ -1,4 +1,6 @@
+use repository::{RepositoryError, TransferProcessorType};
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::oneshot;
@@ -6,28 +8,39 @@ use tokio::task::JoinHandle;
 
 use crate::service_provider::ServiceProvider;
 
+use self::changelog::{changelog_processors, process_changelog_processors};
+use self::dead_letter::get_retry_due_changelogs;
 use self::transfer::invoice::ProcessInvoiceTransfersError;
 use self::transfer::requisition::ProcessRequisitionTransfersError;
 use self::transfer::{
     invoice::process_invoice_transfers, requisition::process_requisition_transfers,
 };
 
+pub mod changelog;
+pub mod dead_letter;
 #[cfg(test)]
 mod test_helpers;
 pub(crate) mod transfer;
 
 const CHANNEL_BUFFER_SIZE: usize = 30;
+/// Changelog processors are also run periodically, to pick up changes that didn't trigger them
+/// and to retry failed records after their back off
+const CHANGELOG_PROCESSORS_INTERVAL: Duration = Duration::from_secs(60);
+/// How often to check for failed transfer records that are due for an automatic retry
+const TRANSFER_RETRY_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Clone)]
 pub struct ProcessorsTrigger {
     requisition_transfer: Sender<()>,
     invoice_transfer: Sender<()>,
+    changelog_processors: Sender<()>,
     await_process_queue: Sender<oneshot::Sender<()>>,
 }
 
 pub struct Processors {
     requisition_transfer: Receiver<()>,
     invoice_transfer: Receiver<()>,
+    changelog_processors: Receiver<()>,
     await_process_queue: Receiver<oneshot::Sender<()>>,
 }
 
@@ -37,6 +50,10 @@ enum ProcessorsError {
     InvoiceTransfer(ProcessInvoiceTransfersError),
     #[error("Error in requisition transfer processor ({0})")]
     RequisitionTransfer(ProcessRequisitionTransfersError),
+    #[error("Error in changelog processors ({0:?})")]
+    ChangelogProcessors(RepositoryError),
+    #[error("Error checking transfer retries ({0:?})")]
+    TransferRetries(RepositoryError),
     #[error("Error when waiting for the process queue to be processed")]
     AwaitProcessQueue(()),
 }
@@ -49,17 +66,22 @@ impl Processors {
         let (invoice_transfer_sender, invoice_transfer_receiver) =
             mpsc::channel(CHANNEL_BUFFER_SIZE);
 
+        let (changelog_processors_sender, changelog_processors_receiver) =
+            mpsc::channel(CHANNEL_BUFFER_SIZE);
+
         let (request_check_sender, request_check_receiver) = mpsc::channel(CHANNEL_BUFFER_SIZE);
 
         (
             ProcessorsTrigger {
                 requisition_transfer: requisition_transfer_sender,
                 invoice_transfer: invoice_transfer_sender,
+                changelog_processors: changelog_processors_sender,
                 await_process_queue: request_check_sender,
             },
             Processors {
                 requisition_transfer: requisition_transfer_receiver,
                 invoice_transfer: invoice_transfer_receiver,
+                changelog_processors: changelog_processors_receiver,
                 await_process_queue: request_check_receiver,
             },
         )
@@ -69,10 +91,17 @@ impl Processors {
         let Processors {
             mut requisition_transfer,
             mut invoice_transfer,
+            changelog_processors: mut changelog_processors_trigger,
             mut await_process_queue,
         } = self;
 
         tokio::spawn(async move {
+            let mut changelog_processors_interval =
+                tokio::time::interval(CHANGELOG_PROCESSORS_INTERVAL);
+            // No need to wake up periodically if there is nothing to process
+            let has_changelog_processors = !changelog_processors().is_empty();
+            let mut transfer_retry_interval = tokio::time::interval(TRANSFER_RETRY_INTERVAL);
+
             loop {
                 // See test below for reasoning behind biased, even though there is no foreseen use case where
                 // requisition must be processed before shipment, it easy to reason about future use cases if
@@ -86,6 +115,16 @@ impl Processors {
                     Some(_) = invoice_transfer.recv() => {
                         process_invoice_transfers(&service_provider).map_err(ProcessorsError::InvoiceTransfer)
                     },
+                    Some(_) = changelog_processors_trigger.recv() => {
+                        process_changelog_processors(&service_provider, &changelog_processors()).map_err(ProcessorsError::ChangelogProcessors)
+                    },
+                    // Stop ticking once the trigger is closed so the task can end with the other channels
+                    _ = changelog_processors_interval.tick(), if has_changelog_processors && !changelog_processors_trigger.is_closed() => {
+                        process_changelog_processors(&service_provider, &changelog_processors()).map_err(ProcessorsError::ChangelogProcessors)
+                    },
+                    _ = transfer_retry_interval.tick(), if !requisition_transfer.is_closed() && !invoice_transfer.is_closed() => {
+                        retry_due_transfers(&service_provider)
+                    },
                     Some(sender) = await_process_queue.recv() => {
                         sender.send(()).map_err(ProcessorsError::AwaitProcessQueue)
                     },
@@ -101,6 +140,28 @@ impl Processors {
     }
 }
 
+/// Runs the transfer processors that have failed records due for an automatic retry
+fn retry_due_transfers(service_provider: &ServiceProvider) -> Result<(), ProcessorsError> {
+    let ctx = service_provider
+        .basic_context()
+        .map_err(ProcessorsError::TransferRetries)?;
+    let is_retry_due = |processor_type| {
+        get_retry_due_changelogs(&ctx.connection, processor_type)
+            .map(|changelogs| !changelogs.is_empty())
+            .map_err(ProcessorsError::TransferRetries)
+    };
+
+    if is_retry_due(TransferProcessorType::RequisitionTransfer)? {
+        process_requisition_transfers(service_provider)
+            .map_err(ProcessorsError::RequisitionTransfer)?;
+    }
+    if is_retry_due(TransferProcessorType::InvoiceTransfer)? {
+        process_invoice_transfers(service_provider).map_err(ProcessorsError::InvoiceTransfer)?;
+    }
+
+    Ok(())
+}
+
 impl ProcessorsTrigger {
     pub(crate) fn trigger_requisition_transfer_processors(&self) {
         if let Err(error) = self.requisition_transfer.try_send(()) {
@@ -117,6 +178,12 @@ impl ProcessorsTrigger {
         }
     }
 
+    pub(crate) fn trigger_changelog_processors(&self) {
+        if let Err(error) = self.changelog_processors.try_send(()) {
+            log::error!("Problem triggering changelog processors {:#?}", error)
+        }
+    }
+
     /// Waits till all current events in the processor queue are handled.
     /// Its guaranteed that all queued processor events that where in the queue before calling
     /// this method are handled when this method returns.
@@ -143,6 +210,7 @@ impl ProcessorsTrigger {
         ProcessorsTrigger {
             requisition_transfer: mpsc::channel(1).0,
             invoice_transfer: mpsc::channel(1).0,
+            changelog_processors: mpsc::channel(1).0,
             await_process_queue: mpsc::channel(1).0,
         }
     }
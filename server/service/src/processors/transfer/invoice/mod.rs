@@ -1,14 +1,17 @@
 use crate::{
     cursor_controller::CursorController,
-    processors::transfer::{
-        get_linked_original_shipment, get_requisition_and_linked_requisition,
-        invoice::{
-            assign_invoice_number::AssignInvoiceNumberProcessor,
-            create_inbound_invoice::CreateInboundInvoiceProcessor,
-            delete_inbound_invoice::DeleteInboundInvoiceProcessor,
-            link_outbound_invoice::LinkOutboundInvoiceProcessor,
-            update_inbound_invoice::UpdateInboundInvoiceProcessor,
-            update_outbound_invoice_status::UpdateOutboundInvoiceStatusProcessor,
+    processors::{
+        dead_letter::{get_retry_due_changelogs, record_transfer_result},
+        transfer::{
+            get_linked_original_shipment, get_requisition_and_linked_requisition,
+            invoice::{
+                assign_invoice_number::AssignInvoiceNumberProcessor,
+                create_inbound_invoice::CreateInboundInvoiceProcessor,
+                delete_inbound_invoice::DeleteInboundInvoiceProcessor,
+                link_outbound_invoice::LinkOutboundInvoiceProcessor,
+                update_inbound_invoice::UpdateInboundInvoiceProcessor,
+                update_outbound_invoice_status::UpdateOutboundInvoiceStatusProcessor,
+            },
         },
     },
     service_provider::ServiceProvider,
@@ -17,7 +20,7 @@ use crate::{
 use repository::{
     ChangelogFilter, ChangelogRepository, ChangelogRow, ChangelogTableName, EqualFilter, Invoice,
     InvoiceFilter, InvoiceRepository, KeyType, RepositoryError, Requisition, RowActionType,
-    StorageConnection,
+    StorageConnection, TransferProcessorType,
 };
 use thiserror::Error;
 
@@ -121,6 +124,21 @@ pub(crate) fn process_invoice_transfers(
         .table_name(ChangelogTableName::Invoice.equal_to())
         .name_id(EqualFilter::equal_any(active_stores.name_ids().clone()));
 
+    // Previously failed records that are due to be retried (automatically or on request)
+    let retry_logs =
+        get_retry_due_changelogs(&ctx.connection, TransferProcessorType::InvoiceTransfer)
+            .map_err(Error::DatabaseError)?;
+    for log in retry_logs {
+        let result = process_invoice_transfer(&ctx.connection, &active_stores, &processors, &log);
+        record_transfer_result(
+            &ctx.connection,
+            TransferProcessorType::InvoiceTransfer,
+            &log,
+            result,
+        )
+        .map_err(Error::DatabaseError)?;
+    }
+
     loop {
         let cursor = cursor_controller
             .get(&ctx.connection)
@@ -135,34 +153,16 @@ pub(crate) fn process_invoice_transfers(
         }
 
         for log in logs {
-            let name_id = log
-                .name_id
-                .as_ref()
-                .ok_or_else(|| Error::NameIdIsMissingFromChangelog(log.clone()))?;
-
-            // Prepare record
-            let operation = match &log.row_action {
-                RowActionType::Upsert => get_upsert_operation(&ctx.connection, &log)
-                    .map_err(Error::GetUpsertOperationError)?,
-                RowActionType::Delete => get_delete_operation(&ctx.connection, &log)
-                    .map_err(Error::GetDeleteOperationError)?,
-            };
-
-            let record = InvoiceTransferProcessorRecord {
-                operation,
-                other_party_store_id: active_stores
-                    .get_store_id_for_name_id(name_id)
-                    .ok_or_else(|| Error::NameIsNotAnActiveStore(log.clone()))?,
-            };
-
-            // TODO: MERGE: Ignore if invoice name_link_id points to store's name. Supplying to itself! (Can happen with names are merge into stores)
-
-            // Try record against all of the processors
-            for processor in processors.iter() {
-                processor
-                    .try_process_record_common(&ctx.connection, &record)
-                    .map_err(Error::ProcessorError)?;
-            }
+            // A record that fails is kept as a dead letter rather than blocking the records after it
+            let result =
+                process_invoice_transfer(&ctx.connection, &active_stores, &processors, &log);
+            record_transfer_result(
+                &ctx.connection,
+                TransferProcessorType::InvoiceTransfer,
+                &log,
+                result,
+            )
+            .map_err(Error::DatabaseError)?;
 
             cursor_controller
                 .update(&ctx.connection, (log.cursor + 1) as u64)
@@ -173,6 +173,48 @@ pub(crate) fn process_invoice_transfers(
     Ok(())
 }
 
+fn process_invoice_transfer(
+    connection: &StorageConnection,
+    active_stores: &ActiveStoresOnSite,
+    processors: &[Box<dyn InvoiceTransferProcessor>],
+    log: &ChangelogRow,
+) -> Result<(), ProcessInvoiceTransfersError> {
+    use ProcessInvoiceTransfersError as Error;
+
+    let name_id = log
+        .name_id
+        .as_ref()
+        .ok_or_else(|| Error::NameIdIsMissingFromChangelog(log.clone()))?;
+
+    // Prepare record
+    let operation = match &log.row_action {
+        RowActionType::Upsert => {
+            get_upsert_operation(connection, log).map_err(Error::GetUpsertOperationError)?
+        }
+        RowActionType::Delete => {
+            get_delete_operation(connection, log).map_err(Error::GetDeleteOperationError)?
+        }
+    };
+
+    let record = InvoiceTransferProcessorRecord {
+        operation,
+        other_party_store_id: active_stores
+            .get_store_id_for_name_id(name_id)
+            .ok_or_else(|| Error::NameIsNotAnActiveStore(log.clone()))?,
+    };
+
+    // TODO: MERGE: Ignore if invoice name_link_id points to store's name. Supplying to itself! (Can happen with names are merge into stores)
+
+    // Try record against all of the processors
+    for processor in processors.iter() {
+        processor
+            .try_process_record_common(connection, &record)
+            .map_err(Error::ProcessorError)?;
+    }
+
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 pub(crate) enum GetUpsertOperationError {
     #[error("Invoice not found {0:?}")]
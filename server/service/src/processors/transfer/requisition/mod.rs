@@ -10,20 +10,23 @@ pub(crate) mod test;
 
 use repository::{
     ChangelogFilter, ChangelogRepository, ChangelogRow, ChangelogTableName, EqualFilter, KeyType,
-    RepositoryError, Requisition, RowActionType, StorageConnection,
+    RepositoryError, Requisition, RowActionType, StorageConnection, TransferProcessorType,
 };
 use thiserror::Error;
 
 use crate::{
     cursor_controller::CursorController,
-    processors::transfer::{
-        get_requisition_and_linked_requisition,
-        requisition::{
-            assign_requisition_number::AssignRequisitionNumberProcessor,
-            create_response_requisition::CreateResponseRequisitionProcessor,
-            link_request_requisition::LinkRequestRequisitionProcessor,
-            update_request_requisition_approved_quantities::UpdateRequestRequisitionApprovedQuantitiesProcessor,
-            update_request_requisition_status::UpdateRequestRequisitionStatusProcessor,
+    processors::{
+        dead_letter::{get_retry_due_changelogs, record_transfer_result},
+        transfer::{
+            get_requisition_and_linked_requisition,
+            requisition::{
+                assign_requisition_number::AssignRequisitionNumberProcessor,
+                create_response_requisition::CreateResponseRequisitionProcessor,
+                link_request_requisition::LinkRequestRequisitionProcessor,
+                update_request_requisition_approved_quantities::UpdateRequestRequisitionApprovedQuantitiesProcessor,
+                update_request_requisition_status::UpdateRequestRequisitionStatusProcessor,
+            },
         },
     },
     service_provider::ServiceProvider,
@@ -88,6 +91,22 @@ pub(crate) fn process_requisition_transfers(
         // Filter out deletes
         .action(RowActionType::Upsert.equal_to());
 
+    // Previously failed records that are due to be retried (automatically or on request)
+    let retry_logs =
+        get_retry_due_changelogs(&ctx.connection, TransferProcessorType::RequisitionTransfer)
+            .map_err(Error::DatabaseError)?;
+    for log in retry_logs {
+        let result =
+            process_requisition_transfer(&ctx.connection, &active_stores, &processors, &log);
+        record_transfer_result(
+            &ctx.connection,
+            TransferProcessorType::RequisitionTransfer,
+            &log,
+            result,
+        )
+        .map_err(Error::DatabaseError)?;
+    }
+
     loop {
         let cursor = cursor_controller
             .get(&ctx.connection)
@@ -102,34 +121,16 @@ pub(crate) fn process_requisition_transfers(
         }
 
         for log in logs {
-            let name_id = log
-                .name_id
-                .as_ref()
-                .ok_or_else(|| Error::NameIdIsMissingFromChangelog(log.clone()))?;
-
-            // Prepare record
-            let (requisition, linked_requisition) = match &log.row_action {
-                RowActionType::Upsert => {
-                    get_requisition_and_linked_requisition(&ctx.connection, &log.record_id)
-                        .map_err(Error::GetRequisitionAndLinkedRequisitionError)?
-                }
-                RowActionType::Delete => continue,
-            };
-
-            let record = RequisitionTransferProcessorRecord {
-                requisition,
-                linked_requisition,
-                other_party_store_id: active_stores
-                    .get_store_id_for_name_id(name_id)
-                    .ok_or_else(|| Error::NameIsNotAnActiveStore(log.clone()))?,
-            };
-
-            // Try record against all of the processors
-            for processor in processors.iter() {
-                processor
-                    .try_process_record_common(&ctx.connection, &record)
-                    .map_err(Error::ProcessorError)?;
-            }
+            // A record that fails is kept as a dead letter rather than blocking the records after it
+            let result =
+                process_requisition_transfer(&ctx.connection, &active_stores, &processors, &log);
+            record_transfer_result(
+                &ctx.connection,
+                TransferProcessorType::RequisitionTransfer,
+                &log,
+                result,
+            )
+            .map_err(Error::DatabaseError)?;
 
             cursor_controller
                 .update(&ctx.connection, (log.cursor + 1) as u64)
@@ -140,6 +141,44 @@ pub(crate) fn process_requisition_transfers(
     Ok(())
 }
 
+fn process_requisition_transfer(
+    connection: &StorageConnection,
+    active_stores: &ActiveStoresOnSite,
+    processors: &[Box<dyn RequisitionTransferProcessor>],
+    log: &ChangelogRow,
+) -> Result<(), ProcessRequisitionTransfersError> {
+    use ProcessRequisitionTransfersError as Error;
+
+    let name_id = log
+        .name_id
+        .as_ref()
+        .ok_or_else(|| Error::NameIdIsMissingFromChangelog(log.clone()))?;
+
+    // Prepare record
+    let (requisition, linked_requisition) = match &log.row_action {
+        RowActionType::Upsert => get_requisition_and_linked_requisition(connection, &log.record_id)
+            .map_err(Error::GetRequisitionAndLinkedRequisitionError)?,
+        RowActionType::Delete => return Ok(()),
+    };
+
+    let record = RequisitionTransferProcessorRecord {
+        requisition,
+        linked_requisition,
+        other_party_store_id: active_stores
+            .get_store_id_for_name_id(name_id)
+            .ok_or_else(|| Error::NameIsNotAnActiveStore(log.clone()))?,
+    };
+
+    // Try record against all of the processors
+    for processor in processors.iter() {
+        processor
+            .try_process_record_common(connection, &record)
+            .map_err(Error::ProcessorError)?;
+    }
+
+    Ok(())
+}
+
 #[derive(Error, Debug)]
 #[error("Database error in processor ({0}) {1:?}")]
 pub(crate) struct ProcessorError(String, RepositoryError);
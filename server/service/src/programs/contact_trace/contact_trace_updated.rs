@@ -11,6 +11,8 @@ use repository::{
 use std::str::FromStr;
 use util::hash::sha256;
 
+use crate::document::document_names;
+
 /// Callback called when a program enrolment document has been updated
 pub(crate) fn update_contact_trace_row(
     con: &StorageConnection,
@@ -28,11 +30,12 @@ pub(crate) fn update_contact_trace_row(
         })?
         .naive_utc();
 
-    // Retrieve existing or create now contact trace id
+    // Retrieve existing or create now contact trace id, the contact trace of a renamed document is
+    // found by the name of its previous version
     let repo = ContactTraceRepository::new(con);
     let contact_trace_row = repo
         .query_by_filter(ContactTraceFilter {
-            document_name: Some(StringFilter::equal_to(&document.name)),
+            document_name: Some(StringFilter::equal_any(document_names(con, document)?)),
             ..ContactTraceFilter::default()
         })?
         .pop();
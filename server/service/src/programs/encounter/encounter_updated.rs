@@ -5,9 +5,12 @@ use repository::{
 };
 use util::hash::sha256;
 
-use crate::programs::{
-    program_event::{ProgramEventService, ProgramEventServiceTrait},
-    update_program_document::{update_program_events, UpdateProgramDocumentError},
+use crate::{
+    document::document_names,
+    programs::{
+        program_event::{ProgramEventService, ProgramEventServiceTrait},
+        update_program_document::{update_program_events, UpdateProgramDocumentError},
+    },
 };
 
 use super::{
@@ -86,8 +89,11 @@ fn update_encounter_row(
         });
 
     let repo = EncounterRepository::new(con);
+    // Encounter of a renamed document is found by the name of its previous version
     let encounter = repo
-        .query_by_filter(EncounterFilter::new().document_name(EqualFilter::equal_to(&doc.name)))?
+        .query_by_filter(
+            EncounterFilter::new().document_name(EqualFilter::equal_any(document_names(con, doc)?)),
+        )?
         .pop();
     // Documents are identified by a human readable name. Thus, use hash(name) as an ID.
     // For example, an ID works better in an web URL.
@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+
+use repository::{Pagination, PaginationOption, PatientFilter, PatientRepository};
+
+use crate::{get_default_pagination, service_provider::ServiceContext, ListError, ListResult};
+
+use super::{
+    matching::{normalise, patient_match_score, soundex},
+    Patient, PatientSearch,
+};
+
+/// Default minimum score of a pair of patients to be reported as possible duplicates
+pub const DEFAULT_DUPLICATE_MIN_SCORE: f64 = 0.8;
+/// Patients sharing a blocking key are only compared if there are at most this many of them,
+/// e.g. very common last names would otherwise compare a large part of all patients
+pub const MAX_DUPLICATE_BLOCK_SIZE: usize = 200;
+
+const MAX_LIMIT: u32 = 1000;
+const MIN_LIMIT: u32 = 1;
+
+pub struct DuplicatePatientCandidate {
+    pub patient: Patient,
+    pub duplicate: Patient,
+    /// How similar the patients are, between 0.0 and 1.0
+    pub score: f64,
+}
+
+/// Pairs of patients that are likely the same person, best matches first.
+/// Only patients sharing a blocking key (sound of the last name, date of birth or national health
+/// number) are compared, to avoid comparing every patient with every other patient. Blocks with
+/// more than MAX_DUPLICATE_BLOCK_SIZE patients are skipped
+pub fn duplicate_patient_candidates(
+    ctx: &ServiceContext,
+    pagination: Option<PaginationOption>,
+    min_score: Option<f64>,
+    allowed_ctx: Option<&[String]>,
+) -> Result<ListResult<DuplicatePatientCandidate>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let min_score = min_score.unwrap_or(DEFAULT_DUPLICATE_MIN_SCORE);
+    let patients = PatientRepository::new(&ctx.connection).query(
+        Pagination::all(),
+        Some(PatientFilter::new()),
+        None,
+        allowed_ctx,
+    )?;
+
+    let mut blocks: HashMap<String, Vec<usize>> = HashMap::new();
+    for (index, patient) in patients.iter().enumerate() {
+        for key in blocking_keys(patient) {
+            blocks.entry(key).or_default().push(index);
+        }
+    }
+
+    let mut compared: HashSet<(usize, usize)> = HashSet::new();
+    let mut candidates = Vec::new();
+    for indexes in blocks
+        .values()
+        .filter(|indexes| indexes.len() <= MAX_DUPLICATE_BLOCK_SIZE)
+    {
+        for (position, &a) in indexes.iter().enumerate() {
+            for &b in &indexes[position + 1..] {
+                if !compared.insert((a.min(b), a.max(b))) {
+                    continue;
+                }
+                let score = patient_pair_score(&patients[a], &patients[b]);
+                if score >= min_score {
+                    candidates.push((a, b, score));
+                }
+            }
+        }
+    }
+
+    candidates.sort_by(|(_, _, a), (_, _, b)| b.total_cmp(a));
+    let count = candidates.len() as u32;
+    let rows = candidates
+        .into_iter()
+        .skip(pagination.offset as usize)
+        .take(pagination.limit as usize)
+        .map(|(a, b, score)| DuplicatePatientCandidate {
+            patient: patients[a].clone(),
+            duplicate: patients[b].clone(),
+            score,
+        })
+        .collect();
+
+    Ok(ListResult { rows, count })
+}
+
+fn blocking_keys(patient: &Patient) -> Vec<String> {
+    let mut keys = Vec::new();
+    if let Some(last_name) = patient.last_name.as_deref().and_then(soundex) {
+        keys.push(format!("last_name:{last_name}"));
+    }
+    if let Some(date_of_birth) = &patient.date_of_birth {
+        keys.push(format!("date_of_birth:{date_of_birth}"));
+    }
+    if let Some(nhn) = patient.national_health_number.as_deref().map(normalise) {
+        if !nhn.is_empty() {
+            keys.push(format!("nhn:{nhn}"));
+        }
+    }
+    keys
+}
+
+/// Scores the second patient against the details of the first patient, patient codes are unique
+/// and aren't compared
+fn patient_pair_score(a: &Patient, b: &Patient) -> f64 {
+    let search = PatientSearch {
+        code: None,
+        code_2: a.national_health_number.clone(),
+        first_name: a.first_name.clone(),
+        last_name: a.last_name.clone(),
+        date_of_birth: a.date_of_birth,
+        gender: a.gender.clone(),
+        identifier: None,
+    };
+    patient_match_score(&search, b, false)
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, GenderType, NameRow, NameRowRepository,
+        NameRowType,
+    };
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn duplicate_patients() {
+        let (_, connection, connection_manager, _) =
+            setup_all("duplicate_patients", MockDataInserts::none()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+
+        let patient = |id: &str, first_name: &str, last_name: &str, date_of_birth: &str| NameRow {
+            id: id.to_string(),
+            r#type: NameRowType::Patient,
+            code: id.to_string(),
+            first_name: Some(first_name.to_string()),
+            last_name: Some(last_name.to_string()),
+            date_of_birth: Some(date_of_birth.parse().unwrap()),
+            gender: Some(GenderType::Female),
+            ..Default::default()
+        };
+        let name_repo = NameRowRepository::new(&connection);
+        for row in [
+            patient("mary_1", "Mary", "Tembo", "1985-06-01"),
+            patient("mary_2", "Marie", "Tembo", "1985-01-06"),
+            patient("grace", "Grace", "Tembo", "2001-11-20"),
+            patient("ruth", "Ruth", "Banda", "1985-06-01"),
+        ] {
+            name_repo.upsert_one(&row).unwrap();
+        }
+
+        let result = duplicate_patient_candidates(&context, None, None, None).unwrap();
+        assert_eq!(result.count, 1);
+        let mut ids = vec![
+            result.rows[0].patient.id.clone(),
+            result.rows[0].duplicate.id.clone(),
+        ];
+        ids.sort();
+        assert_eq!(ids, vec!["mary_1", "mary_2"]);
+        assert!(result.rows[0].score >= DEFAULT_DUPLICATE_MIN_SCORE);
+
+        // Lower min score includes less similar patients
+        let result = duplicate_patient_candidates(&context, None, Some(0.5), None).unwrap();
+        assert!(result.count > 1);
+
+        // Paginated, best matches first
+        let page = duplicate_patient_candidates(
+            &context,
+            Some(PaginationOption {
+                limit: Some(1),
+                offset: Some(1),
+            }),
+            Some(0.5),
+            None,
+        )
+        .unwrap();
+        assert_eq!(page.count, result.count);
+        assert_eq!(page.rows.len(), 1);
+        assert_eq!(page.rows[0].score, result.rows[1].score);
+
+        let result = duplicate_patient_candidates(
+            &context,
+            Some(PaginationOption {
+                limit: Some(MAX_LIMIT + 1),
+                offset: None,
+            }),
+            None,
+            None,
+        );
+        assert!(matches!(result, Err(ListError::LimitAboveMax(_))));
+    }
+
+    #[actix_rt::test]
+    async fn duplicate_patients_oversized_block() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "duplicate_patients_oversized_block",
+            MockDataInserts::none(),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+
+        // Same last name and date of birth, only the national health number block is small enough
+        let name_repo = NameRowRepository::new(&connection);
+        for index in 0..=MAX_DUPLICATE_BLOCK_SIZE {
+            name_repo
+                .upsert_one(&NameRow {
+                    id: format!("patient_{index}"),
+                    r#type: NameRowType::Patient,
+                    code: format!("patient_{index}"),
+                    first_name: Some("Mary".to_string()),
+                    last_name: Some("Tembo".to_string()),
+                    date_of_birth: Some("1985-06-01".parse().unwrap()),
+                    national_health_number: Some(match index {
+                        0 | 1 => "NHN-1".to_string(),
+                        _ => format!("NHN-{index}-X"),
+                    }),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+
+        let result = duplicate_patient_candidates(&context, None, None, None).unwrap();
+        assert_eq!(result.count, 1);
+        let mut ids = vec![
+            result.rows[0].patient.id.clone(),
+            result.rows[0].duplicate.id.clone(),
+        ];
+        ids.sort();
+        assert_eq!(ids, vec!["patient_0", "patient_1"]);
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use repository::{
+    EqualFilter, NameRow, NameRowRepository, NameRowType, RepositoryError, StoreFilter,
+    StoreRepository,
+};
+use tera::escape_html;
+
+use crate::{
+    report::qr_code::qr_code_svg,
+    service_provider::ServiceContext,
+    static_files::{StaticFileCategory, StaticFileService},
+};
+
+#[derive(PartialEq, Debug)]
+pub enum PrintPatientIdCardError {
+    PatientDoesNotExist,
+    StoreDoesNotExist,
+    FileError(String),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for PrintPatientIdCardError {
+    fn from(error: RepositoryError) -> Self {
+        PrintPatientIdCardError::DatabaseError(error)
+    }
+}
+
+/// Generates a printable ID card for the patient with a QR code of the patient code, returns the
+/// id of the (temporary) HTML file
+pub(crate) fn print_patient_id_card(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    store_id: &str,
+    patient_id: &str,
+) -> Result<String, PrintPatientIdCardError> {
+    let patient = NameRowRepository::new(&ctx.connection)
+        .find_one_by_id(patient_id)?
+        .filter(|name| name.r#type == NameRowType::Patient)
+        .ok_or(PrintPatientIdCardError::PatientDoesNotExist)?;
+    let store = StoreRepository::new(&ctx.connection)
+        .query_one(StoreFilter::new().id(EqualFilter::equal_to(store_id)))?
+        .ok_or(PrintPatientIdCardError::StoreDoesNotExist)?;
+
+    let html = patient_id_card_html(&patient, &store.name_row.name);
+
+    let file_service = StaticFileService::new(base_dir)
+        .map_err(|err| PrintPatientIdCardError::FileError(format!("{}", err)))?;
+    let now: DateTime<Utc> = SystemTime::now().into();
+    let file = file_service
+        .store_file(
+            &format!("{}_patient_id_card.html", now.format("%Y%m%d_%H%M%S")),
+            StaticFileCategory::Temporary,
+            html.as_bytes(),
+        )
+        .map_err(|err| PrintPatientIdCardError::FileError(format!("{}", err)))?;
+    Ok(file.id)
+}
+
+fn patient_id_card_html(patient: &NameRow, store_name: &str) -> String {
+    let date_of_birth = patient
+        .date_of_birth
+        .map(|date| date.format("%d/%m/%Y").to_string())
+        .unwrap_or_default();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<style>
+  @page {{ size: 85.6mm 54mm; margin: 0; }}
+  body {{ margin: 0; font-family: sans-serif; }}
+  .card {{ width: 85.6mm; height: 54mm; box-sizing: border-box; padding: 4mm; display: flex; }}
+  .details {{ flex: 1; }}
+  .store {{ font-size: 9pt; color: #555555; }}
+  .name {{ font-size: 13pt; font-weight: bold; margin-top: 3mm; }}
+  .code {{ font-size: 12pt; font-family: monospace; margin-top: 2mm; }}
+  .date-of-birth {{ font-size: 9pt; margin-top: 2mm; }}
+  .qr-code svg {{ width: 30mm; height: 30mm; }}
+</style>
+</head>
+<body>
+<div class="card">
+  <div class="details">
+    <div class="store">{store_name}</div>
+    <div class="name">{name}</div>
+    <div class="code">{code}</div>
+    <div class="date-of-birth">{date_of_birth}</div>
+  </div>
+  <div class="qr-code">{qr_code}</div>
+</div>
+</body>
+</html>"#,
+        store_name = escape_html(store_name),
+        name = escape_html(&patient.name),
+        code = escape_html(&patient.code),
+        date_of_birth = date_of_birth,
+        qr_code = qr_code_svg(&patient.code),
+    )
+}
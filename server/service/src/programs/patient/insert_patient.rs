@@ -6,7 +6,11 @@ use repository::{
 
 use crate::service_provider::{ServiceContext, ServiceProvider};
 
-use super::patient_updated::{create_patient_name_store_join, patient_name};
+use super::{
+    patient_code::{generate_patient_code, is_valid_patient_code, GeneratePatientCodeError},
+    patient_updated::{create_patient_name_store_join, patient_name},
+};
+use crate::store_preference::get_store_preferences;
 
 #[derive(Default)]
 pub struct InsertPatient {
@@ -28,6 +32,9 @@ pub struct InsertPatient {
 pub enum InsertPatientError {
     PatientExists,
     NotAPatient,
+    /// Code doesn't match the store's patient code scheme, e.g. the check digit is wrong
+    InvalidPatientCode,
+    InvalidPatientCodePrefix(String),
     InternalError(String),
     DatabaseError(RepositoryError),
 }
@@ -40,7 +47,11 @@ fn validate_patient_does_not_exist(
     Ok(existing.is_none())
 }
 
-fn validate(con: &StorageConnection, input: &InsertPatient) -> Result<(), InsertPatientError> {
+fn validate(
+    con: &StorageConnection,
+    store_id: &str,
+    input: &InsertPatient,
+) -> Result<(), InsertPatientError> {
     if input.r#type != NameRowType::Patient {
         return Err(InsertPatientError::NotAPatient);
     }
@@ -48,6 +59,12 @@ fn validate(con: &StorageConnection, input: &InsertPatient) -> Result<(), Insert
     if !validate_patient_does_not_exist(con, input)? {
         return Err(InsertPatientError::PatientExists);
     }
+
+    // An empty code is generated from the store's patient code scheme
+    let scheme = get_store_preferences(con, store_id)?.patient_code_scheme;
+    if !input.code.is_empty() && !is_valid_patient_code(scheme, &input.code) {
+        return Err(InsertPatientError::InvalidPatientCode);
+    }
     Ok(())
 }
 
@@ -98,7 +115,13 @@ pub(crate) fn insert_patient(
     let patient = ctx
         .connection
         .transaction_sync(|con| {
-            validate(con, &input)?;
+            validate(con, store_id, &input)?;
+            let mut input = input;
+            if input.code.is_empty() {
+                if let Some(code) = generate_patient_code(con, store_id)? {
+                    input.code = code;
+                }
+            }
             let row = generate(input, store_id);
 
             let name_repo = NameRowRepository::new(con);
@@ -131,3 +154,17 @@ impl From<RepositoryError> for InsertPatientError {
         InsertPatientError::DatabaseError(err)
     }
 }
+
+impl From<GeneratePatientCodeError> for InsertPatientError {
+    fn from(err: GeneratePatientCodeError) -> Self {
+        match err {
+            GeneratePatientCodeError::StoreDoesNotExist => {
+                InsertPatientError::InternalError("Store does not exist".to_string())
+            }
+            GeneratePatientCodeError::InvalidPrefix(prefix) => {
+                InsertPatientError::InvalidPatientCodePrefix(prefix)
+            }
+            GeneratePatientCodeError::DatabaseError(err) => InsertPatientError::DatabaseError(err),
+        }
+    }
+}
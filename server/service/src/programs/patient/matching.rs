@@ -0,0 +1,319 @@
+use chrono::NaiveDate;
+use repository::{GenderType, Patient};
+
+use super::PatientSearch;
+
+// Weights of the compared fields, identifiers are (nearly) unique and weigh more than names
+const CODE_WEIGHT: f64 = 5.0;
+const CODE_2_WEIGHT: f64 = 5.0;
+const IDENTIFIER_WEIGHT: f64 = 4.0;
+const LAST_NAME_WEIGHT: f64 = 3.0;
+const FIRST_NAME_WEIGHT: f64 = 2.0;
+const DATE_OF_BIRTH_WEIGHT: f64 = 3.0;
+const GENDER_WEIGHT: f64 = 1.0;
+
+/// Similarity used when the patient doesn't have a value for a searched field
+const UNKNOWN_SIMILARITY: f64 = 0.5;
+/// Similarity of names that sound the same (same soundex code) but are spelled differently
+const PHONETIC_SIMILARITY: f64 = 0.9;
+/// First and last name entered the wrong way around
+const SWAPPED_NAMES_FACTOR: f64 = 0.9;
+/// Identifiers less similar than this are considered different (rather than a typo)
+const MIN_IDENTIFIER_SIMILARITY: f64 = 0.75;
+/// Date of birth similarity drops from 1.0 to 0.5 over this many days, and to 0.0 beyond
+pub(crate) const DATE_OF_BIRTH_TOLERANCE_DAYS: i64 = 365;
+/// Day and month entered the wrong way around
+const TRANSPOSED_DATE_OF_BIRTH_SIMILARITY: f64 = 0.8;
+
+/// Lower case ASCII letters and digits only, e.g. "O'Brien-Smith" -> "obriensmith"
+pub(crate) fn normalise(value: &str) -> String {
+    value
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// American soundex code, e.g. "Robert" and "Rupert" are both "R163"
+pub(crate) fn soundex(value: &str) -> Option<String> {
+    let code = |c: char| match c {
+        'b' | 'f' | 'p' | 'v' => Some('1'),
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+        'd' | 't' => Some('3'),
+        'l' => Some('4'),
+        'm' | 'n' => Some('5'),
+        'r' => Some('6'),
+        _ => None,
+    };
+
+    let letters: Vec<char> = normalise(value)
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .collect();
+    let first = *letters.first()?;
+
+    let mut result = first.to_ascii_uppercase().to_string();
+    let mut previous = code(first);
+    for &letter in &letters[1..] {
+        let current = code(letter);
+        if current.is_some() && current != previous {
+            result.extend(current);
+        }
+        // 'h' and 'w' don't separate letters with the same code, vowels do
+        if letter != 'h' && letter != 'w' {
+            previous = current;
+        }
+        if result.len() == 4 {
+            break;
+        }
+    }
+
+    Some(format!("{result:0<4}"))
+}
+
+/// 1.0 for the same name, close to 1.0 for typos (Jaro-Winkler) and names that sound the same
+pub(crate) fn name_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalise(a), normalise(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+
+    let phonetic = if soundex(&a) == soundex(&b) {
+        PHONETIC_SIMILARITY
+    } else {
+        0.0
+    };
+
+    strsim::jaro_winkler(&a, &b).max(phonetic)
+}
+
+/// 1.0 for the same identifier (ignoring case and punctuation), identifiers with a typo (e.g. two
+/// digits swapped) are similar, otherwise 0.0
+pub(crate) fn identifier_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalise(a), normalise(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let similarity = strsim::normalized_damerau_levenshtein(&a, &b);
+    if similarity < MIN_IDENTIFIER_SIMILARITY {
+        return 0.0;
+    }
+    similarity
+}
+
+pub(crate) fn date_of_birth_similarity(a: &NaiveDate, b: &NaiveDate) -> f64 {
+    use chrono::Datelike;
+
+    let days = (*a - *b).num_days().abs();
+    let within_tolerance = if days <= DATE_OF_BIRTH_TOLERANCE_DAYS {
+        1.0 - 0.5 * days as f64 / DATE_OF_BIRTH_TOLERANCE_DAYS as f64
+    } else {
+        0.0
+    };
+
+    let transposed = a.year() == b.year() && a.day() == b.month() && a.month() == b.day();
+    if days > 0 && transposed {
+        return within_tolerance.max(TRANSPOSED_DATE_OF_BIRTH_SIMILARITY);
+    }
+
+    within_tolerance
+}
+
+fn gender_similarity(a: &GenderType, b: &GenderType) -> f64 {
+    match (a, b) {
+        (GenderType::Unknown, _) | (_, GenderType::Unknown) => UNKNOWN_SIMILARITY,
+        (a, b) if a == b => 1.0,
+        _ => 0.0,
+    }
+}
+
+/// Weighted average of the similarity of the searched fields, between 0.0 and 1.0.
+/// `identifier_matched` is set when the patient was found by the identifier (which can also match
+/// program enrolment ids)
+pub(crate) fn patient_match_score(
+    search: &PatientSearch,
+    patient: &Patient,
+    identifier_matched: bool,
+) -> f64 {
+    let mut weighted_similarity = 0.0;
+    let mut total_weight = 0.0;
+    let mut add = |weight: f64, similarity: Option<f64>| {
+        weighted_similarity += weight * similarity.unwrap_or(UNKNOWN_SIMILARITY);
+        total_weight += weight;
+    };
+
+    if let Some(code) = &search.code {
+        add(
+            CODE_WEIGHT,
+            Some(identifier_similarity(code, &patient.code)),
+        );
+    }
+    if let Some(code_2) = &search.code_2 {
+        add(
+            CODE_2_WEIGHT,
+            patient
+                .national_health_number
+                .as_ref()
+                .map(|nhn| identifier_similarity(code_2, nhn)),
+        );
+    }
+    if let Some(identifier) = &search.identifier {
+        let similarity = [Some(&patient.code), patient.national_health_number.as_ref()]
+            .iter()
+            .flatten()
+            .map(|value| identifier_similarity(identifier, value))
+            .fold(0.0, f64::max);
+        let similarity = if identifier_matched { 1.0 } else { similarity };
+        add(IDENTIFIER_WEIGHT, Some(similarity));
+    }
+
+    let (first_name, last_name) = names_similarity(search, patient);
+    if search.first_name.is_some() {
+        add(FIRST_NAME_WEIGHT, first_name);
+    }
+    if search.last_name.is_some() {
+        add(LAST_NAME_WEIGHT, last_name);
+    }
+
+    if let Some(date_of_birth) = &search.date_of_birth {
+        add(
+            DATE_OF_BIRTH_WEIGHT,
+            patient.date_of_birth.as_ref().map(|patient_date_of_birth| {
+                date_of_birth_similarity(date_of_birth, patient_date_of_birth)
+            }),
+        );
+    }
+    if let Some(gender) = &search.gender {
+        add(
+            GENDER_WEIGHT,
+            patient
+                .gender
+                .as_ref()
+                .map(|patient_gender| gender_similarity(gender, patient_gender)),
+        );
+    }
+
+    if total_weight == 0.0 {
+        return 1.0;
+    }
+    weighted_similarity / total_weight
+}
+
+/// Similarity of first and last name, also compares the names the other way around in case
+/// they have been swapped
+fn names_similarity(search: &PatientSearch, patient: &Patient) -> (Option<f64>, Option<f64>) {
+    let similarity = |a: &Option<String>, b: &Option<String>| match (a, b) {
+        (Some(a), Some(b)) => Some(name_similarity(a, b)),
+        _ => None,
+    };
+
+    let first_name = similarity(&search.first_name, &patient.first_name);
+    let last_name = similarity(&search.last_name, &patient.last_name);
+
+    let swapped_first_name = similarity(&search.first_name, &patient.last_name);
+    let swapped_last_name = similarity(&search.last_name, &patient.first_name);
+
+    let total = |a: Option<f64>, b: Option<f64>| a.unwrap_or(0.0) + b.unwrap_or(0.0);
+    if total(swapped_first_name, swapped_last_name) * SWAPPED_NAMES_FACTOR
+        > total(first_name, last_name)
+    {
+        return (
+            swapped_first_name.map(|s| s * SWAPPED_NAMES_FACTOR),
+            swapped_last_name.map(|s| s * SWAPPED_NAMES_FACTOR),
+        );
+    }
+
+    (first_name, last_name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_soundex() {
+        assert_eq!(soundex("Robert"), Some("R163".to_string()));
+        assert_eq!(soundex("Rupert"), Some("R163".to_string()));
+        assert_eq!(soundex("Ashcraft"), Some("A261".to_string()));
+        assert_eq!(soundex("Tymczak"), Some("T522".to_string()));
+        assert_eq!(soundex("Pfister"), Some("P236".to_string()));
+        assert_eq!(soundex("Lee"), Some("L000".to_string()));
+        assert_eq!(soundex("O'Hara"), Some("O600".to_string()));
+        assert_eq!(soundex("123"), None);
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(name_similarity("Mary-Jane", "maryjane"), 1.0);
+        assert!(name_similarity("Catherine", "Cathrine") > 0.9);
+        assert_eq!(name_similarity("Smith", "Smyth"), PHONETIC_SIMILARITY);
+        assert!(name_similarity("Smith", "Jones") < 0.5);
+
+        assert_eq!(identifier_similarity("nhn-123", "NHN123"), 1.0);
+        assert!(identifier_similarity("NHN1234", "NHN1243") > 0.8);
+        assert_eq!(identifier_similarity("NHN1234", "XYZ9876"), 0.0);
+
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            date_of_birth_similarity(&date(1990, 3, 4), &date(1990, 3, 4)),
+            1.0
+        );
+        assert_eq!(
+            date_of_birth_similarity(&date(1990, 1, 12), &date(1990, 12, 1)),
+            TRANSPOSED_DATE_OF_BIRTH_SIMILARITY
+        );
+        let half_year = date_of_birth_similarity(&date(1990, 1, 1), &date(1990, 7, 2));
+        assert!((half_year - 0.75).abs() < 0.01);
+        assert_eq!(
+            date_of_birth_similarity(&date(1990, 1, 1), &date(1992, 1, 1)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_patient_match_score() {
+        let patient = Patient {
+            id: "patient".to_string(),
+            code: "P001".to_string(),
+            national_health_number: Some("NHN1234".to_string()),
+            first_name: Some("Catherine".to_string()),
+            last_name: Some("Smith".to_string()),
+            date_of_birth: NaiveDate::from_ymd_opt(1990, 3, 4),
+            gender: Some(GenderType::Female),
+            ..Default::default()
+        };
+        let search = |first_name: &str, last_name: &str, date_of_birth| PatientSearch {
+            code: None,
+            code_2: None,
+            first_name: Some(first_name.to_string()),
+            last_name: Some(last_name.to_string()),
+            date_of_birth,
+            gender: None,
+            identifier: None,
+        };
+
+        let exact = search("Catherine", "Smith", NaiveDate::from_ymd_opt(1990, 3, 4));
+        assert_eq!(patient_match_score(&exact, &patient, false), 1.0);
+
+        let misspelled = search("Katherine", "Smyth", NaiveDate::from_ymd_opt(1990, 4, 3));
+        let misspelled_score = patient_match_score(&misspelled, &patient, false);
+        assert!(misspelled_score > 0.8 && misspelled_score < 1.0);
+
+        let swapped = search("Smith", "Catherine", NaiveDate::from_ymd_opt(1990, 3, 4));
+        assert!(patient_match_score(&swapped, &patient, false) > 0.9);
+
+        let different = search("John", "Jones", NaiveDate::from_ymd_opt(1960, 1, 1));
+        assert!(patient_match_score(&different, &patient, false) < 0.5);
+
+        // Different national health number outweighs similar names
+        let different_nhn = PatientSearch {
+            code_2: Some("NHN9876".to_string()),
+            ..search("Catherine", "Smith", None)
+        };
+        assert!(patient_match_score(&different_nhn, &patient, false) < 0.6);
+    }
+}
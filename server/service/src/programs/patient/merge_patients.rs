@@ -0,0 +1,426 @@
+use chrono::Utc;
+use repository::{
+    Document, DocumentFilter, DocumentRepository, DocumentStatus, EqualFilter, NameLinkRow,
+    NameLinkRowRepository, NameRowRepository, NameRowType, Pagination, Patient, PatientFilter,
+    ProgramEnrolmentFilter, ProgramEnrolmentRepository, RepositoryError, StorageConnection,
+    TransactionError,
+};
+use util::constants::PATIENT_TYPE;
+
+use crate::{
+    document::raw_document::RawDocument,
+    service_provider::{ServiceContext, ServiceProvider},
+    sync::integrate_document::sync_upsert_document,
+};
+
+#[derive(PartialEq, Debug)]
+pub enum MergePatientsError {
+    SurvivingPatientDoesNotExist,
+    MergedPatientDoesNotExist,
+    CannotMergePatientWithItself,
+    /// Both patients are enrolled in the program (program id), one of the enrolments needs to be
+    /// removed first
+    BothPatientsEnrolledInProgram(String),
+    InternalError(String),
+    DatabaseError(RepositoryError),
+}
+
+pub struct MergePatients {
+    pub surviving_patient_id: String,
+    pub merged_patient_id: String,
+}
+
+/// Merges a duplicate patient into the surviving patient:
+/// - documents of the merged patient (and thus their encounters, program enrolments and contact
+///   traces) are re-owned by the surviving patient, see `generate_documents`
+/// - name links of the merged patient point to the surviving patient, other records referencing
+///   the merged patient (e.g. program events, vaccinations, prescriptions) follow
+/// - the merged patient is marked as deleted
+///
+/// Name links are owned by the central server, patients should only be merged on the central
+/// server (see `CentralProgramsMutations`)
+pub fn merge_patients(
+    ctx: &ServiceContext,
+    service_provider: &ServiceProvider,
+    input: MergePatients,
+) -> Result<Patient, MergePatientsError> {
+    let patient = ctx
+        .connection
+        .transaction_sync(|con| {
+            validate(con, &input)?;
+            let MergePatients {
+                surviving_patient_id,
+                merged_patient_id,
+            } = input;
+
+            // Find documents before name links are updated, afterwards they would be returned for
+            // the surviving patient as well
+            let documents = DocumentRepository::new(con).query(
+                Pagination::all(),
+                Some(DocumentFilter::new().owner(EqualFilter::equal_to(&merged_patient_id))),
+                None,
+            )?;
+
+            let name_link_repo = NameLinkRowRepository::new(con);
+            for name_link in name_link_repo.find_many_by_name_id(&merged_patient_id)? {
+                name_link_repo.upsert_one(&NameLinkRow {
+                    name_id: surviving_patient_id.clone(),
+                    ..name_link
+                })?;
+            }
+
+            for document in documents {
+                if document.status == DocumentStatus::Deleted {
+                    continue;
+                }
+                let new_versions = generate_documents(
+                    &ctx.user_id,
+                    &surviving_patient_id,
+                    &merged_patient_id,
+                    document,
+                )
+                .map_err(MergePatientsError::InternalError)?;
+                for new_version in new_versions {
+                    sync_upsert_document(con, &new_version)?;
+                }
+            }
+
+            NameRowRepository::new(con).mark_deleted(&merged_patient_id)?;
+
+            service_provider
+                .patient_service
+                .get_patients(
+                    ctx,
+                    None,
+                    Some(PatientFilter::new().id(EqualFilter::equal_to(&surviving_patient_id))),
+                    None,
+                    None,
+                )?
+                .rows
+                .pop()
+                .ok_or(MergePatientsError::InternalError(
+                    "Can't find the surviving patient".to_string(),
+                ))
+        })
+        .map_err(|err: TransactionError<MergePatientsError>| err.to_inner_error())?;
+    Ok(patient)
+}
+
+fn validate(con: &StorageConnection, input: &MergePatients) -> Result<(), MergePatientsError> {
+    if input.surviving_patient_id == input.merged_patient_id {
+        return Err(MergePatientsError::CannotMergePatientWithItself);
+    }
+
+    let is_active_patient = |id: &str| -> Result<bool, RepositoryError> {
+        Ok(NameRowRepository::new(con)
+            .find_one_by_id(id)?
+            .map(|row| row.r#type == NameRowType::Patient && row.deleted_datetime.is_none())
+            .unwrap_or(false))
+    };
+    if !is_active_patient(&input.surviving_patient_id)? {
+        return Err(MergePatientsError::SurvivingPatientDoesNotExist);
+    }
+    if !is_active_patient(&input.merged_patient_id)? {
+        return Err(MergePatientsError::MergedPatientDoesNotExist);
+    }
+
+    let program_ids = |patient_id: &str| -> Result<Vec<String>, RepositoryError> {
+        Ok(ProgramEnrolmentRepository::new(con)
+            .query(
+                Pagination::all(),
+                Some(ProgramEnrolmentFilter::new().patient_id(EqualFilter::equal_to(patient_id))),
+                None,
+            )?
+            .into_iter()
+            .map(|enrolment| enrolment.row.program_id)
+            .collect())
+    };
+    let surviving_program_ids = program_ids(&input.surviving_patient_id)?;
+    if let Some(program_id) = program_ids(&input.merged_patient_id)?
+        .into_iter()
+        .find(|program_id| surviving_program_ids.contains(program_id))
+    {
+        return Err(MergePatientsError::BothPatientsEnrolledInProgram(
+            program_id,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Start of the names of documents named after the patient, see `patient_doc_name`
+fn patient_doc_name_prefix(patient_id: &str) -> String {
+    format!("p/{}/", patient_id)
+}
+
+/// New versions of a document of the merged patient, owned by the surviving patient:
+/// - the merged patient document is deleted, the surviving patient already has one
+/// - documents named after the merged patient are moved under the surviving patient's name (so
+///   they are found by name for the surviving patient), the document under the old name is deleted
+///   and is the parent of the renamed document
+/// - other documents get a new version owned by the surviving patient
+fn generate_documents(
+    user_id: &str,
+    surviving_patient_id: &str,
+    merged_patient_id: &str,
+    document: Document,
+) -> Result<Vec<Document>, String> {
+    let new_version = |name: &str, parent: &str, status: DocumentStatus| {
+        RawDocument {
+            name: name.to_string(),
+            parents: vec![parent.to_string()],
+            author: user_id.to_string(),
+            datetime: Utc::now(),
+            r#type: document.r#type.clone(),
+            data: document.data.clone(),
+            form_schema_id: document.form_schema_id.clone(),
+            status,
+            owner_name_id: Some(surviving_patient_id.to_string()),
+            context_id: document.context_id.clone(),
+        }
+        .finalise()
+    };
+
+    if document.r#type == PATIENT_TYPE {
+        return Ok(vec![new_version(
+            &document.name,
+            &document.id,
+            DocumentStatus::Deleted,
+        )?]);
+    }
+
+    let Some(name_suffix) = document
+        .name
+        .strip_prefix(&patient_doc_name_prefix(merged_patient_id))
+    else {
+        return Ok(vec![new_version(
+            &document.name,
+            &document.id,
+            document.status.clone(),
+        )?]);
+    };
+
+    let deleted = new_version(&document.name, &document.id, DocumentStatus::Deleted)?;
+    let renamed = new_version(
+        &format!(
+            "{}{}",
+            patient_doc_name_prefix(surviving_patient_id),
+            name_suffix
+        ),
+        &deleted.id,
+        document.status.clone(),
+    )?;
+    Ok(vec![deleted, renamed])
+}
+
+impl From<RepositoryError> for MergePatientsError {
+    fn from(err: RepositoryError) -> Self {
+        MergePatientsError::DatabaseError(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Timelike, Utc};
+    use repository::{
+        mock::{context_program_a, mock_form_schema_empty, MockDataInserts},
+        test_db::setup_all,
+        DocumentRegistryCategory, DocumentRegistryRow, DocumentRegistryRowRepository,
+        FormSchemaRowRepository, StringFilter,
+    };
+    use util::{
+        constants::{PATIENT_CONTEXT_ID, PATIENT_TYPE},
+        inline_init,
+    };
+
+    use crate::{
+        programs::{
+            patient::{main_patient_doc_name, test::mock_patient_1, UpdateProgramPatient},
+            program_enrolment::{program_schema::SchemaProgramEnrolment, UpsertProgramEnrolment},
+        },
+        service_provider::ServiceProvider,
+    };
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_merge_patients() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "test_merge_patients",
+            MockDataInserts::none()
+                .units()
+                .items()
+                .names()
+                .stores()
+                .name_store_joins()
+                .full_master_list()
+                .contexts()
+                .programs(),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "");
+        let ctx = service_provider.basic_context().unwrap();
+
+        let schema = mock_form_schema_empty();
+        FormSchemaRowRepository::new(&connection)
+            .upsert_one(&schema)
+            .unwrap();
+        let enrolment_doc_type = "ProgramEnrolmentType".to_string();
+        let program_context = context_program_a().id;
+        let registry_repo = DocumentRegistryRowRepository::new(&connection);
+        registry_repo
+            .upsert_one(&DocumentRegistryRow {
+                id: "patient_id".to_string(),
+                category: DocumentRegistryCategory::Patient,
+                document_type: PATIENT_TYPE.to_string(),
+                context_id: PATIENT_CONTEXT_ID.to_string(),
+                name: None,
+                form_schema_id: Some(schema.id.clone()),
+                config: None,
+            })
+            .unwrap();
+        registry_repo
+            .upsert_one(&DocumentRegistryRow {
+                id: "program_enrolment_id".to_string(),
+                category: DocumentRegistryCategory::ProgramEnrolment,
+                document_type: enrolment_doc_type.clone(),
+                context_id: program_context.clone(),
+                name: None,
+                form_schema_id: Some(schema.id.clone()),
+                config: None,
+            })
+            .unwrap();
+
+        // Surviving, merged and another duplicate patient, the duplicates are enrolled in program
+        for id in ["surviving", "merged", "duplicate"] {
+            let mut patient = mock_patient_1();
+            patient.id = id.to_string();
+            patient.code = Some(id.to_string());
+            service_provider
+                .patient_service
+                .upsert_program_patient(
+                    &ctx,
+                    &service_provider,
+                    "store_a",
+                    "user",
+                    UpdateProgramPatient {
+                        data: serde_json::to_value(&patient).unwrap(),
+                        schema_id: schema.id.clone(),
+                        parent: None,
+                    },
+                )
+                .unwrap();
+        }
+        let program = inline_init(|v: &mut SchemaProgramEnrolment| {
+            v.enrolment_datetime = Utc::now().with_nanosecond(0).unwrap().to_rfc3339();
+            v.program_enrolment_id = Some("enrolment".to_string());
+        });
+        for patient_id in ["merged", "duplicate"] {
+            service_provider
+                .program_enrolment_service
+                .upsert_program_enrolment(
+                    &ctx,
+                    &service_provider,
+                    "user",
+                    UpsertProgramEnrolment {
+                        data: serde_json::to_value(program.clone()).unwrap(),
+                        schema_id: schema.id.clone(),
+                        parent: None,
+                        patient_id: patient_id.to_string(),
+                        r#type: enrolment_doc_type.clone(),
+                    },
+                    vec![program_context.clone()],
+                )
+                .unwrap();
+        }
+
+        let merge = |surviving: &str, merged: &str| {
+            service_provider.patient_service.merge_patients(
+                &ctx,
+                &service_provider,
+                MergePatients {
+                    surviving_patient_id: surviving.to_string(),
+                    merged_patient_id: merged.to_string(),
+                },
+            )
+        };
+        assert_eq!(
+            merge("surviving", "surviving"),
+            Err(MergePatientsError::CannotMergePatientWithItself)
+        );
+        assert_eq!(
+            merge("invalid", "merged"),
+            Err(MergePatientsError::SurvivingPatientDoesNotExist)
+        );
+        assert_eq!(
+            merge("surviving", "invalid"),
+            Err(MergePatientsError::MergedPatientDoesNotExist)
+        );
+
+        let patient = merge("surviving", "merged").unwrap();
+        assert_eq!(patient.id, "surviving");
+
+        // Program enrolment (and its document) now belongs to the surviving patient
+        let enrolment = ProgramEnrolmentRepository::new(&connection)
+            .query_by_filter(
+                ProgramEnrolmentFilter::new().patient_id(EqualFilter::equal_to("surviving")),
+            )
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(
+            enrolment.row.program_enrolment_id,
+            Some("enrolment".to_string())
+        );
+        let document = DocumentRepository::new(&connection)
+            .query_by_filter(
+                DocumentFilter::new().name(StringFilter::equal_to(&enrolment.row.document_name)),
+            )
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(document.owner_name_id, Some("surviving".to_string()));
+        // Enrolment document is moved under the surviving patient's name, the old one is deleted
+        assert!(document.name.starts_with("p/surviving/"));
+        let old_document = DocumentRepository::new(&connection)
+            .find_one_by_id(&document.parent_ids[0])
+            .unwrap()
+            .unwrap();
+        assert!(old_document.name.starts_with("p/merged/"));
+        assert_eq!(old_document.status, DocumentStatus::Deleted);
+        let surviving_documents = DocumentRepository::new(&connection)
+            .query_by_filter(
+                DocumentFilter::new()
+                    .name(StringFilter::starts_with("p/surviving/"))
+                    .r#type(EqualFilter::equal_to(&enrolment_doc_type)),
+            )
+            .unwrap();
+        assert_eq!(surviving_documents, vec![document.clone()]);
+
+        // Merged patient and its patient document are deleted
+        let merged_patient = NameRowRepository::new(&connection)
+            .find_one_by_id("merged")
+            .unwrap()
+            .unwrap();
+        assert!(merged_patient.deleted_datetime.is_some());
+        let merged_document = DocumentRepository::new(&connection)
+            .query_by_filter(
+                DocumentFilter::new()
+                    .name(StringFilter::equal_to(&main_patient_doc_name("merged"))),
+            )
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(merged_document.status, DocumentStatus::Deleted);
+        assert_eq!(
+            merge("surviving", "merged"),
+            Err(MergePatientsError::MergedPatientDoesNotExist)
+        );
+
+        // Surviving patient is now enrolled in the same program as the duplicate
+        assert!(matches!(
+            merge("surviving", "duplicate"),
+            Err(MergePatientsError::BothPatientsEnrolledInProgram(_))
+        ));
+    }
+}
@@ -3,9 +3,14 @@ use util::constants::PATIENT_TYPE;
 
 use crate::service_provider::ServiceContext;
 use crate::service_provider::ServiceProvider;
-use crate::ListResult;
+use crate::{ListError, ListResult};
 
+mod duplicates;
+mod id_card;
 mod insert_patient;
+mod matching;
+mod merge_patients;
+mod patient_code;
 pub mod patient_schema;
 pub mod patient_updated;
 mod query;
@@ -14,7 +19,11 @@ mod search_central;
 mod update_patient;
 mod upsert_program_patient;
 
+pub use self::duplicates::*;
+pub use self::id_card::*;
 pub use self::insert_patient::*;
+pub use self::merge_patients::*;
+pub use self::patient_code::*;
 pub use self::query::*;
 pub use self::search::*;
 pub use self::search_central::*;
@@ -68,6 +77,25 @@ pub trait PatientServiceTrait: Sync + Send {
         patient_search(ctx, service_provider, input, allowed_ctx)
     }
 
+    fn duplicate_patient_candidates(
+        &self,
+        ctx: &ServiceContext,
+        pagination: Option<PaginationOption>,
+        min_score: Option<f64>,
+        allowed_ctx: Option<&[String]>,
+    ) -> Result<ListResult<DuplicatePatientCandidate>, ListError> {
+        duplicate_patient_candidates(ctx, pagination, min_score, allowed_ctx)
+    }
+
+    fn merge_patients(
+        &self,
+        ctx: &ServiceContext,
+        service_provider: &ServiceProvider,
+        input: MergePatients,
+    ) -> Result<Patient, MergePatientsError> {
+        merge_patients(ctx, service_provider, input)
+    }
+
     fn insert_patient(
         &self,
         ctx: &ServiceContext,
@@ -86,6 +114,16 @@ pub trait PatientServiceTrait: Sync + Send {
     ) -> Result<Patient, UpdatePatientError> {
         update_patient(ctx, service_provider, input)
     }
+
+    fn print_patient_id_card(
+        &self,
+        ctx: &ServiceContext,
+        base_dir: &Option<String>,
+        store_id: &str,
+        patient_id: &str,
+    ) -> Result<String, PrintPatientIdCardError> {
+        print_patient_id_card(ctx, base_dir, store_id, patient_id)
+    }
 }
 
 pub struct PatientService {}
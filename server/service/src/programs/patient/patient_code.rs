@@ -0,0 +1,289 @@
+use repository::{
+    EqualFilter, NumberRowType, PatientCodeScheme, RepositoryError, StorageConnection, StoreFilter,
+    StoreRepository, StoreRowRepository,
+};
+
+use crate::{number::next_number, store_preference::get_store_preferences};
+
+/// Minimum number of digits of the sequence part of generated patient codes
+const SEQUENCE_DIGITS: usize = 6;
+
+const VERHOEFF_MULTIPLICATION: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+const VERHOEFF_PERMUTATION: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 7, 6, 8, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+const VERHOEFF_INVERSE: [u8; 10] = [0, 4, 3, 2, 1, 5, 6, 7, 8, 9];
+
+#[derive(PartialEq, Debug)]
+pub enum GeneratePatientCodeError {
+    StoreDoesNotExist,
+    /// Configured `patient_code_prefix` contains characters other than digits
+    InvalidPrefix(String),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for GeneratePatientCodeError {
+    fn from(error: RepositoryError) -> Self {
+        GeneratePatientCodeError::DatabaseError(error)
+    }
+}
+
+fn to_digits(value: &str) -> Option<Vec<u8>> {
+    value
+        .chars()
+        .map(|c| c.to_digit(10).map(|digit| digit as u8))
+        .collect()
+}
+
+pub fn luhn_check_digit(digits: &[u8]) -> u8 {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, digit)| {
+            let digit = *digit as u32;
+            // Every second digit is doubled, starting with the one next to the check digit
+            if index % 2 == 0 {
+                let doubled = digit * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                digit
+            }
+        })
+        .sum();
+    ((10 - sum % 10) % 10) as u8
+}
+
+pub fn verhoeff_check_digit(digits: &[u8]) -> u8 {
+    let checksum = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .fold(0, |checksum, (index, digit)| {
+            let permuted = VERHOEFF_PERMUTATION[(index + 1) % 8][*digit as usize];
+            VERHOEFF_MULTIPLICATION[checksum as usize][permuted as usize]
+        });
+    VERHOEFF_INVERSE[checksum as usize]
+}
+
+fn check_digit(scheme: PatientCodeScheme, digits: &[u8]) -> Option<u8> {
+    match scheme {
+        PatientCodeScheme::Manual => None,
+        PatientCodeScheme::PrefixSequenceLuhn => Some(luhn_check_digit(digits)),
+        PatientCodeScheme::PrefixSequenceVerhoeff => Some(verhoeff_check_digit(digits)),
+    }
+}
+
+/// Checks the code has the format of the scheme, i.e. digits (optionally separated by `-`) ending
+/// with a valid check digit. Any code is valid for `PatientCodeScheme::Manual`
+pub fn is_valid_patient_code(scheme: PatientCodeScheme, code: &str) -> bool {
+    if scheme == PatientCodeScheme::Manual {
+        return true;
+    }
+
+    let Some(digits) = to_digits(&code.replace('-', "")) else {
+        return false;
+    };
+    let Some((last, payload)) = digits.split_last() else {
+        return false;
+    };
+    !payload.is_empty() && check_digit(scheme, payload) == Some(*last)
+}
+
+/// Generates the next patient code for the store, in the format
+/// `{prefix}-{sequence}{check digit}`, e.g. `204-0000127`.
+///
+/// Sequences are allocated per prefix and site, so that stores on the same site using the same
+/// prefix never hand out the same code. The prefix defaults to the site id, which makes codes
+/// generated offline at different sites unique.
+/// Returns None if the store uses `PatientCodeScheme::Manual`
+pub fn generate_patient_code(
+    connection: &StorageConnection,
+    store_id: &str,
+) -> Result<Option<String>, GeneratePatientCodeError> {
+    let preferences = get_store_preferences(connection, store_id)?;
+    if preferences.patient_code_scheme == PatientCodeScheme::Manual {
+        return Ok(None);
+    }
+
+    let store = StoreRowRepository::new(connection)
+        .find_one_by_id(store_id)?
+        .ok_or(GeneratePatientCodeError::StoreDoesNotExist)?;
+
+    let prefix = match preferences.patient_code_prefix {
+        Some(prefix) => prefix,
+        None => store.site_id.to_string(),
+    };
+    let Some(prefix_digits) = to_digits(&prefix) else {
+        return Err(GeneratePatientCodeError::InvalidPrefix(prefix));
+    };
+
+    // Count against the first store of the site, the number table is per store
+    let sequence_store_id = StoreRepository::new(connection)
+        .query_by_filter(StoreFilter::new().site_id(EqualFilter::equal_to_i32(store.site_id)))?
+        .into_iter()
+        .map(|store| store.store_row.id)
+        .min()
+        .unwrap_or(store.id);
+
+    let sequence = next_number(
+        connection,
+        &NumberRowType::Patient(prefix.clone()),
+        &sequence_store_id,
+    )?;
+    let sequence = format!("{:0width$}", sequence, width = SEQUENCE_DIGITS);
+
+    let mut digits = prefix_digits;
+    // Sequence is always made of digits
+    digits.extend(to_digits(&sequence).unwrap_or_default());
+    let check_digit = check_digit(preferences.patient_code_scheme, &digits).unwrap_or_default();
+
+    Ok(Some(format!("{}-{}{}", prefix, sequence, check_digit)))
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        NameRowType, PatientCodeScheme, StorePreferenceRow, StorePreferenceRowRepository,
+    };
+
+    use crate::{
+        programs::patient::{
+            is_valid_patient_code, luhn_check_digit, verhoeff_check_digit, InsertPatient,
+            InsertPatientError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn patient_code_generation_and_validation() {
+        assert_eq!(luhn_check_digit(&[7, 9, 9, 2, 7, 3, 9, 8, 7, 1]), 3);
+        assert_eq!(verhoeff_check_digit(&[2, 3, 6]), 3);
+        assert!(is_valid_patient_code(
+            PatientCodeScheme::PrefixSequenceLuhn,
+            "79927-398713"
+        ));
+        assert!(!is_valid_patient_code(
+            PatientCodeScheme::PrefixSequenceLuhn,
+            "79927-398714"
+        ));
+        assert!(is_valid_patient_code(
+            PatientCodeScheme::PrefixSequenceVerhoeff,
+            "2363"
+        ));
+        // Transposed digits are caught by Verhoeff
+        assert!(!is_valid_patient_code(
+            PatientCodeScheme::PrefixSequenceVerhoeff,
+            "3263"
+        ));
+        assert!(!is_valid_patient_code(
+            PatientCodeScheme::PrefixSequenceVerhoeff,
+            "AB-1"
+        ));
+        assert!(is_valid_patient_code(PatientCodeScheme::Manual, "AB-1"));
+
+        let (_, _, connection_manager, _) = setup_all(
+            "patient_code_generation_and_validation",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "");
+        let context = service_provider.basic_context().unwrap();
+        let service = &service_provider.patient_service;
+        let store_id = mock_store_a().id;
+
+        let insert = |id: &str, code: &str| {
+            service.insert_patient(
+                &context,
+                &service_provider,
+                &store_id,
+                InsertPatient {
+                    id: id.to_string(),
+                    code: code.to_string(),
+                    r#type: NameRowType::Patient,
+                    ..Default::default()
+                },
+            )
+        };
+
+        // Manual scheme, codes are not validated
+        assert!(insert("patient_1", "ANY CODE").is_ok());
+
+        // Site id (100) is used as prefix by default
+        StorePreferenceRowRepository::new(&context.connection)
+            .upsert_one(&StorePreferenceRow {
+                id: store_id.clone(),
+                patient_code_scheme: PatientCodeScheme::PrefixSequenceVerhoeff,
+                ..Default::default()
+            })
+            .unwrap();
+        let patient = insert("patient_2", "").unwrap();
+        assert_eq!(patient.code, "100-0000013");
+        let patient = insert("patient_3", "").unwrap();
+        assert_eq!(patient.code, "100-0000021");
+
+        // Codes entered by the user, e.g. from another site, need a valid check digit
+        assert_eq!(
+            insert("patient_4", "200-0000100").err(),
+            Some(InsertPatientError::InvalidPatientCode)
+        );
+        assert_eq!(
+            insert("patient_4", "200-0000010").unwrap().code,
+            "200-0000010"
+        );
+
+        // Configured prefix
+        StorePreferenceRowRepository::new(&context.connection)
+            .upsert_one(&StorePreferenceRow {
+                id: store_id.clone(),
+                patient_code_scheme: PatientCodeScheme::PrefixSequenceLuhn,
+                patient_code_prefix: Some("42".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let patient = insert("patient_5", "").unwrap();
+        assert_eq!(patient.code, "42-0000010");
+
+        StorePreferenceRowRepository::new(&context.connection)
+            .upsert_one(&StorePreferenceRow {
+                id: store_id.clone(),
+                patient_code_scheme: PatientCodeScheme::PrefixSequenceLuhn,
+                patient_code_prefix: Some("A1".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            insert("patient_6", "").err(),
+            Some(InsertPatientError::InvalidPatientCodePrefix(
+                "A1".to_string()
+            ))
+        );
+    }
+}
@@ -1,18 +1,23 @@
-use chrono::NaiveDate;
-use repository::{
-    DateFilter, EqualFilter, GenderType, PaginationOption, PatientSort, PatientSortField,
-    RepositoryError, StringFilter,
-};
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+use repository::{DateFilter, EqualFilter, GenderType, RepositoryError, StringFilter};
 
 use crate::{
     service_provider::{ServiceContext, ServiceProvider},
-    ListResult,
+    usize_to_u32, ListResult,
 };
 
-use super::{Patient, PatientFilter};
+use super::{
+    matching::{normalise, patient_match_score, DATE_OF_BIRTH_TOLERANCE_DAYS},
+    Patient, PatientFilter,
+};
 
 const PAGINATION_LIMIT: u32 = 100;
+/// Patients matching the search worse than this are not returned
+const MIN_SEARCH_SCORE: f64 = 0.6;
 
+#[derive(Clone, Default, Debug)]
 pub struct PatientSearch {
     pub code: Option<String>,
     pub code_2: Option<String>,
@@ -25,79 +30,182 @@ pub struct PatientSearch {
 
 pub struct PatientSearchResult {
     pub patient: Patient,
-    /// Indicates how good the match was
+    /// Indicates how good the match was, between 0.0 and 1.0
     pub score: f64,
 }
 
+/// Candidates are found with broad queries (same identifier, same initials or date of birth within
+/// tolerance), each candidate is then scored against the search and best matches are returned
 pub fn patient_search(
     ctx: &ServiceContext,
     service_provider: &ServiceProvider,
     input: PatientSearch,
     allowed_ctx: Option<&[String]>,
 ) -> Result<ListResult<PatientSearchResult>, RepositoryError> {
-    let mut filter = PatientFilter::new();
-    let PatientSearch {
-        code,
-        code_2,
-        first_name,
-        last_name,
-        date_of_birth,
-        gender,
-        identifier,
-    } = input;
-
-    if let Some(code) = code {
-        filter = filter.code(StringFilter::equal_to(&code));
+    // patient id -> (patient, found by identifier)
+    let mut candidates: HashMap<String, (Patient, bool)> = HashMap::new();
+    for (filter, is_identifier_filter) in candidate_filters(&input) {
+        let patients = service_provider
+            .patient_service
+            .get_patients(ctx, None, Some(filter), None, allowed_ctx)?
+            .rows;
+        for patient in patients {
+            let (_, identifier_matched) = candidates
+                .entry(patient.id.clone())
+                .or_insert((patient, false));
+            *identifier_matched |= is_identifier_filter;
+        }
     }
-    if let Some(code_2) = code_2 {
-        filter = filter.code_2(StringFilter::equal_to(&code_2));
+
+    let mut rows: Vec<PatientSearchResult> = candidates
+        .into_values()
+        .map(|(patient, identifier_matched)| PatientSearchResult {
+            score: patient_match_score(&input, &patient, identifier_matched),
+            patient,
+        })
+        .filter(|result| result.score >= MIN_SEARCH_SCORE)
+        .collect();
+
+    rows.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.patient.code.cmp(&b.patient.code))
+    });
+    let count = usize_to_u32(rows.len());
+    rows.truncate(PAGINATION_LIMIT as usize);
+
+    Ok(ListResult { rows, count })
+}
+
+/// Filters for patients that could match the search, the flag is set for the identifier filter
+fn candidate_filters(search: &PatientSearch) -> Vec<(PatientFilter, bool)> {
+    let mut filters = Vec::new();
+
+    if let Some(code) = &search.code {
+        filters.push((
+            PatientFilter::new().code(StringFilter::equal_to(code)),
+            false,
+        ));
+    }
+    if let Some(code_2) = &search.code_2 {
+        filters.push((
+            PatientFilter::new().code_2(StringFilter::equal_to(code_2)),
+            false,
+        ));
     }
-    if let Some(first_name) = first_name {
-        filter = filter.first_name(StringFilter::like(&first_name));
+    if let Some(identifier) = &search.identifier {
+        filters.push((
+            PatientFilter::new().identifier(StringFilter::like(identifier)),
+            true,
+        ));
     }
-    if let Some(last_name) = last_name {
-        filter = filter.last_name(StringFilter::like(&last_name));
+
+    // Misspelled names usually still start with the same letter (soundex relies on it as well)
+    let initial = |name: &Option<String>| {
+        name.as_deref()
+            .map(normalise)
+            .and_then(|name| name.chars().next())
+            .map(|initial| initial.to_string())
+    };
+    if let Some(initial) = initial(&search.first_name) {
+        filters.push((
+            PatientFilter::new().first_name(StringFilter::starts_with(&initial)),
+            false,
+        ));
     }
-    if let Some(date_of_birth) = date_of_birth {
-        filter = filter.date_of_birth(DateFilter::equal_to(date_of_birth));
+    if let Some(initial) = initial(&search.last_name) {
+        filters.push((
+            PatientFilter::new().last_name(StringFilter::starts_with(&initial)),
+            false,
+        ));
     }
-    if let Some(gender) = gender {
-        filter = filter.gender(EqualFilter {
-            equal_to: Some(gender),
-            not_equal_to: None,
-            equal_any: None,
-            not_equal_all: None,
-            equal_any_or_null: None,
-            is_null: None,
-        });
+
+    if let Some(date_of_birth) = search.date_of_birth {
+        let tolerance = Duration::days(DATE_OF_BIRTH_TOLERANCE_DAYS);
+        filters.push((
+            PatientFilter::new().date_of_birth(DateFilter::date_range(
+                &(date_of_birth - tolerance),
+                &(date_of_birth + tolerance),
+            )),
+            false,
+        ));
     }
-    if let Some(identifier) = identifier {
-        filter = filter.identifier(StringFilter::like(&identifier));
+
+    if filters.is_empty() {
+        // Nothing to narrow down the candidates with
+        let mut filter = PatientFilter::new();
+        if let Some(gender) = &search.gender {
+            filter = filter.gender(EqualFilter {
+                equal_to: Some(gender.clone()),
+                not_equal_to: None,
+                equal_any: None,
+                not_equal_all: None,
+                equal_any_or_null: None,
+                is_null: None,
+            });
+        }
+        filters.push((filter, false));
     }
-    let results = service_provider.patient_service.get_patients(
-        ctx,
-        Some(PaginationOption {
-            limit: Some(PAGINATION_LIMIT),
-            offset: Some(0),
-        }),
-        Some(filter),
-        Some(PatientSort {
-            key: PatientSortField::Code,
-            desc: Some(false),
-        }),
-        allowed_ctx,
-    )?;
-
-    let results = ListResult {
-        rows: results
-            .rows
-            .into_iter()
-            .map(|patient| PatientSearchResult {
-                patient,
-                score: 1.0,
-            })
-            .collect(),
-        count: results.count,
+
+    filters
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, NameRow, NameRowRepository, NameRowType,
     };
-    Ok(results)
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::*;
+
+    fn patient(id: &str, first_name: &str, last_name: &str, date_of_birth: &str) -> NameRow {
+        NameRow {
+            id: id.to_string(),
+            r#type: NameRowType::Patient,
+            code: id.to_string(),
+            first_name: Some(first_name.to_string()),
+            last_name: Some(last_name.to_string()),
+            date_of_birth: Some(date_of_birth.parse().unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn patient_search_scored() {
+        let (_, connection, connection_manager, _) =
+            setup_all("patient_search_scored", MockDataInserts::none()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+
+        let name_repo = NameRowRepository::new(&connection);
+        for row in [
+            patient("catherine", "Catherine", "Smith", "1990-03-04"),
+            patient("katherine", "Katherine", "Smyth", "1990-04-03"),
+            patient("john", "John", "Jones", "1990-03-04"),
+        ] {
+            name_repo.upsert_one(&row).unwrap();
+        }
+
+        let result = patient_search(
+            &context,
+            &service_provider,
+            PatientSearch {
+                first_name: Some("Catherine".to_string()),
+                last_name: Some("Smith".to_string()),
+                date_of_birth: "1990-03-04".parse().ok(),
+                ..Default::default()
+            },
+            None,
+        )
+        .unwrap();
+
+        // John Jones is a candidate (same date of birth) but not a match
+        assert_eq!(result.count, 2);
+        assert_eq!(result.rows[0].patient.id, "catherine");
+        assert_eq!(result.rows[0].score, 1.0);
+        assert_eq!(result.rows[1].patient.id, "katherine");
+        assert!(result.rows[1].score > 0.8 && result.rows[1].score < 1.0);
+    }
 }
@@ -1,17 +1,19 @@
 use chrono::Utc;
 use repository::{
     DocumentRegistry, DocumentRegistryCategory, DocumentRegistryFilter, DocumentRegistryRepository,
-    DocumentStatus, EqualFilter, RepositoryError, TransactionError,
+    DocumentStatus, EqualFilter, NameRowRepository, RepositoryError, TransactionError,
 };
 use util::constants::PATIENT_TYPE;
 
 use crate::{
     document::{document_service::DocumentInsertError, is_latest_doc, raw_document::RawDocument},
     service_provider::{ServiceContext, ServiceProvider},
+    store_preference::get_store_preferences,
 };
 
 use super::{
     main_patient_doc_name,
+    patient_code::{generate_patient_code, is_valid_patient_code, GeneratePatientCodeError},
     patient_schema::SchemaPatient,
     patient_updated::{create_patient_name_store_join, update_patient_row},
     Patient, PatientFilter,
@@ -22,6 +24,9 @@ pub enum UpdateProgramPatientError {
     InvalidPatientId,
     InvalidParentId,
     PatientExists,
+    /// Code doesn't match the store's patient code scheme, e.g. the check digit is wrong
+    InvalidPatientCode,
+    InvalidPatientCodePrefix(String),
     InvalidDataSchema(Vec<String>),
     PatientDocumentRegistryDoesNotExit,
     DataSchemaDoesNotExist,
@@ -46,7 +51,17 @@ pub fn upsert_program_patient(
     let patient = ctx
         .connection
         .transaction_sync(|_| {
-            let (patient, registry) = validate(ctx, service_provider, &input)?;
+            let (mut patient, registry) = validate(ctx, service_provider, store_id, &input)?;
+            let mut input = input;
+            // An empty code of a new patient is generated from the store's patient code scheme
+            if input.parent.is_none() && patient.code.as_deref().unwrap_or_default().is_empty() {
+                if let Some(code) = generate_patient_code(&ctx.connection, store_id)? {
+                    if let Some(data) = input.data.as_object_mut() {
+                        data.insert("code".to_string(), code.clone().into());
+                    }
+                    patient.code = Some(code);
+                }
+            }
             let patient_id = patient.id.clone();
             let doc = generate(user_id, &patient, registry, input)?;
             let doc_timestamp = doc.datetime;
@@ -117,6 +132,22 @@ impl From<RepositoryError> for UpdateProgramPatientError {
     }
 }
 
+impl From<GeneratePatientCodeError> for UpdateProgramPatientError {
+    fn from(err: GeneratePatientCodeError) -> Self {
+        match err {
+            GeneratePatientCodeError::StoreDoesNotExist => {
+                UpdateProgramPatientError::InternalError("Store does not exist".to_string())
+            }
+            GeneratePatientCodeError::InvalidPrefix(prefix) => {
+                UpdateProgramPatientError::InvalidPatientCodePrefix(prefix)
+            }
+            GeneratePatientCodeError::DatabaseError(err) => {
+                UpdateProgramPatientError::DatabaseError(err)
+            }
+        }
+    }
+}
+
 fn generate(
     user_id: &str,
     patient: &SchemaPatient,
@@ -180,9 +211,29 @@ fn validate_document_type(
     Ok(entry.pop())
 }
 
+/// Codes that are already stored for the patient are valid, e.g. codes entered before the store
+/// used a patient code scheme
+fn validate_patient_code(
+    ctx: &ServiceContext,
+    store_id: &str,
+    patient: &SchemaPatient,
+) -> Result<bool, RepositoryError> {
+    let Some(code) = patient.code.as_deref().filter(|code| !code.is_empty()) else {
+        return Ok(true);
+    };
+    let existing = NameRowRepository::new(&ctx.connection).find_one_by_id(&patient.id)?;
+    if existing.map(|name| name.code == code).unwrap_or(false) {
+        return Ok(true);
+    }
+
+    let scheme = get_store_preferences(&ctx.connection, store_id)?.patient_code_scheme;
+    Ok(is_valid_patient_code(scheme, code))
+}
+
 fn validate(
     ctx: &ServiceContext,
     service_provider: &ServiceProvider,
+    store_id: &str,
     input: &UpdateProgramPatient,
 ) -> Result<(SchemaPatient, DocumentRegistry), UpdateProgramPatientError> {
     let patient = validate_patient_schema(input)?;
@@ -199,6 +250,10 @@ fn validate(
         return Err(UpdateProgramPatientError::PatientExists);
     }
 
+    if !validate_patient_code(ctx, store_id, &patient)? {
+        return Err(UpdateProgramPatientError::InvalidPatientCode);
+    }
+
     Ok((patient, document_registry))
 }
 
@@ -209,7 +264,8 @@ pub mod test {
         test_db::setup_all,
         DocumentFilter, DocumentRegistryCategory, DocumentRegistryRow,
         DocumentRegistryRowRepository, DocumentRepository, EqualFilter, FormSchemaRowRepository,
-        Pagination, PatientFilter, PatientRepository, StringFilter,
+        Pagination, PatientCodeScheme, PatientFilter, PatientRepository, StorePreferenceRow,
+        StorePreferenceRowRepository, StringFilter,
     };
     use serde_json::json;
     use util::{
@@ -402,4 +458,86 @@ pub mod test {
             )
             .unwrap();
     }
+    #[actix_rt::test]
+    async fn test_program_patient_code() {
+        let (_, _, connection_manager, _) = setup_all(
+            "test_program_patient_code",
+            MockDataInserts::none()
+                .names()
+                .stores()
+                .form_schemas()
+                .name_store_joins(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "");
+        let ctx = service_provider.basic_context().unwrap();
+
+        let schema = mock_form_schema_empty();
+        FormSchemaRowRepository::new(&ctx.connection)
+            .upsert_one(&schema)
+            .unwrap();
+        DocumentRegistryRowRepository::new(&ctx.connection)
+            .upsert_one(&DocumentRegistryRow {
+                id: "patient_id".to_string(),
+                category: DocumentRegistryCategory::Patient,
+                document_type: PATIENT_TYPE.to_string(),
+                context_id: PATIENT_CONTEXT_ID.to_string(),
+                name: None,
+                form_schema_id: Some(schema.id.clone()),
+                config: None,
+            })
+            .unwrap();
+        StorePreferenceRowRepository::new(&ctx.connection)
+            .upsert_one(&StorePreferenceRow {
+                id: "store_a".to_string(),
+                patient_code_scheme: PatientCodeScheme::PrefixSequenceVerhoeff,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let service = &service_provider.patient_service;
+        let upsert = |patient: &SchemaPatient, parent: Option<String>| {
+            service.upsert_program_patient(
+                &ctx,
+                &service_provider,
+                "store_a",
+                "user",
+                upsert_program_patient::UpdateProgramPatient {
+                    data: serde_json::to_value(patient).unwrap(),
+                    schema_id: schema.id.clone(),
+                    parent,
+                },
+            )
+        };
+
+        // Invalid check digit
+        let mut patient = mock_patient_1();
+        patient.code = Some("200-0000100".to_string());
+        assert_eq!(
+            upsert(&patient, None).err(),
+            Some(UpdateProgramPatientError::InvalidPatientCode)
+        );
+
+        // Code is generated from the store's scheme (site id 100 as prefix)
+        patient.code = None;
+        let result = upsert(&patient, None).unwrap();
+        assert_eq!(result.code, "100-0000013");
+        let document = service_provider
+            .document_service
+            .document(&ctx, &main_patient_doc_name(&patient.id), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(document.data["code"], json!("100-0000013"));
+
+        // Changed code is validated
+        patient.code = Some("100-0000014".to_string());
+        assert_eq!(
+            upsert(&patient, Some(document.id.clone())).err(),
+            Some(UpdateProgramPatientError::InvalidPatientCode)
+        );
+        patient.code = Some("200-0000010".to_string());
+        let result = upsert(&patient, Some(document.id)).unwrap();
+        assert_eq!(result.code, "200-0000010");
+    }
 }
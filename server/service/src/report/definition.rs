@@ -63,11 +63,56 @@ pub enum ReportOutputType {
     Html,
 }
 
+/// Spreadsheet (Excel or CSV) output that is built directly from the report data, instead of
+/// being scraped from the rendered HTML
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SpreadsheetTemplate {
+    /// Each sheet is a separate worksheet in Excel, only the first sheet is used for CSV
+    pub sheets: Vec<SpreadsheetSheet>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SpreadsheetSheet {
+    pub name: String,
+    /// JSON pointer to the array of rows in the report data, e.g. `/data/stocktake/lines/nodes`.
+    /// The report data has the same shape as the Tera template context, i.e. `data` and
+    /// `arguments`
+    pub rows: String,
+    pub columns: Vec<SpreadsheetColumn>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct SpreadsheetColumn {
+    /// Column header
+    pub title: String,
+    /// JSON pointer to the value, relative to the row, e.g. `/item/name`
+    pub value: String,
+    #[serde(default)]
+    pub r#type: SpreadsheetColumnType,
+    /// Column width in number of characters
+    pub width: Option<f64>,
+    /// Adjacent columns with the same group share a merged header above the column headers
+    pub group: Option<String>,
+    /// Excel number format, e.g. `0.00` or `dd/mm/yyyy`, a default is used for dates
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub enum SpreadsheetColumnType {
+    #[default]
+    Text,
+    Number,
+    Date,
+    DateTime,
+    Boolean,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(tag = "type", content = "data")]
 pub enum ReportDefinitionEntry {
     Manifest(Manifest),
     TeraTemplate(TeraTemplate),
+    Spreadsheet(SpreadsheetTemplate),
     /// Custom http query
     GraphGLQuery(GraphQlQuery),
     /// Use default predefined query
@@ -88,6 +133,9 @@ pub struct ReportDefinitionIndex {
     pub query: Vec<String>,
     pub convert_data: Option<String>,
     pub custom_wasm_function: Option<String>,
+    /// Reference to a spreadsheet entry, used when printing to Excel or CSV
+    #[serde(default)]
+    pub spreadsheet: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -161,6 +209,7 @@ mod report_dsl_test {
                     query: vec!["query".to_string()],
                     convert_data: None,
                     custom_wasm_function: None,
+                    spreadsheet: None,
                 },
                 entries: HashMap::from([
                     (
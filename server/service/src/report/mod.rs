@@ -1,6 +1,8 @@
 pub mod default_queries;
 pub mod definition;
 mod html_printing;
-mod qr_code;
+pub(crate) mod qr_code;
 pub mod report_service;
+pub mod schedule;
+mod spreadsheet;
 mod string_or_vec;
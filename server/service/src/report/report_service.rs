@@ -5,12 +5,17 @@ use extism::{
     host_fn, FromBytes, Manifest, PluginBuilder, ToBytes, UserData, Wasm, WasmMetadata, PTR,
 };
 use repository::{
-    raw_query, EqualFilter, JsonRawRow, PaginationOption, Report, ReportFilter, ReportRepository,
-    ReportRowRepository, ReportSort, ReportType, RepositoryError, StorageConnection,
+    read_only_json_query, EqualFilter, PaginationOption, ReadOnlyQueryError, ReadOnlyQueryLimits,
+    Report, ReportFilter, ReportRepository, ReportRowRepository, ReportSort, ReportType,
+    RepositoryError, StorageConnection,
 };
 use scraper::{ElementRef, Html, Selector};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, time::SystemTime};
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, SystemTime},
+};
 use util::uuid::uuid;
 
 use crate::{
@@ -24,16 +29,19 @@ use crate::{
 use super::{
     default_queries::get_default_gql_query,
     definition::{
-        GraphQlQuery, ReportDefinition, ReportDefinitionEntry, ReportRef, SQLQuery, TeraTemplate,
+        GraphQlQuery, ReportDefinition, ReportDefinitionEntry, ReportRef, SQLQuery,
+        SpreadsheetTemplate, TeraTemplate,
     },
     html_printing::html_to_pdf,
     qr_code::qr_code_svg,
+    spreadsheet::{sheets_from_data, to_csv, write_excel, CellValue, Sheet, SheetColumn},
 };
 
 pub enum PrintFormat {
     Pdf,
     Html,
     Excel,
+    Csv,
 }
 
 #[derive(Debug)]
@@ -72,6 +80,8 @@ pub struct ResolvedReportDefinition {
     pub queries: Vec<ResolvedReportQuery>,
     pub resources: HashMap<String, serde_json::Value>,
     pub convert_data: Option<String>,
+    /// Spreadsheet used for Excel and CSV output, if not set the rendered HTML is converted
+    pub spreadsheet: Option<SpreadsheetTemplate>,
 }
 
 pub struct GeneratedReport {
@@ -126,6 +136,19 @@ pub trait ReportServiceTrait: Sync + Send {
         translation_service: &Localisations,
         current_language: Option<String>,
     ) -> Result<String, ReportError> {
+        if let (Some(spreadsheet), Some(PrintFormat::Excel | PrintFormat::Csv)) =
+            (&report.spreadsheet, &format)
+        {
+            let sheets =
+                generate_spreadsheet(connection, report, spreadsheet, report_data, arguments)?;
+            return match format {
+                Some(PrintFormat::Csv) => {
+                    print_sheets_to_csv(base_dir, sheets, report.name.clone())
+                }
+                _ => print_sheets_to_excel(base_dir, sheets, report.name.clone()),
+            };
+        }
+
         let document = generate_report(
             connection,
             report,
@@ -142,6 +165,9 @@ pub trait ReportServiceTrait: Sync + Send {
             Some(PrintFormat::Excel) => {
                 print_html_report_to_excel(base_dir, document, report.name.clone())
             }
+            Some(PrintFormat::Csv) => {
+                print_html_report_to_csv(base_dir, document, report.name.clone())
+            }
             Some(PrintFormat::Pdf) | None => {
                 generate_html_report_to_pdf(base_dir, document, report.name.clone())
             }
@@ -227,37 +253,66 @@ fn inner_text(element_ref: ElementRef) -> &str {
         .unwrap_or_default()
 }
 
+/// Scrapes the table headers and cells of a rendered HTML report into a single (untyped) sheet
+fn sheet_from_html_report(document: GeneratedReport) -> Sheet {
+    let fragment = Html::parse_fragment(&format_html_document(document));
+
+    let selectors = Selectors::new(&fragment); // Store Html when creating
+
+    Sheet {
+        name: "Report".to_string(),
+        columns: selectors
+            .headers()
+            .into_iter()
+            .map(|header| SheetColumn {
+                title: header.to_string(),
+                ..Default::default()
+            })
+            .collect(),
+        rows: selectors
+            .rows_and_cells()
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| CellValue::Text(cell.to_string()))
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
 /// Converts the report to an Excel file and returns the file id
 fn print_html_report_to_excel(
     base_dir: &Option<String>,
     document: GeneratedReport,
     report_name: String,
 ) -> Result<String, ReportError> {
-    let sheet_name = "Report";
-
-    let mut book = umya_spreadsheet::new_file();
-    book.set_sheet_name(0, sheet_name).unwrap();
-    let sheet = book.get_sheet_by_name_mut(sheet_name).unwrap();
-
-    let fragment = Html::parse_fragment(&format_html_document(document));
-
-    let selectors = Selectors::new(&fragment); // Store Html when creating
-
-    for (index, header) in selectors.headers().into_iter().enumerate() {
-        let cell = sheet.get_cell_mut((index as u32 + 1, 1));
-
-        cell.set_value(header);
-        cell.get_style_mut().get_font_mut().set_bold(true);
-    }
+    print_sheets_to_excel(
+        base_dir,
+        vec![sheet_from_html_report(document)],
+        report_name,
+    )
+}
 
-    for (row_index, row) in selectors.rows_and_cells().into_iter().enumerate() {
-        for (cell_index, cell) in row.into_iter().enumerate() {
-            sheet
-                .get_cell_mut((cell_index as u32 + 1, row_index as u32 + 2))
-                .set_value(cell);
-        }
-    }
+/// Converts the report to a CSV file and returns the file id
+fn print_html_report_to_csv(
+    base_dir: &Option<String>,
+    document: GeneratedReport,
+    report_name: String,
+) -> Result<String, ReportError> {
+    print_sheets_to_csv(
+        base_dir,
+        vec![sheet_from_html_report(document)],
+        report_name,
+    )
+}
 
+/// Writes sheets to an Excel file and returns the file id
+fn print_sheets_to_excel(
+    base_dir: &Option<String>,
+    sheets: Vec<Sheet>,
+    report_name: String,
+) -> Result<String, ReportError> {
     let now: DateTime<Utc> = SystemTime::now().into();
     let file_service = StaticFileService::new(base_dir)
         .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))?;
@@ -270,12 +325,32 @@ fn print_html_report_to_excel(
         )
         .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))?;
 
-    umya_spreadsheet::writer::xlsx::write(&book, reserved_file.path)
-        .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))?;
+    write_excel(sheets, Path::new(&reserved_file.path))?;
 
     Ok(reserved_file.id)
 }
 
+/// Writes the first sheet to a CSV file and returns the file id
+fn print_sheets_to_csv(
+    base_dir: &Option<String>,
+    sheets: Vec<Sheet>,
+    report_name: String,
+) -> Result<String, ReportError> {
+    let csv = sheets.first().map(to_csv).unwrap_or_default();
+
+    let file_service = StaticFileService::new(base_dir)
+        .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))?;
+    let now: DateTime<Utc> = SystemTime::now().into();
+    let file = file_service
+        .store_file(
+            &format!("{}_{}.csv", now.format("%Y%m%d_%H%M%S"), report_name),
+            StaticFileCategory::Temporary,
+            csv.as_bytes(),
+        )
+        .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))?;
+    Ok(file.id)
+}
+
 /// Puts the document content, header and footer into a <html> template.
 /// This assumes that the document contains the html body.
 fn format_html_document(document: GeneratedReport) -> String {
@@ -404,6 +479,19 @@ fn resolve_report_definition(
     // resolve the query entry
     let queries = query_from_resolved_template(query_entry)?;
 
+    let spreadsheet = match &fully_loaded_report.index.spreadsheet {
+        Some(spreadsheet) => match fully_loaded_report.entries.get(spreadsheet) {
+            Some(ReportDefinitionEntry::Spreadsheet(spreadsheet)) => Some(spreadsheet.clone()),
+            _ => {
+                return Err(ReportError::InvalidReportDefinition(format!(
+                    "Invalid spreadsheet reference: {}",
+                    spreadsheet
+                )))
+            }
+        },
+        None => None,
+    };
+
     let resources = resources_from_resolved_template(&fully_loaded_report);
     Ok(ResolvedReportDefinition {
         name,
@@ -414,44 +502,115 @@ fn resolve_report_definition(
         queries,
         resources,
         convert_data: fully_loaded_report.index.convert_data,
+        spreadsheet,
     })
 }
 
+const WASM_SQL_MAX_ROWS: usize = 10_000;
+const WASM_SQL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Query sent by a convert_data plugin via the `sql` host function. Parameters are bound to the
+/// positional placeholders `$1`, `$2`, ... of the statement (for both Sqlite and Postgres).
 #[derive(Serialize, Debug, Deserialize, FromBytes)]
 #[encoding(Json)]
 struct WasmSqlQuery {
     statement: String,
+    #[serde(default)]
     parameters: Vec<serde_json::Value>,
 }
 
-#[derive(Serialize, Debug, Deserialize, FromBytes)]
+#[derive(Serialize, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum WasmSqlErrorCode {
+    NotReadOnly,
+    InvalidParameters,
+    RowLimitExceeded,
+    Timeout,
+    InvalidRow,
+    DatabaseError,
+}
+
+#[derive(Serialize, Debug, Deserialize, PartialEq)]
+struct WasmSqlError {
+    code: WasmSqlErrorCode,
+    message: String,
+}
+
+/// `rows` is empty if the query failed, in which case `error` is set
+#[derive(Serialize, Debug, Deserialize, FromBytes, PartialEq)]
 #[encoding(Json)]
 struct WasmSqlResult {
     rows: Vec<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<WasmSqlError>,
 }
 
 host_fn!(sql(user_data: StorageConnection; key: Json<WasmSqlQuery>) -> Json<WasmSqlResult> {
-    Ok(wasm_sql(user_data, key))
+    Ok(Json(wasm_sql(user_data, key)))
 });
 
 fn wasm_sql(
     user_data: UserData<StorageConnection>,
-    Json(WasmSqlQuery {
+    Json(query): Json<WasmSqlQuery>,
+) -> WasmSqlResult {
+    let result = match user_data.get() {
+        Ok(connection) => match connection.lock() {
+            Ok(connection) => run_wasm_sql(&connection, query),
+            Err(err) => Err(WasmSqlError {
+                code: WasmSqlErrorCode::DatabaseError,
+                message: format!("Connection is not available: {}", err),
+            }),
+        },
+        Err(err) => Err(WasmSqlError {
+            code: WasmSqlErrorCode::DatabaseError,
+            message: format!("Connection is not available: {}", err),
+        }),
+    };
+
+    match result {
+        Ok(rows) => WasmSqlResult { rows, error: None },
+        Err(error) => WasmSqlResult {
+            rows: Vec::new(),
+            error: Some(error),
+        },
+    }
+}
+
+fn run_wasm_sql(
+    connection: &StorageConnection,
+    WasmSqlQuery {
         statement,
         parameters,
-    }): Json<WasmSqlQuery>,
-) -> Json<WasmSqlResult> {
-    let _ = parameters; // Explicitly ignore parameters
-    let con_mut = user_data.get().unwrap();
-    let con = con_mut.lock().unwrap();
-    let results = raw_query(&con, statement);
-    Json(WasmSqlResult {
-        rows: results
-            .into_iter()
-            .map(|JsonRawRow { json_row }| {
-                serde_json::from_str::<serde_json::Value>(&json_row).unwrap()
-            })
-            .collect(),
+    }: WasmSqlQuery,
+) -> Result<Vec<serde_json::Value>, WasmSqlError> {
+    let limits = ReadOnlyQueryLimits {
+        max_rows: WASM_SQL_MAX_ROWS,
+        timeout: WASM_SQL_TIMEOUT,
+    };
+
+    read_only_json_query(connection, &statement, &parameters, &limits).map_err(|error| {
+        use ReadOnlyQueryError as Error;
+        let (code, message) = match error {
+            Error::NotReadOnly => (
+                WasmSqlErrorCode::NotReadOnly,
+                "Only SELECT statements are allowed".to_string(),
+            ),
+            Error::InvalidParameters(message) => (WasmSqlErrorCode::InvalidParameters, message),
+            Error::RowLimitExceeded(max_rows) => (
+                WasmSqlErrorCode::RowLimitExceeded,
+                format!("Query returned more than {} rows", max_rows),
+            ),
+            Error::Timeout => (
+                WasmSqlErrorCode::Timeout,
+                format!(
+                    "Query took longer than {} seconds",
+                    limits.timeout.as_secs()
+                ),
+            ),
+            Error::InvalidRow(message) => (WasmSqlErrorCode::InvalidRow, message),
+            Error::DatabaseError(error) => (WasmSqlErrorCode::DatabaseError, error.to_string()),
+        };
+        WasmSqlError { code, message }
     })
 }
 
@@ -578,6 +737,21 @@ fn generate_report(
     })
 }
 
+fn generate_spreadsheet(
+    connection: StorageConnection,
+    report: &ResolvedReportDefinition,
+    spreadsheet: &SpreadsheetTemplate,
+    data: serde_json::Value,
+    arguments: Option<serde_json::Value>,
+) -> Result<Vec<Sheet>, ReportError> {
+    let report_data = ReportData { data, arguments };
+    let report_data = transform_data(connection, report_data, report.convert_data.clone());
+    let report_data = serde_json::to_value(report_data)
+        .map_err(|err| ReportError::DocGenerationError(format!("Spreadsheet data: {:?}", err)))?;
+
+    sheets_from_data(spreadsheet, &report_data)
+}
+
 fn tera_templates_from_resolved_template(
     report: &ReportDefinition,
 ) -> Option<HashMap<String, TeraTemplate>> {
@@ -642,7 +816,8 @@ fn resources_from_resolved_template(
             | ReportDefinitionEntry::GraphGLQuery(_)
             | ReportDefinitionEntry::Ref(_)
             | ReportDefinitionEntry::SQLQuery(_)
-            | ReportDefinitionEntry::TeraTemplate(_) => None,
+            | ReportDefinitionEntry::TeraTemplate(_)
+            | ReportDefinitionEntry::Spreadsheet(_) => None,
         })
         .collect()
 }
@@ -753,6 +928,7 @@ mod report_service_test {
                 query: vec!["query".to_string()],
                 convert_data: None,
                 custom_wasm_function: None,
+                spreadsheet: None,
             },
             entries: HashMap::from([
                 (
@@ -784,6 +960,7 @@ mod report_service_test {
                 query: vec![],
                 convert_data: None,
                 custom_wasm_function: None,
+                spreadsheet: None,
             },
             entries: HashMap::from([(
                 "footer.html".to_string(),
@@ -1002,6 +1179,7 @@ mod report_generation_test {
             templates,
             resources: HashMap::new(),
             convert_data: None,
+            spreadsheet: None,
         };
 
         let report_data = json!(null);
@@ -1038,3 +1216,70 @@ mod report_generation_test {
         assert!(generated_report.document.contains("Nom"));
     }
 }
+
+#[cfg(test)]
+mod wasm_sql_test {
+    use repository::{mock::MockDataInserts, test_db::setup_all};
+    use serde_json::json;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn test_wasm_sql() {
+        let (_, connection, _, _) =
+            setup_all("test_wasm_sql", MockDataInserts::none().names().stores()).await;
+
+        #[cfg(feature = "postgres")]
+        let statement =
+            "SELECT json_build_object('id', id)::text AS json_row FROM store WHERE id = $1";
+        #[cfg(not(feature = "postgres"))]
+        let statement = "SELECT json_object('id', id) AS json_row FROM store WHERE id = $1";
+
+        let result = run_wasm_sql(
+            &connection,
+            WasmSqlQuery {
+                statement: statement.to_string(),
+                parameters: vec![json!("store_a")],
+            },
+        );
+        assert_eq!(result, Ok(vec![json!({"id": "store_a"})]));
+
+        let result = run_wasm_sql(
+            &connection,
+            WasmSqlQuery {
+                statement: "UPDATE store SET code = 'x'".to_string(),
+                parameters: vec![],
+            },
+        );
+        assert_eq!(
+            result.map_err(|error| error.code),
+            Err(WasmSqlErrorCode::NotReadOnly)
+        );
+
+        let result = run_wasm_sql(
+            &connection,
+            WasmSqlQuery {
+                statement: statement.to_string(),
+                parameters: vec![json!(["store_a"])],
+            },
+        );
+        assert_eq!(
+            result.map_err(|error| error.code),
+            Err(WasmSqlErrorCode::InvalidParameters)
+        );
+
+        // errors are returned to the plugin
+        let result = serde_json::to_value(WasmSqlResult {
+            rows: Vec::new(),
+            error: Some(WasmSqlError {
+                code: WasmSqlErrorCode::RowLimitExceeded,
+                message: "message".to_string(),
+            }),
+        })
+        .unwrap();
+        assert_eq!(
+            result,
+            json!({"rows": [], "error": {"code": "ROW_LIMIT_EXCEEDED", "message": "message"}})
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+/// How far ahead to look for the next matching date (covers e.g. `0 0 29 2 *`)
+const MAX_DAYS_AHEAD: u32 = 366 * 8;
+
+/// Parsed cron expression with the five standard fields:
+/// `minute hour day-of-month month day-of-week`
+///
+/// Each field can be `*`, a value, a range (`1-5`), a step (`*/15`, `1-10/2`) or a comma
+/// separated list of those. Day of week is 0-7 (0 and 7 are Sunday).
+/// The shortcuts `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` are also supported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: BTreeSet<u32>,
+    hours: BTreeSet<u32>,
+    days_of_month: BTreeSet<u32>,
+    months: BTreeSet<u32>,
+    days_of_week: BTreeSet<u32>,
+    // Standard cron behaviour: if both day fields are restricted a day matching either is used
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<CronSchedule, String> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            expression => expression,
+        };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "Expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            ));
+        };
+
+        let days_of_week = parse_field(day_of_week, 0..=7, "day of week")?
+            .into_iter()
+            // 7 is an alias for Sunday
+            .map(|day| day % 7)
+            .collect();
+
+        Ok(CronSchedule {
+            minutes: parse_field(minute, 0..=59, "minute")?,
+            hours: parse_field(hour, 0..=23, "hour")?,
+            days_of_month: parse_field(day_of_month, 1..=31, "day of month")?,
+            months: parse_field(month, 1..=12, "month")?,
+            days_of_week,
+            day_of_month_restricted: day_of_month != "*",
+            day_of_week_restricted: day_of_week != "*",
+        })
+    }
+
+    /// First matching datetime strictly after `after` (at minute precision)
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+
+        let mut date = start.date();
+        let mut earliest_time = start.time();
+        for _ in 0..MAX_DAYS_AHEAD {
+            if self.matches_date(date) {
+                if let Some(time) = self.first_time_from(earliest_time) {
+                    return Some(date.and_time(time));
+                }
+            }
+            date = date.succ_opt()?;
+            earliest_time = NaiveTime::MIN;
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(&date.month()) {
+            return false;
+        }
+
+        let day_of_month = self.days_of_month.contains(&date.day());
+        let day_of_week = self
+            .days_of_week
+            .contains(&date.weekday().num_days_from_sunday());
+
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            (true, false) => day_of_month,
+            (false, true) => day_of_week,
+            (false, false) => true,
+        }
+    }
+
+    fn first_time_from(&self, from: NaiveTime) -> Option<NaiveTime> {
+        for hour in self.hours.range(from.hour()..) {
+            let from_minute = if *hour == from.hour() {
+                from.minute()
+            } else {
+                0
+            };
+            if let Some(minute) = self.minutes.range(from_minute..).next() {
+                return NaiveTime::from_hms_opt(*hour, *minute, 0);
+            }
+        }
+        None
+    }
+}
+
+fn parse_field(
+    field: &str,
+    range: RangeInclusive<u32>,
+    name: &str,
+) -> Result<BTreeSet<u32>, String> {
+    let mut values = BTreeSet::new();
+
+    for part in field.split(',') {
+        let (values_part, step) = match part.split_once('/') {
+            Some((values_part, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid {} step: {}", name, part))?;
+                if step == 0 {
+                    return Err(format!("Invalid {} step: {}", name, part));
+                }
+                (values_part, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = match values_part {
+            "*" => (*range.start(), *range.end()),
+            values_part => match values_part.split_once('-') {
+                Some((start, end)) => (
+                    parse_value(start, &range, name)?,
+                    parse_value(end, &range, name)?,
+                ),
+                None => {
+                    let value = parse_value(values_part, &range, name)?;
+                    // `5/15` means every 15 starting at 5
+                    let end = if part.contains('/') {
+                        *range.end()
+                    } else {
+                        value
+                    };
+                    (value, end)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("Invalid {} range: {}", name, part));
+        }
+
+        values.extend((start..=end).step_by(step as usize));
+    }
+
+    Ok(values)
+}
+
+fn parse_value(value: &str, range: &RangeInclusive<u32>, name: &str) -> Result<u32, String> {
+    let parsed: u32 = value
+        .parse()
+        .map_err(|_| format!("Invalid {}: {}", name, value))?;
+    if !range.contains(&parsed) {
+        return Err(format!(
+            "Invalid {}: {} (must be between {} and {})",
+            name,
+            value,
+            range.start(),
+            range.end()
+        ));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use super::CronSchedule;
+
+    fn datetime(date: (i32, u32, u32), time: (u32, u32)) -> chrono::NaiveDateTime {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .unwrap()
+            .and_hms_opt(time.0, time.1, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cron_schedule() {
+        // Monthly, first of the month at 6am
+        let schedule = CronSchedule::parse("0 6 1 * *").unwrap();
+        assert_eq!(
+            schedule.next_after(datetime((2024, 1, 15), (10, 30))),
+            Some(datetime((2024, 2, 1), (6, 0)))
+        );
+        assert_eq!(
+            schedule.next_after(datetime((2024, 2, 1), (5, 59))),
+            Some(datetime((2024, 2, 1), (6, 0)))
+        );
+        // Strictly after
+        assert_eq!(
+            schedule.next_after(datetime((2024, 2, 1), (6, 0))),
+            Some(datetime((2024, 3, 1), (6, 0)))
+        );
+
+        // Weekly, Monday 7:30 (2024-01-15 is a Monday)
+        let schedule = CronSchedule::parse("30 7 * * 1").unwrap();
+        assert_eq!(
+            schedule.next_after(datetime((2024, 1, 15), (7, 30))),
+            Some(datetime((2024, 1, 22), (7, 30)))
+        );
+
+        // Sunday as 7
+        assert_eq!(
+            CronSchedule::parse("0 0 * * 7").unwrap(),
+            CronSchedule::parse("@weekly").unwrap()
+        );
+
+        // Steps, lists and ranges
+        let schedule = CronSchedule::parse("*/15 9-17 * * 1-5").unwrap();
+        assert_eq!(
+            schedule.next_after(datetime((2024, 1, 19), (17, 45))), // Friday
+            Some(datetime((2024, 1, 22), (9, 0)))
+        );
+        let schedule = CronSchedule::parse("5,35 * * * *").unwrap();
+        assert_eq!(
+            schedule.next_after(datetime((2024, 1, 1), (23, 40))),
+            Some(datetime((2024, 1, 2), (0, 5)))
+        );
+
+        // Day of month OR day of week when both are restricted
+        let schedule = CronSchedule::parse("0 0 13 * 5").unwrap();
+        assert_eq!(
+            schedule.next_after(datetime((2024, 1, 10), (0, 0))),
+            Some(datetime((2024, 1, 12), (0, 0))) // Friday before the 13th
+        );
+
+        // Leap day
+        let schedule = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            schedule.next_after(datetime((2024, 3, 1), (0, 0))),
+            Some(datetime((2028, 2, 29), (0, 0)))
+        );
+
+        // Never matches
+        let schedule = CronSchedule::parse("0 0 31 2 *").unwrap();
+        assert_eq!(schedule.next_after(datetime((2024, 1, 1), (0, 0))), None);
+
+        // Invalid
+        assert!(CronSchedule::parse("0 6 1 *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+        assert!(CronSchedule::parse("5-1 * * * *").is_err());
+        assert!(CronSchedule::parse("a * * * *").is_err());
+    }
+}
@@ -0,0 +1,459 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime, TimeZone, Utc};
+use repository::{
+    ReportRowRepository, ReportScheduleFormat, ReportScheduleRow, ReportScheduleRowRepository,
+    ReportScheduleRunRow, ReportScheduleRunRowRepository, ReportScheduleRunStatus, RepositoryError,
+    StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::ServiceContext,
+    static_files::{StaticFileCategory, StaticFileService},
+    validate::check_store_id_matches,
+};
+
+use self::cron::CronSchedule;
+
+use super::report_service::PrintFormat;
+
+pub mod cron;
+pub mod scheduler;
+
+/// Upper bound when skipping past missed occurrences, e.g. a schedule running every minute
+/// while the server was off for a couple of months
+const MAX_MISSED_OCCURRENCES: usize = 200_000;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpsertReportSchedule {
+    pub id: String,
+    pub report_id: String,
+    pub arguments: Option<serde_json::Value>,
+    pub format: ReportScheduleFormat,
+    /// Cron expression, see `CronSchedule`
+    pub schedule: String,
+    /// Absolute path of the directory generated reports are written to
+    pub output_directory: String,
+    pub is_active: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertReportScheduleError {
+    ScheduleDoesNotBelongToCurrentStore,
+    ReportDoesNotExist,
+    InvalidSchedule(String),
+    OutputDirectoryMustBeAbsolute,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeleteReportScheduleError {
+    ScheduleDoesNotExist,
+    ScheduleDoesNotBelongToCurrentStore,
+    DatabaseError(RepositoryError),
+}
+
+/// Creates or updates a report schedule, reports are generated as the current user
+pub fn upsert_report_schedule(
+    ctx: &ServiceContext,
+    input: UpsertReportSchedule,
+) -> Result<ReportScheduleRow, UpsertReportScheduleError> {
+    let schedule = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let existing = validate_upsert(connection, &ctx.store_id, &input)?;
+
+            let UpsertReportSchedule {
+                id,
+                report_id,
+                arguments,
+                format,
+                schedule,
+                output_directory,
+                is_active,
+            } = input;
+
+            let schedule = ReportScheduleRow {
+                id,
+                report_id,
+                store_id: ctx.store_id.clone(),
+                user_id: ctx.user_id.clone(),
+                arguments: arguments.map(|arguments| arguments.to_string()),
+                format,
+                schedule,
+                output_directory,
+                is_active,
+                created_datetime: existing
+                    .map(|existing| existing.created_datetime)
+                    .unwrap_or(Utc::now().naive_utc()),
+            };
+            ReportScheduleRowRepository::new(connection).upsert_one(&schedule)?;
+
+            Ok(schedule)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(schedule)
+}
+
+fn validate_upsert(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpsertReportSchedule,
+) -> Result<Option<ReportScheduleRow>, UpsertReportScheduleError> {
+    use UpsertReportScheduleError::*;
+
+    let existing = ReportScheduleRowRepository::new(connection).find_one_by_id(&input.id)?;
+    if let Some(existing) = &existing {
+        if !check_store_id_matches(&existing.store_id, store_id) {
+            return Err(ScheduleDoesNotBelongToCurrentStore);
+        }
+    }
+
+    ReportRowRepository::new(connection)
+        .find_one_by_id(&input.report_id)?
+        .ok_or(ReportDoesNotExist)?;
+
+    CronSchedule::parse(&input.schedule).map_err(InvalidSchedule)?;
+
+    if !Path::new(&input.output_directory).is_absolute() {
+        return Err(OutputDirectoryMustBeAbsolute);
+    }
+
+    Ok(existing)
+}
+
+/// Deletes a report schedule and its run history
+pub fn delete_report_schedule(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<String, DeleteReportScheduleError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = ReportScheduleRowRepository::new(connection);
+            let schedule = repository
+                .find_one_by_id(&id)?
+                .ok_or(DeleteReportScheduleError::ScheduleDoesNotExist)?;
+            if !check_store_id_matches(&schedule.store_id, &ctx.store_id) {
+                return Err(DeleteReportScheduleError::ScheduleDoesNotBelongToCurrentStore);
+            }
+            ReportScheduleRunRowRepository::new(connection).delete_by_schedule_id(&id)?;
+            repository.delete(&id)?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(id)
+}
+
+pub fn get_report_schedules(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<Vec<ReportScheduleRow>, RepositoryError> {
+    ReportScheduleRowRepository::new(&ctx.connection).find_many_by_store_id(store_id)
+}
+
+/// Run history of a schedule, latest first. Returns `NotFound` if the schedule doesn't belong to
+/// the store
+pub fn get_report_schedule_runs(
+    ctx: &ServiceContext,
+    store_id: &str,
+    report_schedule_id: &str,
+    limit: Option<u32>,
+) -> Result<Vec<ReportScheduleRunRow>, RepositoryError> {
+    let schedule = ReportScheduleRowRepository::new(&ctx.connection)
+        .find_one_by_id(report_schedule_id)?
+        .filter(|schedule| schedule.store_id == store_id)
+        .ok_or(RepositoryError::NotFound)?;
+
+    ReportScheduleRunRowRepository::new(&ctx.connection)
+        .find_many_by_schedule_id(&schedule.id, limit)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DueReportSchedule {
+    pub schedule: ReportScheduleRow,
+    /// Latest occurrence of the schedule that is due (missed occurrences are not caught up)
+    pub scheduled_datetime: NaiveDateTime,
+}
+
+/// Active schedules with an occurrence between their last run (or creation) and `now`.
+/// Schedules are evaluated in the server's local time, all datetimes are UTC
+pub fn get_due_report_schedules(
+    connection: &StorageConnection,
+    now: NaiveDateTime,
+) -> Result<Vec<DueReportSchedule>, RepositoryError> {
+    let run_repository = ReportScheduleRunRowRepository::new(connection);
+    let local_now = to_local(now);
+
+    let mut result = Vec::new();
+    for schedule in ReportScheduleRowRepository::new(connection).find_many_active()? {
+        let cron = match CronSchedule::parse(&schedule.schedule) {
+            Ok(cron) => cron,
+            Err(error) => {
+                log::error!("Invalid report schedule {}: {}", schedule.id, error);
+                continue;
+            }
+        };
+
+        let last = match run_repository.find_latest_by_schedule_id(&schedule.id)? {
+            Some(run) => run.scheduled_datetime,
+            None => schedule.created_datetime,
+        };
+
+        let mut due = None;
+        let mut candidate = to_local(last);
+        for _ in 0..MAX_MISSED_OCCURRENCES {
+            match cron.next_after(candidate) {
+                Some(next) if next <= local_now => {
+                    due = Some(next);
+                    candidate = next;
+                }
+                _ => break,
+            }
+        }
+
+        let Some(due) = due.and_then(to_utc) else {
+            continue;
+        };
+        result.push(DueReportSchedule {
+            schedule,
+            scheduled_datetime: due,
+        });
+    }
+
+    Ok(result)
+}
+
+fn to_local(datetime: NaiveDateTime) -> NaiveDateTime {
+    Local.from_utc_datetime(&datetime).naive_local()
+}
+
+fn to_utc(datetime: NaiveDateTime) -> Option<NaiveDateTime> {
+    Local
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map(|datetime| datetime.naive_utc())
+}
+
+/// Records the start of a run, this also stops the occurrence from being due again
+pub fn start_report_schedule_run(
+    connection: &StorageConnection,
+    due: &DueReportSchedule,
+) -> Result<ReportScheduleRunRow, RepositoryError> {
+    let run = ReportScheduleRunRow {
+        id: uuid(),
+        report_schedule_id: due.schedule.id.clone(),
+        scheduled_datetime: due.scheduled_datetime,
+        started_datetime: Utc::now().naive_utc(),
+        finished_datetime: None,
+        status: ReportScheduleRunStatus::Running,
+        output_path: None,
+        error: None,
+    };
+    ReportScheduleRunRowRepository::new(connection).upsert_one(&run)?;
+    Ok(run)
+}
+
+/// Records the outcome of a run, `result` is the output path or an error message
+pub fn finish_report_schedule_run(
+    connection: &StorageConnection,
+    run: ReportScheduleRunRow,
+    result: Result<String, String>,
+) -> Result<ReportScheduleRunRow, RepositoryError> {
+    let (status, output_path, error) = match result {
+        Ok(output_path) => (ReportScheduleRunStatus::Success, Some(output_path), None),
+        Err(error) => (ReportScheduleRunStatus::Error, None, Some(error)),
+    };
+    let run = ReportScheduleRunRow {
+        finished_datetime: Some(Utc::now().naive_utc()),
+        status,
+        output_path,
+        error,
+        ..run
+    };
+    ReportScheduleRunRowRepository::new(connection).upsert_one(&run)?;
+    Ok(run)
+}
+
+/// Copies a generated report from the temporary static files to the schedule's output
+/// directory and returns the output path
+pub fn copy_report_to_output_directory(
+    base_dir: &Option<String>,
+    file_id: &str,
+    output_directory: &str,
+) -> anyhow::Result<String> {
+    let file_service = StaticFileService::new(base_dir)?;
+    let file = file_service
+        .find_file(file_id, StaticFileCategory::Temporary)?
+        .ok_or_else(|| anyhow::anyhow!("Generated report file {} not found", file_id))?;
+
+    std::fs::create_dir_all(output_directory)?;
+    let output_path: PathBuf = Path::new(output_directory).join(&file.name);
+    std::fs::copy(&file.path, &output_path)?;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+impl ReportScheduleFormat {
+    pub fn to_print_format(&self) -> PrintFormat {
+        match self {
+            ReportScheduleFormat::Pdf => PrintFormat::Pdf,
+            ReportScheduleFormat::Html => PrintFormat::Html,
+            ReportScheduleFormat::Excel => PrintFormat::Excel,
+            ReportScheduleFormat::Csv => PrintFormat::Csv,
+        }
+    }
+}
+
+impl From<RepositoryError> for UpsertReportScheduleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertReportScheduleError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeleteReportScheduleError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteReportScheduleError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        ReportRow, ReportScheduleFormat, ReportScheduleRowRepository, ReportScheduleRunStatus,
+    };
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn report_schedule() {
+        let (_, _, connection_manager, _) =
+            setup_all("report_schedule", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "user_account_a".to_string())
+            .unwrap();
+        let report_id = "report".to_string();
+        ReportRowRepository::new(&context.connection)
+            .upsert_one(&ReportRow {
+                id: report_id.clone(),
+                name: "Report".to_string(),
+                code: "report".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let input = UpsertReportSchedule {
+            id: "schedule".to_string(),
+            report_id: report_id.clone(),
+            format: ReportScheduleFormat::Pdf,
+            schedule: "0 6 1 * *".to_string(),
+            output_directory: std::env::temp_dir().to_string_lossy().to_string(),
+            is_active: true,
+            ..Default::default()
+        };
+
+        // Errors
+        assert_eq!(
+            upsert_report_schedule(
+                &context,
+                UpsertReportSchedule {
+                    report_id: "invalid".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(UpsertReportScheduleError::ReportDoesNotExist)
+        );
+        assert!(matches!(
+            upsert_report_schedule(
+                &context,
+                UpsertReportSchedule {
+                    schedule: "every day".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(UpsertReportScheduleError::InvalidSchedule(_))
+        ));
+        assert_eq!(
+            upsert_report_schedule(
+                &context,
+                UpsertReportSchedule {
+                    output_directory: "reports".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(UpsertReportScheduleError::OutputDirectoryMustBeAbsolute)
+        );
+
+        // Success
+        let schedule = upsert_report_schedule(&context, input.clone()).unwrap();
+        assert_eq!(schedule.user_id, "user_account_a");
+
+        // Not due until the first of next month
+        let now = Utc::now().naive_utc();
+        assert_eq!(
+            get_due_report_schedules(&context.connection, now),
+            Ok(vec![])
+        );
+
+        // Due, latest missed occurrence is used
+        ReportScheduleRowRepository::new(&context.connection)
+            .upsert_one(&ReportScheduleRow {
+                created_datetime: now - Duration::days(100),
+                ..schedule.clone()
+            })
+            .unwrap();
+        let due = get_due_report_schedules(&context.connection, now).unwrap();
+        assert_eq!(due.len(), 1);
+        let scheduled_datetime = due[0].scheduled_datetime;
+        assert!(scheduled_datetime <= now);
+        assert!(scheduled_datetime > now - Duration::days(32));
+
+        // Run history
+        let run = start_report_schedule_run(&context.connection, &due[0]).unwrap();
+        assert_eq!(
+            get_due_report_schedules(&context.connection, now),
+            Ok(vec![])
+        );
+        finish_report_schedule_run(&context.connection, run, Err("failed".to_string())).unwrap();
+
+        let runs =
+            get_report_schedule_runs(&context, &mock_store_a().id, "schedule", None).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, ReportScheduleRunStatus::Error);
+        assert_eq!(runs[0].error, Some("failed".to_string()));
+        assert_eq!(runs[0].scheduled_datetime, scheduled_datetime);
+
+        // Inactive schedules are never due
+        upsert_report_schedule(
+            &context,
+            UpsertReportSchedule {
+                is_active: false,
+                ..input.clone()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            get_due_report_schedules(&context.connection, now + Duration::days(100)),
+            Ok(vec![])
+        );
+
+        // Delete
+        assert_eq!(
+            delete_report_schedule(&context, "schedule".to_string()),
+            Ok("schedule".to_string())
+        );
+        assert_eq!(
+            delete_report_schedule(&context, "schedule".to_string()),
+            Err(DeleteReportScheduleError::ScheduleDoesNotExist)
+        );
+    }
+}
@@ -0,0 +1,305 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use chrono::Utc;
+
+use crate::{
+    auth_data::AuthData, report::report_service::ResolvedReportQuery,
+    service_provider::ServiceProvider, settings::is_develop, token::TokenService,
+};
+
+use super::{
+    copy_report_to_output_directory, finish_report_schedule_run, get_due_report_schedules,
+    start_report_schedule_run, DueReportSchedule,
+};
+
+/// How often to check for due report schedules, schedules have minute precision
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Validity of the auth token used to query report data as the schedule's user
+const AUTH_TOKEN_VALID_FOR_SEC: usize = 5 * 60;
+
+/// Report data request of a scheduled report
+pub struct ScheduledReportDataRequest<'a> {
+    pub store_id: &'a str,
+    /// Short lived auth token of the schedule's user, report queries run with their permissions
+    pub auth_token: String,
+    pub queries: &'a Vec<ResolvedReportQuery>,
+    pub arguments: Option<serde_json::Value>,
+}
+
+/// Fetches the data of a scheduled report. Report queries are resolved by the GraphQL layer, in
+/// the same way as for the `generateReport` query
+#[async_trait::async_trait]
+pub trait ReportDataFetcher: Send + Sync {
+    async fn fetch_data(
+        &self,
+        request: ScheduledReportDataRequest<'_>,
+    ) -> anyhow::Result<serde_json::Value>;
+}
+
+/// Generates reports on their schedule and writes them to the schedule's output directory
+pub struct ReportScheduler {
+    service_provider: Arc<ServiceProvider>,
+    auth: Arc<AuthData>,
+    base_dir: Option<String>,
+    data_fetcher: Box<dyn ReportDataFetcher>,
+}
+
+impl ReportScheduler {
+    pub fn new(
+        service_provider: Arc<ServiceProvider>,
+        auth: Arc<AuthData>,
+        base_dir: Option<String>,
+        data_fetcher: Box<dyn ReportDataFetcher>,
+    ) -> Self {
+        ReportScheduler {
+            service_provider,
+            auth,
+            base_dir,
+            data_fetcher,
+        }
+    }
+
+    /// ReportScheduler entry point, this method is meant to be run within main `select!` macro
+    pub async fn run(self) {
+        loop {
+            if let Err(error) = self.run_due_schedules().await {
+                log::error!("Problem running report schedules: {:#}", error);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    }
+
+    pub async fn run_due_schedules(&self) -> anyhow::Result<()> {
+        let ctx = self.service_provider.basic_context()?;
+        let due_schedules = get_due_report_schedules(&ctx.connection, Utc::now().naive_utc())?;
+
+        for due in due_schedules {
+            let run = start_report_schedule_run(&ctx.connection, &due)?;
+            let result = self
+                .generate_report(&due)
+                .await
+                .map_err(|error| format!("{:#}", error));
+            if let Err(error) = &result {
+                log::error!("Scheduled report {} failed: {}", due.schedule.id, error);
+            }
+            finish_report_schedule_run(&ctx.connection, run, result)?;
+        }
+
+        Ok(())
+    }
+
+    /// Generates the report as the schedule's user and returns the path of the file in the
+    /// output directory
+    async fn generate_report(&self, due: &DueReportSchedule) -> anyhow::Result<String> {
+        let schedule = &due.schedule;
+        let auth_token = self.auth_token(&schedule.user_id)?;
+
+        let service_provider = &self.service_provider;
+        let service = &service_provider.report_service;
+        let service_context =
+            service_provider.context(schedule.store_id.clone(), schedule.user_id.clone())?;
+
+        let resolved_report = service
+            .resolve_report(&service_context, &schedule.report_id)
+            .map_err(|error| anyhow!("Failed to resolve report: {:?}", error))?;
+        let arguments: Option<serde_json::Value> = schedule
+            .arguments
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()?;
+
+        let report_data = self
+            .data_fetcher
+            .fetch_data(ScheduledReportDataRequest {
+                store_id: &schedule.store_id,
+                auth_token,
+                queries: &resolved_report.queries,
+                arguments: arguments.clone(),
+            })
+            .await?;
+
+        let file_id = service
+            .generate_html_report(
+                service_context.connection,
+                &self.base_dir,
+                &resolved_report,
+                report_data,
+                arguments,
+                Some(schedule.format.to_print_format()),
+                &service_provider.translations_service,
+                None,
+            )
+            .map_err(|error| anyhow!("Failed to generate report: {:?}", error))?;
+
+        copy_report_to_output_directory(&self.base_dir, &file_id, &schedule.output_directory)
+    }
+
+    /// Short lived auth token for the user the report is generated as, the user doesn't need to be
+    /// logged in. Report queries check the user's stored permissions
+    fn auth_token(&self, user_id: &str) -> anyhow::Result<String> {
+        // Tokens are stored with the user's password (used for syncing the user with the central
+        // server). Keep the password of the user's sessions, it is empty if the user hasn't logged
+        // in since the server started and will be set by their next login
+        let password = self
+            .auth
+            .token_bucket
+            .read()
+            .map_err(|error| anyhow!("{}", error))?
+            .get_password(user_id);
+
+        let mut service = TokenService::new(
+            &self.auth.token_bucket,
+            self.auth.auth_token_secret.as_bytes(),
+            !is_develop(),
+        );
+        let pair = service
+            .jwt_token(
+                user_id,
+                &password,
+                AUTH_TOKEN_VALID_FOR_SEC,
+                AUTH_TOKEN_VALID_FOR_SEC,
+            )
+            .map_err(|error| anyhow!("Failed to create auth token: {:?}", error))?;
+
+        Ok(pair.token)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    };
+
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        ReportRow, ReportRowRepository, ReportScheduleFormat, ReportScheduleRow,
+        ReportScheduleRowRepository, ReportScheduleRunStatus,
+    };
+
+    use crate::{
+        auth_data::AuthData,
+        report::{
+            definition::{
+                ReportDefinition, ReportDefinitionEntry, ReportDefinitionIndex, ReportOutputType,
+                TeraTemplate,
+            },
+            schedule::{get_report_schedule_runs, upsert_report_schedule, UpsertReportSchedule},
+        },
+        service_provider::ServiceProvider,
+        token_bucket::TokenBucket,
+    };
+
+    use super::*;
+
+    /// Checks the auth token of the request and fails with the user the token was issued for
+    struct TokenCheckingDataFetcher {
+        auth: Arc<AuthData>,
+    }
+
+    #[async_trait::async_trait]
+    impl ReportDataFetcher for TokenCheckingDataFetcher {
+        async fn fetch_data(
+            &self,
+            request: ScheduledReportDataRequest<'_>,
+        ) -> anyhow::Result<serde_json::Value> {
+            let claims = TokenService::new(
+                &self.auth.token_bucket,
+                self.auth.auth_token_secret.as_bytes(),
+                true,
+            )
+            .verify_token(&request.auth_token, None)
+            .map_err(|error| anyhow!("Invalid token: {:?}", error))?;
+            Err(anyhow!("Data requested by {}", claims.sub))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn report_scheduler_user_not_logged_in() {
+        let (_, _, connection_manager, _) = setup_all(
+            "report_scheduler_user_not_logged_in",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider = Arc::new(ServiceProvider::new(connection_manager, "app_data"));
+        let context = service_provider
+            .context(mock_store_a().id, "user_account_a".to_string())
+            .unwrap();
+        ReportRowRepository::new(&context.connection)
+            .upsert_one(&ReportRow {
+                id: "report".to_string(),
+                name: "Report".to_string(),
+                code: "report".to_string(),
+                template: serde_json::to_string(&ReportDefinition {
+                    index: ReportDefinitionIndex {
+                        template: Some("template.html".to_string()),
+                        header: None,
+                        footer: None,
+                        query: vec![],
+                        convert_data: None,
+                        custom_wasm_function: None,
+                        spreadsheet: None,
+                    },
+                    entries: HashMap::from([(
+                        "template.html".to_string(),
+                        ReportDefinitionEntry::TeraTemplate(TeraTemplate {
+                            output: ReportOutputType::Html,
+                            template: "Report".to_string(),
+                        }),
+                    )]),
+                })
+                .unwrap(),
+                ..Default::default()
+            })
+            .unwrap();
+        let schedule = upsert_report_schedule(
+            &context,
+            UpsertReportSchedule {
+                id: "schedule".to_string(),
+                report_id: "report".to_string(),
+                format: ReportScheduleFormat::Pdf,
+                schedule: "0 6 1 * *".to_string(),
+                output_directory: std::env::temp_dir().to_string_lossy().to_string(),
+                is_active: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        ReportScheduleRowRepository::new(&context.connection)
+            .upsert_one(&ReportScheduleRow {
+                created_datetime: Utc::now().naive_utc() - Duration::days(100),
+                ..schedule
+            })
+            .unwrap();
+
+        // No tokens (and password) for the user, e.g. after a server restart
+        let auth = Arc::new(AuthData {
+            auth_token_secret: "secret".to_string(),
+            token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+            no_ssl: true,
+            debug_no_access_control: false,
+        });
+        let scheduler = ReportScheduler::new(
+            service_provider.clone(),
+            auth.clone(),
+            None,
+            Box::new(TokenCheckingDataFetcher { auth }),
+        );
+        scheduler.run_due_schedules().await.unwrap();
+
+        let runs =
+            get_report_schedule_runs(&context, &mock_store_a().id, "schedule", None).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, ReportScheduleRunStatus::Error);
+        assert!(runs[0]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("Data requested by user_account_a"));
+    }
+}
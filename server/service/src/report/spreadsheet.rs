@@ -0,0 +1,415 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde_json::Value;
+use std::path::Path;
+
+use super::{
+    definition::{SpreadsheetColumnType, SpreadsheetTemplate},
+    report_service::ReportError,
+};
+
+const DEFAULT_DATE_FORMAT: &str = "yyyy-mm-dd";
+const DEFAULT_DATE_TIME_FORMAT: &str = "yyyy-mm-dd hh:mm:ss";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum CellValue {
+    Empty,
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    Boolean(bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct SheetColumn {
+    pub title: String,
+    pub group: Option<String>,
+    pub width: Option<f64>,
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Sheet {
+    pub name: String,
+    pub columns: Vec<SheetColumn>,
+    pub rows: Vec<Vec<CellValue>>,
+}
+
+/// Builds typed sheets from the report data, see `SpreadsheetTemplate`
+pub(crate) fn sheets_from_data(
+    template: &SpreadsheetTemplate,
+    data: &Value,
+) -> Result<Vec<Sheet>, ReportError> {
+    template
+        .sheets
+        .iter()
+        .map(|sheet| {
+            let rows = match data.pointer(&sheet.rows) {
+                Some(Value::Array(rows)) => rows.as_slice(),
+                // No data for this sheet, e.g. an empty query result
+                None | Some(Value::Null) => &[],
+                Some(_) => {
+                    return Err(ReportError::DocGenerationError(format!(
+                        "Spreadsheet rows for sheet {} ({}) is not an array",
+                        sheet.name, sheet.rows
+                    )))
+                }
+            };
+
+            Ok(Sheet {
+                name: sheet.name.clone(),
+                columns: sheet
+                    .columns
+                    .iter()
+                    .map(|column| SheetColumn {
+                        title: column.title.clone(),
+                        group: column.group.clone(),
+                        width: column.width,
+                        format: column.format.clone().or_else(|| match column.r#type {
+                            SpreadsheetColumnType::Date => Some(DEFAULT_DATE_FORMAT.to_string()),
+                            SpreadsheetColumnType::DateTime => {
+                                Some(DEFAULT_DATE_TIME_FORMAT.to_string())
+                            }
+                            _ => None,
+                        }),
+                    })
+                    .collect(),
+                rows: rows
+                    .iter()
+                    .map(|row| {
+                        sheet
+                            .columns
+                            .iter()
+                            .map(|column| cell_value(row.pointer(&column.value), &column.r#type))
+                            .collect()
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Converts a json value to the column type, falls back to text if value can't be converted
+fn cell_value(value: Option<&Value>, r#type: &SpreadsheetColumnType) -> CellValue {
+    let value = match value {
+        None | Some(Value::Null) => return CellValue::Empty,
+        Some(value) => value,
+    };
+    let text = match value {
+        Value::String(string) => string.clone(),
+        value => value.to_string(),
+    };
+
+    let typed = match r#type {
+        SpreadsheetColumnType::Text => None,
+        SpreadsheetColumnType::Number => match value {
+            Value::Number(number) => number.as_f64(),
+            _ => text.trim().parse::<f64>().ok(),
+        }
+        .map(CellValue::Number),
+        SpreadsheetColumnType::Date => parse_date_time(&text)
+            .map(|datetime| datetime.date())
+            .or_else(|| NaiveDate::parse_from_str(&text, "%Y-%m-%d").ok())
+            .map(CellValue::Date),
+        SpreadsheetColumnType::DateTime => parse_date_time(&text).map(CellValue::DateTime),
+        SpreadsheetColumnType::Boolean => match value {
+            Value::Bool(bool) => Some(*bool),
+            _ => text.parse::<bool>().ok(),
+        }
+        .map(CellValue::Boolean),
+    };
+
+    typed.unwrap_or(CellValue::Text(text))
+}
+
+fn parse_date_time(text: &str) -> Option<NaiveDateTime> {
+    chrono::DateTime::parse_from_rfc3339(text)
+        .map(|datetime| datetime.naive_utc())
+        .ok()
+        .or_else(|| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f").ok())
+        .or_else(|| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f").ok())
+}
+
+/// Excel stores dates as (fractional) number of days since 1899-12-30
+fn excel_serial_date(datetime: NaiveDateTime) -> f64 {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    (datetime - epoch).num_seconds() as f64 / 86400.0
+}
+
+/// 1 based column index to Excel column name, e.g. 1 -> A, 27 -> AA
+fn column_name(mut index: u32) -> String {
+    let mut name = String::new();
+    while index > 0 {
+        let remainder = (index - 1) % 26;
+        name.insert(0, (b'A' + remainder as u8) as char);
+        index = (index - 1) / 26;
+    }
+    name
+}
+
+pub(crate) fn write_excel(sheets: Vec<Sheet>, path: &Path) -> Result<(), ReportError> {
+    let mut book = umya_spreadsheet::new_file();
+
+    for (sheet_index, sheet) in sheets.into_iter().enumerate() {
+        let worksheet = if sheet_index == 0 {
+            book.set_sheet_name(0, sheet.name.clone())
+                .map_err(|err| ReportError::DocGenerationError(err.to_string()))?;
+            book.get_sheet_by_name_mut(&sheet.name).unwrap()
+        } else {
+            book.new_sheet(sheet.name.clone())
+                .map_err(|err| ReportError::DocGenerationError(err.to_string()))?
+        };
+
+        let has_groups = sheet.columns.iter().any(|column| column.group.is_some());
+        let header_row = if has_groups { 2 } else { 1 };
+
+        // Merged group headers
+        if has_groups {
+            let mut column_index = 0;
+            while column_index < sheet.columns.len() {
+                let group = &sheet.columns[column_index].group;
+                let mut end_index = column_index;
+                while end_index + 1 < sheet.columns.len()
+                    && &sheet.columns[end_index + 1].group == group
+                {
+                    end_index += 1;
+                }
+                if let Some(group) = group {
+                    let cell = worksheet.get_cell_mut((column_index as u32 + 1, 1));
+                    cell.set_value(group.clone());
+                    cell.get_style_mut().get_font_mut().set_bold(true);
+                    if end_index > column_index {
+                        worksheet.add_merge_cells(format!(
+                            "{}1:{}1",
+                            column_name(column_index as u32 + 1),
+                            column_name(end_index as u32 + 1)
+                        ));
+                    }
+                }
+                column_index = end_index + 1;
+            }
+        }
+
+        for (column_index, column) in sheet.columns.iter().enumerate() {
+            let column_number = column_index as u32 + 1;
+            let cell = worksheet.get_cell_mut((column_number, header_row));
+            cell.set_value(column.title.clone());
+            cell.get_style_mut().get_font_mut().set_bold(true);
+
+            if let Some(width) = column.width {
+                worksheet
+                    .get_column_dimension_by_number_mut(&column_number)
+                    .set_width(width);
+            }
+        }
+
+        for (row_index, row) in sheet.rows.into_iter().enumerate() {
+            let row_number = row_index as u32 + header_row + 1;
+            for (column_index, value) in row.into_iter().enumerate() {
+                let cell = worksheet.get_cell_mut((column_index as u32 + 1, row_number));
+                match value {
+                    CellValue::Empty => continue,
+                    CellValue::Text(text) => {
+                        cell.set_value(text);
+                    }
+                    CellValue::Number(number) => {
+                        cell.set_value_number(number);
+                    }
+                    CellValue::Date(date) => {
+                        cell.set_value_number(excel_serial_date(
+                            date.and_hms_opt(0, 0, 0).unwrap(),
+                        ));
+                    }
+                    CellValue::DateTime(datetime) => {
+                        cell.set_value_number(excel_serial_date(datetime));
+                    }
+                    CellValue::Boolean(bool) => {
+                        cell.set_value_bool(bool);
+                    }
+                };
+                // Rows can have more cells than there are columns, e.g. from html reports
+                let format = sheet
+                    .columns
+                    .get(column_index)
+                    .and_then(|column| column.format.as_ref());
+                if let Some(format) = format {
+                    cell.get_style_mut()
+                        .get_number_format_mut()
+                        .set_format_code(format.clone());
+                }
+            }
+        }
+    }
+
+    umya_spreadsheet::writer::xlsx::write(&book, path)
+        .map_err(|err| ReportError::DocGenerationError(format!("{}", err)))
+}
+
+/// Comma separated values with a header line, dates are in ISO 8601 format
+pub(crate) fn to_csv(sheet: &Sheet) -> String {
+    let mut lines = vec![sheet
+        .columns
+        .iter()
+        .map(|column| csv_escape(&column.title))
+        .collect::<Vec<_>>()
+        .join(",")];
+
+    for row in &sheet.rows {
+        lines.push(
+            row.iter()
+                .map(|value| match value {
+                    CellValue::Empty => String::new(),
+                    CellValue::Text(text) => csv_escape(text),
+                    CellValue::Number(number) => number.to_string(),
+                    CellValue::Date(date) => date.format("%Y-%m-%d").to_string(),
+                    CellValue::DateTime(datetime) => {
+                        datetime.format("%Y-%m-%dT%H:%M:%S").to_string()
+                    }
+                    CellValue::Boolean(bool) => bool.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+    }
+
+    let mut csv = lines.join("\r\n");
+    csv.push_str("\r\n");
+    csv
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    use crate::report::definition::SpreadsheetTemplate;
+
+    use super::{
+        column_name, sheets_from_data, to_csv, write_excel, CellValue, Sheet, SheetColumn,
+    };
+
+    #[test]
+    fn test_sheets_from_data() {
+        let template: SpreadsheetTemplate = serde_json::from_value(json!({
+            "sheets": [{
+                "name": "Stock",
+                "rows": "/data/stockLines/nodes",
+                "columns": [
+                    { "title": "Item", "value": "/item/name", "width": 30.0 },
+                    { "title": "Packs", "value": "/packs", "type": "Number", "group": "Stock" },
+                    { "title": "Expiry", "value": "/expiryDate", "type": "Date", "group": "Stock" },
+                ]
+            }, {
+                "name": "Empty",
+                "rows": "/data/missing",
+                "columns": [{ "title": "Name", "value": "/name" }]
+            }]
+        }))
+        .unwrap();
+
+        let data = json!({
+            "data": {
+                "stockLines": {
+                    "nodes": [
+                        { "item": { "name": "Item, A" }, "packs": 10.5, "expiryDate": "2025-01-31" },
+                        { "item": { "name": "Item B" }, "packs": "2", "expiryDate": null },
+                        { "item": { "name": "Item C" }, "packs": "n/a", "expiryDate": "invalid" }
+                    ]
+                }
+            },
+            "arguments": null
+        });
+
+        let sheets = sheets_from_data(&template, &data).unwrap();
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(sheets[1].rows.len(), 0);
+
+        let sheet = &sheets[0];
+        assert_eq!(
+            sheet.columns[2],
+            SheetColumn {
+                title: "Expiry".to_string(),
+                group: Some("Stock".to_string()),
+                width: None,
+                format: Some("yyyy-mm-dd".to_string()),
+            }
+        );
+        assert_eq!(
+            sheet.rows,
+            vec![
+                vec![
+                    CellValue::Text("Item, A".to_string()),
+                    CellValue::Number(10.5),
+                    CellValue::Date(NaiveDate::from_ymd_opt(2025, 1, 31).unwrap()),
+                ],
+                vec![
+                    CellValue::Text("Item B".to_string()),
+                    CellValue::Number(2.0),
+                    CellValue::Empty,
+                ],
+                vec![
+                    CellValue::Text("Item C".to_string()),
+                    CellValue::Text("n/a".to_string()),
+                    CellValue::Text("invalid".to_string()),
+                ]
+            ]
+        );
+
+        assert_eq!(
+            to_csv(sheet),
+            "Item,Packs,Expiry\r\n\"Item, A\",10.5,2025-01-31\r\nItem B,2,\r\nItem C,n/a,invalid\r\n"
+        );
+
+        // Rows are not an array
+        let data = json!({ "data": { "stockLines": { "nodes": "invalid" } } });
+        assert!(sheets_from_data(&template, &data).is_err());
+    }
+
+    #[test]
+    fn test_column_name() {
+        assert_eq!(column_name(1), "A");
+        assert_eq!(column_name(26), "Z");
+        assert_eq!(column_name(27), "AA");
+        assert_eq!(column_name(53), "BA");
+    }
+
+    #[test]
+    fn test_write_excel_ragged_row() {
+        let sheet = Sheet {
+            name: "Report".to_string(),
+            columns: vec![SheetColumn {
+                title: "Item".to_string(),
+                ..Default::default()
+            }],
+            rows: vec![
+                vec![CellValue::Text("Item A".to_string())],
+                // More cells than columns
+                vec![
+                    CellValue::Text("Item B".to_string()),
+                    CellValue::Number(10.0),
+                    CellValue::Text("extra".to_string()),
+                ],
+            ],
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.xlsx");
+        write_excel(vec![sheet], &path).unwrap();
+
+        let book = umya_spreadsheet::reader::xlsx::read(&path).unwrap();
+        let worksheet = book.get_sheet_by_name("Report").unwrap();
+        assert_eq!(worksheet.get_value((1, 3)), "Item B");
+        assert_eq!(worksheet.get_value((3, 3)), "extra");
+    }
+}
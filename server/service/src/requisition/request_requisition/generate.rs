@@ -1,7 +1,13 @@
+use std::collections::HashMap;
+
 use chrono::Utc;
-use repository::{EqualFilter, RepositoryError, RequisitionLineRow, RequisitionRow};
+use repository::{
+    EqualFilter, ForecastMethod, RepositoryError, RequisitionLineRow, RequisitionRow,
+    StorePreferenceRowRepository,
+};
 use util::uuid::uuid;
 
+use crate::forecasting::{get_item_forecasts, ForecastOptions};
 use crate::item_stats::{get_item_stats, ItemStatsFilter};
 use crate::service_provider::ServiceContext;
 
@@ -48,13 +54,38 @@ pub fn generate_requisition_lines(
         ctx,
         store_id,
         None,
-        Some(ItemStatsFilter::new().item_id(EqualFilter::equal_any(item_ids))),
+        Some(ItemStatsFilter::new().item_id(EqualFilter::equal_any(item_ids.clone()))),
     )?;
 
+    // Suggested quantities use the forecast monthly consumption if the store uses a forecast
+    // method other than plain average monthly consumption
+    let forecast_method = StorePreferenceRowRepository::new(&ctx.connection)
+        .find_one_by_id(store_id)?
+        .map(|preference| preference.forecast_method)
+        .unwrap_or_default();
+    let forecasts: HashMap<String, f64> = match forecast_method {
+        ForecastMethod::AverageMonthlyConsumption => HashMap::new(),
+        method => get_item_forecasts(
+            &ctx.connection,
+            store_id,
+            item_ids,
+            ForecastOptions {
+                method: Some(method),
+                lookback_months: None,
+            },
+        )?
+        .into_iter()
+        .map(|forecast| (forecast.item_id, forecast.forecast_monthly_consumption))
+        .collect(),
+    };
+
     let result = item_stats_rows
         .into_iter()
         .map(|item_stats| {
-            let average_monthly_consumption = item_stats.average_monthly_consumption;
+            let average_monthly_consumption = forecasts
+                .get(&item_stats.item_id)
+                .copied()
+                .unwrap_or(item_stats.average_monthly_consumption);
             let available_stock_on_hand = item_stats.available_stock_on_hand;
             let suggested_quantity = generate_suggested_quantity(GenerateSuggestedQuantity {
                 average_monthly_consumption,
@@ -1,4 +1,5 @@
 use crate::{
+    item::unit_of_measure::{quantity_to_base_units, ConvertQuantityError, QuantityInUnit},
     requisition::common::check_requisition_row_exists,
     requisition_line::{common::check_requisition_line_exists, query::get_requisition_line},
     service_provider::ServiceContext,
@@ -16,6 +17,9 @@ pub struct UpdateRequestRequisitionLine {
     pub id: String,
     pub requested_quantity: Option<f64>,
     pub comment: Option<String>,
+    /// Requested quantity in one of the item's units of measure, replaces requested_quantity
+    /// (in the item's base unit) when set
+    pub requested_quantity_in_unit: Option<QuantityInUnit>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -27,6 +31,7 @@ pub enum UpdateRequestRequisitionLineError {
     NotARequestRequisition,
     UpdatedRequisitionLineDoesNotExist,
     RequisitionDoesNotExist,
+    InvalidQuantityInUnit(ConvertQuantityError),
     DatabaseError(RepositoryError),
 }
 
@@ -39,6 +44,7 @@ pub fn update_request_requisition_line(
     let requisition_line = ctx
         .connection
         .transaction_sync(|connection| {
+            let input = resolve_quantity_in_unit(connection, input)?;
             let requisition_row = validate(connection, &ctx.store_id, &input)?;
             let updated_requisition_line_row = generate(requisition_row, input);
 
@@ -53,6 +59,26 @@ pub fn update_request_requisition_line(
     Ok(requisition_line)
 }
 
+fn resolve_quantity_in_unit(
+    connection: &StorageConnection,
+    mut input: UpdateRequestRequisitionLine,
+) -> Result<UpdateRequestRequisitionLine, OutError> {
+    let Some(quantity_in_unit) = input.requested_quantity_in_unit.take() else {
+        return Ok(input);
+    };
+    // Missing line is reported by validation
+    let Some(requisition_line) = check_requisition_line_exists(connection, &input.id)? else {
+        return Ok(input);
+    };
+
+    input.requested_quantity = Some(
+        quantity_to_base_units(connection, &requisition_line.item_row.id, &quantity_in_unit)
+            .map_err(OutError::InvalidQuantityInUnit)?,
+    );
+
+    Ok(input)
+}
+
 fn validate(
     connection: &StorageConnection,
     store_id: &str,
@@ -87,6 +113,7 @@ fn generate(
         id: _,
         requested_quantity: updated_requested_quantity,
         comment: updated_comment,
+        requested_quantity_in_unit: _,
     }: UpdateRequestRequisitionLine,
 ) -> RequisitionLineRow {
     inline_edit(&existing, |mut u| {
@@ -207,6 +234,7 @@ mod test {
                     id: test_line.id.clone(),
                     requested_quantity: Some(99.0),
                     comment: Some("comment".to_string()),
+                    requested_quantity_in_unit: None,
                 },
             )
             .unwrap();
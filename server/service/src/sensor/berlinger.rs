@@ -1,17 +1,18 @@
+use super::logger_file::{parse_logger_file, LoggerFile, LoggerFileParser};
 use super::update::update_sensor_logs_for_breach;
 use anyhow::Context;
-use chrono::{Local, LocalResult, NaiveDateTime, TimeZone};
+use chrono::NaiveDateTime;
 use repository::{DatetimeFilter, EqualFilter};
 use repository::{
     RepositoryError, Sensor, SensorFilter, SensorRepository, SensorRow, SensorRowRepository,
-    SensorType, StorageConnection, TemperatureBreach, TemperatureBreachConfig,
-    TemperatureBreachConfigFilter, TemperatureBreachConfigRepository, TemperatureBreachConfigRow,
+    StorageConnection, TemperatureBreach, TemperatureBreachConfig, TemperatureBreachConfigFilter,
+    TemperatureBreachConfigRepository, TemperatureBreachConfigRow,
     TemperatureBreachConfigRowRepository, TemperatureBreachFilter, TemperatureBreachRepository,
     TemperatureBreachRow, TemperatureBreachRowRepository, TemperatureBreachType, TemperatureLog,
     TemperatureLogFilter, TemperatureLogRepository, TemperatureLogRow, TemperatureLogRowRepository,
 };
 use serde::Serialize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use util::uuid::uuid;
 
@@ -235,29 +236,29 @@ fn sensor_add_breach_config_if_new(
 fn sensor_add_if_new(
     connection: &StorageConnection,
     store_id: &str,
-    temperature_sensor: &temperature_sensor::Sensor,
+    logger_file: &LoggerFile,
 ) -> Result<Option<String>, RepositoryError> {
-    let result = get_matching_sensor_serial(connection, &temperature_sensor.serial)?;
+    let result = get_matching_sensor_serial(connection, &logger_file.serial)?;
 
     if !result.is_empty() {
         return Ok(None);
     };
 
     let mut interval_seconds = None;
-    if let Some(interval_duration) = temperature_sensor.log_interval {
+    if let Some(interval_duration) = logger_file.log_interval {
         interval_seconds = Some(interval_duration.num_seconds() as i32);
     }
     let new_sensor = SensorRow {
         id: uuid(),
-        serial: temperature_sensor.serial.clone(),
-        name: temperature_sensor.name.clone(),
+        serial: logger_file.serial.clone(),
+        name: logger_file.name.clone(),
         store_id: store_id.to_string(),
         location_id: None,
         last_connection_datetime: None,
         battery_level: None,
         is_active: true,
         log_interval: interval_seconds,
-        r#type: SensorType::Berlinger,
+        r#type: logger_file.sensor_type.clone(),
     };
     SensorRowRepository::new(connection).upsert_one(&new_sensor)?;
     log::info!("Added sensor {:?} ", new_sensor);
@@ -282,120 +283,43 @@ pub enum ReadSensorError {
     Other(#[from] anyhow::Error),
 }
 
-fn convert_from_localtime(
-    sensor: &temperature_sensor::Sensor,
-) -> Result<temperature_sensor::Sensor, ReadSensorError> {
-    // map logs
-    let logs_mapped: Option<Vec<temperature_sensor::TemperatureLog>> = match sensor.clone().logs {
-        None => None,
-        Some(logs) => Some(
-            logs.into_iter()
-                .map(
-                    |temperature_sensor::TemperatureLog {
-                         timestamp,
-                         temperature,
-                     }| {
-                        let local = match Local.from_local_datetime(&timestamp) {
-                            LocalResult::None => {
-                                return Err(anyhow::anyhow!("Cannot convert to local timestamp"))
-                            }
-                            LocalResult::Single(r) => r,
-                            LocalResult::Ambiguous(r, _) => r,
-                        };
-                        Ok(temperature_sensor::TemperatureLog {
-                            temperature,
-                            timestamp: local.naive_utc(),
-                        })
-                    },
-                )
-                .collect::<Result<_, _>>()?,
-        ),
-    };
-    // map temperature breaches
-    let breaches_mapped: Option<Vec<temperature_sensor::TemperatureBreach>> = match sensor
-        .clone()
-        .breaches
-    {
-        None => None,
-        Some(breaches) => Some(
-            breaches
-                .into_iter()
-                .map(
-                    |temperature_sensor::TemperatureBreach {
-                         breach_type,
-                         start_timestamp,
-                         end_timestamp,
-                         duration,
-                         acknowledged,
-                     }| {
-                        let local_start = match Local.from_local_datetime(&start_timestamp) {
-                            LocalResult::None => {
-                                return Err(anyhow::anyhow!("Cannot convert to local timestamp"))
-                            }
-                            LocalResult::Single(r) => r,
-                            LocalResult::Ambiguous(r, _) => r,
-                        };
-                        let local_end = match Local.from_local_datetime(&end_timestamp) {
-                            LocalResult::None => {
-                                return Err(anyhow::anyhow!("Cannot convert to local timestamp"))
-                            }
-                            LocalResult::Single(r) => r,
-                            LocalResult::Ambiguous(r, _) => r,
-                        };
-                        Ok(temperature_sensor::TemperatureBreach {
-                            breach_type,
-                            start_timestamp: local_start.naive_utc(),
-                            end_timestamp: local_end.naive_utc(),
-                            duration,
-                            acknowledged,
-                        })
-                    },
-                )
-                .collect::<Result<_, _>>()?,
-        ),
-    };
-    // convert last connected timestamp
-    let last_connected_timestamp_converted = match sensor.clone().last_connected_timestamp {
-        None => None,
-        Some(timestamp) => Some(match Local.from_local_datetime(&timestamp) {
-            LocalResult::None => {
-                return Err(anyhow::anyhow!("Cannot convert to local timestamp").into())
-            }
-            LocalResult::Single(r) => r.naive_utc(),
-            LocalResult::Ambiguous(r, _) => r.naive_utc(),
-        }),
-    };
+/// Berlinger Fridge-tag text file, any file not recognised by other parsers is read as Fridge-tag
+pub struct BerlingerParser;
+
+impl LoggerFileParser for BerlingerParser {
+    fn name(&self) -> &'static str {
+        "Berlinger Fridge-tag"
+    }
 
-    let mut sensor_mapped = sensor.clone();
-    sensor_mapped.last_connected_timestamp = last_connected_timestamp_converted;
-    sensor_mapped.breaches = breaches_mapped;
-    sensor_mapped.logs = logs_mapped;
+    fn can_parse(&self, _: &str, _: &str) -> bool {
+        true
+    }
 
-    Ok(sensor_mapped)
+    fn parse(&self, file: &Path, _: &str) -> Result<LoggerFile, String> {
+        temperature_sensor::read_sensor_file(&file.to_string_lossy()).map(LoggerFile::from)
+    }
 }
 
+/// Reads a Fridge-tag or another logger file supported by `logger_file_parsers` and adds the sensor,
+/// breach configs, logs and breaches
 pub fn read_sensor(
     connection: &StorageConnection,
     store_id: &str,
     fridgetag_file: PathBuf,
 ) -> anyhow::Result<ReadSensor, ReadSensorError> {
-    let filename = fridgetag_file.to_string_lossy();
-
-    let temperature_sensor_unmapped =
-        temperature_sensor::read_sensor_file(&filename).map_err(ReadSensorError::StringError)?;
-    let temperature_sensor = convert_from_localtime(&temperature_sensor_unmapped)?;
+    let logger_file = parse_logger_file(&fridgetag_file)?;
 
-    integrate_sensor_data(connection, store_id, temperature_sensor)
+    integrate_logger_file(connection, store_id, logger_file)
 }
 
-fn integrate_sensor_data(
+fn integrate_logger_file(
     connection: &StorageConnection,
     store_id: &str,
-    temperature_sensor: temperature_sensor::Sensor,
+    logger_file: LoggerFile,
 ) -> anyhow::Result<ReadSensor, ReadSensorError> {
-    let new_sensor_id = sensor_add_if_new(connection, store_id, &temperature_sensor)?;
+    let new_sensor_id = sensor_add_if_new(connection, store_id, &logger_file)?;
 
-    let result = get_matching_sensor_serial(connection, &temperature_sensor.serial)?;
+    let result = get_matching_sensor_serial(connection, &logger_file.serial)?;
 
     let sensor_row = result
         .clone()
@@ -405,17 +329,18 @@ fn integrate_sensor_data(
 
     // Filter sensor data by previous last connected time
     let last_connected = sensor_row.last_connection_datetime;
-    let temperature_sensor =
-        temperature_sensor::filter_sensor(temperature_sensor, last_connected, None);
+    let LoggerFile {
+        configs: temperature_sensor_configs,
+        breaches: temperature_sensor_breaches,
+        logs: temperature_sensor_logs,
+        last_connected_timestamp,
+        ..
+    } = logger_file.filter_since(last_connected);
 
-    let temperature_sensor_configs = temperature_sensor.configs.unwrap_or_default();
     for temperature_sensor_config in temperature_sensor_configs.iter() {
         sensor_add_breach_config_if_new(connection, &sensor_row, temperature_sensor_config)?;
     }
 
-    let temperature_sensor_breaches = temperature_sensor.breaches.unwrap_or_default();
-    let temperature_sensor_logs = temperature_sensor.logs.unwrap_or_default();
-
     let result = ReadSensor {
         new_sensor_id,
         number_of_logs: temperature_sensor_logs.len() as u32,
@@ -449,9 +374,9 @@ fn integrate_sensor_data(
     }
 
     // Finally, update sensor's last connected time if it has changed
-    if sensor_row.last_connection_datetime != temperature_sensor.last_connected_timestamp {
+    if sensor_row.last_connection_datetime != last_connected_timestamp {
         SensorRowRepository::new(connection).upsert_one(&SensorRow {
-            last_connection_datetime: temperature_sensor.last_connected_timestamp,
+            last_connection_datetime: last_connected_timestamp,
             ..sensor_row
         })?;
     }
@@ -485,7 +410,7 @@ fn sort_breaches_by_type(
 #[cfg(test)]
 mod test {
 
-    use super::integrate_sensor_data;
+    use super::integrate_logger_file;
     use crate::{
         sensor::berlinger::breach_sort_weight,
         test_helpers::{setup_all_and_service_provider, ServiceTestContext},
@@ -651,7 +576,7 @@ mod test {
         };
 
         // INTERGRATE MOCK DATA
-        integrate_sensor_data(&connection, &mock_store_a().id, data.clone()).unwrap();
+        integrate_logger_file(&connection, &mock_store_a().id, data.clone().into()).unwrap();
 
         // CHECK BREACHES
         let mut breaches = TemperatureBreachRepository::new(&connection)
@@ -766,7 +691,7 @@ mod test {
         };
 
         // INTERGRATE MOCK DATA
-        integrate_sensor_data(&connection, &mock_store_a().id, s2_data).unwrap();
+        integrate_logger_file(&connection, &mock_store_a().id, s2_data.into()).unwrap();
 
         // CHECK BREACHES
         let mut breaches = TemperatureBreachRepository::new(&connection)
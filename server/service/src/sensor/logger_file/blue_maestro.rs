@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use repository::SensorType;
+
+use super::{generic_csv::parse_log_table, LoggerFile, LoggerFileParser};
+
+/// CSV exported from the Blue Maestro Tempo app, the serial (MAC address) and name of the sensor
+/// are above the log table
+pub struct BlueMaestroParser;
+
+impl LoggerFileParser for BlueMaestroParser {
+    fn name(&self) -> &'static str {
+        "Blue Maestro CSV"
+    }
+
+    fn can_parse(&self, file_name: &str, content: &str) -> bool {
+        let content = content.to_lowercase();
+        file_name.ends_with(".csv")
+            && (content.contains("blue maestro")
+                || content.contains("bluemaestro")
+                || content.contains("tempo"))
+    }
+
+    fn parse(&self, _: &Path, content: &str) -> Result<LoggerFile, String> {
+        parse_log_table(content, SensorType::BlueMaestro)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_blue_maestro_csv() {
+        let content = "Blue Maestro Tempo Disc export\n\
+                       Name,Fridge A\n\
+                       MAC Address,D5:71:3A:0E:2B:19\n\
+                       Logging Interval,15 min\n\
+                       Timestamp,Temperature,Humidity,Dew Point\n\
+                       2024-02-01 10:00,4.5,40,1.0\n\
+                       2024-02-01 10:15,5.1,41,1.2\n";
+
+        let parser = BlueMaestroParser;
+        assert!(parser.can_parse("tempo_export.csv", content));
+        assert!(!parser.can_parse("export.csv", "Date,Temperature\n2024-02-01,4.5"));
+
+        let file = parser
+            .parse(Path::new("tempo_export.csv"), content)
+            .unwrap();
+        assert_eq!(file.sensor_type, SensorType::BlueMaestro);
+        assert_eq!(file.serial, "D5:71:3A:0E:2B:19");
+        assert_eq!(file.name, "Fridge A");
+        assert_eq!(file.log_interval, Some(Duration::minutes(15)));
+        assert_eq!(file.logs.len(), 2);
+        assert_eq!(file.logs[1].temperature, 5.1);
+    }
+}
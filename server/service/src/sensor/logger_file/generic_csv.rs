@@ -0,0 +1,166 @@
+use std::path::Path;
+
+use repository::SensorType;
+use temperature_sensor as ts;
+
+use super::{
+    table::{find_column, parse_date, parse_datetime, parse_duration, parse_time, Table},
+    LoggerFile, LoggerFileParser,
+};
+
+pub(super) const SERIAL_KEYS: &[&str] = &["serial", "s/n", "device id", "logger id", "mac", "id"];
+pub(super) const NAME_KEYS: &[&str] = &["name", "description", "label"];
+const DATETIME_COLUMNS: &[&str] = &["timestamp", "date", "time"];
+const TEMPERATURE_COLUMNS: &[&str] = &["temp"];
+
+/// CSV with a date (and time) column and a temperature column, e.g. LogTag Analyzer exports.
+/// Serial number and name are read from the lines above the header. Breaches are not part of the
+/// file, they are detected from the logs with the store's breach configs
+pub struct GenericCsvParser;
+
+impl LoggerFileParser for GenericCsvParser {
+    fn name(&self) -> &'static str {
+        "CSV temperature log"
+    }
+
+    fn can_parse(&self, file_name: &str, content: &str) -> bool {
+        (file_name.ends_with(".csv") || file_name.ends_with(".tsv"))
+            && Table::read(content, is_log_header).is_some()
+    }
+
+    fn parse(&self, _: &Path, content: &str) -> Result<LoggerFile, String> {
+        let sensor_type = if content.to_lowercase().contains("logtag") {
+            SensorType::LogTag
+        } else {
+            SensorType::Generic
+        };
+        parse_log_table(content, sensor_type)
+    }
+}
+
+pub(super) fn is_log_header(fields: &[String]) -> bool {
+    find_column(fields, TEMPERATURE_COLUMNS).is_some()
+        && find_column(fields, DATETIME_COLUMNS).is_some()
+}
+
+/// Reads logs from a table with a date time column (or separate date and time columns) and a
+/// temperature column, rows that can't be read (e.g. summary at the end) are skipped
+pub(super) fn parse_log_table(
+    content: &str,
+    sensor_type: SensorType,
+) -> Result<LoggerFile, String> {
+    let table = Table::read(content, is_log_header).ok_or("Temperature log header not found")?;
+
+    let serial = table
+        .metadata_value(SERIAL_KEYS)
+        .ok_or("Serial number not found")?;
+    let name = table
+        .metadata_value(NAME_KEYS)
+        .unwrap_or_else(|| serial.clone());
+
+    let temperature_column = table
+        .column(TEMPERATURE_COLUMNS)
+        .ok_or("Temperature column not found")?;
+    let datetime_column = table
+        .column(DATETIME_COLUMNS)
+        .ok_or("Date column not found")?;
+    let time_column = table
+        .header
+        .iter()
+        .enumerate()
+        .position(|(index, column)| index != datetime_column && column.contains("time"));
+
+    let mut logs: Vec<ts::TemperatureLog> = table
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let datetime = row.get(datetime_column)?;
+            let timestamp = parse_datetime(datetime).or_else(|| {
+                let time = parse_time(row.get(time_column?)?)?;
+                Some(parse_date(datetime)?.and_time(time))
+            })?;
+            let temperature = table.temperature(row.get(temperature_column)?)?;
+            Some(ts::TemperatureLog {
+                temperature,
+                timestamp,
+            })
+        })
+        .collect();
+    if logs.is_empty() {
+        return Err("No temperature logs found".to_string());
+    }
+    logs.sort_by_key(|log| log.timestamp);
+
+    let log_interval = table
+        .metadata_value(&["interval"])
+        .and_then(|interval| parse_duration(&interval))
+        .or_else(|| match logs.as_slice() {
+            [first, second, ..] => Some(second.timestamp - first.timestamp),
+            _ => None,
+        });
+
+    Ok(LoggerFile {
+        sensor_type,
+        serial,
+        name,
+        log_interval,
+        last_connected_timestamp: logs.last().map(|log| log.timestamp),
+        configs: Vec::new(),
+        breaches: Vec::new(),
+        logs,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, NaiveDate};
+
+    use super::*;
+
+    #[test]
+    fn test_generic_csv() {
+        let content = "LogTag Analyzer export\n\
+                       Serial Number,1234567\n\
+                       Description,Vaccine fridge\n\
+                       Date,Time,Temperature (°C)\n\
+                       01/02/2024,10:00,4.5\n\
+                       01/02/2024,10:10,8.5\n\
+                       Maximum,,8.5\n";
+
+        let parser = GenericCsvParser;
+        assert!(parser.can_parse("export.csv", content));
+        assert!(!parser.can_parse("export.txt", content));
+        assert!(!parser.can_parse("export.csv", "Date,Humidity\n01/02/2024,40"));
+
+        let file = parser.parse(Path::new("export.csv"), content).unwrap();
+        assert_eq!(file.sensor_type, SensorType::LogTag);
+        assert_eq!(file.serial, "1234567");
+        assert_eq!(file.name, "Vaccine fridge");
+        assert_eq!(file.log_interval, Some(Duration::minutes(10)));
+
+        let date = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        let logs: Vec<_> = file
+            .logs
+            .iter()
+            .map(|log| (log.timestamp, log.temperature))
+            .collect();
+        assert_eq!(
+            logs,
+            vec![
+                (date.and_hms_opt(10, 0, 0).unwrap(), 4.5),
+                (date.and_hms_opt(10, 10, 0).unwrap(), 8.5)
+            ]
+        );
+        assert_eq!(file.last_connected_timestamp, date.and_hms_opt(10, 10, 0));
+
+        assert_eq!(
+            parser
+                .parse(
+                    Path::new("export.csv"),
+                    "Date,Temperature\n01/02/2024 10:00,4.5"
+                )
+                .err(),
+            Some("Serial number not found".to_string())
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::{fs, path::Path};
+
+use chrono::{Duration, Local, LocalResult, NaiveDateTime, TimeZone};
+use repository::SensorType;
+use temperature_sensor as ts;
+
+use super::berlinger::{BerlingerParser, ReadSensorError};
+
+use self::{
+    blue_maestro::BlueMaestroParser, generic_csv::GenericCsvParser,
+    thirty_day_recorder::ThirtyDayRecorderParser,
+};
+
+pub mod blue_maestro;
+pub mod generic_csv;
+mod table;
+pub mod thirty_day_recorder;
+
+/// Sensor, logs, breach configs and breaches read from a logger file
+#[derive(Clone)]
+pub struct LoggerFile {
+    pub sensor_type: SensorType,
+    pub serial: String,
+    pub name: String,
+    pub log_interval: Option<Duration>,
+    pub last_connected_timestamp: Option<NaiveDateTime>,
+    pub configs: Vec<ts::TemperatureBreachConfig>,
+    pub breaches: Vec<ts::TemperatureBreach>,
+    pub logs: Vec<ts::TemperatureLog>,
+}
+
+pub trait LoggerFileParser {
+    /// Name of the file format, used in errors
+    fn name(&self) -> &'static str;
+
+    /// Checks if the file is in this format, `file_name` is lower case
+    fn can_parse(&self, file_name: &str, content: &str) -> bool;
+
+    /// Reads the file, timestamps are returned as they are in the file (local time)
+    fn parse(&self, file: &Path, content: &str) -> Result<LoggerFile, String>;
+}
+
+/// Parsers are tried in order and the first one that can parse the file is used.
+/// To support another file format implement `LoggerFileParser` and add it to this list,
+/// Berlinger is last as it's used for any file not recognised by other parsers
+pub fn logger_file_parsers() -> Vec<Box<dyn LoggerFileParser>> {
+    vec![
+        Box::new(BlueMaestroParser),
+        Box::new(ThirtyDayRecorderParser),
+        Box::new(GenericCsvParser),
+        Box::new(BerlingerParser),
+    ]
+}
+
+/// Reads a logger file with the first parser that recognises it, timestamps are converted from
+/// local time to UTC
+pub fn parse_logger_file(file: &Path) -> Result<LoggerFile, ReadSensorError> {
+    let bytes = fs::read(file).map_err(|error| ReadSensorError::Other(error.into()))?;
+    let content = String::from_utf8_lossy(&bytes);
+    let content = content.trim_start_matches('\u{feff}');
+    let file_name = file
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let parser = logger_file_parsers()
+        .into_iter()
+        .find(|parser| parser.can_parse(&file_name, content))
+        .ok_or_else(|| ReadSensorError::StringError("Unknown logger file format".to_string()))?;
+
+    let logger_file = parser
+        .parse(file, content)
+        .map_err(|error| ReadSensorError::StringError(format!("{}: {error}", parser.name())))?;
+
+    convert_from_localtime(logger_file)
+}
+
+fn local_to_utc(datetime: NaiveDateTime) -> Result<NaiveDateTime, ReadSensorError> {
+    match Local.from_local_datetime(&datetime) {
+        LocalResult::None => Err(anyhow::anyhow!("Cannot convert to local timestamp").into()),
+        LocalResult::Single(r) => Ok(r.naive_utc()),
+        LocalResult::Ambiguous(r, _) => Ok(r.naive_utc()),
+    }
+}
+
+fn convert_from_localtime(logger_file: LoggerFile) -> Result<LoggerFile, ReadSensorError> {
+    let logs = logger_file
+        .logs
+        .into_iter()
+        .map(|log| {
+            Ok(ts::TemperatureLog {
+                timestamp: local_to_utc(log.timestamp)?,
+                ..log
+            })
+        })
+        .collect::<Result<_, ReadSensorError>>()?;
+
+    let breaches = logger_file
+        .breaches
+        .into_iter()
+        .map(|breach| {
+            Ok(ts::TemperatureBreach {
+                start_timestamp: local_to_utc(breach.start_timestamp)?,
+                end_timestamp: local_to_utc(breach.end_timestamp)?,
+                ..breach
+            })
+        })
+        .collect::<Result<_, ReadSensorError>>()?;
+
+    let last_connected_timestamp = logger_file
+        .last_connected_timestamp
+        .map(local_to_utc)
+        .transpose()?;
+
+    Ok(LoggerFile {
+        logs,
+        breaches,
+        last_connected_timestamp,
+        ..logger_file
+    })
+}
+
+impl LoggerFile {
+    /// Only keeps logs and breaches from `last_connected` onwards, earlier data has been
+    /// integrated when the file was last read
+    pub(crate) fn filter_since(self, last_connected: Option<NaiveDateTime>) -> LoggerFile {
+        let Some(last_connected) = last_connected else {
+            return self;
+        };
+
+        LoggerFile {
+            logs: self
+                .logs
+                .into_iter()
+                .filter(|log| log.timestamp >= last_connected)
+                .collect(),
+            breaches: self
+                .breaches
+                .into_iter()
+                .filter(|breach| breach.end_timestamp >= last_connected)
+                .collect(),
+            ..self
+        }
+    }
+}
+
+impl From<ts::Sensor> for LoggerFile {
+    fn from(sensor: ts::Sensor) -> Self {
+        LoggerFile {
+            sensor_type: SensorType::Berlinger,
+            serial: sensor.serial,
+            name: sensor.name,
+            log_interval: sensor.log_interval,
+            last_connected_timestamp: sensor.last_connected_timestamp,
+            configs: sensor.configs.unwrap_or_default(),
+            breaches: sensor.breaches.unwrap_or_default(),
+            logs: sensor.logs.unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+    "%d/%m/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d-%m-%Y %H:%M:%S",
+    "%d-%m-%Y %H:%M",
+    "%d %b %Y %H:%M:%S",
+    "%d %b %Y %H:%M",
+    "%d-%b-%Y %H:%M:%S",
+    "%d-%b-%Y %H:%M",
+];
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d", "%Y/%m/%d", "%d/%m/%Y", "%d.%m.%Y", "%d-%m-%Y", "%d %b %Y", "%d-%b-%Y",
+];
+const TIME_FORMATS: &[&str] = &["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+
+/// Rows of a delimited text file (CSV, semicolon or tab separated, or text copied from a PDF
+/// report with columns separated by multiple spaces). Lines before the header are read as
+/// "key: value" or "key,value" metadata
+pub(super) struct Table {
+    /// Lower case metadata keys
+    pub(super) metadata: HashMap<String, String>,
+    /// Lower case column names
+    pub(super) header: Vec<String>,
+    pub(super) rows: Vec<Vec<String>>,
+    /// Decimal separator is a comma when columns are separated with semicolons
+    decimal_comma: bool,
+}
+
+impl Table {
+    /// Reads the table, the header is the first line for which `is_header` returns true
+    pub(super) fn read(content: &str, is_header: impl Fn(&[String]) -> bool) -> Option<Table> {
+        let mut metadata = HashMap::new();
+        let mut lines = content.lines();
+
+        let (header, decimal_comma) = loop {
+            let line = lines.next()?;
+            let delimiter = delimiter(line);
+            let fields: Vec<String> = split_line(line, delimiter)
+                .into_iter()
+                .map(|field| field.to_lowercase())
+                .collect();
+            if is_header(&fields) {
+                break (fields, delimiter == Delimiter::Semicolon);
+            }
+            if let Some((key, value)) = metadata_entry(line) {
+                metadata.entry(key).or_insert(value);
+            }
+        };
+
+        let rows = lines
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| split_line(line, delimiter(line)))
+            .collect();
+
+        Some(Table {
+            metadata,
+            header,
+            rows,
+            decimal_comma,
+        })
+    }
+
+    /// Index of the first column that contains any of the names
+    pub(super) fn column(&self, names: &[&str]) -> Option<usize> {
+        find_column(&self.header, names)
+    }
+
+    /// First metadata value with a key containing any of the names
+    pub(super) fn metadata_value(&self, names: &[&str]) -> Option<String> {
+        names.iter().find_map(|name| {
+            let mut keys: Vec<&String> = self
+                .metadata
+                .keys()
+                .filter(|key| key.contains(name))
+                .collect();
+            keys.sort();
+            keys.first()
+                .and_then(|key| self.metadata.get(*key))
+                .filter(|value| !value.is_empty())
+                .cloned()
+        })
+    }
+
+    pub(super) fn temperature(&self, value: &str) -> Option<f64> {
+        let value = if self.decimal_comma {
+            value.replace(',', ".")
+        } else {
+            value.to_string()
+        };
+        parse_temperature(&value)
+    }
+}
+
+pub(super) fn find_column(header: &[String], names: &[&str]) -> Option<usize> {
+    header
+        .iter()
+        .position(|column| names.iter().any(|name| column.contains(name)))
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Delimiter {
+    Comma,
+    Semicolon,
+    Tab,
+    Spaces,
+}
+
+fn delimiter(line: &str) -> Delimiter {
+    let count = |c: char| line.matches(c).count();
+    match (count('\t'), count(';'), count(',')) {
+        (tabs, _, _) if tabs > 0 => Delimiter::Tab,
+        (_, semicolons, commas) if semicolons > 0 && semicolons >= commas => Delimiter::Semicolon,
+        (_, _, commas) if commas > 0 => Delimiter::Comma,
+        _ => Delimiter::Spaces,
+    }
+}
+
+/// Splits a line into trimmed fields, double quoted fields can contain the delimiter
+fn split_line(line: &str, delimiter: Delimiter) -> Vec<String> {
+    let separator = match delimiter {
+        Delimiter::Comma => ',',
+        Delimiter::Semicolon => ';',
+        Delimiter::Tab => '\t',
+        Delimiter::Spaces => {
+            // Single spaces are part of values (e.g. date and time), columns are separated by more
+            return line
+                .split("  ")
+                .map(str::trim)
+                .filter(|field| !field.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+    };
+
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == separator && !in_quotes => {
+                fields.push(field.trim().to_string());
+                field.clear();
+            }
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// "key: value" when columns are separated by spaces (e.g. PDF text), otherwise first two columns
+fn metadata_entry(line: &str) -> Option<(String, String)> {
+    let (key, value) = match (delimiter(line), line.split_once(':')) {
+        (Delimiter::Spaces, Some((key, value))) => (key.to_string(), value.to_string()),
+        (delimiter, _) => {
+            let mut fields = split_line(line, delimiter).into_iter();
+            (fields.next()?, fields.next().unwrap_or_default())
+        }
+    };
+
+    let key = key
+        .trim()
+        .trim_end_matches(':')
+        .trim_matches('"')
+        .trim()
+        .to_lowercase();
+    let value = value.trim().trim_matches('"').trim().to_string();
+    (!key.is_empty()).then_some((key, value))
+}
+
+pub(super) fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+pub(super) fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+pub(super) fn parse_time(value: &str) -> Option<NaiveTime> {
+    let value = value.trim();
+    TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+}
+
+/// Temperature in degrees Celsius, e.g. "4.5", "+8.0 °C" or "-0.5C"
+pub(super) fn parse_temperature(value: &str) -> Option<f64> {
+    let value = value
+        .trim()
+        .trim_end_matches(['C', 'c'])
+        .trim_end()
+        .trim_end_matches('°')
+        .trim();
+    value.trim_start_matches('+').parse().ok()
+}
+
+/// Durations like "10h", "1h 30m", "90 min", "01:30" (hours and minutes) or "45" (minutes)
+pub(super) fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim().to_lowercase();
+    if value.is_empty() {
+        return None;
+    }
+
+    if let Some((hours, minutes)) = value.split_once(':') {
+        let hours: i64 = hours.trim().parse().ok()?;
+        let minutes: i64 = minutes.trim().parse().ok()?;
+        return Some(Duration::hours(hours) + Duration::minutes(minutes));
+    }
+
+    if let Ok(minutes) = value.parse::<i64>() {
+        return Some(Duration::minutes(minutes));
+    }
+
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+    let mut found = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        if c.is_whitespace() || number.is_empty() {
+            continue;
+        }
+        let amount: f64 = number.parse().ok()?;
+        number.clear();
+        let seconds = match c {
+            'd' => 24.0 * 60.0 * 60.0,
+            'h' => 60.0 * 60.0,
+            'm' => 60.0,
+            's' => 1.0,
+            _ => return None,
+        };
+        // Skip rest of the unit, e.g. "min" or "hours"
+        while chars.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            chars.next();
+        }
+        duration = duration + Duration::seconds((amount * seconds) as i64);
+        found = true;
+    }
+
+    (found && number.is_empty()).then_some(duration)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_table() {
+        let content = "Device name,Fridge 1\n\
+                       Serial Number: \"AB-123\"\n\
+                       \n\
+                       Date;Temperature (°C)\n\
+                       2024-01-01 10:00;4,5\n\
+                       \"2024-01-01 10:15\";-0,5\n";
+
+        let table = Table::read(content, |fields| {
+            find_column(fields, &["temperature"]).is_some()
+        })
+        .unwrap();
+        assert_eq!(
+            table.metadata_value(&["serial"]),
+            Some("AB-123".to_string())
+        );
+        assert_eq!(
+            table.metadata_value(&["name"]),
+            Some("Fridge 1".to_string())
+        );
+        assert_eq!(table.column(&["date"]), Some(0));
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[1][0], "2024-01-01 10:15");
+        assert_eq!(table.temperature(&table.rows[1][1]), Some(-0.5));
+
+        let pdf_text = "Date        Min °C     Max °C\n01.02.2024  +2.1       +8.4\n";
+        let table =
+            Table::read(pdf_text, |fields| find_column(fields, &["max"]).is_some()).unwrap();
+        assert_eq!(table.rows, vec![vec!["01.02.2024", "+2.1", "+8.4"]]);
+    }
+
+    #[test]
+    fn test_parse_values() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        assert_eq!(
+            parse_datetime("01/02/2024 13:05"),
+            date.and_hms_opt(13, 5, 0)
+        );
+        assert_eq!(parse_date("01-Feb-2024"), Some(date));
+        assert_eq!(parse_time("1:05 PM"), NaiveTime::from_hms_opt(13, 5, 0));
+
+        assert_eq!(parse_temperature("+8.0 °C"), Some(8.0));
+        assert_eq!(parse_temperature("-0.5C"), Some(-0.5));
+        assert_eq!(parse_temperature("n/a"), None);
+
+        assert_eq!(parse_duration("10h"), Some(Duration::hours(10)));
+        assert_eq!(parse_duration("1h 30min"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("01:30"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("60"), Some(Duration::minutes(60)));
+        assert_eq!(parse_duration("8.0"), None);
+        assert_eq!(parse_duration("abc"), None);
+    }
+}
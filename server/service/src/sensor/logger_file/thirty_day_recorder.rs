@@ -0,0 +1,269 @@
+use std::path::Path;
+
+use chrono::{Duration, NaiveDate, NaiveTime};
+use repository::SensorType;
+use temperature_sensor as ts;
+
+use super::{
+    generic_csv::{NAME_KEYS, SERIAL_KEYS},
+    table::{find_column, parse_date, parse_duration, parse_temperature, Table},
+    LoggerFile, LoggerFileParser,
+};
+
+/// WHO PQS default alarms of 30 day temperature recorders (30DTR)
+const DEFAULT_HIGH_ALARM_TEMPERATURE: f64 = 8.0;
+const DEFAULT_HIGH_ALARM_MINUTES: i64 = 10 * 60;
+const DEFAULT_LOW_ALARM_TEMPERATURE: f64 = -0.5;
+const DEFAULT_LOW_ALARM_MINUTES: i64 = 60;
+
+/// Daily summary report of a 30 day temperature recorder, as CSV or as text copied from the PDF
+/// report. Each day has a minimum and maximum temperature and the time spent above the high
+/// alarm and below the low alarm temperature.
+/// Minimum and maximum are stored as two logs per day (at 00:00 and 12:00), breaches are the days
+/// on which the time outside the alarm temperature exceeded the alarm duration
+pub struct ThirtyDayRecorderParser;
+
+impl LoggerFileParser for ThirtyDayRecorderParser {
+    fn name(&self) -> &'static str {
+        "30 day temperature recorder report"
+    }
+
+    fn can_parse(&self, _: &str, content: &str) -> bool {
+        let lower_case = content.to_lowercase();
+        if lower_case.contains("fridge-tag") || lower_case.contains("berlinger") {
+            return false;
+        }
+        Table::read(content, is_summary_header).is_some()
+    }
+
+    fn parse(&self, _: &Path, content: &str) -> Result<LoggerFile, String> {
+        let table = Table::read(content, is_summary_header).ok_or("Daily summary not found")?;
+
+        let serial = table
+            .metadata_value(SERIAL_KEYS)
+            .ok_or("Serial number not found")?;
+        let name = table
+            .metadata_value(NAME_KEYS)
+            .unwrap_or_else(|| serial.clone());
+
+        let (high_temperature, high_duration) = table
+            .metadata_value(&["high alarm", "upper alarm", "high limit"])
+            .and_then(|alarm| parse_alarm(&alarm))
+            .unwrap_or((
+                DEFAULT_HIGH_ALARM_TEMPERATURE,
+                Duration::minutes(DEFAULT_HIGH_ALARM_MINUTES),
+            ));
+        let (low_temperature, low_duration) = table
+            .metadata_value(&["low alarm", "lower alarm", "low limit"])
+            .and_then(|alarm| parse_alarm(&alarm))
+            .unwrap_or((
+                DEFAULT_LOW_ALARM_TEMPERATURE,
+                Duration::minutes(DEFAULT_LOW_ALARM_MINUTES),
+            ));
+        let configs = vec![
+            ts::TemperatureBreachConfig {
+                breach_type: ts::BreachType::HotCumulative,
+                maximum_temperature: high_temperature,
+                minimum_temperature: -273.0,
+                duration: high_duration,
+            },
+            ts::TemperatureBreachConfig {
+                breach_type: ts::BreachType::ColdCumulative,
+                maximum_temperature: 100.0,
+                minimum_temperature: low_temperature,
+                duration: low_duration,
+            },
+        ];
+
+        let header = &table.header;
+        let date_column = find_column(header, &["date", "day"]).ok_or("Date column not found")?;
+        let min_column = column_starting_with(header, "min").ok_or("Minimum column not found")?;
+        let max_column = column_starting_with(header, "max").ok_or("Maximum column not found")?;
+        let high_column = find_column(header, &["high", "upper", "above"]);
+        let low_column = find_column(header, &["low", "lower", "below"]);
+
+        let mut days: Vec<Day> = table
+            .rows
+            .iter()
+            .filter_map(|row| {
+                let alarm_time = |column: Option<usize>| {
+                    column
+                        .and_then(|column| row.get(column))
+                        .and_then(|value| parse_duration(value))
+                        .unwrap_or_else(Duration::zero)
+                };
+                Some(Day {
+                    date: parse_date(row.get(date_column)?)?,
+                    minimum: table.temperature(row.get(min_column)?)?,
+                    maximum: table.temperature(row.get(max_column)?)?,
+                    time_above: alarm_time(high_column),
+                    time_below: alarm_time(low_column),
+                })
+            })
+            .collect();
+        if days.is_empty() {
+            return Err("No daily temperatures found".to_string());
+        }
+        days.sort_by_key(|day| day.date);
+
+        let mut logs = Vec::new();
+        let mut breaches = Vec::new();
+        for day in &days {
+            let start = day.date.and_time(NaiveTime::MIN);
+            // Logs are unique by timestamp so minimum and maximum can't share one
+            logs.push(ts::TemperatureLog {
+                timestamp: start,
+                temperature: day.minimum,
+            });
+            logs.push(ts::TemperatureLog {
+                timestamp: start + Duration::hours(12),
+                temperature: day.maximum,
+            });
+
+            let mut add_breach = |breach_type, duration: Duration, alarm_duration| {
+                if duration > Duration::zero() && duration >= alarm_duration {
+                    breaches.push(ts::TemperatureBreach {
+                        breach_type,
+                        start_timestamp: start,
+                        end_timestamp: start + Duration::days(1),
+                        duration,
+                        acknowledged: false,
+                    });
+                }
+            };
+            add_breach(ts::BreachType::HotCumulative, day.time_above, high_duration);
+            add_breach(ts::BreachType::ColdCumulative, day.time_below, low_duration);
+        }
+
+        Ok(LoggerFile {
+            sensor_type: SensorType::ThirtyDayRecorder,
+            serial,
+            name,
+            log_interval: None,
+            // Last day can still change, it's read again next time
+            last_connected_timestamp: days.last().map(|day| day.date.and_time(NaiveTime::MIN)),
+            configs,
+            breaches,
+            logs,
+        })
+    }
+}
+
+struct Day {
+    date: NaiveDate,
+    minimum: f64,
+    maximum: f64,
+    time_above: Duration,
+    time_below: Duration,
+}
+
+fn is_summary_header(fields: &[String]) -> bool {
+    find_column(fields, &["date", "day"]).is_some()
+        && column_starting_with(fields, "min").is_some()
+        && column_starting_with(fields, "max").is_some()
+}
+
+fn column_starting_with(header: &[String], prefix: &str) -> Option<usize> {
+    header.iter().position(|column| column.starts_with(prefix))
+}
+
+/// Alarm temperature and duration, e.g. "+8.0 °C, 10h" or "10 h above +8 °C"
+fn parse_alarm(value: &str) -> Option<(f64, Duration)> {
+    let tokens: Vec<&str> = value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .collect();
+    let temperature = |token: &&str| parse_temperature(token.trim_start_matches(['>', '<']));
+    let is_temperature = |token: &&str| {
+        token.contains('°')
+            || token.ends_with(['C', 'c'])
+            || token.trim_start_matches(['>', '<']).starts_with(['+', '-'])
+    };
+
+    let index = tokens
+        .iter()
+        .position(|token| is_temperature(token) && temperature(token).is_some())
+        .or_else(|| tokens.iter().position(|token| temperature(token).is_some()))?;
+    let remainder: Vec<&str> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(token_index, _)| *token_index != index)
+        .map(|(_, token)| *token)
+        .collect();
+
+    Some((
+        temperature(&tokens[index])?,
+        parse_duration(&remainder.join(" "))?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_alarm() {
+        assert_eq!(
+            parse_alarm("+8.0 °C, 10h"),
+            Some((8.0, Duration::hours(10)))
+        );
+        assert_eq!(
+            parse_alarm("10 h above +8 °C"),
+            Some((8.0, Duration::hours(10)))
+        );
+        assert_eq!(
+            parse_alarm("< -0.5°C for 60 min"),
+            Some((-0.5, Duration::minutes(60)))
+        );
+        assert_eq!(parse_alarm("n/a"), None);
+    }
+
+    #[test]
+    fn test_thirty_day_recorder() {
+        let content = "30 Day Temperature Recorder  Daily report\n\
+                       Serial number:  30DTR-0042\n\
+                       Description:  Health centre fridge\n\
+                       High alarm:  +8.0 °C  10 h\n\
+                       \n\
+                       Date        Min °C   Max °C   Time above   Time below\n\
+                       01.02.2024  +2.1     +8.4     11:00        00:00\n\
+                       02.02.2024  -1.0     +6.0     00:00        00:30\n";
+
+        let parser = ThirtyDayRecorderParser;
+        assert!(parser.can_parse("report.txt", content));
+        assert!(!parser.can_parse("report.txt", "Fridge-tag 2\nDate  Min  Max\n"));
+
+        let file = parser.parse(Path::new("report.txt"), content).unwrap();
+        assert_eq!(file.sensor_type, SensorType::ThirtyDayRecorder);
+        assert_eq!(file.serial, "30DTR-0042");
+        assert_eq!(file.name, "Health centre fridge");
+        assert_eq!(file.configs.len(), 2);
+        assert_eq!(file.configs[0].maximum_temperature, 8.0);
+        // Default low alarm
+        assert_eq!(file.configs[1].minimum_temperature, -0.5);
+        assert_eq!(file.configs[1].duration, Duration::minutes(60));
+
+        let day = NaiveDate::from_ymd_opt(2024, 2, 1).unwrap();
+        let logs: Vec<_> = file
+            .logs
+            .iter()
+            .map(|log| (log.timestamp, log.temperature))
+            .collect();
+        assert_eq!(
+            logs,
+            vec![
+                (day.and_hms_opt(0, 0, 0).unwrap(), 2.1),
+                (day.and_hms_opt(12, 0, 0).unwrap(), 8.4),
+                (day.succ_opt().unwrap().and_hms_opt(0, 0, 0).unwrap(), -1.0),
+                (day.succ_opt().unwrap().and_hms_opt(12, 0, 0).unwrap(), 6.0),
+            ]
+        );
+
+        // Only time above exceeded the alarm duration, time below was 30 of 60 minutes
+        assert_eq!(file.breaches.len(), 1);
+        let breach = &file.breaches[0];
+        assert!(matches!(breach.breach_type, ts::BreachType::HotCumulative));
+        assert_eq!(breach.start_timestamp, day.and_hms_opt(0, 0, 0).unwrap());
+        assert_eq!(breach.duration, Duration::hours(11));
+    }
+}
@@ -11,6 +11,7 @@ use repository::{PaginationOption, Sensor, SensorFilter, SensorSort};
 
 pub mod berlinger;
 pub mod insert;
+pub mod logger_file;
 pub mod query;
 pub mod update;
 mod validate;
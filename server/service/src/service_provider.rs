@@ -20,6 +20,7 @@ use crate::{
         document_service::{DocumentService, DocumentServiceTrait},
         form_schema_service::{FormSchemaService, FormSchemaServiceTrait},
     },
+    forecasting::{ForecastingService, ForecastingServiceTrait},
     invoice::{InvoiceService, InvoiceServiceTrait},
     invoice_line::{InvoiceLineService, InvoiceLineServiceTrait},
     item::ItemServiceTrait,
@@ -30,9 +31,15 @@ use crate::{
     log_service::{LogService, LogServiceTrait},
     master_list::{MasterListService, MasterListServiceTrait},
     name::{NameService, NameServiceTrait},
+    period_close::{PeriodCloseService, PeriodCloseServiceTrait},
     plugin_data::{PluginDataService, PluginDataServiceTrait},
     pricing::{PricingService, PricingServiceTrait},
-    processors::ProcessorsTrigger,
+    print::{LabelPrintingService, LabelPrintingServiceTrait},
+    processors::{
+        changelog::{ChangelogProcessorService, ChangelogProcessorServiceTrait},
+        dead_letter::{TransferDeadLetterService, TransferDeadLetterServiceTrait},
+        ProcessorsTrigger,
+    },
     program::ProgramServiceTrait,
     programs::{
         contact_trace::{ContactTraceService, ContactTraceServiceTrait},
@@ -52,6 +59,7 @@ use crate::{
     rnr_form::{RnRFormService, RnRFormServiceTrait},
     sensor::{SensorService, SensorServiceTrait},
     settings_service::{SettingsService, SettingsServiceTrait},
+    shelf_life_rule::{ShelfLifeRuleService, ShelfLifeRuleServiceTrait},
     standard_reports::StandardReports,
     stock_line::{StockLineService, StockLineServiceTrait},
     stocktake::{StocktakeService, StocktakeServiceTrait},
@@ -102,6 +110,7 @@ pub struct ServiceProvider {
     pub requisition_count_service: Box<dyn RequisitionCountServiceTrait>,
     // Stock stats
     pub item_stats_service: Box<dyn ItemStatsServiceTrait>,
+    pub forecasting_service: Box<dyn ForecastingServiceTrait>,
     // Stock
     pub stock_line_service: Box<dyn StockLineServiceTrait>,
     pub repack_service: Box<dyn RepackServiceTrait>,
@@ -126,6 +135,9 @@ pub struct ServiceProvider {
     // Sync
     pub site_info_service: Box<dyn SiteInfoTrait>,
     pub sync_status_service: Box<dyn SyncStatusTrait>,
+    // Processors
+    pub changelog_processor_service: Box<dyn ChangelogProcessorServiceTrait>,
+    pub transfer_dead_letter_service: Box<dyn TransferDeadLetterServiceTrait>,
     // Triggers
     processors_trigger: ProcessorsTrigger,
     pub sync_trigger: SyncTrigger,
@@ -145,6 +157,7 @@ pub struct ServiceProvider {
     pub asset_service: Box<dyn AssetServiceTrait>,
     // Label Printer
     pub label_printer_settings_service: Box<dyn LabelPrinterSettingsServiceTrait>,
+    pub label_printing_service: Box<dyn LabelPrintingServiceTrait>,
     // Demographic
     pub demographic_service: Box<dyn DemographicServiceTrait>,
     // Vaccine Course
@@ -154,6 +167,8 @@ pub struct ServiceProvider {
     // Programs
     pub program_service: Box<dyn ProgramServiceTrait>,
     pub pricing_service: Box<dyn PricingServiceTrait>,
+    pub shelf_life_rule_service: Box<dyn ShelfLifeRuleServiceTrait>,
+    pub period_close_service: Box<dyn PeriodCloseServiceTrait>,
     // Translations
     pub translations_service: Box<Localisations>,
     // Standard Reports
@@ -206,6 +221,7 @@ impl ServiceProvider {
             requisition_line_service: Box::new(RequisitionLineService {}),
             item_service: Box::new(crate::item::ItemService {}),
             item_stats_service: Box::new(ItemStatsService {}),
+            forecasting_service: Box::new(ForecastingService {}),
             clinician_service: Box::new(ClinicianService {}),
             general_service: Box::new(GeneralService {}),
             report_service: Box::new(ReportService {}),
@@ -223,6 +239,8 @@ impl ServiceProvider {
             app_data_service: Box::new(AppDataService::new(app_data_folder)),
             site_info_service: Box::new(SiteInfoService),
             sync_status_service: Box::new(SyncStatusService),
+            changelog_processor_service: Box::new(ChangelogProcessorService {}),
+            transfer_dead_letter_service: Box::new(TransferDeadLetterService {}),
             processors_trigger,
             sync_trigger,
             site_is_initialised_trigger,
@@ -240,11 +258,14 @@ impl ServiceProvider {
             label_printer_settings_service: Box::new(
                 crate::label_printer_settings_service::LabelPrinterSettingsService {},
             ),
+            label_printing_service: Box::new(LabelPrintingService {}),
             name_service: Box::new(NameService {}),
             demographic_service: Box::new(crate::demographic::DemographicService {}),
             vaccine_course_service: Box::new(crate::vaccine_course::VaccineCourseService {}),
             program_service: Box::new(crate::program::ProgramService {}),
             pricing_service: Box::new(PricingService {}),
+            shelf_life_rule_service: Box::new(ShelfLifeRuleService {}),
+            period_close_service: Box::new(PeriodCloseService {}),
             rnr_form_service: Box::new(RnRFormService {}),
             vaccination_service: Box::new(VaccinationService {}),
             translations_service: Box::new(Localisations::new()),
@@ -11,6 +11,7 @@ pub struct Settings {
     pub sync: Option<SyncSettings>,
     pub logging: Option<LoggingSettings>,
     pub backup: Option<BackupSettings>,
+    pub mail: Option<MailSettings>,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -50,6 +51,22 @@ pub struct BackupSettings {
     pub pg_bin_dir: Option<String>,
     // Number of backups to keep
     pub max_number_of_backups: Option<u32>,
+    // Directory postgres archives WAL files to (archive_command), used for point in time restore
+    pub wal_archive_dir: Option<String>,
+}
+
+/// SMTP server used for email notifications (e.g. temperature breaches)
+#[derive(serde::Deserialize, Clone)]
+pub struct MailSettings {
+    pub host: String,
+    pub port: u16,
+    /// Connect without TLS, only for local test servers
+    #[serde(default)]
+    pub danger_allow_insecure: bool,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Sender address, e.g. "mSupply <notifications@example.org>"
+    pub from: String,
 }
 
 pub fn is_develop() -> bool {
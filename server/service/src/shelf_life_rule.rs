@@ -0,0 +1,202 @@
+use repository::{
+    ItemLinkRowRepository, ItemRowRepository, NameLinkRowRepository, NameRowRepository,
+    RepositoryError, ShelfLifeRuleRow, ShelfLifeRuleRowRepository, StorageConnection,
+};
+
+use crate::{service_provider::ServiceContext, validate::check_store_id_matches};
+
+pub trait ShelfLifeRuleServiceTrait: Sync + Send {
+    fn get_shelf_life_rules(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<ShelfLifeRuleRow>, RepositoryError> {
+        ShelfLifeRuleRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)
+    }
+
+    fn upsert_shelf_life_rule(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertShelfLifeRule,
+    ) -> Result<ShelfLifeRuleRow, UpsertShelfLifeRuleError> {
+        upsert_shelf_life_rule(ctx, input)
+    }
+
+    fn delete_shelf_life_rule(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<String, DeleteShelfLifeRuleError> {
+        delete_shelf_life_rule(ctx, id)
+    }
+}
+
+pub struct ShelfLifeRuleService {}
+impl ShelfLifeRuleServiceTrait for ShelfLifeRuleService {}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpsertShelfLifeRule {
+    pub id: String,
+    /// Rule applies to all customers if not set
+    pub name_id: Option<String>,
+    /// Rule applies to all items if not set
+    pub item_id: Option<String>,
+    pub minimum_remaining_days: i32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertShelfLifeRuleError {
+    RuleDoesNotBelongToCurrentStore,
+    NameDoesNotExist,
+    ItemDoesNotExist,
+    MinimumRemainingDaysCannotBeNegative,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeleteShelfLifeRuleError {
+    RuleDoesNotExist,
+    RuleDoesNotBelongToCurrentStore,
+    DatabaseError(RepositoryError),
+}
+
+pub fn upsert_shelf_life_rule(
+    ctx: &ServiceContext,
+    input: UpsertShelfLifeRule,
+) -> Result<ShelfLifeRuleRow, UpsertShelfLifeRuleError> {
+    let rule = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate_upsert(connection, &ctx.store_id, &input)?;
+
+            let UpsertShelfLifeRule {
+                id,
+                name_id,
+                item_id,
+                minimum_remaining_days,
+            } = input;
+
+            let rule = ShelfLifeRuleRow {
+                id,
+                store_id: ctx.store_id.clone(),
+                name_link_id: name_id,
+                item_link_id: item_id,
+                minimum_remaining_days,
+            };
+            ShelfLifeRuleRowRepository::new(connection).upsert_one(&rule)?;
+
+            Ok(rule)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(rule)
+}
+
+fn validate_upsert(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpsertShelfLifeRule,
+) -> Result<(), UpsertShelfLifeRuleError> {
+    use UpsertShelfLifeRuleError::*;
+
+    if let Some(existing) = ShelfLifeRuleRowRepository::new(connection).find_one_by_id(&input.id)? {
+        if !check_store_id_matches(&existing.store_id, store_id) {
+            return Err(RuleDoesNotBelongToCurrentStore);
+        }
+    }
+
+    if input.minimum_remaining_days < 0 {
+        return Err(MinimumRemainingDaysCannotBeNegative);
+    }
+
+    if let Some(name_id) = &input.name_id {
+        NameRowRepository::new(connection)
+            .find_one_by_id(name_id)?
+            .ok_or(NameDoesNotExist)?;
+    }
+
+    if let Some(item_id) = &input.item_id {
+        ItemRowRepository::new(connection)
+            .find_active_by_id(item_id)?
+            .ok_or(ItemDoesNotExist)?;
+    }
+
+    Ok(())
+}
+
+pub fn delete_shelf_life_rule(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<String, DeleteShelfLifeRuleError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = ShelfLifeRuleRowRepository::new(connection);
+            let rule = repository
+                .find_one_by_id(&id)?
+                .ok_or(DeleteShelfLifeRuleError::RuleDoesNotExist)?;
+            if !check_store_id_matches(&rule.store_id, &ctx.store_id) {
+                return Err(DeleteShelfLifeRuleError::RuleDoesNotBelongToCurrentStore);
+            }
+            repository.delete(&id)?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(id)
+}
+
+/// Minimum remaining shelf life (in days) stock of an item must have to be issued to a customer,
+/// the strictest of all rules matching the customer and item is used
+pub fn get_minimum_remaining_shelf_life_days(
+    connection: &StorageConnection,
+    store_id: &str,
+    name_link_id: &str,
+    item_id: &str,
+) -> Result<Option<i32>, RepositoryError> {
+    let rules = ShelfLifeRuleRowRepository::new(connection).find_many_by_store_id(store_id)?;
+    if rules.is_empty() {
+        return Ok(None);
+    }
+
+    // Rules and invoices may reference different links of the same (merged) name or item
+    let name_link_ids: Vec<String> =
+        match NameLinkRowRepository::new(connection).find_one_by_id(name_link_id)? {
+            Some(name_link) => NameLinkRowRepository::new(connection)
+                .find_many_by_name_id(&name_link.name_id)?
+                .into_iter()
+                .map(|link| link.id)
+                .collect(),
+            None => vec![name_link_id.to_string()],
+        };
+    let item_link_ids: Vec<String> = ItemLinkRowRepository::new(connection)
+        .find_many_by_item_id(item_id)?
+        .into_iter()
+        .map(|link| link.id)
+        .collect();
+
+    let minimum_remaining_days = rules
+        .into_iter()
+        .filter(|rule| match &rule.name_link_id {
+            Some(id) => name_link_ids.contains(id),
+            None => true,
+        })
+        .filter(|rule| match &rule.item_link_id {
+            Some(id) => item_link_ids.contains(id),
+            None => true,
+        })
+        .map(|rule| rule.minimum_remaining_days)
+        .max();
+
+    Ok(minimum_remaining_days)
+}
+
+impl From<RepositoryError> for UpsertShelfLifeRuleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertShelfLifeRuleError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeleteShelfLifeRuleError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteShelfLifeRuleError::DatabaseError(error)
+    }
+}
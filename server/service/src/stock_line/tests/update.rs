@@ -3,7 +3,7 @@ mod test {
     use repository::{
         mock::{mock_stock_line_a, mock_store_a, mock_user_account_a, MockDataInserts},
         test_db::setup_all,
-        StockLineRowRepository,
+        StockLineRowRepository, VvmStatus,
     };
     use util::{inline_edit, inline_init};
 
@@ -113,5 +113,25 @@ mod test {
                 l
             })
         );
+
+        // VVM status can be set and cleared
+        let update_vvm_status = |vvm_status: Option<VvmStatus>| {
+            service
+                .update_stock_line(
+                    &context,
+                    inline_init(|r: &mut UpdateStockLine| {
+                        r.id = mock_stock_line_a().id;
+                        r.vvm_status = Some(NullableUpdate { value: vvm_status });
+                    }),
+                )
+                .unwrap()
+                .stock_line_row
+                .vvm_status
+        };
+        assert_eq!(
+            update_vvm_status(Some(VvmStatus::Stage2)),
+            Some(VvmStatus::Stage2)
+        );
+        assert_eq!(update_vvm_status(None), None);
     }
 }
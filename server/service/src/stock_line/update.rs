@@ -3,7 +3,7 @@ use repository::{
     location_movement::{LocationMovementFilter, LocationMovementRepository},
     ActivityLogType, BarcodeRow, BarcodeRowRepository, DatetimeFilter, EqualFilter,
     LocationMovementRow, LocationMovementRowRepository, RepositoryError, StockLine, StockLineRow,
-    StockLineRowRepository, StorageConnection,
+    StockLineRowRepository, StorageConnection, VvmStatus,
 };
 use util::uuid::uuid;
 
@@ -29,6 +29,7 @@ pub struct UpdateStockLine {
     pub batch: Option<String>,
     pub barcode: Option<String>,
     pub item_variant_id: Option<NullableUpdate<String>>,
+    pub vvm_status: Option<NullableUpdate<VvmStatus>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -133,6 +134,7 @@ fn generate(
         on_hold,
         barcode,
         item_variant_id,
+        vvm_status,
     }: UpdateStockLine,
 ) -> Result<GenerateResult, UpdateStockLineError> {
     let mut existing = existing_line.stock_line_row;
@@ -183,6 +185,7 @@ fn generate(
     existing.item_variant_id = item_variant_id
         .map(|v| v.value)
         .unwrap_or(existing.item_variant_id);
+    existing.vvm_status = vvm_status.map(|v| v.value).unwrap_or(existing.vvm_status);
 
     Ok(GenerateResult {
         new_stock_line: existing,
@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use repository::{
+    EqualFilter, RepositoryError, StocktakeBlindCountRow, StocktakeBlindCountRowRepository,
+    StocktakeLine, StocktakeLineFilter, StocktakeLineRecountRow, StocktakeLineRecountRowRepository,
+    StocktakeLineRepository, StocktakeLineRowRepository, StocktakeRow, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::ServiceContext,
+    stocktake::{check_stocktake_exist, check_stocktake_not_finalised},
+    validate::check_store_id_matches,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct SetStocktakeBlindCount {
+    pub stocktake_id: String,
+    pub is_blind_count: bool,
+    /// Counted lines with a variance above this percentage of the snapshot need to be
+    /// recounted, no recounts if None
+    pub recount_tolerance_percentage: Option<f64>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetStocktakeBlindCountError {
+    DatabaseError(RepositoryError),
+    InvalidStore,
+    StocktakeDoesNotExist,
+    CannotEditFinalised,
+    CountAlreadySubmitted,
+    InvalidRecountTolerance,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StocktakeBlindCountSubmission {
+    pub blind_count: StocktakeBlindCountRow,
+    /// Lines that need to be recounted before the count can be submitted, empty if the count
+    /// was submitted
+    pub recount_lines: Vec<StocktakeLine>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SubmitStocktakeBlindCountError {
+    DatabaseError(RepositoryError),
+    InvalidStore,
+    StocktakeDoesNotExist,
+    CannotEditFinalised,
+    NotABlindCount,
+    CountAlreadySubmitted,
+    /// All lines need to be counted before the count is submitted
+    UncountedLines(Vec<StocktakeLine>),
+}
+
+pub fn get_stocktake_blind_count(
+    ctx: &ServiceContext,
+    stocktake_id: &str,
+) -> Result<Option<StocktakeBlindCountRow>, RepositoryError> {
+    StocktakeBlindCountRowRepository::new(&ctx.connection).find_one_by_id(stocktake_id)
+}
+
+/// Turns blind count mode on or off for a stocktake that hasn't been submitted yet.
+/// Returns the blind count, None if it was turned off
+pub fn set_stocktake_blind_count(
+    ctx: &ServiceContext,
+    input: SetStocktakeBlindCount,
+) -> Result<Option<StocktakeBlindCountRow>, SetStocktakeBlindCountError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (stocktake, existing) = validate_set(connection, &ctx.store_id, &input)?;
+
+            if !input.is_blind_count {
+                StocktakeLineRecountRowRepository::new(connection)
+                    .delete_by_stocktake_id(&stocktake.id)?;
+                StocktakeBlindCountRowRepository::new(connection).delete(&stocktake.id)?;
+                return Ok(None);
+            }
+
+            let blind_count = StocktakeBlindCountRow {
+                id: stocktake.id,
+                store_id: stocktake.store_id,
+                recount_tolerance_percentage: input.recount_tolerance_percentage,
+                ..existing.unwrap_or_default()
+            };
+            StocktakeBlindCountRowRepository::new(connection).upsert_one(&blind_count)?;
+
+            Ok(Some(blind_count))
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+/// Submits the count of a blind count stocktake. Counted lines with a variance above the
+/// recount tolerance have their count cleared and need to be recounted and submitted again,
+/// each line is recounted at most once. Once submitted the snapshot quantities are visible
+pub fn submit_stocktake_blind_count(
+    ctx: &ServiceContext,
+    stocktake_id: &str,
+) -> Result<StocktakeBlindCountSubmission, SubmitStocktakeBlindCountError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (mut blind_count, lines) =
+                validate_submit(connection, &ctx.store_id, stocktake_id)?;
+
+            let now = Utc::now().naive_utc();
+            let recount_repository = StocktakeLineRecountRowRepository::new(connection);
+            let mut recounts: HashMap<String, StocktakeLineRecountRow> = recount_repository
+                .find_many_by_stocktake_ids(&[stocktake_id.to_string()])?
+                .into_iter()
+                .map(|recount| (recount.stocktake_line_id.clone(), recount))
+                .collect();
+
+            let mut recount_lines = Vec::new();
+            for mut line in lines {
+                let counted_number_of_packs = line.line.counted_number_of_packs.unwrap_or_default();
+
+                if let Some(mut recount) = recounts.remove(&line.line.id) {
+                    if recount.recounted_datetime.is_none() {
+                        recount.recounted_datetime = Some(now);
+                        recount_repository.upsert_one(&recount)?;
+                    }
+                    continue;
+                }
+
+                let snapshot_number_of_packs = line.line.snapshot_number_of_packs;
+                if !exceeds_recount_tolerance(
+                    blind_count.recount_tolerance_percentage,
+                    snapshot_number_of_packs,
+                    counted_number_of_packs,
+                ) {
+                    continue;
+                }
+
+                recount_repository.upsert_one(&StocktakeLineRecountRow {
+                    id: uuid(),
+                    stocktake_id: stocktake_id.to_string(),
+                    stocktake_line_id: line.line.id.clone(),
+                    first_counted_number_of_packs: counted_number_of_packs,
+                    snapshot_number_of_packs,
+                    requested_datetime: now,
+                    recounted_datetime: None,
+                })?;
+                line.line.counted_number_of_packs = None;
+                StocktakeLineRowRepository::new(connection).upsert_one(&line.line)?;
+                recount_lines.push(line);
+            }
+
+            if recount_lines.is_empty() {
+                blind_count.submitted_datetime = Some(now);
+                blind_count.submitted_by_user_id = Some(ctx.user_id.clone());
+                StocktakeBlindCountRowRepository::new(connection).upsert_one(&blind_count)?;
+            }
+
+            Ok(StocktakeBlindCountSubmission {
+                blind_count,
+                recount_lines,
+            })
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+fn exceeds_recount_tolerance(
+    recount_tolerance_percentage: Option<f64>,
+    snapshot_number_of_packs: f64,
+    counted_number_of_packs: f64,
+) -> bool {
+    let Some(tolerance) = recount_tolerance_percentage else {
+        return false;
+    };
+    let difference = (counted_number_of_packs - snapshot_number_of_packs).abs();
+    if difference == 0.0 {
+        return false;
+    }
+    if snapshot_number_of_packs <= 0.0 {
+        // Any stock found for a line without snapshot stock is a variance above the tolerance
+        return true;
+    }
+
+    difference / snapshot_number_of_packs * 100.0 > tolerance
+}
+
+fn validate_set(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &SetStocktakeBlindCount,
+) -> Result<(StocktakeRow, Option<StocktakeBlindCountRow>), SetStocktakeBlindCountError> {
+    use SetStocktakeBlindCountError::*;
+
+    let stocktake =
+        check_stocktake_exist(connection, &input.stocktake_id)?.ok_or(StocktakeDoesNotExist)?;
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(InvalidStore);
+    }
+    if !check_stocktake_not_finalised(&stocktake.status) {
+        return Err(CannotEditFinalised);
+    }
+    if input
+        .recount_tolerance_percentage
+        .is_some_and(|tolerance| tolerance < 0.0)
+    {
+        return Err(InvalidRecountTolerance);
+    }
+
+    let existing =
+        StocktakeBlindCountRowRepository::new(connection).find_one_by_id(&stocktake.id)?;
+    if existing
+        .as_ref()
+        .is_some_and(|blind_count| blind_count.is_submitted())
+    {
+        return Err(CountAlreadySubmitted);
+    }
+
+    Ok((stocktake, existing))
+}
+
+fn validate_submit(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<(StocktakeBlindCountRow, Vec<StocktakeLine>), SubmitStocktakeBlindCountError> {
+    use SubmitStocktakeBlindCountError::*;
+
+    let stocktake =
+        check_stocktake_exist(connection, stocktake_id)?.ok_or(StocktakeDoesNotExist)?;
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(InvalidStore);
+    }
+    if !check_stocktake_not_finalised(&stocktake.status) {
+        return Err(CannotEditFinalised);
+    }
+    let blind_count = StocktakeBlindCountRowRepository::new(connection)
+        .find_one_by_id(stocktake_id)?
+        .ok_or(NotABlindCount)?;
+    if blind_count.is_submitted() {
+        return Err(CountAlreadySubmitted);
+    }
+
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(stocktake_id)),
+        Some(store_id.to_string()),
+    )?;
+    let uncounted: Vec<StocktakeLine> = lines
+        .iter()
+        .filter(|line| line.line.counted_number_of_packs.is_none())
+        .cloned()
+        .collect();
+    if !uncounted.is_empty() {
+        return Err(UncountedLines(uncounted));
+    }
+
+    Ok((blind_count, lines))
+}
+
+impl From<RepositoryError> for SetStocktakeBlindCountError {
+    fn from(error: RepositoryError) -> Self {
+        SetStocktakeBlindCountError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for SubmitStocktakeBlindCountError {
+    fn from(error: RepositoryError) -> Self {
+        SubmitStocktakeBlindCountError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_stocktake_line_stock_deficit, mock_stocktake_stock_deficit, mock_store_a,
+            mock_user_account_a, MockDataInserts,
+        },
+        test_db::setup_all,
+        StocktakeLineRecountRowRepository, StocktakeLineRow, StocktakeLineRowRepository,
+        StocktakeStatus,
+    };
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake::{
+            SetStocktakeBlindCount, SetStocktakeBlindCountError, SubmitStocktakeBlindCountError,
+            UpdateStocktake, UpdateStocktakeError, UpdateStocktakeStatus,
+        },
+    };
+
+    #[actix_rt::test]
+    async fn stocktake_blind_count() {
+        let (_, connection, connection_manager, _) =
+            setup_all("stocktake_blind_count", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.stocktake_service;
+        let stocktake_id = mock_stocktake_stock_deficit().id;
+        let finalise = || UpdateStocktake {
+            id: stocktake_id.clone(),
+            status: Some(UpdateStocktakeStatus::Finalised),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            service.submit_stocktake_blind_count(&context, &stocktake_id),
+            Err(SubmitStocktakeBlindCountError::NotABlindCount)
+        );
+        assert_eq!(
+            service.set_stocktake_blind_count(
+                &context,
+                SetStocktakeBlindCount {
+                    stocktake_id: stocktake_id.clone(),
+                    is_blind_count: true,
+                    recount_tolerance_percentage: Some(-1.0),
+                }
+            ),
+            Err(SetStocktakeBlindCountError::InvalidRecountTolerance)
+        );
+        let blind_count = service
+            .set_stocktake_blind_count(
+                &context,
+                SetStocktakeBlindCount {
+                    stocktake_id: stocktake_id.clone(),
+                    is_blind_count: true,
+                    recount_tolerance_percentage: Some(10.0),
+                },
+            )
+            .unwrap()
+            .unwrap();
+        assert!(!blind_count.is_submitted());
+
+        // Count needs to be submitted before finalising
+        assert_eq!(
+            service.update_stocktake(&context, finalise()),
+            Err(UpdateStocktakeError::BlindCountNotSubmitted)
+        );
+
+        // Deficit of 10 out of 30 packs is above the tolerance
+        let submission = service
+            .submit_stocktake_blind_count(&context, &stocktake_id)
+            .unwrap();
+        assert!(!submission.blind_count.is_submitted());
+        assert_eq!(submission.recount_lines.len(), 1);
+        assert_eq!(
+            submission.recount_lines[0].line.id,
+            mock_stocktake_line_stock_deficit().id
+        );
+        assert_eq!(
+            submission.recount_lines[0].line.counted_number_of_packs,
+            None
+        );
+        assert!(matches!(
+            service.submit_stocktake_blind_count(&context, &stocktake_id),
+            Err(SubmitStocktakeBlindCountError::UncountedLines(_))
+        ));
+
+        // Recount confirms the first count, the line isn't recounted again
+        StocktakeLineRowRepository::new(&connection)
+            .upsert_one(&StocktakeLineRow {
+                comment: Some("Recounted".to_string()),
+                ..mock_stocktake_line_stock_deficit()
+            })
+            .unwrap();
+        let submission = service
+            .submit_stocktake_blind_count(&context, &stocktake_id)
+            .unwrap();
+        assert!(submission.blind_count.is_submitted());
+        assert!(submission.recount_lines.is_empty());
+        let recounts = StocktakeLineRecountRowRepository::new(&connection)
+            .find_many_by_stocktake_ids(&[stocktake_id.clone()])
+            .unwrap();
+        assert_eq!(recounts.len(), 1);
+        assert_eq!(
+            recounts[0].first_counted_number_of_packs,
+            mock_stocktake_line_stock_deficit()
+                .counted_number_of_packs
+                .unwrap()
+        );
+        assert!(recounts[0].recounted_datetime.is_some());
+
+        assert_eq!(
+            service.set_stocktake_blind_count(
+                &context,
+                SetStocktakeBlindCount {
+                    stocktake_id: stocktake_id.clone(),
+                    is_blind_count: false,
+                    recount_tolerance_percentage: None,
+                }
+            ),
+            Err(SetStocktakeBlindCountError::CountAlreadySubmitted)
+        );
+
+        let stocktake = service.update_stocktake(&context, finalise()).unwrap();
+        assert_eq!(stocktake.status, StocktakeStatus::Finalised);
+    }
+}
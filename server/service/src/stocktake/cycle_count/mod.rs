@@ -0,0 +1,502 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, Months, NaiveDateTime, Utc};
+use repository::{
+    ConsumptionFilter, ConsumptionRepository, CycleCountPlanRow, CycleCountPlanRowRepository,
+    CycleCountPlanRunRow, CycleCountPlanRunRowRepository, DateFilter, DatetimeFilter, EqualFilter,
+    ItemType, MasterListFilter, MasterListLineFilter, MasterListLineRepository,
+    MasterListRepository, Pagination, RepositoryError, StockLineFilter, StockLineRepository,
+    StocktakeFilter, StocktakeLineFilter, StocktakeLineRepository, StocktakeRepository,
+    StocktakeStatus, StorageConnection,
+};
+
+use crate::{service_provider::ServiceContext, validate::check_store_id_matches};
+
+pub mod scheduler;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpsertCycleCountPlan {
+    pub id: String,
+    pub name: String,
+    pub master_list_id: Option<String>,
+    pub a_class_percentage: f64,
+    pub b_class_percentage: f64,
+    pub a_class_interval_days: i32,
+    pub b_class_interval_days: i32,
+    pub c_class_interval_days: i32,
+    pub run_interval_days: i32,
+    pub consumption_look_back_months: i32,
+    pub is_active: bool,
+}
+
+impl Default for UpsertCycleCountPlan {
+    /// Monthly A, quarterly B and yearly C counts with a weekly stocktake
+    fn default() -> Self {
+        UpsertCycleCountPlan {
+            id: String::new(),
+            name: String::new(),
+            master_list_id: None,
+            a_class_percentage: 80.0,
+            b_class_percentage: 95.0,
+            a_class_interval_days: 30,
+            b_class_interval_days: 90,
+            c_class_interval_days: 365,
+            run_interval_days: 7,
+            consumption_look_back_months: 12,
+            is_active: true,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertCycleCountPlanError {
+    PlanDoesNotBelongToCurrentStore,
+    MasterListNotFoundForThisStore,
+    /// Percentages must be 0 < A <= B <= 100
+    InvalidClassPercentages,
+    /// Intervals must be positive
+    InvalidInterval,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeleteCycleCountPlanError {
+    PlanDoesNotExist,
+    PlanDoesNotBelongToCurrentStore,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CycleCountClass {
+    A,
+    B,
+    C,
+}
+
+/// Item of a cycle count plan with its ABC class and when it was last counted
+#[derive(Clone, Debug, PartialEq)]
+pub struct CycleCountItem {
+    pub item_id: String,
+    pub class: CycleCountClass,
+    /// Consumption over the plan's look back period at the current cost price
+    pub consumption_value: f64,
+    /// Finalised datetime of the latest stocktake the item was counted in
+    pub last_counted_datetime: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct CycleCountCoverageStatistics {
+    pub item_count: u32,
+    /// Items counted in a finalised stocktake during the period
+    pub counted_item_count: u32,
+}
+
+impl CycleCountCoverageStatistics {
+    pub fn percentage(&self) -> Option<f64> {
+        (self.item_count > 0)
+            .then(|| self.counted_item_count as f64 / self.item_count as f64 * 100.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CycleCountClassCoverage {
+    pub class: CycleCountClass,
+    pub statistics: CycleCountCoverageStatistics,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CycleCountCoverage {
+    pub plan: CycleCountPlanRow,
+    pub from: NaiveDateTime,
+    pub to: NaiveDateTime,
+    pub classes: Vec<CycleCountClassCoverage>,
+    pub total: CycleCountCoverageStatistics,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CycleCountCoverageError {
+    PlanDoesNotExist,
+    InvalidPeriod,
+    DatabaseError(RepositoryError),
+}
+
+/// Days within which each item of the class should be counted
+pub fn class_interval_days(plan: &CycleCountPlanRow, class: CycleCountClass) -> i32 {
+    match class {
+        CycleCountClass::A => plan.a_class_interval_days,
+        CycleCountClass::B => plan.b_class_interval_days,
+        CycleCountClass::C => plan.c_class_interval_days,
+    }
+}
+
+/// Creates or updates a cycle count plan, stocktakes are created as the current user
+pub fn upsert_cycle_count_plan(
+    ctx: &ServiceContext,
+    input: UpsertCycleCountPlan,
+) -> Result<CycleCountPlanRow, UpsertCycleCountPlanError> {
+    let plan = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let existing = validate_upsert(connection, &ctx.store_id, &input)?;
+
+            let UpsertCycleCountPlan {
+                id,
+                name,
+                master_list_id,
+                a_class_percentage,
+                b_class_percentage,
+                a_class_interval_days,
+                b_class_interval_days,
+                c_class_interval_days,
+                run_interval_days,
+                consumption_look_back_months,
+                is_active,
+            } = input;
+
+            let plan = CycleCountPlanRow {
+                id,
+                store_id: ctx.store_id.clone(),
+                user_id: ctx.user_id.clone(),
+                name,
+                master_list_id,
+                a_class_percentage,
+                b_class_percentage,
+                a_class_interval_days,
+                b_class_interval_days,
+                c_class_interval_days,
+                run_interval_days,
+                consumption_look_back_months,
+                is_active,
+                created_datetime: existing
+                    .map(|existing| existing.created_datetime)
+                    .unwrap_or(Utc::now().naive_utc()),
+            };
+            CycleCountPlanRowRepository::new(connection).upsert_one(&plan)?;
+
+            Ok(plan)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(plan)
+}
+
+fn validate_upsert(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpsertCycleCountPlan,
+) -> Result<Option<CycleCountPlanRow>, UpsertCycleCountPlanError> {
+    use UpsertCycleCountPlanError::*;
+
+    let existing = CycleCountPlanRowRepository::new(connection).find_one_by_id(&input.id)?;
+    if let Some(existing) = &existing {
+        if !check_store_id_matches(&existing.store_id, store_id) {
+            return Err(PlanDoesNotBelongToCurrentStore);
+        }
+    }
+
+    if let Some(master_list_id) = &input.master_list_id {
+        let count = MasterListRepository::new(connection).count(Some(
+            MasterListFilter::new()
+                .id(EqualFilter::equal_to(master_list_id))
+                .exists_for_store_id(EqualFilter::equal_to(store_id)),
+        ))?;
+        if count == 0 {
+            return Err(MasterListNotFoundForThisStore);
+        }
+    }
+
+    if input.a_class_percentage <= 0.0
+        || input.a_class_percentage > input.b_class_percentage
+        || input.b_class_percentage > 100.0
+    {
+        return Err(InvalidClassPercentages);
+    }
+
+    if [
+        input.a_class_interval_days,
+        input.b_class_interval_days,
+        input.c_class_interval_days,
+        input.run_interval_days,
+        input.consumption_look_back_months,
+    ]
+    .iter()
+    .any(|value| *value <= 0)
+    {
+        return Err(InvalidInterval);
+    }
+
+    Ok(existing)
+}
+
+/// Deletes a cycle count plan and its run history, generated stocktakes are kept
+pub fn delete_cycle_count_plan(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<String, DeleteCycleCountPlanError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = CycleCountPlanRowRepository::new(connection);
+            let plan = repository
+                .find_one_by_id(&id)?
+                .ok_or(DeleteCycleCountPlanError::PlanDoesNotExist)?;
+            if !check_store_id_matches(&plan.store_id, &ctx.store_id) {
+                return Err(DeleteCycleCountPlanError::PlanDoesNotBelongToCurrentStore);
+            }
+            CycleCountPlanRunRowRepository::new(connection).delete_by_plan_id(&id)?;
+            repository.delete(&id)?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(id)
+}
+
+pub fn get_cycle_count_plans(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+    CycleCountPlanRowRepository::new(&ctx.connection).find_many_by_store_id(store_id)
+}
+
+/// Run history of a plan, latest first. Returns `NotFound` if the plan doesn't belong to the
+/// store
+pub fn get_cycle_count_plan_runs(
+    ctx: &ServiceContext,
+    store_id: &str,
+    cycle_count_plan_id: &str,
+    limit: Option<u32>,
+) -> Result<Vec<CycleCountPlanRunRow>, RepositoryError> {
+    let plan = CycleCountPlanRowRepository::new(&ctx.connection)
+        .find_one_by_id(cycle_count_plan_id)?
+        .filter(|plan| plan.store_id == store_id)
+        .ok_or(RepositoryError::NotFound)?;
+
+    CycleCountPlanRunRowRepository::new(&ctx.connection).find_many_by_plan_id(&plan.id, limit)
+}
+
+/// Percentage of the plan's items, by class, that were counted in a finalised stocktake of the
+/// store during the period. Items are classified as of the end of the period
+pub fn get_cycle_count_coverage(
+    ctx: &ServiceContext,
+    cycle_count_plan_id: &str,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<CycleCountCoverage, CycleCountCoverageError> {
+    if from >= to {
+        return Err(CycleCountCoverageError::InvalidPeriod);
+    }
+    let plan = CycleCountPlanRowRepository::new(&ctx.connection)
+        .find_one_by_id(cycle_count_plan_id)?
+        .filter(|plan| plan.store_id == ctx.store_id)
+        .ok_or(CycleCountCoverageError::PlanDoesNotExist)?;
+
+    let items = classify_cycle_count_items(&ctx.connection, &plan, to)?;
+    let counted = get_last_counted(&ctx.connection, &plan.store_id, from, to)?;
+
+    let mut classes: HashMap<CycleCountClass, CycleCountCoverageStatistics> = HashMap::new();
+    let mut total = CycleCountCoverageStatistics::default();
+    for item in &items {
+        let is_counted = counted.contains_key(&item.item_id) as u32;
+        for statistics in [classes.entry(item.class).or_default(), &mut total] {
+            statistics.item_count += 1;
+            statistics.counted_item_count += is_counted;
+        }
+    }
+
+    let mut classes: Vec<CycleCountClassCoverage> = classes
+        .into_iter()
+        .map(|(class, statistics)| CycleCountClassCoverage { class, statistics })
+        .collect();
+    classes.sort_by_key(|coverage| coverage.class);
+
+    Ok(CycleCountCoverage {
+        plan,
+        from,
+        to,
+        classes,
+        total,
+    })
+}
+
+/// Items of the plan with their ABC class, sorted by consumption value (highest first).
+/// Items are ranked by consumption value, items within the first `a_class_percentage` of the
+/// cumulative value are class A, within `b_class_percentage` class B, the rest (including items
+/// without consumption) class C
+pub fn classify_cycle_count_items(
+    connection: &StorageConnection,
+    plan: &CycleCountPlanRow,
+    now: NaiveDateTime,
+) -> Result<Vec<CycleCountItem>, RepositoryError> {
+    let look_back_start = now
+        .checked_sub_months(Months::new(plan.consumption_look_back_months as u32))
+        .unwrap_or(now);
+    let store_id = &plan.store_id;
+
+    let consumption = ConsumptionRepository::new(connection).query(Some(
+        ConsumptionFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .date(DateFilter::date_range(&look_back_start.date(), &now.date())),
+    ))?;
+    let mut consumed_units: HashMap<String, f64> = HashMap::new();
+    for row in consumption {
+        *consumed_units.entry(row.item_id).or_default() += row.quantity;
+    }
+
+    // Cost per unit, averaged over the item's stock lines in the store
+    let stock_lines = StockLineRepository::new(connection).query_by_filter(
+        StockLineFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .has_packs_in_store(true),
+        Some(store_id.clone()),
+    )?;
+    let mut unit_costs: HashMap<String, (f64, u32)> = HashMap::new();
+    for line in &stock_lines {
+        let row = &line.stock_line_row;
+        if row.pack_size <= 0.0 {
+            continue;
+        }
+        let (sum, count) = unit_costs.entry(line.item_row.id.clone()).or_default();
+        *sum += row.cost_price_per_pack / row.pack_size;
+        *count += 1;
+    }
+
+    let item_ids: Vec<String> = match &plan.master_list_id {
+        Some(master_list_id) => MasterListLineRepository::new(connection)
+            .query_by_filter(
+                MasterListLineFilter::new()
+                    .master_list_id(EqualFilter::equal_to(master_list_id))
+                    .item_type(ItemType::Stock.equal_to()),
+            )?
+            .into_iter()
+            .map(|line| line.item_id)
+            .collect(),
+        None => {
+            let mut item_ids: Vec<String> = stock_lines
+                .iter()
+                .map(|line| line.item_row.id.clone())
+                .chain(consumed_units.keys().cloned())
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .collect();
+            item_ids.sort();
+            item_ids
+        }
+    };
+
+    let longest_interval_days = plan
+        .a_class_interval_days
+        .max(plan.b_class_interval_days)
+        .max(plan.c_class_interval_days);
+    let last_counted = get_last_counted(
+        connection,
+        store_id,
+        now - Duration::days(longest_interval_days as i64),
+        now,
+    )?;
+
+    let mut items: Vec<CycleCountItem> = item_ids
+        .into_iter()
+        .map(|item_id| {
+            let unit_cost = unit_costs
+                .get(&item_id)
+                .map(|(sum, count)| sum / *count as f64)
+                .unwrap_or_default();
+            CycleCountItem {
+                consumption_value: consumed_units.get(&item_id).copied().unwrap_or_default()
+                    * unit_cost,
+                last_counted_datetime: last_counted.get(&item_id).copied(),
+                class: CycleCountClass::C,
+                item_id,
+            }
+        })
+        .collect();
+    items.sort_by(|a, b| b.consumption_value.total_cmp(&a.consumption_value));
+
+    let total_value: f64 = items.iter().map(|item| item.consumption_value).sum();
+    if total_value > 0.0 {
+        let mut cumulative = 0.0;
+        for item in items.iter_mut() {
+            if item.consumption_value <= 0.0 {
+                break;
+            }
+            // Class is based on the share before the item, the highest value item is always A
+            let percentage = cumulative / total_value * 100.0;
+            item.class = if percentage < plan.a_class_percentage {
+                CycleCountClass::A
+            } else if percentage < plan.b_class_percentage {
+                CycleCountClass::B
+            } else {
+                CycleCountClass::C
+            };
+            cumulative += item.consumption_value;
+        }
+    }
+
+    Ok(items)
+}
+
+/// Latest finalised datetime, by item, of the finalised stocktakes of the store in the period
+/// where the item was counted
+fn get_last_counted(
+    connection: &StorageConnection,
+    store_id: &str,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<HashMap<String, NaiveDateTime>, RepositoryError> {
+    let stocktakes = StocktakeRepository::new(connection).query(
+        Pagination::all(),
+        Some(
+            StocktakeFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .status(StocktakeStatus::Finalised.equal_to())
+                .finalised_datetime(DatetimeFilter::date_range(from, to)),
+        ),
+        None,
+    )?;
+    let finalised: HashMap<String, NaiveDateTime> = stocktakes
+        .into_iter()
+        .filter_map(|stocktake| Some((stocktake.id, stocktake.finalised_datetime?)))
+        .collect();
+    if finalised.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new()
+            .stocktake_id(EqualFilter::equal_any(finalised.keys().cloned().collect())),
+        Some(store_id.to_string()),
+    )?;
+
+    let mut last_counted: HashMap<String, NaiveDateTime> = HashMap::new();
+    for line in lines {
+        if line.line.counted_number_of_packs.is_none() {
+            continue;
+        }
+        let Some(finalised_datetime) = finalised.get(&line.line.stocktake_id) else {
+            continue;
+        };
+        let last = last_counted
+            .entry(line.item.id)
+            .or_insert(*finalised_datetime);
+        *last = (*last).max(*finalised_datetime);
+    }
+    Ok(last_counted)
+}
+
+impl From<RepositoryError> for UpsertCycleCountPlanError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertCycleCountPlanError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeleteCycleCountPlanError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteCycleCountPlanError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for CycleCountCoverageError {
+    fn from(error: RepositoryError) -> Self {
+        CycleCountCoverageError::DatabaseError(error)
+    }
+}
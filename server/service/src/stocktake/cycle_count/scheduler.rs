@@ -0,0 +1,425 @@
+use std::{collections::HashSet, sync::Arc};
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use repository::{
+    CycleCountPlanRow, CycleCountPlanRowRepository, CycleCountPlanRunRow,
+    CycleCountPlanRunRowRepository, EqualFilter, Pagination, RepositoryError, StocktakeFilter,
+    StocktakeLineFilter, StocktakeLineRepository, StocktakeLineRowRepository, StocktakeRepository,
+    StocktakeStatus, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::{ServiceContext, ServiceProvider},
+    stocktake::{
+        insert::generate_lines_for_items, insert_stocktake, InsertStocktake, InsertStocktakeError,
+    },
+};
+
+use super::{class_interval_days, classify_cycle_count_items, CycleCountClass, CycleCountItem};
+
+/// How often to check for due cycle count plans, plans run with day precision
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+/// Runs checked for stocktakes that are still open
+const OPEN_STOCKTAKE_RUN_LIMIT: u32 = 20;
+
+#[derive(Debug, PartialEq)]
+pub enum RunCycleCountPlanError {
+    InsertStocktakeError(InsertStocktakeError),
+    DatabaseError(RepositoryError),
+}
+
+/// Active plans that never ran, or whose last run is at least `run_interval_days` ago
+pub fn get_due_cycle_count_plans(
+    connection: &StorageConnection,
+    now: NaiveDateTime,
+) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+    let run_repository = CycleCountPlanRunRowRepository::new(connection);
+
+    let mut result = Vec::new();
+    for plan in CycleCountPlanRowRepository::new(connection).find_many_active()? {
+        let is_due = match run_repository.find_latest_by_plan_id(&plan.id)? {
+            Some(run) => run.run_datetime + Duration::days(plan.run_interval_days as i64) <= now,
+            None => true,
+        };
+        if is_due {
+            result.push(plan);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Items to count in the next stocktake of the plan. Each class is spread over its interval:
+/// every run takes its share of the class's items (those counted longest ago first), plus any
+/// items that are overdue. Items that were counted recently, or are in a stocktake of the plan
+/// that is still open, are skipped
+pub fn get_items_due_for_count(
+    connection: &StorageConnection,
+    plan: &CycleCountPlanRow,
+    now: NaiveDateTime,
+) -> Result<Vec<CycleCountItem>, RepositoryError> {
+    let open_item_ids = get_open_item_ids(connection, plan)?;
+    let items = classify_cycle_count_items(connection, plan, now)?;
+    let run_interval_days = plan.run_interval_days as i64;
+
+    let mut result = Vec::new();
+    for class in [CycleCountClass::A, CycleCountClass::B, CycleCountClass::C] {
+        let interval_days = class_interval_days(plan, class) as i64;
+        let mut class_items: Vec<&CycleCountItem> =
+            items.iter().filter(|item| item.class == class).collect();
+        let quota =
+            (class_items.len() as i64 * run_interval_days + interval_days - 1) / interval_days;
+
+        // Never counted first, then the ones counted longest ago
+        class_items.sort_by_key(|item| item.last_counted_datetime);
+        let recently_counted_after =
+            now - Duration::days((interval_days - run_interval_days).max(0));
+
+        let mut selected = 0;
+        for item in class_items {
+            if open_item_ids.contains(&item.item_id) {
+                continue;
+            }
+            let is_overdue = item
+                .last_counted_datetime
+                .is_some_and(|last| last + Duration::days(interval_days) <= now);
+            let is_eligible = item
+                .last_counted_datetime
+                .map_or(true, |last| last <= recently_counted_after);
+            if is_overdue || (is_eligible && selected < quota) {
+                selected += 1;
+                result.push(item.clone());
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Items in stocktakes generated by the plan that are not finalised yet
+fn get_open_item_ids(
+    connection: &StorageConnection,
+    plan: &CycleCountPlanRow,
+) -> Result<HashSet<String>, RepositoryError> {
+    let stocktake_ids: Vec<String> = CycleCountPlanRunRowRepository::new(connection)
+        .find_many_by_plan_id(&plan.id, Some(OPEN_STOCKTAKE_RUN_LIMIT))?
+        .into_iter()
+        .filter_map(|run| run.stocktake_id)
+        .collect();
+    if stocktake_ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let open_stocktake_ids: Vec<String> = StocktakeRepository::new(connection)
+        .query(
+            Pagination::all(),
+            Some(StocktakeFilter::new().id(EqualFilter::equal_any(stocktake_ids))),
+            None,
+        )?
+        .into_iter()
+        .filter(|stocktake| stocktake.status != StocktakeStatus::Finalised)
+        .map(|stocktake| stocktake.id)
+        .collect();
+    if open_stocktake_ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_any(open_stocktake_ids)),
+        Some(plan.store_id.clone()),
+    )?;
+    Ok(lines.into_iter().map(|line| line.item.id).collect())
+}
+
+/// Generates the plan's next partial stocktake with the items that are due for counting. The run
+/// is recorded without a stocktake if no items are due
+pub fn run_cycle_count_plan(
+    ctx: &ServiceContext,
+    plan: &CycleCountPlanRow,
+    now: NaiveDateTime,
+) -> Result<CycleCountPlanRunRow, RunCycleCountPlanError> {
+    let run = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let items = get_items_due_for_count(connection, plan, now)?;
+
+            let stocktake_id = if items.is_empty() {
+                None
+            } else {
+                let stocktake = insert_stocktake(
+                    ctx,
+                    InsertStocktake {
+                        id: uuid(),
+                        description: Some(format!("Cycle count: {}", plan.name)),
+                        ..Default::default()
+                    },
+                )
+                .map_err(RunCycleCountPlanError::InsertStocktakeError)?;
+
+                let item_ids: Vec<String> = items.iter().map(|item| item.item_id.clone()).collect();
+                let line_repository = StocktakeLineRowRepository::new(connection);
+                for line in
+                    generate_lines_for_items(connection, &plan.store_id, &stocktake.id, &item_ids)?
+                {
+                    line_repository.upsert_one(&line)?;
+                }
+                Some(stocktake.id)
+            };
+
+            let run = CycleCountPlanRunRow {
+                id: uuid(),
+                cycle_count_plan_id: plan.id.clone(),
+                run_datetime: now,
+                stocktake_id,
+                item_count: items.len() as i32,
+            };
+            CycleCountPlanRunRowRepository::new(connection).upsert_one(&run)?;
+
+            Ok(run)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(run)
+}
+
+impl From<RepositoryError> for RunCycleCountPlanError {
+    fn from(error: RepositoryError) -> Self {
+        RunCycleCountPlanError::DatabaseError(error)
+    }
+}
+
+/// Periodically generates the stocktakes of due cycle count plans
+pub struct CycleCountScheduler;
+
+impl CycleCountScheduler {
+    /// CycleCountScheduler entry point, this method is meant to be run within main `select!`
+    /// macro
+    pub async fn run(service_provider: Arc<ServiceProvider>) {
+        loop {
+            if let Err(error) = Self::run_due_plans(&service_provider) {
+                log::error!("Problem running cycle count plans: {:#}", error);
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    }
+
+    pub fn run_due_plans(service_provider: &ServiceProvider) -> anyhow::Result<()> {
+        let now = Utc::now().naive_utc();
+        let connection = service_provider.connection()?;
+
+        for plan in get_due_cycle_count_plans(&connection, now)? {
+            let ctx = service_provider.context(plan.store_id.clone(), plan.user_id.clone())?;
+            match run_cycle_count_plan(&ctx, &plan, now) {
+                Ok(run) => log::info!(
+                    "Cycle count plan {} generated stocktake {:?} with {} items",
+                    plan.id,
+                    run.stocktake_id,
+                    run.item_count
+                ),
+                Err(error) => log::error!("Cycle count plan {} failed: {:?}", plan.id, error),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{mock_name_a, mock_store_a, mock_user_account_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        CycleCountPlanRow, CycleCountPlanRowRepository, EqualFilter, InvoiceLineRow,
+        InvoiceLineType, InvoiceRow, InvoiceType, ItemRow, StockLineRow, StocktakeLineFilter,
+        StocktakeLineRepository, StocktakeLineRowRepository, StocktakeRowRepository,
+        StocktakeStatus,
+    };
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake::cycle_count::{get_cycle_count_coverage, CycleCountClass},
+    };
+
+    use super::{get_due_cycle_count_plans, get_items_due_for_count, run_cycle_count_plan};
+
+    fn item(id: &str) -> ItemRow {
+        ItemRow {
+            id: id.to_string(),
+            name: id.to_string(),
+            code: id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[actix_rt::test]
+    async fn cycle_count_plan() {
+        let now = Utc::now().naive_utc();
+        let consumed = [("item_1", 900.0), ("item_2", 80.0), ("item_3", 20.0)];
+        let mock_data = MockData {
+            items: ["item_1", "item_2", "item_3", "item_4"].map(item).to_vec(),
+            stock_lines: ["item_1", "item_2", "item_3", "item_4"]
+                .map(|item_id| StockLineRow {
+                    id: format!("{item_id}_stock_line"),
+                    item_link_id: item_id.to_string(),
+                    store_id: mock_store_a().id,
+                    pack_size: 1.0,
+                    cost_price_per_pack: 1.0,
+                    total_number_of_packs: 10.0,
+                    available_number_of_packs: 10.0,
+                    ..Default::default()
+                })
+                .to_vec(),
+            invoices: vec![InvoiceRow {
+                id: "consumption".to_string(),
+                store_id: mock_store_a().id,
+                name_link_id: mock_name_a().id,
+                r#type: InvoiceType::OutboundShipment,
+                picked_datetime: Some(now - Duration::days(10)),
+                ..Default::default()
+            }],
+            invoice_lines: consumed
+                .map(|(item_id, number_of_packs)| InvoiceLineRow {
+                    id: format!("{item_id}_consumption"),
+                    invoice_id: "consumption".to_string(),
+                    item_link_id: item_id.to_string(),
+                    r#type: InvoiceLineType::StockOut,
+                    pack_size: 1.0,
+                    number_of_packs,
+                    ..Default::default()
+                })
+                .to_vec(),
+            ..Default::default()
+        };
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "cycle_count_plan",
+            MockDataInserts::none()
+                .names()
+                .stores()
+                .units()
+                .user_accounts(),
+            mock_data,
+        )
+        .await;
+
+        let plan = CycleCountPlanRow {
+            id: "plan".to_string(),
+            store_id: mock_store_a().id,
+            user_id: mock_user_account_a().id,
+            name: "Monthly".to_string(),
+            a_class_percentage: 80.0,
+            b_class_percentage: 95.0,
+            a_class_interval_days: 30,
+            b_class_interval_days: 90,
+            c_class_interval_days: 360,
+            run_interval_days: 30,
+            consumption_look_back_months: 12,
+            is_active: true,
+            created_datetime: now,
+            ..Default::default()
+        };
+        CycleCountPlanRowRepository::new(&connection)
+            .upsert_one(&plan)
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let ctx = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+
+        // One item of each class is due, C items are counted in order of consumption value
+        let due_items: Vec<(String, CycleCountClass)> =
+            get_items_due_for_count(&connection, &plan, now)
+                .unwrap()
+                .into_iter()
+                .map(|item| (item.item_id, item.class))
+                .collect();
+        assert_eq!(
+            due_items,
+            vec![
+                ("item_1".to_string(), CycleCountClass::A),
+                ("item_2".to_string(), CycleCountClass::B),
+                ("item_3".to_string(), CycleCountClass::C),
+            ]
+        );
+
+        assert_eq!(
+            get_due_cycle_count_plans(&connection, now).unwrap().len(),
+            1
+        );
+        let run = run_cycle_count_plan(&ctx, &plan, now).unwrap();
+        assert_eq!(run.item_count, 3);
+        let stocktake_id = run.stocktake_id.unwrap();
+        assert_eq!(get_due_cycle_count_plans(&connection, now).unwrap(), vec![]);
+        assert_eq!(
+            get_due_cycle_count_plans(&connection, now + Duration::days(30))
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // Items in the open stocktake are skipped
+        let due_items: Vec<String> = get_items_due_for_count(&connection, &plan, now)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.item_id)
+            .collect();
+        assert_eq!(due_items, vec!["item_4".to_string()]);
+
+        // Count and finalise the stocktake
+        let lines = StocktakeLineRepository::new(&connection)
+            .query_by_filter(
+                StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&stocktake_id)),
+                None,
+            )
+            .unwrap();
+        assert_eq!(lines.len(), 3);
+        for mut line in lines.into_iter().map(|line| line.line) {
+            line.counted_number_of_packs = Some(line.snapshot_number_of_packs);
+            StocktakeLineRowRepository::new(&connection)
+                .upsert_one(&line)
+                .unwrap();
+        }
+        let mut stocktake = StocktakeRowRepository::new(&connection)
+            .find_one_by_id(&stocktake_id)
+            .unwrap()
+            .unwrap();
+        stocktake.status = StocktakeStatus::Finalised;
+        stocktake.finalised_datetime = Some(now);
+        StocktakeRowRepository::new(&connection)
+            .upsert_one(&stocktake)
+            .unwrap();
+
+        let coverage = get_cycle_count_coverage(
+            &ctx,
+            &plan.id,
+            now - Duration::days(30),
+            now + Duration::days(1),
+        )
+        .unwrap();
+        let percentages: Vec<(CycleCountClass, Option<f64>)> = coverage
+            .classes
+            .iter()
+            .map(|coverage| (coverage.class, coverage.statistics.percentage()))
+            .collect();
+        assert_eq!(
+            percentages,
+            vec![
+                (CycleCountClass::A, Some(100.0)),
+                (CycleCountClass::B, Some(100.0)),
+                (CycleCountClass::C, Some(50.0)),
+            ]
+        );
+        assert_eq!(coverage.total.percentage(), Some(75.0));
+
+        // On the next run the A item is due again, the counted B and C items are not
+        let due_items: Vec<String> =
+            get_items_due_for_count(&connection, &plan, now + Duration::days(30))
+                .unwrap()
+                .into_iter()
+                .map(|item| item.item_id)
+                .collect();
+        assert_eq!(due_items, vec!["item_1".to_string(), "item_4".to_string()]);
+    }
+}
@@ -2,8 +2,9 @@ mod validate;
 use validate::validate;
 
 use repository::{
-    ActivityLogType, EqualFilter, RepositoryError, StocktakeLineFilter, StocktakeLineRepository,
-    StocktakeRowRepository, TransactionError,
+    ActivityLogType, EqualFilter, RepositoryError, StocktakeBlindCountRowRepository,
+    StocktakeLineFilter, StocktakeLineRecountRowRepository, StocktakeLineRepository,
+    StocktakeRowRepository, StocktakeVarianceApprovalRowRepository, TransactionError,
 };
 
 use crate::{
@@ -54,6 +55,11 @@ pub fn delete_stocktake(
                 None,
             )?;
 
+            StocktakeVarianceApprovalRowRepository::new(connection)
+                .delete_by_stocktake_id(&stocktake_id)?;
+            StocktakeLineRecountRowRepository::new(connection)
+                .delete_by_stocktake_id(&stocktake_id)?;
+            StocktakeBlindCountRowRepository::new(connection).delete(&stocktake_id)?;
             StocktakeRowRepository::new(connection).delete(&stocktake_id)?;
             Ok(())
         })
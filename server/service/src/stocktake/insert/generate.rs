@@ -102,6 +102,16 @@ pub fn generate_lines_from_master_list(
         .map(|r| r.item_id)
         .collect();
 
+    generate_lines_for_items(connection, store_id, stocktake_id, &item_ids)
+}
+
+/// Lines for the stock of the items in the store, or an empty line for items without stock
+pub fn generate_lines_for_items(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+    item_ids: &[String],
+) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
     let mut result = Vec::<StocktakeLineRow>::new();
 
     item_ids.iter().for_each(|item_id| {
@@ -158,6 +168,7 @@ pub fn generate_lines_from_master_list(
                     available_number_of_packs: _,
                     barcode_id: _,
                     item_variant_id: _,
+                    vvm_status: _,
                 } = line.stock_line_row;
 
                 result.push(StocktakeLineRow {
@@ -219,6 +230,7 @@ pub fn generate_lines_from_location(
                 available_number_of_packs: _,
                 barcode_id: _,
                 item_variant_id: _,
+                vvm_status: _,
             } = line.stock_line_row;
 
             StocktakeLineRow {
@@ -276,6 +288,7 @@ pub fn generate_lines_with_stock(
                 available_number_of_packs: _,
                 barcode_id: _,
                 item_variant_id: _,
+                vvm_status: _,
             } = line.stock_line_row;
 
             StocktakeLineRow {
@@ -334,6 +347,7 @@ pub fn generate_lines_expiring_before(
                 available_number_of_packs: _,
                 barcode_id: _,
                 item_variant_id: _,
+                vvm_status: _,
             } = line.stock_line_row;
 
             StocktakeLineRow {
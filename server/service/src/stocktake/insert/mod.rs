@@ -3,6 +3,7 @@ use validate::validate;
 
 mod generate;
 use generate::generate;
+pub(crate) use generate::generate_lines_for_items;
 
 use chrono::NaiveDate;
 use repository::{
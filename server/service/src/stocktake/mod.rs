@@ -1,6 +1,16 @@
 use crate::{service_provider::ServiceContext, ListError, ListResult};
+use chrono::NaiveDateTime;
 use repository::PaginationOption;
-use repository::{RepositoryError, Stocktake, StocktakeFilter, StocktakeSort};
+use repository::{
+    CycleCountPlanRow, CycleCountPlanRunRow, RepositoryError, Stocktake, StocktakeBlindCountRow,
+    StocktakeFilter, StocktakeSort,
+};
+
+use self::cycle_count::{
+    delete_cycle_count_plan, get_cycle_count_coverage, get_cycle_count_plan_runs,
+    get_cycle_count_plans, upsert_cycle_count_plan, CycleCountCoverage, CycleCountCoverageError,
+    DeleteCycleCountPlanError, UpsertCycleCountPlan, UpsertCycleCountPlanError,
+};
 
 pub mod query;
 use self::query::{get_stocktake, get_stocktakes};
@@ -17,9 +27,17 @@ pub use self::update::*;
 mod batch;
 pub use self::batch::*;
 
+mod blind_count;
+pub use self::blind_count::*;
+
+pub mod cycle_count;
+
 mod validate;
 pub use self::validate::*;
 
+mod variance;
+pub use self::variance::*;
+
 pub trait StocktakeServiceTrait: Sync + Send {
     fn get_stocktakes(
         &self,
@@ -76,6 +94,90 @@ pub trait StocktakeServiceTrait: Sync + Send {
     ) -> Result<BatchStocktakeResult, RepositoryError> {
         batch_stocktake(ctx, input)
     }
+
+    fn get_stocktake_variances(
+        &self,
+        ctx: &ServiceContext,
+        stocktake_id: &str,
+    ) -> Result<Vec<StocktakeLineVariance>, RepositoryError> {
+        get_stocktake_variances(ctx, stocktake_id)
+    }
+
+    fn approve_stocktake_variances(
+        &self,
+        ctx: &ServiceContext,
+        input: ApproveStocktakeVariances,
+    ) -> Result<Vec<StocktakeLineVariance>, ApproveStocktakeVariancesError> {
+        approve_stocktake_variances(ctx, input)
+    }
+
+    fn get_stocktake_blind_count(
+        &self,
+        ctx: &ServiceContext,
+        stocktake_id: &str,
+    ) -> Result<Option<StocktakeBlindCountRow>, RepositoryError> {
+        get_stocktake_blind_count(ctx, stocktake_id)
+    }
+
+    fn set_stocktake_blind_count(
+        &self,
+        ctx: &ServiceContext,
+        input: SetStocktakeBlindCount,
+    ) -> Result<Option<StocktakeBlindCountRow>, SetStocktakeBlindCountError> {
+        set_stocktake_blind_count(ctx, input)
+    }
+
+    fn submit_stocktake_blind_count(
+        &self,
+        ctx: &ServiceContext,
+        stocktake_id: &str,
+    ) -> Result<StocktakeBlindCountSubmission, SubmitStocktakeBlindCountError> {
+        submit_stocktake_blind_count(ctx, stocktake_id)
+    }
+
+    fn get_cycle_count_plans(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+        get_cycle_count_plans(ctx, store_id)
+    }
+
+    fn get_cycle_count_plan_runs(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        cycle_count_plan_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<CycleCountPlanRunRow>, RepositoryError> {
+        get_cycle_count_plan_runs(ctx, store_id, cycle_count_plan_id, limit)
+    }
+
+    fn upsert_cycle_count_plan(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertCycleCountPlan,
+    ) -> Result<CycleCountPlanRow, UpsertCycleCountPlanError> {
+        upsert_cycle_count_plan(ctx, input)
+    }
+
+    fn delete_cycle_count_plan(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<String, DeleteCycleCountPlanError> {
+        delete_cycle_count_plan(ctx, id)
+    }
+
+    fn get_cycle_count_coverage(
+        &self,
+        ctx: &ServiceContext,
+        cycle_count_plan_id: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> Result<CycleCountCoverage, CycleCountCoverageError> {
+        get_cycle_count_coverage(ctx, cycle_count_plan_id, from, to)
+    }
 }
 
 pub struct StocktakeService {}
@@ -156,6 +156,7 @@ fn generate_stock_in_out_or_update(
             // Default
             total_before_tax: None,
             tax_percentage: None,
+            quantity_in_unit: None,
         })
     } else {
         StockChange::StockOut(InsertStockOutLine {
@@ -173,6 +174,7 @@ fn generate_stock_in_out_or_update(
             sell_price_per_pack: Some(sell_price_per_pack),
             total_before_tax: None,
             tax_percentage: None,
+            quantity_in_unit: None,
         })
     };
 
@@ -327,6 +329,7 @@ fn generate_new_stock_line(
         total_before_tax: None,
         tax_percentage: None,
         item_variant_id: None,
+        quantity_in_unit: None,
     });
 
     // If new stock line has a location, create location movement
@@ -63,6 +63,14 @@ pub enum UpdateStocktakeError {
     /// Holds list of affected stock lines
     SnapshotCountCurrentCountMismatch(Vec<StocktakeLine>),
     StockLinesReducedBelowZero(Vec<StockLine>),
+    /// Lines with a variance above the store's thresholds without an adjustment reason or comment
+    VarianceReasonNotProvided(Vec<StocktakeLine>),
+    /// Lines with a variance above the store's thresholds that haven't been approved
+    VarianceNotApproved(Vec<StocktakeLine>),
+    /// Blind count stocktakes can only be finalised once the count is submitted
+    BlindCountNotSubmitted,
+    /// Finalising would adjust stock within a closed period
+    DateInClosedPeriod,
 }
 
 pub fn update_stocktake(
@@ -185,7 +193,7 @@ mod test {
             mock_stocktake_stock_deficit, mock_stocktake_stock_surplus, mock_store_a, MockData,
             MockDataInserts,
         },
-        test_db::setup_all_with_data,
+        test_db::{setup_all, setup_all_with_data},
         EqualFilter, InvoiceLineRepository, InvoiceLineRowRepository, InvoiceLineType,
         StockLineRow, StockLineRowRepository, StocktakeLine, StocktakeLineFilter,
         StocktakeLineRepository, StocktakeLineRow, StocktakeLineRowRepository, StocktakeRepository,
@@ -194,6 +202,7 @@ mod test {
     use util::{constants::INVENTORY_ADJUSTMENT_NAME_CODE, inline_edit, inline_init};
 
     use crate::{
+        period_close::close_period_for_today,
         service_provider::ServiceProvider,
         stocktake::{
             update::{UpdateStocktake, UpdateStocktakeError},
@@ -650,4 +659,28 @@ mod test {
         // still has initial batch name (was not updated)
         assert_eq!(stock_line.batch, Some("initial batch name".to_string()),);
     }
+
+    #[actix_rt::test]
+    async fn update_stocktake_in_closed_period() {
+        let (_, _, connection_manager, _) =
+            setup_all("update_stocktake_in_closed_period", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.stocktake_service;
+
+        close_period_for_today(&context.connection, &mock_store_a().id);
+        let error = service
+            .update_stocktake(
+                &context,
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = mock_stocktake_stock_surplus().id;
+                    i.status = Some(UpdateStocktakeStatus::Finalised);
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(error, UpdateStocktakeError::DateInClosedPeriod);
+    }
 }
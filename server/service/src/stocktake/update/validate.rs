@@ -1,10 +1,15 @@
+use chrono::Utc;
 use repository::{
-    EqualFilter, RepositoryError, StockLine, StockLineFilter, StockLineRepository, StocktakeLine,
-    StocktakeLineFilter, StocktakeLineRepository, StocktakeRow, StorageConnection,
+    EqualFilter, RepositoryError, StockLine, StockLineFilter, StockLineRepository,
+    StocktakeBlindCountRowRepository, StocktakeLine, StocktakeLineFilter, StocktakeLineRepository,
+    StocktakeRow, StorageConnection,
 };
 
 use crate::{
-    stocktake::{check_stocktake_exist, check_stocktake_not_finalised},
+    period_close::validate_not_in_closed_period,
+    stocktake::{
+        calculate_stocktake_variances, check_stocktake_exist, check_stocktake_not_finalised,
+    },
     validate::check_store_id_matches,
 };
 
@@ -38,6 +43,17 @@ pub fn validate(
             return Err(UpdateStocktakeError::NoLines);
         }
 
+        if !validate_not_in_closed_period(connection, store_id, Utc::now().naive_utc())? {
+            return Err(UpdateStocktakeError::DateInClosedPeriod);
+        }
+
+        if StocktakeBlindCountRowRepository::new(connection)
+            .find_one_by_id(&existing.id)?
+            .is_some_and(|blind_count| !blind_count.is_submitted())
+        {
+            return Err(UpdateStocktakeError::BlindCountNotSubmitted);
+        }
+
         if let Some(stock_reduced_to_zero) =
             check_stock_lines_reduced_to_zero(connection, &stocktake_lines)?
         {
@@ -51,6 +67,31 @@ pub fn validate(
                 mismatches,
             ));
         }
+
+        let variances = calculate_stocktake_variances(
+            connection,
+            store_id,
+            &existing.id,
+            stocktake_lines.clone(),
+        )?;
+        let without_reason: Vec<StocktakeLine> = variances
+            .iter()
+            .filter(|variance| variance.requires_approval && !variance.has_reason())
+            .map(|variance| variance.line.clone())
+            .collect();
+        if !without_reason.is_empty() {
+            return Err(UpdateStocktakeError::VarianceReasonNotProvided(
+                without_reason,
+            ));
+        }
+        let not_approved: Vec<StocktakeLine> = variances
+            .into_iter()
+            .filter(|variance| !variance.is_approved())
+            .map(|variance| variance.line)
+            .collect();
+        if !not_approved.is_empty() {
+            return Err(UpdateStocktakeError::VarianceNotApproved(not_approved));
+        }
     }
 
     Ok((existing, stocktake_lines, status_changed))
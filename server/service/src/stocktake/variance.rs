@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use repository::{
+    ActivityLogType, EqualFilter, RepositoryError, StocktakeLine, StocktakeLineFilter,
+    StocktakeLineRepository, StocktakeRow, StocktakeVarianceApprovalRow,
+    StocktakeVarianceApprovalRowRepository, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    activity_log::activity_log_entry,
+    service_provider::ServiceContext,
+    stocktake::{check_stocktake_exist, check_stocktake_not_finalised},
+    store_preference::get_store_preferences,
+    validate::check_store_id_matches,
+};
+
+/// Adjustment a stocktake line will create when the stocktake is finalised
+#[derive(Debug, Clone, PartialEq)]
+pub struct StocktakeLineVariance {
+    pub line: StocktakeLine,
+    /// Counted minus snapshot number of packs
+    pub difference_number_of_packs: f64,
+    /// Absolute value of the difference at cost price
+    pub value: f64,
+    /// Absolute difference in percent of the snapshot, None for lines without snapshot stock
+    pub percentage: Option<f64>,
+    /// Variance exceeds the value or percentage threshold of the store
+    pub requires_approval: bool,
+    /// Latest approval that is still valid for the counted number of packs
+    pub approval: Option<StocktakeVarianceApprovalRow>,
+}
+
+impl StocktakeLineVariance {
+    /// Lines above the thresholds need an adjustment reason or a comment
+    pub fn has_reason(&self) -> bool {
+        self.line.line.inventory_adjustment_reason_id.is_some()
+            || self
+                .line
+                .line
+                .comment
+                .as_ref()
+                .is_some_and(|comment| !comment.trim().is_empty())
+    }
+
+    pub fn is_approved(&self) -> bool {
+        !self.requires_approval || self.approval.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ApproveStocktakeVariances {
+    pub stocktake_id: String,
+    /// Lines to approve, all lines requiring approval if empty
+    pub line_ids: Vec<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ApproveStocktakeVariancesError {
+    DatabaseError(RepositoryError),
+    InvalidStore,
+    StocktakeDoesNotExist,
+    CannotEditFinalised,
+    /// Variances must be approved by a different user than the one who created the stocktake
+    CannotApproveOwnStocktake,
+    LineDoesNotExist(String),
+    LineDoesNotRequireApproval(String),
+    ReasonNotProvided(String),
+}
+
+/// Variances of the counted lines of a stocktake that have a difference
+pub fn get_stocktake_variances(
+    ctx: &ServiceContext,
+    stocktake_id: &str,
+) -> Result<Vec<StocktakeLineVariance>, RepositoryError> {
+    let lines = StocktakeLineRepository::new(&ctx.connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(stocktake_id)),
+        Some(ctx.store_id.clone()),
+    )?;
+    calculate_stocktake_variances(&ctx.connection, &ctx.store_id, stocktake_id, lines)
+}
+
+pub(crate) fn calculate_stocktake_variances(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+    lines: Vec<StocktakeLine>,
+) -> Result<Vec<StocktakeLineVariance>, RepositoryError> {
+    let preferences = get_store_preferences(connection, store_id)?;
+    let value_threshold = preferences.stocktake_variance_value_threshold;
+    let percentage_threshold = preferences.stocktake_variance_percentage_threshold;
+
+    // Approvals are sorted latest first, keep the latest per line
+    let mut approvals: HashMap<String, StocktakeVarianceApprovalRow> = HashMap::new();
+    for approval in StocktakeVarianceApprovalRowRepository::new(connection)
+        .find_many_by_stocktake_id(stocktake_id)?
+    {
+        approvals
+            .entry(approval.stocktake_line_id.clone())
+            .or_insert(approval);
+    }
+
+    let variances = lines
+        .into_iter()
+        .filter_map(|line| {
+            let counted_number_of_packs = line.line.counted_number_of_packs?;
+            let snapshot_number_of_packs = line.line.snapshot_number_of_packs;
+            let difference_number_of_packs = counted_number_of_packs - snapshot_number_of_packs;
+            if difference_number_of_packs == 0.0 {
+                return None;
+            }
+
+            let cost_price_per_pack = line
+                .line
+                .cost_price_per_pack
+                .or_else(|| line.stock_line.as_ref().map(|s| s.cost_price_per_pack))
+                .unwrap_or_default();
+            let value = difference_number_of_packs.abs() * cost_price_per_pack;
+            let percentage = (snapshot_number_of_packs > 0.0)
+                .then(|| difference_number_of_packs.abs() / snapshot_number_of_packs * 100.0);
+
+            let requires_approval = (value_threshold > 0.0 && value > value_threshold)
+                || (percentage_threshold > 0.0
+                    && percentage.is_some_and(|percentage| percentage > percentage_threshold));
+            let approval = approvals
+                .remove(&line.line.id)
+                .filter(|approval| approval.counted_number_of_packs == counted_number_of_packs);
+
+            Some(StocktakeLineVariance {
+                line,
+                difference_number_of_packs,
+                value,
+                percentage,
+                requires_approval,
+                approval,
+            })
+        })
+        .collect();
+
+    Ok(variances)
+}
+
+/// Approves variances of stocktake lines above the store's thresholds. Each approval is recorded
+/// in the activity log of the stocktake
+pub fn approve_stocktake_variances(
+    ctx: &ServiceContext,
+    input: ApproveStocktakeVariances,
+) -> Result<Vec<StocktakeLineVariance>, ApproveStocktakeVariancesError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (stocktake, variances) = validate(connection, ctx, &input)?;
+
+            let repository = StocktakeVarianceApprovalRowRepository::new(connection);
+            let approved_datetime = Utc::now().naive_utc();
+            let mut approved = Vec::new();
+            for mut variance in variances {
+                let approval = StocktakeVarianceApprovalRow {
+                    id: uuid(),
+                    stocktake_id: stocktake.id.clone(),
+                    stocktake_line_id: variance.line.line.id.clone(),
+                    counted_number_of_packs: variance
+                        .line
+                        .line
+                        .counted_number_of_packs
+                        .unwrap_or_default(),
+                    approved_by_user_id: ctx.user_id.clone(),
+                    approved_datetime,
+                    comment: input.comment.clone(),
+                };
+                repository.upsert_one(&approval)?;
+
+                activity_log_entry(
+                    ctx,
+                    ActivityLogType::StocktakeVarianceApproved,
+                    Some(stocktake.id.clone()),
+                    Some(format!(
+                        "{}: {}",
+                        variance.line.item.name, variance.line.line.snapshot_number_of_packs
+                    )),
+                    Some(format!(
+                        "{}: {}",
+                        variance.line.item.name, approval.counted_number_of_packs
+                    )),
+                )?;
+
+                variance.approval = Some(approval);
+                approved.push(variance);
+            }
+
+            Ok(approved)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+/// Returns the stocktake and the variances to approve
+fn validate(
+    connection: &StorageConnection,
+    ctx: &ServiceContext,
+    input: &ApproveStocktakeVariances,
+) -> Result<(StocktakeRow, Vec<StocktakeLineVariance>), ApproveStocktakeVariancesError> {
+    use ApproveStocktakeVariancesError::*;
+
+    let stocktake =
+        check_stocktake_exist(connection, &input.stocktake_id)?.ok_or(StocktakeDoesNotExist)?;
+    if !check_store_id_matches(&ctx.store_id, &stocktake.store_id) {
+        return Err(InvalidStore);
+    }
+    if !check_stocktake_not_finalised(&stocktake.status) {
+        return Err(CannotEditFinalised);
+    }
+    if stocktake.user_id == ctx.user_id {
+        return Err(CannotApproveOwnStocktake);
+    }
+
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&stocktake.id)),
+        Some(ctx.store_id.clone()),
+    )?;
+    let line_ids: Vec<String> = lines.iter().map(|line| line.line.id.clone()).collect();
+    let variances = calculate_stocktake_variances(connection, &ctx.store_id, &stocktake.id, lines)?;
+
+    let variances = if input.line_ids.is_empty() {
+        variances
+            .into_iter()
+            .filter(|variance| !variance.is_approved())
+            .collect()
+    } else {
+        let mut by_line_id: HashMap<String, StocktakeLineVariance> = variances
+            .into_iter()
+            .map(|variance| (variance.line.line.id.clone(), variance))
+            .collect();
+        input
+            .line_ids
+            .iter()
+            .map(|line_id| match by_line_id.remove(line_id) {
+                Some(variance) if variance.requires_approval => Ok(variance),
+                // Counted lines without a difference have no variance
+                Some(_) => Err(LineDoesNotRequireApproval(line_id.clone())),
+                None if line_ids.contains(line_id) => {
+                    Err(LineDoesNotRequireApproval(line_id.clone()))
+                }
+                None => Err(LineDoesNotExist(line_id.clone())),
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    if let Some(variance) = variances.iter().find(|variance| !variance.has_reason()) {
+        return Err(ReasonNotProvided(variance.line.line.id.clone()));
+    }
+
+    Ok((stocktake, variances))
+}
+
+impl From<RepositoryError> for ApproveStocktakeVariancesError {
+    fn from(error: RepositoryError) -> Self {
+        ApproveStocktakeVariancesError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_stocktake_line_stock_deficit, mock_stocktake_stock_deficit, mock_store_a,
+            mock_user_account_a, mock_user_account_b, MockDataInserts,
+        },
+        test_db::setup_all,
+        ActivityLogRowRepository, ActivityLogType, StocktakeLineRow, StocktakeLineRowRepository,
+        StocktakeRow, StocktakeRowRepository, StocktakeStatus, StorePreferenceRow,
+        StorePreferenceRowRepository,
+    };
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake::{
+            ApproveStocktakeVariances, ApproveStocktakeVariancesError, UpdateStocktake,
+            UpdateStocktakeError, UpdateStocktakeStatus,
+        },
+    };
+
+    #[actix_rt::test]
+    async fn approve_stocktake_variances() {
+        let (_, connection, connection_manager, _) =
+            setup_all("approve_stocktake_variances", MockDataInserts::all()).await;
+
+        StorePreferenceRowRepository::new(&connection)
+            .upsert_one(&StorePreferenceRow {
+                id: mock_store_a().id,
+                stocktake_variance_percentage_threshold: 10.0,
+                ..Default::default()
+            })
+            .unwrap();
+        StocktakeRowRepository::new(&connection)
+            .upsert_one(&StocktakeRow {
+                user_id: mock_user_account_a().id,
+                ..mock_stocktake_stock_deficit()
+            })
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let counter = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let approver = service_provider
+            .context(mock_store_a().id, mock_user_account_b().id)
+            .unwrap();
+        let service = service_provider.stocktake_service;
+        let finalise = || UpdateStocktake {
+            id: mock_stocktake_stock_deficit().id,
+            status: Some(UpdateStocktakeStatus::Finalised),
+            ..Default::default()
+        };
+        let approve = || ApproveStocktakeVariances {
+            stocktake_id: mock_stocktake_stock_deficit().id,
+            comment: Some("Checked the count".to_string()),
+            ..Default::default()
+        };
+
+        let variances = service
+            .get_stocktake_variances(&counter, &mock_stocktake_stock_deficit().id)
+            .unwrap();
+        assert_eq!(variances.len(), 1);
+        assert_eq!(variances[0].difference_number_of_packs, -10.0);
+        assert!(variances[0].requires_approval);
+        assert!(!variances[0].is_approved());
+
+        // Reason is required above the threshold
+        assert!(matches!(
+            service.update_stocktake(&counter, finalise()),
+            Err(UpdateStocktakeError::VarianceReasonNotProvided(_))
+        ));
+        assert_eq!(
+            service.approve_stocktake_variances(&approver, approve()),
+            Err(ApproveStocktakeVariancesError::ReasonNotProvided(
+                mock_stocktake_line_stock_deficit().id
+            ))
+        );
+        StocktakeLineRowRepository::new(&connection)
+            .upsert_one(&StocktakeLineRow {
+                comment: Some("Expired stock discarded".to_string()),
+                ..mock_stocktake_line_stock_deficit()
+            })
+            .unwrap();
+
+        // Approval is required from a second user
+        assert!(matches!(
+            service.update_stocktake(&counter, finalise()),
+            Err(UpdateStocktakeError::VarianceNotApproved(_))
+        ));
+        assert_eq!(
+            service.approve_stocktake_variances(&counter, approve()),
+            Err(ApproveStocktakeVariancesError::CannotApproveOwnStocktake)
+        );
+        assert_eq!(
+            service.approve_stocktake_variances(
+                &approver,
+                ApproveStocktakeVariances {
+                    line_ids: vec!["invalid".to_string()],
+                    ..approve()
+                }
+            ),
+            Err(ApproveStocktakeVariancesError::LineDoesNotExist(
+                "invalid".to_string()
+            ))
+        );
+
+        let approved = service
+            .approve_stocktake_variances(&approver, approve())
+            .unwrap();
+        assert_eq!(approved.len(), 1);
+        assert_eq!(
+            approved[0].approval.as_ref().unwrap().approved_by_user_id,
+            mock_user_account_b().id
+        );
+        let activity_logs = ActivityLogRowRepository::new(&connection)
+            .find_many_by_record_id(&mock_stocktake_stock_deficit().id)
+            .unwrap();
+        assert!(activity_logs.iter().any(|log| log.r#type
+            == ActivityLogType::StocktakeVarianceApproved
+            && log.user_id == Some(mock_user_account_b().id)));
+
+        let stocktake = service.update_stocktake(&counter, finalise()).unwrap();
+        assert_eq!(stocktake.status, StocktakeStatus::Finalised);
+    }
+}
@@ -21,6 +21,7 @@ pub fn generate(
         sell_price_per_pack,
         note,
         inventory_adjustment_reason_id,
+        counted_quantity_in_unit: _,
     }: InsertStocktakeLine,
 ) -> StocktakeLineRow {
     let snapshot_number_of_packs = if let Some(stock_line) = stock_line {
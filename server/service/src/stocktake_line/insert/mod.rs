@@ -4,8 +4,14 @@ mod generate;
 use generate::generate;
 
 use chrono::NaiveDate;
-use repository::{RepositoryError, StockLine, StocktakeLine, StocktakeLineRowRepository};
-
+use repository::{
+    EqualFilter, RepositoryError, StockLine, StockLineFilter, StockLineRepository, StocktakeLine,
+    StocktakeLineRowRepository, StorageConnection,
+};
+
+use crate::item::unit_of_measure::{
+    quantity_to_number_of_packs, ConvertQuantityError, PackRounding, QuantityInUnit,
+};
 use crate::NullableUpdate;
 use crate::{service_provider::ServiceContext, stocktake_line::query::get_stocktake_line};
 
@@ -25,6 +31,9 @@ pub struct InsertStocktakeLine {
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
+    /// Counted quantity in one of the item's units of measure, replaces counted_number_of_packs
+    /// when set
+    pub counted_quantity_in_unit: Option<QuantityInUnit>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -45,6 +54,7 @@ pub enum InsertStocktakeLineError {
     AdjustmentReasonNotProvided,
     AdjustmentReasonNotValid,
     StockLineReducedBelowZero(StockLine),
+    InvalidQuantityInUnit(ConvertQuantityError),
 }
 
 pub fn insert_stocktake_line(
@@ -54,6 +64,7 @@ pub fn insert_stocktake_line(
     let result = ctx
         .connection
         .transaction_sync(|connection| {
+            let input = resolve_quantity_in_unit(connection, input)?;
             let GenerateResult {
                 stock_line,
                 item_id,
@@ -71,6 +82,49 @@ pub fn insert_stocktake_line(
     Ok(result)
 }
 
+fn resolve_quantity_in_unit(
+    connection: &StorageConnection,
+    mut input: InsertStocktakeLine,
+) -> Result<InsertStocktakeLine, InsertStocktakeLineError> {
+    let Some(quantity_in_unit) = input.counted_quantity_in_unit.take() else {
+        return Ok(input);
+    };
+    // Missing stock line or item is reported by validation
+    let (item_id, pack_size) = match (&input.stock_line_id, &input.item_id) {
+        (Some(stock_line_id), _) => match StockLineRepository::new(connection)
+            .query_by_filter(
+                StockLineFilter::new().id(EqualFilter::equal_to(stock_line_id)),
+                None,
+            )?
+            .pop()
+        {
+            Some(stock_line) => (
+                stock_line.item_row.id,
+                input
+                    .pack_size
+                    .unwrap_or(stock_line.stock_line_row.pack_size),
+            ),
+            None => return Ok(input),
+        },
+        // Pack size is needed to count a new batch in a unit of measure
+        (None, Some(item_id)) => (item_id.clone(), input.pack_size.unwrap_or_default()),
+        (None, None) => return Ok(input),
+    };
+
+    input.counted_number_of_packs = Some(
+        quantity_to_number_of_packs(
+            connection,
+            &item_id,
+            &quantity_in_unit,
+            pack_size,
+            PackRounding::Fractional,
+        )
+        .map_err(InsertStocktakeLineError::InvalidQuantityInUnit)?,
+    );
+
+    Ok(input)
+}
+
 impl From<RepositoryError> for InsertStocktakeLineError {
     fn from(error: RepositoryError) -> Self {
         InsertStocktakeLineError::DatabaseError(error)
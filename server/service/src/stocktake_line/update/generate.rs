@@ -17,6 +17,7 @@ pub fn generate(
         sell_price_per_pack,
         note,
         inventory_adjustment_reason_id,
+        counted_quantity_in_unit: _,
     }: UpdateStocktakeLine,
 ) -> Result<StocktakeLineRow, UpdateStocktakeLineError> {
     let existing_line = existing.line;
@@ -4,10 +4,18 @@ mod validate;
 use validate::validate;
 
 use chrono::NaiveDate;
-use repository::{RepositoryError, StockLine, StocktakeLine, StocktakeLineRowRepository};
+use repository::{
+    EqualFilter, RepositoryError, StockLine, StocktakeLine, StocktakeLineFilter,
+    StocktakeLineRepository, StocktakeLineRowRepository, StorageConnection,
+};
 
 use crate::{
-    service_provider::ServiceContext, stocktake_line::query::get_stocktake_line, NullableUpdate,
+    item::unit_of_measure::{
+        quantity_to_number_of_packs, ConvertQuantityError, PackRounding, QuantityInUnit,
+    },
+    service_provider::ServiceContext,
+    stocktake_line::query::get_stocktake_line,
+    NullableUpdate,
 };
 
 #[derive(Default, Debug, Clone)]
@@ -24,6 +32,9 @@ pub struct UpdateStocktakeLine {
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
+    /// Counted quantity in one of the item's units of measure, replaces counted_number_of_packs
+    /// when set
+    pub counted_quantity_in_unit: Option<QuantityInUnit>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -40,6 +51,7 @@ pub enum UpdateStocktakeLineError {
     AdjustmentReasonNotValid,
     SnapshotCountCurrentCountMismatchLine(StocktakeLine),
     StockLineReducedBelowZero(StockLine),
+    InvalidQuantityInUnit(ConvertQuantityError),
 }
 
 pub fn update_stocktake_line(
@@ -49,6 +61,7 @@ pub fn update_stocktake_line(
     let result = ctx
         .connection
         .transaction_sync(|connection| {
+            let input = resolve_quantity_in_unit(connection, input)?;
             let existing = validate(connection, &ctx.store_id, &input)?;
             let new_stocktake_line = generate(existing, input)?;
             StocktakeLineRowRepository::new(connection).upsert_one(&new_stocktake_line)?;
@@ -62,6 +75,49 @@ pub fn update_stocktake_line(
     Ok(result)
 }
 
+fn resolve_quantity_in_unit(
+    connection: &StorageConnection,
+    mut input: UpdateStocktakeLine,
+) -> Result<UpdateStocktakeLine, UpdateStocktakeLineError> {
+    let Some(quantity_in_unit) = input.counted_quantity_in_unit.take() else {
+        return Ok(input);
+    };
+    // Missing line is reported by validation
+    let Some(StocktakeLine {
+        line,
+        item,
+        stock_line,
+        ..
+    }) = StocktakeLineRepository::new(connection)
+        .query_by_filter(
+            StocktakeLineFilter::new().id(EqualFilter::equal_to(&input.id)),
+            None,
+        )?
+        .pop()
+    else {
+        return Ok(input);
+    };
+    // Pack size is needed to count a new batch in a unit of measure
+    let pack_size = input
+        .pack_size
+        .or(line.pack_size)
+        .or(stock_line.map(|stock_line| stock_line.pack_size))
+        .unwrap_or_default();
+
+    input.counted_number_of_packs = Some(
+        quantity_to_number_of_packs(
+            connection,
+            &item.id,
+            &quantity_in_unit,
+            pack_size,
+            PackRounding::Fractional,
+        )
+        .map_err(UpdateStocktakeLineError::InvalidQuantityInUnit)?,
+    );
+
+    Ok(input)
+}
+
 #[cfg(test)]
 mod stocktake_line_test {
     use chrono::NaiveDate;
@@ -5,7 +5,10 @@ use repository::{
 };
 
 use crate::{
-    document::is_latest_doc,
+    document::{
+        document_names, is_latest_doc,
+        merge::{merge_document_heads, resolve_document_conflicts},
+    },
     programs::{
         contact_trace::{
             contact_trace_schema::SchemaContactTrace,
@@ -21,8 +24,7 @@ pub(crate) struct DocumentUpsert(pub(crate) Document);
 
 impl Upsert for DocumentUpsert {
     fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
-        let change_log_id = sync_upsert_document(con, &self.0)?;
-        Ok(Some(change_log_id))
+        sync_upsert_document(con, &self.0)
     }
 
     fn assert_upserted(&self, con: &StorageConnection) {
@@ -33,21 +35,44 @@ impl Upsert for DocumentUpsert {
     }
 }
 
-fn sync_upsert_document(
+/// Inserts the document and, if it's the latest version, updates the records derived from it
+/// (program enrolment, encounter, contact trace). Also used when re-owning documents of a merged
+/// patient.
+/// Versions of the document edited concurrently (e.g. offline on different sites) are merged, see
+/// `merge_document_heads`.
+/// Returns None if the document version already exists
+pub(crate) fn sync_upsert_document(
     con: &StorageConnection,
     document: &Document,
-) -> Result<i64, RepositoryError> {
+) -> Result<Option<i64>, RepositoryError> {
+    let repo = DocumentRepository::new(con);
+    // Document ids are hashes of the content, the same version can already exist, e.g. when
+    // concurrent versions have been merged on different sites
+    if repo.find_one_by_id(&document.id)?.is_some() {
+        return Ok(None);
+    }
+
     // Fetch current document by name to check if the new document is the latest in the DB
     let new_doc_is_latest = is_latest_doc(con, &document.name, document.datetime)?;
 
     // Insert the new document
     // Note, every document is immutable for which reason an insert (instead of an upsert) is used.
-    let change_log_id = DocumentRepository::new(con).insert(document)?;
+    let change_log_id = repo.insert(document)?;
+    resolve_document_conflicts(con, document)?;
 
-    // Only if the new document is the latest, update the aux tables
-    if !new_doc_is_latest {
-        return Ok(change_log_id);
-    }
+    // Only if the new document (or the merge with it) is the latest, update the aux tables
+    match merge_document_heads(con, &document.name)? {
+        Some(merged) => update_derived_records(con, &merged)?,
+        None if new_doc_is_latest => update_derived_records(con, document)?,
+        None => {}
+    };
+    Ok(Some(change_log_id))
+}
+
+fn update_derived_records(
+    con: &StorageConnection,
+    document: &Document,
+) -> Result<(), RepositoryError> {
     let Some(registry) = DocumentRegistryRepository::new(con)
         .query_by_filter(
             DocumentRegistryFilter::new().document_type(EqualFilter::equal_to(&document.r#type)),
@@ -55,7 +80,7 @@ fn sync_upsert_document(
         .pop()
     else {
         log::warn!("Received unknown document type: {}", document.r#type);
-        return Ok(change_log_id);
+        return Ok(());
     };
     match registry.category {
         DocumentRegistryCategory::Patient => {
@@ -66,7 +91,7 @@ fn sync_upsert_document(
         DocumentRegistryCategory::ContactTrace => update_contact_trace(con, document)?,
         DocumentRegistryCategory::Custom => {}
     };
-    Ok(change_log_id)
+    Ok(())
 }
 
 fn update_program_enrolment(
@@ -106,7 +131,8 @@ fn update_encounter(con: &StorageConnection, document: &Document) -> Result<(),
     let encounter_start_time = encounter.start_datetime;
     let existing_encounter = EncounterRepository::new(con)
         .query_by_filter(
-            EncounterFilter::new().document_name(EqualFilter::equal_to(&document.name)),
+            EncounterFilter::new()
+                .document_name(EqualFilter::equal_any(document_names(con, document)?)),
         )?
         .pop();
 
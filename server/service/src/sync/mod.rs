@@ -7,7 +7,7 @@ pub(crate) mod central_data_synchroniser;
 pub(crate) mod central_data_synchroniser_v6;
 pub mod file_sync_driver;
 pub mod file_synchroniser;
-mod integrate_document;
+pub(crate) mod integrate_document;
 pub(crate) mod remote_data_synchroniser;
 pub mod settings;
 pub mod site_info;
@@ -284,6 +284,7 @@ impl Synchroniser {
         ctx.processors_trigger
             .trigger_requisition_transfer_processors();
         ctx.processors_trigger.trigger_invoice_transfer_processors();
+        ctx.processors_trigger.trigger_changelog_processors();
 
         Ok(())
     }
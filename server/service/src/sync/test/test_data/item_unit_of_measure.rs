@@ -0,0 +1,45 @@
+use repository::{ItemUnitOfMeasureRow, UnitOfMeasureType};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "item_unit_of_measure";
+
+const ITEM_UNIT_OF_MEASURE1: (&str, &str) = (
+    "0b6a3c1e-4f7d-4a52-9d0e-2c8f5b7a1e33",
+    r#"{
+        "id": "0b6a3c1e-4f7d-4a52-9d0e-2c8f5b7a1e33",
+        "item_link_id": "8F252B5884B74888AAB73A0D42C09E7A",
+        "name": "Box",
+        "type": "OUTER_PACK",
+        "conversion_factor": 100.0,
+        "deleted_datetime": null
+    }"#,
+);
+
+fn item_unit_of_measure1() -> ItemUnitOfMeasureRow {
+    ItemUnitOfMeasureRow {
+        id: ITEM_UNIT_OF_MEASURE1.0.to_string(),
+        item_link_id: "8F252B5884B74888AAB73A0D42C09E7A".to_string(), // ITEM_1.0
+        name: "Box".to_string(),
+        r#type: UnitOfMeasureType::OuterPack,
+        conversion_factor: 100.0,
+        deleted_datetime: None,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        ITEM_UNIT_OF_MEASURE1,
+        item_unit_of_measure1(),
+    )]
+}
+
+pub(crate) fn test_v6_central_push_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: ITEM_UNIT_OF_MEASURE1.0.to_string(),
+        push_data: json!(item_unit_of_measure1()),
+    }]
+}
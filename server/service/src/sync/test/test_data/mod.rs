@@ -20,6 +20,7 @@ pub(crate) mod indicator_value;
 pub(crate) mod invoice;
 pub(crate) mod invoice_line;
 pub(crate) mod item;
+pub(crate) mod item_unit_of_measure;
 pub(crate) mod item_variant;
 pub(crate) mod location;
 pub(crate) mod location_movement;
@@ -34,7 +35,11 @@ pub(crate) mod name_tag;
 pub(crate) mod name_tag_join;
 pub(crate) mod packaging_variant;
 pub(crate) mod period;
+pub(crate) mod period_close;
 pub(crate) mod period_schedule;
+pub(crate) mod price_list;
+pub(crate) mod price_list_line;
+pub(crate) mod price_list_name_join;
 pub(crate) mod program_indicator;
 pub(crate) mod program_requisition_settings;
 pub(crate) mod property;
@@ -105,6 +110,7 @@ pub(crate) fn get_all_pull_upsert_central_test_records() -> Vec<TestSyncIncoming
     test_records.append(&mut indicator_attribute::test_pull_upsert_records());
     test_records.append(&mut item_variant::test_pull_upsert_records());
     test_records.append(&mut packaging_variant::test_pull_upsert_records());
+    test_records.append(&mut item_unit_of_measure::test_pull_upsert_records());
 
     test_records
 }
@@ -135,6 +141,10 @@ pub(crate) fn get_all_pull_upsert_remote_test_records() -> Vec<TestSyncIncomingR
     test_records.append(&mut rnr_form::test_pull_upsert_records());
     test_records.append(&mut rnr_form_line::test_pull_upsert_records());
     test_records.append(&mut vaccination::test_pull_upsert_records());
+    test_records.append(&mut price_list::test_pull_upsert_records());
+    test_records.append(&mut price_list_line::test_pull_upsert_records());
+    test_records.append(&mut price_list_name_join::test_pull_upsert_records());
+    test_records.append(&mut period_close::test_pull_upsert_records());
 
     test_records
 }
@@ -164,6 +174,8 @@ pub(crate) fn get_all_pull_delete_remote_test_records() -> Vec<TestSyncIncomingR
     test_records.append(&mut invoice_line::test_pull_delete_records());
     test_records.append(&mut name_tag_join::test_pull_delete_records());
     test_records.append(&mut indicator_value::test_pull_delete_records());
+    test_records.append(&mut price_list_line::test_pull_delete_records());
+    test_records.append(&mut price_list_name_join::test_pull_delete_records());
 
     test_records
 }
@@ -205,6 +217,7 @@ pub(crate) fn get_all_sync_v6_records() -> Vec<TestSyncOutgoingRecord> {
     test_records.append(&mut name_oms_fields::test_v6_central_push_records());
     test_records.append(&mut item_variant::test_v6_central_push_records());
     test_records.append(&mut packaging_variant::test_v6_central_push_records());
+    test_records.append(&mut item_unit_of_measure::test_v6_central_push_records());
     test_records.append(&mut property::test_v6_central_push_records());
 
     // Remote
@@ -219,6 +232,10 @@ pub(crate) fn get_all_sync_v6_records() -> Vec<TestSyncOutgoingRecord> {
     test_records.append(&mut demographic::test_v6_records());
     test_records.append(&mut vaccine_course_dose::test_v6_records());
     test_records.append(&mut vaccination::test_v6_records());
+    test_records.append(&mut price_list::test_v6_records());
+    test_records.append(&mut price_list_line::test_v6_records());
+    test_records.append(&mut price_list_name_join::test_v6_records());
+    test_records.append(&mut period_close::test_v6_records());
 
     test_records
 }
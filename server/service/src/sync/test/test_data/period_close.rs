@@ -0,0 +1,49 @@
+use chrono::NaiveDate;
+use repository::PeriodCloseRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "period_close";
+
+const PERIOD_CLOSE_1: (&str, &str) = (
+    "6e2b8d0a-3c4f-4f1e-9a7b-1d5c2e8f4a90",
+    r#"{
+        "id": "6e2b8d0a-3c4f-4f1e-9a7b-1d5c2e8f4a90",
+        "store_id": "store_a",
+        "period_start": "2024-01-01",
+        "period_end": "2024-01-31",
+        "closed_datetime": "2024-02-02T09:30:00",
+        "user_id": "user_account_a"
+    }"#,
+);
+
+fn period_close_1() -> PeriodCloseRow {
+    PeriodCloseRow {
+        id: PERIOD_CLOSE_1.0.to_string(),
+        store_id: "store_a".to_string(),
+        period_start: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        period_end: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+        closed_datetime: NaiveDate::from_ymd_opt(2024, 2, 2)
+            .unwrap()
+            .and_hms_opt(9, 30, 0)
+            .unwrap(),
+        user_id: "user_account_a".to_string(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PERIOD_CLOSE_1,
+        period_close_1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PERIOD_CLOSE_1.0.to_string(),
+        push_data: json!(period_close_1()),
+    }]
+}
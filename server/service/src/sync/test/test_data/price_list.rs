@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+use repository::PriceListRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "price_list";
+
+const PRICE_LIST_1: (&str, &str) = (
+    "a3bd4f3c-8a8e-4d39-b1c5-5d3b1e0a6c11",
+    r#"{
+        "id": "a3bd4f3c-8a8e-4d39-b1c5-5d3b1e0a6c11",
+        "store_id": "store_a",
+        "name": "Customer prices",
+        "priority": 1,
+        "valid_from": "2024-01-01",
+        "valid_to": null,
+        "is_active": true
+    }"#,
+);
+
+fn price_list_1() -> PriceListRow {
+    PriceListRow {
+        id: PRICE_LIST_1.0.to_string(),
+        store_id: "store_a".to_string(),
+        name: "Customer prices".to_string(),
+        priority: 1,
+        valid_from: NaiveDate::from_ymd_opt(2024, 1, 1),
+        valid_to: None,
+        is_active: true,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PRICE_LIST_1,
+        price_list_1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PRICE_LIST_1.0.to_string(),
+        push_data: json!(price_list_1()),
+    }]
+}
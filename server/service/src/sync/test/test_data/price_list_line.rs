@@ -0,0 +1,49 @@
+use repository::{PriceListLineRow, PriceListLineRowDelete};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "price_list_line";
+
+const PRICE_LIST_LINE_1: (&str, &str) = (
+    "5e0b5e55-0c55-4b8e-9d0a-0a4c1c0c6f21",
+    r#"{
+        "id": "5e0b5e55-0c55-4b8e-9d0a-0a4c1c0c6f21",
+        "price_list_id": "a3bd4f3c-8a8e-4d39-b1c5-5d3b1e0a6c11",
+        "item_link_id": "8F252B5884B74888AAB73A0D42C09E7A",
+        "price_per_unit": 2.5
+    }"#,
+);
+
+fn price_list_line_1() -> PriceListLineRow {
+    PriceListLineRow {
+        id: PRICE_LIST_LINE_1.0.to_string(),
+        price_list_id: "a3bd4f3c-8a8e-4d39-b1c5-5d3b1e0a6c11".to_string(),
+        item_link_id: "8F252B5884B74888AAB73A0D42C09E7A".to_string(),
+        price_per_unit: 2.5,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PRICE_LIST_LINE_1,
+        price_list_line_1(),
+    )]
+}
+
+pub(crate) fn test_pull_delete_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_delete(
+        TABLE_NAME,
+        PRICE_LIST_LINE_1.0,
+        PriceListLineRowDelete(PRICE_LIST_LINE_1.0.to_string()),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PRICE_LIST_LINE_1.0.to_string(),
+        push_data: json!(price_list_line_1()),
+    }]
+}
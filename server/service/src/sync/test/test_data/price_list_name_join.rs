@@ -0,0 +1,49 @@
+use repository::{PriceListNameJoinRow, PriceListNameJoinRowDelete};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "price_list_name_join";
+
+const PRICE_LIST_NAME_JOIN_1: (&str, &str) = (
+    "0c9f4a8e-6b1d-4f0e-8d5c-3e2a7b9c1d31",
+    r#"{
+        "id": "0c9f4a8e-6b1d-4f0e-8d5c-3e2a7b9c1d31",
+        "price_list_id": "a3bd4f3c-8a8e-4d39-b1c5-5d3b1e0a6c11",
+        "name_link_id": "1FB32324AF8049248D929CFB35F255BA",
+        "name_tag_id": null
+    }"#,
+);
+
+fn price_list_name_join_1() -> PriceListNameJoinRow {
+    PriceListNameJoinRow {
+        id: PRICE_LIST_NAME_JOIN_1.0.to_string(),
+        price_list_id: "a3bd4f3c-8a8e-4d39-b1c5-5d3b1e0a6c11".to_string(),
+        name_link_id: Some("1FB32324AF8049248D929CFB35F255BA".to_string()),
+        name_tag_id: None,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PRICE_LIST_NAME_JOIN_1,
+        price_list_name_join_1(),
+    )]
+}
+
+pub(crate) fn test_pull_delete_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_delete(
+        TABLE_NAME,
+        PRICE_LIST_NAME_JOIN_1.0,
+        PriceListNameJoinRowDelete(PRICE_LIST_NAME_JOIN_1.0.to_string()),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PRICE_LIST_NAME_JOIN_1.0.to_string(),
+        push_data: json!(price_list_name_join_1()),
+    }]
+}
@@ -1,5 +1,7 @@
 use crate::sync::test::TestSyncIncomingRecord;
-use repository::{StorePreferenceRow, StorePreferenceType};
+use repository::{
+    AllocationStrategy, ForecastMethod, PatientCodeScheme, StorePreferenceRow, StorePreferenceType,
+};
 
 const TABLE_NAME: &str = "pref";
 
@@ -65,6 +67,13 @@ const STORE_PREFERENCE_1: (&str, &str) = (
         "boxPrefix": "",
         "boxPercentageSpace": 0,
         "omSupplyUsesProgramModule": true,
+        "omSupplyAllocationStrategy": "minimise_batches",
+        "omSupplyAllocationPreferredLocationID": "",
+        "omSupplyForecastMethod": "seasonal_naive",
+        "omSupplyStocktakeVarianceValueThreshold": 500,
+        "omSupplyStocktakeVariancePercentageThreshold": 10.5,
+        "omSupplyPatientCodeScheme": "prefix_sequence_verhoeff",
+        "omSupplyPatientCodePrefix": "204",
         "stocktakeFrequency": 1.34
     }
 }"#,
@@ -157,6 +166,13 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
                 months_items_expire: 2.12,
                 stocktake_frequency: 1.34,
                 extra_fields_in_requisition: false,
+                allocation_strategy: AllocationStrategy::MinimiseBatches,
+                allocation_preferred_location_id: None,
+                forecast_method: ForecastMethod::SeasonalNaive,
+                stocktake_variance_value_threshold: 500.0,
+                stocktake_variance_percentage_threshold: 10.5,
+                patient_code_scheme: PatientCodeScheme::PrefixSequenceVerhoeff,
+                patient_code_prefix: Some("204".to_string()),
             },
         ),
         TestSyncIncomingRecord::new_pull_upsert(
@@ -179,6 +195,14 @@ pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
                 months_items_expire: 3.0,
                 stocktake_frequency: 1.0,
                 extra_fields_in_requisition: true,
+                // Missing, should default to FEFO
+                allocation_strategy: AllocationStrategy::Fefo,
+                allocation_preferred_location_id: None,
+                forecast_method: ForecastMethod::AverageMonthlyConsumption,
+                stocktake_variance_value_threshold: 0.0,
+                stocktake_variance_percentage_threshold: 0.0,
+                patient_code_scheme: PatientCodeScheme::Manual,
+                patient_code_prefix: None,
             },
         ),
     ]
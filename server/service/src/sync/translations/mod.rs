@@ -21,6 +21,7 @@ pub(crate) mod indicator_value;
 pub(crate) mod invoice;
 pub(crate) mod invoice_line;
 pub(crate) mod item;
+pub(crate) mod item_unit_of_measure;
 pub(crate) mod item_variant;
 pub(crate) mod location;
 pub(crate) mod location_movement;
@@ -35,7 +36,11 @@ pub(crate) mod name_tag;
 pub(crate) mod name_tag_join;
 pub(crate) mod packaging_variant;
 pub(crate) mod period;
+pub(crate) mod period_close;
 pub(crate) mod period_schedule;
+pub(crate) mod price_list;
+pub(crate) mod price_list_line;
+pub(crate) mod price_list_name_join;
 pub(crate) mod program_indicator;
 pub(crate) mod program_requisition_settings;
 pub(crate) mod property;
@@ -152,6 +157,14 @@ pub(crate) fn all_translators() -> SyncTranslators {
         // Item Variant
         item_variant::boxed(),
         packaging_variant::boxed(),
+        // Item unit of measure
+        item_unit_of_measure::boxed(),
+        // Price list
+        price_list::boxed(),
+        price_list_line::boxed(),
+        price_list_name_join::boxed(),
+        // Period close
+        period_close::boxed(),
     ]
 }
 
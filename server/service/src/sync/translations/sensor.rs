@@ -144,6 +144,9 @@ impl SyncTranslation for SensorTranslation {
             SensorType::BlueMaestro => "BLUE_MAESTRO",
             SensorType::Laird => "LAIRD",
             SensorType::Berlinger => "BERLINGER",
+            SensorType::LogTag => "LOG_TAG",
+            SensorType::ThirtyDayRecorder => "THIRTY_DAY_RECORDER",
+            SensorType::Generic => "GENERIC",
         }
         .to_string();
 
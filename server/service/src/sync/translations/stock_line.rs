@@ -8,7 +8,7 @@ use crate::sync::{
 use chrono::NaiveDate;
 use repository::{
     ChangelogRow, ChangelogTableName, EqualFilter, StockLine, StockLineFilter, StockLineRepository,
-    StockLineRow, StorageConnection, SyncBufferRow,
+    StockLineRow, StorageConnection, SyncBufferRow, VvmStatus,
 };
 use serde::{Deserialize, Serialize};
 
@@ -45,6 +45,8 @@ pub struct LegacyStockLineRow {
     pub barcode_id: Option<String>,
     #[serde(rename = "om_item_variant_id")]
     pub item_variant_id: Option<String>,
+    #[serde(rename = "om_vvm_status")]
+    pub vvm_status: Option<VvmStatus>,
 }
 // Needs to be added to all_translators()
 #[deny(dead_code)]
@@ -95,6 +97,7 @@ impl SyncTranslation for StockLineTranslation {
             supplier_id,
             barcode_id,
             item_variant_id,
+            vvm_status,
         } = serde_json::from_str::<LegacyStockLineRow>(&sync_record.data)?;
 
         let barcode_id = clear_invalid_barcode_id(connection, barcode_id)?;
@@ -116,6 +119,7 @@ impl SyncTranslation for StockLineTranslation {
             supplier_link_id: supplier_id,
             barcode_id,
             item_variant_id,
+            vvm_status,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -155,6 +159,7 @@ impl SyncTranslation for StockLineTranslation {
                     supplier_link_id: _,
                     barcode_id,
                     item_variant_id,
+                    vvm_status,
                 },
             item_row,
             supplier_name_row,
@@ -178,6 +183,7 @@ impl SyncTranslation for StockLineTranslation {
             supplier_id: supplier_name_row.map(|supplier| supplier.id),
             barcode_id,
             item_variant_id,
+            vvm_status,
         };
 
         Ok(PushTranslateResult::upsert(
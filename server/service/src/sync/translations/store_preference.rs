@@ -1,7 +1,10 @@
-use repository::{StorageConnection, StorePreferenceRow, StorePreferenceType, SyncBufferRow};
+use repository::{
+    AllocationStrategy, ForecastMethod, PatientCodeScheme, StorageConnection, StorePreferenceRow,
+    StorePreferenceType, SyncBufferRow,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::sync::sync_serde::string_to_f64;
+use crate::sync::sync_serde::{empty_str_as_option_string, string_to_f64};
 
 use super::{PullTranslateResult, SyncTranslation};
 
@@ -64,6 +67,115 @@ pub struct LegacyPrefData {
     #[serde(default)]
     #[serde(rename = "useExtraFieldsForRequisitions")]
     pub extra_fields_in_requisition: bool,
+    #[serde(default)]
+    #[serde(rename = "omSupplyAllocationStrategy")]
+    pub allocation_strategy: LegacyAllocationStrategy,
+    #[serde(default)]
+    #[serde(deserialize_with = "empty_str_as_option_string")]
+    #[serde(rename = "omSupplyAllocationPreferredLocationID")]
+    pub allocation_preferred_location_id: Option<String>,
+    #[serde(default)]
+    #[serde(rename = "omSupplyForecastMethod")]
+    pub forecast_method: LegacyForecastMethod,
+    #[serde(default)]
+    #[serde(rename = "omSupplyStocktakeVarianceValueThreshold")]
+    pub stocktake_variance_value_threshold: f64,
+    #[serde(default)]
+    #[serde(rename = "omSupplyStocktakeVariancePercentageThreshold")]
+    pub stocktake_variance_percentage_threshold: f64,
+    #[serde(default)]
+    #[serde(rename = "omSupplyPatientCodeScheme")]
+    pub patient_code_scheme: LegacyPatientCodeScheme,
+    #[serde(default)]
+    #[serde(deserialize_with = "empty_str_as_option_string")]
+    #[serde(rename = "omSupplyPatientCodePrefix")]
+    pub patient_code_prefix: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub enum LegacyAllocationStrategy {
+    #[default]
+    #[serde(rename = "fefo")]
+    Fefo,
+    #[serde(rename = "fifo")]
+    Fifo,
+    #[serde(rename = "prefer_location")]
+    PreferLocation,
+    #[serde(rename = "avoid_breaking_packs")]
+    AvoidBreakingPacks,
+    #[serde(rename = "minimise_batches")]
+    MinimiseBatches,
+    // Unknown strategy, falls back to default
+    #[serde(other)]
+    Others,
+}
+
+impl LegacyAllocationStrategy {
+    fn to_domain(self) -> AllocationStrategy {
+        match self {
+            LegacyAllocationStrategy::Fefo | LegacyAllocationStrategy::Others => {
+                AllocationStrategy::Fefo
+            }
+            LegacyAllocationStrategy::Fifo => AllocationStrategy::Fifo,
+            LegacyAllocationStrategy::PreferLocation => AllocationStrategy::PreferLocation,
+            LegacyAllocationStrategy::AvoidBreakingPacks => AllocationStrategy::AvoidBreakingPacks,
+            LegacyAllocationStrategy::MinimiseBatches => AllocationStrategy::MinimiseBatches,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub enum LegacyForecastMethod {
+    #[default]
+    #[serde(rename = "average_monthly_consumption")]
+    AverageMonthlyConsumption,
+    #[serde(rename = "weighted_moving_average")]
+    WeightedMovingAverage,
+    #[serde(rename = "seasonal_naive")]
+    SeasonalNaive,
+    // Unknown method, falls back to default
+    #[serde(other)]
+    Others,
+}
+
+impl LegacyForecastMethod {
+    fn to_domain(self) -> ForecastMethod {
+        match self {
+            LegacyForecastMethod::AverageMonthlyConsumption | LegacyForecastMethod::Others => {
+                ForecastMethod::AverageMonthlyConsumption
+            }
+            LegacyForecastMethod::WeightedMovingAverage => ForecastMethod::WeightedMovingAverage,
+            LegacyForecastMethod::SeasonalNaive => ForecastMethod::SeasonalNaive,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub enum LegacyPatientCodeScheme {
+    #[default]
+    #[serde(rename = "manual")]
+    Manual,
+    #[serde(rename = "prefix_sequence_luhn")]
+    PrefixSequenceLuhn,
+    #[serde(rename = "prefix_sequence_verhoeff")]
+    PrefixSequenceVerhoeff,
+    // Unknown scheme, falls back to default
+    #[serde(other)]
+    Others,
+}
+
+impl LegacyPatientCodeScheme {
+    fn to_domain(self) -> PatientCodeScheme {
+        match self {
+            LegacyPatientCodeScheme::Manual | LegacyPatientCodeScheme::Others => {
+                PatientCodeScheme::Manual
+            }
+            LegacyPatientCodeScheme::PrefixSequenceLuhn => PatientCodeScheme::PrefixSequenceLuhn,
+            LegacyPatientCodeScheme::PrefixSequenceVerhoeff => {
+                PatientCodeScheme::PrefixSequenceVerhoeff
+            }
+        }
+    }
 }
 
 // Needs to be added to all_translators()
@@ -114,6 +226,13 @@ impl SyncTranslation for StorePreferenceTranslation {
             months_items_expire,
             stocktake_frequency,
             extra_fields_in_requisition,
+            allocation_strategy,
+            allocation_preferred_location_id,
+            forecast_method,
+            stocktake_variance_value_threshold,
+            stocktake_variance_percentage_threshold,
+            patient_code_scheme,
+            patient_code_prefix,
         } = data;
 
         let result = StorePreferenceRow {
@@ -132,6 +251,13 @@ impl SyncTranslation for StorePreferenceTranslation {
             months_items_expire,
             stocktake_frequency,
             extra_fields_in_requisition,
+            allocation_strategy: allocation_strategy.to_domain(),
+            allocation_preferred_location_id,
+            forecast_method: forecast_method.to_domain(),
+            stocktake_variance_value_threshold,
+            stocktake_variance_percentage_threshold,
+            patient_code_scheme: patient_code_scheme.to_domain(),
+            patient_code_prefix,
         };
 
         Ok(PullTranslateResult::upsert(result))
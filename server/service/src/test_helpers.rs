@@ -51,6 +51,7 @@ pub(crate) async fn setup_all_with_data_and_service_provider(
         sync: None,
         logging: None,
         backup: None,
+        mail: None,
     });
     let (sync_trigger, _) = SynchroniserDriver::init(file_sync_trigger);
     let (site_is_initialise_trigger, _) = SiteIsInitialisedCallback::init();
@@ -46,6 +46,7 @@ pub fn generate_create_prescription(
         expiry_date: None,
         cost_price_per_pack: None,
         sell_price_per_pack: None,
+        quantity_in_unit: None,
     };
 
     let finalise_prescription = UpdatePrescription {
@@ -32,6 +32,7 @@ pub enum InsertVaccinationError {
     ReasonNotProvided,
     StockLineDoesNotExist,
     ItemDoesNotBelongToVaccineCourse,
+    DateInClosedPeriod,
     CreatedRecordNotFound,
     InternalError(String),
     DatabaseError(RepositoryError),
@@ -147,6 +148,7 @@ mod insert {
         StockLineRowRepository,
     };
 
+    use crate::period_close::close_period_for_today;
     use crate::service_provider::ServiceProvider;
     use crate::vaccination::insert::{InsertVaccination, InsertVaccinationError};
 
@@ -364,6 +366,25 @@ mod insert {
             Err(InsertVaccinationError::ItemDoesNotBelongToVaccineCourse)
         );
 
+        // DateInClosedPeriod
+        close_period_for_today(&context.connection, store_id);
+        assert_eq!(
+            service.insert_vaccination(
+                &context,
+                store_id,
+                InsertVaccination {
+                    id: "new_id".to_string(),
+                    encounter_id: mock_immunisation_encounter_a().id,
+                    vaccine_course_dose_id: mock_vaccine_course_a_dose_b().id,
+                    facility_name_id: Some(mock_name_1().id),
+                    given: true,
+                    stock_line_id: Some(mock_stock_line_vaccine_item_a().id),
+                    ..Default::default()
+                }
+            ),
+            Err(InsertVaccinationError::DateInClosedPeriod)
+        );
+
         // Insert dose B as NOT GIVEN
         service
             .insert_vaccination(
@@ -1,8 +1,10 @@
+use chrono::Utc;
 use repository::{ProgramEnrolmentRow, RepositoryError, StockLine, StorageConnection};
 
 use crate::{
     common_stock::{check_stock_line_exists, CommonStockLineError},
     name::validate::check_name_exists,
+    period_close::validate_not_in_closed_period,
     vaccination::validate::{
         check_clinician_exists, check_encounter_exists, check_item_belongs_to_vaccine_course,
         check_program_enrolment_exists, check_vaccination_does_not_exist_for_dose,
@@ -82,6 +84,13 @@ pub fn validate(
             .vaccine_course_id,
     )?;
 
+    // Given from stock, a prescription is created
+    if stock_line.is_some()
+        && !validate_not_in_closed_period(connection, store_id, Utc::now().naive_utc())?
+    {
+        return Err(InsertVaccinationError::DateInClosedPeriod);
+    }
+
     Ok((program_enrolment.row, stock_line))
 }
 
@@ -33,6 +33,7 @@ pub enum UpdateVaccinationError {
     ItemDoesNotBelongToVaccineCourse,
     NotNextDose,
     NotMostRecentGivenDose,
+    DateInClosedPeriod,
     UpdatedRecordNotFound,
     InternalError(String),
     DatabaseError(RepositoryError),
@@ -184,6 +185,7 @@ mod update {
         VaccinationRowRepository,
     };
 
+    use crate::period_close::close_period_for_today;
     use crate::service_provider::ServiceProvider;
     use crate::vaccination::update::{UpdateVaccination, UpdateVaccinationError};
     use crate::NullableUpdate;
@@ -364,6 +366,25 @@ mod update {
             ),
             Err(UpdateVaccinationError::NotNextDose)
         );
+
+        // DateInClosedPeriod
+        close_period_for_today(&context.connection, store_id);
+        assert_eq!(
+            service.update_vaccination(
+                &context,
+                store_id,
+                UpdateVaccination {
+                    id: mock_vaccination_a().id,
+                    given: Some(true),
+                    stock_line_id: Some(NullableUpdate {
+                        value: Some(mock_stock_line_vaccine_item_a().id)
+                    }),
+                    update_transactions: Some(true),
+                    ..Default::default()
+                }
+            ),
+            Err(UpdateVaccinationError::DateInClosedPeriod)
+        );
     }
 
     #[actix_rt::test]
@@ -1,3 +1,4 @@
+use chrono::Utc;
 use repository::{
     EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, RepositoryError, StockLine,
     StorageConnection, VaccinationRow,
@@ -6,6 +7,7 @@ use repository::{
 use crate::{
     common_stock::{check_stock_line_exists, CommonStockLineError},
     name::validate::check_name_exists,
+    period_close::validate_not_in_closed_period,
     vaccination::validate::{
         check_clinician_exists, check_encounter_exists, check_item_belongs_to_vaccine_course,
         check_vaccination_exists, get_related_vaccinations,
@@ -137,6 +139,14 @@ pub fn validate(
         }
     }
 
+    // Prescription or customer return may be created
+    if input.update_transactions == Some(true)
+        && (existing_stock_line.is_some() || new_stock_line.is_some())
+        && !validate_not_in_closed_period(connection, store_id, Utc::now().naive_utc())?
+    {
+        return Err(UpdateVaccinationError::DateInClosedPeriod);
+    }
+
     Ok(ValidateResult {
         vaccination: vaccination.vaccination_row,
         patient_id: encounter.patient_link_id,
@@ -2,9 +2,139 @@ use std::collections::HashMap;
 
 use chrono::NaiveDate;
 
-#[derive(Debug)]
+/// FNC1 is transmitted by scanners as the ASCII group separator, terminating variable length data
+pub const GROUP_SEPARATOR: char = '\u{1D}';
+
+#[derive(Debug, PartialEq)]
 pub enum GS1ParseError {
     InvalidFormat,
+    UnknownApplicationIdentifier(String),
+    InvalidLength(String),
+    InvalidCharacters(String),
+    InvalidCheckDigit(String),
+    InvalidDate(String),
+}
+
+struct ApplicationIdentifier {
+    /// AI, or the leading digits of it for AIs ending in a decimal point position (e.g. 310n)
+    prefix: &'static str,
+    ai_length: usize,
+    min_length: usize,
+    max_length: usize,
+    numeric: bool,
+    check_digit: bool,
+    date: bool,
+}
+
+const fn ai(
+    prefix: &'static str,
+    ai_length: usize,
+    min_length: usize,
+    max_length: usize,
+    numeric: bool,
+) -> ApplicationIdentifier {
+    ApplicationIdentifier {
+        prefix,
+        ai_length,
+        min_length,
+        max_length,
+        numeric,
+        check_digit: false,
+        date: false,
+    }
+}
+
+const fn ai_with_check_digit(
+    prefix: &'static str,
+    ai_length: usize,
+    length: usize,
+) -> ApplicationIdentifier {
+    ApplicationIdentifier {
+        check_digit: true,
+        ..ai(prefix, ai_length, length, length, true)
+    }
+}
+
+const fn ai_date(prefix: &'static str) -> ApplicationIdentifier {
+    ApplicationIdentifier {
+        date: true,
+        ..ai(prefix, 2, 6, 6, true)
+    }
+}
+
+// https://ref.gs1.org/ai/, first matching prefix is used
+const APPLICATION_IDENTIFIERS: &[ApplicationIdentifier] = &[
+    ai_with_check_digit("00", 2, 18), // SSCC
+    ai_with_check_digit("01", 2, 14), // GTIN
+    ai_with_check_digit("02", 2, 14), // GTIN of contained trade items
+    ai("10", 2, 1, 20, false),        // Batch or lot number
+    ai_date("11"),                    // Production date
+    ai_date("12"),                    // Due date
+    ai_date("13"),                    // Packaging date
+    ai_date("15"),                    // Best before date
+    ai_date("16"),                    // Sell by date
+    ai_date("17"),                    // Expiration date
+    ai("20", 2, 2, 2, true),          // Internal product variant
+    ai("21", 2, 1, 20, false),        // Serial number
+    ai("22", 2, 1, 20, false),        // Consumer product variant
+    ai("235", 3, 1, 28, false),       // Third party controlled serialised extension of GTIN
+    ai("240", 3, 1, 30, false),       // Additional product identification
+    ai("241", 3, 1, 30, false),       // Customer part number
+    ai("242", 3, 1, 6, true),         // Made-to-Order variation number
+    ai("243", 3, 1, 20, false),       // Packaging component number
+    ai("250", 3, 1, 30, false),       // Secondary serial number
+    ai("251", 3, 1, 30, false),       // Reference to source entity
+    ai("253", 3, 14, 30, false),      // Global Document Type Identifier
+    ai("254", 3, 1, 20, false),       // GLN extension component
+    ai("30", 2, 1, 8, true),          // Variable count of items
+    ai("31", 4, 6, 6, true),          // Trade measures, e.g. 310n net weight in kg
+    ai("32", 4, 6, 6, true),
+    ai("33", 4, 6, 6, true), // Logistic measures
+    ai("34", 4, 6, 6, true),
+    ai("35", 4, 6, 6, true),
+    ai("36", 4, 6, 6, true),
+    ai("37", 2, 1, 8, true), // Count of trade items contained in a logistic unit
+    ai("390", 4, 1, 15, true), // Amount payable
+    ai("391", 4, 4, 18, true), // Amount payable with ISO currency code
+    ai("392", 4, 1, 15, true), // Amount payable for a variable measure trade item
+    ai("393", 4, 4, 18, true),
+    ai("400", 3, 1, 30, false),        // Customer's purchase order number
+    ai("401", 3, 1, 30, false),        // Global Identification Number for Consignment
+    ai_with_check_digit("402", 3, 17), // Global Shipment Identification Number
+    ai("403", 3, 1, 30, false),        // Routing code
+    ai_with_check_digit("41", 3, 13),  // 410-417 Global Location Numbers
+    ai("420", 3, 1, 20, false),        // Ship to postal code
+    ai("421", 3, 4, 12, false),        // Ship to postal code with ISO country code
+    ai("422", 3, 3, 3, true),          // Country of origin
+    ai("7003", 4, 10, 10, true),       // Expiration date and time
+    ai("7004", 4, 1, 4, true),         // Active potency
+    ai("8004", 4, 1, 30, false),       // Global Individual Asset Identifier
+    ai("8006", 4, 18, 18, true),       // Identification of an individual trade item piece
+    ai("8013", 4, 1, 25, false),       // Global Model Number
+    ai("8020", 4, 1, 25, false),       // Payment slip reference number
+    ai("90", 2, 1, 30, false),         // Information mutually agreed between trading partners
+    ai("9", 2, 1, 90, false),          // 91-99 Company internal information
+];
+
+/// AIs with a predefined data length, which aren't terminated by a separator
+const PREDEFINED_LENGTH_PREFIXES: &[&str] = &[
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "31",
+    "32", "33", "34", "35", "36", "41",
+];
+
+fn find_application_identifier(input: &str) -> Option<&'static ApplicationIdentifier> {
+    APPLICATION_IDENTIFIERS.iter().find(|entry| {
+        input.starts_with(entry.prefix)
+            && input
+                .get(..entry.ai_length)
+                .is_some_and(|ai| ai.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+fn is_predefined_length(ai: &str) -> bool {
+    PREDEFINED_LENGTH_PREFIXES
+        .iter()
+        .any(|prefix| ai.starts_with(prefix))
 }
 
 #[derive(Debug)]
@@ -54,6 +184,63 @@ impl GS1 {
         Ok(Self { gs1 })
     }
 
+    /// Parses raw scanner input (AIs without brackets, variable length data terminated by
+    /// a group separator) or a human readable string, validating the data of each known AI
+    pub fn parse(gs1_input: &str) -> Result<Self, GS1ParseError> {
+        let input = gs1_input.trim_start_matches('\u{FEFF}').trim();
+
+        let gs1 = if input.starts_with('(') {
+            parse_gs1_string(input.to_string())?
+        } else {
+            parse_element_string(strip_symbology_identifier(input))?
+        };
+
+        for (ai, data) in &gs1 {
+            validate_data_element(ai, data)?;
+        }
+
+        Ok(Self { gs1 })
+    }
+
+    /// Element string as encoded in a GS1 barcode, predefined length AIs are placed
+    /// first and the separator is added after each variable length data element
+    /// (except the last)
+    pub fn to_element_string_with_separator(&self, separator: &str) -> String {
+        let mut data_elements: Vec<(&String, &String)> = self.gs1.iter().collect();
+        data_elements.sort_by_key(|(ai, _)| (!is_predefined_length(ai), ai.to_string()));
+
+        let mut element_string = String::new();
+        let mut needs_separator = false;
+        for (ai, data) in data_elements {
+            if needs_separator {
+                element_string.push_str(separator);
+            }
+            element_string.push_str(ai);
+            element_string.push_str(data);
+            needs_separator = !is_predefined_length(ai);
+        }
+
+        element_string
+    }
+
+    pub fn to_element_string(&self) -> String {
+        self.to_element_string_with_separator(&GROUP_SEPARATOR.to_string())
+    }
+
+    pub fn to_human_readable_string(&self) -> String {
+        let mut data_elements: Vec<(&String, &String)> = self.gs1.iter().collect();
+        data_elements.sort_by_key(|(ai, _)| (!is_predefined_length(ai), ai.to_string()));
+
+        data_elements
+            .into_iter()
+            .map(|(ai, data)| format!("({}){}", ai, data))
+            .collect()
+    }
+
+    pub fn insert(&mut self, ai: &str, data: String) {
+        self.gs1.insert(ai.to_string(), data);
+    }
+
     pub fn get(&self, key: &str) -> Option<&String> {
         self.gs1.get(key)
     }
@@ -62,6 +249,31 @@ impl GS1 {
         self.gs1.get("01").cloned()
     }
 
+    pub fn batch(&self) -> Option<String> {
+        self.gs1.get("10").cloned()
+    }
+
+    pub fn production_date(&self) -> Option<NaiveDate> {
+        self.gs1.get("11").and_then(|data| parse_date(data))
+    }
+
+    pub fn best_before_date(&self) -> Option<NaiveDate> {
+        self.gs1.get("15").and_then(|data| parse_date(data))
+    }
+
+    pub fn expiry_date(&self) -> Option<NaiveDate> {
+        self.gs1.get("17").and_then(|data| parse_date(data))
+    }
+
+    /// Variable count of items (AI 30)
+    pub fn count(&self) -> Option<u32> {
+        self.gs1.get("30").and_then(|data| data.parse().ok())
+    }
+
+    pub fn additional_product_id(&self) -> Option<String> {
+        self.gs1.get("240").cloned()
+    }
+
     pub fn serial_number(&self) -> Option<String> {
         self.gs1.get("21").cloned()
     }
@@ -96,6 +308,128 @@ impl GS1 {
     }
 }
 
+/// GTIN-8, 12, 13 or 14 with a valid check digit, padded to the 14 digits used by AI 01
+pub fn gtin_14(gtin: &str) -> Option<String> {
+    let gtin = gtin.trim();
+    let valid_length = matches!(gtin.len(), 8 | 12 | 13 | 14);
+    if !valid_length || !gtin.chars().all(|c| c.is_ascii_digit()) || !is_check_digit_valid(gtin) {
+        return None;
+    }
+    Some(format!("{:0>14}", gtin))
+}
+
+/// GS1 modulo 10 check digit, the last digit of the input
+pub fn is_check_digit_valid(digits: &str) -> bool {
+    let Some(check_digit) = digits.chars().last().and_then(|c| c.to_digit(10)) else {
+        return false;
+    };
+
+    let mut sum = 0;
+    // Weights alternate 3, 1 starting from the digit before the check digit
+    for (index, c) in digits.chars().rev().skip(1).enumerate() {
+        let Some(digit) = c.to_digit(10) else {
+            return false;
+        };
+        sum += if index % 2 == 0 { digit * 3 } else { digit };
+    }
+
+    (10 - sum % 10) % 10 == check_digit
+}
+
+/// YYMMDD, day 00 is the last day of the month
+fn parse_date(data: &str) -> Option<NaiveDate> {
+    if data.len() != 6 || !data.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let year = 2000 + data[0..2].parse::<i32>().ok()?;
+    let month = data[2..4].parse::<u32>().ok()?;
+    let day = data[4..6].parse::<u32>().ok()?;
+
+    if day != 0 {
+        return NaiveDate::from_ymd_opt(year, month, day);
+    }
+    let next_month = match month {
+        12 => NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        1..=11 => NaiveDate::from_ymd_opt(year, month + 1, 1),
+        _ => None,
+    };
+    next_month?.pred_opt()
+}
+
+/// Scanners can be configured to prefix the symbology, e.g. ]d2 for GS1 DataMatrix
+fn strip_symbology_identifier(input: &str) -> &str {
+    match input.strip_prefix(']') {
+        Some(rest) if rest.len() >= 2 && rest.is_char_boundary(2) => &rest[2..],
+        _ => input,
+    }
+}
+
+fn parse_element_string(input: &str) -> Result<HashMap<String, String>, GS1ParseError> {
+    let mut gs1 = HashMap::new();
+    let mut rest = input;
+
+    loop {
+        // Leading FNC1 and separators after predefined length data are allowed
+        rest = rest.trim_start_matches(GROUP_SEPARATOR);
+        if rest.is_empty() {
+            break;
+        }
+
+        let application_identifier = find_application_identifier(rest).ok_or_else(|| {
+            GS1ParseError::UnknownApplicationIdentifier(rest.chars().take(4).collect())
+        })?;
+        let (ai, after_ai) = rest.split_at(application_identifier.ai_length);
+
+        let data_end = if is_predefined_length(ai) {
+            after_ai
+                .char_indices()
+                .nth(application_identifier.max_length)
+                .map(|(index, _)| index)
+                .unwrap_or(after_ai.len())
+        } else {
+            after_ai.find(GROUP_SEPARATOR).unwrap_or(after_ai.len())
+        };
+        let (data, after_data) = after_ai.split_at(data_end);
+
+        if data.is_empty() {
+            return Err(GS1ParseError::InvalidLength(ai.to_string()));
+        }
+        gs1.insert(ai.to_string(), data.to_string());
+        rest = after_data;
+    }
+
+    if gs1.is_empty() {
+        return Err(GS1ParseError::InvalidFormat);
+    }
+
+    Ok(gs1)
+}
+
+fn validate_data_element(ai: &str, data: &str) -> Result<(), GS1ParseError> {
+    let application_identifier = find_application_identifier(ai)
+        .filter(|entry| entry.ai_length == ai.len())
+        .ok_or_else(|| GS1ParseError::UnknownApplicationIdentifier(ai.to_string()))?;
+
+    let length = data.chars().count();
+    if length < application_identifier.min_length || length > application_identifier.max_length {
+        return Err(GS1ParseError::InvalidLength(ai.to_string()));
+    }
+    // GS1 data is limited to a subset of printable ASCII
+    if !data.chars().all(|c| c.is_ascii_graphic() || c == ' ')
+        || (application_identifier.numeric && !data.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(GS1ParseError::InvalidCharacters(ai.to_string()));
+    }
+    if application_identifier.check_digit && !is_check_digit_valid(data) {
+        return Err(GS1ParseError::InvalidCheckDigit(ai.to_string()));
+    }
+    if application_identifier.date && parse_date(data).is_none() {
+        return Err(GS1ParseError::InvalidDate(ai.to_string()));
+    }
+
+    Ok(())
+}
+
 fn parse_gs1_string(gs1_input: String) -> Result<HashMap<String, String>, GS1ParseError> {
     // Should start with '(' if it's a GS1 string although can have a BOM at the start \u{FEFF}, so we should ignore that if present
     // If we support http in future this would start with `http`
@@ -138,7 +472,53 @@ fn parse_gs1_string(gs1_input: String) -> Result<HashMap<String, String>, GS1Par
 
 #[cfg(test)]
 mod test {
-    use crate::{gs1::parse_gs1_string, GS1};
+    use chrono::NaiveDate;
+
+    use crate::{gs1::parse_gs1_string, gtin_14, GS1ParseError, GS1};
+
+    #[test]
+    fn gs1_parse_scanner_input() {
+        // GS1 DataMatrix with symbology identifier, batch terminated by a group separator
+        let gs1 = GS1::parse("]d201095011010209171725063010ABC123\u{1D}2112345\u{1D}3024").unwrap();
+        assert_eq!(gs1.gtin(), Some("09501101020917".to_string()));
+        assert_eq!(gs1.expiry_date(), NaiveDate::from_ymd_opt(2025, 6, 30));
+        assert_eq!(gs1.batch(), Some("ABC123".to_string()));
+        assert_eq!(gs1.serial_number(), Some("12345".to_string()));
+        assert_eq!(gs1.count(), Some(24));
+        assert_eq!(
+            gs1.to_element_string(),
+            "01095011010209171725063010ABC123\u{1D}2112345\u{1D}3024"
+        );
+        assert_eq!(
+            gs1.to_human_readable_string(),
+            "(01)09501101020917(17)250630(10)ABC123(21)12345(30)24"
+        );
+
+        // Human readable input is validated the same way, day 00 is the end of the month
+        let gs1 = GS1::parse("(01)09501101020917(17)260200(240)PROD-1").unwrap();
+        assert_eq!(gs1.expiry_date(), NaiveDate::from_ymd_opt(2026, 2, 28));
+        assert_eq!(gs1.additional_product_id(), Some("PROD-1".to_string()));
+
+        assert_eq!(
+            GS1::parse("0109501101020918").unwrap_err(),
+            GS1ParseError::InvalidCheckDigit("01".to_string())
+        );
+        assert_eq!(
+            GS1::parse("010950110102091717251330").unwrap_err(),
+            GS1ParseError::InvalidDate("17".to_string())
+        );
+        assert_eq!(
+            GS1::parse("01095011010209").unwrap_err(),
+            GS1ParseError::InvalidLength("01".to_string())
+        );
+        assert_eq!(
+            GS1::parse("(05)1234").unwrap_err(),
+            GS1ParseError::UnknownApplicationIdentifier("05".to_string())
+        );
+
+        assert_eq!(gtin_14("9501101020917"), Some("09501101020917".to_string()));
+        assert_eq!(gtin_14("9501101020918"), None);
+    }
 
     #[test]
     fn gs1_from_data_elements() {